@@ -1,12 +1,37 @@
-use crate::components::KaraokeLine;
+use crate::SafeMode;
+use crate::auto_contrast::use_auto_contrast;
+use crate::components::{
+    AdPlaying, AlbumArt, FetchProgress, Instrumental, KaraokeLine, LyricsPicker, NoLyrics,
+    PlaybackControls, ProviderStatusLine, SettingsPanel, TimingEditorPanel, TrackHeader,
+};
+use crate::cursor_visibility::use_cursor_auto_hide;
+use crate::lyric_card::{self, CardTarget};
+use crate::resources;
+use crate::state::KaraokeState;
 use crate::theme_watcher::use_theme_watcher;
+use crate::tray::{TrayAction, use_tray_menu};
 use crate::window_resize::use_window_auto_resize;
-use crate::window_state::WindowState;
+use crate::window_state::{self, WindowState, use_window_placement};
 use dioxus::desktop::tao::event::{Event as WryEvent, WindowEvent};
 use dioxus::desktop::{use_window, use_wry_event_handler};
+use dioxus::html::geometry::WheelDelta;
 use dioxus::prelude::*;
+use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
+use versualizer_core::{
+    LyricsCache, LyricsFetcher, LyricsTranslator, SyncEngine, TimingEditor, TrackInfo, UiConfig,
+    paths,
+};
+
+/// Wheel pixels that scroll the lyrics by one line
+const PIXELS_PER_SCROLL_LINE: f64 = 50.0;
+
+/// Line lead change per Ctrl+Shift+] or Ctrl+Shift+[ press
+const LINE_LEAD_STEP_MS: i64 = 50;
+
+/// Track offset change per Ctrl+Shift+→ or Ctrl+Shift+← press
+const TRACK_OFFSET_STEP_MS: i64 = 100;
 
 /// Root application component.
 /// Renders a transparent container with the karaoke line display.
@@ -16,12 +41,180 @@ pub fn App() -> Element {
     let cancel_token: CancellationToken = use_context();
 
     // Get reactive CSS content from theme watcher
-    // This watches ~/.config/versualizer/theme.css for changes and hot-reloads
-    let css_content = use_theme_watcher(cancel_token.clone());
+    // This watches ~/.config/versualizer/theme.css for changes and hot-reloads,
+    // except in safe mode which always uses the default theme
+    let SafeMode(safe_mode) = use_context();
+    let css_content = use_theme_watcher(cancel_token.clone(), safe_mode);
+
+    // Extra fonts from the fonts resource directories, available to the theme by name
+    let font_css = use_hook(resources::font_face_css);
 
     // Auto-resize window when CSS changes affect content dimensions
     use_window_auto_resize(css_content);
 
+    // Keep the overlay on the display chosen in [ui.window] monitor
+    use_window_placement();
+
+    // Optionally hide the cursor while it idles over the overlay
+    let ui_config = use_context::<Signal<UiConfig>>()();
+    use_cursor_auto_hide(ui_config.cursor);
+
+    // Optionally switch to dark text while the background around the overlay is light
+    let background_tone = use_auto_contrast(ui_config.contrast);
+
+    // Ctrl+Shift+S saves the current line as a PNG lyric card, Ctrl+Shift+C copies it,
+    // Ctrl+Shift+B bookmarks it, Ctrl+Shift+E saves the song's lyrics as an .lrc file,
+    // Ctrl+Shift+T translates the current song, Ctrl+Shift+O toggles the settings panel,
+    // Ctrl+Shift+L switches to the next provider's lyrics for the current song,
+    // Ctrl+Shift+W picks the song's lyrics by hand,
+    // Ctrl+Shift+R opens or saves the timing editor, Ctrl+Shift+] and Ctrl+Shift+[ switch
+    // lines earlier or later, Ctrl+Shift+→ and Ctrl+Shift+← nudge the current track's
+    // lyrics earlier or later and remember it
+    let mut karaoke: KaraokeState = use_context();
+    let mut settings_open = use_signal(|| false);
+    let mut picker_open = use_signal(|| false);
+    let timing_editor = use_signal(|| None::<(TrackInfo, TimingEditor)>);
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let translator: Option<Arc<LyricsTranslator>> = use_context();
+    let cache: Arc<LyricsCache> = use_context();
+    let lyrics_fetcher: Arc<LyricsFetcher> = use_context();
+
+    // The tray menu offers the same actions for when the overlay has no keyboard focus
+    let tray_sync_engine = sync_engine.clone();
+    let tray_translator = translator.clone();
+    let tray_cache = cache.clone();
+    let tray_lyrics_fetcher = lyrics_fetcher.clone();
+    let tray_cancel_token = cancel_token.clone();
+    let tray_window = window.clone();
+    use_tray_menu(move |action| match action {
+        TrayAction::SaveLyricCard | TrayAction::CopyLyricCard => {
+            let target = if action == TrayAction::SaveLyricCard {
+                CardTarget::File
+            } else {
+                CardTarget::Clipboard
+            };
+            spawn(lyric_card::export_current_line(
+                karaoke,
+                tray_sync_engine.clone(),
+                target,
+            ));
+        }
+        TrayAction::BookmarkLine => {
+            spawn(bookmark_current_line(
+                karaoke,
+                tray_sync_engine.clone(),
+                tray_cache.clone(),
+            ));
+        }
+        TrayAction::ExportLyrics => {
+            spawn(export_current_lyrics(tray_sync_engine.clone()));
+        }
+        TrayAction::TranslateSong => {
+            if let Some(ref translator) = tray_translator {
+                spawn(translate_current_track(
+                    translator.clone(),
+                    tray_sync_engine.clone(),
+                    karaoke,
+                ));
+            }
+        }
+        TrayAction::NextLyricsSource => {
+            spawn(next_lyrics_source(tray_lyrics_fetcher.clone()));
+        }
+        TrayAction::WrongLyrics => picker_open.set(true),
+        TrayAction::NextDisplay => window_state::move_to_next_monitor(&tray_window),
+        TrayAction::Settings => settings_open.set(true),
+        TrayAction::Quit => tray_cancel_token.cancel(),
+    });
+    let on_key_down = move |evt: KeyboardEvent| {
+        if timing_editor.read().is_some()
+            && timing_editor_key(evt.code(), timing_editor, &sync_engine)
+        {
+            return;
+        }
+        let modifiers = evt.modifiers();
+        if !(modifiers.ctrl() && modifiers.shift()) {
+            return;
+        }
+        match evt.code() {
+            Code::KeyS | Code::KeyC => {
+                let target = if evt.code() == Code::KeyS {
+                    CardTarget::File
+                } else {
+                    CardTarget::Clipboard
+                };
+                spawn(lyric_card::export_current_line(
+                    karaoke,
+                    sync_engine.clone(),
+                    target,
+                ));
+            }
+            Code::KeyB => {
+                spawn(bookmark_current_line(
+                    karaoke,
+                    sync_engine.clone(),
+                    cache.clone(),
+                ));
+            }
+            Code::KeyE => {
+                spawn(export_current_lyrics(sync_engine.clone()));
+            }
+            Code::KeyT => {
+                if let Some(ref translator) = translator {
+                    spawn(translate_current_track(
+                        translator.clone(),
+                        sync_engine.clone(),
+                        karaoke,
+                    ));
+                }
+            }
+            Code::KeyL => {
+                spawn(next_lyrics_source(lyrics_fetcher.clone()));
+            }
+            Code::KeyO => settings_open.toggle(),
+            Code::KeyW => picker_open.toggle(),
+            Code::KeyR => {
+                spawn(toggle_timing_editor(lyrics_fetcher.clone(), timing_editor));
+            }
+            Code::BracketRight | Code::BracketLeft => {
+                let step = if evt.code() == Code::BracketRight {
+                    LINE_LEAD_STEP_MS
+                } else {
+                    -LINE_LEAD_STEP_MS
+                };
+                let lead_ms = sync_engine.set_line_lead_ms(sync_engine.line_lead_ms() + step);
+                info!("Line lead set to {}ms", lead_ms);
+            }
+            Code::ArrowRight | Code::ArrowLeft => {
+                let step = if evt.code() == Code::ArrowRight {
+                    TRACK_OFFSET_STEP_MS
+                } else {
+                    -TRACK_OFFSET_STEP_MS
+                };
+                spawn(nudge_track_offset(sync_engine.clone(), cache.clone(), step));
+            }
+            _ => {}
+        }
+    };
+
+    // Scrolling over the overlay peeks back through sung lines or ahead at upcoming ones
+    let (max_lines, _) = ui_config.layout.visible_lines();
+    let on_wheel = move |evt: WheelEvent| {
+        // Lines are small and fractional peeks are fine, so f32 precision is plenty
+        #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+        let lines = match evt.delta() {
+            WheelDelta::Pixels(delta) => (delta.y / PIXELS_PER_SCROLL_LINE) as f32,
+            WheelDelta::Lines(delta) => delta.y as f32,
+            WheelDelta::Pages(delta) => delta.y as f32 * max_lines as f32,
+        };
+        karaoke.peek_by(lines);
+    };
+
+    // Hovering the lyrics pauses auto-follow so they can be read at leisure
+    let pause_on_hover = ui_config.follow.pause_on_hover;
+    let on_mouse_enter = move |_: MouseEvent| karaoke.set_hovering(true, pause_on_hover);
+    let on_mouse_leave = move |_: MouseEvent| karaoke.set_hovering(false, pause_on_hover);
+
     // Handle window close event (triggered by X button)
     // Save window position before closing
     let window_for_close = window.clone();
@@ -34,12 +227,8 @@ pub fn App() -> Element {
         {
             info!("Window close requested, shutting down gracefully...");
 
-            // Save window position before closing
-            if let Ok(position) = window_for_close.outer_position() {
-                let state = WindowState {
-                    x: position.x,
-                    y: position.y,
-                };
+            // Save window position and display before closing
+            if let Some(state) = WindowState::capture(&window_for_close) {
                 state.save();
             }
 
@@ -66,12 +255,35 @@ pub fn App() -> Element {
     #[cfg(target_os = "macos")]
     return rsx! {
         // Dynamic style element - re-renders when css_content signal changes
+        style { dangerous_inner_html: "{font_css}" }
         style { dangerous_inner_html: "{css_content}" }
 
         div {
-            class: "app",
+            class: "app {background_tone().as_css_class()}",
+            tabindex: 0,
+            onkeydown: on_key_down,
+            onwheel: on_wheel,
+            onmouseenter: on_mouse_enter,
+            onmouseleave: on_mouse_leave,
 
+            AlbumArt {}
+            TrackHeader {}
             KaraokeLine {}
+            NoLyrics {}
+            FetchProgress {}
+            Instrumental {}
+            AdPlaying {}
+            ProviderStatusLine {}
+            TimingEditorPanel { editor: timing_editor }
+            PlaybackControls {}
+            WrongLyricsButton { on_click: move |()| picker_open.set(true) }
+            SettingsButton { on_click: move |()| settings_open.set(true) }
+            if settings_open() {
+                SettingsPanel { on_close: move |()| settings_open.set(false) }
+            }
+            if picker_open() {
+                LyricsPicker { on_close: move |()| picker_open.set(false) }
+            }
         }
     };
 
@@ -82,14 +294,232 @@ pub fn App() -> Element {
         };
         return rsx! {
             // Dynamic style element - re-renders when css_content signal changes
+            style { dangerous_inner_html: "{font_css}" }
             style { dangerous_inner_html: "{css_content}" }
 
             div {
-                class: "app",
+                class: "app {background_tone().as_css_class()}",
+                tabindex: 0,
                 onmousedown: on_mouse_down,
+                onkeydown: on_key_down,
+                onwheel: on_wheel,
+                onmouseenter: on_mouse_enter,
+                onmouseleave: on_mouse_leave,
 
+                AlbumArt {}
+                TrackHeader {}
                 KaraokeLine {}
+                NoLyrics {}
+                FetchProgress {}
+                Instrumental {}
+                AdPlaying {}
+                ProviderStatusLine {}
+                TimingEditorPanel { editor: timing_editor }
+                PlaybackControls {}
+                WrongLyricsButton { on_click: move |()| picker_open.set(true) }
+                SettingsButton { on_click: move |()| settings_open.set(true) }
+                if settings_open() {
+                    SettingsPanel { on_close: move |()| settings_open.set(false) }
+                }
+                if picker_open() {
+                    LyricsPicker { on_close: move |()| picker_open.set(false) }
+                }
             }
         };
     }
 }
+
+/// "Wrong lyrics?" button that opens the lyrics picker, shown while the pointer is over the
+/// overlay
+#[component]
+fn WrongLyricsButton(on_click: EventHandler<()>) -> Element {
+    rsx! {
+        button {
+            class: "wrong-lyrics-button",
+            title: "Wrong lyrics? (Ctrl+Shift+W)",
+            // Keep the click from starting a window drag
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+            onclick: move |_| on_click.call(()),
+            "?"
+        }
+    }
+}
+
+/// Gear button that opens the settings panel, shown while the pointer is over the overlay
+#[component]
+fn SettingsButton(on_click: EventHandler<()>) -> Element {
+    rsx! {
+        button {
+            class: "settings-button",
+            title: "Settings (Ctrl+Shift+O)",
+            // Keep the click from starting a window drag
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+            onclick: move |_| on_click.call(()),
+            "⚙"
+        }
+    }
+}
+
+/// Translate the current track's cached lyrics and show the translation under each line.
+/// The translation is cached, so it reappears the next time the song plays.
+async fn translate_current_track(
+    translator: Arc<LyricsTranslator>,
+    sync_engine: Arc<SyncEngine>,
+    mut karaoke: KaraokeState,
+) {
+    let Some(track) = sync_engine.current_track().await else {
+        info!("No current track to translate");
+        return;
+    };
+    match translator.translate_track(&track).await {
+        Ok(translation) => {
+            info!(
+                "Translated {} lines of {} into {}",
+                translation.lines.len(),
+                track.name,
+                translation.target_lang
+            );
+            karaoke.set_translations(&translation.lines);
+        }
+        Err(e) => warn!("Failed to translate {}: {}", track.name, e),
+    }
+}
+
+/// Show the current track's lyrics from the next provider that has them cached
+async fn next_lyrics_source(lyrics_fetcher: Arc<LyricsFetcher>) {
+    match lyrics_fetcher.next_alternative().await {
+        Ok(Some(provider)) => info!("Switched to lyrics from {}", provider),
+        Ok(None) => info!("No other lyrics source for the current track"),
+        Err(e) => warn!("Failed to switch lyrics source: {}", e),
+    }
+}
+
+/// Keys that drive the open timing editor: Space starts the next line at the playback
+/// position, Backspace takes back the last one and Escape discards the changes. Returns
+/// whether the key was one of them.
+fn timing_editor_key(
+    code: Code,
+    mut editor: Signal<Option<(TrackInfo, TimingEditor)>>,
+    sync_engine: &Arc<SyncEngine>,
+) -> bool {
+    match code {
+        Code::Space => {
+            let sync_engine = sync_engine.clone();
+            spawn(async move {
+                let position = sync_engine.playback_position().await;
+                if let Some((_, editor)) = editor.write().as_mut() {
+                    editor.tap(position);
+                }
+            });
+        }
+        Code::Backspace => {
+            if let Some((_, editor)) = editor.write().as_mut() {
+                editor.undo();
+            }
+        }
+        Code::Escape => {
+            editor.set(None);
+            info!("Discarded the lyrics timing changes");
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Open the timing editor on the current track's cached lyrics, or save the lines timed so
+/// far and close it if it is already open
+async fn toggle_timing_editor(
+    lyrics_fetcher: Arc<LyricsFetcher>,
+    mut editor: Signal<Option<(TrackInfo, TimingEditor)>>,
+) {
+    if let Some((track, open)) = editor.take() {
+        if open.current_index() == 0 {
+            info!("No lines were timed, closing the timing editor");
+            return;
+        }
+        match lyrics_fetcher
+            .save_edited_lyrics(&track, open.finish())
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => info!("No cached lyrics left to replace for {}", track.name),
+            Err(e) => warn!("Failed to save the retimed lyrics: {}", e),
+        }
+        return;
+    }
+    match lyrics_fetcher.edit_current_lyrics().await {
+        Ok(Some((track, opened))) => {
+            info!("Retiming {} lines of {}", opened.lines().len(), track.name);
+            editor.set(Some((track, opened)));
+        }
+        Ok(None) => info!("No cached lyrics to retime for the current track"),
+        Err(e) => warn!("Failed to open the timing editor: {}", e),
+    }
+}
+
+/// Bookmark the current line of the current track
+async fn bookmark_current_line(
+    karaoke: KaraokeState,
+    sync_engine: Arc<SyncEngine>,
+    cache: Arc<LyricsCache>,
+) {
+    let Some(line) = karaoke
+        .current_line()
+        .filter(|line| !line.text.trim().is_empty())
+    else {
+        info!("No current lyric line to bookmark");
+        return;
+    };
+    let Some(track) = sync_engine.current_track().await else {
+        info!("No current track to bookmark a line of");
+        return;
+    };
+    match cache
+        .add_bookmark(&track, line.start_time_ms, &line.text)
+        .await
+    {
+        Ok(_) => info!("Bookmarked \"{}\" in {}", line.text, track.name),
+        Err(e) => warn!("Failed to bookmark the current line: {}", e),
+    }
+}
+
+/// Save the current track's lyrics, with its offset applied, as an `.lrc` file in the
+/// lyrics exports folder
+async fn export_current_lyrics(sync_engine: Arc<SyncEngine>) {
+    let Some(track) = sync_engine.current_track().await else {
+        info!("No current track to export lyrics of");
+        return;
+    };
+    let path = paths::lyrics_export_path(&track);
+    match sync_engine.export_current_lyrics(&path).await {
+        Ok(true) => info!("Exported lyrics of {} to {:?}", track.name, path),
+        Ok(false) => info!("No synced lyrics to export for {}", track.name),
+        Err(e) => warn!("Failed to export lyrics to {:?}: {}", path, e),
+    }
+}
+
+/// Change the current track's lyrics offset by `step_ms` and save it for the next time the
+/// track plays
+async fn nudge_track_offset(sync_engine: Arc<SyncEngine>, cache: Arc<LyricsCache>, step_ms: i64) {
+    let offset_ms = sync_engine.track_offset_ms() + step_ms;
+    set_track_offset(sync_engine, cache, offset_ms).await;
+}
+
+/// Set the current track's lyrics offset and save it for the next time the track plays
+pub async fn set_track_offset(
+    sync_engine: Arc<SyncEngine>,
+    cache: Arc<LyricsCache>,
+    offset_ms: i64,
+) {
+    let Some(track) = sync_engine.current_track().await else {
+        return;
+    };
+    let offset_ms = sync_engine.set_track_offset_ms(offset_ms).await;
+    info!("Lyrics offset for {} set to {}ms", track.name, offset_ms);
+    if let Err(e) = cache
+        .set_offset(track.source.as_str(), &track.source_track_id, offset_ms)
+        .await
+    {
+        warn!("Failed to save the lyrics offset: {}", e);
+    }
+}
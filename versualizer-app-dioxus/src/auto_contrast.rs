@@ -0,0 +1,184 @@
+//! Switch text colors to match the brightness of what is behind the overlay.
+//!
+//! The screen is sampled in thin bands just above and below the window rather than under
+//! it, so the overlay's own text and background never feed back into the measurement.
+//! Sampling works on Windows and X11. Elsewhere the theme's colors are kept.
+
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+use std::time::Duration;
+use tracing::{debug, info};
+use versualizer_core::contrast::average_luminance;
+use versualizer_core::{BackgroundTone, ContrastConfig};
+
+/// Height of the bands sampled above and below the window (physical pixels)
+const BAND_HEIGHT: u32 = 8;
+
+/// Samples taken across the width of each band
+const SAMPLES_PER_BAND: u32 = 32;
+
+/// Screen area in physical pixels
+#[derive(Debug, Clone, Copy)]
+struct ScreenRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+/// Hook that tracks the tone of the background around the window.
+///
+/// Stays at [`BackgroundTone::Dark`], the default theme's assumption, unless `auto_adjust`
+/// is enabled in `[ui.contrast]`.
+pub fn use_auto_contrast(config: ContrastConfig) -> Signal<BackgroundTone> {
+    let window = use_window();
+    let mut tone = use_signal(BackgroundTone::default);
+
+    use_future(move || {
+        let window = window.clone();
+        async move {
+            if !config.auto_adjust {
+                return;
+            }
+
+            let interval = Duration::from_millis(config.sample_interval_ms);
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let Ok(position) = window.outer_position() else {
+                    continue;
+                };
+                let size = window.outer_size();
+                let bands = [
+                    ScreenRect {
+                        x: position.x,
+                        y: position.y.saturating_sub_unsigned(BAND_HEIGHT),
+                        width: size.width,
+                        height: BAND_HEIGHT,
+                    },
+                    ScreenRect {
+                        x: position.x,
+                        y: position.y.saturating_add_unsigned(size.height),
+                        width: size.width,
+                        height: BAND_HEIGHT,
+                    },
+                ];
+                let Ok(pixels) = tokio::task::spawn_blocking(move || sample(&bands)).await else {
+                    continue;
+                };
+                let Some(pixels) = pixels else {
+                    info!("Screen sampling is not available here; keeping the theme's colors");
+                    return;
+                };
+                let Some(luminance) = average_luminance(&pixels) else {
+                    continue;
+                };
+
+                let previous = *tone.peek();
+                let next = BackgroundTone::from_luminance(luminance, previous);
+                if next != previous {
+                    debug!(
+                        "Background luminance {:.2}, switching to {}",
+                        luminance,
+                        next.as_css_class()
+                    );
+                    tone.set(next);
+                }
+            }
+        }
+    });
+
+    tone
+}
+
+/// Points spread across the middle row of `rect`
+#[cfg(windows)]
+fn sample_points(rect: ScreenRect) -> impl Iterator<Item = (i32, i32)> {
+    let step = (rect.width / SAMPLES_PER_BAND).max(1);
+    let y = rect.y.saturating_add_unsigned(rect.height / 2);
+    (0..rect.width.min(SAMPLES_PER_BAND))
+        .map(move |i| (rect.x.saturating_add_unsigned(i * step + step / 2), y))
+}
+
+/// Colors of the screen at points across `bands`. Points off the screen are skipped.
+/// Returns `None` if the screen cannot be read at all.
+#[cfg(windows)]
+#[allow(unsafe_code)]
+fn sample(bands: &[ScreenRect]) -> Option<Vec<[u8; 3]>> {
+    use windows_sys::Win32::Graphics::Gdi::{CLR_INVALID, GetDC, GetPixel, ReleaseDC};
+
+    // SAFETY: the screen device context is released before returning, and `GetPixel` only
+    // reads from it
+    unsafe {
+        let screen = GetDC(std::ptr::null_mut());
+        if screen.is_null() {
+            return None;
+        }
+        let pixels = bands
+            .iter()
+            .flat_map(|&band| sample_points(band))
+            .map(|(x, y)| GetPixel(screen, x, y))
+            .filter(|&color| color != CLR_INVALID)
+            .map(|color| color.to_le_bytes())
+            .map(|[r, g, b, _]| [r, g, b])
+            .collect();
+        ReleaseDC(std::ptr::null_mut(), screen);
+        Some(pixels)
+    }
+}
+
+/// Colors of the screen along the middle row of each band. Parts off the screen are
+/// skipped. Returns `None` without an X server, e.g. on Wayland without Xwayland.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn sample(bands: &[ScreenRect]) -> Option<Vec<[u8; 3]>> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt, ImageFormat};
+
+    let (connection, screen) = x11rb::connect(None).ok()?;
+    let root = connection.setup().roots.get(screen)?;
+
+    let mut pixels = Vec::new();
+    for band in bands {
+        let y = band.y.saturating_add_unsigned(band.height / 2);
+        let left = band.x.max(0);
+        let right = band
+            .x
+            .saturating_add_unsigned(band.width)
+            .min(i32::from(root.width_in_pixels));
+        if !(0..i32::from(root.height_in_pixels)).contains(&y) || left >= right {
+            continue;
+        }
+        // In range of the screen, whose size fits in i16 and u16
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let image = connection
+            .get_image(
+                ImageFormat::Z_PIXMAP,
+                root.root,
+                left as i16,
+                y as i16,
+                (right - left) as u16,
+                1,
+                !0,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        // True color screens store a pixel as blue, green, red and padding bytes
+        let Some(image) = image.filter(|image| image.depth >= 24) else {
+            continue;
+        };
+        let row: Vec<_> = image.data.chunks_exact(4).collect();
+        let step = (row.len() / SAMPLES_PER_BAND as usize).max(1);
+        pixels.extend(
+            row.iter()
+                .step_by(step)
+                .map(|pixel| [pixel[2], pixel[1], pixel[0]]),
+        );
+    }
+    Some(pixels)
+}
+
+/// Screen sampling is not implemented on this platform
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn sample(_bands: &[ScreenRect]) -> Option<Vec<[u8; 3]>> {
+    None
+}
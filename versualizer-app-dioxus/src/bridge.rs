@@ -1,9 +1,14 @@
-use crate::state::{KaraokeState, LocalPlaybackTimer};
+use crate::state::{CurrentBeat, KaraokeState, LocalPlaybackTimer, MissingLyrics};
 use dioxus::prelude::*;
 use std::sync::Arc;
-use tracing::info;
-use versualizer_core::config::UiConfig;
-use versualizer_core::{DurationExt, SyncEngine, SyncEvent};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+use versualizer_core::config::{UiConfig, VersualizerConfig};
+use versualizer_core::{
+    AudioFeatures, DurationExt, LyricsTranslator, ProviderStatusRegistry, SyncEngine, SyncEvent,
+    apply_line_lead,
+};
 
 /// Bridge `SyncEngine` events to Dioxus signals, with local playback timing.
 ///
@@ -15,28 +20,75 @@ use versualizer_core::{DurationExt, SyncEngine, SyncEvent};
 /// - Only hard-syncing on major events (play/pause/seek/track change)
 /// - Using drift correction (configurable threshold) for regular position updates
 /// - Locally computing line index at configured framerate instead of on every sync event
+#[allow(clippy::too_many_lines)]
 pub fn use_sync_engine_bridge(sync_engine: &Arc<SyncEngine>, karaoke: KaraokeState) {
-    // Get UI config from context to read the configured framerate and drift threshold
-    let ui_config: UiConfig = use_context();
-    let framerate = ui_config.animation.framerate;
-    let drift_threshold_ms = ui_config.animation.drift_threshold_ms;
+    // UI config from context, replaced when config.toml changes except for framerate and drift
+    let ui_config: Signal<UiConfig> = use_context();
+    let framerate = ui_config.peek().animation.framerate;
+    let drift_threshold_ms = ui_config.peek().animation.drift_threshold_ms;
+
+    // Audio features of the current track, published by the features service when quantizing
+    let features_rx: watch::Receiver<Option<Arc<AudioFeatures>>> = use_context();
 
     // Create the local playback timer with configured framerate and drift threshold
     let timer = use_signal(|| LocalPlaybackTimer::new(framerate, drift_threshold_ms));
 
+    // Songs translated earlier show their cached translation as soon as lyrics load, and
+    // with auto_translate the others are translated then
+    let translator: Option<Arc<LyricsTranslator>> = use_context();
+
+    // The track without lyrics, for the no-lyrics renderer
+    let mut missing_lyrics: MissingLyrics = use_context();
+
+    // The beat the current line pulses on
+    let mut current_beat: CurrentBeat = use_context();
+
     // Clone once for the closure, then move into async block
     let sync_engine = sync_engine.clone();
+    let timer_sync_engine = sync_engine.clone();
 
     // Spawn the sync event listener
     use_future(move || {
         let sync_engine = sync_engine.clone();
+        let translator = translator.clone();
         async move {
+            let mut karaoke = karaoke;
             let mut rx = sync_engine.subscribe();
 
             loop {
                 match rx.recv().await {
+                    Ok(SyncEvent::UnsyncedLyricsLoaded { text }) => {
+                        // Plain lyrics scroll along the track, so they need its duration
+                        let duration_ms = sync_engine
+                            .current_track()
+                            .await
+                            .map_or(0, |track| track.duration.as_millis_u64());
+                        karaoke.set_unsynced_lyrics(&text, duration_ms);
+                        missing_lyrics.clear();
+                        info!("Showing {} lines of unsynced lyrics", text.lines().count());
+                    }
                     Ok(event) => {
-                        handle_sync_event(event, karaoke, timer);
+                        match event {
+                            SyncEvent::LyricsNotFound => {
+                                missing_lyrics.set(sync_engine.current_track().await);
+                            }
+                            SyncEvent::LyricsLoaded { .. }
+                            | SyncEvent::LyricsBlocked { .. }
+                            | SyncEvent::TrackInstrumental
+                            | SyncEvent::TrackChanged { .. }
+                            | SyncEvent::PlaybackStopped
+                            | SyncEvent::AdPlaying => missing_lyrics.clear(),
+                            _ => {}
+                        }
+                        let lyrics_loaded = matches!(event, SyncEvent::LyricsLoaded { .. });
+                        handle_sync_event(event, karaoke, timer, ui_config);
+                        if lyrics_loaded && let Some(ref translator) = translator {
+                            spawn(show_translation(
+                                translator.clone(),
+                                sync_engine.clone(),
+                                karaoke,
+                            ));
+                        }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => {
                         info!("Sync event channel closed");
@@ -54,6 +106,8 @@ pub fn use_sync_engine_bridge(sync_engine: &Arc<SyncEngine>, karaoke: KaraokeSta
     use_effect(move || {
         // Make karaoke mutable for the async block
         let mut karaoke = karaoke;
+        let sync_engine = timer_sync_engine.clone();
+        let features_rx = features_rx.clone();
         spawn(async move {
             loop {
                 // Read timer state into local variables to avoid holding borrow across await
@@ -65,16 +119,33 @@ pub fn use_sync_engine_bridge(sync_engine: &Arc<SyncEngine>, karaoke: KaraokeSta
                 // Only update when playing and we have lyrics
                 if is_playing {
                     if let Some(ref lyrics) = *karaoke.lyrics.peek() {
-                        // Compute current position from local timer
-                        let position_ms = timer.peek().interpolated_position_ms();
+                        // Compute current position from local timer, shifted by the line lead
+                        let position =
+                            Duration::from_millis(timer.peek().interpolated_position_ms());
+                        let position_ms =
+                            apply_line_lead(position, sync_engine.line_lead_ms()).as_millis_u64();
 
-                        // Derive line index from position
-                        let new_index = lyrics.line_index_at(position_ms);
+                        // Derive line index from position, on the beat when the tempo is known
+                        let tempo = ui_config
+                            .peek()
+                            .animation
+                            .quantize_to_beat
+                            .then(|| features_rx.borrow().as_ref().and_then(|f| f.tempo))
+                            .flatten();
+                        let new_index = tempo.map_or_else(
+                            || lyrics.line_index_at(position_ms),
+                            |tempo| lyrics.line_index_on_beat(position_ms, tempo),
+                        );
 
                         // Only update signal if line actually changed (reduces re-renders)
                         if new_index != *karaoke.current_index.peek() {
                             karaoke.current_index.set(new_index);
                         }
+
+                        // Beats follow the music, not the line lead
+                        if ui_config.peek().animation.beat_pulse {
+                            current_beat.set(beat_index_at(&features_rx, position));
+                        }
                     }
 
                     // Active polling: at configured framerate for smooth line transitions
@@ -88,10 +159,71 @@ pub fn use_sync_engine_bridge(sync_engine: &Arc<SyncEngine>, karaoke: KaraokeSta
     });
 }
 
+/// Replace the UI settings with those of a reloaded config
+fn apply_ui_config(mut ui_config: Signal<UiConfig>, config: &VersualizerConfig) {
+    // The view can be chosen per window with --view
+    let view = ui_config.peek().layout.view;
+    let mut ui = config.ui.clone();
+    ui.layout.view = view;
+    ui_config.set(ui);
+}
+
+/// The beat playing at `position`, if the current track's audio analysis is known
+fn beat_index_at(
+    features_rx: &watch::Receiver<Option<Arc<AudioFeatures>>>,
+    position: Duration,
+) -> Option<usize> {
+    features_rx
+        .borrow()
+        .as_ref()
+        .and_then(|features| features.beat_index_at(position))
+}
+
+/// Mirror the provider status registry into `karaoke.provider_statuses`.
+pub fn use_provider_status_bridge(registry: &Arc<ProviderStatusRegistry>, karaoke: KaraokeState) {
+    let registry = registry.clone();
+    use_future(move || {
+        let mut rx = registry.subscribe();
+        let mut provider_statuses = karaoke.provider_statuses;
+        async move {
+            loop {
+                let statuses = rx.borrow_and_update().clone();
+                provider_statuses.set(statuses);
+                if rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Show the cached translation of the current track, if it has been translated before.
+/// With `auto_translate`, untranslated tracks are translated now.
+async fn show_translation(
+    translator: Arc<LyricsTranslator>,
+    sync_engine: Arc<SyncEngine>,
+    mut karaoke: KaraokeState,
+) {
+    let Some(track) = sync_engine.current_track().await else {
+        return;
+    };
+    let translation = if translator.auto_translates() {
+        translator.translate_track(&track).await.map(Some)
+    } else {
+        translator.cached_translation(&track).await
+    };
+    match translation {
+        Ok(Some(translation)) => karaoke.set_translations(&translation.lines),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to load translation of {}: {}", track.name, e),
+    }
+}
+
 fn handle_sync_event(
     event: SyncEvent,
     mut karaoke: KaraokeState,
     mut timer: Signal<LocalPlaybackTimer>,
+    ui_config: Signal<UiConfig>,
 ) {
     match event {
         // === Lyrics events ===
@@ -103,7 +235,9 @@ fn handle_sync_event(
             karaoke.sync_animation_position(current_pos_ms);
             info!("Loaded {} precomputed lyric lines", lyrics.lines.len());
         }
-        SyncEvent::LyricsNotFound => {
+        SyncEvent::LyricsNotFound
+        | SyncEvent::TrackInstrumental
+        | SyncEvent::LyricsBlocked { .. } => {
             karaoke.clear_lyrics();
         }
 
@@ -132,7 +266,7 @@ fn handle_sync_event(
             timer.write().set_playing(false);
             karaoke.set_playing(false);
         }
-        SyncEvent::PlaybackStopped => {
+        SyncEvent::PlaybackStopped | SyncEvent::AdPlaying => {
             karaoke.clear_lyrics();
             timer.write().hard_sync(0);
             timer.write().set_playing(false);
@@ -145,9 +279,19 @@ fn handle_sync_event(
             timer.write().drift_correct(position.as_millis_u64());
         }
 
+        SyncEvent::ConfigChanged { config } => apply_ui_config(ui_config, &config),
+
         // === Errors ===
-        SyncEvent::Error { .. } => {
-            // Errors are logged elsewhere
+        // Unsynced lyrics are handled by the event listener, which looks up the track duration
+        SyncEvent::Error { .. }
+        | SyncEvent::LyricsTrimmed { .. }
+        | SyncEvent::LyricsSource { .. }
+        | SyncEvent::LyricsRetrying { .. }
+        | SyncEvent::LyricsFetchStarted { .. }
+        | SyncEvent::LyricsFetchFailed { .. }
+        | SyncEvent::AnalysisLoaded { .. }
+        | SyncEvent::UnsyncedLyricsLoaded { .. } => {
+            // Errors and warnings are logged elsewhere
         }
     }
 }
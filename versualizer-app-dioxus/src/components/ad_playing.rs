@@ -0,0 +1,46 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use versualizer_core::{SyncEngine, SyncEvent};
+
+/// Muted "Ad playing" shown while Spotify plays an ad on a free account, in place of the
+/// lyrics of a track that is not there
+#[component]
+pub fn AdPlaying() -> Element {
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut ad_playing = use_signal(|| false);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        async move {
+            let mut rx = sync_engine.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(SyncEvent::AdPlaying) => ad_playing.set(true),
+                    Ok(
+                        SyncEvent::TrackChanged { .. }
+                        | SyncEvent::PlaybackStarted { .. }
+                        | SyncEvent::PlaybackStopped,
+                    ) => {
+                        if *ad_playing.peek() {
+                            ad_playing.set(false);
+                        }
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+
+    if !ad_playing() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "ad-playing",
+            "Ad playing"
+        }
+    }
+}
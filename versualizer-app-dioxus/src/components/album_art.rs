@@ -0,0 +1,78 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use versualizer_core::{ArtworkCache, ArtworkDisplay, SyncEngine, SyncEvent, UiConfig};
+
+/// Album art of the playing track, blurred behind the lyrics or as a badge in the corner,
+/// as set by `[ui.artwork] display`. Covers are shown as `data:` URLs from the artwork
+/// cache, so each one is downloaded once.
+#[component]
+pub fn AlbumArt() -> Element {
+    let ui_config = use_context::<Signal<UiConfig>>();
+    let artwork: Option<Arc<ArtworkCache>> = use_context();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut image = use_signal(|| None::<String>);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        let artwork = artwork.clone();
+        async move {
+            let Some(artwork) = artwork else {
+                return;
+            };
+            let mut rx = sync_engine.subscribe();
+            let mut shown_url = None::<String>;
+            let mut track = sync_engine.current_track().await;
+            loop {
+                let url = track.as_ref().and_then(|track| track.album_art_url.clone());
+                let display = ui_config.peek().artwork.display;
+                if display != ArtworkDisplay::Off && url != shown_url {
+                    let data_url = match url.as_deref() {
+                        Some(url) => match artwork.get(url).await {
+                            Ok(cover) => Some(cover.data_url()),
+                            Err(e) => {
+                                warn!("Failed to load album art: {}", e);
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+                    image.set(data_url);
+                    shown_url = url;
+                }
+
+                track = match rx.recv().await {
+                    Ok(
+                        SyncEvent::TrackChanged { track, .. }
+                        | SyncEvent::PlaybackStarted { track, .. },
+                    ) => Some(track),
+                    Ok(SyncEvent::PlaybackStopped | SyncEvent::AdPlaying) => None,
+                    // Turning the artwork on shows the current cover
+                    Ok(SyncEvent::ConfigChanged { .. }) => sync_engine.current_track().await,
+                    Ok(_) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        debug!("Album art missed {} sync events", n);
+                        sync_engine.current_track().await
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+            }
+        }
+    });
+
+    let Some(url) = image() else {
+        return rsx! {};
+    };
+    match ui_config.read().artwork.display {
+        ArtworkDisplay::Off => rsx! {},
+        ArtworkDisplay::Background => rsx! {
+            div {
+                class: "album-art-background",
+                style: "background-image: url({url})",
+            }
+        },
+        ArtworkDisplay::Badge => rsx! {
+            img { class: "album-art-badge", src: "{url}", alt: "" }
+        },
+    }
+}
@@ -0,0 +1,44 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::warn;
+use versualizer_core::{CacheBackups, LyricsCache};
+
+/// Button to back up the lyrics cache now, like `versualizer-cli backup`
+#[component]
+pub fn BackupSection() -> Element {
+    let cache: Arc<LyricsCache> = use_context();
+    let backups: CacheBackups = use_context();
+    let mut busy = use_signal(|| false);
+    let mut message = use_signal(|| None::<String>);
+
+    let back_up = move |_| {
+        let (cache, backups) = (cache.clone(), backups.clone());
+        busy.set(true);
+        spawn(async move {
+            let summary = match backups.create(&cache).await {
+                Ok(path) => format!("Backed up to {}", path.display()),
+                Err(e) => {
+                    warn!("Failed to back up the lyrics cache: {}", e);
+                    format!("Backup failed: {e}")
+                }
+            };
+            message.set(Some(summary));
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Backups" }
+            button {
+                disabled: busy(),
+                onclick: back_up,
+                "Back up lyrics cache"
+            }
+            if let Some(message) = message() {
+                div { class: "backup-message", "{message}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::{info, warn};
+use versualizer_core::{Bookmark, LyricsCache};
+
+/// Lines bookmarked with Ctrl+Shift+B or the tray menu, newest first, each with a delete
+/// button. `versualizer-cli bookmarks export` exports them as Markdown or JSON.
+#[component]
+pub fn BookmarksSection() -> Element {
+    let cache: Arc<LyricsCache> = use_context();
+
+    let mut bookmarks = use_resource({
+        let cache = cache.clone();
+        move || load_bookmarks(cache.clone())
+    });
+
+    let delete = move |id: i64| {
+        let cache = cache.clone();
+        spawn(async move {
+            info!("Deleting bookmark {}", id);
+            if let Err(e) = cache.delete_bookmark(id).await {
+                warn!("Failed to delete bookmark {}: {}", id, e);
+            }
+            bookmarks.restart();
+        });
+    };
+
+    let bookmarks = bookmarks.read().clone().unwrap_or_default();
+    if bookmarks.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Bookmarks" }
+            for bookmark in bookmarks {
+                div {
+                    key: "{bookmark.id}",
+                    class: "bookmark",
+                    div { class: "bookmark-text", "{bookmark.text}" }
+                    div {
+                        class: "bookmark-source",
+                        span { "{bookmark.artist} - {bookmark.track}" }
+                        button {
+                            onclick: {
+                                let delete = delete.clone();
+                                let id = bookmark.id;
+                                move |_| delete(id)
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load all bookmarks
+async fn load_bookmarks(cache: Arc<LyricsCache>) -> Vec<Bookmark> {
+    cache.bookmarks().await.unwrap_or_else(|e| {
+        warn!("Failed to load bookmarks: {}", e);
+        Vec::new()
+    })
+}
@@ -0,0 +1,108 @@
+use dioxus::prelude::*;
+use tracing::{info, warn};
+use versualizer_core::encryption::delete_keyring_cache_key;
+use versualizer_core::paths::{DataCategory, DataPath, user_data_paths};
+use versualizer_lyrics_musixmatch::musixmatch_data_paths;
+use versualizer_spotify_api::{delete_keyring_token, spotify_data_paths};
+
+/// Buttons to delete each category of locally stored data, like `versualizer-cli purge`.
+/// Each button asks for a second click before deleting anything.
+#[component]
+pub fn DataPurgeSection() -> Element {
+    let mut armed = use_signal(|| None::<DataCategory>);
+    let mut message = use_signal(|| None::<String>);
+
+    let mut purge = move |category: DataCategory| {
+        if *armed.peek() != Some(category) {
+            armed.set(Some(category));
+            message.set(Some(format!(
+                "Click again to delete all {}",
+                category.label()
+            )));
+            return;
+        }
+        armed.set(None);
+        message.set(Some(format!("Deleting {}…", category.label())));
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || purge_category(category)).await;
+            let summary = match result {
+                Ok(summary) => summary,
+                Err(e) => format!("Failed to delete {}: {e}", category.label()),
+            };
+            message.set(Some(summary));
+        });
+    };
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Stored data" }
+            div {
+                class: "purge-actions",
+                for category in DataCategory::ALL {
+                    button {
+                        key: "{category.label()}",
+                        class: if *armed.read() == Some(category) { "purge-armed" } else { "" },
+                        onclick: move |_| purge(category),
+                        "Delete {category.label()}"
+                    }
+                }
+            }
+            if let Some(message) = message() {
+                div { class: "purge-message", "{message}" }
+            }
+        }
+    }
+}
+
+/// Delete every stored path in `category`, along with the cache key or Spotify token in
+/// the OS keyring when purging the cache or tokens. Returns a summary for display.
+fn purge_category(category: DataCategory) -> String {
+    let targets: Vec<DataPath> = user_data_paths()
+        .into_iter()
+        .chain(spotify_data_paths())
+        .chain(musixmatch_data_paths())
+        .filter(|p| p.category == category && p.path.exists())
+        .collect();
+
+    let mut deleted = 0;
+    let mut failures = Vec::new();
+    for target in &targets {
+        match target.remove() {
+            Ok(_) => deleted += 1,
+            Err(e) => {
+                warn!("Failed to delete {}: {}", target.path.display(), e);
+                failures.push(target.path.display().to_string());
+            }
+        }
+    }
+    if category == DataCategory::Cache
+        && let Err(e) = delete_keyring_cache_key()
+    {
+        warn!("Failed to delete the cache key from the OS keyring: {}", e);
+        failures.push("cache key in the OS keyring".to_string());
+    }
+    if category == DataCategory::Tokens
+        && let Err(e) = delete_keyring_token()
+    {
+        warn!(
+            "Failed to delete the Spotify token from the OS keyring: {}",
+            e
+        );
+        failures.push("Spotify token in the OS keyring".to_string());
+    }
+
+    info!("Purged {} {} paths", deleted, category.label());
+    if failures.is_empty() {
+        format!(
+            "Deleted {deleted} {} paths. Restart Versualizer to start fresh.",
+            category.label()
+        )
+    } else {
+        format!(
+            "Deleted {deleted} {} paths, could not delete: {}",
+            category.label(),
+            failures.join(", ")
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use crate::state::KaraokeState;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use versualizer_core::{SyncEngine, SyncEvent};
+
+/// Which provider the lyrics fetcher is asking, shown in place of the lyrics while they
+/// load, e.g. "Searching lrclib…". Hidden once lyrics load or no provider had any.
+#[component]
+pub fn FetchProgress() -> Element {
+    let karaoke = use_context::<KaraokeState>();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut progress = use_signal(|| None::<String>);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        async move {
+            let mut rx = sync_engine.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(SyncEvent::LyricsFetchStarted { provider }) => {
+                        progress.set(Some(format!("Searching {provider}…")));
+                    }
+                    Ok(SyncEvent::LyricsFetchFailed { provider, .. }) => {
+                        progress.set(Some(format!("{provider} failed, still searching…")));
+                    }
+                    Ok(
+                        SyncEvent::LyricsLoaded { .. }
+                        | SyncEvent::UnsyncedLyricsLoaded { .. }
+                        | SyncEvent::LyricsNotFound
+                        | SyncEvent::TrackInstrumental
+                        | SyncEvent::LyricsRetrying { .. }
+                        | SyncEvent::TrackChanged { .. }
+                        | SyncEvent::PlaybackStopped
+                        | SyncEvent::AdPlaying,
+                    ) => {
+                        if progress.peek().is_some() {
+                            progress.set(None);
+                        }
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+
+    if karaoke.lyrics.read().is_some() {
+        return rsx! {};
+    }
+    let Some(progress) = progress() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div {
+            class: "fetch-progress",
+            "{progress}"
+        }
+    }
+}
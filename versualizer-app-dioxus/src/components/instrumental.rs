@@ -0,0 +1,50 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use versualizer_core::{SyncEngine, SyncEvent};
+
+/// "Instrumental ♪" shown in place of the lyrics when a provider reports the track as
+/// instrumental, so it does not look like a track whose lyrics are missing
+#[component]
+pub fn Instrumental() -> Element {
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut instrumental = use_signal(|| false);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        async move {
+            let mut rx = sync_engine.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(SyncEvent::TrackInstrumental) => instrumental.set(true),
+                    Ok(
+                        SyncEvent::LyricsLoaded { .. }
+                        | SyncEvent::UnsyncedLyricsLoaded { .. }
+                        | SyncEvent::LyricsNotFound
+                        | SyncEvent::LyricsBlocked { .. }
+                        | SyncEvent::TrackChanged { .. }
+                        | SyncEvent::PlaybackStopped
+                        | SyncEvent::AdPlaying,
+                    ) => {
+                        if *instrumental.peek() {
+                            instrumental.set(false);
+                        }
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+
+    if !instrumental() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "instrumental",
+            "Instrumental ♪"
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use crate::state::{INTRO_LINE_INDEX, KaraokeState, PrecomputedLyrics};
+use crate::state::{CurrentBeat, DuetColumn, INTRO_LINE_INDEX, KaraokeState, TimedWord};
 use dioxus::prelude::*;
 use dioxus_motion::prelude::*;
-use versualizer_core::UiConfig;
+use std::time::Duration;
+use versualizer_core::{LayoutMode, LocaleFormatter, OverlayView, TickerOverflow, UiConfig};
 
 /// Buffer lines for smooth animation (not user-configurable).
 /// These extra lines are rendered outside the visible area to enable
@@ -9,31 +10,140 @@ use versualizer_core::UiConfig;
 const BUFFER_LINES_BEFORE: usize = 1;
 const BUFFER_LINES_AFTER: usize = 1;
 
+/// How often to check whether auto-follow should resume
+const FOLLOW_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Karaoke display component that shows current and upcoming lyrics
 /// with smooth animations powered by dioxus-motion.
 ///
 /// Colors are configured via CSS variables in theme.css:
 /// - `--sung-color`: Color for sung text (use rgba for transparency)
 /// - `--unsung-color`: Color for unsung text (use rgba for transparency)
+///
+/// With `duet_split`, duets get a stack of lines for each singer, side by side, each with
+/// its own current line and fill.
 #[component]
 pub fn KaraokeLine() -> Element {
+    let mut karaoke = use_context::<KaraokeState>();
+    let config = use_context::<Signal<UiConfig>>()();
+    let formatter = use_context::<LocaleFormatter>();
+
+    let is_duet = karaoke
+        .lyrics
+        .read()
+        .as_ref()
+        .is_some_and(|lyrics| lyrics.is_duet);
+    let duet = config.layout.duet_split && is_duet;
+    let following = karaoke.view_anchor.read().is_none();
+
+    // Resume auto-follow a while after the user stops scrolling or moves the pointer away
+    let resume_after = Duration::from_millis(config.follow.resume_after_ms);
+    let mut resume_in = use_signal(|| None::<Duration>);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(FOLLOW_CHECK_INTERVAL).await;
+            if karaoke.should_resume_follow(resume_after) {
+                karaoke.resume_follow();
+            }
+            let remaining = karaoke.resume_follow_in(resume_after);
+            if *resume_in.peek() != remaining {
+                resume_in.set(remaining);
+            }
+        }
+    });
+
+    let resume_label = match *resume_in.read() {
+        Some(remaining) if !following => {
+            format!("Resume in {}s ↓", formatter.format_countdown(remaining))
+        }
+        _ => "Resume ↓".to_string(),
+    };
+
+    rsx! {
+        if !following {
+            // Resume affordance while auto-follow is paused. Stop mousedown so clicking
+            // it does not start a window drag.
+            button {
+                class: "resume-follow",
+                onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+                onclick: move |_| karaoke.resume_follow(),
+                "{resume_label}"
+            }
+        }
+        if duet {
+            div {
+                class: "duet-columns",
+                LineStack { column: Some(DuetColumn::Left) }
+                LineStack { column: Some(DuetColumn::Right) }
+            }
+        } else {
+            LineStack { column: None }
+        }
+    }
+}
+
+/// A stack of lines scrolling to its current line: every line, or the lines of one
+/// `column` of a split duet, whose current line is the last of them to start
+#[allow(clippy::too_many_lines)]
+#[component]
+fn LineStack(column: Option<DuetColumn>) -> Element {
     let karaoke = use_context::<KaraokeState>();
-    let config = use_context::<UiConfig>();
+    let config = use_context::<Signal<UiConfig>>()();
+
+    // The stack's lines only change with the lyrics
+    let stack = use_memo(move || {
+        karaoke
+            .lyrics
+            .read()
+            .as_ref()
+            .map(|lyrics| lyrics.stack_lines(column))
+            .unwrap_or_default()
+    });
 
     // Read signals
     let is_playing = *karaoke.is_playing.read();
     let current_index = *karaoke.current_index.read(); // i32: -1 = intro, 0+ = line index
-    let lyrics = karaoke.lyrics.read();
+    // Lines are placed by their rank in the stack, the current one by its line's
+    let current_rank = stack.read().rank_at(current_index);
+    let stack_current_index = stack.read().index_at(current_rank);
+    let view_anchor = *karaoke.view_anchor.read();
+    let has_lyrics = karaoke.lyrics.read().is_some();
+    // Plain lyrics scroll along with the track but have no current line to fill
+    let unsynced = karaoke
+        .lyrics
+        .read()
+        .as_ref()
+        .is_some_and(|lyrics| lyrics.unsynced);
+    // Duets color each singer's lines
+    let is_duet = karaoke
+        .lyrics
+        .read()
+        .as_ref()
+        .is_some_and(|lyrics| lyrics.is_duet);
     let animation_sync_position_ms = *karaoke.animation_sync_position_ms.read();
+    // Alternating classes restart the pulse animation on every beat
+    let beat_class = match *use_context::<CurrentBeat>().index.read() {
+        Some(beat) if beat % 2 == 0 => " beat-even",
+        Some(_) => " beat-odd",
+        None => "",
+    };
 
-    // Calculate how many lines to request (visible + buffer)
-    let visible_count = config.layout.max_lines;
-    let lines_after = visible_count.saturating_sub(1) + BUFFER_LINES_AFTER;
+    // Visible slots: `history_lines` sung lines above the current line, the rest below
+    let view = config.layout.view;
+    let (visible_count, history_lines) = config.layout.visible_lines();
+    let lines_before = history_lines + BUFFER_LINES_BEFORE;
+    let lines_after = visible_count.saturating_sub(1 + history_lines) + BUFFER_LINES_AFTER;
 
-    // Get visible lines with buffer
-    let visible = karaoke.visible_lines(BUFFER_LINES_BEFORE, lines_after);
+    // Only render the lines around where the view is (or is scrolling to), so large
+    // layouts and long peeks never build more than a screenful of DOM nodes.
+    // Anchors are bounded by the lyrics length, far inside i32's range.
+    #[allow(clippy::cast_possible_truncation)]
+    let view_center = view_anchor.map_or(current_rank, |anchor| {
+        stack.read().rank_of_position(anchor).round() as i32
+    });
+    let visible = karaoke.lines_window(&stack.read(), view_center, lines_before, lines_after);
 
-    // Animated scroll offset - represents the current line index as a float
+    // Animated scroll offset - represents the current line's rank as a float
     // -1.0 for intro, 0.0+ for actual lines
     // Safe cast: INTRO_LINE_INDEX is -1, which is exactly representable in f32
     #[allow(clippy::cast_precision_loss)]
@@ -42,108 +152,129 @@ pub fn KaraokeLine() -> Element {
     // Keep Signal reference for use in effect (must read INSIDE effect for reactivity)
     let current_index_signal = karaoke.current_index;
 
+    // Spring parameters from [ui.animation] (already clamped to sane bounds on load).
+    // With animations off, scrolling jumps straight to the target.
+    let scroll_mode = if config.animation.enabled {
+        AnimationMode::Spring(Spring {
+            stiffness: config.animation.spring_stiffness,
+            damping: config.animation.spring_damping,
+            mass: config.animation.spring_mass,
+            ..Default::default()
+        })
+    } else {
+        AnimationMode::Tween(Tween::new(Duration::ZERO))
+    };
+
     // Animate scroll offset when current line changes
     use_effect(move || {
         // Read signal INSIDE effect - creates reactive dependency so effect re-runs
-        let target_offset = *current_index_signal.read();
+        let target_offset = stack.read().rank_at(*current_index_signal.read());
 
         #[allow(clippy::cast_precision_loss)]
         let target = target_offset as f32;
 
         // Use spring animation for smooth, natural scrolling
-        scroll_offset.animate_to(
-            target,
-            AnimationConfig::new(AnimationMode::Spring(Spring {
-                stiffness: 180.0,
-                damping: 20.0,
-                mass: 1.0,
-                ..Default::default()
-            })),
-        );
+        scroll_offset.animate_to(target, AnimationConfig::new(scroll_mode));
     });
 
-    // Set CSS variables from config (all calculations done in CSS)
-    let container_style = format!("--max-lines: {visible_count};");
+    // While auto-follow is paused, animate a peek offset that cancels out the follow
+    // scroll so the view stays put, and the current line keeps its fill animation
+    let mut peek_offset = use_motion(0.0f32);
+    let anchor_signal = karaoke.view_anchor;
+    use_effect(move || {
+        let anchor = *anchor_signal.read();
+        let stack = stack.read();
+        #[allow(clippy::cast_precision_loss)]
+        let current = stack.rank_at(*current_index_signal.read()) as f32;
+        let target = anchor.map_or(0.0, |anchor| stack.rank_of_position(anchor) - current);
+        peek_offset.animate_to(target, AnimationConfig::new(scroll_mode));
+    });
 
-    // Play state for CSS animation
-    let play_state = if is_playing { "running" } else { "paused" };
+    // Entrance effect for the line that becomes current
+    let line_entrance = config.animation.line_entrance;
+    let entrance_class = line_entrance.as_css_class();
 
-    // Check if there's an intro (lyrics exist and have intro duration > 0)
-    let has_intro = lyrics.as_ref().is_some_and(PrecomputedLyrics::has_intro);
+    // Set CSS variables from config (all calculations done in CSS)
+    let fill_easing = config.animation.fill_easing.as_css();
+    let container_style = format!(
+        "--max-lines: {visible_count}; --history-lines: {history_lines}; --fill-easing: {fill_easing};"
+    );
 
-    // Determine which index in `visible` array is the current line.
-    // When current_index < 0 (intro), the first visible line is the intro line at index 0.
-    // When current_index >= 0, we need to account for buffer lines before.
-    let current_visible_idx: Option<usize> = if current_index < 0 {
-        // In intro: if there's an intro line, it's at visible[0]
-        if has_intro { Some(0) } else { None }
+    // Ticker mode puts the current line alone on one line, scrolling it if it is too long
+    let mode = config.layout.mode;
+    let mut view_class = format!("{} {}", view.as_css_class(), mode.as_css_class());
+    if mode == LayoutMode::Ticker && config.layout.ticker_overflow == TickerOverflow::Scroll {
+        view_class.push_str(" ticker-scroll");
+    }
+    if !config.animation.enabled {
+        view_class.push_str(" no-animation");
+    }
+    let mut lines_class = if unsynced {
+        format!("lines {view_class} unsynced")
     } else {
-        // On an actual line - calculate position in visible array
-        // current_index >= 0 is guaranteed by the outer if condition
-        let idx = usize::try_from(current_index).unwrap_or(0);
-        let actual_buffer_before = idx.min(BUFFER_LINES_BEFORE);
-        if visible.len() > actual_buffer_before {
-            Some(actual_buffer_before)
-        } else {
-            None
-        }
+        format!("lines {view_class}")
     };
+    if let Some(column) = column {
+        lines_class.push_str(" duet ");
+        lines_class.push_str(column.as_css_class());
+    }
+    // The performer sees how long until the next line starts
+    let show_countdown = view == OverlayView::Performer;
+
+    // Play state for CSS animation
+    let play_state = if is_playing { "running" } else { "paused" };
 
     // If no lyrics loaded, show empty container
-    if lyrics.is_none() || visible.is_empty() {
+    if !has_lyrics || visible.is_empty() {
         return rsx! {
             div {
-                class: "lines",
+                class: "lines {view_class}",
                 style: "{container_style}",
             }
         };
     }
 
-    // Get the current animated scroll value
+    // Get the current animated scroll values. The follow offset tracks the current line;
+    // the view offset adds the user's peek and decides where lines are drawn.
     let animated_offset = scroll_offset.get_value();
-
-    // Calculate where the visible array starts in absolute line index terms.
-    // In intro mode (current_index < 0), visible starts at -1 (intro line).
-    // Otherwise, it starts at max(0, current_index - BUFFER_LINES_BEFORE).
-    let visible_start_idx: i32 = if current_index < 0 {
-        INTRO_LINE_INDEX // -1
-    } else {
-        // Safe: BUFFER_LINES_BEFORE is a small constant (1)
-        #[allow(clippy::cast_possible_wrap)]
-        let buffer = i32::try_from(BUFFER_LINES_BEFORE).unwrap_or(i32::MAX);
-        (current_index - buffer).max(0)
-    };
+    let view_offset = animated_offset + peek_offset.get_value();
 
     rsx! {
         div {
-            class: "lines",
+            class: "{lines_class}",
             style: "{container_style}",
 
-            for (idx, line) in visible.iter().enumerate() {
+            for (line_rank, line_absolute_idx, line) in visible.iter() {
                 {
-                    // Calculate the absolute line index for this visible line
-                    // Safe: idx is a small index into visible array (typically < 10 elements)
-                    #[allow(clippy::cast_possible_wrap)]
-                    let line_absolute_idx = visible_start_idx + i32::try_from(idx).unwrap_or(i32::MAX);
+                    let line_rank = *line_rank;
+                    let line_absolute_idx = *line_absolute_idx;
 
-                    // Distance from current line (used by CSS for scale and opacity calculations)
+                    // Distance from the view position (used by CSS for scale and opacity calculations)
                     #[allow(clippy::cast_precision_loss)]
-                    let distance = (line_absolute_idx as f32) - animated_offset;
+                    let distance = (line_rank as f32) - view_offset;
 
-                    // Determine if this line is the "current" line
-                    let is_current = distance.abs() < 0.5 && current_visible_idx == Some(idx);
+                    // The current line switches once the follow scroll is halfway there
+                    #[allow(clippy::cast_precision_loss)]
+                    let follow_distance = (line_rank as f32) - animated_offset;
+                    let is_current = !unsynced
+                        && Some(line_absolute_idx) == stack_current_index
+                        && follow_distance.abs() < 0.5;
+                    // In a duet column the current line may be the other singer's turn
+                    let is_sung_now = is_current && line_absolute_idx == current_index;
 
-                    let line_class = if is_current {
-                        "karaoke-line current"
-                    } else {
-                        "karaoke-line upcoming"
-                    };
+                    let state_class = if is_current { "current" } else { "upcoming" };
+                    let line_class = format!(
+                        "karaoke-line {state_class} {}{}{}",
+                        if is_duet { line.part.as_css_class() } else { "" },
+                        if is_sung_now { beat_class } else { "" },
+                        if line.rtl { " rtl" } else { "" }
+                    );
 
                     // Pass raw values to CSS - all transform/opacity calculations done in CSS
                     let line_duration_ms = line.duration_ms;
                     let line_style = format!(
-                        "--line-index: {line_absolute_idx}; \
-                         --scroll-offset: {animated_offset}; \
+                        "--line-index: {line_rank}; \
+                         --scroll-offset: {view_offset}; \
                          --distance: {distance}; \
                          --duration: {line_duration_ms}ms; \
                          --play-state: {play_state};",
@@ -158,12 +289,13 @@ pub fn KaraokeLine() -> Element {
                     let animation_key = format!("{}-{}", line.start_time_ms, animation_sync_position_ms);
 
                     // Calculate animation offset for seek support (negative delay starts animation partway)
-                    // Only apply offset if we've synced into this line
-                    let animation_delay_ms: i64 = if animation_sync_position_ms >= line.start_time_ms
-                        && animation_sync_position_ms < line.start_time_ms.saturating_add(line.duration_ms)
-                    {
-                        // We're syncing within this line - calculate offset
-                        let offset = animation_sync_position_ms.saturating_sub(line.start_time_ms);
+                    // Only apply offset if we've synced into or past this line: a duet column
+                    // keeps showing its last line, filled, while the other singer sings
+                    let animation_delay_ms: i64 = if animation_sync_position_ms >= line.start_time_ms {
+                        // We're syncing within or after this line - calculate offset
+                        let offset = animation_sync_position_ms
+                            .saturating_sub(line.start_time_ms)
+                            .min(line.duration_ms);
                         // Negative delay to start animation partway through
                         // Safe: offset is always <= duration which fits in i64
                         #[allow(clippy::cast_possible_wrap)]
@@ -173,26 +305,50 @@ pub fn KaraokeLine() -> Element {
                         0
                     };
 
+                    // Per-word effects (typewriter) render each word with its own reveal delay
+                    let entrance_words = if is_current && line_entrance.is_per_word() {
+                        line.word_reveal_offsets()
+                    } else {
+                        Vec::new()
+                    };
+
                     rsx! {
                         div {
                             key: "{line_key}",
                             class: "{line_class}",
                             style: "{line_style}",
+                            dir: if line.rtl { "rtl" } else { "ltr" },
 
                             if is_current {
                                 // Current line with karaoke fill animation
                                 // Wrap in a keyed div to restart animation on line change or seek
                                 div {
                                     key: "{animation_key}",
-                                    class: "current-line-wrapper",
+                                    class: "current-line-wrapper {entrance_class}",
                                     style: "--animation-delay: {animation_delay_ms}ms;",
-                                    span {
-                                        class: "current-line-unsung",
-                                        "{line.text}"
+                                    if line.is_long_instrumental() {
+                                        CountdownDots {}
+                                    } else if entrance_words.is_empty() {
+                                        span {
+                                            class: "current-line-unsung",
+                                            "{line.text}"
+                                        }
+                                        span {
+                                            class: "current-line-sung",
+                                            "{line.text}"
+                                        }
+                                    } else {
+                                        span {
+                                            class: "current-line-unsung",
+                                            EntranceWords { words: entrance_words.clone() }
+                                        }
+                                        span {
+                                            class: "current-line-sung",
+                                            EntranceWords { words: entrance_words.clone() }
+                                        }
                                     }
-                                    span {
-                                        class: "current-line-sung",
-                                        "{line.text}"
+                                    if show_countdown && !line.is_long_instrumental() {
+                                        div { class: "next-line-countdown" }
                                     }
                                 }
                             } else {
@@ -202,6 +358,18 @@ pub fn KaraokeLine() -> Element {
                                     "{line.text}"
                                 }
                             }
+                            if let Some(ref romanization) = line.romanization {
+                                div {
+                                    class: "line-romanization",
+                                    "{romanization}"
+                                }
+                            }
+                            if let Some(ref translation) = line.translation {
+                                div {
+                                    class: "line-translation",
+                                    "{translation}"
+                                }
+                            }
                         }
                     }
                 }
@@ -209,3 +377,32 @@ pub fn KaraokeLine() -> Element {
         }
     }
 }
+
+/// Three dots that fill one after another over an instrumental intro or break, so the
+/// next line starts as the last one fills. Timed by the line's `--duration` and
+/// `--animation-delay` like the karaoke fill.
+#[component]
+fn CountdownDots() -> Element {
+    rsx! {
+        span {
+            class: "countdown-dots",
+            for index in 0..3 {
+                span { class: "countdown-dot", style: "--dot-index: {index};" }
+            }
+        }
+    }
+}
+
+/// Line text split into words that are revealed one after another
+#[component]
+fn EntranceWords(words: Vec<TimedWord>) -> Element {
+    rsx! {
+        for word in words.iter() {
+            span {
+                class: "entrance-word",
+                style: "--word-delay: {word.offset_ms}ms;",
+                "{word.text}"
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::{info, warn};
+use versualizer_core::{ConflictResolution, DiffLine, LyricsCache, LyricsConflict, LyricsDiff};
+
+/// A conflict with its line diff, ready to display
+#[derive(Clone, Debug, PartialEq)]
+struct ConflictView {
+    id: i64,
+    title: String,
+    provider: String,
+    diff: LyricsDiff,
+}
+
+/// Lyrics that a provider returned differently from the cache, shown as a line diff with
+/// keep mine / take theirs / merge buttons
+#[component]
+pub fn LyricsConflictsSection() -> Element {
+    let cache: Arc<LyricsCache> = use_context();
+
+    let mut conflicts = use_resource({
+        let cache = cache.clone();
+        move || load_conflicts(cache.clone())
+    });
+
+    let resolve = move |id: i64, resolution: ConflictResolution| {
+        let cache = cache.clone();
+        spawn(async move {
+            info!("Resolving lyrics conflict {} with {:?}", id, resolution);
+            if let Err(e) = cache.resolve_conflict(id, resolution).await {
+                warn!("Failed to resolve lyrics conflict {}: {}", id, e);
+            }
+            conflicts.restart();
+        });
+    };
+
+    let conflicts = conflicts.read().clone().unwrap_or_default();
+    if conflicts.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Lyrics conflicts" }
+            for conflict in conflicts {
+                div {
+                    key: "{conflict.id}",
+                    class: "conflict",
+                    div {
+                        class: "conflict-title",
+                        span { "{conflict.title}" }
+                        span {
+                            class: "conflict-source",
+                            "{conflict.provider} · {similarity_percent(&conflict.diff)}% alike"
+                        }
+                    }
+                    div {
+                        class: "conflict-diff",
+                        for (i, line) in conflict.diff.lines.iter().enumerate() {
+                            match line {
+                                DiffLine::Same(text) => rsx! {
+                                    div { key: "{i}", class: "diff-same", "  {text}" }
+                                },
+                                DiffLine::Removed(text) => rsx! {
+                                    div { key: "{i}", class: "diff-removed", "− {text}" }
+                                },
+                                DiffLine::Added(text) => rsx! {
+                                    div { key: "{i}", class: "diff-added", "+ {text}" }
+                                },
+                            }
+                        }
+                    }
+                    div {
+                        class: "auth-row-actions",
+                        for (label, resolution) in [
+                            ("Keep mine", ConflictResolution::KeepMine),
+                            ("Take theirs", ConflictResolution::TakeTheirs),
+                            ("Merge", ConflictResolution::Merge),
+                        ] {
+                            button {
+                                key: "{label}",
+                                onclick: {
+                                    let resolve = resolve.clone();
+                                    let id = conflict.id;
+                                    move |_| resolve(id, resolution)
+                                },
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load unresolved conflicts with their diffs
+async fn load_conflicts(cache: Arc<LyricsCache>) -> Vec<ConflictView> {
+    let conflicts: Vec<LyricsConflict> = match cache.conflicts().await {
+        Ok(conflicts) => conflicts,
+        Err(e) => {
+            warn!("Failed to load lyrics conflicts: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut views = Vec::with_capacity(conflicts.len());
+    for conflict in conflicts {
+        match cache.conflict_diff(conflict.id).await {
+            Ok(Some(diff)) => views.push(ConflictView {
+                id: conflict.id,
+                title: format!("{} - {}", conflict.artist, conflict.track),
+                provider: conflict.provider,
+                diff,
+            }),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to diff lyrics conflict {}: {}", conflict.id, e),
+        }
+    }
+    views
+}
+
+/// Similarity as a whole percentage
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn similarity_percent(diff: &LyricsDiff) -> u32 {
+    (diff.similarity * 100.0).round() as u32
+}
@@ -0,0 +1,262 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use versualizer_core::{BlockKind, LyricsCandidate, LyricsFetcher, SyncEngine, TrackInfo};
+
+/// The current track with the lyrics to choose from
+#[derive(Clone, Debug, PartialEq)]
+struct Choices {
+    track: TrackInfo,
+    /// Lyrics already cached for the track, one per provider
+    cached: Vec<LyricsCandidate>,
+    /// Providers' search results that are not cached yet
+    found: Vec<LyricsCandidate>,
+    /// Why the track's lyrics are blocked, if they are
+    blocked: Option<BlockKind>,
+}
+
+/// "Wrong lyrics?" picker, opened with its overlay button, the tray menu, or Ctrl+Shift+W.
+/// Lists the current track's cached lyrics from every provider and the providers' search
+/// results. The chosen lyrics are pinned in the cache under the track's ID, so they are
+/// used whenever it plays again. The track or its artist can also be blocked from showing
+/// lyrics at all, or unblocked when it already is.
+#[component]
+pub fn LyricsPicker(on_close: EventHandler<()>) -> Element {
+    let fetcher: Arc<LyricsFetcher> = use_context();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut message = use_signal(|| None::<String>);
+    let mut choosing = use_signal(|| false);
+
+    let choices = use_resource({
+        let fetcher = fetcher.clone();
+        move || load_choices(fetcher.clone(), sync_engine.clone())
+    });
+
+    let block = {
+        let fetcher = fetcher.clone();
+        move |track: TrackInfo, kind: Option<BlockKind>| {
+            let fetcher = fetcher.clone();
+            spawn(async move {
+                choosing.set(true);
+                let result = match kind {
+                    Some(kind) => fetcher.block(kind, &track).await,
+                    None => fetcher.unblock(&track).await.map(|_| ()),
+                };
+                match result {
+                    Ok(()) => on_close.call(()),
+                    Err(e) => {
+                        warn!("Failed to update the blocklist: {}", e);
+                        message.set(Some(format!("Could not update the blocklist: {e}")));
+                    }
+                }
+                choosing.set(false);
+            });
+        }
+    };
+
+    let choose = move |track: TrackInfo, candidate: LyricsCandidate| {
+        let fetcher = fetcher.clone();
+        spawn(async move {
+            choosing.set(true);
+            match fetcher.use_candidate(&track, &candidate).await {
+                Ok(()) => {
+                    info!(
+                        "Using {}'s lyrics {} for {}",
+                        candidate.provider, candidate.id, track.name
+                    );
+                    on_close.call(());
+                }
+                Err(e) => {
+                    warn!("Failed to use the chosen lyrics: {}", e);
+                    message.set(Some(format!("Could not use these lyrics: {e}")));
+                }
+            }
+            choosing.set(false);
+        });
+    };
+
+    let blocking = match &*choices.read() {
+        Some(Some(choices)) => {
+            let track = choices.track.clone();
+            let artist = BlockKind::Artist.label_of(&track);
+            match choices.blocked {
+                Some(kind) => rsx! {
+                    div {
+                        class: "settings-section",
+                        h3 { "Hidden" }
+                        div {
+                            class: "picker-status",
+                            "Lyrics are hidden for this {kind}"
+                        }
+                        div {
+                            class: "auth-row-actions",
+                            button {
+                                disabled: choosing(),
+                                onclick: {
+                                    let block = block.clone();
+                                    move |_| block(track.clone(), None)
+                                },
+                                "Show lyrics again"
+                            }
+                        }
+                    }
+                },
+                None => rsx! {
+                    div {
+                        class: "settings-section",
+                        h3 { "Never show lyrics" }
+                        div {
+                            class: "auth-row-actions",
+                            button {
+                                disabled: choosing(),
+                                onclick: {
+                                    let block = block.clone();
+                                    let track = track.clone();
+                                    move |_| block(track.clone(), Some(BlockKind::Track))
+                                },
+                                "For this track"
+                            }
+                            button {
+                                disabled: choosing(),
+                                onclick: {
+                                    let block = block.clone();
+                                    move |_| block(track.clone(), Some(BlockKind::Artist))
+                                },
+                                "For {artist}"
+                            }
+                        }
+                    }
+                },
+            }
+        }
+        _ => rsx! {},
+    };
+
+    let body = match &*choices.read() {
+        None => rsx! {
+            div { class: "picker-status", "Searching providers…" }
+        },
+        Some(None) => rsx! {
+            div { class: "picker-status", "Nothing is playing" }
+        },
+        Some(Some(choices)) if choices.blocked.is_some() => rsx! {},
+        Some(Some(choices)) if choices.cached.is_empty() && choices.found.is_empty() => rsx! {
+            div { class: "picker-status", "No provider has lyrics for this track" }
+        },
+        Some(Some(choices)) => {
+            let sections = [("Cached", &choices.cached), ("Found", &choices.found)];
+            rsx! {
+                for (title, candidates) in sections {
+                    if !candidates.is_empty() {
+                        div {
+                            key: "{title}",
+                            class: "settings-section",
+                            h3 { "{title}" }
+                            for candidate in candidates.iter().cloned() {
+                                div {
+                                    key: "{candidate.provider}-{candidate.id}",
+                                    class: "picker-candidate",
+                                    div {
+                                        class: "conflict-title",
+                                        span { "{candidate.artist} - {candidate.title}" }
+                                        span {
+                                            class: "conflict-source",
+                                            "{describe(&candidate)}"
+                                        }
+                                    }
+                                    if let Some(ref preview) = candidate.preview {
+                                        div { class: "picker-preview", "{preview}" }
+                                    }
+                                    div {
+                                        class: "auth-row-actions",
+                                        button {
+                                            disabled: choosing(),
+                                            onclick: {
+                                                let choose = choose.clone();
+                                                let track = choices.track.clone();
+                                                let candidate = candidate.clone();
+                                                move |_| choose(track.clone(), candidate.clone())
+                                            },
+                                            "Use these"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "settings-panel",
+            // Keep clicks inside the picker from dragging the window
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+
+            div {
+                class: "settings-header",
+                span { "Wrong lyrics?" }
+                button { onclick: move |_| on_close.call(()), "Close" }
+            }
+            if let Some(message) = message() {
+                div { class: "picker-status", "{message}" }
+            }
+            {body}
+            {blocking}
+        }
+    }
+}
+
+/// The current track's cached lyrics and search results, or `None` if nothing plays
+async fn load_choices(
+    fetcher: Arc<LyricsFetcher>,
+    sync_engine: Arc<SyncEngine>,
+) -> Option<Choices> {
+    let track = sync_engine.current_track().await?;
+    let blocked = fetcher.blocked_by(&track).await;
+    if blocked.is_some() {
+        return Some(Choices {
+            track,
+            cached: Vec::new(),
+            found: Vec::new(),
+            blocked,
+        });
+    }
+    let cached = fetcher.cached_candidates(&track).await.unwrap_or_else(|e| {
+        warn!("Failed to read cached lyrics: {}", e);
+        Vec::new()
+    });
+    let found = fetcher
+        .search(&track)
+        .await
+        .into_iter()
+        .filter(|candidate| {
+            !cached
+                .iter()
+                .any(|c| c.provider == candidate.provider && c.id == candidate.id)
+        })
+        .collect();
+    Some(Choices {
+        track,
+        cached,
+        found,
+        blocked,
+    })
+}
+
+/// Provider, length and whether the lyrics are timed, e.g. "lrclib · 3:05 · synced"
+fn describe(candidate: &LyricsCandidate) -> String {
+    let mut parts = vec![candidate.provider.clone()];
+    if let Some(duration) = candidate
+        .duration_secs
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+    {
+        let secs = duration.as_secs();
+        parts.push(format!("{}:{:02}", secs / 60, secs % 60));
+    }
+    parts.push(if candidate.synced { "synced" } else { "plain" }.to_string());
+    parts.join(" · ")
+}
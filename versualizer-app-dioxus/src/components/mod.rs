@@ -1,3 +1,30 @@
+mod ad_playing;
+mod album_art;
+mod backup;
+mod bookmarks;
+mod data_purge;
+mod fetch_progress;
+mod instrumental;
 mod karaoke_line;
+mod lyrics_conflicts;
+mod lyrics_picker;
+mod no_lyrics;
+mod playback_controls;
+mod preferences;
+mod provider_status_line;
+mod settings_panel;
+mod timing_editor_panel;
+mod track_header;
 
+pub use ad_playing::AdPlaying;
+pub use album_art::AlbumArt;
+pub use fetch_progress::FetchProgress;
+pub use instrumental::Instrumental;
 pub use karaoke_line::KaraokeLine;
+pub use lyrics_picker::LyricsPicker;
+pub use no_lyrics::NoLyrics;
+pub use playback_controls::PlaybackControls;
+pub use provider_status_line::ProviderStatusLine;
+pub use settings_panel::SettingsPanel;
+pub use timing_editor_panel::TimingEditorPanel;
+pub use track_header::TrackHeader;
@@ -0,0 +1,54 @@
+use crate::state::MissingLyrics;
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+use versualizer_core::{NoLyricsDisplay, NowSingingFiles, UiConfig};
+
+/// Shown in place of the lyrics once no provider had any for the track, as chosen by
+/// `[ui.behavior] no_lyrics`: the track scrolling by, its album art, a custom message, or
+/// nothing at all with the overlay window hidden.
+#[component]
+pub fn NoLyrics() -> Element {
+    let missing_lyrics = use_context::<MissingLyrics>();
+    let config = use_context::<Signal<UiConfig>>()();
+    let display = config.behavior.no_lyrics;
+    let window = use_window();
+
+    // Hide the whole overlay until the next lyrics load
+    use_effect(move || {
+        let missing = missing_lyrics.track.read().is_some();
+        if display == NoLyricsDisplay::Hide {
+            window.set_visible(!missing);
+        }
+    });
+
+    let track = missing_lyrics.track.read();
+    let Some(ref track) = *track else {
+        return rsx! {};
+    };
+
+    match (display, track.album_art_url.as_deref()) {
+        (NoLyricsDisplay::AlbumArt, Some(url)) => rsx! {
+            div {
+                class: "no-lyrics no-lyrics-album-art",
+                img { src: "{url}", alt: "{track.album}" }
+            }
+        },
+        // Sources without artwork show the track instead
+        (NoLyricsDisplay::Track | NoLyricsDisplay::AlbumArt, _) => rsx! {
+            div {
+                class: "no-lyrics no-lyrics-track",
+                span { class: "marquee", "{track.name} · {track.artist}" }
+            }
+        },
+        (NoLyricsDisplay::Message, _) => {
+            let message = NowSingingFiles::format_track(&config.behavior.no_lyrics_message, track);
+            rsx! {
+                div {
+                    class: "no-lyrics no-lyrics-message",
+                    "{message}"
+                }
+            }
+        }
+        (NoLyricsDisplay::Hide, _) => rsx! {},
+    }
+}
@@ -0,0 +1,91 @@
+use crate::state::KaraokeState;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use versualizer_core::{PlaybackController, SyncEngine};
+
+/// How far the skip back and forward buttons seek
+const SEEK_STEP: Duration = Duration::from_secs(10);
+
+/// A playback command from one of the buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Previous,
+    SeekBack,
+    PlayPause,
+    SeekForward,
+    Next,
+}
+
+/// Previous, skip back, play/pause, skip forward and next buttons, shown while the pointer
+/// is over the overlay. Hidden when the music source cannot be controlled.
+#[component]
+pub fn PlaybackControls() -> Element {
+    let controller: Option<Arc<dyn PlaybackController>> = use_context();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let karaoke = use_context::<KaraokeState>();
+    let Some(controller) = controller else {
+        return rsx! {};
+    };
+    let playing = *karaoke.is_playing.read();
+
+    let buttons = [
+        (Command::Previous, "⏮", "Previous track"),
+        (Command::SeekBack, "⏪", "Back 10 seconds"),
+        (
+            Command::PlayPause,
+            if playing { "⏸" } else { "▶" },
+            if playing { "Pause" } else { "Play" },
+        ),
+        (Command::SeekForward, "⏩", "Forward 10 seconds"),
+        (Command::Next, "⏭", "Next track"),
+    ];
+
+    rsx! {
+        div { class: "playback-controls",
+            for (command , symbol , title) in buttons {
+                button {
+                    key: "{title}",
+                    class: "playback-button",
+                    title,
+                    // Keep the click from starting a window drag
+                    onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+                    onclick: {
+                        let controller = controller.clone();
+                        let sync_engine = sync_engine.clone();
+                        move |_| {
+                            spawn(run(controller.clone(), sync_engine.clone(), command, playing));
+                        }
+                    },
+                    "{symbol}"
+                }
+            }
+        }
+    }
+}
+
+async fn run(
+    controller: Arc<dyn PlaybackController>,
+    sync_engine: Arc<SyncEngine>,
+    command: Command,
+    playing: bool,
+) {
+    let result = match command {
+        Command::Previous => controller.previous().await,
+        Command::Next => controller.next().await,
+        Command::PlayPause if playing => controller.pause().await,
+        Command::PlayPause => controller.play().await,
+        Command::SeekBack => {
+            let position = sync_engine.playback_position().await;
+            controller.seek(position.saturating_sub(SEEK_STEP)).await
+        }
+        Command::SeekForward => {
+            let position = sync_engine.playback_position().await;
+            controller.seek(position + SEEK_STEP).await
+        }
+    };
+    if let Err(e) = result {
+        warn!("Playback control failed: {}", e);
+    }
+}
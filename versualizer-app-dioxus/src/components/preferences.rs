@@ -0,0 +1,319 @@
+use crate::app::set_track_offset;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::{info, warn};
+use versualizer_core::config::{
+    LayoutMode, LyricsProviderType, MAX_GLOBAL_OFFSET_MS, MAX_LINE_LEAD_MS, MAX_LINES_LIMIT,
+};
+use versualizer_core::sync::MAX_TRACK_OFFSET_MS;
+use versualizer_core::{
+    ConfigEditor, CoreError, LyricsCache, SyncEngine, THEME_FILE_NAME, VersualizerConfig,
+    theme_names,
+};
+
+/// Config values the preferences section edits
+#[derive(Clone, Debug, PartialEq)]
+struct Preferences {
+    providers: Vec<LyricsProviderType>,
+    max_lines: usize,
+    mode: LayoutMode,
+    animation: bool,
+    spring_stiffness: f32,
+    spring_damping: f32,
+    theme: String,
+    line_lead_ms: i64,
+    offset_ms: i64,
+}
+
+impl Preferences {
+    fn from_config(config: &VersualizerConfig) -> Self {
+        Self {
+            providers: config.lyrics.providers.clone(),
+            max_lines: config.ui.layout.max_lines,
+            mode: config.ui.layout.mode,
+            animation: config.ui.animation.enabled,
+            spring_stiffness: config.ui.animation.spring_stiffness,
+            spring_damping: config.ui.animation.spring_damping,
+            theme: config.ui.theme.clone(),
+            line_lead_ms: config.sync.line_lead_ms,
+            offset_ms: config.sync.offset_ms,
+        }
+    }
+
+    /// Write the values into config.toml, keeping everything else in it as it is
+    fn save(&self) -> Result<VersualizerConfig, CoreError> {
+        let mut editor = ConfigEditor::open()?;
+        editor.set(
+            "lyrics.providers",
+            self.providers
+                .iter()
+                .map(|provider| provider.name())
+                .collect::<toml_edit::Array>(),
+        )?;
+        editor.set(
+            "ui.layout.max_lines",
+            i64::try_from(self.max_lines).unwrap_or(1),
+        )?;
+        editor.set("ui.layout.mode", self.mode.name())?;
+        editor.set("ui.animation.enabled", self.animation)?;
+        editor.set(
+            "ui.animation.spring_stiffness",
+            f64::from(self.spring_stiffness),
+        )?;
+        editor.set(
+            "ui.animation.spring_damping",
+            f64::from(self.spring_damping),
+        )?;
+        editor.set("ui.theme", self.theme.as_str())?;
+        editor.set("sync.line_lead_ms", self.line_lead_ms)?;
+        editor.set("sync.offset_ms", self.offset_ms)?;
+        editor.save()
+    }
+}
+
+/// Common config values, written back to config.toml on save. The config watcher applies
+/// them like a hand edit of the file; colors and fonts are set in the chosen theme's CSS.
+/// The current track's offset is saved in the lyrics cache instead, as the hotkeys do.
+#[allow(clippy::too_many_lines)]
+#[component]
+pub fn PreferencesSection() -> Element {
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let cache: Arc<LyricsCache> = use_context();
+    let mut preferences = use_signal(|| None::<Preferences>);
+    let mut message = use_signal(|| None::<String>);
+    let themes = use_hook(theme_names);
+    let mut track_offset_ms = use_signal(|| sync_engine.track_offset_ms());
+
+    // Read the file rather than the running config, which safe mode reduces
+    use_hook(
+        move || match ConfigEditor::open().and_then(|editor| editor.config()) {
+            Ok(config) => preferences.set(Some(Preferences::from_config(&config))),
+            Err(e) => message.set(Some(format!("Config file cannot be edited here: {e}"))),
+        },
+    );
+
+    let engine = sync_engine.clone();
+    let save = move |_| {
+        let Some(values) = preferences() else {
+            return;
+        };
+        match values.save() {
+            Ok(config) => {
+                // Timing is not part of what a reloaded config changes
+                engine.set_line_lead_ms(config.sync.line_lead_ms);
+                let engine = engine.clone();
+                spawn(async move {
+                    engine.set_global_offset_ms(config.sync.offset_ms).await;
+                });
+                info!("Saved preferences to the config file");
+                message.set(Some("Saved".to_string()));
+            }
+            Err(e) => {
+                warn!("Failed to save preferences: {}", e);
+                message.set(Some(format!("Not saved: {e}")));
+            }
+        }
+    };
+
+    let Some(values) = preferences() else {
+        return rsx! {
+            div {
+                class: "settings-section",
+                h3 { "Preferences" }
+                if let Some(message) = message() {
+                    div { class: "preferences-message", "{message}" }
+                }
+            }
+        };
+    };
+    let last = values.providers.len().saturating_sub(1);
+    // Keep a theme that is set in config.toml but missing from the themes directory
+    let mut theme_choices = themes.clone();
+    if !values.theme.is_empty() && !theme_choices.contains(&values.theme) {
+        theme_choices.push(values.theme.clone());
+    }
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Preferences" }
+            div { class: "preferences-label", "Lyrics providers, tried in order" }
+            for (index, provider) in values.providers.iter().enumerate() {
+                div {
+                    key: "{provider.name()}",
+                    class: "preferences-provider",
+                    span { "{provider.name()}" }
+                    button {
+                        disabled: index == 0,
+                        onclick: move |_| {
+                            if let Some(values) = preferences.write().as_mut() {
+                                values.providers.swap(index - 1, index);
+                            }
+                        },
+                        "↑"
+                    }
+                    button {
+                        disabled: index == last,
+                        onclick: move |_| {
+                            if let Some(values) = preferences.write().as_mut() {
+                                values.providers.swap(index, index + 1);
+                            }
+                        },
+                        "↓"
+                    }
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Lines shown"
+                input {
+                    r#type: "number",
+                    min: "1",
+                    max: "{MAX_LINES_LIMIT}",
+                    value: "{values.max_lines}",
+                    oninput: move |evt| {
+                        if let (Ok(max_lines), Some(values)) = (evt.value().parse(), preferences.write().as_mut()) {
+                            values.max_lines = max_lines;
+                        }
+                    },
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Layout"
+                select {
+                    value: "{values.mode.name()}",
+                    onchange: move |evt| {
+                        let mode = LayoutMode::ALL.into_iter().find(|mode| mode.name() == evt.value());
+                        if let (Some(mode), Some(values)) = (mode, preferences.write().as_mut()) {
+                            values.mode = mode;
+                        }
+                    },
+                    for mode in LayoutMode::ALL {
+                        option { value: "{mode.name()}", selected: mode == values.mode, "{mode.name()}" }
+                    }
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Animations"
+                input {
+                    r#type: "checkbox",
+                    checked: values.animation,
+                    onchange: move |evt| {
+                        if let Some(values) = preferences.write().as_mut() {
+                            values.animation = evt.checked();
+                        }
+                    },
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Scroll stiffness"
+                input {
+                    r#type: "number",
+                    step: "10",
+                    value: "{values.spring_stiffness}",
+                    oninput: move |evt| {
+                        if let (Ok(stiffness), Some(values)) = (evt.value().parse(), preferences.write().as_mut()) {
+                            values.spring_stiffness = stiffness;
+                        }
+                    },
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Scroll damping"
+                input {
+                    r#type: "number",
+                    step: "1",
+                    value: "{values.spring_damping}",
+                    oninput: move |evt| {
+                        if let (Ok(damping), Some(values)) = (evt.value().parse(), preferences.write().as_mut()) {
+                            values.spring_damping = damping;
+                        }
+                    },
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Theme"
+                select {
+                    value: "{values.theme}",
+                    onchange: move |evt| {
+                        if let Some(values) = preferences.write().as_mut() {
+                            values.theme = evt.value();
+                        }
+                    },
+                    option { value: "", selected: values.theme.is_empty(), "{THEME_FILE_NAME}" }
+                    for theme in theme_choices {
+                        option {
+                            key: "{theme}",
+                            value: "{theme}",
+                            selected: theme == values.theme,
+                            "{theme}"
+                        }
+                    }
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Line lead (ms)"
+                input {
+                    r#type: "number",
+                    step: "50",
+                    min: "-{MAX_LINE_LEAD_MS}",
+                    max: "{MAX_LINE_LEAD_MS}",
+                    value: "{values.line_lead_ms}",
+                    oninput: move |evt| {
+                        if let (Ok(lead_ms), Some(values)) = (evt.value().parse(), preferences.write().as_mut()) {
+                            values.line_lead_ms = lead_ms;
+                        }
+                    },
+                }
+            }
+            label {
+                class: "preferences-row",
+                "Offset for all tracks (ms)"
+                input {
+                    r#type: "number",
+                    step: "50",
+                    min: "-{MAX_GLOBAL_OFFSET_MS}",
+                    max: "{MAX_GLOBAL_OFFSET_MS}",
+                    value: "{values.offset_ms}",
+                    oninput: move |evt| {
+                        if let (Ok(offset_ms), Some(values)) = (evt.value().parse(), preferences.write().as_mut()) {
+                            values.offset_ms = offset_ms;
+                        }
+                    },
+                }
+            }
+            button { onclick: save, "Save to config.toml" }
+            div {
+                class: "preferences-row",
+                "Offset for this track (ms)"
+                input {
+                    r#type: "number",
+                    step: "100",
+                    min: "-{MAX_TRACK_OFFSET_MS}",
+                    max: "{MAX_TRACK_OFFSET_MS}",
+                    value: "{track_offset_ms}",
+                    oninput: move |evt| {
+                        if let Ok(offset_ms) = evt.value().parse() {
+                            track_offset_ms.set(offset_ms);
+                        }
+                    },
+                }
+                button {
+                    onclick: move |_| {
+                        spawn(set_track_offset(sync_engine.clone(), cache.clone(), track_offset_ms()));
+                    },
+                    "Apply"
+                }
+            }
+            if let Some(message) = message() {
+                div { class: "preferences-message", "{message}" }
+            }
+        }
+    }
+}
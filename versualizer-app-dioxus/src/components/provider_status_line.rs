@@ -0,0 +1,71 @@
+use crate::state::KaraokeState;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use versualizer_core::provider_status::{offline_notice, unreachable_details};
+use versualizer_core::{SyncEngine, SyncEvent};
+
+/// Subtle inline notice shown while every lyrics provider is unreachable, so a network
+/// outage is distinguishable from a track that has no lyrics. Hovering shows each
+/// provider's last error, and a scheduled retry of the lookup is announced alongside.
+#[component]
+pub fn ProviderStatusLine() -> Element {
+    let karaoke = use_context::<KaraokeState>();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    // Upcoming retry as (attempt, max attempts, delay), until lyrics load or the track changes
+    let mut retry = use_signal(|| None::<(u32, u32, Duration)>);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        async move {
+            let mut rx = sync_engine.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(SyncEvent::LyricsRetrying {
+                        attempt,
+                        max_attempts,
+                        retry_in,
+                    }) => retry.set(Some((attempt, max_attempts, retry_in))),
+                    Ok(
+                        SyncEvent::LyricsLoaded { .. }
+                        | SyncEvent::UnsyncedLyricsLoaded { .. }
+                        | SyncEvent::LyricsNotFound
+                        | SyncEvent::TrackInstrumental
+                        | SyncEvent::TrackChanged { .. }
+                        | SyncEvent::PlaybackStopped
+                        | SyncEvent::AdPlaying,
+                    ) => {
+                        if retry.peek().is_some() {
+                            retry.set(None);
+                        }
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+
+    let statuses = karaoke.provider_statuses.read();
+    let showing_lyrics = karaoke.lyrics.read().is_some();
+
+    let Some(notice) = offline_notice(&statuses, showing_lyrics) else {
+        return rsx! {};
+    };
+    let notice = match retry() {
+        Some((attempt, max_attempts, retry_in)) => format!(
+            "{notice} · retry {attempt}/{max_attempts} in {}s",
+            retry_in.as_secs()
+        ),
+        None => notice.to_string(),
+    };
+
+    rsx! {
+        div {
+            class: "provider-status",
+            title: unreachable_details(&statuses),
+            "{notice}"
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use super::backup::BackupSection;
+use super::bookmarks::BookmarksSection;
+use super::data_purge::DataPurgeSection;
+use super::lyrics_conflicts::LyricsConflictsSection;
+use super::preferences::PreferencesSection;
+use dioxus::prelude::*;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+use versualizer_core::{
+    AuthState, CheckStatus, LocaleFormatter, LyricsFetcher, ProviderStatus, ProviderStatusRegistry,
+};
+use versualizer_spotify_api::SpotifyOAuth;
+
+/// How often expiry countdowns are redrawn
+const COUNTDOWN_TICK: Duration = Duration::from_secs(1);
+
+/// Name of the Spotify music source row
+const SPOTIFY_SOURCE: &str = "spotify";
+
+/// One row of the authentication status list
+#[derive(Clone, Debug, PartialEq)]
+struct AuthRow {
+    name: String,
+    /// "Music source" or "Lyrics provider"
+    kind: &'static str,
+    /// `None` for providers that need no credentials
+    state: Option<AuthState>,
+    /// Error from the provider's most recent request
+    last_error: Option<String>,
+}
+
+/// Settings overlay, opened with the settings button, the tray menu, or Ctrl+Shift+O
+#[component]
+pub fn SettingsPanel(on_close: EventHandler<()>) -> Element {
+    rsx! {
+        div {
+            class: "settings-panel",
+            // Keep clicks inside the panel from dragging the window
+            onmousedown: move |evt: MouseEvent| evt.stop_propagation(),
+
+            div {
+                class: "settings-header",
+                span { "Settings" }
+                button { onclick: move |_| on_close.call(()), "Close" }
+            }
+            PreferencesSection {}
+            AuthStatusSection {}
+            LyricsConflictsSection {}
+            BookmarksSection {}
+            BackupSection {}
+            DataPurgeSection {}
+        }
+    }
+}
+
+/// Authentication state of the music source and every lyrics provider, with
+/// re-authenticate and test buttons
+#[component]
+fn AuthStatusSection() -> Element {
+    let fetcher: Arc<LyricsFetcher> = use_context();
+    let registry: Arc<ProviderStatusRegistry> = use_context();
+    let spotify_oauth: Option<Arc<SpotifyOAuth>> = use_context();
+    let formatter: LocaleFormatter = use_context();
+    let mut messages = use_signal(Vec::<(String, String)>::new);
+    let mut now = use_signal(SystemTime::now);
+
+    let mut rows = use_resource({
+        let fetcher = fetcher.clone();
+        let registry = registry.clone();
+        let spotify_oauth = spotify_oauth.clone();
+        move || load_rows(fetcher.clone(), registry.clone(), spotify_oauth.clone())
+    });
+
+    // Reload whenever a provider request changes the registry
+    use_future({
+        let registry = registry.clone();
+        move || {
+            let mut rx = registry.subscribe();
+            async move {
+                while rx.changed().await.is_ok() {
+                    rows.restart();
+                }
+            }
+        }
+    });
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(COUNTDOWN_TICK).await;
+            now.set(SystemTime::now());
+        }
+    });
+
+    let mut set_message = move |name: &str, message: String| {
+        let mut messages = messages.write();
+        messages.retain(|(row, _)| row != name);
+        messages.push((name.to_string(), message));
+    };
+
+    let reauth = {
+        let fetcher = fetcher.clone();
+        let spotify_oauth = spotify_oauth.clone();
+        move |name: String| {
+            let fetcher = fetcher.clone();
+            let spotify_oauth = spotify_oauth.clone();
+            set_message(&name, "Re-authenticating…".into());
+            spawn(async move {
+                info!("Re-authenticating {}", name);
+                let result = match spotify_oauth.filter(|_| name == SPOTIFY_SOURCE) {
+                    Some(oauth) => oauth.reauthenticate().await.map_err(|e| e.to_string()),
+                    None => fetcher.reauth(&name).await.map_err(|e| e.to_string()),
+                };
+                let message = match result {
+                    Ok(()) => "Re-authenticated".to_string(),
+                    Err(e) => {
+                        warn!("Re-authenticating {} failed: {}", name, e);
+                        format!("Re-authentication failed: {e}")
+                    }
+                };
+                set_message(&name, message);
+                rows.restart();
+            });
+        }
+    };
+
+    let test = move |name: String| {
+        let fetcher = fetcher.clone();
+        let spotify_oauth = spotify_oauth.clone();
+        set_message(&name, "Testing…".into());
+        spawn(async move {
+            let result = match spotify_oauth.filter(|_| name == SPOTIFY_SOURCE) {
+                Some(oauth) => Ok(oauth.check_saved_token()),
+                None => fetcher.check_provider(&name).await,
+            };
+            let (passed, message) = match result {
+                Ok(check) => (
+                    check.status == CheckStatus::Pass,
+                    format!("{} {}", check.status.label(), check.detail),
+                ),
+                Err(e) => (false, e.to_string()),
+            };
+            set_message(&name, message);
+            if !passed {
+                rows.restart();
+            }
+        });
+    };
+
+    let now = *now.read();
+    let rows = rows.read().clone().unwrap_or_default();
+    let messages = messages.read().clone();
+
+    rsx! {
+        div {
+            class: "settings-section",
+            h3 { "Authentication" }
+            for row in rows {
+                div {
+                    key: "{row.name}",
+                    class: if row.state.as_ref().is_none_or(|state| state.authenticated) {
+                        "auth-row"
+                    } else {
+                        "auth-row auth-row-problem"
+                    },
+                    div {
+                        class: "auth-row-title",
+                        span { class: "auth-row-name", "{row.name}" }
+                        span { class: "auth-row-kind", "{row.kind}" }
+                    }
+                    div { class: "auth-row-state", "{describe_state(row.state.as_ref(), now, formatter)}" }
+                    if let Some(ref error) = row.last_error {
+                        div { class: "auth-row-error", "Last error: {error}" }
+                    }
+                    if let Some((_, message)) = messages.iter().find(|(name, _)| *name == row.name) {
+                        div { class: "auth-row-message", "{message}" }
+                    }
+                    div {
+                        class: "auth-row-actions",
+                        if row.state.is_some() {
+                            button {
+                                onclick: {
+                                    let mut reauth = reauth.clone();
+                                    let name = row.name.clone();
+                                    move |_| reauth(name.clone())
+                                },
+                                "Re-authenticate"
+                            }
+                        }
+                        button {
+                            onclick: {
+                                let mut test = test.clone();
+                                let name = row.name.clone();
+                                move |_| test(name.clone())
+                            },
+                            "Test"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Gather the auth state of the music source and lyrics providers
+async fn load_rows(
+    fetcher: Arc<LyricsFetcher>,
+    registry: Arc<ProviderStatusRegistry>,
+    spotify_oauth: Option<Arc<SpotifyOAuth>>,
+) -> Vec<AuthRow> {
+    let statuses = registry.snapshot();
+    let mut rows = Vec::new();
+    if let Some(oauth) = spotify_oauth {
+        rows.push(AuthRow {
+            name: SPOTIFY_SOURCE.to_string(),
+            kind: "Music source",
+            state: Some(oauth.auth_state().await),
+            last_error: None,
+        });
+    }
+    for (name, state) in fetcher.auth_states().await {
+        let last_error = match statuses.get(name) {
+            Some(ProviderStatus::Unreachable { error } | ProviderStatus::Failing { error }) => {
+                Some(error.clone())
+            }
+            Some(ProviderStatus::Available) | None => None,
+        };
+        rows.push(AuthRow {
+            name: name.to_string(),
+            kind: "Lyrics provider",
+            state,
+            last_error,
+        });
+    }
+    rows
+}
+
+/// State line with an expiry countdown, e.g. "Signed in · expires in 42:10"
+fn describe_state(
+    state: Option<&AuthState>,
+    now: SystemTime,
+    formatter: LocaleFormatter,
+) -> String {
+    let Some(state) = state else {
+        return "No sign-in needed".to_string();
+    };
+    match state
+        .expires_at
+        .map(|expires_at| expires_at.duration_since(now))
+    {
+        Some(Ok(remaining)) => format!(
+            "{} · expires in {}",
+            state.detail,
+            formatter.format_duration(remaining)
+        ),
+        Some(Err(_)) => format!("{} · expired", state.detail),
+        None => state.detail.clone(),
+    }
+}
@@ -0,0 +1,44 @@
+use dioxus::prelude::*;
+use versualizer_core::{TimingEditor, TrackInfo};
+
+/// Lyrics being retimed with Ctrl+Shift+R: the last tapped line, the line the next tap
+/// starts, and the one after it, over the karaoke display
+#[component]
+pub fn TimingEditorPanel(editor: Signal<Option<(TrackInfo, TimingEditor)>>) -> Element {
+    let editor = editor.read();
+    let Some((_, editor)) = editor.as_ref() else {
+        return rsx! {};
+    };
+    let lines = editor.lines();
+    let index = editor.current_index();
+    let text = |i: Option<usize>| {
+        i.and_then(|i| lines.get(i))
+            .map(|line| line.text.clone())
+            .unwrap_or_default()
+    };
+    let (previous, current, next) = (
+        text(index.checked_sub(1)),
+        text(Some(index)),
+        text(Some(index + 1)),
+    );
+    let progress = format!("{index}/{}", lines.len());
+
+    rsx! {
+        div {
+            class: "timing-editor",
+            div { class: "timing-editor-line timing-editor-done", "{previous}" }
+            div { class: "timing-editor-line timing-editor-current",
+                if editor.is_done() {
+                    "Every line is timed"
+                } else {
+                    "{current}"
+                }
+            }
+            div { class: "timing-editor-line", "{next}" }
+            div {
+                class: "timing-editor-hint",
+                "{progress} · Space: start line · Backspace: undo · Ctrl+Shift+R: save · Esc: discard"
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use dioxus::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+use versualizer_core::{SyncEngine, UiConfig};
+
+/// How often the progress bar moves; its CSS transition fills in between
+const PROGRESS_TICK: Duration = Duration::from_millis(250);
+
+/// "Artist – Title" of the playing track and a bar with how far it has played, shown with
+/// `[ui.layout] show_track_info` and `show_progress`. The position is the sync engine's
+/// interpolated one, so the bar keeps moving between polls.
+#[component]
+pub fn TrackHeader() -> Element {
+    let ui_config = use_context::<Signal<UiConfig>>();
+    let sync_engine: Arc<SyncEngine> = use_context();
+    let mut title = use_signal(|| None::<String>);
+    // Played fraction of the track in tenths of a percent, so small steps don't re-render
+    let mut progress = use_signal(|| 0_u32);
+
+    use_future(move || {
+        let sync_engine = sync_engine.clone();
+        async move {
+            loop {
+                let layout = ui_config.peek().layout.clone();
+                if layout.show_track_info || layout.show_progress {
+                    let state = sync_engine.state().await;
+                    let label = state
+                        .track
+                        .as_ref()
+                        .map(|track| format!("{} – {}", track.artist, track.name));
+                    if *title.peek() != label {
+                        title.set(label);
+                    }
+                    let permille = state.track.as_ref().map_or(0, |track| {
+                        played_permille(state.interpolated_position(), track.duration)
+                    });
+                    if *progress.peek() != permille {
+                        progress.set(permille);
+                    }
+                }
+                tokio::time::sleep(PROGRESS_TICK).await;
+            }
+        }
+    });
+
+    let layout = ui_config.read().layout.clone();
+    let Some(title) = title() else {
+        return rsx! {};
+    };
+    if !layout.show_track_info && !layout.show_progress {
+        return rsx! {};
+    }
+    let percent = f64::from(progress()) / 10.0;
+
+    rsx! {
+        div {
+            class: "track-header",
+            if layout.show_track_info {
+                span { class: "track-header-title", "{title}" }
+            }
+            if layout.show_progress {
+                div {
+                    class: "track-progress",
+                    div { class: "track-progress-fill", style: "width: {percent}%" }
+                }
+            }
+        }
+    }
+}
+
+/// How much of a track of `duration` has played at `position`, in tenths of a percent
+fn played_permille(position: Duration, duration: Duration) -> u32 {
+    if duration.is_zero() {
+        return 0;
+    }
+    let permille = position.as_millis().saturating_mul(1000) / duration.as_millis();
+    u32::try_from(permille.min(1000)).unwrap_or(1000)
+}
@@ -0,0 +1,65 @@
+//! Auto-hide the mouse cursor while it idles over the overlay.
+//!
+//! Cursor movement is observed through window-level wry events, and a
+//! background loop hides the cursor once it has been still for the configured
+//! timeout. Any movement over the window restores it.
+
+use dioxus::desktop::tao::event::{Event as WryEvent, WindowEvent};
+use dioxus::desktop::{use_window, use_wry_event_handler};
+use dioxus::prelude::*;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use versualizer_core::CursorConfig;
+
+/// How often the idle timer is checked
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Hook that hides the cursor after it idles over the window.
+///
+/// Does nothing unless `hide_when_idle` is enabled in `[ui.cursor]`.
+pub fn use_cursor_auto_hide(config: CursorConfig) {
+    let window = use_window();
+    let mut last_moved = use_signal(Instant::now);
+    let mut hidden = use_signal(|| false);
+
+    // Restore the cursor on any movement over the window
+    let window_for_events = window.clone();
+    use_wry_event_handler(move |event, _| {
+        if !config.hide_when_idle {
+            return;
+        }
+
+        if let WryEvent::WindowEvent {
+            event: WindowEvent::CursorMoved { .. } | WindowEvent::CursorEntered { .. },
+            ..
+        } = event
+        {
+            last_moved.set(Instant::now());
+            if *hidden.peek() {
+                window_for_events.set_cursor_visible(true);
+                hidden.set(false);
+            }
+        }
+    });
+
+    // Hide the cursor once it has been idle for the configured timeout
+    use_future(move || {
+        let window = window.clone();
+        async move {
+            if !config.hide_when_idle {
+                return;
+            }
+
+            let timeout = Duration::from_millis(config.idle_timeout_ms);
+            loop {
+                tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+
+                if !*hidden.peek() && last_moved.peek().elapsed() >= timeout {
+                    debug!("Cursor idle for {:?}, hiding", timeout);
+                    window.set_cursor_visible(false);
+                    hidden.set(true);
+                }
+            }
+        }
+    });
+}
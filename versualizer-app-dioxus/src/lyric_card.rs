@@ -0,0 +1,242 @@
+//! Export the current lyric line as a shareable PNG "lyric card".
+//!
+//! The card is laid out by [`LyricCard::to_svg`] using colors and fonts read from the live
+//! theme's CSS variables, then rasterized with `resvg` and saved to the pictures directory
+//! or copied to the clipboard.
+
+use crate::resources;
+use crate::state::KaraokeState;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use dioxus::prelude::*;
+use resvg::{tiny_skia, usvg};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+use versualizer_core::SyncEngine;
+use versualizer_core::lyric_card::{AlbumArt, CARD_HEIGHT, CARD_WIDTH, CardTheme, LyricCard};
+
+/// Timeout for downloading album art
+const ALBUM_ART_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors that can occur while exporting a lyric card
+#[derive(Debug, Error)]
+pub enum LyricCardError {
+    #[error("Failed to lay out lyric card: {0}")]
+    Svg(#[from] usvg::Error),
+
+    #[error("Failed to allocate lyric card image")]
+    Allocation,
+
+    #[error("Failed to encode lyric card PNG: {0}")]
+    Encode(String),
+
+    #[error("Failed to write lyric card: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to copy lyric card to the clipboard: {0}")]
+    Clipboard(String),
+}
+
+/// Where an exported card goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardTarget {
+    /// Save a PNG to the lyric cards directory
+    File,
+    /// Copy the PNG to the clipboard
+    Clipboard,
+}
+
+/// Read card colors and font from the current theme's CSS variables.
+/// Falls back to defaults for anything the theme does not define.
+async fn theme_from_document() -> CardTheme {
+    let js = r"
+        const style = getComputedStyle(document.documentElement);
+        const read = (name) => style.getPropertyValue(name).trim();
+        return {
+            background: read('--background-color'),
+            text_color: read('--current-sung-color'),
+            secondary_color: read('--unsung-color'),
+            font_family: read('--font-family'),
+        };
+    ";
+
+    let defaults = CardTheme::default();
+    match document::eval(js).await {
+        Ok(value) => match serde_json::from_value::<CardTheme>(value) {
+            Ok(theme) => CardTheme {
+                background: non_empty_or(theme.background, defaults.background),
+                text_color: non_empty_or(theme.text_color, defaults.text_color),
+                secondary_color: non_empty_or(theme.secondary_color, defaults.secondary_color),
+                font_family: non_empty_or(theme.font_family, defaults.font_family),
+            },
+            Err(e) => {
+                warn!("Failed to parse theme values for lyric card: {}", e);
+                defaults
+            }
+        },
+        Err(e) => {
+            warn!("Failed to read theme values for lyric card: {}", e);
+            defaults
+        }
+    }
+}
+
+fn non_empty_or(value: String, fallback: String) -> String {
+    if value.is_empty() { fallback } else { value }
+}
+
+/// Download the track's album art. Returns `None` (and the card is laid out without art)
+/// if the download fails or the response is not an image.
+async fn fetch_album_art(url: &str) -> Option<AlbumArt> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(ALBUM_ART_TIMEOUT)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status);
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Failed to download album art for lyric card: {}", e);
+            return None;
+        }
+    };
+    let mime = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|mime| mime.starts_with("image/"))?
+        .to_string();
+    match response.bytes().await {
+        Ok(data) => Some(AlbumArt {
+            mime,
+            data: data.to_vec(),
+        }),
+        Err(e) => {
+            warn!("Failed to download album art for lyric card: {}", e);
+            None
+        }
+    }
+}
+
+/// Render the card to PNG bytes.
+///
+/// # Errors
+///
+/// Returns an error if the SVG cannot be parsed or the PNG cannot be encoded.
+fn render_png(card: &LyricCard, theme: &CardTheme) -> Result<Vec<u8>, LyricCardError> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    for font in resources::fonts() {
+        if let Err(e) = options.fontdb_mut().load_font_file(&font) {
+            warn!("Failed to load font {:?} for lyric card: {}", font, e);
+        }
+    }
+
+    let tree = usvg::Tree::from_str(&card.to_svg(theme), &options)?;
+    let mut pixmap =
+        tiny_skia::Pixmap::new(CARD_WIDTH, CARD_HEIGHT).ok_or(LyricCardError::Allocation)?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .map_err(|e| LyricCardError::Encode(e.to_string()))
+}
+
+/// Save a rendered card to the lyric cards directory.
+/// Returns the path of the written file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+fn save_png(png: &[u8]) -> Result<PathBuf, LyricCardError> {
+    let dir = versualizer_core::paths::lyric_cards_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("lyric-card-{timestamp}.png"));
+    std::fs::write(&path, png)?;
+
+    info!("Saved lyric card to {:?}", path);
+    Ok(path)
+}
+
+/// Copy a rendered card to the clipboard through the webview's async clipboard API
+///
+/// # Errors
+///
+/// Returns an error if the webview rejects the clipboard write.
+async fn copy_png(png: &[u8]) -> Result<(), LyricCardError> {
+    let js = format!(
+        r"
+        const bytes = Uint8Array.from(atob({}), (c) => c.charCodeAt(0));
+        const blob = new Blob([bytes], {{ type: 'image/png' }});
+        await navigator.clipboard.write([new ClipboardItem({{ 'image/png': blob }})]);
+        return true;
+        ",
+        serde_json::Value::String(BASE64.encode(png))
+    );
+    document::eval(&js)
+        .await
+        .map_err(|e| LyricCardError::Clipboard(e.to_string()))?;
+    info!("Copied lyric card to the clipboard");
+    Ok(())
+}
+
+/// Build a card from the line currently on screen and the current track, then save or
+/// copy it. Does nothing during the intro or if no lyrics or track are loaded.
+pub async fn export_current_line(
+    karaoke: KaraokeState,
+    sync_engine: Arc<SyncEngine>,
+    target: CardTarget,
+) {
+    let Some(line) = karaoke
+        .current_line()
+        .filter(|line| !line.text.trim().is_empty())
+    else {
+        info!("No current lyric line to export");
+        return;
+    };
+    let Some(track) = sync_engine.current_track().await else {
+        info!("No current track to export a lyric card for");
+        return;
+    };
+
+    let album_art = match &track.album_art_url {
+        Some(url) => fetch_album_art(url).await,
+        None => None,
+    };
+    let card = LyricCard {
+        line: line.text,
+        title: track.name,
+        artist: track.artist,
+        album_art,
+    };
+    let theme = theme_from_document().await;
+
+    // Rasterizing and font loading are blocking work, keep them off the UI thread
+    let png = match tokio::task::spawn_blocking(move || render_png(&card, &theme)).await {
+        Ok(Ok(png)) => png,
+        Ok(Err(e)) => {
+            warn!("Failed to render lyric card: {}", e);
+            return;
+        }
+        Err(e) => {
+            warn!("Lyric card render task failed: {}", e);
+            return;
+        }
+    };
+    let result = match target {
+        CardTarget::File => tokio::task::spawn_blocking(move || save_png(&png))
+            .await
+            .unwrap_or_else(|e| Err(LyricCardError::Io(std::io::Error::other(e))))
+            .map(|_| ()),
+        CardTarget::Clipboard => copy_png(&png).await,
+    };
+    if let Err(e) = result {
+        warn!("Failed to export lyric card: {}", e);
+    }
+}
@@ -1,15 +1,22 @@
 #![cfg_attr(feature = "bundle", windows_subsystem = "windows")]
 mod app;
+mod auto_contrast;
 mod bridge;
 mod components;
+mod cursor_visibility;
+mod lyric_card;
+mod overrides_watcher;
+mod resources;
 mod state;
 mod theme_watcher;
+mod tray;
 mod window_resize;
 mod window_state;
 
 use crate::app::App;
-use crate::bridge::use_sync_engine_bridge;
-use crate::state::KaraokeState;
+use crate::bridge::{use_provider_status_bridge, use_sync_engine_bridge};
+use crate::resources::Resource;
+use crate::state::{CurrentBeat, KaraokeState, MissingLyrics};
 use crate::window_state::WindowState;
 use dioxus::desktop::tao::dpi::PhysicalPosition;
 use dioxus::desktop::tao::window::Icon;
@@ -17,34 +24,54 @@ use dioxus::desktop::{LogicalSize, WindowBuilder};
 use dioxus::prelude::*;
 use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::sync::{Notify, watch};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
-use versualizer_core::config::LyricsProviderType;
+use versualizer_core::config::{LyricsProviderType, UiConfig};
 use versualizer_core::{
-    CoreError, LyricsCache, LyricsFetcher, LyricsProvider, MusicSource, SyncEngine, SyncEvent,
-    TomlParseError, VersualizerConfig,
+    ArtworkCache, AudioFeatures, Blocklist, CacheBackups, CacheMaintenance, CheckStatus,
+    ConfigWatcher, ContentCipher, CoreError, EventLog, LyricsCache, LyricsFetcher, LyricsMirror,
+    LyricsOverrides, LyricsTranslator, MusicBrainzEnricher, MusicSource, NowSingingFiles,
+    OverlayView, PlaybackController, Preflight, ProviderStatusRegistry, QueryNormalizer,
+    SessionRecorder, SyncEngine, SyncEvent, TextPipeline, TomlParseError, TracePlayer,
+    VersualizerConfig,
 };
-use versualizer_lyrics_lrclib::LrclibProvider;
-use versualizer_lyrics_spotify::SpotifyLyricsProvider;
+use versualizer_lyrics_genius::{GENIUS_API_URL, GENIUS_CONFIG_TEMPLATE};
+use versualizer_lyrics_lrclib::LRCLIB_API_URL;
+use versualizer_lyrics_musixmatch::MUSIXMATCH_API_URL;
+use versualizer_lyrics_spotify::SpotifyDealer;
+use versualizer_providers::create_providers;
+use versualizer_server::LyricsServer;
+use versualizer_source_windows_media::WindowsMediaPoller;
 use versualizer_spotify_api::{
-    SPOTIFY_CONFIG_TEMPLATE, SpotifyOAuth, SpotifyPoller, SpotifyProviderConfig,
+    AudioFeaturesService, SPOTIFY_API_URL, SPOTIFY_CONFIG_TEMPLATE, SpotifyController,
+    SpotifyOAuth, SpotifyPoller, SpotifyProviderConfig,
 };
 
 const APP_NAME: &str = "Versualizer";
 
 #[allow(clippy::too_many_lines)]
 fn main() {
+    // `--config <path>` or `--profile <name>` replace config.toml for this run
+    let config_override = config_arg();
+    if let Some(ref path) = config_override {
+        versualizer_core::paths::set_config_path(path);
+    }
+
     // Initialize logging with optional file output
     // Check config for logging.enabled before full config load
     let file_logging_enabled = check_file_logging_enabled();
     init_tracing(file_logging_enabled);
+    if let Some(path) = config_override {
+        info!("Using config file {:?}", path);
+    }
 
     // Load config or create template on first run
     // Pass provider templates to include in the generated config file
-    let provider_templates: &[&str] = &[SPOTIFY_CONFIG_TEMPLATE];
+    let provider_templates: &[&str] = &[SPOTIFY_CONFIG_TEMPLATE, GENIUS_CONFIG_TEMPLATE];
     let config = match VersualizerConfig::load_or_create(Some(provider_templates)) {
         Ok(config) => config,
         Err(CoreError::ConfigNotFound { path }) => {
@@ -64,14 +91,25 @@ fn main() {
         }
     };
 
-    // Validate config fields and show dialog if any are missing
+    // Safe mode starts without anything that could be crashing the app, so the user can
+    // open the settings panel and fix whichever setting is at fault
+    let safe_mode = safe_mode_arg();
+    let config = if safe_mode {
+        warn!("Safe mode: only cached lyrics, no animations, default theme and no network");
+        config.into_safe_mode()
+    } else {
+        config
+    };
+
+    // Validate config fields and show dialog if any are missing. Safe mode never signs in,
+    // so it does not need the Spotify credentials.
     let validation = validate_config_fields(&config);
-    if !validation.is_valid() {
+    if !safe_mode && !validation.is_valid() {
         show_config_error_dialog(&validation, &VersualizerConfig::config_path());
     }
 
     // Validate provider-specific config based on music source (for any remaining validation)
-    if let Err(e) = validate_provider_config(&config) {
+    if !safe_mode && let Err(e) = validate_provider_config(&config) {
         error!("{e}");
         std::process::exit(1);
     }
@@ -90,18 +128,62 @@ fn main() {
 
     // Initialize lyrics cache
     let cache = runtime.block_on(async {
-        match LyricsCache::new().await {
-            Ok(cache) => Arc::new(cache),
+        let cache_path = config.cache.db_path(config.music.source);
+        let cache = match LyricsCache::open(&cache_path).await {
+            Ok(cache) => cache,
             Err(e) => {
                 error!("Failed to initialize lyrics cache: {}", e);
                 std::process::exit(1);
             }
+        };
+        if !config.lyrics.encrypt_cache {
+            return Arc::new(cache);
+        }
+        let secret_store = config.lyrics.secret_store.open();
+        match ContentCipher::load_or_create(secret_store.as_ref()) {
+            Ok(cipher) => {
+                let cache = cache.with_encryption(cipher);
+                if let Err(e) = cache.encrypt_existing().await {
+                    error!("Failed to encrypt existing cache entries: {}", e);
+                }
+                Arc::new(cache)
+            }
+            Err(e) => {
+                error!("Failed to load cache encryption key: {}", e);
+                std::process::exit(1);
+            }
         }
     });
 
     // Create lyrics providers based on config
     let providers = create_providers(&config);
 
+    // On-demand translation of the current song's cached lyrics
+    let translator = if safe_mode {
+        None
+    } else {
+        match LyricsTranslator::new(config.lyrics.translation.clone(), cache.clone()) {
+            Ok(translator) => Some(Arc::new(translator)),
+            Err(e) => {
+                error!("Failed to initialize lyrics translator: {}", e);
+                None
+            }
+        }
+    };
+
+    // Album art behind or beside the lyrics, downloaded once per cover
+    let artwork = if safe_mode {
+        None
+    } else {
+        match ArtworkCache::new(&config.network.retry) {
+            Ok(artwork) => Some(Arc::new(artwork)),
+            Err(e) => {
+                error!("Failed to initialize album art cache: {}", e);
+                None
+            }
+        }
+    };
+
     let provider_names: Vec<_> = providers.iter().map(|p| p.name()).collect();
     info!(
         "Initialized {} lyrics provider(s): {:?}",
@@ -122,21 +204,188 @@ fn main() {
     }
 
     // Create lyrics fetcher with cancellation token
-    let lyrics_fetcher = Arc::new(LyricsFetcher::new(
+    let overrides = LyricsOverrides::new(versualizer_core::paths::overrides_dir());
+    let mut lyrics_fetcher = LyricsFetcher::new(
         sync_engine.clone(),
-        cache,
+        cache.clone(),
         providers,
         Some(cancel_token.clone()),
-    ));
+    )
+    .with_overrides(overrides.clone());
+    if let Some(mirror_dir) = config.lyrics.mirror_dir_path() {
+        info!("Mirroring fetched lyrics to {:?}", mirror_dir);
+        lyrics_fetcher = lyrics_fetcher.with_mirror(LyricsMirror::new(mirror_dir));
+    }
+    if !config.lyrics.allow_unsynced {
+        info!("Unsynced lyrics are not shown");
+        lyrics_fetcher = lyrics_fetcher.with_unsynced_fallback(false);
+    }
+    if config.lyrics.parallel_fetch {
+        info!("Lyrics providers are queried in parallel");
+        lyrics_fetcher = lyrics_fetcher.with_parallel_fetch(true);
+    }
+    if config.lyrics.processors.romanize_lines {
+        info!("Japanese and Korean lines are shown with their romanization");
+        lyrics_fetcher = lyrics_fetcher.with_line_romanization(true);
+    }
+    lyrics_fetcher = lyrics_fetcher.with_not_found_ttl(config.cache.not_found_ttl());
+    lyrics_fetcher = lyrics_fetcher
+        .with_unreachable_retry(config.lyrics.retry_after(), config.lyrics.retry_attempts)
+        .with_blocklist(Blocklist::from_config(&config.lyrics.blocklist));
+    lyrics_fetcher =
+        lyrics_fetcher.with_query_normalizer(QueryNormalizer::from_config(&config.lyrics.query));
+    if config.lyrics.query.musicbrainz {
+        match MusicBrainzEnricher::new(&config.network.retry) {
+            Ok(enricher) => {
+                info!("Tracks are looked up on MusicBrainz before searching lyrics");
+                lyrics_fetcher = lyrics_fetcher.with_enricher(Box::new(enricher));
+            }
+            Err(e) => warn!("Failed to create MusicBrainz client: {}", e),
+        }
+    }
+    let processors = TextPipeline::from_config(&config.lyrics);
+    if !processors.is_empty() {
+        info!("Lyrics text processors: {:?}", processors.names());
+        lyrics_fetcher = lyrics_fetcher.with_processors(processors);
+    }
+    let provider_status = lyrics_fetcher.provider_status();
+    let lyrics_fetcher = Arc::new(lyrics_fetcher);
 
     // Spawn background tasks
-    runtime.spawn(start_spotify_poller(
-        config.clone(),
+    // Spotify sign-in, shared by the poller and the settings auth page
+    let mut spotify_oauth = None;
+    // A replayed session trace stands in for the live music source
+    if let Some(trace_path) = replay_trace_arg() {
+        match TracePlayer::load(&trace_path) {
+            Ok(player) => {
+                info!("Replaying session trace from {:?}", trace_path);
+                runtime.spawn(player.play(sync_engine.clone(), cancel_token.clone()));
+            }
+            Err(e) => {
+                error!("Failed to load session trace {:?}: {}", trace_path, e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        match config.music.source {
+            MusicSource::WindowsMedia => {
+                runtime.spawn(start_windows_media_poller(
+                    sync_engine.clone(),
+                    cancel_token.clone(),
+                ));
+            }
+            _ if safe_mode => {
+                info!("Safe mode: not connecting to {:?}", config.music.source);
+            }
+            _ => {
+                if let Some((oauth, spotify_config)) = create_spotify_oauth(&config) {
+                    spotify_oauth = Some(oauth.clone());
+                    let wakeup = {
+                        let _guard = runtime.enter();
+                        spawn_spotify_dealer(&spotify_config, &cancel_token)
+                    };
+                    runtime.spawn(start_spotify_poller(
+                        oauth,
+                        spotify_config,
+                        wakeup,
+                        lyrics_fetcher.clone(),
+                        sync_engine.clone(),
+                        cancel_token.clone(),
+                    ));
+                }
+            }
+        }
+    }
+    if config.logging.event_log_size > 0 {
+        match runtime.block_on(EventLog::new(config.logging.event_log_size)) {
+            Ok(event_log) => {
+                let event_log = event_log.with_printed_summary(config.logging.show_session_summary);
+                runtime.spawn(event_log.run(sync_engine.clone(), cancel_token.clone()));
+            }
+            Err(e) => error!("Failed to open event log: {}", e),
+        }
+    }
+    if config.logging.record_session {
+        // Make room for the new trace within the configured limit
+        let keep = config.logging.max_session_traces.saturating_sub(1);
+        if let Err(e) = runtime.block_on(SessionRecorder::prune_traces(
+            &versualizer_core::paths::traces_dir(),
+            keep,
+        )) {
+            warn!("Failed to delete old session traces: {}", e);
+        }
+        let trace_path = SessionRecorder::default_path();
+        match runtime.block_on(SessionRecorder::create(&trace_path)) {
+            Ok(recorder) => {
+                info!("Recording session trace to {:?}", trace_path);
+                runtime.spawn(recorder.run(sync_engine.clone(), cancel_token.clone()));
+            }
+            Err(e) => error!("Failed to start session recording: {}", e),
+        }
+    }
+    if config.sync.line_lead_ms != 0 {
+        info!("Line lead: {}ms", config.sync.line_lead_ms);
+        sync_engine.set_line_lead_ms(config.sync.line_lead_ms);
+    }
+    if config.sync.offset_ms != 0 {
+        info!("Global offset: {}ms", config.sync.offset_ms);
+        runtime.block_on(sync_engine.set_global_offset_ms(config.sync.offset_ms));
+    }
+    // Play/pause, skip and seek buttons, for music sources that take commands
+    let playback_controller = spotify_oauth
+        .clone()
+        .map(|oauth| Arc::new(SpotifyController::new(oauth)) as Arc<dyn PlaybackController>);
+    // Audio features of the current track for beat-quantized line changes and the beat
+    // pulse. Without the service the sender is dropped and the features stay unknown.
+    let (features_tx, features_rx) = watch::channel(None::<Arc<AudioFeatures>>);
+    if config.ui.animation.quantize_to_beat || config.ui.animation.beat_pulse {
+        if let Some(oauth) = spotify_oauth.clone() {
+            runtime.spawn(AudioFeaturesService::new(oauth).run(
+                sync_engine.clone(),
+                features_tx,
+                cancel_token.clone(),
+            ));
+        } else {
+            warn!("quantize_to_beat and beat_pulse need the Spotify music source; they are off");
+        }
+    }
+    if let Some(now_singing) = NowSingingFiles::from_config(&config.now_singing) {
+        runtime.spawn(now_singing.run(sync_engine.clone(), cancel_token.clone()));
+    }
+    if config.server.enabled {
+        let server = LyricsServer::new(&config.server, sync_engine.clone(), cancel_token.clone());
+        runtime.spawn(server.run());
+    }
+    let backups = CacheBackups::new(
+        versualizer_core::paths::backups_dir(),
+        config.cache.backup_keep,
+    );
+    if let Some(interval) = config.cache.backup_interval() {
+        runtime.spawn(
+            backups
+                .clone()
+                .run(cache.clone(), interval, cancel_token.clone()),
+        );
+    }
+    if let Some(maintenance) = CacheMaintenance::from_config(&config.cache) {
+        runtime.spawn(maintenance.run(cache.clone(), sync_engine.clone(), cancel_token.clone()));
+    }
+    runtime.spawn(start_lyrics_fetcher(lyrics_fetcher.clone()));
+    runtime.spawn(overrides_watcher::watch_overrides(
+        overrides,
+        lyrics_fetcher.clone(),
         sync_engine.clone(),
         cancel_token.clone(),
     ));
-    runtime.spawn(start_lyrics_fetcher(lyrics_fetcher));
+    runtime.spawn(
+        ConfigWatcher::new(sync_engine.clone())
+            .with_safe_mode(safe_mode)
+            .run(cancel_token.clone()),
+    );
     runtime.spawn(log_sync_events(sync_engine.clone()));
+    if !safe_mode {
+        runtime.spawn(log_preflight(config.clone(), lyrics_fetcher.clone()));
+    }
 
     // Load saved window position if available
     let saved_position = WindowState::load();
@@ -191,12 +440,36 @@ fn main() {
         .with_window(window)
         .with_disable_context_menu(true);
 
+    // Resolve the UI locale once so components format numbers consistently
+    let locale_formatter = config.ui.locale_formatter();
+    info!("Using UI locale: {}", locale_formatter.name());
+
+    // Another window can show the same song in a different view, e.g. `--view audience`
+    let mut ui_config = config.ui;
+    if let Some(view) = view_arg() {
+        info!("Overlay view: {:?}", view);
+        ui_config.layout.view = view;
+    }
+
     // Launch Dioxus application
-    // Use with_context to inject SyncEngine, UI config, and cancellation token before launch
+    // Use with_context to inject SyncEngine, provider status and auth hooks, playback control,
+    // the lyrics cache and its backups, the album art cache, UI config, locale formatter,
+    // safe mode, and cancellation token before launch
     dioxus::LaunchBuilder::desktop()
         .with_cfg(dioxus_config)
         .with_context(sync_engine)
-        .with_context(config.ui)
+        .with_context(provider_status)
+        .with_context(features_rx)
+        .with_context(lyrics_fetcher)
+        .with_context(cache)
+        .with_context(backups)
+        .with_context(spotify_oauth)
+        .with_context(playback_controller)
+        .with_context(translator)
+        .with_context(artwork)
+        .with_context(locale_formatter)
+        .with_context(ui_config)
+        .with_context(SafeMode(safe_mode))
         .with_context(cancel_token)
         .launch(app);
 }
@@ -205,6 +478,12 @@ fn main() {
 fn app() -> Element {
     // Create karaoke state with granular signals
     let karaoke = use_context_provider(KaraokeState::new);
+    use_context_provider(MissingLyrics::new);
+    use_context_provider(CurrentBeat::new);
+
+    // UI settings as a signal, so edits to config.toml re-render what they change
+    let ui_config: UiConfig = use_context();
+    use_context_provider(|| Signal::new(ui_config));
 
     // Get the sync engine from context (injected via with_context)
     let sync_engine: Arc<SyncEngine> = use_context();
@@ -212,6 +491,10 @@ fn app() -> Element {
     // Bridge SyncEngine events to Dioxus signals
     use_sync_engine_bridge(&sync_engine, karaoke);
 
+    // Bridge provider health to the offline status line
+    let provider_status: Arc<ProviderStatusRegistry> = use_context();
+    use_provider_status_bridge(&provider_status, karaoke);
+
     rsx! {
         document::Link { rel: "icon", href: asset!("/icons/icon.ico") },
         document::Title { "{APP_NAME}" },
@@ -223,10 +506,8 @@ fn app() -> Element {
 fn validate_provider_config(config: &VersualizerConfig) -> Result<(), CoreError> {
     if config.music.source == MusicSource::Spotify {
         let spotify_config =
-            SpotifyProviderConfig::from_providers(&config.providers)?.ok_or_else(|| {
-                CoreError::ConfigMissingField {
-                    field: "providers.spotify".into(),
-                }
+            SpotifyProviderConfig::load(config)?.ok_or_else(|| CoreError::ConfigMissingField {
+                field: "providers.spotify".into(),
             })?;
         spotify_config.validate()?;
     }
@@ -261,7 +542,7 @@ fn validate_config_fields(config: &VersualizerConfig) -> ConfigValidationResult
     let mut missing_fields = Vec::new();
 
     if config.music.source == MusicSource::Spotify {
-        match SpotifyProviderConfig::from_providers(&config.providers) {
+        match SpotifyProviderConfig::load(config) {
             Ok(Some(spotify_config)) => {
                 if spotify_config.client_id.is_empty() {
                     missing_fields.push("providers.spotify.client_id".into());
@@ -403,7 +684,7 @@ fn reset_config_to_template(config_path: &Path) -> std::io::Result<()> {
     use std::fs;
     use versualizer_core::config::build_config_template;
 
-    let provider_templates: &[&str] = &[SPOTIFY_CONFIG_TEMPLATE];
+    let provider_templates: &[&str] = &[SPOTIFY_CONFIG_TEMPLATE, GENIUS_CONFIG_TEMPLATE];
     let template = build_config_template(Some(provider_templates));
 
     fs::write(config_path, template)
@@ -424,121 +705,171 @@ fn show_generic_error_dialog(error_message: &str) {
         .show();
 }
 
-fn create_providers(config: &VersualizerConfig) -> Vec<Box<dyn LyricsProvider>> {
-    config
+/// Run the preflight checks that `versualizer-cli doctor` also runs and log any problems.
+/// Config validity was already checked during startup. The `sp_dc` check goes through the
+/// fetcher's own Spotify lyrics provider so its token is shared with lyric fetches.
+async fn log_preflight(config: VersualizerConfig, lyrics_fetcher: Arc<LyricsFetcher>) {
+    let mut preflight = Preflight::new();
+    if config.music.source == MusicSource::Spotify {
+        preflight
+            .check_reachable("Spotify Web API", SPOTIFY_API_URL)
+            .await;
+    }
+    if config
         .lyrics
         .providers
-        .iter()
-        .filter_map(|provider_type| -> Option<Box<dyn LyricsProvider>> {
-            match provider_type {
-                LyricsProviderType::Lrclib => {
-                    info!("Initializing LRCLIB provider");
-                    match LrclibProvider::new() {
-                        Ok(provider) => Some(Box::new(provider)),
-                        Err(e) => {
-                            error!("Failed to create LRCLIB provider: {}", e);
-                            None
-                        }
-                    }
-                }
-                LyricsProviderType::SpotifyLyrics => {
-                    // Access Spotify config from providers section
-                    let spotify_config =
-                        match SpotifyProviderConfig::from_providers(&config.providers) {
-                            Ok(Some(cfg)) => cfg,
-                            Ok(None) => {
-                                info!("Skipping Spotify lyrics provider: not configured");
-                                return None;
-                            }
-                            Err(e) => {
-                                error!("Failed to parse Spotify config: {}", e);
-                                return None;
-                            }
-                        };
-
-                    spotify_config.sp_dc.as_ref().map_or_else(
-                        || {
-                            info!("Skipping Spotify lyrics provider: sp_dc not configured");
-                            None
-                        },
-                        |sp_dc| {
-                            if sp_dc.is_empty() {
-                                info!("Skipping Spotify lyrics provider: sp_dc is empty");
-                                None
-                            } else {
-                                info!("Initializing Spotify lyrics provider (sp_dc configured)");
-                                let secret_key_url = spotify_config.secret_key_url.clone();
-                                match SpotifyLyricsProvider::new(sp_dc, secret_key_url) {
-                                    Ok(provider) => {
-                                        Some(Box::new(provider) as Box<dyn LyricsProvider>)
-                                    }
-                                    Err(e) => {
-                                        error!("Failed to create Spotify lyrics provider: {}", e);
-                                        None
-                                    }
-                                }
-                            }
-                        },
-                    )
-                }
-            }
-        })
-        .collect()
-}
+        .contains(&LyricsProviderType::Lrclib)
+    {
+        preflight.check_reachable("LRCLIB", LRCLIB_API_URL).await;
+    }
+    if config
+        .lyrics
+        .providers
+        .contains(&LyricsProviderType::Musixmatch)
+    {
+        preflight
+            .check_reachable("Musixmatch", MUSIXMATCH_API_URL)
+            .await;
+    }
+    if config
+        .lyrics
+        .providers
+        .contains(&LyricsProviderType::Genius)
+    {
+        preflight.check_reachable("Genius", GENIUS_API_URL).await;
+    }
+    if config
+        .lyrics
+        .providers
+        .contains(&LyricsProviderType::SpotifyLyrics)
+        && let Ok(result) = lyrics_fetcher
+            .check_provider(versualizer_lyrics_spotify::PROVIDER_NAME)
+            .await
+    {
+        preflight.push(result);
+    }
+    for (name, _) in config.lyrics.plugin_paths() {
+        if let Ok(result) = lyrics_fetcher.check_provider(&name).await {
+            preflight.push(result);
+        }
+    }
+    preflight
+        .check_cache(&config.cache.db_path(config.music.source))
+        .await;
 
-/// Start the Spotify poller to fetch playback state
-async fn start_spotify_poller(
-    config: VersualizerConfig,
-    sync_engine: Arc<SyncEngine>,
-    cancel_token: CancellationToken,
-) {
-    info!("Initializing Spotify Web API poller...");
+    if preflight.status() == CheckStatus::Pass {
+        info!("Preflight checks passed");
+    } else {
+        warn!(
+            "Preflight checks found problems:\n{}",
+            preflight.render_table()
+        );
+    }
+}
 
+/// Create the Spotify OAuth manager from `[providers.spotify]`, along with the
+/// configured poll interval
+fn create_spotify_oauth(
+    config: &VersualizerConfig,
+) -> Option<(Arc<SpotifyOAuth>, SpotifyProviderConfig)> {
     // Get Spotify config from providers section
-    let spotify_config = match SpotifyProviderConfig::from_providers(&config.providers) {
+    let spotify_config = match SpotifyProviderConfig::load(config) {
         Ok(Some(cfg)) => cfg,
         Ok(None) => {
             error!("Spotify provider not configured");
-            return;
+            return None;
         }
         Err(e) => {
             error!("Failed to parse Spotify config: {}", e);
-            return;
+            return None;
         }
     };
 
-    let oauth = match SpotifyOAuth::new(
+    match SpotifyOAuth::new(
         &spotify_config.client_id,
         &spotify_config.client_secret,
         &spotify_config.oauth_redirect_uri,
     ) {
-        Ok(oauth) => Arc::new(oauth),
+        Ok(oauth) => Some((
+            Arc::new(oauth.with_secret_store(config.lyrics.secret_store.open())),
+            spotify_config,
+        )),
         Err(e) => {
             error!("Failed to create Spotify OAuth: {}", e);
-            return;
+            None
         }
+    }
+}
+
+/// Listen for pushed Spotify playback changes when `push_updates` is on and `sp_dc` is
+/// set, returning what is notified on each one
+fn spawn_spotify_dealer(
+    spotify_config: &SpotifyProviderConfig,
+    cancel_token: &CancellationToken,
+) -> Option<Arc<Notify>> {
+    if !spotify_config.push_updates {
+        return None;
+    }
+    let Some(sp_dc) = spotify_config
+        .sp_dc
+        .as_ref()
+        .filter(|sp_dc| !sp_dc.is_empty())
+    else {
+        warn!("Spotify push updates need providers.spotify.sp_dc, polling only");
+        return None;
     };
+    match SpotifyDealer::new(sp_dc, spotify_config.secret_key_url.clone()) {
+        Ok(dealer) => Some(dealer.spawn(cancel_token.clone())),
+        Err(e) => {
+            error!("Failed to start Spotify push updates: {}", e);
+            None
+        }
+    }
+}
 
-    // Ensure we're authenticated
+/// Start the Spotify poller to fetch playback state, polling early whenever `wakeup` is
+/// notified and prefetching the lyrics of queued tracks through `lyrics_fetcher`
+async fn start_spotify_poller(
+    oauth: Arc<SpotifyOAuth>,
+    spotify_config: SpotifyProviderConfig,
+    wakeup: Option<Arc<Notify>>,
+    lyrics_fetcher: Arc<LyricsFetcher>,
+    sync_engine: Arc<SyncEngine>,
+    cancel_token: CancellationToken,
+) {
+    info!("Initializing Spotify Web API poller...");
+
+    // Ensure we're authenticated. If that fails, poll once the user signs in through
+    // "Re-authenticate" in the settings panel.
     if let Err(e) = oauth.ensure_authenticated().await {
         error!("Spotify authentication failed: {}", e);
-        return;
+        info!("Waiting for Spotify sign-in from the settings panel");
+        tokio::select! {
+            () = cancel_token.cancelled() => return,
+            () = oauth.wait_for_sign_in() => {}
+        }
     }
 
     info!("Spotify authenticated successfully!");
 
     // Create and start the poller with cancellation token
-    let poller = Arc::new(SpotifyPoller::new(
-        oauth,
-        sync_engine,
-        spotify_config.poll_interval_ms,
-        Some(cancel_token),
-    ));
+    let poll_interval_ms = spotify_config.poll_interval_ms;
+    let mut poller = SpotifyPoller::new(oauth, sync_engine, poll_interval_ms, Some(cancel_token))
+        .with_queue_prefetch(lyrics_fetcher, spotify_config.prefetch_queue);
+    if let Some(wakeup) = wakeup {
+        poller = poller.with_wakeup(wakeup);
+    }
+    let poller = Arc::new(poller);
 
-    info!(
-        "Starting Spotify poller (interval: {}ms)",
-        spotify_config.poll_interval_ms
-    );
+    info!("Starting Spotify poller (interval: {}ms)", poll_interval_ms);
+    let handle = poller.start();
+    let _ = handle.await;
+}
+
+/// Start the Windows media session poller to follow any local player
+async fn start_windows_media_poller(sync_engine: Arc<SyncEngine>, cancel_token: CancellationToken) {
+    info!("Starting Windows media session poller");
+    let poller = Arc::new(WindowsMediaPoller::new(sync_engine, Some(cancel_token)));
     let handle = poller.start();
     let _ = handle.await;
 }
@@ -550,7 +881,64 @@ async fn start_lyrics_fetcher(lyrics_fetcher: Arc<LyricsFetcher>) {
     let _ = handle.await;
 }
 
+/// Path passed via `--replay <path>`, if any
+fn replay_trace_arg() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
+
+/// Config file passed via `--config <path>`, or that of `--profile <name>`, if any
+fn config_arg() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => return args.next().map(PathBuf::from),
+            "--profile" => {
+                return args
+                    .next()
+                    .map(|name| versualizer_core::paths::profile_config_path(&name));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the app was launched with `--safe-mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafeMode(pub bool);
+
+/// Whether `--safe-mode` was passed
+fn safe_mode_arg() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--safe-mode")
+}
+
+/// View passed via `--view <performer|audience>`, if any
+fn view_arg() -> Option<OverlayView> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--view" {
+            let name = args.next()?;
+            let view = OverlayView::from_name(&name);
+            if view.is_none() {
+                warn!(
+                    "Unknown view {:?}, expected \"performer\" or \"audience\"",
+                    name
+                );
+            }
+            return view;
+        }
+    }
+    None
+}
+
 /// Log all sync events to the console
+#[allow(clippy::too_many_lines)]
 async fn log_sync_events(sync_engine: Arc<SyncEngine>) {
     let mut rx = sync_engine.subscribe();
 
@@ -573,6 +961,9 @@ async fn log_sync_events(sync_engine: Arc<SyncEngine>) {
                     SyncEvent::PlaybackStopped => {
                         info!("Playback stopped");
                     }
+                    SyncEvent::AdPlaying => {
+                        info!("Ad playing, skipping lyrics");
+                    }
                     SyncEvent::TrackChanged { track, position } => {
                         info!(
                             "Track changed: {} - {} [{}] (at {:?})",
@@ -585,15 +976,69 @@ async fn log_sync_events(sync_engine: Arc<SyncEngine>) {
                     SyncEvent::SeekOccurred { position } => {
                         info!("Seek to {:?}", position);
                     }
+                    SyncEvent::LyricsFetchStarted { provider } => {
+                        debug!("Searching {} for lyrics", provider);
+                    }
+                    SyncEvent::LyricsFetchFailed { provider, reason } => {
+                        debug!("Lyrics provider {} failed: {}", provider, reason);
+                    }
                     SyncEvent::LyricsLoaded { lyrics } => {
                         info!("Lyrics loaded: {} lines", lyrics.lines.len());
                     }
+                    SyncEvent::UnsyncedLyricsLoaded { text } => {
+                        info!("Unsynced lyrics loaded: {} lines", text.lines().count());
+                    }
                     SyncEvent::LyricsNotFound => {
                         info!("No lyrics found for current track");
                     }
-                    SyncEvent::Error { message } => {
+                    SyncEvent::TrackInstrumental => {
+                        info!("Current track is instrumental");
+                    }
+                    SyncEvent::LyricsBlocked { kind } => {
+                        info!("Lyrics of current {} are blocked", kind);
+                    }
+                    SyncEvent::LyricsRetrying {
+                        attempt,
+                        max_attempts,
+                        retry_in,
+                    } => {
+                        info!(
+                            "Lyrics providers unreachable, retrying in {:?} ({}/{})",
+                            retry_in, attempt, max_attempts
+                        );
+                    }
+                    SyncEvent::LyricsTrimmed { removed } => {
+                        warn!("Lyrics trimmed to fit the size limits, dropped {}", removed);
+                    }
+                    SyncEvent::LyricsSource { provider } => {
+                        info!("Lyrics source: {}", provider);
+                    }
+                    SyncEvent::AnalysisLoaded { features } => {
+                        info!(
+                            "Audio analysis loaded: {} beats, {} sections",
+                            features.beats.len(),
+                            features.sections.len()
+                        );
+                    }
+                    SyncEvent::ConfigChanged { config } => {
+                        info!(
+                            "Config reloaded, lyrics providers: {}",
+                            config.lyrics.provider_names().join(", ")
+                        );
+                    }
+                    SyncEvent::Error {
+                        message,
+                        fetch_id: None,
+                    } => {
                         error!("Sync error: {}", message);
                     }
+                    SyncEvent::Error {
+                        message,
+                        fetch_id: Some(fetch_id),
+                    } => {
+                        // Already logged as a warning by the fetcher, inside its fetch span
+                        debug!("Lyrics fetch {} error: {}", fetch_id, message);
+                    }
                 }
             }
             Err(tokio::sync::broadcast::error::RecvError::Closed) => {
@@ -607,12 +1052,12 @@ async fn log_sync_events(sync_engine: Arc<SyncEngine>) {
     }
 }
 
-/// Load window icon from embedded PNG for taskbar display
+/// Load window icon from the app icon resource for taskbar display
 fn load_window_icon() -> Option<Icon> {
     // Use the 64x64 PNG for good taskbar resolution
-    let icon_bytes = include_bytes!("../icons/64x64.png");
+    let icon_bytes = resources::load(Resource::AppIcon);
 
-    let img = match image::load_from_memory(icon_bytes) {
+    let img = match image::load_from_memory(&icon_bytes) {
         Ok(img) => img.into_rgba8(),
         Err(e) => {
             error!("Failed to load window icon: {}", e);
@@ -653,9 +1098,7 @@ fn check_file_logging_enabled() -> bool {
         return false;
     };
 
-    toml::from_str::<PartialConfig>(&content)
-        .map(|c| c.logging.enabled)
-        .unwrap_or(false)
+    toml::from_str::<PartialConfig>(&content).is_ok_and(|c| c.logging.enabled)
 }
 
 /// Initialize tracing with console output and optional file logging
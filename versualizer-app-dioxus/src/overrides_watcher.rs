@@ -0,0 +1,79 @@
+//! Hot-reload of user-supplied `.lrc` override files.
+//!
+//! Watches the overrides folder (`~/.config/versualizer/overrides/`) and shows an override
+//! file as soon as one for the current track is created or saved, so an external LRC
+//! editor gives instant feedback on timing fixes.
+
+use notify_debouncer_mini::{DebounceEventResult, new_debouncer, notify::RecursiveMode};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+use versualizer_core::{LyricsFetcher, LyricsOverrides, SyncEngine};
+
+/// Reload overrides for the current track when their files change, until cancelled
+pub async fn watch_overrides(
+    overrides: LyricsOverrides,
+    fetcher: Arc<LyricsFetcher>,
+    sync_engine: Arc<SyncEngine>,
+    cancel_token: CancellationToken,
+) {
+    if let Err(e) = tokio::fs::create_dir_all(overrides.dir()).await {
+        error!(
+            "Failed to create overrides folder {:?}: {}",
+            overrides.dir(),
+            e
+        );
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::channel::<Vec<PathBuf>>(16);
+    // Debounce so an editor's write-and-rename save reloads once
+    let mut debouncer = match new_debouncer(
+        Duration::from_millis(300),
+        move |res: DebounceEventResult| {
+            if let Ok(events) = res {
+                let _ = tx.blocking_send(events.into_iter().map(|event| event.path).collect());
+            }
+        },
+    ) {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Failed to create overrides watcher: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = debouncer
+        .watcher()
+        .watch(overrides.dir(), RecursiveMode::NonRecursive)
+    {
+        error!("Failed to watch overrides folder: {}", e);
+        return;
+    }
+    info!("Watching {:?} for lyrics overrides", overrides.dir());
+
+    loop {
+        let paths = tokio::select! {
+            () = cancel_token.cancelled() => break,
+            paths = rx.recv() => match paths {
+                Some(paths) => paths,
+                None => break,
+            },
+        };
+        let Some(track) = sync_engine.current_track().await else {
+            continue;
+        };
+        if paths
+            .iter()
+            .any(|path| overrides.is_override_for(path, &track) && path.exists())
+        {
+            info!("Override file for {} changed, reloading", track.name);
+            fetcher.load_override(&track).await;
+        }
+    }
+
+    info!("Overrides watcher shutting down");
+    drop(debouncer);
+}
@@ -0,0 +1,112 @@
+//! Resources shipped with the app: the default theme, the app icon, and extra fonts.
+//!
+//! Every resource is compiled in. Files in the user's config directory override it, and
+//! with the `bundle` feature so do files in the platform bundle (macOS `.app`, MSIX,
+//! Linux packages), which lets store builds ship updated resources next to the binary.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use versualizer_core::resources::{find_resource, font_files, resource_dirs};
+
+/// A resource with a compiled-in fallback
+#[derive(Debug, Clone, Copy)]
+pub enum Resource {
+    /// Template copied to the user's `theme.css` on first run
+    DefaultTheme,
+    /// Window and tray icon
+    AppIcon,
+}
+
+impl Resource {
+    /// Path relative to a resource directory, matching the bundle layout in `Dioxus.toml`
+    const fn relative_path(self) -> &'static str {
+        match self {
+            Self::DefaultTheme => "assets/default_theme.css",
+            Self::AppIcon => "icons/64x64.png",
+        }
+    }
+
+    const fn embedded(self) -> &'static [u8] {
+        match self {
+            Self::DefaultTheme => include_bytes!("../assets/default_theme.css"),
+            Self::AppIcon => include_bytes!("../icons/64x64.png"),
+        }
+    }
+}
+
+/// Directories searched for resource files, most important first
+fn search_dirs() -> Vec<PathBuf> {
+    resource_dirs(cfg!(feature = "bundle"))
+}
+
+/// Load `resource` from the first directory that has it, or the compiled-in copy
+#[must_use]
+pub fn load(resource: Resource) -> Cow<'static, [u8]> {
+    if let Some(path) = find_resource(&search_dirs(), resource.relative_path()) {
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                info!("Loaded {:?} from {:?}", resource, path);
+                return Cow::Owned(bytes);
+            }
+            Err(e) => warn!("Failed to read {:?}, using built-in copy: {}", path, e),
+        }
+    }
+    Cow::Borrowed(resource.embedded())
+}
+
+/// The default theme CSS
+#[must_use]
+pub fn default_theme_css() -> String {
+    String::from_utf8_lossy(&load(Resource::DefaultTheme)).into_owned()
+}
+
+/// Extra font files from the `fonts` resource directories
+#[must_use]
+pub fn fonts() -> Vec<PathBuf> {
+    font_files(&search_dirs())
+}
+
+/// `@font-face` rules embedding every extra font, so themes can use them by file name
+/// (e.g. `font-family: "Inter"` for `fonts/Inter.ttf`). The webview cannot load local
+/// files, so the fonts are inlined as data URIs.
+#[must_use]
+pub fn font_face_css() -> String {
+    let mut css = String::new();
+    for path in fonts() {
+        let Some(family) = path.file_stem().map(|stem| stem.to_string_lossy()) else {
+            continue;
+        };
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to read font {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let _ = writeln!(
+            css,
+            "@font-face {{ font-family: \"{family}\"; src: url(data:{};base64,{}); }}",
+            font_mime(&path),
+            BASE64.encode(bytes)
+        );
+    }
+    css
+}
+
+fn font_mime(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("otf") => "font/otf",
+        Some("ttc") => "font/collection",
+        Some("woff2") => "font/woff2",
+        _ => "font/ttf",
+    }
+}
@@ -1,7 +1,10 @@
 use dioxus::prelude::*;
 use std::time::{Duration, Instant};
-use tracing::{info, trace};
-use versualizer_core::LrcFile;
+use tracing::{info, trace, warn};
+use versualizer_core::{
+    LrcFile, ProviderStatuses, TOGETHER_PART, Tempo, TrackInfo, split_at_words,
+    split_keeping_spacing,
+};
 
 /// Convert u128 milliseconds to u64, saturating at `u64::MAX`.
 /// In practice, this is safe because song durations never exceed `u64::MAX` milliseconds
@@ -10,6 +13,15 @@ fn millis_to_u64(millis: u128) -> u64 {
     u64::try_from(millis).unwrap_or(u64::MAX)
 }
 
+/// A single word with its start offset relative to the beginning of its line
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimedWord {
+    /// The word text
+    pub text: String,
+    /// When this word starts, relative to the line start (milliseconds)
+    pub offset_ms: u64,
+}
+
 /// A precomputed lyric line with all timing info needed for UI animation
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct TimedLine {
@@ -19,6 +31,187 @@ pub struct TimedLine {
     pub start_time_ms: u64,
     /// Duration until the next line starts (milliseconds)
     pub duration_ms: u64,
+    /// Word-level timing from enhanced LRC (empty if the lyrics are line-synced only)
+    pub words: Vec<TimedWord>,
+    /// Translation shown underneath the line, once the song has been translated
+    pub translation: Option<String>,
+    /// Romanized reading shown above the line (`lyrics.processors.romanize_lines`)
+    pub romanization: Option<String>,
+    /// Who sings the line in a duet, which sets its color and column
+    pub part: SingerPart,
+    /// Written right-to-left (Hebrew, Arabic, ...), so it is aligned and filled from the right
+    pub rtl: bool,
+}
+
+/// Who sings a line, from the lyrics' part prefixes (`v1:`, `M:`, `D:`, ...)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SingerPart {
+    /// The lyrics do not say, or there is only one singer
+    #[default]
+    Unknown,
+    /// The first singer of the song
+    First,
+    /// The second singer of the song
+    Second,
+    /// Both singers together (`D:`)
+    Together,
+    /// Anyone but the first two singers
+    Other,
+}
+
+impl SingerPart {
+    /// Column of the line when duets are split (`ui.layout.duet_split`)
+    #[must_use]
+    pub const fn duet_column(self) -> DuetColumn {
+        match self {
+            Self::First => DuetColumn::Left,
+            Self::Second => DuetColumn::Right,
+            Self::Unknown | Self::Together | Self::Other => DuetColumn::Both,
+        }
+    }
+
+    /// Get the CSS class that colors a line sung by this part
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::Unknown => "",
+            Self::Other => "part-other",
+            Self::First => "part-first",
+            Self::Second => "part-second",
+            Self::Together => "part-together",
+        }
+    }
+}
+
+/// Where a line goes when duets are split into two columns
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuetColumn {
+    /// Sung together, or by anyone but the first two singers
+    #[default]
+    Both,
+    /// Sung by the first singer
+    Left,
+    /// Sung by the second singer
+    Right,
+}
+
+impl DuetColumn {
+    /// Get the CSS class applied to the lines of this column
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::Both => "duet-both",
+            Self::Left => "duet-left",
+            Self::Right => "duet-right",
+        }
+    }
+
+    /// Whether this column shows a line sung by `part`. Lines sung together are in both.
+    #[must_use]
+    pub fn holds(self, part: SingerPart) -> bool {
+        let column = part.duet_column();
+        column == Self::Both || column == self
+    }
+}
+
+/// The lines of one stack in the overlay: every line, or one singer's column of a split
+/// duet. Lines are placed by their rank in the stack, which is their index in the stack of
+/// every line, and each stack has its own current line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StackLines {
+    /// Indices of the lines in the lyrics, starting with `INTRO_LINE_INDEX` for the intro
+    indices: Vec<i32>,
+    /// Rank of the first line: `INTRO_LINE_INDEX` for the intro, otherwise 0
+    first_rank: i32,
+    /// Whether every line is in the stack, so ranks are line indices
+    every_line: bool,
+}
+
+impl StackLines {
+    /// Rank of the last line in the stack at or before line `index`, one before the first
+    /// rank if there is none. This is the stack's current line when `index` is current.
+    #[must_use]
+    pub fn rank_at(&self, index: i32) -> i32 {
+        let count = self.indices.partition_point(|&i| i <= index);
+        // Safe: line count is always much less than i32::MAX
+        i32::try_from(count).unwrap_or(i32::MAX) - 1 + self.first_rank
+    }
+
+    /// Index in the lyrics of the line at `rank`
+    #[must_use]
+    pub fn index_at(&self, rank: i32) -> Option<i32> {
+        usize::try_from(rank - self.first_rank)
+            .ok()
+            .and_then(|i| self.indices.get(i).copied())
+    }
+
+    /// The rank a view at line `position` (fractional while scrolling) is at
+    #[must_use]
+    pub fn rank_of_position(&self, position: f32) -> f32 {
+        if self.every_line {
+            return position;
+        }
+        // Positions are bounded by the lyrics length, far inside i32's range
+        #[allow(clippy::cast_possible_truncation)]
+        let index = position.round() as i32;
+        #[allow(clippy::cast_precision_loss)]
+        let rank = self.rank_at(index) as f32;
+        rank
+    }
+}
+
+impl TimedLine {
+    /// Whether this is an instrumental break long enough for countdown dots
+    #[must_use]
+    pub fn is_long_instrumental(&self) -> bool {
+        self.text == MUSIC_NOTE && self.duration_ms >= COUNTDOWN_DOTS_MIN_GAP_MS
+    }
+
+    /// Split the line into words with reveal offsets for per-word effects.
+    ///
+    /// The word texts are slices of the line text, so joined together they reproduce it
+    /// with its original spacing and punctuation. Uses word timings when the lyrics provide
+    /// them. Otherwise the words are spread evenly across the line duration.
+    #[must_use]
+    pub fn word_reveal_offsets(&self) -> Vec<TimedWord> {
+        let timed_texts: Vec<&str> = self.words.iter().map(|w| w.text.as_str()).collect();
+        if let Some(segments) = split_at_words(&self.text, &timed_texts) {
+            return segments
+                .into_iter()
+                .zip(&self.words)
+                .map(|(text, word)| TimedWord {
+                    text: text.to_string(),
+                    offset_ms: word.offset_ms,
+                })
+                .collect();
+        }
+
+        let segments = split_keeping_spacing(&self.text);
+        let count = u64::try_from(segments.len()).unwrap_or(u64::MAX).max(1);
+        let step_ms = self.duration_ms / count;
+
+        segments
+            .into_iter()
+            .zip(0_u64..)
+            .map(|(text, i)| TimedWord {
+                text: text.to_string(),
+                offset_ms: step_ms.saturating_mul(i),
+            })
+            .collect()
+    }
+}
+
+/// Whether `text` reads right-to-left, going by its first letter with a strong direction as
+/// the Unicode bidi algorithm does for a paragraph
+fn is_rtl(text: &str) -> bool {
+    text.chars().find(|c| c.is_alphabetic()).is_some_and(|c| {
+        matches!(c,
+                // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic and Arabic extensions
+                '\u{0590}'..='\u{08FF}'
+                // Hebrew and Arabic presentation forms
+                | '\u{FB1D}'..='\u{FDFF}'
+                | '\u{FE70}'..='\u{FEFF}')
+    })
 }
 
 /// Sentinel value indicating we're in the instrumental intro (before first lyric line)
@@ -27,13 +220,26 @@ pub const INTRO_LINE_INDEX: i32 = -1;
 /// Music note character for instrumental sections
 const MUSIC_NOTE: &str = "\u{266A}"; // ♪
 
-/// Precomputed lyrics with all timing information
+/// Shortest instrumental intro or break that counts down to the next line with dots
+const COUNTDOWN_DOTS_MIN_GAP_MS: u64 = 3000;
+
+/// How long a line is shown when nothing tells when the next one starts
+const DEFAULT_LINE_DURATION_MS: u64 = 5000;
+
+/// Precomputed lyrics with all timing information.
+///
+/// Built from lyrics the sync engine has already trimmed to the caps in
+/// [`versualizer_core::limits`], so a pathological provider response stays bounded in memory.
 #[derive(Clone, Debug, Default)]
 pub struct PrecomputedLyrics {
     /// All lines with their timing info
     pub lines: Vec<TimedLine>,
     /// Duration of instrumental intro (0 if lyrics start at beginning)
     pub intro_duration_ms: u64,
+    /// Plain lyrics with estimated timing, shown without a current line or fill
+    pub unsynced: bool,
+    /// Whether lines are sung by at least two different singers
+    pub is_duet: bool,
 }
 
 impl PrecomputedLyrics {
@@ -41,17 +247,16 @@ impl PrecomputedLyrics {
     #[must_use]
     pub fn from_lrc(lrc: &LrcFile) -> Self {
         let mut lines = Vec::with_capacity(lrc.lines.len());
+        // Singers in the order they first sing, the first two of which get a color and column each
+        let mut parts: Vec<&str> = Vec::new();
 
-        for (i, line) in lrc.lines.iter().enumerate() {
+        for (line, next_start) in lrc.windows() {
             let start_time_ms = millis_to_u64(line.start_time.as_millis());
 
             // Duration is time until next line, or default 5 seconds for last line
-            let duration_ms = if i + 1 < lrc.lines.len() {
-                let next_start = millis_to_u64(lrc.lines[i + 1].start_time.as_millis());
-                next_start.saturating_sub(start_time_ms)
-            } else {
-                5000 // Default 5 seconds for the last line
-            };
+            let duration_ms = next_start.map_or(DEFAULT_LINE_DURATION_MS, |next_start| {
+                millis_to_u64(next_start.as_millis()).saturating_sub(start_time_ms)
+            });
 
             // Use music note for empty/whitespace-only lines (instrumental breaks)
             let text = if line.text.trim().is_empty() {
@@ -60,10 +265,46 @@ impl PrecomputedLyrics {
                 line.text.clone()
             };
 
+            let words = line
+                .words
+                .as_ref()
+                .map(|words| {
+                    words
+                        .iter()
+                        .map(|word| TimedWord {
+                            text: word.text.clone(),
+                            offset_ms: millis_to_u64(word.start_time.as_millis())
+                                .saturating_sub(start_time_ms),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let part = match line.part.as_deref() {
+                None => SingerPart::Unknown,
+                Some(TOGETHER_PART) => SingerPart::Together,
+                Some(part) => {
+                    let index = parts.iter().position(|p| *p == part).unwrap_or_else(|| {
+                        parts.push(part);
+                        parts.len() - 1
+                    });
+                    match index {
+                        0 => SingerPart::First,
+                        1 => SingerPart::Second,
+                        _ => SingerPart::Other,
+                    }
+                }
+            };
+
             lines.push(TimedLine {
                 text,
                 start_time_ms,
                 duration_ms,
+                words,
+                translation: None,
+                romanization: line.romanized.clone(),
+                part,
+                rtl: is_rtl(&line.text),
             });
         }
 
@@ -73,6 +314,44 @@ impl PrecomputedLyrics {
         Self {
             lines,
             intro_duration_ms,
+            unsynced: false,
+            is_duet: parts.len() >= 2,
+        }
+    }
+
+    /// Create lyrics from plain text without timing. The lines are spread evenly over
+    /// `track_duration_ms` so the view scrolls along with the track, or shown for 5 seconds
+    /// each if the duration is unknown.
+    #[must_use]
+    pub fn from_plain(text: &str, track_duration_ms: u64) -> Self {
+        let texts: Vec<&str> = text.lines().map(str::trim).collect();
+        let count = u64::try_from(texts.len()).unwrap_or(u64::MAX).max(1);
+        let duration_ms = if track_duration_ms == 0 {
+            DEFAULT_LINE_DURATION_MS
+        } else {
+            (track_duration_ms / count).max(1)
+        };
+
+        let lines = texts
+            .into_iter()
+            .zip(0_u64..)
+            .map(|(text, i)| TimedLine {
+                rtl: is_rtl(text),
+                text: text.to_string(),
+                start_time_ms: duration_ms.saturating_mul(i),
+                duration_ms,
+                words: Vec::new(),
+                translation: None,
+                romanization: None,
+                part: SingerPart::Unknown,
+            })
+            .collect();
+
+        Self {
+            lines,
+            intro_duration_ms: 0,
+            unsynced: true,
+            is_duet: false,
         }
     }
 
@@ -80,18 +359,46 @@ impl PrecomputedLyrics {
     /// Returns `INTRO_LINE_INDEX` (-1) if we're before the first line starts.
     #[must_use]
     pub fn line_index_at(&self, position_ms: u64) -> i32 {
-        // Find the last line that started before or at the current position
+        self.last_started(|line| line.start_time_ms <= position_ms)
+    }
+
+    /// Like [`Self::line_index_at`], but each line starts on the beat nearest its timestamp
+    #[must_use]
+    pub fn line_index_on_beat(&self, position_ms: u64, tempo: Tempo) -> i32 {
+        let position = Duration::from_millis(position_ms);
+        // Snapping keeps the start times in order, so they can still be binary searched
+        self.last_started(|line| tempo.snap(Duration::from_millis(line.start_time_ms)) <= position)
+    }
+
+    /// Index of the last line `started` holds for, binary searched as lines are in start
+    /// order, or `INTRO_LINE_INDEX` if it holds for none
+    fn last_started(&self, started: impl Fn(&TimedLine) -> bool) -> i32 {
         self.lines
-            .iter()
-            .enumerate()
-            .rev()
-            .find(|(_, line)| line.start_time_ms <= position_ms)
-            .map_or(INTRO_LINE_INDEX, |(i, _)| {
+            .partition_point(started)
+            .checked_sub(1)
+            .map_or(INTRO_LINE_INDEX, |i| {
                 // Safe: line count is always much less than i32::MAX
                 i32::try_from(i).unwrap_or(i32::MAX)
             })
     }
 
+    /// The lines of the stack showing `column` of a split duet, or every line for `None`
+    #[must_use]
+    pub fn stack_lines(&self, column: Option<DuetColumn>) -> StackLines {
+        let intro = self.has_intro().then_some(INTRO_LINE_INDEX);
+        let lines = self
+            .lines
+            .iter()
+            .zip(0..)
+            .filter(|(line, _)| column.is_none_or(|column| column.holds(line.part)))
+            .map(|(_, index)| index);
+        StackLines {
+            indices: intro.into_iter().chain(lines).collect(),
+            first_rank: intro.unwrap_or(0),
+            every_line: column.is_none(),
+        }
+    }
+
     /// Create a virtual "intro line" with music note for the instrumental intro period
     #[must_use]
     pub fn intro_line(&self) -> TimedLine {
@@ -99,7 +406,38 @@ impl PrecomputedLyrics {
             text: MUSIC_NOTE.into(),
             start_time_ms: 0,
             duration_ms: self.intro_duration_ms,
+            words: Vec::new(),
+            translation: None,
+            romanization: None,
+            part: SingerPart::Unknown,
+            rtl: false,
+        }
+    }
+
+    /// Attach line-aligned translations. Blank translations leave their line untranslated.
+    /// Returns `false` (and changes nothing) if the translation does not have one entry
+    /// per line, e.g. because it was made for an older version of the lyrics.
+    pub fn set_translations(&mut self, translations: &[String]) -> bool {
+        if translations.len() != self.lines.len() {
+            return false;
+        }
+        for (line, translation) in self.lines.iter_mut().zip(translations) {
+            let translation = translation.trim();
+            line.translation = (!translation.is_empty()).then(|| translation.to_string());
         }
+        true
+    }
+
+    /// Get the line at an absolute index, where `INTRO_LINE_INDEX` is the intro line
+    /// (only present when the song has an intro).
+    #[must_use]
+    pub fn line_at(&self, index: i32) -> Option<TimedLine> {
+        if index == INTRO_LINE_INDEX {
+            return self.has_intro().then(|| self.intro_line());
+        }
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.lines.get(i).cloned())
     }
 
     /// Check if there's an instrumental intro (first line doesn't start at 0)
@@ -126,6 +464,15 @@ pub struct KaraokeState {
     /// Position at last seek/sync in milliseconds (used to calculate animation offset).
     /// Also used as part of the animation key to force restart on seek or lyrics load.
     pub animation_sync_position_ms: Signal<u64>,
+    /// Absolute line index the view is pinned to while auto-follow is paused
+    /// (`None` = following the current line)
+    pub view_anchor: Signal<Option<f32>>,
+    /// When the user last scrolled or the pointer left, used to time auto-follow resume
+    pub follow_paused_at: Signal<Option<Instant>>,
+    /// Whether the pointer is over the lyrics
+    pub hovering: Signal<bool>,
+    /// Latest status of every lyrics provider that has been tried
+    pub provider_statuses: Signal<ProviderStatuses>,
 }
 
 impl KaraokeState {
@@ -137,7 +484,108 @@ impl KaraokeState {
             current_index: Signal::new(INTRO_LINE_INDEX),
             is_playing: Signal::new(false),
             animation_sync_position_ms: Signal::new(0),
+            view_anchor: Signal::new(None),
+            follow_paused_at: Signal::new(None),
+            hovering: Signal::new(false),
+            provider_statuses: Signal::new(ProviderStatuses::new()),
+        }
+    }
+
+    /// Where the view is: the pinned anchor while paused, otherwise the current line
+    #[must_use]
+    pub fn view_position(&self) -> f32 {
+        // Line indices are far inside f32's exact integer range
+        #[allow(clippy::cast_precision_loss)]
+        let current = *self.current_index.read() as f32;
+        self.view_anchor.read().unwrap_or(current)
+    }
+
+    /// Stop following the current line, pinning the view where it is
+    pub fn pause_follow(&mut self) {
+        if self.lyrics.read().is_none() || self.view_anchor.read().is_some() {
+            return;
+        }
+        let position = self.view_position();
+        self.view_anchor.set(Some(position));
+        self.follow_paused_at.set(Some(Instant::now()));
+    }
+
+    /// Scroll the view by `lines`, pausing auto-follow and staying within the lyrics
+    pub fn peek_by(&mut self, lines: f32) {
+        let Some(line_count) = self.lyrics.read().as_ref().map(|l| l.lines.len()) else {
+            return;
+        };
+
+        // Lyrics are far shorter than f32's exact integer range
+        #[allow(clippy::cast_precision_loss)]
+        let (min, max) = (INTRO_LINE_INDEX as f32, (line_count as f32 - 1.0).max(0.0));
+        let anchor = (self.view_position() + lines).clamp(min, max);
+
+        self.view_anchor.set(Some(anchor));
+        self.follow_paused_at.set(Some(Instant::now()));
+    }
+
+    /// Resume following the current line
+    pub fn resume_follow(&mut self) {
+        self.view_anchor.set(None);
+        self.follow_paused_at.set(None);
+    }
+
+    /// Track pointer hover over the lyrics. Entering pauses auto-follow when
+    /// `pause_on_hover` is set; leaving starts the resume timer.
+    pub fn set_hovering(&mut self, hovering: bool, pause_on_hover: bool) {
+        self.hovering.set(hovering);
+        if hovering && pause_on_hover {
+            self.pause_follow();
+        } else if !hovering && self.view_anchor.read().is_some() {
+            self.follow_paused_at.set(Some(Instant::now()));
+        }
+    }
+
+    /// Whether auto-follow has been paused long enough (and the pointer is gone) to resume
+    #[must_use]
+    pub fn should_resume_follow(&self, resume_after: Duration) -> bool {
+        !*self.hovering.peek()
+            && self
+                .follow_paused_at
+                .peek()
+                .is_some_and(|at| at.elapsed() >= resume_after)
+    }
+
+    /// Time left until auto-follow resumes, or `None` while following or hovering
+    #[must_use]
+    pub fn resume_follow_in(&self, resume_after: Duration) -> Option<Duration> {
+        if *self.hovering.peek() {
+            return None;
         }
+        let paused_at = *self.follow_paused_at.peek();
+        paused_at.map(|at| resume_after.saturating_sub(at.elapsed()))
+    }
+
+    /// Get the lines of `stack` with their ranks and line indices, from rank
+    /// `center - before` to `center + after`. Ranks without a line (before the intro or
+    /// past the end) are skipped.
+    #[must_use]
+    pub fn lines_window(
+        &self,
+        stack: &StackLines,
+        center: i32,
+        before: usize,
+        after: usize,
+    ) -> Vec<(i32, i32, TimedLine)> {
+        let lyrics = self.lyrics.read();
+        let Some(ref lyrics) = *lyrics else {
+            return Vec::new();
+        };
+
+        let before = i32::try_from(before).unwrap_or(i32::MAX);
+        let after = i32::try_from(after).unwrap_or(i32::MAX);
+        (center.saturating_sub(before)..=center.saturating_add(after))
+            .filter_map(|rank| {
+                let index = stack.index_at(rank)?;
+                lyrics.line_at(index).map(|line| (rank, index, line))
+            })
+            .collect()
     }
 
     /// Sync the animation position for seek events or initial lyrics load.
@@ -153,12 +601,37 @@ impl KaraokeState {
         self.lyrics.set(Some(precomputed));
         // Reset to intro state - timer will update current_index
         self.current_index.set(INTRO_LINE_INDEX);
+        self.resume_follow();
+    }
+
+    /// Show plain lyrics, scrolled along a track of `duration_ms`
+    pub fn set_unsynced_lyrics(&mut self, text: &str, duration_ms: u64) {
+        self.lyrics
+            .set(Some(PrecomputedLyrics::from_plain(text, duration_ms)));
+        self.current_index.set(0);
+        self.resume_follow();
+    }
+
+    /// Show `translations` underneath the current lyrics, one per line
+    pub fn set_translations(&mut self, translations: &[String]) {
+        let mut lyrics = self.lyrics.write();
+        let Some(ref mut lyrics) = *lyrics else {
+            return;
+        };
+        if !lyrics.set_translations(translations) {
+            warn!(
+                "Ignoring translation with {} lines for lyrics with {} lines",
+                translations.len(),
+                lyrics.lines.len()
+            );
+        }
     }
 
     /// Clear lyrics (no lyrics available or track changed)
     pub fn clear_lyrics(&mut self) {
         self.lyrics.set(None);
         self.current_index.set(INTRO_LINE_INDEX);
+        self.resume_follow();
     }
 
     /// Set the playing state
@@ -166,39 +639,90 @@ impl KaraokeState {
         self.is_playing.set(playing);
     }
 
-    /// Get visible lines around the current position.
-    /// When in intro (idx < 0), returns intro line + first few actual lines.
-    /// When on a line (idx >= 0), returns lines around the current position.
+    /// The lyric line being sung, or `None` during the intro or without synced lyrics
     #[must_use]
-    pub fn visible_lines(&self, before: usize, after: usize) -> Vec<TimedLine> {
-        let lyrics = self.lyrics.read();
-        let current_idx = *self.current_index.read();
+    pub fn current_line(&self) -> Option<TimedLine> {
+        let index = *self.current_index.peek();
+        if index == INTRO_LINE_INDEX {
+            return None;
+        }
+        let lyrics = self.lyrics.peek();
+        let lyrics = lyrics.as_ref().filter(|lyrics| !lyrics.unsynced)?;
+        lyrics.line_at(index)
+    }
+}
 
-        let Some(ref lyrics) = *lyrics else {
-            return Vec::new();
-        };
+impl Default for KaraokeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        if current_idx < 0 {
-            // In intro: show intro line (music note) + upcoming lines
-            let mut result = Vec::with_capacity(1 + after);
-            if lyrics.has_intro() {
-                result.push(lyrics.intro_line());
-            }
-            // Add upcoming actual lines
-            result.extend(lyrics.lines.iter().take(after).cloned());
-            result
-        } else {
-            // On an actual line
-            // current_idx >= 0 is guaranteed by the outer if condition
-            let idx = usize::try_from(current_idx).unwrap_or(0);
-            let start = idx.saturating_sub(before);
-            let end = (idx + after + 1).min(lyrics.lines.len());
-            lyrics.lines[start..end].to_vec()
+/// The track shown in place of lyrics after no provider had lyrics for it.
+///
+/// Kept apart from [`KaraokeState`] since only the no-lyrics renderer reads it.
+#[derive(Clone, Copy)]
+pub struct MissingLyrics {
+    /// The track without lyrics (`None` while lyrics are shown, loading, or nothing plays)
+    pub track: Signal<Option<TrackInfo>>,
+}
+
+impl MissingLyrics {
+    /// Create an empty state
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            track: Signal::new(None),
+        }
+    }
+
+    /// Record that no lyrics were found for `track`
+    pub fn set(&mut self, track: Option<TrackInfo>) {
+        self.track.set(track);
+    }
+
+    /// Lyrics loaded or the track changed
+    pub fn clear(&mut self) {
+        if self.track.peek().is_some() {
+            self.track.set(None);
         }
     }
 }
 
-impl Default for KaraokeState {
+impl Default for MissingLyrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The beat playing, for pulsing the current line with `beat_pulse`.
+///
+/// Kept apart from [`KaraokeState`] since only the current line reads it.
+#[derive(Clone, Copy)]
+pub struct CurrentBeat {
+    /// Index of the beat in the track's audio analysis (`None` without one, or between
+    /// tracks)
+    pub index: Signal<Option<usize>>,
+}
+
+impl CurrentBeat {
+    /// Create a state without a beat
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            index: Signal::new(None),
+        }
+    }
+
+    /// Move to beat `index`, only touching the signal when it changes
+    pub fn set(&mut self, index: Option<usize>) {
+        if *self.index.peek() != index {
+            self.index.set(index);
+        }
+    }
+}
+
+impl Default for CurrentBeat {
     fn default() -> Self {
         Self::new()
     }
@@ -1,10 +1,11 @@
 //! Theme file watching and hot-reload CSS injection.
 //!
 //! This module handles:
-//! 1. Copying the embedded CSS template to the user's config directory on first run
-//! 2. Loading CSS from the user's theme file at runtime
-//! 3. Watching the theme file for changes and updating a Signal to trigger re-render
+//! 1. Copying the default CSS template to the user's config directory on first run
+//! 2. Loading CSS from the theme chosen in `ui.theme` at runtime
+//! 3. Watching the theme files for changes and updating a Signal to trigger re-render
 
+use crate::resources::default_theme_css;
 use dioxus::prelude::*;
 use notify_debouncer_mini::{DebounceEventResult, new_debouncer, notify::RecursiveMode};
 use std::fs;
@@ -15,6 +16,7 @@ use thiserror::Error;
 use tokio::sync::mpsc as tokio_mpsc;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
+use versualizer_core::config::UiConfig;
 
 /// Errors that can occur during theme operations
 #[derive(Debug, Error)]
@@ -26,17 +28,16 @@ pub enum ThemeError {
     WatcherError(#[from] notify::Error),
 }
 
-/// Embedded default CSS template (compiled into the binary)
-const DEFAULT_CSS: &str = include_str!("../assets/default_theme.css");
-
-/// Initialize theme file, copying the embedded template if it doesn't exist.
-/// Returns the CSS content to use.
+/// Initialize theme file, copying the default template if it doesn't exist, and the
+/// directory for named themes. Returns the CSS content of theme.css.
 ///
 /// # Errors
 ///
 /// Returns an error if the config directory cannot be created or the file cannot be written.
 pub fn initialize_theme() -> Result<String, ThemeError> {
     let theme_path = versualizer_core::theme_path();
+    // Also creates the config directory
+    fs::create_dir_all(versualizer_core::themes_dir())?;
 
     if theme_path.exists() {
         // Load existing theme
@@ -49,28 +50,28 @@ pub fn initialize_theme() -> Result<String, ThemeError> {
             theme_path
         );
 
-        // Ensure config directory exists
-        let config_dir = versualizer_core::config_dir();
-        fs::create_dir_all(&config_dir)?;
-
-        // Write the embedded CSS template
-        fs::write(&theme_path, DEFAULT_CSS)?;
+        // Write the default CSS template (bundled or embedded)
+        let default_css = default_theme_css();
+        fs::write(&theme_path, &default_css)?;
 
-        Ok(DEFAULT_CSS.to_string())
+        Ok(default_css)
     }
 }
 
-/// Load CSS content from the theme file.
+/// Load CSS content from the theme called `name`, or from theme.css when it is empty.
 /// Falls back to embedded CSS if the file cannot be read.
 #[must_use]
-pub fn load_theme_css() -> String {
-    let theme_path = versualizer_core::theme_path();
+pub fn load_theme_css(name: &str) -> String {
+    let theme_path = versualizer_core::theme_path_named(name);
 
     match fs::read_to_string(&theme_path) {
         Ok(css) => css,
         Err(e) => {
-            warn!("Failed to read theme file, using embedded CSS: {}", e);
-            DEFAULT_CSS.to_string()
+            warn!(
+                "Failed to read theme file {:?}, using embedded CSS: {}",
+                theme_path, e
+            );
+            default_theme_css()
         }
     }
 }
@@ -80,27 +81,58 @@ pub fn load_theme_css() -> String {
 /// This hook:
 /// 1. Initializes the theme file on first run
 /// 2. Provides a `Signal<String>` with the current CSS content
-/// 3. Watches the theme file for changes and updates the signal
+/// 3. Watches the theme files for changes and updates the signal
+/// 4. Switches themes when `ui.theme` changes
 ///
 /// When the signal updates, the component re-renders and the `<style>` element
 /// in the RSX is updated with the new CSS content.
+///
+/// With `use_default`, the user's theme file is neither read nor watched and the
+/// default theme is used instead (safe mode).
 #[must_use]
-pub fn use_theme_watcher(cancel_token: CancellationToken) -> Signal<String> {
+pub fn use_theme_watcher(cancel_token: CancellationToken, use_default: bool) -> Signal<String> {
+    let ui_config = use_context::<Signal<UiConfig>>();
+    let theme = use_memo(move || ui_config.read().theme.clone());
+
     // Initialize CSS signal with current theme content
     let mut css_content = use_signal(|| {
-        initialize_theme().unwrap_or_else(|e| {
+        if use_default {
+            info!("Using the default theme");
+            return default_theme_css();
+        }
+        let css = initialize_theme().unwrap_or_else(|e| {
             error!("Failed to initialize theme: {}", e);
-            DEFAULT_CSS.to_string()
-        })
+            default_theme_css()
+        });
+        let name = theme.peek();
+        if name.is_empty() {
+            css
+        } else {
+            info!("Using the {:?} theme", *name);
+            load_theme_css(&name)
+        }
+    });
+
+    // Load the newly chosen theme when the config changes
+    let mut loaded_theme = use_signal(|| theme.peek().clone());
+    use_effect(move || {
+        let name = theme();
+        if use_default || name == *loaded_theme.peek() {
+            return;
+        }
+        info!("Switching to the {:?} theme", name);
+        css_content.set(load_theme_css(&name));
+        loaded_theme.set(name);
     });
 
     // Spawn the file watcher task
     use_effect(move || {
+        if use_default {
+            return;
+        }
         let cancel_token = cancel_token.clone();
 
         spawn(async move {
-            let theme_path = versualizer_core::theme_path();
-
             // Create a tokio channel for file watcher events
             // Using Arc to share the sender across threads
             let (tx, mut rx) = tokio_mpsc::channel::<()>(16);
@@ -127,20 +159,26 @@ pub fn use_theme_watcher(cancel_token: CancellationToken) -> Signal<String> {
                 }
             };
 
-            // Watch the theme file's parent directory (more reliable than watching the file directly)
-            let watch_path = theme_path
-                .parent()
-                .map_or_else(|| theme_path.clone(), PathBuf::from);
-
-            if let Err(e) = debouncer
-                .watcher()
-                .watch(&watch_path, RecursiveMode::NonRecursive)
-            {
-                error!("Failed to watch theme directory: {}", e);
-                return;
+            // Watch the directories of theme.css and the named themes (more reliable than
+            // watching the files directly), so edits to either kind are picked up
+            let theme_path = versualizer_core::theme_path();
+            let watch_paths = [
+                theme_path
+                    .parent()
+                    .map_or_else(|| theme_path.clone(), PathBuf::from),
+                versualizer_core::themes_dir(),
+            ];
+            for watch_path in &watch_paths {
+                if let Err(e) = debouncer
+                    .watcher()
+                    .watch(watch_path, RecursiveMode::NonRecursive)
+                {
+                    error!("Failed to watch theme directory {:?}: {}", watch_path, e);
+                    return;
+                }
             }
 
-            info!("Watching theme file for changes: {:?}", theme_path);
+            info!("Watching theme files for changes: {:?}", watch_paths);
 
             // Poll for file changes or cancellation
             loop {
@@ -151,7 +189,7 @@ pub fn use_theme_watcher(cancel_token: CancellationToken) -> Signal<String> {
                     }
                     Some(()) = rx.recv() => {
                         info!("Theme file changed, reloading CSS");
-                        let new_css = load_theme_css();
+                        let new_css = load_theme_css(&theme.peek());
                         css_content.set(new_css);
                     }
                 }
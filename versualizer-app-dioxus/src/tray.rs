@@ -0,0 +1,130 @@
+//! System tray icon with a menu of overlay actions.
+
+use crate::resources::{self, Resource};
+use dioxus::desktop::trayicon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use dioxus::desktop::trayicon::{Icon, init_tray_icon};
+use dioxus::desktop::use_tray_menu_event_handler;
+use dioxus::prelude::*;
+use tracing::error;
+
+/// Actions offered in the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    /// Save the current line as a PNG lyric card
+    SaveLyricCard,
+    /// Copy the current line as a PNG lyric card to the clipboard
+    CopyLyricCard,
+    /// Bookmark the current line
+    BookmarkLine,
+    /// Save the current song's lyrics as an `.lrc` file
+    ExportLyrics,
+    /// Translate the current song and show the translation under each line
+    TranslateSong,
+    /// Show the current song's lyrics from the next provider that has them
+    NextLyricsSource,
+    /// Open the picker for choosing the current song's lyrics by hand
+    WrongLyrics,
+    /// Move the overlay to the next display
+    NextDisplay,
+    /// Open the settings panel
+    Settings,
+    /// Quit the app
+    Quit,
+}
+
+impl TrayAction {
+    const ALL: [Self; 10] = [
+        Self::SaveLyricCard,
+        Self::CopyLyricCard,
+        Self::BookmarkLine,
+        Self::ExportLyrics,
+        Self::TranslateSong,
+        Self::NextLyricsSource,
+        Self::WrongLyrics,
+        Self::NextDisplay,
+        Self::Settings,
+        Self::Quit,
+    ];
+
+    /// Menu item ID
+    const fn id(self) -> &'static str {
+        match self {
+            Self::SaveLyricCard => "save-lyric-card",
+            Self::CopyLyricCard => "copy-lyric-card",
+            Self::BookmarkLine => "bookmark-line",
+            Self::ExportLyrics => "export-lyrics",
+            Self::TranslateSong => "translate-song",
+            Self::NextLyricsSource => "next-lyrics-source",
+            Self::WrongLyrics => "wrong-lyrics",
+            Self::NextDisplay => "next-display",
+            Self::Settings => "settings",
+            Self::Quit => "quit",
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::SaveLyricCard => "Save lyric card",
+            Self::CopyLyricCard => "Copy lyric card",
+            Self::BookmarkLine => "Bookmark line",
+            Self::ExportLyrics => "Export lyrics",
+            Self::TranslateSong => "Translate song",
+            Self::NextLyricsSource => "Next lyrics source",
+            Self::WrongLyrics => "Wrong lyrics?",
+            Self::NextDisplay => "Move to next display",
+            Self::Settings => "Settings",
+            Self::Quit => "Quit",
+        }
+    }
+
+    fn from_event(event: &MenuEvent) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| event.id.0 == action.id())
+    }
+}
+
+/// Show the tray icon and run `on_action` when one of its menu items is chosen
+pub fn use_tray_menu(mut on_action: impl FnMut(TrayAction) + 'static) {
+    use_hook(|| {
+        let menu = Menu::new();
+        for action in TrayAction::ALL {
+            if action == TrayAction::Quit {
+                let _ = menu.append(&PredefinedMenuItem::separator());
+            }
+            let item = MenuItem::with_id(action.id(), action.label(), true, None);
+            if let Err(e) = menu.append(&item) {
+                error!("Failed to add tray menu item: {}", e);
+            }
+        }
+        init_tray_icon(menu, load_tray_icon());
+    });
+
+    use_tray_menu_event_handler(move |event| {
+        if let Some(action) = TrayAction::from_event(event) {
+            on_action(action);
+        }
+    });
+}
+
+/// Load the tray icon from the app icon resource
+fn load_tray_icon() -> Option<Icon> {
+    let icon_bytes = resources::load(Resource::AppIcon);
+
+    let img = match image::load_from_memory(&icon_bytes) {
+        Ok(img) => img.into_rgba8(),
+        Err(e) => {
+            error!("Failed to load tray icon: {}", e);
+            return None;
+        }
+    };
+
+    let (width, height) = img.dimensions();
+    match Icon::from_rgba(img.into_raw(), width, height) {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            error!("Failed to create tray icon: {}", e);
+            None
+        }
+    }
+}
@@ -1,8 +1,15 @@
-//! Window state persistence for saving and restoring window position.
+//! Window state persistence for saving and restoring window position, and choosing the
+//! display the overlay is on.
 
+use dioxus::desktop::tao::dpi::{PhysicalPosition, PhysicalSize};
+use dioxus::desktop::tao::monitor::MonitorHandle;
+use dioxus::desktop::tao::window::Window;
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use tracing::{info, warn};
+use versualizer_core::{MonitorChoice, UiConfig};
 
 /// Persisted window state (position only, size is config-driven).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,9 +18,57 @@ pub struct WindowState {
     pub x: i32,
     /// Y position of the window's outer bounds
     pub y: i32,
+    /// The display the window was on (missing in state saved by older versions)
+    #[serde(default)]
+    pub monitor: Option<SavedMonitor>,
+}
+
+/// A display as it was when the window state was saved, to find it again on restore
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedMonitor {
+    /// Name as the OS reports it
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl SavedMonitor {
+    fn of(monitor: &MonitorHandle) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        Self {
+            name: monitor.name(),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    /// Whether `monitor` is this display: the same name, or the same bounds when the OS
+    /// does not name displays
+    fn matches(&self, monitor: &MonitorHandle) -> bool {
+        match (&self.name, monitor.name()) {
+            (Some(name), Some(other)) => *name == other,
+            _ => *self == Self::of(monitor),
+        }
+    }
 }
 
 impl WindowState {
+    /// Where `window` is now
+    #[must_use]
+    pub fn capture(window: &Window) -> Option<Self> {
+        let position = window.outer_position().ok()?;
+        Some(Self {
+            x: position.x,
+            y: position.y,
+            monitor: window.current_monitor().as_ref().map(SavedMonitor::of),
+        })
+    }
+
     /// Load window state from the cache file.
     /// Returns `None` if the file doesn't exist or can't be parsed.
     #[must_use]
@@ -67,4 +122,144 @@ impl WindowState {
             }
         }
     }
+
+    /// Offset of the window from the top-left corner of the display it was on, if that
+    /// display is still connected
+    fn offset_on(&self, monitors: &[MonitorHandle]) -> (Option<MonitorHandle>, (i32, i32)) {
+        let monitor = monitors
+            .iter()
+            .find(|monitor| match &self.monitor {
+                Some(saved) => saved.matches(monitor),
+                // Older state only has the position, so look for the display it is on
+                None => contains(monitor, self.x, self.y),
+            })
+            .cloned();
+        let origin = self.monitor.as_ref().map_or_else(
+            || {
+                monitor
+                    .as_ref()
+                    .map_or((0, 0), |m| (m.position().x, m.position().y))
+            },
+            |saved| (saved.x, saved.y),
+        );
+        (monitor, (self.x - origin.0, self.y - origin.1))
+    }
+}
+
+/// Keep the overlay on the display chosen with `[ui.window] monitor`, moving it when the
+/// choice changes. With `"auto"` the window stays where it was restored, unless its display
+/// has been disconnected since, in which case it moves to the primary display.
+pub fn use_window_placement() {
+    let window = use_window();
+    let ui_config = use_context::<Signal<UiConfig>>();
+    let choice = use_memo(move || ui_config.read().window.monitor_choice());
+    let mut saved = use_hook(WindowState::load);
+
+    use_effect(move || {
+        let choice = choice();
+        // The saved state places the window once; later changes start from where it is now
+        let from = saved.take().or_else(|| WindowState::capture(&window));
+        place_window(&window, &choice, from.as_ref());
+    });
+}
+
+/// Move `window` to the display chosen by `choice`, at the same offset from the display's
+/// corner as in `from`
+fn place_window(window: &Window, choice: &MonitorChoice, from: Option<&WindowState>) {
+    let monitors: Vec<MonitorHandle> = window.available_monitors().collect();
+    let primary = || {
+        window
+            .primary_monitor()
+            .or_else(|| monitors.first().cloned())
+    };
+    let (current, offset) = from.map_or((None, None), |state| {
+        let (monitor, offset) = state.offset_on(&monitors);
+        (monitor, Some(offset))
+    });
+
+    let target = match choice {
+        MonitorChoice::Auto if current.is_some() || from.is_none() => current,
+        MonitorChoice::Auto => {
+            info!("The overlay's display is disconnected, moving it to the primary display");
+            primary()
+        }
+        MonitorChoice::Primary => primary(),
+        MonitorChoice::Number(number) => {
+            let monitor = number
+                .checked_sub(1)
+                .and_then(|index| monitors.get(index))
+                .cloned();
+            if monitor.is_none() {
+                warn!(
+                    "There is no display {} ({} connected), using the primary display",
+                    number,
+                    monitors.len()
+                );
+            }
+            monitor.or_else(primary)
+        }
+        MonitorChoice::Name(name) => {
+            let monitor = monitors
+                .iter()
+                .find(|monitor| monitor.name().as_deref() == Some(name.as_str()))
+                .cloned();
+            if monitor.is_none() {
+                warn!("No display is named {:?}, using the primary display", name);
+            }
+            monitor.or_else(primary)
+        }
+    };
+
+    if let Some(target) = target {
+        move_to(window, &target, offset);
+    }
+}
+
+/// Move `window` to the display after the one it is on, at the same offset from its corner
+pub fn move_to_next_monitor(window: &Window) {
+    let monitors: Vec<MonitorHandle> = window.available_monitors().collect();
+    let Some(state) = WindowState::capture(window) else {
+        return;
+    };
+    let (current, offset) = state.offset_on(&monitors);
+    let next = current
+        .and_then(|current| monitors.iter().position(|monitor| *monitor == current))
+        .map_or(0, |index| (index + 1) % monitors.len().max(1));
+    if let Some(target) = monitors.get(next) {
+        info!("Moving the overlay to display {}", next + 1);
+        move_to(window, target, Some(offset));
+    }
+}
+
+/// Put `window` on `monitor` at `offset` from its top-left corner, kept inside the display.
+/// Without an offset the window is centered near the bottom.
+fn move_to(window: &Window, monitor: &MonitorHandle, offset: Option<(i32, i32)>) {
+    let origin = monitor.position();
+    let room = fit(monitor.size(), window.outer_size());
+    let (x, y) = offset.unwrap_or((room.0 / 2, room.1 * 9 / 10));
+    let position =
+        PhysicalPosition::new(origin.x + x.clamp(0, room.0), origin.y + y.clamp(0, room.1));
+    if window.outer_position().ok() != Some(position) {
+        window.set_outer_position(position);
+    }
+}
+
+/// How far a window of `window` size can move on a display of `display` size
+fn fit(display: PhysicalSize<u32>, window: PhysicalSize<u32>) -> (i32, i32) {
+    let room =
+        |display: u32, window: u32| i32::try_from(display.saturating_sub(window)).unwrap_or(0);
+    (
+        room(display.width, window.width),
+        room(display.height, window.height),
+    )
+}
+
+/// Whether a point is on `monitor`
+fn contains(monitor: &MonitorHandle, x: i32, y: i32) -> bool {
+    let origin = monitor.position();
+    let size = monitor.size();
+    let width = i32::try_from(size.width).unwrap_or(i32::MAX);
+    let height = i32::try_from(size.height).unwrap_or(i32::MAX);
+    (origin.x..origin.x.saturating_add(width)).contains(&x)
+        && (origin.y..origin.y.saturating_add(height)).contains(&y)
 }
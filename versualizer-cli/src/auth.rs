@@ -0,0 +1,102 @@
+//! `versualizer-cli auth spotify`: sign in to Spotify from the terminal.
+
+use crate::error::{CliError, Result};
+use chrono::{DateTime, Local};
+use pico_args::Arguments;
+use versualizer_spotify_api::{HeadlessSignIn, SpotifyOAuth, SpotifyProviderConfig};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli auth spotify [--force] [--no-browser]
+
+Sign in to Spotify with the client ID and secret in [providers.spotify], through the
+browser, and save the token where the app looks for it. A saved token that is still
+valid is reused, refreshing it if needed.
+
+With --no-browser, for machines without one, an address is printed to open on any other
+device. Sign in there, then paste the address the browser is sent back to (it fails to
+load unless that device can reach the callback, e.g. over an SSH port forward, in which
+case the sign-in completes by itself).
+
+Options:
+  --force       Sign in again even if a saved token is valid
+  --no-browser  Sign in from another device";
+
+/// Run an `auth` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, Spotify is not configured, or signing
+/// in fails.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    let command = args.subcommand()?;
+    let force = args.contains("--force");
+    let no_browser = args.contains("--no-browser");
+    if command.as_deref() != Some("spotify") {
+        return Err(CliError::Usage(USAGE.to_string()));
+    }
+    crate::reject_unused(args)?;
+
+    let config = crate::load_config()?;
+    let Some(spotify) = SpotifyProviderConfig::load(&config)? else {
+        return Err(CliError::Usage(
+            "Spotify is not configured in [providers.spotify]".to_string(),
+        ));
+    };
+    spotify.validate()?;
+    let oauth = SpotifyOAuth::new(
+        &spotify.client_id,
+        &spotify.client_secret,
+        &spotify.oauth_redirect_uri,
+    )?
+    .with_secret_store(config.lyrics.secret_store.open());
+    if no_browser {
+        if force || !oauth.load_cached_token().await? {
+            oauth
+                .authenticate_headless(print_sign_in, read_pasted_address())
+                .await?;
+        }
+    } else if force {
+        oauth.reauthenticate().await?;
+    } else {
+        oauth.ensure_authenticated().await?;
+    }
+
+    let state = oauth.auth_state().await;
+    match state.expires_at {
+        Some(expires_at) => println!(
+            "{} (token expires {})",
+            state.detail,
+            DateTime::<Local>::from(expires_at).format("%Y-%m-%d %H:%M")
+        ),
+        None => println!("{}", state.detail),
+    }
+    Ok(())
+}
+
+fn print_sign_in(sign_in: &HeadlessSignIn) {
+    println!(
+        "Open this address in a browser on any device and sign in:\n\n  {}\n",
+        sign_in.url
+    );
+    if let Some(callback) = &sign_in.callback {
+        println!("If that browser can reach {callback}, the sign-in finishes by itself.");
+    }
+    println!(
+        "Otherwise copy the full address from the browser's address bar after signing in \
+         (the page will fail to load) and paste it here:"
+    );
+}
+
+/// The first line typed on stdin, or `None` if stdin is closed
+async fn read_pasted_address() -> Option<String> {
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(read) if read > 0 => Some(line),
+            _ => None,
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
@@ -0,0 +1,40 @@
+//! `versualizer-cli backup`: snapshot the lyrics cache while the app runs.
+
+use crate::error::Result;
+use pico_args::Arguments;
+use std::path::PathBuf;
+use versualizer_core::paths::backups_dir;
+use versualizer_core::{CacheBackups, VersualizerConfig};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli backup [-o <file>]
+
+Copy the lyrics cache database (lyrics and bookmarks) to a backup file. This is safe
+while Versualizer is running. Without -o the backup goes into the backups folder next to
+config.toml, and the oldest backups beyond [cache] backup_keep are deleted.
+
+Options:
+  -o, --output <file>  Write the backup to this file instead";
+
+/// Run the `backup` command
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or the backup cannot be written.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    let output: Option<PathBuf> = args.opt_value_from_str(["-o", "--output"])?;
+    crate::reject_unused(args)?;
+
+    let cache = crate::open_cache().await?;
+    let path = if let Some(path) = output {
+        cache.backup_to(&path).await?;
+        path
+    } else {
+        let keep = VersualizerConfig::load_or_create(None)?.cache.backup_keep;
+        CacheBackups::new(backups_dir(), keep)
+            .create(&cache)
+            .await?
+    };
+    println!("Backed up the lyrics cache to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! `versualizer-cli bookmarks` subcommands.
+
+use crate::error::{CliError, Result};
+use chrono::Local;
+use pico_args::Arguments;
+use std::path::PathBuf;
+use versualizer_core::BookmarkFormat;
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli bookmarks list
+       versualizer-cli bookmarks export [--format <format>] [-o <file>]
+
+Show or export lines bookmarked with Ctrl+Shift+B in the overlay.
+
+Options:
+  --format <format>    markdown or json (default: markdown)
+  -o, --output <file>  Write the export to a file instead of stdout";
+
+/// Run a `bookmarks` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or the bookmarks cannot be read.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    match args.subcommand()?.as_deref() {
+        Some("list") => {
+            crate::reject_unused(args)?;
+            list().await
+        }
+        Some("export") => {
+            let format = match args.opt_value_from_str::<_, String>("--format")? {
+                Some(name) => BookmarkFormat::from_name(&name)
+                    .ok_or_else(|| CliError::Usage(format!("Unknown format: {name}")))?,
+                None => BookmarkFormat::Markdown,
+            };
+            let output: Option<PathBuf> = args.opt_value_from_str(["-o", "--output"])?;
+            crate::reject_unused(args)?;
+            export(format, output).await
+        }
+        _ => Err(CliError::Usage(USAGE.to_string())),
+    }
+}
+
+async fn list() -> Result<()> {
+    for bookmark in crate::open_cache().await?.bookmarks().await? {
+        println!(
+            "{} {} - {}: {}",
+            bookmark
+                .created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            bookmark.artist,
+            bookmark.track,
+            bookmark.text
+        );
+    }
+    Ok(())
+}
+
+async fn export(format: BookmarkFormat, output: Option<PathBuf>) -> Result<()> {
+    let bookmarks = crate::open_cache().await?.bookmarks().await?;
+    let rendered = format.render(&bookmarks)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            println!(
+                "Exported {} bookmarks to {}",
+                bookmarks.len(),
+                path.display()
+            );
+        }
+        None => println!("{rendered}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+//! `versualizer-cli cache` subcommands: inspect and prune cached lyrics, move them
+//! between machines, and manage the tracks and artists whose lyrics are never shown.
+
+use crate::error::{CliError, Result};
+use chrono::Local;
+use pico_args::Arguments;
+use std::path::PathBuf;
+use versualizer_core::BlockKind;
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli cache ls [<search>]
+       versualizer-cli cache rm <id>...
+       versualizer-cli cache stats
+       versualizer-cli cache export <file>
+       versualizer-cli cache import <file>
+       versualizer-cli cache blocked
+       versualizer-cli cache block (track <track id> | artist <name>)
+       versualizer-cli cache unblock (track <track id> | artist <name>)
+
+ls lists cached tracks with their ID, whether the lyrics are synced, and the provider
+they came from, optionally only those whose artist, title or album contains <search>.
+rm deletes cached tracks by the ID ls shows, so the next play fetches them again. stats
+counts what the cache holds.
+
+export writes the lyrics cache (lyrics, track ID mappings, translations, timing offsets
+and bookmarks) to a portable JSON archive, and import merges an archive into the cache.
+Archives are gzip-compressed when the file name ends in .gz. Content is exported
+decrypted, so keep archives of an encrypted cache somewhere safe.
+
+On import, cached lyrics are only replaced by newer ones from the archive, and bookmarks
+already in the cache are not duplicated.
+
+blocked lists the tracks and artists whose lyrics are never fetched or shown, as blocked
+with block or from the app's \"Wrong lyrics?\" picker. Tracks are blocked by their source
+track ID, and artists by name (case-insensitive). Entries of [lyrics.blocklist] in the
+config are not listed.";
+
+/// Run a `cache` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, the cache cannot be opened, or the
+/// archive cannot be written or read.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    match args.subcommand()?.as_deref() {
+        Some("ls") => list(args).await,
+        Some("rm") => remove(args).await,
+        Some("stats") => {
+            crate::reject_unused(args)?;
+            stats().await
+        }
+        Some("blocked") => {
+            crate::reject_unused(args)?;
+            blocked().await
+        }
+        Some(command @ ("block" | "unblock")) => {
+            let kind: Option<String> = args.opt_free_from_str()?;
+            let key: Option<String> = args.opt_free_from_str()?;
+            crate::reject_unused(args)?;
+            let (Some(kind), Some(key)) = (kind.as_deref().and_then(BlockKind::parse), key) else {
+                return Err(CliError::Usage(USAGE.to_string()));
+            };
+            let cache = crate::open_cache().await?;
+            if command == "block" {
+                cache.block(kind, &key, &key).await?;
+                println!("Blocked lyrics of {kind} {key}");
+            } else if cache.unblock(kind, &key).await? {
+                println!("Unblocked lyrics of {kind} {key}");
+            } else {
+                eprintln!("No blocked {kind} {key}");
+            }
+            Ok(())
+        }
+        Some(command @ ("export" | "import")) => {
+            let path: Option<PathBuf> = args.opt_free_from_str()?;
+            let Some(path) = path else {
+                return Err(CliError::Usage(USAGE.to_string()));
+            };
+            crate::reject_unused(args)?;
+            let cache = crate::open_cache().await?;
+            if command == "export" {
+                let summary = cache.export(&path).await?;
+                println!("Exported {summary} to {}", path.display());
+            } else {
+                let summary = cache.import(&path).await?;
+                println!("Imported {summary} from {}", path.display());
+            }
+            Ok(())
+        }
+        _ => Err(CliError::Usage(USAGE.to_string())),
+    }
+}
+
+async fn list(mut args: Arguments) -> Result<()> {
+    let search: Option<String> = args.opt_free_from_str()?;
+    crate::reject_unused(args)?;
+
+    let tracks = crate::open_cache().await?.list(search.as_deref()).await?;
+    for track in &tracks {
+        let album = track
+            .album
+            .as_deref()
+            .filter(|album| !album.is_empty())
+            .map(|album| format!(" [{album}]"))
+            .unwrap_or_default();
+        println!(
+            "{:>6}  {:<8}  {}  {:<12}  {} - {}{}",
+            track.id,
+            if track.synced { "synced" } else { "plain" },
+            track.fetched_at.with_timezone(&Local).format("%Y-%m-%d"),
+            track.provider,
+            track.artist,
+            track.track,
+            album
+        );
+    }
+    println!("{} cached track(s)", tracks.len());
+    Ok(())
+}
+
+async fn remove(mut args: Arguments) -> Result<()> {
+    let mut ids = Vec::new();
+    while let Some(id) = args.opt_free_from_str::<i64>()? {
+        ids.push(id);
+    }
+    crate::reject_unused(args)?;
+    if ids.is_empty() {
+        return Err(CliError::Usage(USAGE.to_string()));
+    }
+
+    let cache = crate::open_cache().await?;
+    for id in ids {
+        if cache.delete(id).await? {
+            println!("Deleted cached track {id}");
+        } else {
+            eprintln!("No cached track with ID {id}");
+        }
+    }
+    Ok(())
+}
+
+async fn blocked() -> Result<()> {
+    let entries = crate::open_cache().await?.blocked().await?;
+    for entry in &entries {
+        println!(
+            "{:<6}  {}  {:<24}  {}",
+            entry.kind.as_str(),
+            entry.created_at.with_timezone(&Local).format("%Y-%m-%d"),
+            entry.key,
+            entry.label
+        );
+    }
+    println!("{} blocked track(s) and artist(s)", entries.len());
+    Ok(())
+}
+
+async fn stats() -> Result<()> {
+    let stats = crate::open_cache().await?.stats().await?;
+    println!(
+        "Tracks:        {} ({} synced, {} plain)",
+        stats.tracks,
+        stats.synced,
+        stats.tracks - stats.synced
+    );
+    for (provider, count) in &stats.providers {
+        println!("  {provider:<12} {count}");
+    }
+    println!("Track IDs:     {}", stats.track_ids);
+    println!("Translations:  {}", stats.translations);
+    println!("Bookmarks:     {}", stats.bookmarks);
+    println!("Not found:     {}", stats.not_found);
+    println!("Size:          {} KiB", stats.size_bytes.div_ceil(1024));
+    Ok(())
+}
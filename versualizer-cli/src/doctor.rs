@@ -0,0 +1,153 @@
+//! `versualizer-cli doctor`: run the startup preflight checks and print the results.
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use versualizer_core::config::LyricsProviderType;
+use versualizer_core::{
+    CheckResult, CheckStatus, LyricsProvider, MusicSource, Preflight, VersualizerConfig,
+};
+use versualizer_lyrics_genius::{GENIUS_API_URL, GeniusProviderConfig};
+use versualizer_lyrics_lrclib::LRCLIB_API_URL;
+use versualizer_lyrics_musixmatch::MUSIXMATCH_API_URL;
+use versualizer_lyrics_plugin::PluginProvider;
+use versualizer_lyrics_spotify::SpotifyLyricsProvider;
+use versualizer_spotify_api::{SPOTIFY_API_URL, SpotifyOAuth, SpotifyProviderConfig};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli doctor [--offline]
+
+Check config validity, provider reachability, Spotify credentials, and lyrics cache
+integrity, and print a pass/fail table with fix hints. Exits non-zero if any check fails.
+
+Options:
+  --offline  Skip checks that need the network";
+
+/// Run the `doctor` command
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or any check fails.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    let offline = args.contains("--offline");
+    crate::reject_unused(args)?;
+
+    let preflight = checks(offline).await;
+    print!("{}", preflight.render_table());
+
+    let failed = preflight
+        .results()
+        .iter()
+        .filter(|result| result.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        return Err(CliError::ChecksFailed(failed));
+    }
+    Ok(())
+}
+
+async fn checks(offline: bool) -> Preflight {
+    let mut preflight = Preflight::new();
+    let Some(config) = preflight.check_config() else {
+        return preflight;
+    };
+
+    let spotify = match SpotifyProviderConfig::load(&config) {
+        Ok(spotify) => spotify,
+        Err(e) => {
+            preflight.push(CheckResult::fail("Spotify config", e.to_string()));
+            None
+        }
+    };
+    if config.music.source == MusicSource::Spotify {
+        preflight.push(
+            match spotify.as_ref().map(SpotifyProviderConfig::validate) {
+                Some(Ok(())) => CheckResult::pass("Spotify config", "providers.spotify"),
+                Some(Err(e)) => CheckResult::fail("Spotify config", e.to_string())
+                    .with_hint("Add your Spotify app credentials from developer.spotify.com"),
+                None => CheckResult::fail("Spotify config", "Missing [providers.spotify]")
+                    .with_hint("Add a [providers.spotify] section, or change music.source"),
+            },
+        );
+        preflight.push(SpotifyOAuth::check_cached_token(Some(
+            config.lyrics.secret_store.open().as_ref(),
+        )));
+    }
+    if config
+        .lyrics
+        .providers
+        .contains(&LyricsProviderType::Genius)
+    {
+        preflight.push(genius_config_check(&config));
+    }
+    for (name, path) in config.lyrics.plugin_paths() {
+        preflight.push(PluginProvider::new(name, path).check().await);
+    }
+
+    if !offline {
+        if config.music.source == MusicSource::Spotify {
+            preflight
+                .check_reachable("Spotify Web API", SPOTIFY_API_URL)
+                .await;
+        }
+        if config
+            .lyrics
+            .providers
+            .contains(&LyricsProviderType::Lrclib)
+        {
+            preflight.check_reachable("LRCLIB", LRCLIB_API_URL).await;
+        }
+        if config
+            .lyrics
+            .providers
+            .contains(&LyricsProviderType::Musixmatch)
+        {
+            preflight
+                .check_reachable("Musixmatch", MUSIXMATCH_API_URL)
+                .await;
+        }
+        if config
+            .lyrics
+            .providers
+            .contains(&LyricsProviderType::Genius)
+        {
+            preflight.check_reachable("Genius", GENIUS_API_URL).await;
+        }
+        let sp_dc = spotify
+            .as_ref()
+            .and_then(|spotify| spotify.sp_dc.clone().map(|sp_dc| (spotify, sp_dc)));
+        if config
+            .lyrics
+            .providers
+            .contains(&LyricsProviderType::SpotifyLyrics)
+            && let Some((spotify, sp_dc)) = sp_dc
+        {
+            match SpotifyLyricsProvider::new(
+                sp_dc,
+                spotify.secret_key_url.clone(),
+                &config.network.retry,
+            ) {
+                Ok(provider) => preflight.push(provider.check_sp_dc().await),
+                Err(e) => preflight.push(CheckResult::fail("Spotify sp_dc cookie", e.to_string())),
+            }
+        }
+    }
+
+    preflight
+        .check_cache(&config.cache.db_path(config.music.source))
+        .await;
+    preflight
+}
+
+/// Whether `[providers.genius]` is present and has an access token
+fn genius_config_check(config: &VersualizerConfig) -> CheckResult {
+    match GeniusProviderConfig::from_providers(&config.providers)
+        .and_then(|genius| genius.map(|genius| genius.validate()).transpose())
+    {
+        Ok(Some(())) => CheckResult::pass("Genius config", "providers.genius"),
+        Ok(None) => CheckResult::fail("Genius config", "Missing [providers.genius]").with_hint(
+            "Add a [providers.genius] section, or remove \"genius\" from lyrics.providers",
+        ),
+        Err(e) => CheckResult::fail("Genius config", e.to_string())
+            .with_hint("Create a client access token at https://genius.com/api-clients"),
+    }
+}
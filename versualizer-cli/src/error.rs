@@ -0,0 +1,31 @@
+use thiserror::Error;
+use versualizer_core::CoreError;
+use versualizer_spotify_api::SpotifyError;
+
+/// CLI error type for versualizer-cli.
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("Invalid arguments: {0}")]
+    Args(#[from] pico_args::Error),
+
+    #[error(transparent)]
+    Core(#[from] CoreError),
+
+    #[error(transparent)]
+    Spotify(#[from] SpotifyError),
+
+    #[error("No lyrics found for {0}")]
+    LyricsNotFound(String),
+
+    #[error("{0} check(s) failed")]
+    ChecksFailed(usize),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Convenience type alias for Results with `CliError`.
+pub type Result<T> = std::result::Result<T, CliError>;
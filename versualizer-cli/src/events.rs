@@ -0,0 +1,81 @@
+//! `versualizer-cli events` subcommands.
+
+use crate::error::{CliError, Result};
+use chrono::Local;
+use pico_args::Arguments;
+use std::time::Duration;
+use versualizer_core::{EventLog, LoggedEvent};
+
+/// Default number of events shown by `events tail`
+const DEFAULT_TAIL_LINES: usize = 50;
+
+/// How often `events tail --follow` checks for new events
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli events tail [-n <count>] [-f]
+
+Show recent sync events from the persistent event log.
+
+Options:
+  -n, --lines <count>  Number of events to show (default: 50)
+  -f, --follow         Keep printing new events as they are recorded";
+
+/// Run an `events` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or the event log cannot be read.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    match args.subcommand()?.as_deref() {
+        Some("tail") => {
+            let lines = args
+                .opt_value_from_str(["-n", "--lines"])?
+                .unwrap_or(DEFAULT_TAIL_LINES);
+            let follow = args.contains(["-f", "--follow"]);
+            crate::reject_unused(args)?;
+            tail(lines, follow).await
+        }
+        _ => Err(CliError::Usage(USAGE.to_string())),
+    }
+}
+
+async fn tail(lines: usize, follow: bool) -> Result<()> {
+    let path = versualizer_core::paths::event_log_db_path();
+    if !path.exists() {
+        return Err(CliError::Usage(format!(
+            "No event log found at {}. Is `event_log_size` set in the [logging] config?",
+            path.display()
+        )));
+    }
+
+    // Capacity only matters for writers, the CLI never appends
+    let log = EventLog::open(&path, usize::MAX).await?;
+
+    let mut last_id = 0;
+    for event in log.tail(lines).await? {
+        last_id = event.id;
+        print_event(&event);
+    }
+
+    if !follow {
+        return Ok(());
+    }
+    loop {
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+        for event in log.since(last_id).await? {
+            last_id = event.id;
+            print_event(&event);
+        }
+    }
+}
+
+fn print_event(event: &LoggedEvent) {
+    let time = event.recorded_at.with_timezone(&Local);
+    println!(
+        "{} {:<18} {}",
+        time.format("%Y-%m-%d %H:%M:%S%.3f"),
+        event.kind,
+        event.detail
+    );
+}
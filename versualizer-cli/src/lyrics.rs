@@ -0,0 +1,148 @@
+//! `versualizer-cli lyrics get` and `lyrics search`: look up a song's lyrics without the
+//! overlay, or list every provider's candidates and pick one by hand.
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+use versualizer_core::{LyricsCandidate, LyricsLookup, LyricsQuery, LyricsResult};
+use versualizer_providers::create_providers;
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli lyrics get <artist> <track> [options]
+       versualizer-cli lyrics search <artist> <track> [--pick <n>] [options]
+
+Look up the lyrics of a song the way the app does: the lyrics cache first, then the
+providers configured in [lyrics] providers, in order. Synced lyrics are printed as LRC,
+plain lyrics as text. For a track a provider reports as instrumental, only a note is
+printed to stderr. Lyrics found by a provider are added to the cache, so this also
+pre-populates it.
+
+`search` lists every provider's candidates for the song, numbered, best match first. With
+--pick, candidate <n> is fetched, printed and cached as the song's lyrics, pinned over what
+the providers matched automatically.
+
+Options:
+  --album <name>       Album of the song, for a closer match
+  --duration <secs>    Length of the song in seconds, for a closer match
+  --provider <name>    Only ask this provider, e.g. lrclib or musixmatch
+  --no-cache           Neither read nor write the lyrics cache
+  --pick <n>           Use search candidate <n> (search only)
+  --synced-only        Fail instead of printing plain lyrics
+  -v, --verbose        Log what each provider did to stderr";
+
+/// Run a `lyrics` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, the config or cache cannot be opened, or
+/// no lyrics are found.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    let command = args.subcommand()?;
+    let album: Option<String> = args.opt_value_from_str("--album")?;
+    let duration: Option<u32> = args.opt_value_from_str("--duration")?;
+    let provider: Option<String> = args.opt_value_from_str("--provider")?;
+    let pick: Option<usize> = args.opt_value_from_str("--pick")?;
+    let no_cache = args.contains("--no-cache");
+    let synced_only = args.contains("--synced-only");
+    let verbose = args.contains(["-v", "--verbose"]);
+    let artist: Option<String> = args.opt_free_from_str()?;
+    let track: Option<String> = args.opt_free_from_str()?;
+    let (Some(command @ ("get" | "search")), Some(artist), Some(track)) =
+        (command.as_deref(), artist, track)
+    else {
+        return Err(CliError::Usage(USAGE.to_string()));
+    };
+    let search = command == "search";
+    if pick.is_some() && !search {
+        return Err(CliError::Usage(USAGE.to_string()));
+    }
+    crate::reject_unused(args)?;
+
+    if verbose {
+        tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::new("info"))
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
+    let config = crate::load_config()?;
+    let mut providers = create_providers(&config);
+    if let Some(name) = &provider {
+        providers.retain(|p| p.name() == name);
+        if providers.is_empty() {
+            return Err(CliError::Usage(format!(
+                "Provider {name} is not configured in [lyrics] providers"
+            )));
+        }
+    }
+    let mut lookup = LyricsLookup::new(providers).with_unsynced(!synced_only);
+    if !no_cache {
+        lookup = lookup.with_cache(Arc::new(crate::open_config_cache(&config).await?));
+    }
+
+    let mut query = LyricsQuery::new(track.as_str(), artist.as_str());
+    if let Some(album) = album {
+        query = query.with_album(album);
+    }
+    if let Some(duration) = duration {
+        query = query.with_duration(duration);
+    }
+
+    let result = if search {
+        let candidates = lookup.search(&query).await;
+        if candidates.is_empty() {
+            return Err(CliError::LyricsNotFound(format!("{artist} - {track}")));
+        }
+        let Some(pick) = pick else {
+            print_candidates(&candidates);
+            return Ok(());
+        };
+        let candidate = pick
+            .checked_sub(1)
+            .and_then(|index| candidates.get(index))
+            .ok_or_else(|| {
+                CliError::Usage(format!("--pick must be from 1 to {}", candidates.len()))
+            })?;
+        lookup.fetch_candidate(&query, candidate).await?
+    } else {
+        lookup.fetch(&query).await
+    };
+
+    match result {
+        LyricsResult::Synced(lyrics) => print!("{lyrics}"),
+        LyricsResult::Unsynced(text) => println!("{text}"),
+        LyricsResult::Instrumental => eprintln!("{artist} - {track} is instrumental"),
+        LyricsResult::NotFound => {
+            return Err(CliError::LyricsNotFound(format!("{artist} - {track}")));
+        }
+    }
+    Ok(())
+}
+
+/// One numbered row per candidate, with its preview indented below
+fn print_candidates(candidates: &[LyricsCandidate]) {
+    for (number, candidate) in candidates.iter().enumerate() {
+        let duration = candidate
+            .duration_secs
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .map(|duration| {
+                let secs = duration.as_secs();
+                format!("  {}:{:02}", secs / 60, secs % 60)
+            })
+            .unwrap_or_default();
+        println!(
+            "{:>3}. [{}] {} - {}{}  {}",
+            number + 1,
+            candidate.provider,
+            candidate.artist,
+            candidate.title,
+            duration,
+            if candidate.synced { "synced" } else { "plain" }
+        );
+        if let Some(ref preview) = candidate.preview {
+            println!("       {preview}");
+        }
+    }
+}
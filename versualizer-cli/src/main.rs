@@ -0,0 +1,131 @@
+mod auth;
+mod backup;
+mod bookmarks;
+mod cache;
+mod doctor;
+mod error;
+mod events;
+mod lyrics;
+mod purge;
+mod secret;
+mod stats;
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use std::path::PathBuf;
+use versualizer_core::{ContentCipher, LyricsCache, VersualizerConfig, paths};
+
+const USAGE: &str = "\
+Usage: versualizer-cli [--config <path> | --profile <name>] <command>
+
+Commands:
+  auth spotify   Sign in to Spotify for playback polling
+  backup         Back up the lyrics cache, safe while the app is running
+  bookmarks      List or export bookmarked lyric lines as Markdown or JSON
+  cache          List, delete, count, export or import cached lyrics, or block tracks
+  doctor         Check config, provider reachability, credentials, and the lyrics cache
+  events tail    Show recent sync events from the persistent event log
+  lyrics get     Look up the lyrics of a song through the cache and providers
+  lyrics search  List every provider's lyrics for a song and pick the right one
+  purge          Delete cached lyrics, history, tokens, window state, and logs
+  secret         Keep Spotify credentials in the OS keyring instead of the config
+  stats          Summarize the latest listening session from the event log
+
+Options:
+  --config <path>   Use this config file instead of config.toml
+  --profile <name>  Use the config file of a named profile, profiles/<name>.toml
+
+Run `versualizer-cli <command> --help` for command-specific options.";
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Arguments::from_env()).await {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(mut args: Arguments) -> Result<()> {
+    let help = args.contains(["-h", "--help"]);
+    let config: Option<PathBuf> = args.opt_value_from_str("--config")?;
+    let profile: Option<String> = args.opt_value_from_str("--profile")?;
+    if let Some(path) = config.or_else(|| profile.map(|name| paths::profile_config_path(&name))) {
+        paths::set_config_path(path);
+    }
+
+    match args.subcommand()?.as_deref() {
+        Some("auth") if help => Err(CliError::Usage(auth::USAGE.to_string())),
+        Some("auth") => auth::run(args).await,
+        Some("backup") if help => Err(CliError::Usage(backup::USAGE.to_string())),
+        Some("backup") => backup::run(args).await,
+        Some("bookmarks") if help => Err(CliError::Usage(bookmarks::USAGE.to_string())),
+        Some("bookmarks") => bookmarks::run(args).await,
+        Some("cache") if help => Err(CliError::Usage(cache::USAGE.to_string())),
+        Some("cache") => cache::run(args).await,
+        Some("doctor") if help => Err(CliError::Usage(doctor::USAGE.to_string())),
+        Some("doctor") => doctor::run(args).await,
+        Some("events") if help => Err(CliError::Usage(events::USAGE.to_string())),
+        Some("events") => events::run(args).await,
+        Some("lyrics") if help => Err(CliError::Usage(lyrics::USAGE.to_string())),
+        Some("lyrics") => lyrics::run(args).await,
+        Some("purge") if help => Err(CliError::Usage(purge::USAGE.to_string())),
+        Some("purge") => purge::run(args).await,
+        Some("secret") if help => Err(CliError::Usage(secret::USAGE.to_string())),
+        Some("secret") => secret::run(args),
+        Some("stats") if help => Err(CliError::Usage(stats::USAGE.to_string())),
+        Some("stats") => stats::run(args).await,
+        _ => Err(CliError::Usage(USAGE.to_string())),
+    }
+}
+
+/// Fail on any arguments a subcommand did not consume
+fn reject_unused(args: Arguments) -> Result<()> {
+    let remaining = args.finish();
+    if remaining.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Usage(format!(
+            "Unexpected arguments: {}",
+            remaining
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ")
+        )))
+    }
+}
+
+/// Load the app's config, which has to exist already
+fn load_config() -> Result<VersualizerConfig> {
+    let config_path = VersualizerConfig::config_path();
+    if !config_path.exists() {
+        return Err(CliError::Usage(format!(
+            "No config found at {}. Start Versualizer once to create it.",
+            config_path.display()
+        )));
+    }
+    Ok(VersualizerConfig::load_or_create(None)?)
+}
+
+/// Open the lyrics cache the app uses, with its encryption key if encryption is enabled
+async fn open_cache() -> Result<LyricsCache> {
+    let config = load_config()?;
+    let cache_path = config.cache.db_path(config.music.source);
+    if !cache_path.exists() {
+        return Err(CliError::Usage(format!(
+            "No lyrics cache found at {}",
+            cache_path.display()
+        )));
+    }
+    open_config_cache(&config).await
+}
+
+/// Open the lyrics cache of `config`, creating it if needed
+async fn open_config_cache(config: &VersualizerConfig) -> Result<LyricsCache> {
+    let cache = LyricsCache::open(&config.cache.db_path(config.music.source)).await?;
+    if !config.lyrics.encrypt_cache {
+        return Ok(cache);
+    }
+    let secret_store = config.lyrics.secret_store.open();
+    Ok(cache.with_encryption(ContentCipher::load_or_create(secret_store.as_ref())?))
+}
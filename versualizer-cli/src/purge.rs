@@ -0,0 +1,123 @@
+//! `versualizer-cli purge`: delete locally stored user data.
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use std::io::Write;
+use versualizer_core::encryption::delete_keyring_cache_key;
+use versualizer_core::paths::{DataCategory, DataPath, user_data_paths};
+use versualizer_lyrics_musixmatch::musixmatch_data_paths;
+use versualizer_spotify_api::{delete_keyring_token, spotify_data_paths};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli purge (--all | <categories>...) [--dry-run] [-y]
+       versualizer-cli purge --not-found
+
+Delete data Versualizer stores on this machine. Config and theme files are kept.
+
+Categories:
+  --cache         Cached lyrics database (with bookmarks), its backups and encryption key
+  --history       Event log and recorded session traces
+  --tokens        Spotify and Musixmatch tokens, including the Spotify token in the keyring
+  --window-state  Saved window position
+  --logs          Log files
+
+Options:
+  --all           Delete every category above
+  --dry-run       List what would be deleted without deleting anything
+  -y, --yes       Skip the confirmation prompt
+  --not-found     Only forget which tracks had no lyrics, so they are fetched again";
+
+/// Run the `purge` command
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or a path cannot be deleted.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    if args.contains("--not-found") {
+        crate::reject_unused(args)?;
+        let cleared = crate::open_cache().await?.clear_not_found().await?;
+        println!("Forgot {cleared} tracks without lyrics.");
+        return Ok(());
+    }
+
+    let all = args.contains("--all");
+    let flags = [
+        (DataCategory::Cache, args.contains("--cache")),
+        (DataCategory::History, args.contains("--history")),
+        (DataCategory::Tokens, args.contains("--tokens")),
+        (DataCategory::WindowState, args.contains("--window-state")),
+        (DataCategory::Logs, args.contains("--logs")),
+    ];
+    let dry_run = args.contains("--dry-run");
+    let yes = args.contains(["-y", "--yes"]);
+    crate::reject_unused(args)?;
+
+    let categories: Vec<DataCategory> = flags
+        .into_iter()
+        .filter_map(|(category, selected)| (all || selected).then_some(category))
+        .collect();
+    if categories.is_empty() {
+        return Err(CliError::Usage(USAGE.to_string()));
+    }
+
+    let targets: Vec<DataPath> = user_data_paths()
+        .into_iter()
+        .chain(spotify_data_paths())
+        .chain(musixmatch_data_paths())
+        .filter(|p| categories.contains(&p.category) && p.path.exists())
+        .collect();
+
+    if targets.is_empty() {
+        println!("Nothing to delete.");
+        return Ok(());
+    }
+
+    for target in &targets {
+        println!("  [{}] {}", target.category.label(), target.path.display());
+    }
+    if dry_run {
+        return Ok(());
+    }
+    if !yes && !confirm(targets.len())? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for target in &targets {
+        if let Err(e) = target.remove() {
+            eprintln!("Failed to delete {}: {e}", target.path.display());
+            failed += 1;
+        }
+    }
+    if categories.contains(&DataCategory::Cache)
+        && let Err(e) = delete_keyring_cache_key()
+    {
+        eprintln!("Failed to delete the cache key from the OS keyring: {e}");
+        failed += 1;
+    }
+    if categories.contains(&DataCategory::Tokens)
+        && let Err(e) = delete_keyring_token()
+    {
+        eprintln!("Failed to delete the Spotify token from the OS keyring: {e}");
+        failed += 1;
+    }
+
+    if failed > 0 {
+        return Err(CliError::Usage(format!(
+            "{failed} of {} paths could not be deleted",
+            targets.len()
+        )));
+    }
+    println!("Deleted {} paths.", targets.len());
+    Ok(())
+}
+
+fn confirm(count: usize) -> Result<bool> {
+    print!("Permanently delete these {count} paths? Type 'yes' to continue: ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("yes"))
+}
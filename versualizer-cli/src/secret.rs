@@ -0,0 +1,58 @@
+//! `versualizer-cli secret`: keep credentials in the secret store instead of the config file.
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use versualizer_spotify_api::config::{CLIENT_SECRET_NAME, SP_DC_SECRET_NAME};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli secret set <name>
+       versualizer-cli secret rm <name>
+
+Keep a credential in [lyrics] secret_store (the OS keyring unless it is set to \"file\")
+rather than in plaintext in config.toml. set reads the value from one line on stdin, so
+it stays out of the shell history; rm deletes it from the store.
+
+Names:
+  spotify.client_secret  Read when client_secret in [providers.spotify] is empty
+  spotify.sp_dc          Read when sp_dc in [providers.spotify] is set to \"\"
+
+Values in config.toml take precedence, so empty them there after storing them.";
+
+/// Credentials the config falls back to the secret store for
+const NAMES: [&str; 2] = [CLIENT_SECRET_NAME, SP_DC_SECRET_NAME];
+
+/// Run a `secret` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, no value is given, or the secret store
+/// cannot be written.
+pub fn run(mut args: Arguments) -> Result<()> {
+    let command = args.subcommand()?;
+    let name: Option<String> = args.opt_free_from_str()?;
+    crate::reject_unused(args)?;
+    let Some(name) = name.filter(|name| NAMES.contains(&name.as_str())) else {
+        return Err(CliError::Usage(USAGE.to_string()));
+    };
+
+    let store = crate::load_config()?.lyrics.secret_store.open();
+    match command.as_deref() {
+        Some("set") => {
+            eprintln!("Enter {name}:");
+            let mut value = String::new();
+            std::io::stdin().read_line(&mut value)?;
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(CliError::Usage(format!("No value given for {name}")));
+            }
+            store.set(&name, value)?;
+            println!("Stored {name} in the secret store");
+        }
+        Some("rm") => {
+            store.delete(&name)?;
+            println!("Deleted {name} from the secret store");
+        }
+        _ => return Err(CliError::Usage(USAGE.to_string())),
+    }
+    Ok(())
+}
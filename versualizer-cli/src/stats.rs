@@ -0,0 +1,42 @@
+//! `versualizer-cli stats` subcommand.
+
+use crate::error::{CliError, Result};
+use pico_args::Arguments;
+use versualizer_core::{EventLog, SessionStats};
+
+pub const USAGE: &str = "\
+Usage: versualizer-cli stats [--all]
+
+Summarize the latest listening session from the persistent event log: tracks played,
+synced lyrics hit rate, sing-along time, and the provider that found the most lyrics.
+
+Options:
+  --all  Summarize every event still in the log instead of the latest session";
+
+/// Run the `stats` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or the event log cannot be read.
+pub async fn run(mut args: Arguments) -> Result<()> {
+    let all = args.contains("--all");
+    crate::reject_unused(args)?;
+
+    let path = versualizer_core::paths::event_log_db_path();
+    if !path.exists() {
+        return Err(CliError::Usage(format!(
+            "No event log found at {}. Is `event_log_size` set in the [logging] config?",
+            path.display()
+        )));
+    }
+
+    // Capacity only matters for writers, the CLI never appends
+    let log = EventLog::open(&path, usize::MAX).await?;
+    let events = if all {
+        log.tail(usize::MAX).await?
+    } else {
+        log.last_session().await?
+    };
+    println!("{}", SessionStats::from_events(&events));
+    Ok(())
+}
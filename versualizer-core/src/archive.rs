@@ -0,0 +1,236 @@
+//! Portable archives of the lyrics cache.
+//!
+//! [`LyricsCache::export`](crate::LyricsCache::export) writes every cached lyrics entry with
+//! its provider track ID mappings and translations, the per-track timing offsets, and the
+//! bookmarks into a JSON archive, and [`LyricsCache::import`](crate::LyricsCache::import)
+//! merges one into another cache. Unlike a backup, an archive holds plaintext content and no
+//! internal row IDs, so it can be moved between machines, encryption keys and schema
+//! versions, or shared as a curated lyric set. Archives whose file name ends in `.gz` are
+//! gzip-compressed.
+
+use crate::error::{CoreError, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Archive format version written by this release
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// First bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Contents of a cache archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheArchive {
+    /// Format version, see [`ARCHIVE_VERSION`]
+    pub version: u32,
+    /// When the archive was written (Unix seconds)
+    pub exported_at: i64,
+    pub lyrics: Vec<ArchivedLyrics>,
+    #[serde(default)]
+    pub offsets: Vec<ArchivedOffset>,
+    #[serde(default)]
+    pub bookmarks: Vec<ArchivedBookmark>,
+}
+
+/// A cached lyrics entry with everything that refers to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedLyrics {
+    pub artist: String,
+    pub track: String,
+    pub album: Option<String>,
+    pub duration_ms: Option<i64>,
+    /// Lyrics provider the entry came from
+    pub provider: String,
+    /// The lyrics provider's ID for the entry
+    pub provider_id: String,
+    /// `synced` or `unsynced`
+    pub lyrics_type: String,
+    /// LRC text for synced lyrics, plain text otherwise
+    pub content: String,
+    pub fetched_at: i64,
+    /// Music source track IDs that resolve to this entry
+    #[serde(default)]
+    pub mappings: Vec<ArchivedMapping>,
+    #[serde(default)]
+    pub translations: Vec<ArchivedTranslation>,
+}
+
+/// A music source track ID mapped to a lyrics entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedMapping {
+    pub provider: String,
+    pub provider_track_id: String,
+}
+
+/// A translation of a lyrics entry, one line per lyrics line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedTranslation {
+    pub target_lang: String,
+    pub content: String,
+    pub fetched_at: i64,
+}
+
+/// A per-track lyrics timing offset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedOffset {
+    pub provider: String,
+    pub provider_track_id: String,
+    pub offset_ms: i64,
+}
+
+/// A bookmarked lyric line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedBookmark {
+    pub artist: String,
+    pub track: String,
+    pub album: Option<String>,
+    pub position_ms: i64,
+    pub text: String,
+    pub created_at: i64,
+}
+
+/// Number of entries exported or imported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub lyrics: usize,
+    pub offsets: usize,
+    pub bookmarks: usize,
+}
+
+impl fmt::Display for ArchiveSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lyrics, {} timing offsets, {} bookmarks",
+            self.lyrics, self.offsets, self.bookmarks
+        )
+    }
+}
+
+impl CacheArchive {
+    /// Write the archive to `path`, gzip-compressed if the file name ends in `.gz`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be serialized or written.
+    pub async fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)?;
+        let bytes = if path.extension().is_some_and(|ext| ext == "gz") {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&json)?;
+            encoder.finish()?
+        } else {
+            json
+        };
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, bytes).await?;
+        Ok(())
+    }
+
+    /// Read an archive from `path`, plain or gzip-compressed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a cache archive, or was written
+    /// by a newer release.
+    pub async fn read(path: &Path) -> Result<Self> {
+        let bytes = tokio::fs::read(path).await?;
+        let json = if bytes.starts_with(&GZIP_MAGIC) {
+            let mut json = Vec::new();
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut json)?;
+            json
+        } else {
+            bytes
+        };
+        let archive: Self = serde_json::from_slice(&json)?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(CoreError::CacheArchiveTooNew {
+                found: archive.version,
+                supported: ARCHIVE_VERSION,
+            });
+        }
+        Ok(archive)
+    }
+
+    /// Number of entries in the archive
+    #[must_use]
+    pub fn summary(&self) -> ArchiveSummary {
+        ArchiveSummary {
+            lyrics: self.lyrics.len(),
+            offsets: self.offsets.len(),
+            bookmarks: self.bookmarks.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn archive() -> CacheArchive {
+        CacheArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: 1_700_000_000,
+            lyrics: vec![ArchivedLyrics {
+                artist: "Artist".to_string(),
+                track: "Song".to_string(),
+                album: None,
+                duration_ms: Some(180_000),
+                provider: "lrclib".to_string(),
+                provider_id: "1".to_string(),
+                lyrics_type: "synced".to_string(),
+                content: "[00:01.00]Hello\n".to_string(),
+                fetched_at: 1_700_000_000,
+                mappings: vec![ArchivedMapping {
+                    provider: "spotify".to_string(),
+                    provider_track_id: "t1".to_string(),
+                }],
+                translations: Vec::new(),
+            }],
+            offsets: Vec::new(),
+            bookmarks: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read_plain_and_gzip() {
+        let dir =
+            std::env::temp_dir().join(format!("versualizer-archive-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        for name in ["cache.json", "cache.json.gz"] {
+            let path = dir.join(name);
+            archive().write(&path).await.unwrap();
+            assert_eq!(CacheArchive::read(&path).await.unwrap(), archive());
+        }
+        let compressed = std::fs::read(dir.join("cache.json.gz")).unwrap();
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_rejects_newer_version() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-archive-newer-{}.json",
+            std::process::id()
+        ));
+        let mut newer = archive();
+        newer.version = ARCHIVE_VERSION + 1;
+        newer.write(&path).await.unwrap();
+
+        assert!(matches!(
+            CacheArchive::read(&path).await,
+            Err(CoreError::CacheArchiveTooNew { .. })
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+}
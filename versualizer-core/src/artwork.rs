@@ -0,0 +1,206 @@
+//! Album art downloads, kept in the cache directory so each cover is downloaded once.
+//!
+//! [`ArtworkCache`] stores covers under a hash of their URL, which music sources keep stable
+//! per album, and drops the oldest downloads beyond [`MAX_CACHED_ARTWORK`].
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use base64::Engine as _;
+use reqwest_middleware::ClientWithMiddleware;
+use ring::digest::{SHA256, digest};
+use tracing::{debug, warn};
+
+use crate::config::RetryConfig;
+use crate::error::{CoreError, Result};
+use crate::http::{self, HttpLimits};
+use crate::paths;
+
+/// Most covers kept in the cache directory
+pub const MAX_CACHED_ARTWORK: usize = 200;
+
+/// A downloaded cover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artwork {
+    /// Image type, e.g. `image/jpeg`
+    pub mime: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl Artwork {
+    /// The image as a `data:` URL, for showing it without another request
+    #[must_use]
+    pub fn data_url(&self) -> String {
+        format!(
+            "data:{};base64,{}",
+            self.mime,
+            base64::engine::general_purpose::STANDARD.encode(&self.data)
+        )
+    }
+
+    /// Recognize a cached image by its leading bytes
+    fn from_bytes(data: Vec<u8>) -> Option<Self> {
+        let mime = if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            "image/jpeg"
+        } else if data.starts_with(b"\x89PNG") {
+            "image/png"
+        } else if data.starts_with(b"GIF8") {
+            "image/gif"
+        } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            "image/webp"
+        } else {
+            return None;
+        };
+        Some(Self { mime, data })
+    }
+}
+
+/// Downloads album art and keeps it on disk
+pub struct ArtworkCache {
+    dir: PathBuf,
+    client: ClientWithMiddleware,
+}
+
+impl ArtworkCache {
+    /// Create a cache in the default directory (`{cache_dir}/versualizer/artwork/`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(retry: &RetryConfig) -> Result<Self> {
+        let base_client = http::base_client(
+            reqwest::Client::builder().user_agent(http::USER_AGENT),
+            &HttpLimits::IMAGE,
+        )?;
+        Ok(Self {
+            dir: paths::artwork_dir(),
+            client: http::provider_client(base_client, HttpLimits::IMAGE, retry),
+        })
+    }
+
+    /// Keep covers in `dir` instead
+    #[must_use]
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// The cover at `url`, from the cache or downloaded into it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cover is not cached and cannot be downloaded, or is not an
+    /// image.
+    pub async fn get(&self, url: &str) -> Result<Artwork> {
+        let path = self.path_for(url);
+        if let Ok(data) = tokio::fs::read(&path).await
+            && let Some(artwork) = Artwork::from_bytes(data)
+        {
+            debug!("Album art cached at {:?}", path);
+            return Ok(artwork);
+        }
+
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let artwork = Artwork::from_bytes(http::read_body(response).await?).ok_or_else(|| {
+            CoreError::UnexpectedContentType {
+                url: url.to_string(),
+                content_type: "unrecognized image".to_string(),
+            }
+        })?;
+
+        if let Err(e) = tokio::fs::create_dir_all(&self.dir).await {
+            warn!("Failed to create album art cache: {}", e);
+            return Ok(artwork);
+        }
+        if let Err(e) = tokio::fs::write(&path, &artwork.data).await {
+            warn!("Failed to cache album art: {}", e);
+        }
+        let dir = self.dir.clone();
+        tokio::task::spawn_blocking(move || prune(&dir, MAX_CACHED_ARTWORK));
+        Ok(artwork)
+    }
+
+    /// Where the cover at `url` is cached
+    fn path_for(&self, url: &str) -> PathBuf {
+        let hash = digest(&SHA256, url.as_bytes());
+        let mut name = String::with_capacity(32);
+        for byte in &hash.as_ref()[..16] {
+            let _ = write!(name, "{byte:02x}");
+        }
+        self.dir.join(name)
+    }
+}
+
+/// Delete the least recently written covers in `dir` beyond the newest `keep`
+fn prune(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    if files.len() <= keep {
+        return;
+    }
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in files.split_off(keep) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to delete cached album art {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognizes_images() {
+        let jpeg = Artwork::from_bytes(vec![0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
+        assert_eq!(jpeg.mime, "image/jpeg");
+        assert_eq!(jpeg.data_url(), "data:image/jpeg;base64,/9j/4A==");
+        let webp = Artwork::from_bytes(b"RIFF\0\0\0\0WEBPVP8 ".to_vec()).unwrap();
+        assert_eq!(webp.mime, "image/webp");
+        assert!(Artwork::from_bytes(b"<html>".to_vec()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cached_cover_is_not_downloaded() {
+        let dir = std::env::temp_dir().join(format!("versualizer-artwork-{}", std::process::id()));
+        let retry = RetryConfig {
+            max_retries: 0,
+            ..RetryConfig::default()
+        };
+        let cache = ArtworkCache::new(&retry).unwrap().with_dir(&dir);
+        // Nothing listens on this address, so only the cached file can answer
+        let url = "http://127.0.0.1:9/cover.png";
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(cache.path_for(url), b"\x89PNG\r\n").unwrap();
+
+        let artwork = cache.get(url).await.unwrap();
+        assert_eq!(artwork.mime, "image/png");
+        assert!(cache.get("http://127.0.0.1:9/other.png").await.is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_prune_keeps_newest() {
+        let dir =
+            std::env::temp_dir().join(format!("versualizer-artwork-prune-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.join(name), name).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        prune(&dir, 2);
+        assert!(!dir.join("a").exists());
+        assert!(dir.join("b").exists() && dir.join("c").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,170 @@
+//! Audio features of a track: tempo, energy, beats, bars and song sections.
+//!
+//! Music sources that know these (currently Spotify) publish them for the current track.
+//! Consumers look them up through [`TrackAudioFeatures`] so features published for a
+//! previous track are never applied to the current one.
+
+use crate::playback::TrackInfo;
+use crate::tempo::Tempo;
+use std::time::Duration;
+
+/// Audio features of one track
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFeatures {
+    /// Source track ID these features belong to
+    pub source_track_id: String,
+    /// Tempo, if known and plausible
+    pub tempo: Option<Tempo>,
+    /// Perceived intensity from 0.0 (calm) to 1.0 (energetic)
+    pub energy: Option<f32>,
+    /// Song sections (verse, chorus, bridge, ...) in playback order
+    pub sections: Vec<Section>,
+    /// Start of each beat, in playback order
+    pub beats: Vec<Duration>,
+    /// Start of each bar, in playback order
+    pub bars: Vec<Duration>,
+}
+
+impl AudioFeatures {
+    /// Empty features for a track, to be filled in field by field
+    #[must_use]
+    pub fn new(source_track_id: impl Into<String>) -> Self {
+        Self {
+            source_track_id: source_track_id.into(),
+            tempo: None,
+            energy: None,
+            sections: Vec::new(),
+            beats: Vec::new(),
+            bars: Vec::new(),
+        }
+    }
+
+    /// Whether nothing is known about the track
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tempo.is_none()
+            && self.energy.is_none()
+            && self.sections.is_empty()
+            && self.beats.is_empty()
+    }
+
+    /// Index of the beat playing at `position`, `None` before the first beat
+    #[must_use]
+    pub fn beat_index_at(&self, position: Duration) -> Option<usize> {
+        self.beats
+            .partition_point(|&start| start <= position)
+            .checked_sub(1)
+    }
+
+    /// The section playing at `position`
+    #[must_use]
+    pub fn section_at(&self, position: Duration) -> Option<&Section> {
+        self.sections
+            .iter()
+            .take_while(|section| section.start <= position)
+            .last()
+            .filter(|section| position < section.end())
+    }
+}
+
+/// A section of a track with a consistent feel, such as a verse or chorus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Section {
+    /// Start of the section
+    pub start: Duration,
+    /// Length of the section
+    pub duration: Duration,
+    /// Average loudness in decibels (typically -60.0 to 0.0)
+    pub loudness: f32,
+}
+
+impl Section {
+    /// End of the section
+    #[must_use]
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+}
+
+/// Audio features lookup on [`TrackInfo`]
+pub trait TrackAudioFeatures {
+    /// `features` if they were published for this track
+    fn audio_features<'a>(&self, features: Option<&'a AudioFeatures>) -> Option<&'a AudioFeatures>;
+}
+
+impl TrackAudioFeatures for TrackInfo {
+    fn audio_features<'a>(&self, features: Option<&'a AudioFeatures>) -> Option<&'a AudioFeatures> {
+        features.filter(|features| features.source_track_id == self.source_track_id)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+
+    fn section(start_secs: u64, duration_secs: u64) -> Section {
+        Section {
+            start: Duration::from_secs(start_secs),
+            duration: Duration::from_secs(duration_secs),
+            loudness: -8.0,
+        }
+    }
+
+    #[test]
+    fn test_section_at() {
+        let mut features = AudioFeatures::new("track123");
+        features.sections = vec![section(0, 10), section(10, 20), section(30, 15)];
+
+        assert_eq!(
+            features.section_at(Duration::ZERO).unwrap().start,
+            Duration::ZERO
+        );
+        assert_eq!(
+            features.section_at(Duration::from_secs(10)).unwrap().start,
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            features.section_at(Duration::from_secs(44)).unwrap().start,
+            Duration::from_secs(30)
+        );
+        assert!(features.section_at(Duration::from_secs(45)).is_none());
+    }
+
+    #[test]
+    fn test_beat_index_at() {
+        let mut features = AudioFeatures::new("track123");
+        features.beats = [500, 1000, 1500]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+
+        assert_eq!(features.beat_index_at(Duration::from_millis(499)), None);
+        assert_eq!(features.beat_index_at(Duration::from_millis(500)), Some(0));
+        assert_eq!(features.beat_index_at(Duration::from_millis(1200)), Some(1));
+        assert_eq!(features.beat_index_at(Duration::from_mins(1)), Some(2));
+        assert!(!features.is_empty());
+    }
+
+    #[test]
+    fn test_audio_features_only_for_their_track() {
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "track123",
+            "Song",
+            "Artist",
+            "Album",
+            Duration::from_mins(3),
+        );
+        let mut features = AudioFeatures::new("track123");
+        features.tempo = Tempo::new(120.0);
+        let other = AudioFeatures::new("track456");
+
+        assert!(track.audio_features(Some(&features)).is_some());
+        assert!(track.audio_features(Some(&other)).is_none());
+        assert!(track.audio_features(None).is_none());
+        assert!(other.is_empty());
+        assert!(!features.is_empty());
+    }
+}
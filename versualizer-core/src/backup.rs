@@ -0,0 +1,178 @@
+//! Snapshots of the lyrics cache database.
+//!
+//! [`CacheBackups`] writes timestamped copies of the cache (lyrics, bookmarks and everything
+//! else in it) into a backups folder with [`LyricsCache::backup_to`], keeping only the newest
+//! few. Backups can be taken on demand or on a timer while the app runs.
+
+use crate::cache::LyricsCache;
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// File name prefix of cache backups
+const BACKUP_FILE_PREFIX: &str = "lyrics_cache-";
+/// File extension of cache backups
+const BACKUP_FILE_EXTENSION: &str = "db";
+
+/// Folder of cache backups, keeping at most `keep` of them
+#[derive(Debug, Clone)]
+pub struct CacheBackups {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl CacheBackups {
+    /// Write backups into `dir`, deleting the oldest once there are more than `keep`.
+    /// A `keep` of 0 keeps every backup.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            keep,
+        }
+    }
+
+    /// The backups folder
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Back up `cache` to a new timestamped file and prune old backups.
+    /// Returns the path of the new backup.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backup cannot be written or old backups cannot be deleted.
+    pub async fn create(&self, cache: &LyricsCache) -> Result<PathBuf> {
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let path = self.dir.join(format!(
+            "{BACKUP_FILE_PREFIX}{timestamp}.{BACKUP_FILE_EXTENSION}"
+        ));
+        cache.backup_to(&path).await?;
+        self.prune().await?;
+        Ok(path)
+    }
+
+    /// Existing backups, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backups folder cannot be read.
+    pub async fn list(&self) -> Result<Vec<PathBuf>> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Backup names embed a sortable timestamp, so name order is age order
+        let mut backups = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_backup = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(BACKUP_FILE_PREFIX))
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == BACKUP_FILE_EXTENSION);
+            if is_backup {
+                backups.push(path);
+            }
+        }
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Delete the oldest backups so that at most `keep` remain.
+    /// Returns the number of deleted backups.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder cannot be read or a backup cannot be deleted.
+    pub async fn prune(&self) -> Result<usize> {
+        if self.keep == 0 {
+            return Ok(0);
+        }
+        let backups = self.list().await?;
+        let excess = backups.len().saturating_sub(self.keep);
+        for path in &backups[..excess] {
+            tokio::fs::remove_file(path).await?;
+            debug!("Deleted old cache backup {:?}", path);
+        }
+        Ok(excess)
+    }
+
+    /// Back up `cache` every `interval` until cancelled. The first backup is taken one
+    /// interval after starting, so restarting the app does not push out older backups.
+    pub async fn run(
+        self,
+        cache: Arc<LyricsCache>,
+        interval: Duration,
+        cancel_token: CancellationToken,
+    ) {
+        info!(
+            "Backing up the lyrics cache every {:?} to {:?}",
+            interval, self.dir
+        );
+        let start = tokio::time::Instant::now() + interval;
+        let mut ticker = tokio::time::interval_at(start, interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                () = cancel_token.cancelled() => break,
+                _ = ticker.tick() => {
+                    if let Err(e) = self.create(&cache).await {
+                        warn!("Failed to back up the lyrics cache: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_and_prune_backups() {
+        let root =
+            std::env::temp_dir().join(format!("versualizer-backup-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let cache = LyricsCache::open(&root.join("cache.db")).await.unwrap();
+        cache.mark_not_found("spotify", "t1").await.unwrap();
+
+        let backups = CacheBackups::new(root.join("backups"), 2);
+        let path = backups.create(&cache).await.unwrap();
+        let copy = LyricsCache::open(&path).await.unwrap();
+        assert!(
+            copy.is_not_found("spotify", "t1", Duration::from_mins(1))
+                .await
+                .unwrap()
+        );
+
+        // Older backups with earlier timestamps in their names
+        for name in [
+            "lyrics_cache-20000101-000000.db",
+            "lyrics_cache-20000102-000000.db",
+        ] {
+            std::fs::write(backups.dir().join(name), b"").unwrap();
+        }
+        std::fs::write(backups.dir().join("notes.txt"), b"").unwrap();
+
+        assert_eq!(backups.prune().await.unwrap(), 1);
+        let remaining = backups.list().await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining[0].ends_with("lyrics_cache-20000102-000000.db"));
+        assert_eq!(remaining[1], path);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,237 @@
+//! Tracks and artists whose lyrics are never fetched or shown.
+//!
+//! Entries come from `[lyrics.blocklist]` in the config and from the cache database, where
+//! the app's "Wrong lyrics?" picker and `versualizer cache block` add them. A track is
+//! blocked if its source track ID or "Artist - Title" is listed, or its artist or one of its
+//! comma-separated artists is.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::config::BlocklistConfig;
+use crate::playback::TrackInfo;
+
+/// What a blocklist entry matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    /// A single track, by source track ID or "Artist - Title"
+    Track,
+    /// Every track by an artist
+    Artist,
+}
+
+impl BlockKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Track => "track",
+            Self::Artist => "artist",
+        }
+    }
+
+    /// Parse `track` or `artist`
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "track" => Some(Self::Track),
+            "artist" => Some(Self::Artist),
+            _ => None,
+        }
+    }
+
+    /// Key `track` is blocked under: its source track ID, or its first artist
+    #[must_use]
+    pub fn key_of(self, track: &TrackInfo) -> String {
+        match self {
+            Self::Track => normalize_key(&track.source_track_id),
+            Self::Artist => normalize_key(artists(&track.artist).next().unwrap_or_default()),
+        }
+    }
+
+    /// Readable name of what blocking `track` as this kind covers
+    #[must_use]
+    pub fn label_of(self, track: &TrackInfo) -> String {
+        match self {
+            Self::Track => format!("{} - {}", track.artist, track.name),
+            Self::Artist => artists(&track.artist)
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A track or artist blocked from the app, kept in the cache database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockedEntry {
+    pub kind: BlockKind,
+    /// Lowercased source track ID or artist name
+    pub key: String,
+    /// "Artist - Title" or the artist name, as shown when it was blocked
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Set of blocked tracks and artists
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    artists: HashSet<String>,
+    tracks: HashSet<String>,
+}
+
+impl Blocklist {
+    /// Create an empty blocklist
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blocklist with the entries of `[lyrics.blocklist]`
+    #[must_use]
+    pub fn from_config(config: &BlocklistConfig) -> Self {
+        let mut blocklist = Self::new();
+        for artist in &config.artists {
+            blocklist.insert(BlockKind::Artist, artist);
+        }
+        for track in &config.tracks {
+            blocklist.insert(BlockKind::Track, track);
+        }
+        blocklist
+    }
+
+    /// Block `key`, a source track ID or "Artist - Title" for tracks, or an artist name.
+    /// Blank keys are ignored.
+    pub fn insert(&mut self, kind: BlockKind, key: &str) {
+        let key = normalize_key(key);
+        if key.is_empty() {
+            return;
+        }
+        match kind {
+            BlockKind::Track => self.tracks.insert(key),
+            BlockKind::Artist => self.artists.insert(key),
+        };
+    }
+
+    /// Whether nothing is blocked
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.artists.is_empty() && self.tracks.is_empty()
+    }
+
+    /// Why `track` is blocked, or `None` if it is not
+    #[must_use]
+    pub fn blocks(&self, track: &TrackInfo) -> Option<BlockKind> {
+        if self.tracks.contains(&normalize_key(&track.source_track_id))
+            || self.tracks.contains(&normalize_key(&format!(
+                "{} - {}",
+                track.artist, track.name
+            )))
+        {
+            return Some(BlockKind::Track);
+        }
+        let artist = normalize_key(&track.artist);
+        let blocked_artist = self.artists.contains(&artist)
+            || artists(&artist).any(|name| self.artists.contains(name));
+        blocked_artist.then_some(BlockKind::Artist)
+    }
+}
+
+/// Trimmed, lowercased form entries are compared in
+#[must_use]
+pub fn normalize_key(key: &str) -> String {
+    key.trim().to_lowercase()
+}
+
+/// Names in a comma-separated artist list, as sources join several artists
+fn artists(artist: &str) -> impl Iterator<Item = &str> {
+    artist
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+    use std::time::Duration;
+
+    fn track(id: &str, name: &str, artist: &str) -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            id,
+            name,
+            artist,
+            "Album",
+            Duration::from_mins(3),
+        )
+    }
+
+    #[test]
+    fn test_blocks_configured_tracks_and_artists() {
+        let blocklist = Blocklist::from_config(&BlocklistConfig {
+            artists: vec!["Sleep Sounds".to_string()],
+            tracks: vec!["abc123".to_string(), "Band - Wrong Song ".to_string()],
+        });
+
+        assert_eq!(
+            blocklist.blocks(&track("abc123", "Song", "Band")),
+            Some(BlockKind::Track)
+        );
+        assert_eq!(
+            blocklist.blocks(&track("x", "wrong song", "BAND")),
+            Some(BlockKind::Track)
+        );
+        assert_eq!(
+            blocklist.blocks(&track("y", "Rain", "sleep sounds")),
+            Some(BlockKind::Artist)
+        );
+        assert_eq!(blocklist.blocks(&track("z", "Song", "Band")), None);
+    }
+
+    #[test]
+    fn test_blocks_one_of_several_artists() {
+        let mut blocklist = Blocklist::new();
+        blocklist.insert(BlockKind::Artist, "Narrator");
+
+        assert_eq!(
+            blocklist.blocks(&track("a", "Chapter 1", "Author, Narrator")),
+            Some(BlockKind::Artist)
+        );
+        assert_eq!(
+            blocklist.blocks(&track("b", "Song", "Narrators United")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_keys_and_labels_of_track() {
+        let track = track("AbC", "Song", "Lead, Guest");
+
+        assert_eq!(BlockKind::Track.key_of(&track), "abc");
+        assert_eq!(BlockKind::Track.label_of(&track), "Lead, Guest - Song");
+        assert_eq!(BlockKind::Artist.key_of(&track), "lead");
+        assert_eq!(BlockKind::Artist.label_of(&track), "Lead");
+
+        let mut blocklist = Blocklist::new();
+        blocklist.insert(BlockKind::Artist, &BlockKind::Artist.key_of(&track));
+        assert_eq!(blocklist.blocks(&track), Some(BlockKind::Artist));
+    }
+
+    #[test]
+    fn test_ignores_blank_entries() {
+        let mut blocklist = Blocklist::new();
+        blocklist.insert(BlockKind::Artist, "  ");
+
+        assert!(blocklist.is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! Export of bookmarked lyric lines as a Markdown quote collection or JSON.
+
+use crate::cache::Bookmark;
+use crate::error::Result;
+use std::fmt::Write;
+
+/// Export format for bookmarks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkFormat {
+    /// One block quote per bookmark with its song and position
+    Markdown,
+    /// Array of bookmark objects
+    Json,
+}
+
+impl BookmarkFormat {
+    /// Parse a format name (`markdown`/`md` or `json`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Render `bookmarks` in this format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn render(self, bookmarks: &[Bookmark]) -> Result<String> {
+        match self {
+            Self::Markdown => Ok(to_markdown(bookmarks)),
+            Self::Json => Ok(serde_json::to_string_pretty(bookmarks)?),
+        }
+    }
+}
+
+/// Bookmarks as Markdown block quotes, in the given order
+fn to_markdown(bookmarks: &[Bookmark]) -> String {
+    let mut out = String::from("# Bookmarked lyrics\n");
+    for bookmark in bookmarks {
+        let _ = write!(
+            out,
+            "\n> {}\n\n— {}, *{}*",
+            bookmark.text, bookmark.artist, bookmark.track
+        );
+        if let Some(ref album) = bookmark.album {
+            let _ = write!(out, " ({album})");
+        }
+        let _ = writeln!(
+            out,
+            " at {} · {}",
+            format_position(bookmark.position_ms),
+            bookmark.created_at.format("%Y-%m-%d")
+        );
+    }
+    out
+}
+
+/// Position as `m:ss`
+fn format_position(position_ms: u64) -> String {
+    let secs = position_ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn bookmark(text: &str, album: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: 1,
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: album.map(str::to_string),
+            position_ms: 65_400,
+            text: text.to_string(),
+            created_at: DateTime::<Utc>::from_timestamp(1_760_000_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_markdown() {
+        let bookmarks = [
+            bookmark("Hello there", Some("Album")),
+            bookmark("Bye", None),
+        ];
+
+        assert_eq!(
+            BookmarkFormat::Markdown.render(&bookmarks).unwrap(),
+            "# Bookmarked lyrics\n\
+             \n> Hello there\n\n— Artist, *Song* (Album) at 1:05 · 2025-10-09\n\
+             \n> Bye\n\n— Artist, *Song* at 1:05 · 2025-10-09\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json = BookmarkFormat::Json
+            .render(&[bookmark("Hello there", None)])
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["text"], "Hello there");
+        assert_eq!(value[0]["position_ms"], 65_400);
+        assert!(value[0]["album"].is_null());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            BookmarkFormat::from_name("MD"),
+            Some(BookmarkFormat::Markdown)
+        );
+        assert_eq!(
+            BookmarkFormat::from_name("json"),
+            Some(BookmarkFormat::Json)
+        );
+        assert_eq!(BookmarkFormat::from_name("csv"), None);
+    }
+}
@@ -1,13 +1,27 @@
+use crate::archive::{
+    ARCHIVE_VERSION, ArchiveSummary, ArchivedBookmark, ArchivedLyrics, ArchivedMapping,
+    ArchivedOffset, ArchivedTranslation, CacheArchive,
+};
+use crate::blocklist::{BlockKind, BlockedEntry, Blocklist, normalize_key};
+use crate::diff::{self, LyricsDiff};
+use crate::encryption::ContentCipher;
 use crate::error::{CoreError, Result};
 use crate::lrc::LrcFile;
+use crate::playback::TrackInfo;
 use crate::provider::LyricsResult;
 use chrono::{DateTime, Utc};
 use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio_rusqlite::Connection;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-const SCHEMA_SQL: &str = r"
+/// Schema from before migrations were versioned. Every statement is `IF NOT EXISTS`, so it
+/// applies cleanly to both new databases and ones created by older releases.
+const BASELINE_SCHEMA_SQL: &str = r"
 -- Core lyrics storage (source-agnostic)
 CREATE TABLE IF NOT EXISTS lyrics (
     id INTEGER PRIMARY KEY,
@@ -37,8 +51,146 @@ CREATE TABLE IF NOT EXISTS track_id_mapping (
 CREATE INDEX IF NOT EXISTS idx_lyrics_artist_track ON lyrics(artist, track);
 CREATE INDEX IF NOT EXISTS idx_mapping_provider ON track_id_mapping(provider, provider_track_id);
 CREATE INDEX IF NOT EXISTS idx_lyrics_provider_id ON lyrics(provider, provider_id);
+
+-- Line-aligned translations of cached lyrics, one row per target language
+CREATE TABLE IF NOT EXISTS lyrics_translations (
+    lyrics_id INTEGER NOT NULL,
+    target_lang TEXT NOT NULL,
+    content TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    PRIMARY KEY (lyrics_id, target_lang),
+    FOREIGN KEY (lyrics_id) REFERENCES lyrics(id) ON DELETE CASCADE
+);
+
+-- Incoming lyrics that disagree with a cached entry, kept until the user resolves them
+CREATE TABLE IF NOT EXISTS lyrics_conflicts (
+    id INTEGER PRIMARY KEY,
+    lyrics_id INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    FOREIGN KEY (lyrics_id) REFERENCES lyrics(id) ON DELETE CASCADE,
+    UNIQUE(lyrics_id, provider, provider_id)
+);
+
+-- Lines the user bookmarked while listening, independent of the cached lyrics
+CREATE TABLE IF NOT EXISTS bookmarks (
+    id INTEGER PRIMARY KEY,
+    artist TEXT NOT NULL,
+    track TEXT NOT NULL,
+    album TEXT,
+    position_ms INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- Per-track lyrics timing corrections set by the user, keyed by provider track ID
+CREATE TABLE IF NOT EXISTS track_offsets (
+    provider TEXT NOT NULL,
+    provider_track_id TEXT NOT NULL,
+    offset_ms INTEGER NOT NULL,
+    PRIMARY KEY (provider, provider_track_id)
+);
+
+-- Tracks no provider had lyrics for, so replays skip the network until the entry expires
+CREATE TABLE IF NOT EXISTS not_found (
+    source TEXT NOT NULL,
+    track_id TEXT NOT NULL,
+    checked_at INTEGER NOT NULL,
+    PRIMARY KEY (source, track_id)
+);
+
+-- Tracks a prefetch job has finished, so an interrupted job resumes where it stopped
+CREATE TABLE IF NOT EXISTS prefetch_progress (
+    job TEXT NOT NULL,
+    source TEXT NOT NULL,
+    track_id TEXT NOT NULL,
+    found INTEGER NOT NULL,
+    done_at INTEGER NOT NULL,
+    PRIMARY KEY (job, source, track_id)
+);
+";
+
+/// Keep every provider's lyrics for a track next to the entry shown for it, so the user can
+/// switch between them. Existing entries and conflicts become the first alternatives.
+const LYRICS_ALTERNATIVES_SQL: &str = r"
+-- One row per cached track and lyrics provider; `lyrics` holds the one that is shown
+CREATE TABLE lyrics_alternatives (
+    lyrics_id INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    lyrics_type TEXT NOT NULL,
+    content TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    PRIMARY KEY (lyrics_id, provider),
+    FOREIGN KEY (lyrics_id) REFERENCES lyrics(id) ON DELETE CASCADE
+);
+
+-- Set once the user picks an alternative, so later fetches from other providers keep it
+ALTER TABLE lyrics ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+INSERT INTO lyrics_alternatives (lyrics_id, provider, provider_id, lyrics_type, content, fetched_at)
+SELECT id, provider, provider_id, lyrics_type, content, fetched_at FROM lyrics;
+
+INSERT OR IGNORE INTO lyrics_alternatives (lyrics_id, provider, provider_id, lyrics_type, content, fetched_at)
+SELECT lyrics_id, provider, provider_id, 'synced', content, fetched_at FROM lyrics_conflicts;
 ";
 
+/// Remember when the background maintenance task last ran, so restarting the app does not
+/// reset its schedule
+const CACHE_MAINTENANCE_SQL: &str = r"
+-- A single row, replaced after each run
+CREATE TABLE cache_maintenance (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    ran_at INTEGER NOT NULL
+);
+";
+
+/// Tracks and artists the user blocked from showing lyrics
+const BLOCKLIST_SQL: &str = r"
+-- `key` is the lowercased source track ID for tracks and artist name for artists
+CREATE TABLE blocklist (
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    label TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, key)
+);
+";
+
+/// Schema migrations in order. Migration `i` upgrades a database at `user_version` `i` to
+/// `i + 1`. Only ever append to this list: released migrations have already run on users'
+/// databases, so editing or reordering them would leave those databases inconsistent.
+const MIGRATIONS: &[&str] = &[
+    BASELINE_SCHEMA_SQL,
+    LYRICS_ALTERNATIVES_SQL,
+    CACHE_MAINTENANCE_SQL,
+    BLOCKLIST_SQL,
+];
+
+/// `PRAGMA auto_vacuum` value of incremental auto-vacuum
+const INCREMENTAL_AUTO_VACUUM: i64 = 2;
+
+/// Bring the schema up to date, running each pending migration in its own transaction.
+/// Returns the schema version the database had before.
+fn migrate(conn: &mut rusqlite::Connection, migrations: &[&str]) -> Result<u32> {
+    let supported = u32::try_from(migrations.len()).unwrap_or(u32::MAX);
+    let found: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if found > supported {
+        return Err(CoreError::CacheSchemaTooNew { found, supported });
+    }
+
+    for (version, sql) in (found..).zip(&migrations[found as usize..]) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version + 1)?;
+        tx.commit()?;
+        debug!("Migrated lyrics cache schema to version {}", version + 1);
+    }
+    Ok(found)
+}
+
 /// Cached lyrics entry
 #[derive(Debug, Clone)]
 pub struct CachedLyrics {
@@ -58,6 +210,8 @@ pub struct CachedLyrics {
 pub enum LyricsType {
     Synced,
     Unsynced,
+    /// A provider reported the track as instrumental; the content is empty
+    Instrumental,
 }
 
 impl LyricsType {
@@ -65,6 +219,7 @@ impl LyricsType {
         match self {
             Self::Synced => "synced",
             Self::Unsynced => "unsynced",
+            Self::Instrumental => "instrumental",
         }
     }
 
@@ -72,6 +227,7 @@ impl LyricsType {
         match s {
             "synced" => Some(Self::Synced),
             "unsynced" => Some(Self::Unsynced),
+            "instrumental" => Some(Self::Instrumental),
             _ => None,
         }
     }
@@ -87,10 +243,80 @@ impl CachedLyrics {
                 LyricsResult::Synced,
             ),
             LyricsType::Unsynced => LyricsResult::Unsynced(self.content.clone()),
+            LyricsType::Instrumental => LyricsResult::Instrumental,
+        }
+    }
+}
+
+/// One provider's lyrics for a cached track, see [`LyricsCache::alternatives`]
+#[derive(Debug, Clone)]
+pub struct LyricsAlternative {
+    /// The cached entry these are an alternative for
+    pub lyrics_id: i64,
+    pub provider: String,
+    pub provider_id: String,
+    pub lyrics_type: LyricsType,
+    pub content: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl LyricsAlternative {
+    /// Convert the alternative's content to `LyricsResult`
+    #[must_use]
+    pub fn to_lyrics_result(&self) -> LyricsResult {
+        match self.lyrics_type {
+            LyricsType::Synced => LrcFile::parse(&self.content).map_or_else(
+                |_| LyricsResult::Unsynced(self.content.clone()),
+                LyricsResult::Synced,
+            ),
+            LyricsType::Unsynced => LyricsResult::Unsynced(self.content.clone()),
+            LyricsType::Instrumental => LyricsResult::Instrumental,
         }
     }
 }
 
+/// Synced lyrics from a provider that disagree with an existing cache entry
+#[derive(Debug, Clone)]
+pub struct LyricsConflict {
+    pub id: i64,
+    /// The cached entry ("mine") this conflicts with
+    pub lyrics_id: i64,
+    /// Artist and title of the cached entry
+    pub artist: String,
+    pub track: String,
+    /// Provider of the incoming ("theirs") lyrics
+    pub provider: String,
+    pub provider_id: String,
+    /// Incoming LRC content
+    pub content: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A bookmarked lyric line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bookmark {
+    pub id: i64,
+    pub artist: String,
+    pub track: String,
+    pub album: Option<String>,
+    /// Where the line starts in the track
+    pub position_ms: u64,
+    /// Text of the line
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How to resolve a [`LyricsConflict`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Keep the cached lyrics and discard the incoming ones
+    KeepMine,
+    /// Replace the cached lyrics with the incoming ones
+    TakeTheirs,
+    /// Keep the cached lyrics and add lines only the incoming ones have
+    Merge,
+}
+
 /// Track metadata for cache storage
 #[derive(Debug, Clone)]
 pub struct TrackMetadata {
@@ -100,9 +326,41 @@ pub struct TrackMetadata {
     pub duration_ms: Option<i64>,
 }
 
+/// A cached track without its lyrics, see [`LyricsCache::list`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CachedTrack {
+    pub id: i64,
+    pub artist: String,
+    pub track: String,
+    pub album: Option<String>,
+    pub provider: String,
+    pub synced: bool,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Counts of what the cache holds, see [`LyricsCache::stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Cached tracks, and how many of them have synced lyrics
+    pub tracks: usize,
+    pub synced: usize,
+    /// Cached tracks per provider of the shown lyrics, most first
+    pub providers: Vec<(String, usize)>,
+    /// Provider track IDs mapped to cached tracks
+    pub track_ids: usize,
+    pub translations: usize,
+    pub bookmarks: usize,
+    /// Tracks remembered as having no lyrics anywhere
+    pub not_found: usize,
+    /// Size of the database file
+    pub size_bytes: u64,
+}
+
 /// SQLite-based lyrics cache
 pub struct LyricsCache {
     conn: Connection,
+    /// Encrypts the `content` column when encryption-at-rest is enabled
+    cipher: Option<Arc<ContentCipher>>,
 }
 
 impl LyricsCache {
@@ -131,17 +389,111 @@ impl LyricsCache {
 
         let conn = Connection::open(path).await?;
 
-        // Initialize schema
-        conn.call(|conn| {
-            conn.execute_batch(SCHEMA_SQL)?;
-            conn.pragma_update(None, "journal_mode", "WAL")?;
-            conn.pragma_update(None, "foreign_keys", "ON")?;
-            Ok(())
-        })
-        .await?;
+        // Initialize or upgrade the schema
+        let migrated = conn
+            .call(|conn| {
+                // Only takes effect on a new database; `vacuum` converts existing ones
+                conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+                conn.pragma_update(None, "journal_mode", "WAL")?;
+                conn.pragma_update(None, "foreign_keys", "ON")?;
+                Ok(migrate(conn, MIGRATIONS))
+            })
+            .await??;
+        if (migrated as usize) < MIGRATIONS.len() {
+            info!(
+                "Upgraded lyrics cache schema from version {} to {}",
+                migrated,
+                MIGRATIONS.len()
+            );
+        }
 
         info!("Lyrics cache database initialized");
-        Ok(Self { conn })
+        Ok(Self { conn, cipher: None })
+    }
+
+    /// Copy the whole database to `path` with the `SQLite` online backup API, so a consistent
+    /// snapshot can be taken while the app keeps using the cache. An existing file at `path`
+    /// is overwritten. Encrypted content stays encrypted in the copy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the destination cannot be created or the backup fails.
+    pub async fn backup_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let destination = path.to_path_buf();
+        self.conn
+            .call(move |conn| {
+                conn.backup(rusqlite::DatabaseName::Main, &destination, None)?;
+                Ok(())
+            })
+            .await?;
+        info!("Backed up lyrics cache to {:?}", path);
+        Ok(())
+    }
+
+    /// Encrypt lyrics content at rest with the given cipher.
+    /// Existing plaintext rows stay readable; call [`Self::encrypt_existing`] to convert them.
+    ///
+    /// Only lyric and translation bodies are encrypted. Track metadata (artist, title, album,
+    /// duration), provider IDs and the `track_id_mapping` table stay in plaintext because
+    /// every lookup matches on them.
+    #[must_use]
+    pub fn with_encryption(mut self, cipher: ContentCipher) -> Self {
+        self.cipher = Some(Arc::new(cipher));
+        self
+    }
+
+    /// Encrypt any plaintext rows left over from before encryption was enabled.
+    /// Returns the number of rows converted. Does nothing if encryption is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read or updated.
+    pub async fn encrypt_existing(&self) -> Result<usize> {
+        let Some(cipher) = self.cipher.clone() else {
+            return Ok(0);
+        };
+
+        let converted = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut converted = 0;
+                for table in ENCRYPTED_TABLES {
+                    converted += encrypt_table(&tx, &cipher, table)?;
+                }
+                tx.commit()?;
+                Ok(converted)
+            })
+            .await?;
+
+        if converted > 0 {
+            info!("Encrypted {} existing cache entries", converted);
+        }
+        Ok(converted)
+    }
+
+    /// Decrypt a cached entry's content. Entries that fail to decrypt are treated as misses.
+    fn decrypt_entry(&self, entry: Option<CachedLyrics>) -> Option<CachedLyrics> {
+        let mut entry = entry?;
+        if let Some(ref cipher) = self.cipher {
+            match cipher.decrypt(&entry.content) {
+                Ok(content) => entry.content = content,
+                Err(e) => {
+                    warn!("Ignoring cache entry {}: {}", entry.id, e);
+                    return None;
+                }
+            }
+        } else if ContentCipher::is_encrypted(&entry.content) {
+            warn!(
+                "Ignoring encrypted cache entry {} because cache encryption is disabled",
+                entry.id
+            );
+            return None;
+        }
+        Some(entry)
     }
 
     /// Fast lookup by provider track ID (e.g., Spotify track ID)
@@ -161,7 +513,8 @@ impl LyricsCache {
         let provider = provider.to_string();
         let id = provider_track_id.to_string();
 
-        self.conn
+        let result = self
+            .conn
             .call(move |conn| {
                 let mut stmt = conn.prepare_cached(
                     r"
@@ -194,8 +547,9 @@ impl LyricsCache {
 
                 Ok(result)
             })
-            .await
-            .map_err(Into::into)
+            .await?;
+
+        Ok(self.decrypt_entry(result))
     }
 
     /// Fallback lookup by metadata (when source ID not cached)
@@ -213,7 +567,8 @@ impl LyricsCache {
         let track = track.to_lowercase();
         let album = album.map(str::to_lowercase);
 
-        self.conn
+        let result = self
+            .conn
             .call(move |conn| {
                 let result = if let Some(album) = album {
                     let mut stmt = conn.prepare_cached(
@@ -275,11 +630,12 @@ impl LyricsCache {
 
                 Ok(result)
             })
-            .await
-            .map_err(Into::into)
+            .await?;
+
+        Ok(self.decrypt_entry(result))
     }
 
-    /// Store lyrics and create mapping to provider track ID
+    /// Store lyrics and map both the provider track ID and the lyrics provider's own ID to them
     ///
     /// # Errors
     ///
@@ -309,12 +665,10 @@ impl LyricsCache {
         let metadata = metadata.clone();
 
         let (lyrics_type, content) = match lyrics {
-            LyricsResult::Synced(lrc) => {
-                // Store the original LRC content - we need to serialize it
-                let content = serialize_lrc(lrc);
-                (LyricsType::Synced, content)
-            }
+            // Synced lyrics are stored as serialized LRC
+            LyricsResult::Synced(lrc) => (LyricsType::Synced, lrc.to_string()),
             LyricsResult::Unsynced(text) => (LyricsType::Unsynced, text.clone()),
+            LyricsResult::Instrumental => (LyricsType::Instrumental, String::new()),
             LyricsResult::NotFound => {
                 return Err(CoreError::LyricsNotFound {
                     track: metadata.track.clone(),
@@ -323,28 +677,54 @@ impl LyricsCache {
             }
         };
 
+        let incoming = match lyrics {
+            LyricsResult::Synced(lrc) => Some(lrc.clone()),
+            _ => None,
+        };
+        let content = match self.cipher {
+            Some(ref cipher) => cipher.encrypt(&content)?,
+            None => content,
+        };
+
         let now = Utc::now().timestamp();
         let lyrics_type_str = lyrics_type.as_str().to_string();
+        let cipher = self.cipher.clone();
 
         self.conn
             .call(move |conn| {
-                // Insert or update lyrics entry
+                // Synced lyrics from a different provider that disagree with the cached
+                // synced lyrics are kept aside as a conflict instead of overwriting
+                let conflicting = match incoming.as_ref() {
+                    Some(incoming) => conflicting_entry(
+                        conn,
+                        &metadata,
+                        incoming,
+                        (&lyrics_provider, &lyrics_provider_id),
+                        cipher.as_deref(),
+                    )?,
+                    None => None,
+                };
+
+                let lyrics_id = if let Some(id) = conflicting {
+                    insert_conflict(conn, id, (&lyrics_provider, &lyrics_provider_id), &content, now)?;
+                    id
+                } else {
+                    upsert_lyrics(
+                        conn,
+                        &metadata,
+                        (&lyrics_provider, &lyrics_provider_id),
+                        (&lyrics_type_str, &content),
+                        now,
+                    )?
+                };
+
                 conn.execute(
                     r"
-                    INSERT INTO lyrics (artist, track, album, duration_ms, provider, provider_id, lyrics_type, content, fetched_at)
-                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-                    ON CONFLICT(artist, track, album) DO UPDATE SET
-                        provider = excluded.provider,
-                        provider_id = excluded.provider_id,
-                        lyrics_type = excluded.lyrics_type,
-                        content = excluded.content,
-                        fetched_at = excluded.fetched_at
+                    INSERT OR REPLACE INTO lyrics_alternatives (lyrics_id, provider, provider_id, lyrics_type, content, fetched_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
                 ",
                     rusqlite::params![
-                        metadata.artist,
-                        metadata.track,
-                        metadata.album,
-                        metadata.duration_ms,
+                        lyrics_id,
                         lyrics_provider,
                         lyrics_provider_id,
                         lyrics_type_str,
@@ -353,19 +733,26 @@ impl LyricsCache {
                     ],
                 )?;
 
-                let lyrics_id = conn.last_insert_rowid();
-
-                // Create mapping from provider track ID to lyrics
-                conn.execute(
-                    r"
-                    INSERT INTO track_id_mapping (provider, provider_track_id, lyrics_id, created_at)
-                    VALUES (?1, ?2, ?3, ?4)
-                    ON CONFLICT(provider, provider_track_id) DO UPDATE SET
-                        lyrics_id = excluded.lyrics_id,
-                        created_at = excluded.created_at
-                ",
-                    rusqlite::params![provider, provider_track_id, lyrics_id, now],
-                )?;
+                // Map both the music source's track ID and the lyrics provider's own ID to
+                // the lyrics, so a lookup by either hits the cache
+                for (provider, provider_track_id) in [
+                    (&provider, &provider_track_id),
+                    (&lyrics_provider, &lyrics_provider_id),
+                ] {
+                    if provider_track_id.is_empty() {
+                        continue;
+                    }
+                    conn.execute(
+                        r"
+                        INSERT INTO track_id_mapping (provider, provider_track_id, lyrics_id, created_at)
+                        VALUES (?1, ?2, ?3, ?4)
+                        ON CONFLICT(provider, provider_track_id) DO UPDATE SET
+                            lyrics_id = excluded.lyrics_id,
+                            created_at = excluded.created_at
+                    ",
+                        rusqlite::params![provider, provider_track_id, lyrics_id, now],
+                    )?;
+                }
 
                 Ok(lyrics_id)
             })
@@ -373,252 +760,1470 @@ impl LyricsCache {
             .map_err(Into::into)
     }
 
-    /// Delete old cache entries beyond TTL
+    /// Cached translation of entry `lyrics_id` into `target_lang`, one string per line
     ///
     /// # Errors
     ///
-    /// Returns an error if the database cleanup fails.
-    pub async fn cleanup(&self, ttl_days: u32) -> Result<usize> {
-        let cutoff = Utc::now().timestamp() - (i64::from(ttl_days) * 24 * 60 * 60);
-
-        self.conn
+    /// Returns an error if the database query fails.
+    pub async fn get_translation(
+        &self,
+        lyrics_id: i64,
+        target_lang: &str,
+    ) -> Result<Option<Vec<String>>> {
+        let target_lang = target_lang.to_string();
+        let content = self
+            .conn
             .call(move |conn| {
-                let deleted = conn.execute(
-                    "DELETE FROM lyrics WHERE fetched_at < ?1",
-                    rusqlite::params![cutoff],
-                )?;
-                Ok(deleted)
+                Ok(conn
+                    .query_row(
+                        "SELECT content FROM lyrics_translations WHERE lyrics_id = ?1 AND target_lang = ?2",
+                        rusqlite::params![lyrics_id, target_lang],
+                        |row| row.get::<_, String>(0),
+                    )
+                    .optional()?)
             })
-            .await
-            .map_err(Into::into)
+            .await?;
+
+        let Some(content) = content.and_then(|c| decrypt_with(self.cipher.as_deref(), &c)) else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_str(&content)?))
     }
 
-    /// Checkpoint WAL for clean shutdown
+    /// Store a line-aligned translation of entry `lyrics_id`, replacing any previous one
     ///
     /// # Errors
     ///
-    /// Returns an error if the WAL checkpoint fails.
-    pub async fn checkpoint(&self) -> Result<()> {
+    /// Returns an error if the translation cannot be encrypted or stored.
+    pub async fn store_translation(
+        &self,
+        lyrics_id: i64,
+        target_lang: &str,
+        lines: &[String],
+    ) -> Result<()> {
+        let content = serde_json::to_string(lines)?;
+        let content = match self.cipher {
+            Some(ref cipher) => cipher.encrypt(&content)?,
+            None => content,
+        };
+        let target_lang = target_lang.to_string();
+        let now = Utc::now().timestamp();
+
         self.conn
-            .call(|conn| {
-                conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)")?;
+            .call(move |conn| {
+                conn.execute(
+                    r"
+                    INSERT INTO lyrics_translations (lyrics_id, target_lang, content, fetched_at)
+                    VALUES (?1, ?2, ?3, ?4)
+                    ON CONFLICT(lyrics_id, target_lang) DO UPDATE SET
+                        content = excluded.content,
+                        fetched_at = excluded.fetched_at
+                ",
+                    rusqlite::params![lyrics_id, target_lang, content, now],
+                )?;
                 Ok(())
             })
             .await
             .map_err(Into::into)
     }
-}
 
-/// Serialize an `LrcFile` back to LRC format for storage
-fn serialize_lrc(lrc: &LrcFile) -> String {
-    use std::fmt::Write;
-
-    let mut output = String::new();
+    /// List unresolved conflicts, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn conflicts(&self) -> Result<Vec<LyricsConflict>> {
+        let conflicts = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare_cached(
+                    r"
+                    SELECT c.id, c.lyrics_id, l.artist, l.track, c.provider, c.provider_id,
+                           c.content, c.fetched_at
+                    FROM lyrics_conflicts c
+                    JOIN lyrics l ON l.id = c.lyrics_id
+                    ORDER BY c.fetched_at DESC
+                ",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    Ok(LyricsConflict {
+                        id: row.get(0)?,
+                        lyrics_id: row.get(1)?,
+                        artist: row.get(2)?,
+                        track: row.get(3)?,
+                        provider: row.get(4)?,
+                        provider_id: row.get(5)?,
+                        content: row.get(6)?,
+                        fetched_at: DateTime::from_timestamp(row.get::<_, i64>(7)?, 0)
+                            .unwrap_or_else(Utc::now),
+                    })
+                })?;
+                Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+            })
+            .await?;
 
-    // Write metadata
-    if let Some(ref title) = lrc.metadata.title {
-        let _ = writeln!(output, "[ti:{title}]");
-    }
-    if let Some(ref artist) = lrc.metadata.artist {
-        let _ = writeln!(output, "[ar:{artist}]");
-    }
-    if let Some(ref album) = lrc.metadata.album {
-        let _ = writeln!(output, "[al:{album}]");
-    }
-    if lrc.metadata.offset != 0 {
-        let _ = writeln!(output, "[offset:{}]", lrc.metadata.offset);
+        Ok(conflicts
+            .into_iter()
+            .filter_map(|mut conflict| {
+                conflict.content = decrypt_with(self.cipher.as_deref(), &conflict.content)?;
+                Some(conflict)
+            })
+            .collect())
     }
 
-    // Write lines
-    for line in &lrc.lines {
-        let timestamp = format_timestamp(line.start_time);
-
-        if let Some(ref words) = line.words {
-            // Enhanced LRC format
-            let _ = write!(output, "[{timestamp}]");
-            for word in words {
-                let _ = write!(
-                    output,
-                    " <{}> {}",
-                    format_timestamp(word.start_time),
-                    word.text
-                );
-            }
-            output.push('\n');
-        } else {
-            // Simple LRC format
-            let _ = writeln!(output, "[{timestamp}]{}", line.text);
-        }
+    /// Line diff between a conflict's cached ("mine") and incoming ("theirs") lyrics.
+    /// Returns `None` if the conflict or its cached entry no longer exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn conflict_diff(&self, conflict_id: i64) -> Result<Option<LyricsDiff>> {
+        let Some((mine, theirs)) = self.conflict_pair(conflict_id).await? else {
+            return Ok(None);
+        };
+        Ok(Some(LyricsDiff::between(&mine, &theirs)))
     }
 
-    output
-}
+    /// Resolve a conflict by keeping the cached lyrics, taking the incoming ones, or merging.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn resolve_conflict(
+        &self,
+        conflict_id: i64,
+        resolution: ConflictResolution,
+    ) -> Result<()> {
+        let replacement = match resolution {
+            ConflictResolution::KeepMine => None,
+            ConflictResolution::TakeTheirs | ConflictResolution::Merge => {
+                let Some((mine, theirs)) = self.conflict_pair(conflict_id).await? else {
+                    return Ok(());
+                };
+                let lrc = if resolution == ConflictResolution::Merge {
+                    diff::merge(&mine, &theirs)
+                } else {
+                    theirs
+                };
+                let content = lrc.to_string();
+                Some(match self.cipher {
+                    Some(ref cipher) => cipher.encrypt(&content)?,
+                    None => content,
+                })
+            }
+        };
+        let take_provider = resolution == ConflictResolution::TakeTheirs;
+        let now = Utc::now().timestamp();
 
-/// Format a duration as LRC timestamp (mm:ss.xx)
-fn format_timestamp(duration: std::time::Duration) -> String {
-    let total_secs = duration.as_secs();
-    let minutes = total_secs / 60;
-    let seconds = total_secs % 60;
-    let hundredths = duration.subsec_millis() / 10;
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                if let Some(content) = replacement {
+                    tx.execute(
+                        r"
+                        UPDATE lyrics SET
+                            content = ?2,
+                            fetched_at = ?3,
+                            provider = CASE WHEN ?4 THEN c.provider ELSE lyrics.provider END,
+                            provider_id = CASE WHEN ?4 THEN c.provider_id ELSE lyrics.provider_id END
+                        FROM lyrics_conflicts c
+                        WHERE c.id = ?1 AND lyrics.id = c.lyrics_id
+                    ",
+                        rusqlite::params![conflict_id, content, now, take_provider],
+                    )?;
+                }
+                tx.execute(
+                    "DELETE FROM lyrics_conflicts WHERE id = ?1",
+                    [conflict_id],
+                )?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
 
-    format!("{minutes:02}:{seconds:02}.{hundredths:02}")
-}
+    /// Load the cached and incoming lyrics for a conflict
+    async fn conflict_pair(&self, conflict_id: i64) -> Result<Option<(LrcFile, LrcFile)>> {
+        let pair = self
+            .conn
+            .call(move |conn| {
+                Ok(conn
+                    .query_row(
+                        r"
+                        SELECT l.content, c.content FROM lyrics_conflicts c
+                        INNER JOIN lyrics l ON l.id = c.lyrics_id
+                        WHERE c.id = ?1
+                    ",
+                        [conflict_id],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+                    )
+                    .optional()?)
+            })
+            .await?;
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::*;
-    use crate::lrc::{LrcLine, LrcMetadata, LrcWord};
+        let Some((mine, theirs)) = pair else {
+            return Ok(None);
+        };
+        let parse = |content: &str| {
+            decrypt_with(self.cipher.as_deref(), content)
+                .and_then(|content| LrcFile::parse(&content).ok())
+        };
+        Ok(parse(&mine).zip(parse(&theirs)))
+    }
 
-    #[test]
-    fn test_lyrics_type_as_str() {
-        assert_eq!(LyricsType::Synced.as_str(), "synced");
-        assert_eq!(LyricsType::Unsynced.as_str(), "unsynced");
+    /// Every provider's lyrics kept for cached entry `lyrics_id`, ordered by provider name.
+    /// Alternatives that fail to decrypt are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn alternatives(&self, lyrics_id: i64) -> Result<Vec<LyricsAlternative>> {
+        let alternatives = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare_cached(
+                    r"
+                    SELECT provider, provider_id, lyrics_type, content, fetched_at
+                    FROM lyrics_alternatives
+                    WHERE lyrics_id = ?1
+                    ORDER BY provider
+                ",
+                )?;
+                let rows = stmt.query_map([lyrics_id], |row| {
+                    Ok(LyricsAlternative {
+                        lyrics_id,
+                        provider: row.get(0)?,
+                        provider_id: row.get(1)?,
+                        lyrics_type: LyricsType::from_str(&row.get::<_, String>(2)?)
+                            .unwrap_or(LyricsType::Unsynced),
+                        content: row.get(3)?,
+                        fetched_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                            .unwrap_or_else(Utc::now),
+                    })
+                })?;
+                Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+            })
+            .await?;
+
+        Ok(alternatives
+            .into_iter()
+            .filter_map(|mut alternative| {
+                alternative.content = decrypt_with(self.cipher.as_deref(), &alternative.content)?;
+                Some(alternative)
+            })
+            .collect())
+    }
+
+    /// Show `provider`'s alternative for cached entry `lyrics_id` from now on, even after
+    /// other providers' lyrics are fetched for the track. Translations of the previous
+    /// lyrics are dropped since their lines no longer match. Returns `false` if there is no
+    /// such alternative.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn select_alternative(&self, lyrics_id: i64, provider: &str) -> Result<bool> {
+        let provider = provider.to_string();
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let updated = tx.execute(
+                    r"
+                    UPDATE lyrics SET
+                        provider = a.provider,
+                        provider_id = a.provider_id,
+                        lyrics_type = a.lyrics_type,
+                        content = a.content,
+                        fetched_at = a.fetched_at,
+                        pinned = 1
+                    FROM lyrics_alternatives a
+                    WHERE lyrics.id = ?1 AND a.lyrics_id = ?1 AND a.provider = ?2
+                ",
+                    rusqlite::params![lyrics_id, provider],
+                )?;
+                if updated > 0 {
+                    tx.execute(
+                        "DELETE FROM lyrics_translations WHERE lyrics_id = ?1",
+                        [lyrics_id],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(updated > 0)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Keep `lyrics` as `provider`'s alternative for cached entry `lyrics_id` and show it
+    /// from now on, as with [`Self::select_alternative`]. Unlike [`Self::store`], lyrics
+    /// that disagree with the cached ones replace them rather than becoming a conflict, so
+    /// hand-edited lyrics are saved as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lyrics cannot be encrypted or stored.
+    pub async fn replace_lyrics(
+        &self,
+        lyrics_id: i64,
+        provider: &str,
+        provider_id: &str,
+        lyrics: &LrcFile,
+    ) -> Result<()> {
+        let content = lyrics.to_string();
+        let content = match self.cipher {
+            Some(ref cipher) => cipher.encrypt(&content)?,
+            None => content,
+        };
+        let (provider_owned, provider_id) = (provider.to_string(), provider_id.to_string());
+        let now = Utc::now().timestamp();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r"
+                    INSERT OR REPLACE INTO lyrics_alternatives (lyrics_id, provider, provider_id, lyrics_type, content, fetched_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ",
+                    rusqlite::params![
+                        lyrics_id,
+                        provider_owned,
+                        provider_id,
+                        LyricsType::Synced.as_str(),
+                        content,
+                        now
+                    ],
+                )?;
+                Ok(())
+            })
+            .await?;
+        self.select_alternative(lyrics_id, provider).await?;
+        Ok(())
+    }
+
+    /// Bookmark a line of `track` starting at `position_ms`. Returns the bookmark ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text cannot be encrypted or stored.
+    pub async fn add_bookmark(
+        &self,
+        track: &TrackInfo,
+        position_ms: u64,
+        text: &str,
+    ) -> Result<i64> {
+        let text = match self.cipher {
+            Some(ref cipher) => cipher.encrypt(text)?,
+            None => text.to_string(),
+        };
+        let artist = track.artist.clone();
+        let name = track.name.clone();
+        let album = Some(track.album.clone()).filter(|album| !album.is_empty());
+        let position_ms = i64::try_from(position_ms).unwrap_or(i64::MAX);
+        let now = Utc::now().timestamp();
+
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r"
+                    INSERT INTO bookmarks (artist, track, album, position_ms, text, created_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ",
+                    rusqlite::params![artist, name, album, position_ms, text, now],
+                )?;
+                Ok(conn.last_insert_rowid())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// List bookmarks, newest first. Bookmarks that fail to decrypt are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn bookmarks(&self) -> Result<Vec<Bookmark>> {
+        let bookmarks = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare_cached(
+                    r"
+                    SELECT id, artist, track, album, position_ms, text, created_at
+                    FROM bookmarks
+                    ORDER BY created_at DESC, id DESC
+                ",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    Ok(Bookmark {
+                        id: row.get(0)?,
+                        artist: row.get(1)?,
+                        track: row.get(2)?,
+                        album: row.get(3)?,
+                        position_ms: u64::try_from(row.get::<_, i64>(4)?).unwrap_or_default(),
+                        text: row.get(5)?,
+                        created_at: DateTime::from_timestamp(row.get::<_, i64>(6)?, 0)
+                            .unwrap_or_else(Utc::now),
+                    })
+                })?;
+                Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+            })
+            .await?;
+
+        Ok(bookmarks
+            .into_iter()
+            .filter_map(|mut bookmark| {
+                bookmark.text = decrypt_with(self.cipher.as_deref(), &bookmark.text)?;
+                Some(bookmark)
+            })
+            .collect())
+    }
+
+    /// Delete a bookmark
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn delete_bookmark(&self, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute("DELETE FROM bookmarks WHERE id = ?1", [id])?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Save the lyrics timing offset for a track. An offset of 0 removes the saved offset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn set_offset(
+        &self,
+        provider: &str,
+        provider_track_id: &str,
+        offset_ms: i64,
+    ) -> Result<()> {
+        let (provider, track_id) = (provider.to_string(), provider_track_id.to_string());
+        self.conn
+            .call(move |conn| {
+                if offset_ms == 0 {
+                    conn.execute(
+                        "DELETE FROM track_offsets WHERE provider = ?1 AND provider_track_id = ?2",
+                        rusqlite::params![provider, track_id],
+                    )?;
+                } else {
+                    conn.execute(
+                        "INSERT OR REPLACE INTO track_offsets (provider, provider_track_id, offset_ms) VALUES (?1, ?2, ?3)",
+                        rusqlite::params![provider, track_id, offset_ms],
+                    )?;
+                }
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// The saved lyrics timing offset for a track, or 0 if none was saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn get_offset(&self, provider: &str, provider_track_id: &str) -> Result<i64> {
+        let (provider, track_id) = (provider.to_string(), provider_track_id.to_string());
+        self.conn
+            .call(move |conn| {
+                Ok(conn
+                    .query_row(
+                        "SELECT offset_ms FROM track_offsets WHERE provider = ?1 AND provider_track_id = ?2",
+                        rusqlite::params![provider, track_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .unwrap_or(0))
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Remember that no provider had lyrics for a track
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn mark_not_found(&self, source: &str, track_id: &str) -> Result<()> {
+        let (source, track_id) = (source.to_string(), track_id.to_string());
+        let now = Utc::now().timestamp();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT OR REPLACE INTO not_found (source, track_id, checked_at) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![source, track_id, now],
+                )?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Whether a track was found to have no lyrics less than `ttl` ago
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn is_not_found(&self, source: &str, track_id: &str, ttl: Duration) -> Result<bool> {
+        let (source, track_id) = (source.to_string(), track_id.to_string());
+        let cutoff = Utc::now().timestamp() - i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+        self.conn
+            .call(move |conn| {
+                Ok(conn
+                    .query_row(
+                        "SELECT 1 FROM not_found WHERE source = ?1 AND track_id = ?2 AND checked_at > ?3",
+                        rusqlite::params![source, track_id, cutoff],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Forget every track known to have no lyrics, so they are fetched again.
+    /// Returns the number of entries removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn clear_not_found(&self) -> Result<usize> {
+        self.conn
+            .call(|conn| Ok(conn.execute("DELETE FROM not_found", [])?))
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Delete no-lyrics entries older than `ttl`, which [`Self::is_not_found`] already
+    /// ignores. Returns the number of entries removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn prune_not_found(&self, ttl: Duration) -> Result<usize> {
+        let cutoff = Utc::now().timestamp() - i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+        self.conn
+            .call(move |conn| {
+                Ok(conn.execute("DELETE FROM not_found WHERE checked_at <= ?1", [cutoff])?)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Tracks prefetch job `job` has already finished, keyed by `(source, track ID)`, with
+    /// whether synced lyrics were found
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn prefetched_tracks(&self, job: &str) -> Result<HashMap<(String, String), bool>> {
+        let job = job.to_string();
+        self.conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT source, track_id, found FROM prefetch_progress WHERE job = ?1",
+                )?;
+                let rows =
+                    stmt.query_map([job], |row| Ok(((row.get(0)?, row.get(1)?), row.get(2)?)))?;
+                Ok(rows.collect::<std::result::Result<HashMap<_, _>, _>>()?)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Record that prefetch job `job` finished a track, and whether it found synced lyrics
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn mark_prefetched(
+        &self,
+        job: &str,
+        source: &str,
+        track_id: &str,
+        found: bool,
+    ) -> Result<()> {
+        let (job, source, track_id) = (job.to_string(), source.to_string(), track_id.to_string());
+        let now = Utc::now().timestamp();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r"
+                    INSERT OR REPLACE INTO prefetch_progress (job, source, track_id, found, done_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                ",
+                    rusqlite::params![job, source, track_id, found, now],
+                )?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Forget the progress of prefetch job `job`, so it starts over next time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn clear_prefetch_progress(&self, job: &str) -> Result<()> {
+        let job = job.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute("DELETE FROM prefetch_progress WHERE job = ?1", [job])?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Never show lyrics for `key`, a source track ID or an artist name. `label` is how the
+    /// entry is listed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn block(&self, kind: BlockKind, key: &str, label: &str) -> Result<()> {
+        let (key, label) = (normalize_key(key), label.to_string());
+        let now = Utc::now().timestamp();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT OR REPLACE INTO blocklist (kind, key, label, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![kind.as_str(), key, label, now],
+                )?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Show lyrics for a blocked track or artist again. Returns whether it was blocked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn unblock(&self, kind: BlockKind, key: &str) -> Result<bool> {
+        let key = normalize_key(key);
+        self.conn
+            .call(move |conn| {
+                Ok(conn.execute(
+                    "DELETE FROM blocklist WHERE kind = ?1 AND key = ?2",
+                    rusqlite::params![kind.as_str(), key],
+                )? > 0)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Blocked tracks and artists, newest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn blocked(&self) -> Result<Vec<BlockedEntry>> {
+        self.conn
+            .call(|conn| {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT kind, key, label, created_at FROM blocklist ORDER BY created_at DESC, key",
+                )?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                })?;
+                let mut entries = Vec::new();
+                for row in rows {
+                    let (kind, key, label, created_at) = row?;
+                    let Some(kind) = BlockKind::parse(&kind) else {
+                        warn!("Skipping blocklist entry of unknown kind {}", kind);
+                        continue;
+                    };
+                    entries.push(BlockedEntry {
+                        kind,
+                        key,
+                        label,
+                        created_at: DateTime::from_timestamp(created_at, 0)
+                            .unwrap_or_else(Utc::now),
+                    });
+                }
+                Ok(entries)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// `base` with the tracks and artists blocked in the cache added
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn blocklist(&self, base: &Blocklist) -> Result<Blocklist> {
+        let mut blocklist = base.clone();
+        for entry in self.blocked().await? {
+            blocklist.insert(entry.kind, &entry.key);
+        }
+        Ok(blocklist)
+    }
+
+    /// Delete old cache entries beyond TTL
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cleanup fails.
+    pub async fn cleanup(&self, ttl_days: u32) -> Result<usize> {
+        let cutoff = Utc::now().timestamp() - (i64::from(ttl_days) * 24 * 60 * 60);
+
+        self.conn
+            .call(move |conn| {
+                let deleted = conn.execute(
+                    "DELETE FROM lyrics WHERE fetched_at < ?1",
+                    rusqlite::params![cutoff],
+                )?;
+                Ok(deleted)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Cached tracks whose artist, title or album contains `search` (case-insensitive), or
+    /// all of them without a search, ordered by artist and title
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn list(&self, search: Option<&str>) -> Result<Vec<CachedTrack>> {
+        let pattern = format!("%{}%", search.unwrap_or_default().to_lowercase());
+        self.conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r"
+                    SELECT id, artist, track, album, provider, lyrics_type, fetched_at
+                    FROM lyrics
+                    WHERE LOWER(artist) LIKE ?1 OR LOWER(track) LIKE ?1
+                          OR LOWER(COALESCE(album, '')) LIKE ?1
+                    ORDER BY LOWER(artist), LOWER(track)
+                ",
+                )?;
+                let tracks = stmt
+                    .query_map([pattern], |row| {
+                        Ok(CachedTrack {
+                            id: row.get(0)?,
+                            artist: row.get(1)?,
+                            track: row.get(2)?,
+                            album: row.get(3)?,
+                            provider: row.get(4)?,
+                            synced: row.get::<_, String>(5)? == LyricsType::Synced.as_str(),
+                            fetched_at: DateTime::from_timestamp(row.get::<_, i64>(6)?, 0)
+                                .unwrap_or_else(Utc::now),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(tracks)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Delete the cached track `lyrics_id` along with its track ID mappings, translations and
+    /// alternatives. Returns whether it was cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn delete(&self, lyrics_id: i64) -> Result<bool> {
+        self.conn
+            .call(move |conn| {
+                let deleted = conn.execute("DELETE FROM lyrics WHERE id = ?1", [lyrics_id])?;
+                Ok(deleted > 0)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Count what the cache holds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn stats(&self) -> Result<CacheStats> {
+        self.conn
+            .call(|conn| {
+                let count = |table: &str| -> rusqlite::Result<usize> {
+                    conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                        row.get(0)
+                    })
+                };
+                let providers = {
+                    let mut stmt = conn.prepare(
+                        "SELECT provider, COUNT(*) FROM lyrics GROUP BY provider ORDER BY COUNT(*) DESC, provider",
+                    )?;
+                    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect::<std::result::Result<Vec<_>, _>>()?
+                };
+                let page_count: u64 =
+                    conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+                let page_size: u64 =
+                    conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+                Ok(CacheStats {
+                    tracks: count("lyrics")?,
+                    synced: conn.query_row(
+                        "SELECT COUNT(*) FROM lyrics WHERE lyrics_type = 'synced'",
+                        [],
+                        |row| row.get(0),
+                    )?,
+                    providers,
+                    track_ids: count("track_id_mapping")?,
+                    translations: count("lyrics_translations")?,
+                    bookmarks: count("bookmarks")?,
+                    not_found: count("not_found")?,
+                    size_bytes: page_count * page_size,
+                })
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Run `SQLite`'s integrity check. Returns the reported problems, empty if the database is healthy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the check cannot be run.
+    pub async fn integrity_check(&self) -> Result<Vec<String>> {
+        self.conn
+            .call(|conn| {
+                let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+                let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+                let problems = rows
+                    .collect::<std::result::Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .filter(|row| row != "ok")
+                    .collect();
+                Ok(problems)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Export every lyrics entry (with its track ID mappings and translations), timing offset
+    /// and bookmark to a portable archive at `path`, see [`crate::archive`]. Content is
+    /// decrypted, so the archive can be imported with another key; entries that fail to
+    /// decrypt are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read or the archive cannot be written.
+    pub async fn export(&self, path: &Path) -> Result<ArchiveSummary> {
+        let mut archive = self.conn.call(|conn| Ok(read_archive(conn)?)).await?;
+        decrypt_archive(&mut archive, self.cipher.as_deref());
+
+        archive.write(path).await?;
+        let summary = archive.summary();
+        info!("Exported lyrics cache to {:?}: {}", path, summary);
+        Ok(summary)
+    }
+
+    /// Merge the archive at `path` into the cache, in a single transaction. Lyrics replace
+    /// cached entries for the same track only if they were fetched more recently, archived
+    /// track ID mappings and timing offsets replace existing ones, and bookmarks not yet in
+    /// the cache are added. Returns the number of entries written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive cannot be read or is too new, content cannot be
+    /// encrypted, or the database update fails.
+    pub async fn import(&self, path: &Path) -> Result<ArchiveSummary> {
+        let mut archive = CacheArchive::read(path).await?;
+        if let Some(ref cipher) = self.cipher {
+            encrypt_archive(&mut archive, cipher)?;
+        }
+
+        let summary = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let summary = write_archive(&tx, &archive)?;
+                tx.commit()?;
+                Ok(summary)
+            })
+            .await?;
+        info!("Imported lyrics cache archive {:?}: {}", path, summary);
+        Ok(summary)
     }
 
-    #[test]
-    fn test_lyrics_type_from_str() {
-        assert_eq!(LyricsType::from_str("synced"), Some(LyricsType::Synced));
-        assert_eq!(LyricsType::from_str("unsynced"), Some(LyricsType::Unsynced));
-        assert_eq!(LyricsType::from_str("unknown"), None);
-        assert_eq!(LyricsType::from_str(""), None);
+    /// Return the space of deleted entries to the file system. A database created before
+    /// incremental auto-vacuum was turned on is converted with a full `VACUUM` the first time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vacuum fails.
+    pub async fn vacuum(&self) -> Result<()> {
+        self.conn
+            .call(|conn| {
+                let mode: i64 = conn.pragma_query_value(None, "auto_vacuum", |row| row.get(0))?;
+                if mode == INCREMENTAL_AUTO_VACUUM {
+                    conn.execute_batch("PRAGMA incremental_vacuum")?;
+                } else {
+                    info!("Converting the lyrics cache to incremental auto-vacuum");
+                    conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+                    conn.execute_batch("VACUUM")?;
+                }
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
     }
 
-    #[test]
-    fn test_format_timestamp_basic() {
-        use std::time::Duration;
+    /// When [`Self::mark_maintenance`] was last called, if ever
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn last_maintenance(&self) -> Result<Option<DateTime<Utc>>> {
+        let ran_at: Option<i64> = self
+            .conn
+            .call(|conn| {
+                Ok(conn
+                    .query_row("SELECT ran_at FROM cache_maintenance", [], |row| row.get(0))
+                    .optional()?)
+            })
+            .await?;
+        Ok(ran_at.and_then(|secs| DateTime::from_timestamp(secs, 0)))
+    }
 
-        // 12 seconds, 340 milliseconds
-        let duration = Duration::from_millis(12340);
-        assert_eq!(format_timestamp(duration), "00:12.34");
+    /// Record that maintenance ran just now
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database update fails.
+    pub async fn mark_maintenance(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT OR REPLACE INTO cache_maintenance (id, ran_at) VALUES (1, ?1)",
+                    [now],
+                )?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
     }
 
-    #[test]
-    fn test_format_timestamp_with_minutes() {
-        use std::time::Duration;
+    /// Checkpoint WAL for clean shutdown
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the WAL checkpoint fails.
+    pub async fn checkpoint(&self) -> Result<()> {
+        self.conn
+            .call(|conn| {
+                conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)")?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+}
 
-        // 1 minute, 30 seconds
-        let duration = Duration::from_secs(90);
-        assert_eq!(format_timestamp(duration), "01:30.00");
+/// Find the cached entry that incoming synced lyrics from `source` (provider, provider ID)
+/// would conflict with: same track, synced, from a different source, with differing text.
+fn conflicting_entry(
+    conn: &rusqlite::Connection,
+    metadata: &TrackMetadata,
+    incoming: &LrcFile,
+    source: (&str, &str),
+    cipher: Option<&ContentCipher>,
+) -> rusqlite::Result<Option<i64>> {
+    let existing: Option<(i64, String, String, String, String)> = conn
+        .query_row(
+            r"
+            SELECT id, provider, provider_id, lyrics_type, content FROM lyrics
+            WHERE artist = ?1 AND track = ?2 AND album IS ?3
+        ",
+            rusqlite::params![metadata.artist, metadata.track, metadata.album],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    let Some((id, provider, provider_id, lyrics_type, content)) = existing else {
+        return Ok(None);
+    };
+    if (provider.as_str(), provider_id.as_str()) == source
+        || lyrics_type != LyricsType::Synced.as_str()
+    {
+        return Ok(None);
     }
+    let Some(cached) = decrypt_with(cipher, &content).and_then(|c| LrcFile::parse(&c).ok()) else {
+        return Ok(None);
+    };
+    Ok(LyricsDiff::between(&cached, incoming)
+        .is_conflict()
+        .then_some(id))
+}
 
-    #[test]
-    fn test_format_timestamp_zero() {
-        use std::time::Duration;
+/// Record (or refresh) incoming lyrics from `source` as a conflict against cached entry `lyrics_id`
+/// Tables whose `content` column is encrypted when a cipher is set
+const ENCRYPTED_TABLES: [&str; 4] = [
+    "lyrics",
+    "lyrics_alternatives",
+    "lyrics_conflicts",
+    "lyrics_translations",
+];
+
+/// Encrypt the plaintext `content` rows of `table`, returning how many were converted
+fn encrypt_table(
+    tx: &rusqlite::Transaction<'_>,
+    cipher: &ContentCipher,
+    table: &str,
+) -> rusqlite::Result<usize> {
+    let rows = {
+        let mut stmt = tx.prepare(&format!("SELECT rowid, content FROM {table}"))?;
+        stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+    };
+
+    let mut converted = 0;
+    for (rowid, content) in rows {
+        if ContentCipher::is_encrypted(&content) {
+            continue;
+        }
+        let Ok(encrypted) = cipher.encrypt(&content) else {
+            continue;
+        };
+        tx.execute(
+            &format!("UPDATE {table} SET content = ?1 WHERE rowid = ?2"),
+            rusqlite::params![encrypted, rowid],
+        )?;
+        converted += 1;
+    }
+    Ok(converted)
+}
+
+fn insert_conflict(
+    conn: &rusqlite::Connection,
+    lyrics_id: i64,
+    source: (&str, &str),
+    content: &str,
+    now: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        r"
+        INSERT INTO lyrics_conflicts (lyrics_id, provider, provider_id, content, fetched_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT(lyrics_id, provider, provider_id) DO UPDATE SET
+            content = excluded.content,
+            fetched_at = excluded.fetched_at
+    ",
+        rusqlite::params![lyrics_id, source.0, source.1, content, now],
+    )?;
+    Ok(())
+}
 
-        let duration = Duration::ZERO;
-        assert_eq!(format_timestamp(duration), "00:00.00");
+/// Insert or update the lyrics entry for `metadata` with content from `source`, unless the
+/// user pinned another provider's lyrics for it. Returns the entry's ID either way.
+fn upsert_lyrics(
+    conn: &rusqlite::Connection,
+    metadata: &TrackMetadata,
+    source: (&str, &str),
+    (lyrics_type, content): (&str, &str),
+    now: i64,
+) -> rusqlite::Result<i64> {
+    let updated: Option<i64> = conn
+        .query_row(
+            r"
+            INSERT INTO lyrics (artist, track, album, duration_ms, provider, provider_id, lyrics_type, content, fetched_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(artist, track, album) DO UPDATE SET
+                provider = excluded.provider,
+                provider_id = excluded.provider_id,
+                lyrics_type = excluded.lyrics_type,
+                content = excluded.content,
+                fetched_at = excluded.fetched_at
+            WHERE lyrics.pinned = 0 OR lyrics.provider = excluded.provider
+            RETURNING id
+        ",
+            rusqlite::params![
+                metadata.artist,
+                metadata.track,
+                metadata.album,
+                metadata.duration_ms,
+                source.0,
+                source.1,
+                lyrics_type,
+                content,
+                now
+            ],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match updated {
+        Some(id) => Ok(id),
+        None => conn.query_row(
+            "SELECT id FROM lyrics WHERE artist = ?1 AND track = ?2 AND album IS ?3",
+            rusqlite::params![metadata.artist, metadata.track, metadata.album],
+            |row| row.get(0),
+        ),
     }
+}
 
-    #[test]
-    fn test_format_timestamp_long_duration() {
-        use std::time::Duration;
+/// Read the whole cache into an archive, with content as stored
+fn read_archive(conn: &rusqlite::Connection) -> rusqlite::Result<CacheArchive> {
+    let mut mappings: HashMap<i64, Vec<ArchivedMapping>> = HashMap::new();
+    let mut stmt =
+        conn.prepare("SELECT lyrics_id, provider, provider_track_id FROM track_id_mapping")?;
+    for row in stmt.query_map([], |row| {
+        Ok((
+            row.get(0)?,
+            ArchivedMapping {
+                provider: row.get(1)?,
+                provider_track_id: row.get(2)?,
+            },
+        ))
+    })? {
+        let (lyrics_id, mapping) = row?;
+        mappings.entry(lyrics_id).or_default().push(mapping);
+    }
 
-        // 5 minutes, 45 seconds, 670 ms
-        let duration = Duration::from_millis(5 * 60 * 1000 + 45 * 1000 + 670);
-        assert_eq!(format_timestamp(duration), "05:45.67");
+    let mut translations: HashMap<i64, Vec<ArchivedTranslation>> = HashMap::new();
+    let mut stmt = conn
+        .prepare("SELECT lyrics_id, target_lang, content, fetched_at FROM lyrics_translations")?;
+    for row in stmt.query_map([], |row| {
+        Ok((
+            row.get(0)?,
+            ArchivedTranslation {
+                target_lang: row.get(1)?,
+                content: row.get(2)?,
+                fetched_at: row.get(3)?,
+            },
+        ))
+    })? {
+        let (lyrics_id, translation) = row?;
+        translations.entry(lyrics_id).or_default().push(translation);
     }
 
-    #[test]
-    fn test_serialize_lrc_simple() {
-        use std::time::Duration;
+    let mut stmt = conn.prepare(
+        r"
+        SELECT id, artist, track, album, duration_ms, provider, provider_id, lyrics_type, content, fetched_at
+        FROM lyrics
+        ORDER BY id
+    ",
+    )?;
+    let lyrics = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            Ok(ArchivedLyrics {
+                artist: row.get(1)?,
+                track: row.get(2)?,
+                album: row.get(3)?,
+                duration_ms: row.get(4)?,
+                provider: row.get(5)?,
+                provider_id: row.get(6)?,
+                lyrics_type: row.get(7)?,
+                content: row.get(8)?,
+                fetched_at: row.get(9)?,
+                mappings: mappings.remove(&id).unwrap_or_default(),
+                translations: translations.remove(&id).unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt =
+        conn.prepare("SELECT provider, provider_track_id, offset_ms FROM track_offsets")?;
+    let offsets = stmt
+        .query_map([], |row| {
+            Ok(ArchivedOffset {
+                provider: row.get(0)?,
+                provider_track_id: row.get(1)?,
+                offset_ms: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT artist, track, album, position_ms, text, created_at FROM bookmarks ORDER BY id",
+    )?;
+    let bookmarks = stmt
+        .query_map([], |row| {
+            Ok(ArchivedBookmark {
+                artist: row.get(0)?,
+                track: row.get(1)?,
+                album: row.get(2)?,
+                position_ms: row.get(3)?,
+                text: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(CacheArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now().timestamp(),
+        lyrics,
+        offsets,
+        bookmarks,
+    })
+}
 
-        let lrc = LrcFile {
-            metadata: LrcMetadata::default(),
-            lines: vec![
-                LrcLine {
-                    start_time: Duration::from_millis(5000),
-                    text: "Hello world".to_string(),
-                    words: None,
-                },
-                LrcLine {
-                    start_time: Duration::from_millis(10000),
-                    text: "Second line".to_string(),
-                    words: None,
-                },
-            ],
+/// Decrypt the content of an archive read from the cache, dropping anything that cannot be
+/// decrypted
+fn decrypt_archive(archive: &mut CacheArchive, cipher: Option<&ContentCipher>) {
+    let before = archive.summary();
+    archive.lyrics.retain_mut(|lyrics| {
+        let Some(content) = decrypt_with(cipher, &lyrics.content) else {
+            return false;
         };
+        lyrics.content = content;
+        lyrics.translations.retain_mut(|translation| {
+            decrypt_with(cipher, &translation.content)
+                .map(|content| translation.content = content)
+                .is_some()
+        });
+        true
+    });
+    archive.bookmarks.retain_mut(|bookmark| {
+        decrypt_with(cipher, &bookmark.text)
+            .map(|text| bookmark.text = text)
+            .is_some()
+    });
+
+    let after = archive.summary();
+    if after != before {
+        warn!(
+            "Left {} lyrics and {} bookmarks that could not be decrypted out of the export",
+            before.lyrics - after.lyrics,
+            before.bookmarks - after.bookmarks
+        );
+    }
+}
 
-        let serialized = serialize_lrc(&lrc);
-        assert!(serialized.contains("[00:05.00]Hello world"));
-        assert!(serialized.contains("[00:10.00]Second line"));
+/// Encrypt the content of an archive for an encrypted cache
+fn encrypt_archive(archive: &mut CacheArchive, cipher: &ContentCipher) -> Result<()> {
+    for lyrics in &mut archive.lyrics {
+        lyrics.content = cipher.encrypt(&lyrics.content)?;
+        for translation in &mut lyrics.translations {
+            translation.content = cipher.encrypt(&translation.content)?;
+        }
     }
+    for bookmark in &mut archive.bookmarks {
+        bookmark.text = cipher.encrypt(&bookmark.text)?;
+    }
+    Ok(())
+}
 
-    #[test]
-    fn test_serialize_lrc_with_metadata() {
-        use std::time::Duration;
+/// Merge an archive into the cache, see [`LyricsCache::import`]
+fn write_archive(
+    conn: &rusqlite::Connection,
+    archive: &CacheArchive,
+) -> rusqlite::Result<ArchiveSummary> {
+    let mut summary = ArchiveSummary::default();
+
+    for lyrics in &archive.lyrics {
+        if LyricsType::from_str(&lyrics.lyrics_type).is_none() {
+            warn!(
+                "Skipping archived lyrics for {} - {} with unknown type {:?}",
+                lyrics.artist, lyrics.track, lyrics.lyrics_type
+            );
+            continue;
+        }
+        let lyrics_id = import_lyrics(conn, lyrics, &mut summary)?;
+        conn.execute(
+            r"
+            INSERT INTO lyrics_alternatives (lyrics_id, provider, provider_id, lyrics_type, content, fetched_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(lyrics_id, provider) DO UPDATE SET
+                provider_id = excluded.provider_id,
+                lyrics_type = excluded.lyrics_type,
+                content = excluded.content,
+                fetched_at = excluded.fetched_at
+            WHERE excluded.fetched_at > lyrics_alternatives.fetched_at
+        ",
+            rusqlite::params![
+                lyrics_id,
+                lyrics.provider,
+                lyrics.provider_id,
+                lyrics.lyrics_type,
+                lyrics.content,
+                lyrics.fetched_at
+            ],
+        )?;
+
+        for mapping in &lyrics.mappings {
+            conn.execute(
+                r"
+                INSERT INTO track_id_mapping (provider, provider_track_id, lyrics_id, created_at)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(provider, provider_track_id) DO UPDATE SET
+                    lyrics_id = excluded.lyrics_id
+            ",
+                rusqlite::params![
+                    mapping.provider,
+                    mapping.provider_track_id,
+                    lyrics_id,
+                    lyrics.fetched_at
+                ],
+            )?;
+        }
+        for translation in &lyrics.translations {
+            conn.execute(
+                r"
+                INSERT INTO lyrics_translations (lyrics_id, target_lang, content, fetched_at)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(lyrics_id, target_lang) DO UPDATE SET
+                    content = excluded.content,
+                    fetched_at = excluded.fetched_at
+                WHERE excluded.fetched_at > lyrics_translations.fetched_at
+            ",
+                rusqlite::params![
+                    lyrics_id,
+                    translation.target_lang,
+                    translation.content,
+                    translation.fetched_at
+                ],
+            )?;
+        }
+    }
 
-        let lrc = LrcFile {
-            metadata: LrcMetadata {
-                title: Some("Test Song".to_string()),
-                artist: Some("Test Artist".to_string()),
-                album: Some("Test Album".to_string()),
-                offset: 0,
-                ..Default::default()
-            },
-            lines: vec![LrcLine {
-                start_time: Duration::from_millis(5000),
-                text: "Lyrics here".to_string(),
-                words: None,
-            }],
-        };
+    for offset in &archive.offsets {
+        conn.execute(
+            "INSERT OR REPLACE INTO track_offsets (provider, provider_track_id, offset_ms) VALUES (?1, ?2, ?3)",
+            rusqlite::params![offset.provider, offset.provider_track_id, offset.offset_ms],
+        )?;
+        summary.offsets += 1;
+    }
 
-        let serialized = serialize_lrc(&lrc);
-        assert!(serialized.contains("[ti:Test Song]"));
-        assert!(serialized.contains("[ar:Test Artist]"));
-        assert!(serialized.contains("[al:Test Album]"));
+    for bookmark in &archive.bookmarks {
+        summary.bookmarks += conn.execute(
+            r"
+            INSERT INTO bookmarks (artist, track, album, position_ms, text, created_at)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6
+            WHERE NOT EXISTS (
+                SELECT 1 FROM bookmarks
+                WHERE artist = ?1 AND track = ?2 AND album IS ?3 AND position_ms = ?4 AND created_at = ?6
+            )
+        ",
+            rusqlite::params![
+                bookmark.artist,
+                bookmark.track,
+                bookmark.album,
+                bookmark.position_ms,
+                bookmark.text,
+                bookmark.created_at
+            ],
+        )?;
     }
 
-    #[test]
-    fn test_serialize_lrc_with_offset() {
-        use std::time::Duration;
+    Ok(summary)
+}
 
-        let lrc = LrcFile {
-            metadata: LrcMetadata {
-                offset: 500,
-                ..Default::default()
-            },
-            lines: vec![LrcLine {
-                start_time: Duration::from_millis(5000),
-                text: "Test".to_string(),
-                words: None,
-            }],
-        };
+/// Insert an archived lyrics entry, or update the cached entry for the same track if the
+/// archived one is newer. Returns the ID of the entry in the cache.
+fn import_lyrics(
+    conn: &rusqlite::Connection,
+    lyrics: &ArchivedLyrics,
+    summary: &mut ArchiveSummary,
+) -> rusqlite::Result<i64> {
+    // `album IS ?` so entries without an album match too, which the UNIQUE constraint misses
+    let existing: Option<(i64, i64)> = conn
+        .query_row(
+            "SELECT id, fetched_at FROM lyrics WHERE artist = ?1 AND track = ?2 AND album IS ?3",
+            rusqlite::params![lyrics.artist, lyrics.track, lyrics.album],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    match existing {
+        Some((id, fetched_at)) if fetched_at >= lyrics.fetched_at => Ok(id),
+        Some((id, _)) => {
+            conn.execute(
+                r"
+                UPDATE lyrics
+                SET duration_ms = ?2, provider = ?3, provider_id = ?4, lyrics_type = ?5, content = ?6, fetched_at = ?7
+                WHERE id = ?1
+            ",
+                rusqlite::params![
+                    id,
+                    lyrics.duration_ms,
+                    lyrics.provider,
+                    lyrics.provider_id,
+                    lyrics.lyrics_type,
+                    lyrics.content,
+                    lyrics.fetched_at
+                ],
+            )?;
+            summary.lyrics += 1;
+            Ok(id)
+        }
+        None => {
+            conn.execute(
+                r"
+                INSERT INTO lyrics (artist, track, album, duration_ms, provider, provider_id, lyrics_type, content, fetched_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ",
+                rusqlite::params![
+                    lyrics.artist,
+                    lyrics.track,
+                    lyrics.album,
+                    lyrics.duration_ms,
+                    lyrics.provider,
+                    lyrics.provider_id,
+                    lyrics.lyrics_type,
+                    lyrics.content,
+                    lyrics.fetched_at
+                ],
+            )?;
+            summary.lyrics += 1;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
 
-        let serialized = serialize_lrc(&lrc);
-        assert!(serialized.contains("[offset:500]"));
+/// Decrypt stored content if a cipher is set. Returns `None` for content that
+/// cannot be read with the current key (or is encrypted while encryption is off).
+fn decrypt_with(cipher: Option<&ContentCipher>, content: &str) -> Option<String> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(content).ok(),
+        None if ContentCipher::is_encrypted(content) => None,
+        None => Some(content.to_string()),
     }
+}
 
-    #[test]
-    fn test_serialize_lrc_enhanced_format() {
-        use std::time::Duration;
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
 
-        let lrc = LrcFile {
-            metadata: LrcMetadata::default(),
-            lines: vec![LrcLine {
-                start_time: Duration::from_millis(5000),
-                text: "Hello world".to_string(),
-                words: Some(vec![
-                    LrcWord {
-                        start_time: Duration::from_millis(5000),
-                        end_time: Some(Duration::from_millis(5500)),
-                        text: "Hello".to_string(),
-                    },
-                    LrcWord {
-                        start_time: Duration::from_millis(5500),
-                        end_time: Some(Duration::from_millis(6000)),
-                        text: "world".to_string(),
-                    },
-                ]),
-            }],
-        };
+    #[test]
+    fn test_lyrics_type_as_str() {
+        assert_eq!(LyricsType::Synced.as_str(), "synced");
+        assert_eq!(LyricsType::Unsynced.as_str(), "unsynced");
+    }
 
-        let serialized = serialize_lrc(&lrc);
-        assert!(serialized.contains("[00:05.00]"));
-        assert!(serialized.contains("<00:05.00>"));
-        assert!(serialized.contains("Hello"));
-        assert!(serialized.contains("<00:05.50>"));
-        assert!(serialized.contains("world"));
+    #[test]
+    fn test_lyrics_type_from_str() {
+        assert_eq!(LyricsType::from_str("synced"), Some(LyricsType::Synced));
+        assert_eq!(LyricsType::from_str("unsynced"), Some(LyricsType::Unsynced));
+        assert_eq!(LyricsType::from_str("unknown"), None);
+        assert_eq!(LyricsType::from_str(""), None);
     }
 
     #[test]
@@ -665,4 +2270,627 @@ mod tests {
         assert!(result.is_found());
         assert_eq!(result.text(), Some("Plain text lyrics".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_encrypted_store_and_lookup() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-encrypted-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path)
+            .await
+            .unwrap()
+            .with_encryption(ContentCipher::from_key(&[3u8; 32]).unwrap());
+
+        let metadata = TrackMetadata {
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: None,
+            duration_ms: Some(180_000),
+        };
+        cache
+            .store(
+                "spotify",
+                "track123",
+                &LyricsResult::Unsynced("Secret words".to_string()),
+                &metadata,
+                "lrclib",
+                "1",
+            )
+            .await
+            .unwrap();
+
+        let cached = cache
+            .get_by_provider_id("spotify", "track123")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.content, "Secret words");
+        // The lyrics provider's own ID finds the same entry
+        let by_lyrics_id = cache
+            .get_by_provider_id("lrclib", "1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(by_lyrics_id.id, cached.id);
+
+        // Without the key, encrypted rows are treated as cache misses
+        let plain = LyricsCache::open(&path).await.unwrap();
+        assert!(
+            plain
+                .get_by_provider_id("spotify", "track123")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_existing_converts_every_table() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-encrypt-existing-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let plain = LyricsCache::open(&path).await.unwrap();
+        let metadata = TrackMetadata {
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: None,
+            duration_ms: Some(180_000),
+        };
+        let mine = LrcFile::parse("[00:01.00]Hello there\n[00:05.00]General Kenobi").unwrap();
+        let theirs = LrcFile::parse("[00:01.00]Goodbye now\n[00:05.00]Something else").unwrap();
+        plain
+            .store(
+                "spotify",
+                "t1",
+                &LyricsResult::Synced(mine),
+                &metadata,
+                "lrclib",
+                "1",
+            )
+            .await
+            .unwrap();
+        plain
+            .store(
+                "spotify",
+                "t1",
+                &LyricsResult::Synced(theirs),
+                &metadata,
+                "spotify",
+                "2",
+            )
+            .await
+            .unwrap();
+        let lyrics_id = plain
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap()
+            .id;
+        plain
+            .store_translation(lyrics_id, "fr", &["Bonjour".to_string()])
+            .await
+            .unwrap();
+
+        let cache = LyricsCache::open(&path)
+            .await
+            .unwrap()
+            .with_encryption(ContentCipher::from_key(&[3u8; 32]).unwrap());
+        // The entry, both providers' alternatives, the conflict and the translation
+        assert_eq!(cache.encrypt_existing().await.unwrap(), 5);
+        // A second pass finds nothing left to convert
+        assert_eq!(cache.encrypt_existing().await.unwrap(), 0);
+
+        let plaintext = plain
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare(
+                    r"
+                    SELECT content FROM lyrics
+                    UNION ALL SELECT content FROM lyrics_alternatives
+                    UNION ALL SELECT content FROM lyrics_conflicts
+                    UNION ALL SELECT content FROM lyrics_translations
+                ",
+                )?;
+                let contents = stmt
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(contents
+                    .into_iter()
+                    .filter(|content| !ContentCipher::is_encrypted(content))
+                    .count())
+            })
+            .await
+            .unwrap();
+        assert_eq!(plaintext, 0);
+
+        // Everything still reads back through the cipher
+        let conflicts = cache.conflicts().await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].content.contains("Goodbye now"));
+        assert_eq!(
+            cache.get_translation(lyrics_id, "fr").await.unwrap(),
+            Some(vec!["Bonjour".to_string()])
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_disagreeing_provider_is_kept_as_conflict() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-conflict-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        let metadata = TrackMetadata {
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: None,
+            duration_ms: Some(180_000),
+        };
+        let mine = LrcFile::parse("[00:01.00]Hello there\n[00:05.00]General Kenobi").unwrap();
+        let theirs =
+            LrcFile::parse("[00:01.00]Goodbye now\n[00:05.00]Something else\n[00:09.00]Extra line")
+                .unwrap();
+
+        cache
+            .store(
+                "spotify",
+                "t1",
+                &LyricsResult::Synced(mine),
+                &metadata,
+                "lrclib",
+                "1",
+            )
+            .await
+            .unwrap();
+        cache
+            .store(
+                "spotify",
+                "t1",
+                &LyricsResult::Synced(theirs),
+                &metadata,
+                "spotify",
+                "2",
+            )
+            .await
+            .unwrap();
+
+        // The cached lyrics stay in place until the conflict is resolved
+        let cached = cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.provider, "lrclib");
+
+        let conflicts = cache.conflicts().await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].provider, "spotify");
+        assert_eq!(conflicts[0].track, "Song");
+
+        let diff = cache.conflict_diff(conflicts[0].id).await.unwrap().unwrap();
+        assert!(diff.is_conflict());
+
+        cache
+            .resolve_conflict(conflicts[0].id, ConflictResolution::TakeTheirs)
+            .await
+            .unwrap();
+        let cached = cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.provider, "spotify");
+        assert!(cached.content.contains("Goodbye now"));
+        assert!(cache.conflicts().await.unwrap().is_empty());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_round_trip() {
+        use crate::source::MusicSource;
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-bookmarks-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path)
+            .await
+            .unwrap()
+            .with_encryption(ContentCipher::from_key(&[7; 32]).unwrap());
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "t1",
+            "Song",
+            "Artist",
+            "",
+            Duration::from_mins(3),
+        );
+
+        let first = cache
+            .add_bookmark(&track, 1000, "Hello there")
+            .await
+            .unwrap();
+        let second = cache
+            .add_bookmark(&track, 5000, "General Kenobi")
+            .await
+            .unwrap();
+
+        let bookmarks = cache.bookmarks().await.unwrap();
+        assert_eq!(
+            bookmarks.iter().map(|b| b.id).collect::<Vec<_>>(),
+            [second, first]
+        );
+        assert_eq!(bookmarks[0].text, "General Kenobi");
+        assert_eq!(bookmarks[0].position_ms, 5000);
+        assert_eq!(bookmarks[0].album, None);
+
+        cache.delete_bookmark(second).await.unwrap();
+        assert_eq!(cache.bookmarks().await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_expires_and_clears() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-not-found-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        let week = Duration::from_hours(7 * 24);
+
+        assert!(!cache.is_not_found("spotify", "t1", week).await.unwrap());
+        cache.mark_not_found("spotify", "t1").await.unwrap();
+        assert!(cache.is_not_found("spotify", "t1", week).await.unwrap());
+        assert!(!cache.is_not_found("spotify", "t2", week).await.unwrap());
+        assert!(
+            !cache
+                .is_not_found("spotify", "t1", Duration::ZERO)
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(cache.clear_not_found().await.unwrap(), 1);
+        assert!(!cache.is_not_found("spotify", "t1", week).await.unwrap());
+
+        // Pruning keeps entries that have not expired yet
+        cache.mark_not_found("spotify", "t1").await.unwrap();
+        assert_eq!(cache.prune_not_found(week).await.unwrap(), 0);
+        assert_eq!(cache.prune_not_found(Duration::ZERO).await.unwrap(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_round_trip() {
+        use crate::source::MusicSource;
+
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-blocklist-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "T1",
+            "Rain",
+            "Sleep Sounds",
+            "Album",
+            Duration::from_mins(10),
+        );
+
+        cache
+            .block(BlockKind::Artist, "Sleep Sounds", "Sleep Sounds")
+            .await
+            .unwrap();
+        let blocked = cache.blocked().await.unwrap();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].kind, BlockKind::Artist);
+        assert_eq!(blocked[0].key, "sleep sounds");
+
+        let blocklist = cache.blocklist(&Blocklist::new()).await.unwrap();
+        assert_eq!(blocklist.blocks(&track), Some(BlockKind::Artist));
+
+        assert!(
+            cache
+                .unblock(BlockKind::Artist, "SLEEP SOUNDS")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !cache
+                .unblock(BlockKind::Artist, "Sleep Sounds")
+                .await
+                .unwrap()
+        );
+        let blocklist = cache.blocklist(&Blocklist::new()).await.unwrap();
+        assert_eq!(blocklist.blocks(&track), None);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_list_delete_and_stats() {
+        let path =
+            std::env::temp_dir().join(format!("versualizer-cache-list-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        for (id, track, lyrics) in [
+            ("t1", "Song", LyricsResult::Unsynced("Plain".to_string())),
+            (
+                "t2",
+                "Other Song",
+                LyricsResult::Synced(LrcFile::parse("[00:01.00]Synced").unwrap()),
+            ),
+        ] {
+            let metadata = TrackMetadata {
+                artist: "Artist".to_string(),
+                track: track.to_string(),
+                album: None,
+                duration_ms: None,
+            };
+            cache
+                .store("spotify", id, &lyrics, &metadata, "lrclib", id)
+                .await
+                .unwrap();
+        }
+
+        let all = cache.list(None).await.unwrap();
+        assert_eq!(
+            all.iter().map(|t| t.track.as_str()).collect::<Vec<_>>(),
+            ["Other Song", "Song"]
+        );
+        assert!(all[0].synced && !all[1].synced);
+        let found = cache.list(Some("OTHER")).await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.tracks, stats.synced), (2, 1));
+        assert_eq!(stats.providers, [("lrclib".to_string(), 2)]);
+        assert!(stats.size_bytes > 0);
+
+        assert!(cache.delete(found[0].id).await.unwrap());
+        assert!(!cache.delete(found[0].id).await.unwrap());
+        assert!(
+            cache
+                .get_by_provider_id("spotify", "t2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(cache.stats().await.unwrap().tracks, 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_track_offsets() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-offsets-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+
+        assert_eq!(cache.get_offset("spotify", "t1").await.unwrap(), 0);
+        cache.set_offset("spotify", "t1", -300).await.unwrap();
+        cache.set_offset("spotify", "t2", 150).await.unwrap();
+        assert_eq!(cache.get_offset("spotify", "t1").await.unwrap(), -300);
+        assert_eq!(cache.get_offset("spotify", "t2").await.unwrap(), 150);
+
+        cache.set_offset("spotify", "t1", 0).await.unwrap();
+        assert_eq!(cache.get_offset("spotify", "t1").await.unwrap(), 0);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_between_keys() {
+        let dir =
+            std::env::temp_dir().join(format!("versualizer-cache-archive-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let source = LyricsCache::open(&dir.join("source.db"))
+            .await
+            .unwrap()
+            .with_encryption(ContentCipher::from_key(&[5u8; 32]).unwrap());
+        let metadata = TrackMetadata {
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: None,
+            duration_ms: Some(180_000),
+        };
+        let lyrics_id = source
+            .store(
+                "spotify",
+                "t1",
+                &LyricsResult::Unsynced("Secret words".to_string()),
+                &metadata,
+                "lrclib",
+                "1",
+            )
+            .await
+            .unwrap();
+        source
+            .store_translation(lyrics_id, "fr", &["Mots secrets".to_string()])
+            .await
+            .unwrap();
+        source.set_offset("spotify", "t1", -250).await.unwrap();
+        let track = TrackInfo::new(
+            crate::source::MusicSource::Spotify,
+            "t1",
+            "Song",
+            "Artist",
+            "",
+            Duration::from_mins(3),
+        );
+        source.add_bookmark(&track, 1000, "Secret").await.unwrap();
+
+        let archive = dir.join("cache.json.gz");
+        let exported = source.export(&archive).await.unwrap();
+        assert_eq!(
+            exported,
+            ArchiveSummary {
+                lyrics: 1,
+                offsets: 1,
+                bookmarks: 1
+            }
+        );
+
+        let target = LyricsCache::open(&dir.join("target.db"))
+            .await
+            .unwrap()
+            .with_encryption(ContentCipher::from_key(&[6u8; 32]).unwrap());
+        assert_eq!(target.import(&archive).await.unwrap(), exported);
+        let cached = target
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.content, "Secret words");
+        assert_eq!(
+            target.get_translation(cached.id, "fr").await.unwrap(),
+            Some(vec!["Mots secrets".to_string()])
+        );
+        assert_eq!(target.get_offset("spotify", "t1").await.unwrap(), -250);
+        assert_eq!(target.bookmarks().await.unwrap()[0].text, "Secret");
+
+        // Importing again changes nothing but the offsets
+        let again = target.import(&archive).await.unwrap();
+        assert_eq!((again.lyrics, again.bookmarks), (0, 0));
+        assert_eq!(target.bookmarks().await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_alternatives_are_kept_and_pinned() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-cache-alternatives-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        let metadata = TrackMetadata {
+            artist: "Artist".to_string(),
+            track: "Song".to_string(),
+            album: Some("Album".to_string()),
+            duration_ms: Some(180_000),
+        };
+        let lyrics = LyricsResult::Synced(LrcFile::parse("[00:01.00]Hello").unwrap());
+        let store = |provider: &'static str| {
+            let (cache, metadata, lyrics) = (&cache, &metadata, &lyrics);
+            async move {
+                cache
+                    .store("spotify", "t1", lyrics, metadata, provider, "1")
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let id = store("lrclib").await;
+        assert_eq!(store("musixmatch").await, id);
+        let providers: Vec<_> = cache
+            .alternatives(id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|alternative| alternative.provider)
+            .collect();
+        assert_eq!(providers, ["lrclib", "musixmatch"]);
+
+        assert!(cache.select_alternative(id, "lrclib").await.unwrap());
+        assert!(!cache.select_alternative(id, "genius").await.unwrap());
+        // The pinned choice survives fetches from other providers
+        store("musixmatch").await;
+        let cached = cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.provider, "lrclib");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_alternatives_migration_backfills_entries() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut conn, &MIGRATIONS[..1]).unwrap();
+        conn.execute(
+            r"
+            INSERT INTO lyrics (artist, track, album, provider, provider_id, lyrics_type, content, fetched_at)
+            VALUES ('Artist', 'Song', NULL, 'lrclib', '1', 'synced', '[00:01.00]Hello', 0)
+        ",
+            [],
+        )
+        .unwrap();
+
+        migrate(&mut conn, MIGRATIONS).unwrap();
+        let alternatives: i64 = conn
+            .query_row("SELECT COUNT(*) FROM lyrics_alternatives", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(alternatives, 1);
+    }
+
+    #[test]
+    fn test_migrations_run_once_in_order() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let migrations = [
+            BASELINE_SCHEMA_SQL,
+            "ALTER TABLE not_found ADD COLUMN note TEXT",
+        ];
+
+        assert_eq!(migrate(&mut conn, &migrations[..1]).unwrap(), 0);
+        assert_eq!(migrate(&mut conn, &migrations).unwrap(), 1);
+        assert_eq!(migrate(&mut conn, &migrations).unwrap(), 2);
+        conn.execute(
+            "INSERT INTO not_found (source, track_id, checked_at, note) VALUES ('s', 't', 0, 'n')",
+            [],
+        )
+        .unwrap();
+
+        // A database from a newer release is refused rather than modified
+        assert!(matches!(
+            migrate(&mut conn, &migrations[..1]),
+            Err(CoreError::CacheSchemaTooNew {
+                found: 2,
+                supported: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let migrations = [
+            BASELINE_SCHEMA_SQL,
+            "CREATE TABLE extra (id INTEGER); SELECT * FROM missing_table",
+        ];
+
+        assert!(migrate(&mut conn, &migrations).is_err());
+        let version: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+        assert!(
+            conn.prepare("SELECT * FROM extra").is_err(),
+            "partial migration was kept"
+        );
+    }
 }
@@ -1,10 +1,12 @@
 use crate::error::{CoreError, Result};
+use crate::locale::{AUTO_LOCALE, LocaleFormatter};
 use crate::source::MusicSource;
 use const_format::concatcp;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Main configuration structure (source-agnostic)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,9 +17,24 @@ pub struct VersualizerConfig {
     pub lyrics: LyricsConfig,
     /// UI configuration
     pub ui: UiConfig,
+    /// Lyrics cache database selection
+    #[serde(default)]
+    pub cache: CacheConfig,
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Lyric timing adjustments
+    #[serde(default)]
+    pub sync: SyncConfig,
+    /// Plain-text files with the current line and track
+    #[serde(default)]
+    pub now_singing: NowSingingConfig,
+    /// Network behavior shared by all providers
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// Local HTTP and WebSocket server with the live lyric state
+    #[serde(default)]
+    pub server: ServerConfig,
     /// Provider-specific configurations (dynamic)
     #[serde(default)]
     pub providers: ProvidersConfig,
@@ -72,21 +89,399 @@ impl ProvidersConfig {
     }
 }
 
+// Independent on/off settings, each its own key in config.toml
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LyricsConfig {
     /// Provider priority: providers are tried in order
     #[serde(default = "default_providers")]
     pub providers: Vec<LyricsProviderType>,
+    /// Encrypt cached lyrics content at rest (key kept in the secret store)
+    #[serde(default)]
+    pub encrypt_cache: bool,
+    /// Where secrets are kept: the cache encryption key, the Spotify sign-in token, and
+    /// credentials left out of the config file
+    #[serde(default)]
+    pub secret_store: SecretStoreKind,
+    /// Folder that every newly fetched synced lyric is also written to as an `.lrc` file
+    #[serde(default)]
+    pub mirror_dir: Option<String>,
+    /// Show plain lyrics when no provider has synced lyrics for a track
+    #[serde(default = "default_allow_unsynced")]
+    pub allow_unsynced: bool,
+    /// Query all providers at once and use the first synced lyrics to arrive
+    #[serde(default)]
+    pub parallel_fetch: bool,
+    /// Seconds to wait before fetching again when every provider was unreachable
+    #[serde(default = "default_retry_after_secs")]
+    pub retry_after_secs: u64,
+    /// How many times to fetch again while the same track plays (0 = never)
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    /// Mask profanity before lyrics are shown, with the words in `processors.censor_words`
+    /// or a built-in list when that is empty
+    #[serde(default)]
+    pub censor: bool,
+    /// Backend for on-demand lyric translation
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    /// Text post-processing applied to fetched lyrics before they are cached and shown
+    #[serde(default)]
+    pub processors: ProcessorsConfig,
+    /// Clean-up of track and artist names before providers are searched with them
+    #[serde(default)]
+    pub query: QueryConfig,
+    /// Tracks and artists whose lyrics are never shown
+    #[serde(default)]
+    pub blocklist: BlocklistConfig,
+    /// External provider executables by provider name (`[lyrics.plugins]`), tried in name
+    /// order after the built-in providers
+    #[serde(default)]
+    pub plugins: BTreeMap<String, String>,
+}
+
+impl LyricsConfig {
+    /// The mirror folder with a leading `~` or `~/` expanded to the home directory.
+    /// `~user` forms are left as they are.
+    #[must_use]
+    pub fn mirror_dir_path(&self) -> Option<PathBuf> {
+        expand_home(self.mirror_dir.as_deref()?)
+    }
+
+    /// Plugin names with their executable paths, `~` expanded. Blank paths are skipped.
+    #[must_use]
+    pub fn plugin_paths(&self) -> Vec<(String, PathBuf)> {
+        self.plugins
+            .iter()
+            .filter_map(|(name, path)| Some((name.clone(), expand_home(path)?)))
+            .collect()
+    }
+
+    /// Wait before fetching again after every provider was unreachable, at least
+    /// [`MIN_RETRY_AFTER_SECS`] seconds
+    #[must_use]
+    pub fn retry_after(&self) -> Duration {
+        Duration::from_secs(self.retry_after_secs.max(MIN_RETRY_AFTER_SECS))
+    }
+
+    /// Names of the configured providers in priority order, as they report themselves in
+    /// [`LyricsProvider::name`](crate::LyricsProvider::name): `providers`, then plugins
+    #[must_use]
+    pub fn provider_names(&self) -> Vec<String> {
+        self.providers
+            .iter()
+            .map(|provider| provider.name().to_string())
+            .chain(self.plugin_paths().into_iter().map(|(name, _)| name))
+            .collect()
+    }
+}
+
+/// `path` with a leading `~` or `~/` expanded to the home directory, or `None` if it is blank
+fn expand_home(path: &str) -> Option<PathBuf> {
+    let path = path.trim();
+    if path.is_empty() {
+        return None;
+    }
+    if path == "~" {
+        return dirs::home_dir();
+    }
+    match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => Some(dirs::home_dir()?.join(rest)),
+        None => Some(PathBuf::from(path)),
+    }
 }
 
 fn default_providers() -> Vec<LyricsProviderType> {
     vec![LyricsProviderType::Lrclib]
 }
 
+const fn default_allow_unsynced() -> bool {
+    true
+}
+
+/// Shortest wait before fetching again after every provider was unreachable
+pub const MIN_RETRY_AFTER_SECS: u64 = 5;
+const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+
+const fn default_retry_after_secs() -> u64 {
+    DEFAULT_RETRY_AFTER_SECS
+}
+
+const fn default_retry_attempts() -> u32 {
+    DEFAULT_RETRY_ATTEMPTS
+}
+
 impl Default for LyricsConfig {
     fn default() -> Self {
         Self {
             providers: default_providers(),
+            encrypt_cache: false,
+            secret_store: SecretStoreKind::default(),
+            mirror_dir: None,
+            allow_unsynced: default_allow_unsynced(),
+            parallel_fetch: false,
+            retry_after_secs: DEFAULT_RETRY_AFTER_SECS,
+            retry_attempts: DEFAULT_RETRY_ATTEMPTS,
+            censor: false,
+            translation: TranslationConfig::default(),
+            processors: ProcessorsConfig::default(),
+            query: QueryConfig::default(),
+            blocklist: BlocklistConfig::default(),
+            plugins: BTreeMap::new(),
+        }
+    }
+}
+
+/// Translation service used to translate lyrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationBackend {
+    #[default]
+    LibreTranslate,
+    Deepl,
+    /// An LLM behind an OpenAI-compatible chat completions API
+    #[serde(rename = "openai")]
+    OpenAi,
+}
+
+/// Lyric translation settings (`[lyrics.translation]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationConfig {
+    #[serde(default)]
+    pub backend: TranslationBackend,
+    /// Base URL of the translation API, e.g. `https://api-free.deepl.com` (empty = disabled)
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Language to translate into, e.g. `"en"` or `"de"`
+    #[serde(default = "default_translation_target_lang")]
+    pub target_lang: String,
+    /// Model asked by the `openai` backend, e.g. `"gpt-4o-mini"`
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Translate every song as its lyrics load, instead of only on demand
+    #[serde(default)]
+    pub auto_translate: bool,
+}
+
+fn default_translation_target_lang() -> String {
+    "en".into()
+}
+
+impl TranslationConfig {
+    /// Whether a translation endpoint is configured
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        !self.endpoint.trim().is_empty()
+    }
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            backend: TranslationBackend::default(),
+            endpoint: String::new(),
+            api_key: None,
+            target_lang: default_translation_target_lang(),
+            model: None,
+            auto_translate: false,
+        }
+    }
+}
+
+/// A text post-processor that can appear in `lyrics.processors.pipeline`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextProcessorKind {
+    /// Trim lines and collapse runs of whitespace
+    NormalizeWhitespace,
+    /// Mask the words in `censor_words`
+    Censor,
+    /// Replace kana and hangul with their romanization
+    Romanize,
+    /// Convert characters to the `convert_script` target
+    ConvertScript,
+    /// Replace typographic quotes and apostrophes with ASCII ones
+    SmartQuotes,
+}
+
+/// Backend for stored secrets such as the cache encryption key and Spotify credentials
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretStoreKind {
+    /// The OS keyring (Windows Credential Manager, macOS keychain or Secret Service)
+    #[default]
+    Keyring,
+    /// A file in the config directory, for systems without a keyring
+    File,
+}
+
+/// Target of the `convert_script` processor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptTarget {
+    Hiragana,
+    Katakana,
+    /// Fullwidth ASCII letters, digits and punctuation to their regular forms
+    #[default]
+    Halfwidth,
+}
+
+/// Text post-processing pipeline (`[lyrics.processors]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessorsConfig {
+    /// Processors applied to every line, in order
+    #[serde(default)]
+    pub pipeline: Vec<TextProcessorKind>,
+    /// Words masked by the `censor` processor (case-insensitive, whole words), or
+    /// [`DEFAULT_CENSOR_WORDS`](crate::processors::DEFAULT_CENSOR_WORDS) when empty
+    #[serde(default)]
+    pub censor_words: Vec<String>,
+    #[serde(default)]
+    pub convert_script: ScriptTarget,
+    /// Show a romanized reading (romaji or romaja) with Japanese and Korean lines
+    #[serde(default)]
+    pub romanize_lines: bool,
+}
+
+/// Clean-up of the names lyrics are searched with (`[lyrics.query]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryConfig {
+    /// Strip release qualifiers such as "(Remastered 2011)" and featured artists
+    #[serde(default = "default_query_normalize")]
+    pub normalize: bool,
+    /// Extra regular expressions removed from track names (case-insensitive)
+    #[serde(default)]
+    pub title_patterns: Vec<String>,
+    /// Extra regular expressions removed from artist names (case-insensitive)
+    #[serde(default)]
+    pub artist_patterns: Vec<String>,
+    /// Look tracks up on `MusicBrainz` and search with their canonical title and artist
+    #[serde(default)]
+    pub musicbrainz: bool,
+}
+
+const fn default_query_normalize() -> bool {
+    true
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            normalize: default_query_normalize(),
+            title_patterns: Vec::new(),
+            artist_patterns: Vec::new(),
+            musicbrainz: false,
+        }
+    }
+}
+
+/// Tracks and artists whose lyrics are never fetched or shown (`[lyrics.blocklist]`), on
+/// top of the ones blocked from the app, which are kept in the cache database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlocklistConfig {
+    /// Artist names, matched case-insensitively against the track's artist or any one of
+    /// its comma-separated artists
+    #[serde(default)]
+    pub artists: Vec<String>,
+    /// Source track IDs, or "Artist - Title", matched case-insensitively
+    #[serde(default)]
+    pub tracks: Vec<String>,
+}
+
+/// Lyrics cache database selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Named profile with its own cache database (empty = the shared default database)
+    #[serde(default)]
+    pub profile: String,
+    /// Keep a separate cache database for each music source
+    #[serde(default)]
+    pub per_source: bool,
+    /// Hours to remember that no provider had lyrics for a track before trying again
+    /// (0 = always try again)
+    #[serde(default = "default_not_found_ttl_hours")]
+    pub not_found_ttl_hours: u64,
+    /// Hours between automatic backups of the cache database (0 = no automatic backups)
+    #[serde(default)]
+    pub backup_interval_hours: u64,
+    /// Number of backups kept; the oldest are deleted when a new one is written (0 = keep all)
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    /// Days after which cached lyrics are deleted so they are fetched again (0 = keep forever)
+    #[serde(default)]
+    pub lyrics_ttl_days: u32,
+    /// Hours between automatic clean-ups of the cache database (0 = never clean up)
+    #[serde(default = "default_maintenance_interval_hours")]
+    pub maintenance_interval_hours: u64,
+}
+
+const DEFAULT_NOT_FOUND_TTL_HOURS: u64 = 168;
+const DEFAULT_BACKUP_KEEP: usize = 5;
+const DEFAULT_MAINTENANCE_INTERVAL_HOURS: u64 = 24;
+
+const fn default_not_found_ttl_hours() -> u64 {
+    DEFAULT_NOT_FOUND_TTL_HOURS
+}
+
+const fn default_backup_keep() -> usize {
+    DEFAULT_BACKUP_KEEP
+}
+
+const fn default_maintenance_interval_hours() -> u64 {
+    DEFAULT_MAINTENANCE_INTERVAL_HOURS
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            profile: String::new(),
+            per_source: false,
+            not_found_ttl_hours: DEFAULT_NOT_FOUND_TTL_HOURS,
+            backup_interval_hours: 0,
+            backup_keep: DEFAULT_BACKUP_KEEP,
+            lyrics_ttl_days: 0,
+            maintenance_interval_hours: DEFAULT_MAINTENANCE_INTERVAL_HOURS,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Path of the cache database selected by this config for the given music source
+    #[must_use]
+    pub fn db_path(&self, source: MusicSource) -> PathBuf {
+        crate::paths::lyrics_cache_db_path_for(&self.profile, self.per_source.then_some(source))
+    }
+
+    /// How long a track without lyrics is remembered as such
+    #[must_use]
+    pub const fn not_found_ttl(&self) -> Duration {
+        Duration::from_secs(self.not_found_ttl_hours.saturating_mul(3600))
+    }
+
+    /// Time between automatic backups, or `None` if they are disabled
+    #[must_use]
+    pub const fn backup_interval(&self) -> Option<Duration> {
+        if self.backup_interval_hours == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(
+                self.backup_interval_hours.saturating_mul(3600),
+            ))
+        }
+    }
+
+    /// Time between automatic clean-ups, or `None` if they are disabled
+    #[must_use]
+    pub const fn maintenance_interval(&self) -> Option<Duration> {
+        if self.maintenance_interval_hours == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(
+                self.maintenance_interval_hours.saturating_mul(3600),
+            ))
         }
     }
 }
@@ -96,32 +491,591 @@ impl Default for LyricsConfig {
 pub enum LyricsProviderType {
     Lrclib,
     SpotifyLyrics,
+    Musixmatch,
+    EmbeddedTags,
+    Genius,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+impl LyricsProviderType {
+    /// Name the provider reports itself as, the same as in the config file
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Lrclib => "lrclib",
+            Self::SpotifyLyrics => "spotify_lyrics",
+            Self::Musixmatch => "musixmatch",
+            Self::EmbeddedTags => "embedded_tags",
+            Self::Genius => "genius",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiConfig {
+    /// Locale for numbers and times shown in the UI (`"auto"` detects from the environment)
+    #[serde(default = "default_ui_locale")]
+    pub locale: String,
+    /// Name of the theme in the themes directory to use (`themes/{name}.css`). Empty uses
+    /// theme.css in the config directory.
+    #[serde(default)]
+    pub theme: String,
     #[serde(default)]
     pub layout: LayoutConfig,
     #[serde(default)]
     pub animation: AnimationConfig,
+    #[serde(default)]
+    pub cursor: CursorConfig,
+    #[serde(default)]
+    pub follow: FollowConfig,
+    #[serde(default)]
+    pub behavior: BehaviorConfig,
+    #[serde(default)]
+    pub contrast: ContrastConfig,
+    #[serde(default)]
+    pub artwork: ArtworkConfig,
+    #[serde(default)]
+    pub window: WindowConfig,
+}
+
+fn default_ui_locale() -> String {
+    AUTO_LOCALE.to_string()
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            locale: default_ui_locale(),
+            theme: String::new(),
+            layout: LayoutConfig::default(),
+            animation: AnimationConfig::default(),
+            cursor: CursorConfig::default(),
+            follow: FollowConfig::default(),
+            behavior: BehaviorConfig::default(),
+            contrast: ContrastConfig::default(),
+            artwork: ArtworkConfig::default(),
+            window: WindowConfig::default(),
+        }
+    }
+}
+
+impl UiConfig {
+    /// Build a formatter for the configured UI locale
+    #[must_use]
+    pub fn locale_formatter(&self) -> LocaleFormatter {
+        LocaleFormatter::from_config(&self.locale)
+    }
+}
+
+/// Auto-follow of the current line while the user reads ahead or back
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FollowConfig {
+    /// Pause auto-follow while the pointer hovers the lyrics
+    #[serde(default = "default_pause_on_hover")]
+    pub pause_on_hover: bool,
+    /// How long after the pointer leaves (or scrolling stops) auto-follow resumes (milliseconds)
+    #[serde(default = "default_follow_resume_after_ms")]
+    pub resume_after_ms: u64,
+}
+
+const DEFAULT_FOLLOW_RESUME_AFTER_MS: u64 = 3000;
+
+const fn default_pause_on_hover() -> bool {
+    false
+}
+
+const fn default_follow_resume_after_ms() -> u64 {
+    DEFAULT_FOLLOW_RESUME_AFTER_MS
+}
+
+impl Default for FollowConfig {
+    fn default() -> Self {
+        Self {
+            pause_on_hover: default_pause_on_hover(),
+            resume_after_ms: DEFAULT_FOLLOW_RESUME_AFTER_MS,
+        }
+    }
+}
+
+/// What the overlay does in situations other than showing lyrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorConfig {
+    /// What to show when no provider has lyrics for the track
+    #[serde(default)]
+    pub no_lyrics: NoLyricsDisplay,
+    /// Message for `no_lyrics = "message"`; `{title}`, `{artist}` and `{album}` are replaced
+    #[serde(default = "default_no_lyrics_message")]
+    pub no_lyrics_message: String,
+}
+
+const DEFAULT_NO_LYRICS_MESSAGE: &str = "No lyrics for {title}";
+
+fn default_no_lyrics_message() -> String {
+    DEFAULT_NO_LYRICS_MESSAGE.to_string()
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            no_lyrics: NoLyricsDisplay::default(),
+            no_lyrics_message: default_no_lyrics_message(),
+        }
+    }
+}
+
+/// What the overlay shows in place of lyrics when none were found for the track
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoLyricsDisplay {
+    /// The track title and artist, scrolling across the overlay
+    #[default]
+    Track,
+    /// Only the album art, or the track when the source has no artwork
+    AlbumArt,
+    /// `no_lyrics_message`
+    Message,
+    /// Hide the overlay window until lyrics load
+    Hide,
+}
+
+/// Album art of the playing track shown with the lyrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtworkConfig {
+    #[serde(default)]
+    pub display: ArtworkDisplay,
+}
+
+/// How the album art of the playing track is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtworkDisplay {
+    /// Not shown
+    #[default]
+    Off,
+    /// Blurred behind the lyrics, filling the overlay
+    Background,
+    /// A small cover in the corner of the overlay
+    Badge,
+}
+
+/// Where the overlay window is placed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowConfig {
+    /// Display the overlay opens on: `"auto"` (where it was last), `"primary"`, a display
+    /// number counting from 1, or a display name as the OS reports it
+    #[serde(default = "default_window_monitor")]
+    pub monitor: String,
+}
+
+fn default_window_monitor() -> String {
+    AUTO_MONITOR.to_string()
+}
+
+/// `monitor` value that opens the overlay where it was last
+const AUTO_MONITOR: &str = "auto";
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            monitor: default_window_monitor(),
+        }
+    }
+}
+
+impl WindowConfig {
+    /// The display chosen with `monitor`
+    #[must_use]
+    pub fn monitor_choice(&self) -> MonitorChoice {
+        let monitor = self.monitor.trim();
+        if monitor.is_empty() || monitor.eq_ignore_ascii_case(AUTO_MONITOR) {
+            MonitorChoice::Auto
+        } else if monitor.eq_ignore_ascii_case("primary") {
+            MonitorChoice::Primary
+        } else if let Ok(number) = monitor.parse::<usize>() {
+            MonitorChoice::Number(number)
+        } else {
+            MonitorChoice::Name(monitor.to_string())
+        }
+    }
+}
+
+/// A display chosen in `[ui.window] monitor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorChoice {
+    /// Wherever the window was last, or the primary display if that one is gone
+    Auto,
+    /// The primary display
+    Primary,
+    /// The display with this number, counting from 1 in the order the OS lists them
+    Number(usize),
+    /// The display with this name
+    Name(String),
+}
+
+/// Mouse cursor behaviour while hovering the overlay
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CursorConfig {
+    /// Hide the cursor after it has been idle over the overlay for `idle_timeout_ms`
+    #[serde(default)]
+    pub hide_when_idle: bool,
+    /// How long the cursor must stay still before it is hidden (milliseconds)
+    #[serde(default = "default_cursor_idle_timeout_ms")]
+    pub idle_timeout_ms: u64,
+}
+
+const DEFAULT_CURSOR_IDLE_TIMEOUT_MS: u64 = 2000;
+
+/// Shortest allowed idle timeout, so a small value cannot hide the cursor on every move
+const MIN_CURSOR_IDLE_TIMEOUT_MS: u64 = 250;
+
+const fn default_cursor_idle_timeout_ms() -> u64 {
+    DEFAULT_CURSOR_IDLE_TIMEOUT_MS
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            hide_when_idle: false,
+            idle_timeout_ms: DEFAULT_CURSOR_IDLE_TIMEOUT_MS,
+        }
+    }
+}
+
+impl CursorConfig {
+    /// Raise `idle_timeout_ms` to at least `MIN_CURSOR_IDLE_TIMEOUT_MS`
+    pub const fn clamp_to_bounds(&mut self) {
+        if self.idle_timeout_ms < MIN_CURSOR_IDLE_TIMEOUT_MS {
+            self.idle_timeout_ms = MIN_CURSOR_IDLE_TIMEOUT_MS;
+        }
+    }
+}
+
+/// Switching text colors to match the brightness of what is behind the overlay
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ContrastConfig {
+    /// Sample the screen around the overlay every `sample_interval_ms` and switch to dark
+    /// text over light backgrounds
+    #[serde(default)]
+    pub auto_adjust: bool,
+    /// How often the screen is sampled (milliseconds)
+    #[serde(default = "default_contrast_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+}
+
+const DEFAULT_CONTRAST_SAMPLE_INTERVAL_MS: u64 = 2000;
+
+/// Shortest allowed sample interval, so capturing the screen stays cheap
+const MIN_CONTRAST_SAMPLE_INTERVAL_MS: u64 = 500;
+
+const fn default_contrast_sample_interval_ms() -> u64 {
+    DEFAULT_CONTRAST_SAMPLE_INTERVAL_MS
+}
+
+impl Default for ContrastConfig {
+    fn default() -> Self {
+        Self {
+            auto_adjust: false,
+            sample_interval_ms: DEFAULT_CONTRAST_SAMPLE_INTERVAL_MS,
+        }
+    }
+}
+
+impl ContrastConfig {
+    /// Raise `sample_interval_ms` to at least `MIN_CONTRAST_SAMPLE_INTERVAL_MS`
+    pub const fn clamp_to_bounds(&mut self) {
+        if self.sample_interval_ms < MIN_CONTRAST_SAMPLE_INTERVAL_MS {
+            self.sample_interval_ms = MIN_CONTRAST_SAMPLE_INTERVAL_MS;
+        }
+    }
+}
+
+/// Lyric timing adjustments that apply to every track
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Switch to each line this many milliseconds before its timestamp (negative switches
+    /// late). Only affects which line is shown; cached timestamps are left as they are.
+    #[serde(default)]
+    pub line_lead_ms: i64,
+    /// Move the playback position this many milliseconds later for every track, e.g. to
+    /// make up for audio latency. Added to each track's own offset; exports leave it out.
+    #[serde(default)]
+    pub offset_ms: i64,
+}
+
+/// Largest allowed line lead in either direction
+pub const MAX_LINE_LEAD_MS: i64 = 2000;
+
+/// Largest allowed global offset in either direction
+pub const MAX_GLOBAL_OFFSET_MS: i64 = 10_000;
+
+impl SyncConfig {
+    /// Limit `line_lead_ms` to `±MAX_LINE_LEAD_MS` and `offset_ms` to `±MAX_GLOBAL_OFFSET_MS`
+    pub const fn clamp_to_bounds(&mut self) {
+        self.line_lead_ms = clamp_line_lead_ms(self.line_lead_ms);
+        self.offset_ms = clamp_global_offset_ms(self.offset_ms);
+    }
+}
+
+/// `offset_ms` limited to `±MAX_GLOBAL_OFFSET_MS`
+#[must_use]
+pub const fn clamp_global_offset_ms(offset_ms: i64) -> i64 {
+    if offset_ms > MAX_GLOBAL_OFFSET_MS {
+        MAX_GLOBAL_OFFSET_MS
+    } else if offset_ms < -MAX_GLOBAL_OFFSET_MS {
+        -MAX_GLOBAL_OFFSET_MS
+    } else {
+        offset_ms
+    }
+}
+
+/// `lead_ms` limited to `±MAX_LINE_LEAD_MS`
+#[must_use]
+pub const fn clamp_line_lead_ms(lead_ms: i64) -> i64 {
+    if lead_ms > MAX_LINE_LEAD_MS {
+        MAX_LINE_LEAD_MS
+    } else if lead_ms < -MAX_LINE_LEAD_MS {
+        -MAX_LINE_LEAD_MS
+    } else {
+        lead_ms
+    }
+}
+
+/// Plain-text "now singing" files, for streaming software that shows a text file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NowSingingConfig {
+    /// File that always holds the current lyric line (empty disables it)
+    #[serde(default)]
+    pub line_file: String,
+    /// File that always holds the line after the current one (empty disables it)
+    #[serde(default)]
+    pub next_line_file: String,
+    /// File that always holds the current track, formatted with `track_format` (empty disables it)
+    #[serde(default)]
+    pub track_file: String,
+    /// Track file format; `{title}`, `{artist}` and `{album}` are replaced
+    #[serde(default = "default_track_format")]
+    pub track_format: String,
+}
+
+const DEFAULT_TRACK_FORMAT: &str = "{artist} - {title}";
+
+fn default_track_format() -> String {
+    DEFAULT_TRACK_FORMAT.to_string()
+}
+
+impl Default for NowSingingConfig {
+    fn default() -> Self {
+        Self {
+            line_file: String::new(),
+            next_line_file: String::new(),
+            track_file: String::new(),
+            track_format: default_track_format(),
+        }
+    }
+}
+
+impl NowSingingConfig {
+    /// The line file with `~` expanded, if enabled
+    #[must_use]
+    pub fn line_file_path(&self) -> Option<PathBuf> {
+        expand_home(&self.line_file)
+    }
+
+    /// The next line file with `~` expanded, if enabled
+    #[must_use]
+    pub fn next_line_file_path(&self) -> Option<PathBuf> {
+        expand_home(&self.next_line_file)
+    }
+
+    /// The track file with `~` expanded, if enabled
+    #[must_use]
+    pub fn track_file_path(&self) -> Option<PathBuf> {
+        expand_home(&self.track_file)
+    }
+}
+
+/// Network behavior shared by all providers
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Retries of failed provider requests
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+/// Retries of provider requests that fail with a timeout, connection error, or a 429 or 5xx
+/// status. The wait doubles after each attempt, between `backoff_base_ms` and
+/// `max_backoff_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 = no retries)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Milliseconds before the first retry
+    #[serde(default = "default_backoff_base_ms")]
+    pub backoff_base_ms: u64,
+    /// Longest wait between retries in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Randomization of each wait, so clients do not retry in lockstep
+    #[serde(default)]
+    pub jitter: RetryJitter,
+}
+
+/// Randomization of the wait between retries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryJitter {
+    /// Wait exactly the backoff
+    None,
+    /// Wait a random time up to the backoff
+    #[default]
+    Full,
+    /// Wait a random time between `backoff_base_ms` and the backoff
+    Bounded,
+}
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF_BASE_MS: u64 = 1000;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+
+const fn default_max_retries() -> u32 {
+    DEFAULT_MAX_RETRIES
+}
+
+const fn default_backoff_base_ms() -> u64 {
+    DEFAULT_BACKOFF_BASE_MS
+}
+
+const fn default_max_backoff_ms() -> u64 {
+    DEFAULT_MAX_BACKOFF_MS
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff_base_ms: DEFAULT_BACKOFF_BASE_MS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MS,
+            jitter: RetryJitter::Full,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Shortest and longest wait between retries. The longest is never below the shortest.
+    #[must_use]
+    pub fn backoff_bounds(&self) -> (Duration, Duration) {
+        let min = Duration::from_millis(self.backoff_base_ms);
+        (min, Duration::from_millis(self.max_backoff_ms).max(min))
+    }
+}
+
+/// HTTP and WebSocket server with the live lyric state, for browser sources, web remotes
+/// and other visualizers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// Run the server (off by default)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on; the default only accepts connections from this computer
+    #[serde(default = "default_server_address")]
+    pub address: String,
+    /// Port to listen on
+    #[serde(default = "default_server_port")]
+    pub port: u16,
+}
+
+const DEFAULT_SERVER_ADDRESS: &str = "127.0.0.1";
+const DEFAULT_SERVER_PORT: u16 = 9707;
+
+fn default_server_address() -> String {
+    DEFAULT_SERVER_ADDRESS.to_string()
+}
+
+const fn default_server_port() -> u16 {
+    DEFAULT_SERVER_PORT
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_server_address(),
+            port: DEFAULT_SERVER_PORT,
+        }
+    }
 }
 
 /// Logging configuration
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
     /// Enable file logging to cache directory
     #[serde(default)]
     pub enabled: bool,
+    /// Record a JSONL trace of playback and lyric events for reproducing sync bugs
+    #[serde(default)]
+    pub record_session: bool,
+    /// Number of session traces kept; older ones are deleted when a new recording starts
+    #[serde(default = "default_max_session_traces")]
+    pub max_session_traces: usize,
+    /// Number of recent sync events kept in the persistent event log (0 disables it)
+    #[serde(default)]
+    pub event_log_size: usize,
+    /// Print the session summary computed from the event log to standard output on exit
+    #[serde(default)]
+    pub show_session_summary: bool,
+}
+
+const DEFAULT_MAX_SESSION_TRACES: usize = 10;
+
+const fn default_max_session_traces() -> usize {
+    DEFAULT_MAX_SESSION_TRACES
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            record_session: false,
+            max_session_traces: DEFAULT_MAX_SESSION_TRACES,
+            event_log_size: 0,
+            show_session_summary: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayoutConfig {
     #[serde(default = "default_max_lines")]
     pub max_lines: usize,
+    /// Number of already-sung lines kept visible above the current line
+    #[serde(default)]
+    pub history_lines: usize,
+    /// Who the overlay is laid out for, see [`OverlayView`]
+    #[serde(default)]
+    pub view: OverlayView,
+    /// Show duets in two columns, the first singer's lines on the left and the second's on
+    /// the right, when the lyrics say who sings each line
+    #[serde(default)]
+    pub duet_split: bool,
+    /// Show "Artist – Title" of the playing track above the lyrics
+    #[serde(default)]
+    pub show_track_info: bool,
+    /// Show a thin bar with how far the track has played
+    #[serde(default)]
+    pub show_progress: bool,
+    /// Lines stacked on top of each other, or a one-line ticker, see [`LayoutMode`]
+    #[serde(default)]
+    pub mode: LayoutMode,
+    /// What ticker mode does with a line too long for the overlay
+    #[serde(default)]
+    pub ticker_overflow: TickerOverflow,
 }
 
 const DEFAULT_MAX_LINES: usize = 3;
 
+/// Upper bound for `max_lines` (a full Apple Music-style panel)
+pub const MAX_LINES_LIMIT: usize = 8;
+
 const fn default_max_lines() -> usize {
     DEFAULT_MAX_LINES
 }
@@ -130,6 +1084,122 @@ impl Default for LayoutConfig {
     fn default() -> Self {
         Self {
             max_lines: DEFAULT_MAX_LINES,
+            history_lines: 0,
+            view: OverlayView::default(),
+            duet_split: false,
+            show_track_info: false,
+            show_progress: false,
+            mode: LayoutMode::default(),
+            ticker_overflow: TickerOverflow::default(),
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Clamp `max_lines` to 1..=`MAX_LINES_LIMIT` and keep at least the current line
+    /// visible below the history lines.
+    pub fn clamp_to_bounds(&mut self) {
+        self.max_lines = self.max_lines.clamp(1, MAX_LINES_LIMIT);
+        self.history_lines = self.history_lines.min(self.max_lines - 1);
+    }
+
+    /// Lines shown and how many of them are history lines. The audience view and ticker
+    /// mode show the current line alone, whatever `max_lines` says.
+    #[must_use]
+    pub const fn visible_lines(&self) -> (usize, usize) {
+        match (self.mode, self.view) {
+            (LayoutMode::Stack, OverlayView::Performer) => (self.max_lines, self.history_lines),
+            (LayoutMode::Ticker, _) | (_, OverlayView::Audience) => (1, 0),
+        }
+    }
+}
+
+/// What ticker mode does with a line too long for the overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickerOverflow {
+    /// Slide the line left as it fills, so its end is in view when it has been sung
+    #[default]
+    Scroll,
+    /// Cut the line off at the edge of the overlay
+    Clip,
+}
+
+/// How the lines of a song are arranged in the overlay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    /// The current line between sung and upcoming ones, scrolling up as the song goes on
+    #[default]
+    Stack,
+    /// The current line alone on one line with a horizontal fill, for a thin strip such as
+    /// above the taskbar
+    Ticker,
+}
+
+impl LayoutMode {
+    /// All modes, in the order a settings UI lists them
+    pub const ALL: [Self; 2] = [Self::Stack, Self::Ticker];
+
+    /// Config name of the mode, e.g. `ticker`
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Stack => "stack",
+            Self::Ticker => "ticker",
+        }
+    }
+
+    /// Get the CSS class applied to the lyrics container in this mode.
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::Stack => "mode-stack",
+            Self::Ticker => "mode-ticker",
+        }
+    }
+}
+
+/// Who an overlay is laid out for. Several overlays can follow the same song in different
+/// views, e.g. a performer view on the singer's screen and an audience view on a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayView {
+    /// Current and upcoming lines, with a countdown bar to the next line
+    #[default]
+    Performer,
+    /// The current line alone, in large text
+    Audience,
+}
+
+impl OverlayView {
+    /// Parse a view name, e.g. from `--view audience`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "performer" => Some(Self::Performer),
+            "audience" => Some(Self::Audience),
+            _ => None,
+        }
+    }
+
+    /// The view selected by the `view` parameter of a URL query string such as
+    /// `view=audience&scale=2`, if it has a valid one, as the lyrics server reads it
+    #[must_use]
+    pub fn from_query(query: &str) -> Option<Self> {
+        query
+            .trim_start_matches('?')
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("view="))
+            .and_then(Self::from_name)
+    }
+
+    /// Get the CSS class applied to the lyrics container for this view.
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::Performer => "view-performer",
+            Self::Audience => "view-audience",
         }
     }
 }
@@ -143,10 +1213,50 @@ pub struct AnimationConfig {
     /// is trusted to avoid unnecessary visual jumps.
     #[serde(default = "default_drift_threshold_ms")]
     pub drift_threshold_ms: u64,
+    /// Spring stiffness for the line scroll transition. Higher values snap to the
+    /// next line faster.
+    #[serde(default = "default_spring_stiffness")]
+    pub spring_stiffness: f32,
+    /// Spring damping for the line scroll transition. Higher values reduce overshoot.
+    #[serde(default = "default_spring_damping")]
+    pub spring_damping: f32,
+    /// Spring mass for the line scroll transition. Higher values feel heavier and floatier.
+    #[serde(default = "default_spring_mass")]
+    pub spring_mass: f32,
+    /// Easing curve used by the karaoke fill animation on the current line
+    #[serde(default)]
+    pub fill_easing: FillEasing,
+    /// Entrance effect played when a line becomes the current line
+    #[serde(default)]
+    pub line_entrance: LineEntrance,
+    /// Move each line change to the nearest beat, using the track's tempo (Spotify only)
+    #[serde(default)]
+    pub quantize_to_beat: bool,
+    /// Pulse the current line on each beat, using the track's audio analysis (Spotify only)
+    #[serde(default)]
+    pub beat_pulse: bool,
+    /// Animate line scrolling, entrances and the karaoke fill. When off, lines snap into
+    /// place and the current line is shown fully sung.
+    #[serde(default = "default_animations_enabled")]
+    pub enabled: bool,
+}
+
+const fn default_animations_enabled() -> bool {
+    true
 }
 
 const DEFAULT_ANIMATION_FRAMERATE: u32 = 60;
 const DEFAULT_DRIFT_THRESHOLD_MS: u64 = 200;
+const DEFAULT_SPRING_STIFFNESS: f32 = 180.0;
+const DEFAULT_SPRING_DAMPING: f32 = 20.0;
+const DEFAULT_SPRING_MASS: f32 = 1.0;
+
+/// Valid range for `spring_stiffness`
+const SPRING_STIFFNESS_BOUNDS: (f32, f32) = (10.0, 1000.0);
+/// Valid range for `spring_damping`
+const SPRING_DAMPING_BOUNDS: (f32, f32) = (1.0, 100.0);
+/// Valid range for `spring_mass`
+const SPRING_MASS_BOUNDS: (f32, f32) = (0.1, 10.0);
 
 const fn default_animation_framerate() -> u32 {
     DEFAULT_ANIMATION_FRAMERATE
@@ -156,13 +1266,132 @@ const fn default_drift_threshold_ms() -> u64 {
     DEFAULT_DRIFT_THRESHOLD_MS
 }
 
+const fn default_spring_stiffness() -> f32 {
+    DEFAULT_SPRING_STIFFNESS
+}
+
+const fn default_spring_damping() -> f32 {
+    DEFAULT_SPRING_DAMPING
+}
+
+const fn default_spring_mass() -> f32 {
+    DEFAULT_SPRING_MASS
+}
+
 impl Default for AnimationConfig {
     fn default() -> Self {
         Self {
             framerate: DEFAULT_ANIMATION_FRAMERATE,
             drift_threshold_ms: DEFAULT_DRIFT_THRESHOLD_MS,
+            spring_stiffness: DEFAULT_SPRING_STIFFNESS,
+            spring_damping: DEFAULT_SPRING_DAMPING,
+            spring_mass: DEFAULT_SPRING_MASS,
+            fill_easing: FillEasing::default(),
+            line_entrance: LineEntrance::default(),
+            quantize_to_beat: false,
+            beat_pulse: false,
+            enabled: default_animations_enabled(),
+        }
+    }
+}
+
+impl AnimationConfig {
+    /// Clamp spring parameters to sane bounds.
+    ///
+    /// Non-finite values (NaN, infinity) fall back to the defaults, since a spring
+    /// with such parameters would never settle.
+    pub fn clamp_to_bounds(&mut self) {
+        self.spring_stiffness = clamp_finite(
+            self.spring_stiffness,
+            SPRING_STIFFNESS_BOUNDS,
+            DEFAULT_SPRING_STIFFNESS,
+        );
+        self.spring_damping = clamp_finite(
+            self.spring_damping,
+            SPRING_DAMPING_BOUNDS,
+            DEFAULT_SPRING_DAMPING,
+        );
+        self.spring_mass = clamp_finite(self.spring_mass, SPRING_MASS_BOUNDS, DEFAULT_SPRING_MASS);
+    }
+}
+
+/// Clamp a float to `(min, max)`, replacing non-finite values with `default`.
+fn clamp_finite(value: f32, (min, max): (f32, f32), default: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(min, max)
+    } else {
+        default
+    }
+}
+
+/// Easing curve for the karaoke fill animation.
+///
+/// Maps directly onto CSS `animation-timing-function` keywords.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillEasing {
+    /// Constant fill speed across the line
+    #[default]
+    Linear,
+    /// Slow start and end with a faster middle
+    Ease,
+    /// Slow start, accelerating towards the end
+    EaseIn,
+    /// Fast start, decelerating towards the end
+    EaseOut,
+    /// Slow start and end, symmetrical
+    EaseInOut,
+}
+
+impl FillEasing {
+    /// Get the CSS `animation-timing-function` value for this easing.
+    #[must_use]
+    pub const fn as_css(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Ease => "ease",
+            Self::EaseIn => "ease-in",
+            Self::EaseOut => "ease-out",
+            Self::EaseInOut => "ease-in-out",
+        }
+    }
+}
+
+/// Entrance effect for the line that becomes current.
+///
+/// Effects are implemented in the theme CSS via the class returned by
+/// [`LineEntrance::as_css_class`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEntrance {
+    /// No entrance effect, the line appears immediately
+    #[default]
+    None,
+    /// Fade in while sliding up from slightly below
+    FadeUp,
+    /// Fade in from a blurred state
+    BlurIn,
+    /// Reveal word by word, following word timings when the lyrics provide them
+    Typewriter,
+}
+
+impl LineEntrance {
+    /// Get the CSS class applied to the current line wrapper for this effect.
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::None => "entrance-none",
+            Self::FadeUp => "entrance-fade-up",
+            Self::BlurIn => "entrance-blur-in",
+            Self::Typewriter => "entrance-typewriter",
         }
     }
+
+    /// Whether this effect needs the line split into individually timed words.
+    #[must_use]
+    pub const fn is_per_word(self) -> bool {
+        matches!(self, Self::Typewriter)
+    }
 }
 
 impl VersualizerConfig {
@@ -207,15 +1436,155 @@ impl VersualizerConfig {
             return Err(CoreError::ConfigNotFound { path: config_path });
         }
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: Self = toml::from_str(&content)?;
+        Self::parse_with_env(&fs::read_to_string(&config_path)?, provider_templates)
+    }
+
+    /// Parse a config file's contents, clamping layout, animation and sync values to their
+    /// valid ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `content` is not a valid config.
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        Ok(config.clamped())
+    }
+
+    /// Parse a config file's contents like [`parse`](Self::parse), with `VERSUALIZER_*`
+    /// environment variables layered over them, so secrets and per-machine values can stay
+    /// out of the file.
+    ///
+    /// A variable names a key path with `_` between its parts, e.g. `VERSUALIZER_MUSIC_SOURCE`
+    /// for `source` in `[music]` or `VERSUALIZER_PROVIDERS_SPOTIFY_SP_DC` for `sp_dc` in
+    /// `[providers.spotify]`. Sections are matched against those in `content` and in the
+    /// config template with `provider_templates`, so keys containing `_` resolve. Values are
+    /// TOML (`true`, `500`, `["lrclib"]`), or plain text for keys holding strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `content` with the overrides is not a valid config.
+    pub fn parse_with_env(content: &str, provider_templates: Option<&[&str]>) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(content)?;
+        let schema: toml::Table =
+            toml::from_str(&build_config_template(provider_templates)).unwrap_or_default();
+        apply_env_overrides(&mut table, &schema, std::env::vars());
+        let config: Self = table.try_into()?;
+        Ok(config.clamped())
+    }
+
+    /// Clamp layout, animation and sync values to their valid ranges
+    fn clamped(mut self) -> Self {
+        self.ui.layout.clamp_to_bounds();
+        self.ui.animation.clamp_to_bounds();
+        self.ui.cursor.clamp_to_bounds();
+        self.ui.contrast.clamp_to_bounds();
+        self.sync.clamp_to_bounds();
+        self
+    }
+
+    /// Reduce the config to what safe mode (`--safe-mode`) runs: no lyrics providers or
+    /// plugins, so only cached lyrics are shown, no automatic translation, and no animations.
+    /// Everything else is kept so the settings panel still shows the user's values.
+    #[must_use]
+    pub fn into_safe_mode(mut self) -> Self {
+        self.lyrics.providers.clear();
+        self.lyrics.plugins.clear();
+        self.lyrics.translation.auto_translate = false;
+        self.ui.animation.enabled = false;
+        self.ui.animation.line_entrance = LineEntrance::None;
+        self.ui.animation.quantize_to_beat = false;
+        self.ui.animation.beat_pulse = false;
+        self
+    }
+}
+
+/// Prefix of environment variables that override config values
+const ENV_PREFIX: &str = "VERSUALIZER_";
 
-        // Clamp max_lines to valid range (1-3)
-        let mut config = config;
-        config.ui.layout.max_lines = config.ui.layout.max_lines.clamp(1, 3);
+/// Layer the `VERSUALIZER_*` variables in `vars` over `table`, resolving their key paths
+/// against the sections of `table` and `schema`
+fn apply_env_overrides(
+    table: &mut toml::Table,
+    schema: &toml::Table,
+    vars: impl IntoIterator<Item = (String, String)>,
+) {
+    for (name, raw) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = key
+            .to_ascii_lowercase()
+            .split('_')
+            .map(String::from)
+            .collect();
+        let path = env_key_path(&segments, Some(table), Some(schema));
+        let Some((leaf, sections)) = path
+            .split_last()
+            .filter(|(_, sections)| !sections.is_empty())
+        else {
+            tracing::warn!("{} does not name a config value, ignoring it", name);
+            continue;
+        };
+        let holds_string = value_at(table, &path)
+            .or_else(|| value_at(schema, &path))
+            .is_some_and(toml::Value::is_str);
+        let value = if holds_string {
+            toml::Value::String(raw)
+        } else {
+            parse_env_value(raw)
+        };
+
+        if let Some(section) = section_mut(table, sections) {
+            section.insert(leaf.clone(), value);
+        }
+    }
+}
 
-        Ok(config)
+/// The key path that `segments` (the parts of a variable name) stand for: the longest
+/// prefix naming a section in `table` or `schema` is a section, and so on below it, and
+/// the rest is the key
+fn env_key_path(
+    segments: &[String],
+    table: Option<&toml::Table>,
+    schema: Option<&toml::Table>,
+) -> Vec<String> {
+    fn section<'a>(parent: Option<&'a toml::Table>, key: &str) -> Option<&'a toml::Table> {
+        parent?.get(key)?.as_table()
+    }
+    for end in (1..segments.len()).rev() {
+        let key = segments[..end].join("_");
+        let (table, schema) = (section(table, &key), section(schema, &key));
+        if table.is_some() || schema.is_some() {
+            let mut path = vec![key];
+            path.extend(env_key_path(&segments[end..], table, schema));
+            return path;
+        }
     }
+    vec![segments.join("_")]
+}
+
+/// The section at `path` in `table`, created if missing
+fn section_mut<'a>(table: &'a mut toml::Table, path: &[String]) -> Option<&'a mut toml::Table> {
+    path.iter().try_fold(table, |current, key| {
+        current
+            .entry(key.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+    })
+}
+
+fn value_at<'a>(table: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_first()?;
+    rest.iter()
+        .try_fold(table.get(first)?, |value, key| value.get(key.as_str()))
+}
+
+/// `raw` as a TOML value, or as a string if it is not one
+fn parse_env_value(raw: String) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut parsed| parsed.remove("value"))
+        .unwrap_or(toml::Value::String(raw))
 }
 
 /// Build the config template string with optional provider-specific sections.
@@ -236,6 +1605,10 @@ pub fn build_config_template(provider_templates: Option<&[&str]>) -> String {
 /// Base config template (source-agnostic)
 const CONFIG_TEMPLATE_BASE: &str = r#"# Versualizer Configuration
 # ~/.config/versualizer/config.toml
+#
+# Any value can also be set with an environment variable named VERSUALIZER_ and its section
+# and key, e.g. VERSUALIZER_MUSIC_SOURCE or VERSUALIZER_PROVIDERS_SPOTIFY_SP_DC, which takes
+# precedence over this file.
 
 [music]
 # Active music source: "spotify", "mpris", "windows_media", "youtube_music"
@@ -243,22 +1616,215 @@ source = "spotify"
 
 [lyrics]
 # Provider priority: providers are tried in order
-# Available: "lrclib", "spotify_lyrics"
+# Available: "lrclib", "spotify_lyrics", "musixmatch", "embedded_tags", "genius"
+# "embedded_tags" reads SYLT/USLT or LYRICS tags from the playing file, for local music sources,
+# or a .lrc, .ttml, .krc, .srt or .vtt file with the same name next to it
+# "genius" only has plain lyrics, shown when no provider has synced ones (needs [providers.genius])
 providers = ["lrclib"]
+# Encrypt cached lyrics at rest. Only lyric and translation text is encrypted; track titles,
+# artists, albums and provider track IDs stay readable because cache lookups match on them.
+encrypt_cache = false
+# Where secrets are kept (the encryption key, the Spotify sign-in token, and credentials left
+# out of this file): "keyring" (Windows Credential Manager, macOS keychain or Secret Service
+# via secret-tool) or "file" (~/.config/versualizer/.secrets.json, for systems without a
+# keyring; plaintext, readable only by you)
+secret_store = "keyring"
+# Also save every newly fetched synced lyric as an .lrc file in this folder
+# mirror_dir = "~/Music/Lyrics"
+# Show plain lyrics, scrolled along with the track, when no provider has synced lyrics
+allow_unsynced = true
+# Query all providers at once instead of in order. The first synced lyrics to arrive are
+# used (priority breaks ties), which avoids waiting on a slow provider at the top of the list.
+parallel_fetch = false
+# When every provider is unreachable (e.g. a network blip), fetch again after this many seconds
+# (at least 5) while the same track plays, up to retry_attempts times (0 = never)
+retry_after_secs = 60
+retry_attempts = 3
+# Mask profanity (e.g. "f**k") before lyrics are shown, e.g. when the overlay is on stream.
+# Masks [lyrics.processors] censor_words, or a built-in list of common swear words when that is
+# empty. Cached lyrics are kept as fetched.
+censor = false
+
+[lyrics.translation]
+# Translate the current song on demand with Ctrl+Shift+T or "Translate song" in the tray menu.
+# The translation is shown under each line and cached per language.
+# Backend: "libre_translate", "deepl", or "openai" (any OpenAI-compatible chat completions API)
+backend = "libre_translate"
+# API base URL, e.g. "https://libretranslate.com", "https://api-free.deepl.com" or
+# "https://api.openai.com" (empty disables)
+endpoint = ""
+# api_key = ""
+target_lang = "en"
+# Model for the openai backend
+# model = "gpt-4o-mini"
+# Translate every song as soon as its lyrics load
+auto_translate = false
+
+[lyrics.processors]
+# Text clean-up applied in order to lyrics when they are shown. The cache keeps the lyrics as
+# fetched, so changes here also apply to cached songs.
+# Available: "normalize_whitespace", "censor", "romanize", "convert_script", "smart_quotes"
+pipeline = []
+# Words masked by "censor" and lyrics.censor (case-insensitive, whole words); empty uses a
+# built-in list
+censor_words = []
+# Target of "convert_script": "hiragana", "katakana", or "halfwidth"
+convert_script = "halfwidth"
+# Show a romanized reading (romaji for Japanese, romaja for Korean) above each line, keeping
+# the original script. Chinese lines are not romanized yet.
+romanize_lines = false
+
+[lyrics.query]
+# Search providers without release qualifiers and featured artists, e.g. "Song" for
+# "Song (Remastered 2011)" or "Song - Live at Wembley", and "Artist" for "Artist feat. X".
+# The original names are still shown and cached.
+normalize = true
+# Extra regular expressions removed from track and artist names before searching
+# (case-insensitive), e.g. ['\s*\(Taylor''s Version\)']
+title_patterns = []
+artist_patterns = []
+# Look the playing track up on MusicBrainz (by ISRC, or by title, artist and length) and
+# search with its canonical title and artist, which helps with compilations credited to
+# "Various Artists" and misspelled tags. Adds up to a few seconds before each lookup.
+musicbrainz = false
+
+[lyrics.blocklist]
+# Never fetch or show lyrics for these, e.g. podcasts, ASMR or tracks that keep matching the
+# wrong song. The overlay stays idle while they play. Tracks and artists can also be blocked
+# from the "Wrong lyrics?" picker.
+# Artist names (case-insensitive), also matching one of several comma-separated artists
+artists = []
+# Source track IDs, or "Artist - Title" (case-insensitive)
+tracks = []
+
+[lyrics.plugins]
+# External lyrics providers: name = path to an executable speaking the JSON stdin/stdout
+# protocol described in the versualizer-lyrics-plugin crate. Plugins are tried in name order
+# after the providers above.
+# my_provider = "~/.local/bin/my-lyrics-provider"
+
+[cache]
+# Use a separate lyrics cache database per profile, e.g. one per person on a shared machine.
+# Leave empty to use the default lyrics_cache.db
+profile = ""
+# Keep a separate lyrics cache database for each music source
+per_source = false
+# Hours to remember that no provider had lyrics for a track, so replaying it skips the
+# network (0 = always try again). Clear with: versualizer-cli purge --not-found
+not_found_ttl_hours = 168
+# Hours between automatic backups of the lyrics cache into the backups folder next to this
+# file (0 = no automatic backups). Back up on demand from the settings panel or with:
+# versualizer-cli backup
+backup_interval_hours = 0
+# Number of backups kept; the oldest are deleted when a new one is written (0 = keep all)
+backup_keep = 5
+# Delete cached lyrics fetched more than this many days ago, so they are fetched fresh
+# (0 = keep forever)
+lyrics_ttl_days = 0
+# Hours between clean-ups of the lyrics cache: expired lyrics and no-lyrics entries are
+# deleted and their space is returned to the disk. A due clean-up waits until nothing is
+# playing (0 = never clean up)
+maintenance_interval_hours = 24
 
 [logging]
 # Enable file logging to cache directory (versualizer.log)
 enabled = false
+# Record a session trace (traces/session-*.jsonl in the cache directory).
+# Replay one with: versualizer --replay <path>
+record_session = false
+# Number of session traces kept; the oldest are deleted when a new recording starts.
+max_session_traces = 10
+# Number of recent sync events kept in event_log.db for later diagnosis (0 disables).
+# Every event is written to disk, so only enable this (e.g. 1000) while diagnosing sync issues.
+# View them with: versualizer-cli events tail
+# On exit, a session summary (tracks played, lyrics hit rate, sing-along time, top provider)
+# is written to last_session.txt in the cache directory. View it with: versualizer-cli stats
+event_log_size = 0
+# Also print the session summary to the terminal on exit
+show_session_summary = false
+
+[sync]
+# Switch to each line this many milliseconds early (e.g. 200 to see lines before singing them).
+# Negative values switch late. Adjust it live with Ctrl+Shift+] and Ctrl+Shift+[ (50ms steps).
+# For lyrics that are off on a single track, use Ctrl+Shift+→ and Ctrl+Shift+← (100ms steps)
+# instead: that offset is saved in the lyrics cache and applied whenever the track plays.
+line_lead_ms = 0
+# Move the position this many milliseconds later on every track (negative moves it earlier),
+# e.g. 150 when audio reaches your speakers late. It adds to each track's own offset and is
+# left out of exported lyrics.
+offset_ms = 0
+
+[now_singing]
+# Keep the current lyric line in a text file, e.g. for an OBS "Text (GDI+/FreeType 2)" source
+# reading from file. Empty disables it.
+line_file = ""
+# Keep the line after the current one in a text file, for a "next up" source. Empty disables it.
+next_line_file = ""
+# Keep the current track in a text file. Empty disables it.
+track_file = ""
+# Format of the track file: {title}, {artist} and {album} are replaced
+track_format = "{artist} - {title}"
+
+[network.retry]
+# Retries of provider requests that time out, lose the connection, or get a 429 or 5xx status.
+# The wait doubles after each retry, from backoff_base_ms up to max_backoff_ms.
+max_retries = 3
+backoff_base_ms = 1000
+max_backoff_ms = 30000
+# Randomize waits so clients do not retry in lockstep: "full" (0 to the backoff), "bounded"
+# (backoff_base_ms to the backoff) or "none"
+jitter = "full"
+
+[server]
+# Serve the live lyric state for OBS browser sources, web remotes and other visualizers:
+# GET /api/state and /api/lyrics return JSON, and /ws streams sync events and line changes.
+enabled = false
+# Use "0.0.0.0" to accept connections from other devices on the network
+address = "127.0.0.1"
+port = 9707
 
 "#;
 
 /// UI config template
 const CONFIG_TEMPLATE_UI: &str = concatcp!(
+    "[ui]\n",
+    "# Locale for numbers and times in the overlay, e.g. \"de-CH\" or \"fr\".\n",
+    "# \"",
+    AUTO_LOCALE,
+    "\" detects it from LC_ALL / LC_NUMERIC / LANG.\n",
+    "locale = \"",
+    AUTO_LOCALE,
+    "\"\n",
+    "# Theme to use from the themes directory, e.g. \"dark\" for themes/dark.css.\n",
+    "# Empty uses theme.css.\n",
+    "theme = \"\"\n",
+    "\n",
     "[ui.layout]\n",
-    "# The number of song lines to display in the visualizer\n",
+    "# The number of song lines to display in the visualizer (1-",
+    MAX_LINES_LIMIT,
+    ")\n",
     "max_lines = ",
     DEFAULT_MAX_LINES,
     "\n",
+    "# How many of those lines show already-sung lyrics above the current line.\n",
+    "# Scroll over the overlay to peek further back or ahead; it follows the song again after a few seconds.\n",
+    "history_lines = 0\n",
+    "# \"performer\" shows the current and upcoming lines with a countdown bar to the next line;\n",
+    "# \"audience\" shows only the current line in large text (e.g. for a stream or second screen).\n",
+    "# Override it for one window with: versualizer --view audience\n",
+    "view = \"performer\"\n",
+    "# Show duets in two columns: the first singer's lines on the left, the second's on the right.\n",
+    "# Needs lyrics that mark their singers, like TTML lyrics or LRC lines starting with v1: and v2:\n",
+    "duet_split = false\n",
+    "# Show \"Artist – Title\" of the playing track above the lyrics, and a bar with how far it has played.\n",
+    "# Handy when a track has no lyrics or during long instrumental parts.\n",
+    "show_track_info = false\n",
+    "show_progress = false\n",
+    "# \"stack\" shows lines on top of each other; \"ticker\" shows only the current line on one line\n",
+    "# with a horizontal fill, for a thin overlay strip e.g. above the taskbar.\n",
+    "mode = \"stack\"\n",
+    "# In ticker mode, lines too long for the overlay \"scroll\" along with their fill or \"clip\" at its edge.\n",
+    "ticker_overflow = \"scroll\"\n",
     "\n",
     "[ui.animation]\n",
     "# Animation framerate in frames per second\n",
@@ -274,6 +1840,69 @@ const CONFIG_TEMPLATE_UI: &str = concatcp!(
     "drift_threshold_ms = ",
     DEFAULT_DRIFT_THRESHOLD_MS,
     "\n",
+    "# Spring physics for scrolling between lines. Higher stiffness = snappier,\n",
+    "# higher damping = less bounce, higher mass = floatier.\n",
+    "# Bounds: stiffness 10-1000, damping 1-100, mass 0.1-10\n",
+    "spring_stiffness = 180.0\n",
+    "spring_damping = 20.0\n",
+    "spring_mass = 1.0\n",
+    "# Easing for the karaoke fill: \"linear\", \"ease\", \"ease_in\", \"ease_out\", \"ease_in_out\"\n",
+    "fill_easing = \"linear\"\n",
+    "# Entrance effect for the incoming line: \"none\", \"fade_up\", \"blur_in\", \"typewriter\"\n",
+    "# Typewriter follows word timings when the lyrics provide them.\n",
+    "line_entrance = \"none\"\n",
+    "# Move line changes to the nearest beat. Uses the track tempo from Spotify's audio features,\n",
+    "# so it only applies to the Spotify source and apps that still have audio features access.\n",
+    "quantize_to_beat = false\n",
+    "# Pulse the current line on each beat, from Spotify's audio analysis (same limits as above)\n",
+    "beat_pulse = false\n",
+    "# Turn off to snap between lines without scrolling, entrance or fill animations\n",
+    "enabled = true\n",
+    "\n",
+    "[ui.cursor]\n",
+    "# Hide the mouse cursor when it stays still over the overlay\n",
+    "hide_when_idle = false\n",
+    "# Idle time in milliseconds before the cursor is hidden (at least 250)\n",
+    "idle_timeout_ms = ",
+    DEFAULT_CURSOR_IDLE_TIMEOUT_MS,
+    "\n",
+    "\n",
+    "[ui.follow]\n",
+    "# Stop scrolling along with the song while the pointer is over the lyrics\n",
+    "pause_on_hover = false\n",
+    "# Milliseconds after the pointer leaves or scrolling stops before following resumes\n",
+    "resume_after_ms = ",
+    DEFAULT_FOLLOW_RESUME_AFTER_MS,
+    "\n",
+    "\n",
+    "[ui.behavior]\n",
+    "# Shown when no provider has lyrics for the track: \"track\" (title and artist),\n",
+    "# \"album_art\", \"message\" (no_lyrics_message) or \"hide\" (hides the overlay until lyrics load)\n",
+    "no_lyrics = \"track\"\n",
+    "# {title}, {artist} and {album} are replaced\n",
+    "no_lyrics_message = \"",
+    DEFAULT_NO_LYRICS_MESSAGE,
+    "\"\n",
+    "\n",
+    "[ui.contrast]\n",
+    "# Sample the screen just around the overlay and switch to dark text over light backgrounds.\n",
+    "# Works on Windows and X11; macOS and Wayland keep the theme's colors.\n",
+    "auto_adjust = false\n",
+    "# How often the screen is sampled, in milliseconds (at least 500)\n",
+    "sample_interval_ms = ",
+    DEFAULT_CONTRAST_SAMPLE_INTERVAL_MS,
+    "\n",
+    "\n",
+    "[ui.artwork]\n",
+    "# Album art of the playing track: \"off\", \"background\" (blurred behind the lyrics)\n",
+    "# or \"badge\" (a small cover in the corner). Covers are cached after the first download.\n",
+    "display = \"off\"\n",
+    "\n",
+    "[ui.window]\n",
+    "# Display the overlay opens on: \"auto\" (where it was last, or the primary display when\n",
+    "# that one is disconnected), \"primary\", a display number counting from 1, or a display name.\n",
+    "# The tray menu's \"Move to next display\" moves it without editing this file.\n",
+    "monitor = \"auto\"\n",
 );
 
 #[cfg(test)]
@@ -291,12 +1920,241 @@ mod tests {
     fn test_lyrics_config_default() {
         let config = LyricsConfig::default();
         assert_eq!(config.providers, vec![LyricsProviderType::Lrclib]);
+        assert!(config.allow_unsynced);
+        assert!(!config.parallel_fetch);
+        assert_eq!(config.retry_after(), Duration::from_mins(1));
+        assert_eq!(config.retry_attempts, 3);
+        assert!(!config.censor);
+        assert!(config.query.normalize);
+        assert!(config.query.title_patterns.is_empty());
+        assert!(!config.query.musicbrainz);
+    }
+
+    #[test]
+    fn test_translation_config_parses() {
+        let config: TranslationConfig = toml::from_str(
+            r#"
+            backend = "openai"
+            endpoint = "https://api.openai.com"
+            model = "gpt-4o-mini"
+            auto_translate = true
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.backend, TranslationBackend::OpenAi);
+        assert_eq!(config.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(config.target_lang, "en");
+        assert!(config.auto_translate && config.is_enabled());
     }
 
     #[test]
     fn test_layout_config_default() {
         let config = LayoutConfig::default();
         assert_eq!(config.max_lines, 3);
+        assert_eq!(config.history_lines, 0);
+        assert_eq!(config.mode, LayoutMode::Stack);
+        assert_eq!(config.ticker_overflow, TickerOverflow::Scroll);
+    }
+
+    #[test]
+    fn test_mirror_dir_expands_home() {
+        let config = LyricsConfig {
+            mirror_dir: Some("~/Music/Lyrics".to_string()),
+            ..LyricsConfig::default()
+        };
+        let path = config.mirror_dir_path().unwrap();
+        assert!(path.ends_with("Music/Lyrics"));
+        assert!(!path.to_string_lossy().contains('~'));
+
+        let named = LyricsConfig {
+            mirror_dir: Some("~foo/Lyrics".to_string()),
+            ..LyricsConfig::default()
+        };
+        assert_eq!(
+            named.mirror_dir_path().unwrap(),
+            PathBuf::from("~foo/Lyrics")
+        );
+
+        let empty = LyricsConfig {
+            mirror_dir: Some(String::new()),
+            ..LyricsConfig::default()
+        };
+        assert!(empty.mirror_dir_path().is_none());
+    }
+
+    #[test]
+    fn test_plugin_paths() {
+        let config: LyricsConfig = toml::from_str(
+            r#"
+            [plugins]
+            zeta = "/opt/zeta"
+            alpha = "~/bin/alpha"
+            blank = " "
+        "#,
+        )
+        .unwrap();
+        let plugins = config.plugin_paths();
+
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].0, "alpha");
+        assert!(plugins[0].1.ends_with("bin/alpha"));
+        assert_eq!(plugins[1], ("zeta".to_string(), PathBuf::from("/opt/zeta")));
+    }
+
+    #[test]
+    fn test_provider_names() {
+        let config: LyricsConfig = toml::from_str(
+            r#"
+            providers = ["musixmatch", "embedded_tags"]
+            [plugins]
+            mine = "/opt/mine"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.provider_names(),
+            ["musixmatch", "embedded_tags", "mine"]
+        );
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            [music]
+            source = "spotify"
+            [lyrics]
+            providers = ["lrclib"]
+            [ui.layout]
+            max_lines = 3
+        "#,
+        )
+        .unwrap();
+        let schema: toml::Table = toml::from_str(
+            r#"
+            [providers.spotify]
+            client_id = ""
+        "#,
+        )
+        .unwrap();
+        let vars = [
+            ("VERSUALIZER_MUSIC_SOURCE", "mpris"),
+            (
+                "VERSUALIZER_LYRICS_PROVIDERS",
+                r#"["musixmatch", "lrclib"]"#,
+            ),
+            ("VERSUALIZER_LYRICS_PARALLEL_FETCH", "true"),
+            ("VERSUALIZER_UI_LAYOUT_MAX_LINES", "5"),
+            ("VERSUALIZER_PROVIDERS_SPOTIFY_CLIENT_ID", "12345"),
+            ("VERSUALIZER_PROVIDERS_SPOTIFY_SP_DC", "AQB-cookie"),
+            ("VERSUALIZER_NOTHING", "1"),
+            ("HOME", "/root"),
+        ];
+        apply_env_overrides(
+            &mut table,
+            &schema,
+            vars.map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+
+        let config: VersualizerConfig = table.clone().try_into().unwrap();
+        assert_eq!(config.music.source, MusicSource::Mpris);
+        assert_eq!(config.lyrics.provider_names(), ["musixmatch", "lrclib"]);
+        assert!(config.lyrics.parallel_fetch);
+        assert_eq!(config.ui.layout.max_lines, 5);
+        // Keys the template holds as strings stay strings, even if they look like numbers
+        assert_eq!(
+            table["providers"]["spotify"]["client_id"].as_str(),
+            Some("12345")
+        );
+        assert_eq!(
+            table["providers"]["spotify"]["sp_dc"].as_str(),
+            Some("AQB-cookie")
+        );
+        assert!(!table.contains_key("nothing"));
+    }
+
+    #[test]
+    fn test_cache_config_db_path() {
+        let shared = CacheConfig::default().db_path(MusicSource::Spotify);
+        assert!(shared.ends_with("lyrics_cache.db"));
+
+        let partitioned = CacheConfig {
+            profile: "Alex's".to_string(),
+            per_source: true,
+            ..CacheConfig::default()
+        }
+        .db_path(MusicSource::Spotify);
+        assert!(partitioned.ends_with("lyrics_cache-p-alex_s-s-spotify.db"));
+    }
+
+    #[test]
+    fn test_follow_config_default() {
+        let config = FollowConfig::default();
+        assert!(!config.pause_on_hover);
+        assert_eq!(config.resume_after_ms, 3000);
+    }
+
+    #[test]
+    fn test_cursor_config_clamp() {
+        let mut config = CursorConfig {
+            hide_when_idle: true,
+            idle_timeout_ms: 0,
+        };
+        config.clamp_to_bounds();
+        assert_eq!(config.idle_timeout_ms, 250);
+
+        config.idle_timeout_ms = 5000;
+        config.clamp_to_bounds();
+        assert_eq!(config.idle_timeout_ms, 5000);
+    }
+
+    #[test]
+    fn test_overlay_view() {
+        assert_eq!(
+            OverlayView::from_query("?theme=dark&view=audience"),
+            Some(OverlayView::Audience)
+        );
+        assert_eq!(OverlayView::from_query("view=stage"), None);
+        assert_eq!(OverlayView::from_query(""), None);
+
+        let mut layout = LayoutConfig {
+            max_lines: 4,
+            history_lines: 1,
+            view: OverlayView::Performer,
+            duet_split: false,
+            ..LayoutConfig::default()
+        };
+        assert_eq!(layout.visible_lines(), (4, 1));
+        layout.view = OverlayView::Audience;
+        assert_eq!(layout.visible_lines(), (1, 0));
+        layout.view = OverlayView::Performer;
+        layout.mode = LayoutMode::Ticker;
+        assert_eq!(layout.visible_lines(), (1, 0));
+    }
+
+    #[test]
+    fn test_layout_config_clamp() {
+        let mut config = LayoutConfig {
+            max_lines: 20,
+            history_lines: 20,
+            view: OverlayView::Performer,
+            duet_split: false,
+            ..LayoutConfig::default()
+        };
+        config.clamp_to_bounds();
+        assert_eq!(config.max_lines, MAX_LINES_LIMIT);
+        assert_eq!(config.history_lines, MAX_LINES_LIMIT - 1);
+
+        let mut config = LayoutConfig {
+            max_lines: 0,
+            history_lines: 2,
+            view: OverlayView::Performer,
+            duet_split: false,
+            ..LayoutConfig::default()
+        };
+        config.clamp_to_bounds();
+        assert_eq!(config.max_lines, 1);
+        assert_eq!(config.history_lines, 0);
     }
 
     #[test]
@@ -306,11 +2164,166 @@ mod tests {
         assert_eq!(config.drift_threshold_ms, 200);
     }
 
+    #[test]
+    fn test_animation_config_spring_defaults() {
+        let config = AnimationConfig::default();
+        assert!((config.spring_stiffness - 180.0).abs() < f32::EPSILON);
+        assert!((config.spring_damping - 20.0).abs() < f32::EPSILON);
+        assert!((config.spring_mass - 1.0).abs() < f32::EPSILON);
+        assert_eq!(config.fill_easing, FillEasing::Linear);
+    }
+
+    #[test]
+    fn test_sync_config_clamp() {
+        let mut config = SyncConfig {
+            line_lead_ms: 5000,
+            offset_ms: -50_000,
+        };
+        config.clamp_to_bounds();
+        assert_eq!(config.line_lead_ms, MAX_LINE_LEAD_MS);
+        assert_eq!(config.offset_ms, -MAX_GLOBAL_OFFSET_MS);
+        assert_eq!(clamp_line_lead_ms(-5000), -MAX_LINE_LEAD_MS);
+        assert_eq!(clamp_line_lead_ms(200), 200);
+    }
+
+    #[test]
+    fn test_animation_config_clamp_to_bounds() {
+        let mut config = AnimationConfig {
+            spring_stiffness: 5000.0,
+            spring_damping: 0.0,
+            spring_mass: f32::NAN,
+            ..Default::default()
+        };
+        config.clamp_to_bounds();
+
+        assert!((config.spring_stiffness - 1000.0).abs() < f32::EPSILON);
+        assert!((config.spring_damping - 1.0).abs() < f32::EPSILON);
+        assert!((config.spring_mass - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_fill_easing_as_css() {
+        assert_eq!(FillEasing::Linear.as_css(), "linear");
+        assert_eq!(FillEasing::Ease.as_css(), "ease");
+        assert_eq!(FillEasing::EaseIn.as_css(), "ease-in");
+        assert_eq!(FillEasing::EaseOut.as_css(), "ease-out");
+        assert_eq!(FillEasing::EaseInOut.as_css(), "ease-in-out");
+    }
+
+    #[test]
+    fn test_line_entrance_deserialization() {
+        let toml_str = r#"
+[music]
+source = "spotify"
+
+[lyrics]
+
+[ui.animation]
+line_entrance = "typewriter"
+"#;
+
+        let config: VersualizerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.ui.animation.line_entrance, LineEntrance::Typewriter);
+        assert!(config.ui.animation.line_entrance.is_per_word());
+        assert!(!LineEntrance::FadeUp.is_per_word());
+        assert_eq!(LineEntrance::default(), LineEntrance::None);
+    }
+
+    #[test]
+    fn test_config_template_parses() {
+        let template = build_config_template(None);
+        let config: VersualizerConfig = toml::from_str(&template).unwrap();
+
+        assert!(
+            (config.ui.animation.spring_stiffness - DEFAULT_SPRING_STIFFNESS).abs() < f32::EPSILON
+        );
+        assert!((config.ui.animation.spring_damping - DEFAULT_SPRING_DAMPING).abs() < f32::EPSILON);
+        assert!((config.ui.animation.spring_mass - DEFAULT_SPRING_MASS).abs() < f32::EPSILON);
+        assert_eq!(config.ui.animation.fill_easing, FillEasing::Linear);
+        assert_eq!(config.ui.animation.line_entrance, LineEntrance::None);
+        assert!(config.ui.animation.enabled);
+        assert!(!config.ui.layout.duet_split);
+        assert!(!config.ui.contrast.auto_adjust);
+        assert!(config.lyrics.query.normalize);
+        assert_eq!(
+            config.ui.contrast.sample_interval_ms,
+            DEFAULT_CONTRAST_SAMPLE_INTERVAL_MS
+        );
+        assert_eq!(
+            config.cache.not_found_ttl(),
+            Duration::from_secs(DEFAULT_NOT_FOUND_TTL_HOURS * 3600)
+        );
+        assert_eq!(config.cache.backup_interval(), None);
+        assert_eq!(config.cache.backup_keep, DEFAULT_BACKUP_KEEP);
+        assert_eq!(
+            config.cache.maintenance_interval(),
+            Some(Duration::from_secs(
+                DEFAULT_MAINTENANCE_INTERVAL_HOURS * 3600
+            ))
+        );
+        assert_eq!(config.network.retry, RetryConfig::default());
+        assert_eq!(config.server, ServerConfig::default());
+        assert_eq!(config.ui.behavior.no_lyrics, NoLyricsDisplay::Track);
+        assert_eq!(
+            config.ui.behavior.no_lyrics_message,
+            DEFAULT_NO_LYRICS_MESSAGE
+        );
+    }
+
+    #[test]
+    fn test_retry_config_parses() {
+        let config: NetworkConfig =
+            toml::from_str("[retry]\nmax_retries = 5\nmax_backoff_ms = 200\njitter = \"bounded\"")
+                .unwrap();
+        assert_eq!(config.retry.max_retries, 5);
+        assert_eq!(config.retry.jitter, RetryJitter::Bounded);
+        // A maximum below the base is raised to it
+        assert_eq!(
+            config.retry.backoff_bounds(),
+            (Duration::from_secs(1), Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_behavior_config_parses() {
+        let config: UiConfig = toml::from_str(
+            r#"
+            [behavior]
+            no_lyrics = "album_art"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.behavior.no_lyrics, NoLyricsDisplay::AlbumArt);
+        assert_eq!(config.behavior.no_lyrics_message, DEFAULT_NO_LYRICS_MESSAGE);
+    }
+
     #[test]
     fn test_ui_config_default() {
         let config = UiConfig::default();
         assert_eq!(config.layout.max_lines, 3);
         assert_eq!(config.animation.framerate, 60);
+        assert_eq!(config.locale, "auto");
+        assert!(config.theme.is_empty());
+        assert!(!config.cursor.hide_when_idle);
+        assert_eq!(config.cursor.idle_timeout_ms, 2000);
+        assert_eq!(config.window.monitor_choice(), MonitorChoice::Auto);
+    }
+
+    #[test]
+    fn test_monitor_choice() {
+        let choice = |monitor: &str| {
+            WindowConfig {
+                monitor: monitor.to_string(),
+            }
+            .monitor_choice()
+        };
+        assert_eq!(choice(""), MonitorChoice::Auto);
+        assert_eq!(choice("Primary"), MonitorChoice::Primary);
+        assert_eq!(choice("2"), MonitorChoice::Number(2));
+        assert_eq!(
+            choice(" HDMI-1 "),
+            MonitorChoice::Name("HDMI-1".to_string())
+        );
     }
 
     #[test]
@@ -408,4 +2421,33 @@ providers = ["lrclib"]
         assert_eq!(config.ui.layout.max_lines, 3);
         assert_eq!(config.ui.animation.framerate, 60);
     }
+
+    #[test]
+    fn test_into_safe_mode() {
+        let toml_str = r#"
+[music]
+source = "spotify"
+
+[lyrics]
+providers = ["lrclib", "musixmatch"]
+plugins = { mine = "~/plugins/mine.wasm" }
+translation = { backend = "libre_translate", target_lang = "en", auto_translate = true }
+
+[ui.animation]
+line_entrance = "typewriter"
+framerate = 30
+"#;
+
+        let config: VersualizerConfig = toml::from_str(toml_str).unwrap();
+        let config = config.into_safe_mode();
+
+        assert!(config.lyrics.providers.is_empty());
+        assert!(config.lyrics.plugins.is_empty());
+        assert!(!config.lyrics.translation.auto_translate);
+        assert!(!config.ui.animation.enabled);
+        assert_eq!(config.ui.animation.line_entrance, LineEntrance::None);
+        // Settings unrelated to crashes are left alone
+        assert_eq!(config.music.source, MusicSource::Spotify);
+        assert_eq!(config.ui.animation.framerate, 30);
+    }
 }
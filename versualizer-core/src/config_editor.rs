@@ -0,0 +1,168 @@
+//! Writing settings back to `config.toml`.
+//!
+//! [`ConfigEditor`] changes single values in the config file while keeping its comments,
+//! order and formatting, so a file edited from a settings UI still reads like the template.
+//! Edits are validated like a loaded config before the file is written, and a running
+//! [`ConfigWatcher`](crate::ConfigWatcher) then applies them like a hand edit.
+
+use std::fs;
+use std::path::PathBuf;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use crate::config::VersualizerConfig;
+use crate::error::{CoreError, Result};
+
+/// Edits values of a config file in place
+pub struct ConfigEditor {
+    path: PathBuf,
+    document: DocumentMut,
+}
+
+impl ConfigEditor {
+    /// Open the config file (`~/.config/versualizer/config.toml`, or the one chosen with
+    /// `--config` or `--profile`) for editing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid TOML.
+    pub fn open() -> Result<Self> {
+        Self::open_at(VersualizerConfig::config_path())
+    }
+
+    /// Open a config file at a specific path for editing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid TOML.
+    pub fn open_at(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let document = fs::read_to_string(&path)?
+            .parse::<DocumentMut>()
+            .map_err(|e| CoreError::ConfigInvalid {
+                message: e.to_string(),
+            })?;
+        Ok(Self { path, document })
+    }
+
+    /// Set the value at a dotted key path such as `ui.layout.max_lines`, adding the key and
+    /// its sections if they are missing. A replaced value keeps its trailing comment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a section on the path is a value rather than a table.
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) -> Result<()> {
+        let mut value = value.into();
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let leaf = parts.pop().unwrap_or_default();
+
+        let mut table = self.document.as_table_mut() as &mut dyn toml_edit::TableLike;
+        for part in parts {
+            if table.get(part).is_none() {
+                let mut section = Table::new();
+                section.set_implicit(true);
+                table.insert(part, Item::Table(section));
+            }
+            table = table
+                .get_mut(part)
+                .and_then(Item::as_table_like_mut)
+                .ok_or_else(|| CoreError::ConfigInvalid {
+                    message: format!("{part} in {key} is not a section"),
+                })?;
+        }
+
+        if let Some(existing) = table.get_mut(leaf).and_then(Item::as_value_mut) {
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        } else {
+            table.insert(leaf, Item::Value(value));
+        }
+        Ok(())
+    }
+
+    /// The edited config, validated like a loaded one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edits do not make a valid config.
+    pub fn config(&self) -> Result<VersualizerConfig> {
+        VersualizerConfig::parse(&self.document.to_string())
+    }
+
+    /// Validate the edited config and write it to the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edits do not make a valid config or the file cannot be
+    /// written.
+    pub fn save(&self) -> Result<VersualizerConfig> {
+        let config = self.config()?;
+        fs::write(&self.path, self.document.to_string())?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"# Versualizer Configuration
+
+[music]
+source = "spotify"
+
+[lyrics]
+# Provider priority: providers are tried in order
+providers = ["lrclib"] # first match wins
+
+[ui]
+"#;
+
+    #[test]
+    fn test_set_keeps_comments() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-config-edit-{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, CONFIG).unwrap();
+
+        let mut editor = ConfigEditor::open_at(&path).unwrap();
+        editor
+            .set(
+                "lyrics.providers",
+                toml_edit::Array::from_iter(["musixmatch", "lrclib"]),
+            )
+            .unwrap();
+        editor.set("ui.layout.max_lines", 5).unwrap();
+        editor.set("sync.line_lead_ms", -150).unwrap();
+        let config = editor.save().unwrap();
+        assert_eq!(config.lyrics.provider_names(), ["musixmatch", "lrclib"]);
+        assert_eq!(config.ui.layout.max_lines, 5);
+        assert_eq!(config.sync.line_lead_ms, -150);
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Versualizer Configuration"));
+        assert!(written.contains("# Provider priority: providers are tried in order"));
+        assert!(written.contains(r#"providers = ["musixmatch", "lrclib"] # first match wins"#));
+        assert!(written.contains("[ui.layout]\nmax_lines = 5"));
+
+        // A value where a section belongs is refused
+        assert!(editor.set("music.source.name", "mpris").is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_invalid_edit_is_not_saved() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-config-edit-invalid-{}.toml",
+            std::process::id()
+        ));
+        fs::write(&path, CONFIG).unwrap();
+
+        let mut editor = ConfigEditor::open_at(&path).unwrap();
+        editor.set("music.source", "gramophone").unwrap();
+        assert!(editor.save().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONFIG);
+        let _ = fs::remove_file(path);
+    }
+}
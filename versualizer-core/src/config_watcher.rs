@@ -0,0 +1,175 @@
+//! Hot-reload of `config.toml`.
+//!
+//! [`ConfigWatcher`] watches the config file and sends every valid edit as a
+//! [`SyncEvent::ConfigChanged`](crate::SyncEvent::ConfigChanged) event. The lyrics fetcher
+//! applies the provider order and fallbacks from it, and frontends apply whatever UI
+//! settings they can change while running. An edit that does not parse is reported as an
+//! error event and the running config is kept, so a half-typed value never takes effect.
+
+use notify_debouncer_mini::{DebounceEventResult, new_debouncer, notify::RecursiveMode};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::config::VersualizerConfig;
+use crate::sync::SyncEngine;
+
+/// How long saves are collected before reloading, so an editor's write-and-rename save
+/// reloads once
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches the config file and announces reloaded configs on the sync engine
+pub struct ConfigWatcher {
+    path: PathBuf,
+    sync_engine: Arc<SyncEngine>,
+    safe_mode: bool,
+    /// Contents of the config file last loaded, so saves that change nothing are ignored
+    last: String,
+}
+
+impl ConfigWatcher {
+    /// Watch the default config file (`~/.config/versualizer/config.toml`)
+    #[must_use]
+    pub fn new(sync_engine: Arc<SyncEngine>) -> Self {
+        Self::with_path(VersualizerConfig::config_path(), sync_engine)
+    }
+
+    /// Watch a config file at a specific path
+    #[must_use]
+    pub fn with_path(path: impl Into<PathBuf>, sync_engine: Arc<SyncEngine>) -> Self {
+        let path = path.into();
+        let last = std::fs::read_to_string(&path).unwrap_or_default();
+        Self {
+            path,
+            sync_engine,
+            safe_mode: false,
+            last,
+        }
+    }
+
+    /// Reduce reloaded configs to what safe mode runs, like the one loaded at startup
+    #[must_use]
+    pub const fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Reload the config whenever its file changes, until `cancel_token` is cancelled
+    pub async fn run(mut self, cancel_token: CancellationToken) {
+        let Some(dir) = self.path.parent().map(PathBuf::from) else {
+            return;
+        };
+        let (tx, mut rx) = mpsc::channel::<Vec<PathBuf>>(16);
+        let mut debouncer = match new_debouncer(DEBOUNCE, move |res: DebounceEventResult| {
+            if let Ok(events) = res {
+                let _ = tx.blocking_send(events.into_iter().map(|event| event.path).collect());
+            }
+        }) {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Failed to create config watcher: {}", e);
+                return;
+            }
+        };
+        // The folder is watched rather than the file, which editors replace on save
+        if let Err(e) = debouncer.watcher().watch(&dir, RecursiveMode::NonRecursive) {
+            error!("Failed to watch config folder: {}", e);
+            return;
+        }
+        info!("Watching {:?} for config changes", self.path);
+
+        loop {
+            let paths = tokio::select! {
+                () = cancel_token.cancelled() => break,
+                paths = rx.recv() => match paths {
+                    Some(paths) => paths,
+                    None => break,
+                },
+            };
+            if !paths
+                .iter()
+                .any(|path| path.file_name() == self.path.file_name())
+            {
+                continue;
+            }
+            if let Some(config) = self.reload() {
+                info!("Config file changed, applying it");
+                self.sync_engine.report_config_changed(Arc::new(config));
+            }
+        }
+
+        info!("Config watcher shutting down");
+        drop(debouncer);
+    }
+
+    /// The config file's new contents if they changed and are valid. Invalid contents are
+    /// reported as an error event.
+    fn reload(&mut self) -> Option<VersualizerConfig> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read config file: {}", e);
+                return None;
+            }
+        };
+        if content == self.last {
+            return None;
+        }
+        let config = match VersualizerConfig::parse_with_env(&content, None) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Config file not reloaded: {}", e);
+                self.sync_engine
+                    .emit_error(format!("Config file not reloaded: {e}"));
+                return None;
+            }
+        };
+        self.last = content;
+        Some(if self.safe_mode {
+            config.into_safe_mode()
+        } else {
+            config
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::sync::SyncEvent;
+
+    fn config(lyrics: &str) -> String {
+        format!("[music]\nsource = \"spotify\"\n\n[lyrics]\n{lyrics}\n\n[ui]\n")
+    }
+
+    #[tokio::test]
+    async fn test_reload_skips_unchanged_and_invalid() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-config-watch-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, config("providers = [\"lrclib\"]")).unwrap();
+        let sync_engine = SyncEngine::new();
+        let mut rx = sync_engine.subscribe();
+        let mut watcher = ConfigWatcher::with_path(&path, sync_engine).with_safe_mode(true);
+
+        assert!(watcher.reload().is_none());
+
+        std::fs::write(&path, config("providers = [\"lrclib\"")).unwrap();
+        assert!(watcher.reload().is_none());
+        assert!(matches!(rx.try_recv(), Ok(SyncEvent::Error { .. })));
+
+        std::fs::write(&path, config("parallel_fetch = true")).unwrap();
+        let config = watcher.reload().unwrap();
+        assert!(config.lyrics.parallel_fetch);
+        // Safe mode still runs without providers
+        assert!(config.lyrics.providers.is_empty());
+        assert!(watcher.reload().is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
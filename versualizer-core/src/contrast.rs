@@ -0,0 +1,110 @@
+//! Keeping lyrics readable over whatever is behind the overlay.
+//!
+//! Pixels sampled from the screen around the overlay give the brightness of the background,
+//! which decides whether the theme's light-on-dark or dark-on-light colors are used. The
+//! switch points are apart, so a background hovering around mid-gray does not flip the
+//! colors back and forth.
+
+/// Backgrounds darker than this (relative luminance) switch to light text
+const DARK_BELOW: f32 = 0.4;
+
+/// Backgrounds brighter than this (relative luminance) switch to dark text
+const LIGHT_ABOVE: f32 = 0.6;
+
+/// How bright the background behind the overlay is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackgroundTone {
+    /// Light text reads best, as with the default theme
+    #[default]
+    Dark,
+    /// Dark text reads best
+    Light,
+}
+
+impl BackgroundTone {
+    /// The tone for a background of `luminance` (0.0 black to 1.0 white). Luminance between
+    /// the two switch points keeps the `previous` tone.
+    #[must_use]
+    pub fn from_luminance(luminance: f32, previous: Self) -> Self {
+        if luminance < DARK_BELOW {
+            Self::Dark
+        } else if luminance > LIGHT_ABOVE {
+            Self::Light
+        } else {
+            previous
+        }
+    }
+
+    /// Get the CSS class applied to the app container for this tone
+    #[must_use]
+    pub const fn as_css_class(self) -> &'static str {
+        match self {
+            Self::Dark => "background-dark",
+            Self::Light => "background-light",
+        }
+    }
+}
+
+/// Mean relative luminance (0.0 to 1.0) of sRGB `pixels`, or `None` without any pixels
+#[must_use]
+pub fn average_luminance(pixels: &[[u8; 3]]) -> Option<f32> {
+    if pixels.is_empty() {
+        return None;
+    }
+    let total: f32 = pixels.iter().map(|&pixel| relative_luminance(pixel)).sum();
+    // Sample counts are far below the point where f32 loses integer precision
+    #[allow(clippy::cast_precision_loss)]
+    let count = pixels.len() as f32;
+    Some(total / count)
+}
+
+/// Relative luminance of an sRGB color as defined by WCAG
+fn relative_luminance([r, g, b]: [u8; 3]) -> f32 {
+    let linear = |channel: u8| {
+        let c = f32::from(channel) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.0722f32.mul_add(linear(b), 0.2126f32.mul_add(linear(r), 0.7152 * linear(g)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_luminance() {
+        assert_eq!(average_luminance(&[]), None);
+        assert!(average_luminance(&[[0, 0, 0]]).unwrap() < 0.001);
+        assert!((average_luminance(&[[255, 255, 255]]).unwrap() - 1.0).abs() < 0.001);
+        // Green looks much brighter than blue
+        let green = average_luminance(&[[0, 255, 0]]).unwrap();
+        let blue = average_luminance(&[[0, 0, 255]]).unwrap();
+        assert!(green > 0.7 && blue < 0.1);
+        let half = average_luminance(&[[0, 0, 0], [255, 255, 255]]).unwrap();
+        assert!((half - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_tone_switches_with_hysteresis() {
+        let tone = BackgroundTone::from_luminance(0.8, BackgroundTone::Dark);
+        assert_eq!(tone, BackgroundTone::Light);
+        // Mid-gray keeps whichever tone was already in use
+        assert_eq!(
+            BackgroundTone::from_luminance(0.5, tone),
+            BackgroundTone::Light
+        );
+        assert_eq!(
+            BackgroundTone::from_luminance(0.5, BackgroundTone::Dark),
+            BackgroundTone::Dark
+        );
+        assert_eq!(
+            BackgroundTone::from_luminance(0.1, tone),
+            BackgroundTone::Dark
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Controlling the music source from the overlay.
+
+use crate::error::CoreError;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Playback commands a music source accepts. The changes come back through the source's
+/// poller like any other playback change, so callers need not update the sync engine.
+#[async_trait]
+pub trait PlaybackController: Send + Sync {
+    /// Resume playback
+    async fn play(&self) -> Result<(), CoreError>;
+
+    /// Pause playback
+    async fn pause(&self) -> Result<(), CoreError>;
+
+    /// Skip to the next track
+    async fn next(&self) -> Result<(), CoreError>;
+
+    /// Go back to the previous track
+    async fn previous(&self) -> Result<(), CoreError>;
+
+    /// Jump to `position` in the current track
+    async fn seek(&self, position: Duration) -> Result<(), CoreError>;
+}
@@ -0,0 +1,261 @@
+//! Line-level diffing and merging of lyrics from different providers.
+//!
+//! Lyrics are compared by line text only, ignoring case, punctuation and spacing, so two
+//! providers with slightly different timestamps or transcription style but the same words
+//! are considered equal.
+
+use crate::lrc::{LrcFile, LrcLine};
+use std::time::Duration;
+
+/// Similarity below which two lyrics are considered to disagree substantially.
+///
+/// Providers routinely differ in a line or two (an ad-lib, a repeated chorus line), which
+/// leaves similarity around 0.8-0.95 for a typical song. Lyrics for a different version or a
+/// wrong match share far fewer lines.
+pub const CONFLICT_SIMILARITY_THRESHOLD: f32 = 0.7;
+
+/// Lines with the same text this close together are treated as one when merging
+const MERGE_DUPLICATE_WINDOW: Duration = Duration::from_secs(3);
+
+/// One step of a line diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Line present in both (text as in the existing lyrics)
+    Same(String),
+    /// Line only in the existing ("mine") lyrics
+    Removed(String),
+    /// Line only in the incoming ("theirs") lyrics
+    Added(String),
+}
+
+/// Diff between existing and incoming lyrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricsDiff {
+    pub lines: Vec<DiffLine>,
+    /// Share of lines the two sides have in common (0.0 - 1.0)
+    pub similarity: f32,
+}
+
+impl LyricsDiff {
+    /// Diff two lyrics by line text
+    #[must_use]
+    pub fn between(mine: &LrcFile, theirs: &LrcFile) -> Self {
+        let ops = diff_ops(mine, theirs);
+        let lines: Vec<DiffLine> = ops
+            .iter()
+            .map(|op| match *op {
+                DiffOp::Same(i) => DiffLine::Same(mine.lines[i].text.clone()),
+                DiffOp::Removed(i) => DiffLine::Removed(mine.lines[i].text.clone()),
+                DiffOp::Added(j) => DiffLine::Added(theirs.lines[j].text.clone()),
+            })
+            .collect();
+
+        let same = ops
+            .iter()
+            .filter(|op| matches!(op, DiffOp::Same(_)))
+            .count();
+        let total = mine.lines.len() + theirs.lines.len();
+        // Line counts are small enough to be exact in f32
+        #[allow(clippy::cast_precision_loss)]
+        let similarity = if total == 0 {
+            1.0
+        } else {
+            (2 * same) as f32 / total as f32
+        };
+
+        Self { lines, similarity }
+    }
+
+    /// Whether the two sides disagree enough to ask the user which to keep
+    #[must_use]
+    pub fn is_conflict(&self) -> bool {
+        self.similarity < CONFLICT_SIMILARITY_THRESHOLD
+    }
+}
+
+/// Merge two lyrics: keep every line of `mine` and add the lines of `theirs` that do not
+/// line up with one of them, ordered by start time. An added line is dropped if a line with
+/// the same text already starts within a few seconds of it, so merging never duplicates a
+/// line the two sides merely aligned differently.
+#[must_use]
+pub fn merge(mine: &LrcFile, theirs: &LrcFile) -> LrcFile {
+    let mut lines: Vec<LrcLine> = mine.lines.clone();
+    for op in diff_ops(mine, theirs) {
+        let DiffOp::Added(j) = op else {
+            continue;
+        };
+        let line = &theirs.lines[j];
+        let text = normalize(&line.text);
+        let duplicate = lines.iter().any(|existing| {
+            normalize(&existing.text) == text
+                && existing.start_time.abs_diff(line.start_time) <= MERGE_DUPLICATE_WINDOW
+        });
+        if !duplicate {
+            lines.push(line.clone());
+        }
+    }
+    lines.sort_by_key(|l| l.start_time);
+
+    LrcFile {
+        metadata: mine.metadata.clone(),
+        lines,
+    }
+}
+
+/// Comparison key for a line: lowercase words separated by single spaces, without
+/// punctuation
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One step of a diff, by line index into the existing and incoming lyrics
+#[derive(Debug, Clone, Copy)]
+enum DiffOp {
+    Same(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Longest-common-subsequence diff of two lyrics' normalized line texts
+fn diff_ops(mine: &LrcFile, theirs: &LrcFile) -> Vec<DiffOp> {
+    let a: Vec<String> = mine.lines.iter().map(|l| normalize(&l.text)).collect();
+    let b: Vec<String> = theirs.lines.iter().map(|l| normalize(&l.text)).collect();
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffOp::Same(i));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffOp::Removed(i));
+            i += 1;
+        } else {
+            out.push(DiffOp::Added(j));
+            j += 1;
+        }
+    }
+    out.extend((i..a.len()).map(DiffOp::Removed));
+    out.extend((j..b.len()).map(DiffOp::Added));
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_identical_lyrics_ignore_timing() {
+        let mine = LrcFile::parse("[00:01.00]Hello\n[00:02.00]World").unwrap();
+        let theirs = LrcFile::parse("[00:01.20]Hello\n[00:02.40]World").unwrap();
+        let diff = LyricsDiff::between(&mine, &theirs);
+
+        assert!((diff.similarity - 1.0).abs() < f32::EPSILON);
+        assert!(!diff.is_conflict());
+    }
+
+    #[test]
+    fn test_diff_reports_changes() {
+        let mine = LrcFile::parse("[00:01.00]Hello\n[00:02.00]World").unwrap();
+        let theirs = LrcFile::parse("[00:01.00]Hello\n[00:02.00]Planet").unwrap();
+        let diff = LyricsDiff::between(&mine, &theirs);
+
+        assert_eq!(
+            diff.lines,
+            vec![
+                DiffLine::Same("Hello".into()),
+                DiffLine::Removed("World".into()),
+                DiffLine::Added("Planet".into()),
+            ]
+        );
+        assert!(diff.is_conflict());
+    }
+
+    #[test]
+    fn test_punctuation_and_case_are_not_conflicts() {
+        let mine = LrcFile::parse("[00:01.00]Hello, world!\n[00:02.00]It's me").unwrap();
+        let theirs = LrcFile::parse("[00:01.00]hello world\n[00:02.00]It's  me.").unwrap();
+        let diff = LyricsDiff::between(&mine, &theirs);
+
+        assert!((diff.similarity - 1.0).abs() < f32::EPSILON);
+        assert_eq!(diff.lines[0], DiffLine::Same("Hello, world!".into()));
+    }
+
+    fn numbered(lines: &[&str]) -> LrcFile {
+        let mut lrc = String::new();
+        for (i, text) in lines.iter().enumerate() {
+            let _ = writeln!(lrc, "[00:{i:02}.00]{text}");
+        }
+        LrcFile::parse(&lrc).unwrap()
+    }
+
+    #[test]
+    fn test_conflict_threshold() {
+        let base = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+        let mine = numbered(&base);
+
+        // An ad-lib or two is normal between providers
+        let mut minor = base;
+        minor[3] = "x";
+        minor[7] = "y";
+        let diff = LyricsDiff::between(&mine, &numbered(&minor));
+        assert!((diff.similarity - 0.8).abs() < 0.01);
+        assert!(!diff.is_conflict());
+
+        // Half the song differing is a different transcription or a wrong match
+        let major = ["a", "b", "c", "d", "e", "v", "w", "x", "y", "z"];
+        assert!(LyricsDiff::between(&mine, &numbered(&major)).is_conflict());
+        assert!(LyricsDiff::between(&mine, &numbered(&["q", "r", "s"])).is_conflict());
+    }
+
+    #[test]
+    fn test_merge_adds_missing_lines_in_order() {
+        let mine = LrcFile::parse("[00:01.00]One\n[00:03.00]Three").unwrap();
+        let theirs = LrcFile::parse("[00:01.00]One\n[00:02.00]Two").unwrap();
+        let merged = merge(&mine, &theirs);
+
+        let texts: Vec<_> = merged.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["One", "Two", "Three"]);
+    }
+
+    #[test]
+    fn test_merge_does_not_duplicate_lines() {
+        // The two sides order the first lines differently, so only one of them lines up,
+        // but the other is still the same line a moment apart
+        let mine = LrcFile::parse("[00:05.00]Chorus\n[00:09.00]Verse").unwrap();
+        let theirs = LrcFile::parse("[00:04.50]Verse\n[00:07.00]chorus\n[00:12.00]Bridge").unwrap();
+        let merged = merge(&mine, &theirs);
+
+        let texts: Vec<_> = merged.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["Chorus", "Verse", "Bridge"]);
+    }
+
+    #[test]
+    fn test_merge_keeps_repeated_lines_far_apart() {
+        let mine = LrcFile::parse("[00:10.00]Chorus\n[00:20.00]Verse").unwrap();
+        let theirs = LrcFile::parse("[00:10.00]Chorus\n[00:20.00]Verse\n[00:30.00]Chorus").unwrap();
+        let merged = merge(&mine, &theirs);
+
+        let texts: Vec<_> = merged.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, ["Chorus", "Verse", "Chorus"]);
+    }
+}
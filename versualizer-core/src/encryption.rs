@@ -0,0 +1,181 @@
+//! Application-level encryption for cached lyrics content.
+//!
+//! Content is sealed with AES-256-GCM and stored as `enc:v1:<base64(nonce || ciphertext)>`.
+//! Values without that prefix are treated as plaintext so existing caches keep working
+//! after encryption is switched on.
+
+use crate::error::{CoreError, Result};
+use crate::secrets::{KeyringSecretStore, SecretStore};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Name of the cache key in the secret store
+pub const CACHE_KEY_SECRET_NAME: &str = "lyrics_cache_key";
+
+/// Delete the cache key from the OS keyring. Call this when the cache is purged: the key
+/// is useless without the data it encrypted. Does nothing if no keyring is available.
+///
+/// # Errors
+///
+/// Returns an error if the keyring is available but the key cannot be deleted.
+pub fn delete_keyring_cache_key() -> Result<()> {
+    if !KeyringSecretStore::is_available() {
+        return Ok(());
+    }
+    KeyringSecretStore::new().delete(CACHE_KEY_SECRET_NAME)
+}
+
+/// Prefix marking an encrypted value
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// AES-256 key length in bytes
+const KEY_LEN: usize = 32;
+
+/// Encrypts and decrypts cache content with a single AES-256-GCM key
+pub struct ContentCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl ContentCipher {
+    /// Create a cipher from raw key bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not 32 bytes long.
+    pub fn from_key(key: &[u8]) -> Result<Self> {
+        let key =
+            UnboundKey::new(&AES_256_GCM, key).map_err(|_| encryption_error("invalid key"))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Load the cache key from `store`, generating and saving a new one if none exists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed or the stored key is malformed.
+    pub fn load_or_create(store: &dyn SecretStore) -> Result<Self> {
+        if let Some(encoded) = store.get(CACHE_KEY_SECRET_NAME)? {
+            let key = BASE64
+                .decode(encoded)
+                .map_err(|_| encryption_error("stored key is not valid base64"))?;
+            return Self::from_key(&key);
+        }
+
+        let rng = SystemRandom::new();
+        let mut key = [0u8; KEY_LEN];
+        rng.fill(&mut key)
+            .map_err(|_| encryption_error("failed to generate key"))?;
+        store.set(CACHE_KEY_SECRET_NAME, &BASE64.encode(key))?;
+        Self::from_key(&key)
+    }
+
+    /// Encrypt a plaintext value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a nonce cannot be generated or sealing fails.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| encryption_error("failed to generate nonce"))?;
+
+        let mut in_out = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| encryption_error("failed to encrypt content"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&in_out);
+        Ok(format!("{ENCRYPTED_PREFIX}{}", BASE64.encode(payload)))
+    }
+
+    /// Decrypt a stored value. Plaintext values are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is encrypted but cannot be decrypted with this key.
+    pub fn decrypt(&self, stored: &str) -> Result<String> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+
+        let payload = BASE64
+            .decode(encoded)
+            .map_err(|_| encryption_error("content is not valid base64"))?;
+        if payload.len() < NONCE_LEN {
+            return Err(encryption_error("content is truncated"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| encryption_error("invalid nonce"))?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| encryption_error("failed to decrypt content (wrong key?)"))?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|_| encryption_error("decrypted content is not valid UTF-8"))
+    }
+
+    /// Check whether a stored value is encrypted
+    #[must_use]
+    pub fn is_encrypted(stored: &str) -> bool {
+        stored.starts_with(ENCRYPTED_PREFIX)
+    }
+}
+
+fn encryption_error(reason: &str) -> CoreError {
+    CoreError::EncryptionError {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher = ContentCipher::from_key(&[7u8; KEY_LEN]).unwrap();
+        let encrypted = cipher.encrypt("[00:01.00]Hello").unwrap();
+
+        assert!(ContentCipher::is_encrypted(&encrypted));
+        assert!(!encrypted.contains("Hello"));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "[00:01.00]Hello");
+    }
+
+    #[test]
+    fn test_plaintext_passthrough() {
+        let cipher = ContentCipher::from_key(&[7u8; KEY_LEN]).unwrap();
+        assert_eq!(cipher.decrypt("plain lyrics").unwrap(), "plain lyrics");
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let encrypted = ContentCipher::from_key(&[1u8; KEY_LEN])
+            .unwrap()
+            .encrypt("secret")
+            .unwrap();
+        let other = ContentCipher::from_key(&[2u8; KEY_LEN]).unwrap();
+        assert!(other.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        assert!(ContentCipher::from_key(&[0u8; 16]).is_err());
+    }
+}
@@ -23,6 +23,10 @@ pub enum CoreError {
     #[error("Failed to parse config file: {0}")]
     ConfigParseError(#[from] toml::de::Error),
 
+    // Music source errors
+    #[error("Music source {name} failed: {reason}")]
+    SourceFailed { name: String, reason: String },
+
     // Lyrics errors
     #[error("Lyrics not found for track: {track} by {artist}")]
     LyricsNotFound { track: String, artist: String },
@@ -30,28 +34,115 @@ pub enum CoreError {
     #[error("Lyrics provider {provider} failed: {reason}")]
     LyricsProviderFailed { provider: String, reason: String },
 
+    #[error("Translation failed: {reason}")]
+    TranslationFailed { reason: String },
+
     #[error("Failed to parse LRC: {reason}")]
     LrcParseError { reason: String },
 
+    #[error("Failed to parse TTML: {reason}")]
+    TtmlParseError { reason: String },
+
+    #[error("Failed to parse subtitles: {reason}")]
+    SubtitleParseError { reason: String },
+
+    #[error("Failed to decode KRC: {reason}")]
+    KrcParseError { reason: String },
+
     // Cache errors
+    #[cfg(feature = "cache")]
     #[error("Cache database error: {0}")]
     CacheError(#[from] tokio_rusqlite::Error),
 
+    #[cfg(feature = "cache")]
     #[error("SQLite error: {0}")]
     SqliteError(#[from] rusqlite::Error),
 
+    #[error(
+        "Lyrics cache schema version {found} is newer than this version of Versualizer supports ({supported})"
+    )]
+    CacheSchemaTooNew { found: u32, supported: u32 },
+
+    #[error(
+        "Cache archive version {found} is newer than this version of Versualizer supports ({supported})"
+    )]
+    CacheArchiveTooNew { found: u32, supported: u32 },
+
+    #[error("Cache encryption error: {reason}")]
+    EncryptionError { reason: String },
+
+    #[error("Secret store error: {reason}")]
+    SecretStoreError { reason: String },
+
     // Network errors
+    #[cfg(feature = "http")]
     #[error("Network request failed: {0}")]
     NetworkError(#[from] reqwest::Error),
 
     // HTTP middleware errors
+    #[cfg(feature = "http")]
     #[error("HTTP middleware error: {0}")]
-    MiddlewareError(#[from] reqwest_middleware::Error),
+    MiddlewareError(reqwest_middleware::Error),
+
+    #[error("Response from {url} is larger than the {limit} byte limit")]
+    ResponseTooLarge { url: String, limit: u64 },
+
+    #[error("Response from {url} has unexpected content type {content_type}")]
+    UnexpectedContentType { url: String, content_type: String },
 
     // IO errors
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    // Serialization errors
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[cfg(feature = "http")]
+impl From<reqwest_middleware::Error> for CoreError {
+    fn from(error: reqwest_middleware::Error) -> Self {
+        // Middleware such as the response limits in `crate::http` fail with a `CoreError`
+        match error {
+            reqwest_middleware::Error::Middleware(e) => match e.downcast::<Self>() {
+                Ok(e) => e,
+                Err(e) => Self::MiddlewareError(reqwest_middleware::Error::Middleware(e)),
+            },
+            e @ reqwest_middleware::Error::Reqwest(_) => Self::MiddlewareError(e),
+        }
+    }
 }
 
 /// Convenience type alias for Results with `CoreError`.
 pub type Result<T> = std::result::Result<T, CoreError>;
+
+impl CoreError {
+    /// Whether this error means a remote service could not be reached at all
+    /// (connection failure or timeout), as opposed to answering with an error.
+    #[must_use]
+    pub fn is_network(&self) -> bool {
+        match self {
+            #[cfg(feature = "http")]
+            Self::NetworkError(e)
+            | Self::MiddlewareError(reqwest_middleware::Error::Reqwest(e)) => {
+                e.is_connect() || e.is_timeout()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a remote service refused the request for exceeding its rate limit (HTTP 429)
+    #[must_use]
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            #[cfg(feature = "http")]
+            Self::NetworkError(e)
+            | Self::MiddlewareError(reqwest_middleware::Error::Reqwest(e)) => {
+                e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+            }
+            // Providers report unexpected statuses in the failure reason
+            Self::LyricsProviderFailed { reason, .. } => reason.contains("429"),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! Persistent ring buffer of recent sync events.
+//!
+//! [`EventLog`] keeps the last N [`SyncEvent`]s in a small `SQLite` database so intermittent
+//! issues can be diagnosed after a restart. Events are buffered and written in batches; the
+//! writer only ever reads from a broadcast receiver, so a slow disk can never stall the
+//! sync engine. If the writer falls behind, the skipped event count is logged instead.
+//!
+//! Each run starts with a [`SESSION_STARTED`] marker, and ends by summarizing the session
+//! (see [`SessionStats`]) into a text file.
+
+use crate::error::Result;
+use crate::session_stats::SessionStats;
+use crate::sync::{SyncEngine, SyncEvent};
+use crate::time::DurationExt;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_rusqlite::Connection;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+const SCHEMA_SQL: &str = r"
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    detail TEXT NOT NULL
+);
+";
+
+/// Kind of the event that marks the start of an app session
+pub const SESSION_STARTED: &str = "session_started";
+
+/// How often buffered events are written to disk
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Buffered event count that triggers an early flush
+const FLUSH_BATCH_SIZE: usize = 32;
+
+/// A persisted event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedEvent {
+    /// Row ID, increasing with insertion order
+    pub id: i64,
+    /// When the event was received
+    pub recorded_at: DateTime<Utc>,
+    /// Short event name, e.g. `track_changed`
+    pub kind: String,
+    /// Human-readable event details
+    pub detail: String,
+}
+
+impl LoggedEvent {
+    /// Describe a sync event as a `(kind, detail)` pair
+    #[must_use]
+    pub fn describe(event: &SyncEvent) -> (&'static str, String) {
+        let position = |p: &Duration| format!("at {}ms", p.as_millis_u64());
+        match event {
+            SyncEvent::PlaybackStarted { track, position: p } => (
+                "playback_started",
+                format!("{} - {} {}", track.artist, track.name, position(p)),
+            ),
+            SyncEvent::PlaybackPaused { position: p } => ("playback_paused", position(p)),
+            SyncEvent::PlaybackResumed { position: p } => ("playback_resumed", position(p)),
+            SyncEvent::PlaybackStopped => ("playback_stopped", String::new()),
+            SyncEvent::AdPlaying => ("ad_playing", String::new()),
+            SyncEvent::TrackChanged { track, position: p } => (
+                "track_changed",
+                format!("{} - {} {}", track.artist, track.name, position(p)),
+            ),
+            SyncEvent::PositionSync { position: p } => ("position_sync", position(p)),
+            SyncEvent::SeekOccurred { position: p } => ("seek", position(p)),
+            SyncEvent::LyricsFetchStarted { provider } => {
+                ("lyrics_fetch_started", provider.clone())
+            }
+            SyncEvent::LyricsFetchFailed { provider, reason } => {
+                ("lyrics_fetch_failed", format!("{provider}: {reason}"))
+            }
+            SyncEvent::LyricsLoaded { lyrics } => {
+                ("lyrics_loaded", format!("{} lines", lyrics.lines.len()))
+            }
+            SyncEvent::UnsyncedLyricsLoaded { text } => (
+                "unsynced_lyrics_loaded",
+                format!("{} lines", text.lines().count()),
+            ),
+            SyncEvent::LyricsNotFound => ("lyrics_not_found", String::new()),
+            SyncEvent::TrackInstrumental => ("track_instrumental", String::new()),
+            SyncEvent::LyricsBlocked { kind } => ("lyrics_blocked", kind.to_string()),
+            SyncEvent::LyricsRetrying {
+                attempt,
+                max_attempts,
+                retry_in,
+            } => (
+                "lyrics_retrying",
+                format!("{attempt}/{max_attempts} in {}s", retry_in.as_secs()),
+            ),
+            SyncEvent::LyricsTrimmed { removed } => ("lyrics_trimmed", removed.clone()),
+            SyncEvent::LyricsSource { provider } => ("lyrics_source", provider.clone()),
+            SyncEvent::AnalysisLoaded { features } => (
+                "analysis_loaded",
+                format!(
+                    "{} beats, {} bars, {} sections",
+                    features.beats.len(),
+                    features.bars.len(),
+                    features.sections.len()
+                ),
+            ),
+            SyncEvent::ConfigChanged { config } => (
+                "config_changed",
+                format!("providers: {}", config.lyrics.provider_names().join(", ")),
+            ),
+            SyncEvent::Error { message, fetch_id } => (
+                "error",
+                fetch_id.map_or_else(|| message.clone(), |id| format!("fetch {id}: {message}")),
+            ),
+        }
+    }
+}
+
+/// `SQLite`-backed ring buffer of recent sync events
+#[derive(Clone)]
+pub struct EventLog {
+    conn: Connection,
+    capacity: usize,
+    summary_path: Option<PathBuf>,
+    print_summary: bool,
+}
+
+impl EventLog {
+    /// Open the event log at the default location, keeping at most `capacity` events.
+    /// Session summaries are written next to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be created or opened.
+    pub async fn new(capacity: usize) -> Result<Self> {
+        let mut log = Self::open(&crate::paths::event_log_db_path(), capacity).await?;
+        log.summary_path = Some(crate::paths::session_summary_path());
+        Ok(log)
+    }
+
+    /// Open the event log at a specific path, keeping at most `capacity` events
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or initialized.
+    pub async fn open(path: &Path, capacity: usize) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path).await?;
+        conn.call(|conn| {
+            conn.execute_batch(SCHEMA_SQL)?;
+            conn.pragma_update(None, "journal_mode", "WAL")?;
+            Ok(())
+        })
+        .await?;
+
+        Ok(Self {
+            conn,
+            capacity,
+            summary_path: None,
+            print_summary: false,
+        })
+    }
+
+    /// Also print the session summary to standard output when the app exits
+    #[must_use]
+    pub const fn with_printed_summary(mut self, print: bool) -> Self {
+        self.print_summary = print;
+        self
+    }
+
+    /// Append events and trim the log back down to its capacity
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn append(&self, events: Vec<(DateTime<Utc>, String, String)>) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let capacity = i64::try_from(self.capacity).unwrap_or(i64::MAX);
+
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                {
+                    let mut stmt = tx.prepare_cached(
+                        "INSERT INTO events (recorded_at, kind, detail) VALUES (?1, ?2, ?3)",
+                    )?;
+                    for (recorded_at, kind, detail) in events {
+                        stmt.execute(rusqlite::params![
+                            recorded_at.timestamp_millis(),
+                            kind,
+                            detail
+                        ])?;
+                    }
+                }
+                tx.execute(
+                    "DELETE FROM events WHERE id <= (SELECT MAX(id) FROM events) - ?1",
+                    [capacity],
+                )?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Get the most recent `limit` events, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn tail(&self, limit: usize) -> Result<Vec<LoggedEvent>> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let mut events = self
+            .query(
+                "SELECT id, recorded_at, kind, detail FROM events ORDER BY id DESC LIMIT ?1",
+                limit,
+            )
+            .await?;
+        events.reverse();
+        Ok(events)
+    }
+
+    /// Get all events with an ID greater than `after_id`, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn since(&self, after_id: i64) -> Result<Vec<LoggedEvent>> {
+        self.query(
+            "SELECT id, recorded_at, kind, detail FROM events WHERE id > ?1 ORDER BY id",
+            after_id,
+        )
+        .await
+    }
+
+    /// Get the events of the latest session, oldest first. If its start has already been
+    /// trimmed from the log, every event is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn last_session(&self) -> Result<Vec<LoggedEvent>> {
+        self.query(
+            "SELECT id, recorded_at, kind, detail FROM events WHERE id >= COALESCE(
+                (SELECT MAX(id) FROM events WHERE kind = 'session_started'), ?1
+            ) ORDER BY id",
+            0,
+        )
+        .await
+    }
+
+    async fn query(&self, sql: &'static str, param: i64) -> Result<Vec<LoggedEvent>> {
+        self.conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare_cached(sql)?;
+                let rows = stmt.query_map([param], |row| {
+                    Ok(LoggedEvent {
+                        id: row.get(0)?,
+                        recorded_at: DateTime::from_timestamp_millis(row.get(1)?)
+                            .unwrap_or_else(Utc::now),
+                        kind: row.get(2)?,
+                        detail: row.get(3)?,
+                    })
+                })?;
+                Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Persist sync engine events until cancelled or the engine is dropped
+    pub async fn run(self, sync_engine: Arc<SyncEngine>, cancel_token: CancellationToken) {
+        let mut rx = sync_engine.subscribe();
+        let mut pending = vec![(Utc::now(), SESSION_STARTED.to_string(), String::new())];
+        let mut flush_interval = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                () = cancel_token.cancelled() => break,
+                _ = flush_interval.tick() => self.flush(&mut pending).await,
+                event = rx.recv() => match event {
+                    Ok(event) => {
+                        let (kind, detail) = LoggedEvent::describe(&event);
+                        pending.push((Utc::now(), kind.to_string(), detail));
+                        if pending.len() >= FLUSH_BATCH_SIZE {
+                            self.flush(&mut pending).await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        pending.push((Utc::now(), "lagged".to_string(), format!("{n} events dropped")));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            }
+        }
+
+        self.flush(&mut pending).await;
+        self.summarize_session().await;
+        info!("Event log writer stopped");
+    }
+
+    /// Log the session's statistics, and write them to the summary file
+    async fn summarize_session(&self) {
+        let stats = match self.last_session().await {
+            Ok(events) => SessionStats::from_events(&events),
+            Err(e) => {
+                warn!("Failed to read session events: {}", e);
+                return;
+            }
+        };
+        info!("Session summary:\n{}", stats);
+        if self.print_summary {
+            println!("{stats}");
+        }
+        if let Some(ref path) = self.summary_path
+            && let Err(e) = tokio::fs::write(path, format!("{stats}\n")).await
+        {
+            warn!("Failed to write session summary to {:?}: {}", path, e);
+        }
+    }
+
+    async fn flush(&self, pending: &mut Vec<(DateTime<Utc>, String, String)>) {
+        if let Err(e) = self.append(std::mem::take(pending)).await {
+            warn!("Failed to persist sync events: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    async fn open_temp(capacity: usize) -> (EventLog, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-event-log-{}-{}.db",
+            std::process::id(),
+            capacity
+        ));
+        let _ = std::fs::remove_file(&path);
+        (EventLog::open(&path, capacity).await.unwrap(), path)
+    }
+
+    fn entry(detail: &str) -> (DateTime<Utc>, String, String) {
+        (Utc::now(), "position_sync".to_string(), detail.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_ring_buffer_keeps_latest() {
+        let (log, path) = open_temp(3).await;
+        log.append((0..5).map(|i| entry(&i.to_string())).collect())
+            .await
+            .unwrap();
+
+        let events = log.tail(10).await.unwrap();
+        let details: Vec<_> = events.iter().map(|e| e.detail.as_str()).collect();
+        assert_eq!(details, ["2", "3", "4"]);
+
+        let newer = log.since(events[1].id).await.unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].detail, "4");
+
+        // Without a session marker left, the whole log is the latest session
+        assert_eq!(log.last_session().await.unwrap().len(), 3);
+        log.append(vec![
+            (Utc::now(), SESSION_STARTED.to_string(), String::new()),
+            entry("5"),
+        ])
+        .await
+        .unwrap();
+        let session = log.last_session().await.unwrap();
+        let kinds: Vec<_> = session.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, [SESSION_STARTED, "position_sync"]);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_describe_event() {
+        let (kind, detail) = LoggedEvent::describe(&SyncEvent::SeekOccurred {
+            position: Duration::from_millis(1500),
+        });
+        assert_eq!(kind, "seek");
+        assert_eq!(detail, "at 1500ms");
+    }
+}
@@ -1,21 +1,137 @@
 //! Lyrics fetcher that orchestrates multiple lyrics providers.
+//!
+//! Each fetch runs in a `fetch` tracing span with a fetch ID and the track ID, and each
+//! provider request in a nested `provider` span, so log lines (including those of the HTTP
+//! middleware) from providers racing in parallel can be told apart. Provider failures are
+//! sent as [`SyncEvent::Error`] events carrying the fetch ID.
+//!
+//! For the UI, each provider request is announced with [`SyncEvent::LyricsFetchStarted`]
+//! and each failure with [`SyncEvent::LyricsFetchFailed`], so the overlay can show which
+//! provider is being searched. Prefetches run silently.
+//!
+//! When every provider is unreachable the miss is not remembered. The fetch is repeated
+//! after `[lyrics] retry_after_secs` while the same track plays, announced with
+//! [`SyncEvent::LyricsRetrying`], until `retry_attempts` run out.
+//!
+//! Tracks on the blocklist (see [`crate::blocklist`]) are never fetched: the fetcher sends
+//! [`SyncEvent::LyricsBlocked`] instead and the overlay stays idle.
 
-use std::sync::Arc;
+use futures::FutureExt;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
-use tracing::{info, warn};
+use tracing::{Instrument, Span, debug, info, info_span, warn};
 
-use crate::cache::{LyricsCache, TrackMetadata};
+use crate::blocklist::{BlockKind, Blocklist};
+use crate::cache::{LyricsCache, LyricsType, TrackMetadata};
+use crate::config::LyricsConfig;
+use crate::error::{CoreError, Result};
+use crate::lookup::search_providers;
+use crate::lrc::LrcFile;
+use crate::mirror::LyricsMirror;
+use crate::normalize::{QueryEnricher, QueryNormalizer};
+use crate::overrides::LyricsOverrides;
 use crate::playback::TrackInfo;
-use crate::provider::{LyricsProvider, LyricsQuery, LyricsResult};
+use crate::preflight::{CheckResult, CheckStatus};
+use crate::processors::TextPipeline;
+use crate::provider::{
+    AuthState, FetchedLyrics, LyricsCandidate, LyricsProvider, LyricsQuery, LyricsResult,
+};
+use crate::provider_status::{ProviderStatus, ProviderStatusRegistry, all_unreachable};
+use crate::romanize::romanize_line;
+use crate::source::MusicSource;
 use crate::sync::{SyncEngine, SyncEvent};
 use crate::time::DurationExt;
+use crate::timing_editor::{EDITOR_PROVIDER, TimingEditor};
+
+/// How often unreachable providers are probed while every provider is offline, so the
+/// offline notice clears once the network is back rather than on the next track change
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+/// How long after a fetch further track events for the same track are ignored, so a track
+/// change followed by a playback start, or a quick pause and resume, fetches only once
+const FETCH_DEBOUNCE: Duration = Duration::from_secs(2);
+/// Longest wait for a [`QueryEnricher`] before searching with the track's own names
+const ENRICH_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Lyrics fetcher that listens for track changes and fetches lyrics
 pub struct LyricsFetcher {
     sync_engine: Arc<SyncEngine>,
     cache: Arc<LyricsCache>,
     providers: Vec<Box<dyn LyricsProvider>>,
+    /// Indices into `providers` of the ones in use, in priority order
+    order: RwLock<Vec<usize>>,
     cancel_token: CancellationToken,
+    mirror: Option<LyricsMirror>,
+    overrides: Option<LyricsOverrides>,
+    status: Arc<ProviderStatusRegistry>,
+    processors: TextPipeline,
+    normalizer: RwLock<Arc<QueryNormalizer>>,
+    /// Tracks and artists blocked in `[lyrics.blocklist]`; the ones blocked from the app are
+    /// read from the cache
+    blocklist: RwLock<Arc<Blocklist>>,
+    enricher: Option<Box<dyn QueryEnricher>>,
+    romanize_lines: bool,
+    allow_unsynced: AtomicBool,
+    parallel: AtomicBool,
+    retry_after_ms: AtomicU64,
+    retry_attempts: AtomicU32,
+    not_found_ttl: Duration,
+    next_fetch_id: AtomicU64,
+}
+
+/// What the providers returned for a track
+pub(crate) enum Outcome {
+    /// Synced lyrics, from the named provider
+    Synced(&'static str, FetchedLyrics),
+    /// Plain lyrics, from the named provider
+    Unsynced(&'static str, FetchedLyrics),
+    /// The named provider reported the track as instrumental
+    Instrumental(&'static str, FetchedLyrics),
+    /// Nothing usable
+    NotFound,
+    /// Every provider failed with a network error, so there was no answer at all
+    Unreachable,
+}
+
+/// A fetch to repeat after every provider was unreachable
+#[derive(Debug)]
+struct PendingRetry {
+    source: MusicSource,
+    track_id: String,
+    /// Retries made so far, this one included
+    attempt: u32,
+    at: tokio::time::Instant,
+}
+
+impl PendingRetry {
+    /// Whether `track` is the track this retry is for
+    fn is_for(&self, track: &TrackInfo) -> bool {
+        self.source == track.source && self.track_id == track.source_track_id
+    }
+}
+
+/// The last track fetched by [`LyricsFetcher::run`], for debouncing repeated events
+#[derive(Debug, Default)]
+struct FetchDebounce {
+    last: Option<(MusicSource, String, Instant)>,
+}
+
+impl FetchDebounce {
+    /// Whether `track` was fetched less than [`FETCH_DEBOUNCE`] before `now`
+    fn is_repeat(&self, track: &TrackInfo, now: Instant) -> bool {
+        self.last.as_ref().is_some_and(|(source, id, at)| {
+            *source == track.source
+                && *id == track.source_track_id
+                && now.saturating_duration_since(*at) < FETCH_DEBOUNCE
+        })
+    }
+
+    /// Record that `track` finished fetching at `now`
+    fn record(&mut self, track: &TrackInfo, now: Instant) {
+        self.last = Some((track.source, track.source_track_id.clone(), now));
+    }
 }
 
 impl LyricsFetcher {
@@ -35,9 +151,616 @@ impl LyricsFetcher {
         Self {
             sync_engine,
             cache,
+            order: RwLock::new((0..providers.len()).collect()),
             providers,
             cancel_token: cancel_token.unwrap_or_default(),
+            mirror: None,
+            overrides: None,
+            status: ProviderStatusRegistry::new(),
+            processors: TextPipeline::new(),
+            normalizer: RwLock::new(Arc::new(QueryNormalizer::new())),
+            blocklist: RwLock::new(Arc::new(Blocklist::new())),
+            enricher: None,
+            romanize_lines: false,
+            allow_unsynced: AtomicBool::new(true),
+            parallel: AtomicBool::new(false),
+            retry_after_ms: AtomicU64::new(LyricsConfig::default().retry_after().as_millis_u64()),
+            retry_attempts: AtomicU32::new(LyricsConfig::default().retry_attempts),
+            not_found_ttl: Duration::ZERO,
+            next_fetch_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Also write every newly fetched synced lyric to a folder of `.lrc` files
+    #[must_use]
+    pub fn with_mirror(mut self, mirror: LyricsMirror) -> Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// Show lyrics from the overrides folder instead of cached or fetched ones when a file
+    /// exists for the track
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: LyricsOverrides) -> Self {
+        self.overrides = Some(overrides);
+        self
+    }
+
+    /// Show the override lyrics for `track`, if there are any. Returns whether they were shown.
+    pub async fn load_override(&self, track: &TrackInfo) -> bool {
+        let Some(ref overrides) = self.overrides else {
+            return false;
+        };
+        match overrides.load(track).await {
+            Ok(Some(mut lrc)) => {
+                info!("Using override lyrics for {}", track.name);
+                self.prepare_lrc(&mut lrc);
+                self.sync_engine.set_lyrics(lrc).await;
+                self.sync_engine.report_lyrics_source("override");
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                warn!("Failed to read override lyrics for {}: {}", track.name, e);
+                false
+            }
+        }
+    }
+
+    /// Run lyrics through `processors` before they are displayed. The cache and mirror keep
+    /// the lyrics as fetched, so changing the pipeline applies to cached songs too.
+    #[must_use]
+    pub fn with_processors(mut self, processors: TextPipeline) -> Self {
+        self.processors = processors;
+        self
+    }
+
+    /// Clean up track and artist names with `normalizer` before searching providers (the
+    /// built-in rules by default)
+    #[must_use]
+    pub fn with_query_normalizer(self, normalizer: QueryNormalizer) -> Self {
+        *self
+            .normalizer
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(normalizer);
+        self
+    }
+
+    /// Resolve tracks with `enricher` before searching providers, and search with the
+    /// canonical names it finds. Lookups that fail or take longer than 5 seconds are
+    /// skipped.
+    #[must_use]
+    pub fn with_enricher(mut self, enricher: Box<dyn QueryEnricher>) -> Self {
+        self.enricher = Some(enricher);
+        self
+    }
+
+    /// Add a romanized reading (romaji or romaja) to every Japanese or Korean line of synced
+    /// lyrics before they are displayed (off by default)
+    #[must_use]
+    pub const fn with_line_romanization(mut self, romanize: bool) -> Self {
+        self.romanize_lines = romanize;
+        self
+    }
+
+    /// Whether plain lyrics are shown when no provider has synced lyrics (on by default)
+    #[must_use]
+    pub const fn with_unsynced_fallback(mut self, allow: bool) -> Self {
+        self.allow_unsynced = AtomicBool::new(allow);
+        self
+    }
+
+    /// Query all providers at once instead of one at a time (off by default). The first
+    /// synced lyrics to arrive win, with provider priority breaking ties.
+    #[must_use]
+    pub const fn with_parallel_fetch(mut self, parallel: bool) -> Self {
+        self.parallel = AtomicBool::new(parallel);
+        self
+    }
+
+    /// Never fetch or show lyrics for the tracks and artists of `blocklist`, on top of the
+    /// ones blocked in the cache
+    #[must_use]
+    pub fn with_blocklist(self, blocklist: Blocklist) -> Self {
+        *self
+            .blocklist
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Arc::new(blocklist);
+        self
+    }
+
+    /// When every provider is unreachable, fetch again after `after` while the same track
+    /// plays, up to `attempts` times (60 seconds and 3 times by default, 0 never retries)
+    #[must_use]
+    pub fn with_unreachable_retry(self, after: Duration, attempts: u32) -> Self {
+        self.retry_after_ms
+            .store(after.as_millis_u64(), Ordering::Relaxed);
+        self.retry_attempts.store(attempts, Ordering::Relaxed);
+        self
+    }
+
+    /// Remember tracks that no provider had lyrics for, and skip fetching them again until
+    /// `ttl` has passed (off by default). A zero `ttl` disables this.
+    #[must_use]
+    pub const fn with_not_found_ttl(mut self, ttl: Duration) -> Self {
+        self.not_found_ttl = ttl;
+        self
+    }
+
+    /// Apply `[lyrics]` settings of a reloaded config: the order of the providers and
+    /// whether they are used at all, the unsynced fallback, parallel fetching, retries after
+    /// network failures, the clean-up of names searched with (`[lyrics.query]`) and the
+    /// blocklist (`[lyrics.blocklist]`). Providers
+    /// that were not set up at startup need a restart to be added, and are skipped.
+    pub fn apply_config(&self, config: &LyricsConfig) {
+        let mut order = Vec::new();
+        for name in config.provider_names() {
+            if let Some(index) = self.providers.iter().position(|p| p.name() == name) {
+                order.push(index);
+            } else {
+                warn!("Provider {} takes effect after a restart", name);
+            }
+        }
+        *self.order.write().unwrap_or_else(PoisonError::into_inner) = order;
+        self.allow_unsynced
+            .store(config.allow_unsynced, Ordering::Relaxed);
+        self.parallel
+            .store(config.parallel_fetch, Ordering::Relaxed);
+        self.retry_after_ms
+            .store(config.retry_after().as_millis_u64(), Ordering::Relaxed);
+        self.retry_attempts
+            .store(config.retry_attempts, Ordering::Relaxed);
+        *self
+            .normalizer
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) =
+            Arc::new(QueryNormalizer::from_config(&config.query));
+        *self
+            .blocklist
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) =
+            Arc::new(Blocklist::from_config(&config.blocklist));
+        info!(
+            "Applied lyrics settings, providers: {:?}",
+            self.providers()
+                .iter()
+                .map(|p| p.name())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Provider query for `track`, with the names to search with cleaned up
+    pub(crate) async fn query_for(&self, track: &TrackInfo) -> LyricsQuery {
+        let normalizer = self
+            .normalizer
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut query = normalizer.apply(query_for(track));
+        if let Some(ref enricher) = self.enricher {
+            match tokio::time::timeout(ENRICH_TIMEOUT, enricher.resolve(&query)).await {
+                Ok(Ok(Some(canonical))) => {
+                    debug!(
+                        "{} matched {} ({})",
+                        enricher.name(),
+                        track.name,
+                        canonical.id
+                    );
+                    query = normalizer.apply_canonical(query, enricher.name(), &canonical);
+                }
+                Ok(Ok(None)) => debug!("{} has no match for {}", enricher.name(), track.name),
+                Ok(Err(e)) => warn!("{} lookup failed: {}", enricher.name(), e),
+                Err(_) => warn!("{} lookup timed out", enricher.name()),
+            }
+        }
+        if query.search_track.is_some() || query.search_artist.is_some() {
+            debug!(
+                "Searching lyrics for {} - {}",
+                query.search_artist_name(),
+                query.search_track_name()
+            );
+        }
+        query
+    }
+
+    /// Registry of provider health, updated after every provider request
+    #[must_use]
+    pub fn provider_status(&self) -> Arc<ProviderStatusRegistry> {
+        self.status.clone()
+    }
+
+    /// Credential state of every provider, in priority order
+    pub async fn auth_states(&self) -> Vec<(&'static str, Option<AuthState>)> {
+        let providers = self.providers();
+        let mut states = Vec::with_capacity(providers.len());
+        for provider in providers {
+            states.push((provider.name(), provider.auth_state().await));
+        }
+        states
+    }
+
+    /// Re-authenticate `provider` and record the outcome in the status registry
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no such provider is configured or re-authentication fails.
+    pub async fn reauth(&self, provider: &str) -> Result<()> {
+        let provider = self.provider(provider)?;
+        info!("Re-authenticating lyrics provider: {}", provider.name());
+        let result = provider.reauth().await;
+        self.status.record(
+            provider.name(),
+            result
+                .as_ref()
+                .map_or_else(ProviderStatus::from_error, |()| ProviderStatus::Available),
+        );
+        result
+    }
+
+    /// Run `provider`'s check and record a failure in the status registry
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no such provider is configured.
+    pub async fn check_provider(&self, provider: &str) -> Result<CheckResult> {
+        let provider = self.provider(provider)?;
+        let result = provider.check().await;
+        if result.status == CheckStatus::Fail {
+            self.status.record(
+                provider.name(),
+                ProviderStatus::Failing {
+                    error: result.detail.clone(),
+                },
+            );
+        }
+        Ok(result)
+    }
+
+    /// Switch the current track to the next provider's cached lyrics (in provider name order,
+    /// wrapping around) and remember the choice for the next time the track plays. Returns
+    /// the provider now shown, or `None` if no other provider's lyrics are cached for the
+    /// track or override lyrics are pinned over them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read or updated.
+    pub async fn next_alternative(&self) -> Result<Option<String>> {
+        let Some(track) = self.sync_engine.current_track().await else {
+            return Ok(None);
+        };
+        if let Some(ref overrides) = self.overrides
+            && matches!(overrides.load(&track).await, Ok(Some(_)))
+        {
+            return Ok(None);
+        }
+        let Some(cached) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let alternatives: Vec<_> = self
+            .cache
+            .alternatives(cached.id)
+            .await?
+            .into_iter()
+            .filter(|alternative| {
+                self.allow_unsynced.load(Ordering::Relaxed)
+                    || alternative.lyrics_type == LyricsType::Synced
+            })
+            .collect();
+        let current = alternatives
+            .iter()
+            .position(|alternative| alternative.provider == cached.provider);
+        let next = match current {
+            Some(i) => &alternatives[(i + 1) % alternatives.len()],
+            None => match alternatives.first() {
+                Some(first) => first,
+                None => return Ok(None),
+            },
+        };
+        if next.provider == cached.provider {
+            return Ok(None);
+        }
+
+        self.cache
+            .select_alternative(cached.id, &next.provider)
+            .await?;
+        info!(
+            "Switched lyrics for {} - {} to {}",
+            track.artist, track.name, next.provider
+        );
+        match next.to_lyrics_result() {
+            LyricsResult::Synced(mut lrc) => {
+                self.prepare_lrc(&mut lrc);
+                self.sync_engine.set_lyrics(lrc).await;
+            }
+            LyricsResult::Unsynced(text) => {
+                let text = self.processors.apply_text(&text);
+                self.sync_engine.set_unsynced_lyrics(text).await;
+            }
+            LyricsResult::Instrumental => self.sync_engine.set_instrumental().await,
+            LyricsResult::NotFound => {}
+        }
+        self.sync_engine.report_lyrics_source(&next.provider);
+        Ok(Some(next.provider.clone()))
+    }
+
+    /// Start retiming the current track's cached lyrics, synced or plain. Returns the track
+    /// with the editor, or `None` if no lyrics are cached for the current track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read.
+    pub async fn edit_current_lyrics(&self) -> Result<Option<(TrackInfo, TimingEditor)>> {
+        let Some(track) = self.sync_engine.current_track().await else {
+            return Ok(None);
+        };
+        let cached = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?;
+        let editor = cached.and_then(|cached| match cached.to_lyrics_result() {
+            LyricsResult::Synced(lrc) => Some(TimingEditor::from_lrc(lrc)),
+            LyricsResult::Unsynced(text) => Some(TimingEditor::from_text(&text)),
+            LyricsResult::Instrumental | LyricsResult::NotFound => None,
+        });
+        Ok(editor.map(|editor| (track, editor)))
+    }
+
+    /// Save retimed lyrics for `track`, and show them if it is still playing. They are cached
+    /// as the [`EDITOR_PROVIDER`]'s lyrics and pinned over fetched ones, and the track's
+    /// saved offset is cleared since the new timestamps already match playback. Returns
+    /// `false` if no lyrics are cached for the track.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read or updated.
+    pub async fn save_edited_lyrics(&self, track: &TrackInfo, mut lyrics: LrcFile) -> Result<bool> {
+        let Some(cached) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        self.cache
+            .replace_lyrics(cached.id, EDITOR_PROVIDER, &track.source_track_id, &lyrics)
+            .await?;
+        self.cache
+            .set_offset(track.source.as_str(), &track.source_track_id, 0)
+            .await?;
+        info!("Saved retimed lyrics for {} - {}", track.artist, track.name);
+
+        if self.is_playing(track).await {
+            self.sync_engine.set_track_offset_ms(0).await;
+            self.prepare_lrc(&mut lyrics);
+            self.sync_engine.set_lyrics(lyrics).await;
+            self.sync_engine.report_lyrics_source(EDITOR_PROVIDER);
+        }
+        Ok(true)
+    }
+
+    /// Every provider's candidate lyrics for `track`, in priority order and best match first
+    /// within each, for choosing by hand when the automatic match is wrong
+    pub async fn search(&self, track: &TrackInfo) -> Vec<LyricsCandidate> {
+        let query = self.query_for(track).await;
+        search_providers(&query, self.providers()).await
+    }
+
+    /// Lyrics cached for `track`, one candidate per provider that had some, for choosing
+    /// between them by hand
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read.
+    pub async fn cached_candidates(&self, track: &TrackInfo) -> Result<Vec<LyricsCandidate>> {
+        let Some(cached) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        let duration_secs = cached
+            .duration_ms
+            .and_then(|ms| u64::try_from(ms).ok())
+            .map(|ms| Duration::from_millis(ms).as_secs_f64());
+        Ok(self
+            .cache
+            .alternatives(cached.id)
+            .await?
+            .into_iter()
+            .map(|alternative| LyricsCandidate {
+                preview: Some(LyricsCandidate::preview_of(&alternative.content)),
+                synced: alternative.lyrics_type == LyricsType::Synced,
+                provider: alternative.provider,
+                id: alternative.provider_id,
+                title: cached.track.clone(),
+                artist: cached.artist.clone(),
+                duration_secs,
+            })
+            .collect())
+    }
+
+    /// Use `candidate`, from [`Self::cached_candidates`] or [`Self::search`], as `track`'s
+    /// lyrics: cached lyrics are switched to, others are fetched and cached. The choice is
+    /// pinned in the cache under the track's ID over other providers' lyrics, so it is used
+    /// whenever the track plays, and shown straight away if the track is still playing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lyrics cannot be cached, or if the candidate is not cached and
+    /// its provider is not configured, fails or has no lyrics for it.
+    pub async fn use_candidate(
+        &self,
+        track: &TrackInfo,
+        candidate: &LyricsCandidate,
+    ) -> Result<()> {
+        let cached = match self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+        {
+            Some(cached) => {
+                self.cache
+                    .alternatives(cached.id)
+                    .await?
+                    .into_iter()
+                    .find(|alternative| {
+                        alternative.provider == candidate.provider
+                            && alternative.provider_id == candidate.id
+                    })
+            }
+            None => None,
+        };
+        let result = if let Some(alternative) = cached {
+            self.cache
+                .select_alternative(alternative.lyrics_id, &alternative.provider)
+                .await?;
+            alternative.to_lyrics_result()
+        } else {
+            let provider = self.provider(&candidate.provider)?;
+            let query = self.query_for(track).await;
+            let fetched = provider.fetch_candidate(&query, candidate).await?;
+            if !fetched.result.is_found() {
+                return Err(CoreError::LyricsNotFound {
+                    track: candidate.title.clone(),
+                    artist: candidate.artist.clone(),
+                });
+            }
+            let id = self
+                .cache
+                .store(
+                    track.source.as_str(),
+                    &track.source_track_id,
+                    &fetched.result,
+                    &track_metadata(track),
+                    provider.name(),
+                    &fetched.provider_id,
+                )
+                .await?;
+            self.cache.select_alternative(id, provider.name()).await?;
+            fetched.result
+        };
+        info!(
+            "Chose {}'s lyrics {} for {} - {}",
+            candidate.provider, candidate.id, track.artist, track.name
+        );
+
+        if self.is_playing(track).await {
+            match result {
+                LyricsResult::Synced(lrc) => {
+                    let mut display = lrc.clone();
+                    self.prepare_lrc(&mut display);
+                    self.sync_engine.set_lyrics(display).await;
+                    self.mirror_in_background(track, &lrc);
+                }
+                LyricsResult::Unsynced(text) => {
+                    let text = self.processors.apply_text(&text);
+                    self.sync_engine.set_unsynced_lyrics(text).await;
+                }
+                LyricsResult::Instrumental => self.sync_engine.set_instrumental().await,
+                LyricsResult::NotFound => {}
+            }
+            self.sync_engine.report_lyrics_source(&candidate.provider);
+        }
+        Ok(())
+    }
+
+    /// Why `track`'s lyrics are blocked, by `[lyrics.blocklist]` or an entry in the cache, or
+    /// `None` if they are not
+    pub async fn blocked_by(&self, track: &TrackInfo) -> Option<BlockKind> {
+        let configured = self
+            .blocklist
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(kind) = configured.blocks(track) {
+            return Some(kind);
+        }
+        match self.cache.blocklist(&Blocklist::new()).await {
+            Ok(blocklist) => blocklist.blocks(track),
+            Err(e) => {
+                warn!("Failed to read the blocklist: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Never show lyrics for `track` (`kind` [`BlockKind::Track`]) or any track by its first
+    /// artist ([`BlockKind::Artist`]). The entry is kept in the cache, and the overlay goes
+    /// idle straight away if the track is playing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry cannot be stored.
+    pub async fn block(&self, kind: BlockKind, track: &TrackInfo) -> Result<()> {
+        let label = kind.label_of(track);
+        self.cache.block(kind, &kind.key_of(track), &label).await?;
+        info!("Blocked lyrics of {} {}", kind, label);
+        if self.is_playing(track).await {
+            self.sync_engine.set_lyrics_blocked(kind).await;
         }
+        Ok(())
+    }
+
+    /// Show lyrics for `track` again, removing the cache entries that block it or its first
+    /// artist, and fetch them if it is playing. Entries of `[lyrics.blocklist]` are left to
+    /// the config file. Returns whether an entry was removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache update fails.
+    pub async fn unblock(&self, track: &TrackInfo) -> Result<bool> {
+        let mut unblocked = false;
+        for kind in [BlockKind::Track, BlockKind::Artist] {
+            unblocked |= self.cache.unblock(kind, &kind.key_of(track)).await?;
+        }
+        if unblocked && self.is_playing(track).await {
+            self.fetch_lyrics_for_track(track).await;
+        }
+        Ok(unblocked)
+    }
+
+    /// Whether `track` is the one playing
+    async fn is_playing(&self, track: &TrackInfo) -> bool {
+        self.sync_engine
+            .current_track()
+            .await
+            .is_some_and(|current| {
+                current.source == track.source && current.source_track_id == track.source_track_id
+            })
+    }
+
+    /// Providers in use, in priority order
+    pub(crate) fn providers(&self) -> Vec<&dyn LyricsProvider> {
+        self.order
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter_map(|&index| self.providers.get(index))
+            .map(AsRef::as_ref)
+            .collect()
+    }
+
+    /// The lyrics cache fetched lyrics are stored in
+    pub(crate) fn cache(&self) -> &LyricsCache {
+        &self.cache
+    }
+
+    fn provider(&self, name: &str) -> Result<&dyn LyricsProvider> {
+        self.providers
+            .iter()
+            .find(|provider| provider.name() == name)
+            .map(AsRef::as_ref)
+            .ok_or_else(|| CoreError::LyricsProviderFailed {
+                provider: name.to_string(),
+                reason: "not configured".into(),
+            })
     }
 
     /// Get a clone of the cancellation token
@@ -59,6 +782,8 @@ impl LyricsFetcher {
         info!("Initializing lyrics fetching handler");
 
         let mut rx = self.sync_engine.subscribe();
+        let mut debounce = FetchDebounce::default();
+        let mut retry: Option<PendingRetry> = None;
 
         // Check if there's already a track loaded on startup
         if let Some(track) = self.sync_engine.current_track().await
@@ -68,20 +793,56 @@ impl LyricsFetcher {
                 "Found existing track on startup: {} - {}, fetching lyrics",
                 track.artist, track.name
             );
-            self.fetch_lyrics_for_track(&track).await;
+            if self.fetch_lyrics_for_track(&track).await {
+                retry = self.schedule_retry(&track, 1).await;
+            }
+            debounce.record(&track, Instant::now());
         }
 
+        let mut probe = tokio::time::interval(OFFLINE_PROBE_INTERVAL);
+        probe.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
         loop {
+            let retry_at = retry.as_ref().map(|pending| pending.at);
             tokio::select! {
                 () = self.cancel_token.cancelled() => {
                     info!("Lyrics fetcher shutting down");
                     break;
                 }
+                () = sleep_until_some(retry_at), if retry_at.is_some() => {
+                    let Some(pending) = retry.take() else { continue };
+                    match self.sync_engine.current_track().await {
+                        Some(track) if pending.is_for(&track) => {
+                            info!("Fetching lyrics again for {} (retry {})", track.name, pending.attempt);
+                            if self.fetch_lyrics_for_track(&track).await {
+                                retry = self.schedule_retry(&track, pending.attempt + 1).await;
+                            }
+                        }
+                        _ => debug!("Track changed, dropping the lyrics retry"),
+                    }
+                }
+                _ = probe.tick() => {
+                    if all_unreachable(&self.status.snapshot()) {
+                        self.probe_unreachable().await;
+                    }
+                }
                 event = rx.recv() => {
                     match event {
                         Ok(SyncEvent::TrackChanged { track, .. } |
                            SyncEvent::PlaybackStarted { track, .. }) => {
-                            self.fetch_lyrics_for_track(&track).await;
+                            if debounce.is_repeat(&track, Instant::now()) {
+                                debug!("Skipping repeated fetch for {} - {}", track.artist, track.name);
+                                continue;
+                            }
+                            retry = None;
+                            if self.fetch_lyrics_for_track(&track).await {
+                                retry = self.schedule_retry(&track, 1).await;
+                            }
+                            // Timed from the end of the fetch, as events queue up during it
+                            debounce.record(&track, Instant::now());
+                        }
+                        Ok(SyncEvent::ConfigChanged { config }) => {
+                            self.apply_config(&config.lyrics);
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Closed) => {
                             break;
@@ -95,103 +856,1152 @@ impl LyricsFetcher {
         }
     }
 
-    /// Fetch lyrics for a track
-    async fn fetch_lyrics_for_track(&self, track: &TrackInfo) {
-        let provider_names: Vec<_> = self.providers.iter().map(|p| p.name()).collect();
+    /// Plan retry `attempt` for `track` after every provider was unreachable, or show that
+    /// there are no lyrics once retries run out
+    async fn schedule_retry(&self, track: &TrackInfo, attempt: u32) -> Option<PendingRetry> {
+        let max_attempts = self.retry_attempts.load(Ordering::Relaxed);
+        if attempt > max_attempts {
+            if max_attempts > 0 {
+                info!(
+                    "Giving up on lyrics for {} after {} retries",
+                    track.name, max_attempts
+                );
+            }
+            self.sync_engine.set_no_lyrics().await;
+            return None;
+        }
+        let retry_in = Duration::from_millis(self.retry_after_ms.load(Ordering::Relaxed));
         info!(
-            "Fetching lyrics for: {} - {} (source: {}, providers: {:?})",
-            track.artist, track.name, track.source, provider_names
+            "Every provider is unreachable, fetching lyrics again in {:?} ({}/{})",
+            retry_in, attempt, max_attempts
         );
+        self.sync_engine
+            .report_lyrics_retrying(attempt, max_attempts, retry_in);
+        Some(PendingRetry {
+            source: track.source,
+            track_id: track.source_track_id.clone(),
+            attempt,
+            at: tokio::time::Instant::now() + retry_in,
+        })
+    }
 
-        // Check cache first using source-specific ID
-        if let Ok(Some(cached)) = self
+    /// Run the check of every unreachable provider and mark the ones that answer as available
+    async fn probe_unreachable(&self) {
+        let statuses = self.status.snapshot();
+        for provider in self.providers() {
+            if !matches!(
+                statuses.get(provider.name()),
+                Some(ProviderStatus::Unreachable { .. })
+            ) {
+                continue;
+            }
+            if provider.check().await.status != CheckStatus::Fail {
+                info!("Provider {} is reachable again", provider.name());
+                self.status
+                    .record(provider.name(), ProviderStatus::Available);
+            }
+        }
+    }
+
+    /// Write `lrc` to the mirror folder without delaying display on disk I/O
+    pub(crate) fn mirror_in_background(&self, track: &TrackInfo, lrc: &LrcFile) {
+        let Some(ref mirror) = self.mirror else {
+            return;
+        };
+        let (mirror, track, lrc) = (mirror.clone(), track.clone(), lrc.clone());
+        tokio::spawn(async move {
+            match mirror.write(&track, &lrc).await {
+                Ok(Some(path)) => info!("Mirrored lyrics to {:?}", path),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to mirror lyrics: {}", e),
+            }
+        });
+    }
+
+    /// Cache lyrics fetched from `lyrics_provider` for `track`
+    pub(crate) async fn store_in_cache(
+        &self,
+        track: &TrackInfo,
+        fetched: &FetchedLyrics,
+        lyrics_provider: &str,
+    ) {
+        if let Err(e) = self
             .cache
-            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .store(
+                track.source.as_str(),  // music source
+                &track.source_track_id, // source-specific track ID
+                &fetched.result,
+                &track_metadata(track),
+                lyrics_provider, // lyrics_provider (lrclib, spotify_lyrics, etc.)
+                &fetched.provider_id, // lyrics_provider_id
+            )
             .await
         {
-            info!("Using cached lyrics for {}", track.name);
-            if let LyricsResult::Synced(lrc) = cached.to_lyrics_result() {
-                self.sync_engine.set_lyrics(lrc).await;
-                return;
-            }
+            warn!("Failed to cache lyrics: {}", e);
         }
+    }
 
-        // Build query with all provider IDs from track info
-        let mut query = LyricsQuery::new(&track.name, &track.artist)
-            .with_album(&track.album)
-            .with_duration(track.duration_secs())
-            .with_provider_id(track.source.as_str(), &track.source_track_id);
+    /// ID for the next fetch, unique within this process
+    pub(crate) fn next_fetch_id(&self) -> u64 {
+        self.next_fetch_id.fetch_add(1, Ordering::Relaxed)
+    }
 
-        // Copy additional provider IDs
-        for (provider, id) in &track.provider_ids {
-            query = query.with_provider_id(provider, id);
+    /// Send a provider's failure as a progress event and an error event of fetch `fetch_id`
+    fn report_failure(&self, fetch_id: u64, provider: &str, fetched: &Result<FetchedLyrics>) {
+        if let Err(e) = fetched {
+            self.sync_engine
+                .report_fetch_failed(provider, e.to_string());
+            self.sync_engine
+                .emit_fetch_error(fetch_id, format!("Provider {provider} failed: {e}"));
         }
+    }
 
-        for provider in &self.providers {
+    /// Try providers one at a time in priority order, stopping at the first synced lyrics or
+    /// instrumental track
+    async fn query_providers_in_order(&self, fetch_id: u64, query: &LyricsQuery) -> Outcome {
+        // Plain lyrics from the first provider that had them, shown only if no provider
+        // has synced lyrics
+        let mut unsynced = None;
+        let providers = self.providers();
+        let mut unreachable = !providers.is_empty();
+        for provider in providers {
             info!("Trying provider: {}", provider.name());
-            match provider.fetch(&query).await {
-                Ok(fetched) => {
-                    match &fetched.result {
-                        LyricsResult::Synced(lrc) => {
-                            info!(
-                                "Found synced lyrics from {} ({} lines, provider_id: {})",
-                                provider.name(),
-                                lrc.lines.len(),
-                                fetched.provider_id
-                            );
-
-                            // Cache the result
-                            let metadata = TrackMetadata {
-                                artist: track.artist.clone(),
-                                track: track.name.clone(),
-                                album: Some(track.album.clone()),
-                                duration_ms: Some(track.duration.as_millis_i64()),
-                            };
-
-                            if let Err(e) = self
-                                .cache
-                                .store(
-                                    track.source.as_str(),  // music source
-                                    &track.source_track_id, // source-specific track ID
-                                    &fetched.result,
-                                    &metadata,
-                                    provider.name(), // lyrics_provider (lrclib, spotify_lyrics, etc.)
-                                    &fetched.provider_id, // lyrics_provider_id
-                                )
-                                .await
-                            {
-                                warn!("Failed to cache lyrics: {}", e);
-                            }
+            self.sync_engine.report_fetch_started(provider.name());
+            let fetched = provider
+                .fetch(query)
+                .instrument(provider_span(provider.name()))
+                .await;
+            self.report_failure(fetch_id, provider.name(), &fetched);
+            match self.record_fetched(provider.name(), fetched) {
+                outcome @ (Outcome::Synced(..) | Outcome::Instrumental(..)) => return outcome,
+                outcome @ Outcome::Unsynced(..) if unsynced.is_none() => unsynced = Some(outcome),
+                Outcome::Unreachable => continue,
+                _ => {}
+            }
+            unreachable = false;
+        }
+        unsynced.unwrap_or(if unreachable {
+            Outcome::Unreachable
+        } else {
+            Outcome::NotFound
+        })
+    }
 
-                            self.sync_engine.set_lyrics(lrc.clone()).await;
-                            return;
-                        }
-                        LyricsResult::Unsynced(_) => {
-                            info!(
-                                "Provider {} returned unsynced lyrics (not usable for karaoke)",
-                                provider.name()
-                            );
-                            // Continue trying other providers for synced lyrics
-                        }
-                        LyricsResult::NotFound => {
-                            info!("Provider {} returned no lyrics", provider.name());
-                        }
+    /// Query every provider at once and take the first synced lyrics (or report of an
+    /// instrumental track) to arrive. Providers
+    /// that answer in the same poll are ranked by priority, and the requests still running
+    /// are cancelled. Without synced lyrics, the highest-priority plain lyrics are used.
+    async fn query_providers_in_parallel(&self, fetch_id: u64, query: &LyricsQuery) -> Outcome {
+        let providers = self.providers();
+        info!("Querying {} providers in parallel", providers.len());
+        let mut unreachable = !providers.is_empty();
+        let mut pending: FuturesUnordered<_> = providers
+            .into_iter()
+            .enumerate()
+            .map(|(priority, provider)| {
+                self.sync_engine.report_fetch_started(provider.name());
+                let span = provider_span(provider.name());
+                async move { (priority, provider.name(), provider.fetch(query).await) }
+                    .instrument(span)
+            })
+            .collect();
+
+        let mut unsynced: Option<(usize, Outcome)> = None;
+        while let Some((priority, name, fetched)) = pending.next().await {
+            // Collect every other provider that finished in the same poll, so a tie goes
+            // to the higher-priority provider
+            let mut ready = vec![(priority, name, fetched)];
+            while let Some(Some(next)) = pending.next().now_or_never() {
+                ready.push(next);
+            }
+            ready.sort_by_key(|(priority, ..)| *priority);
+
+            for (priority, name, fetched) in ready {
+                self.report_failure(fetch_id, name, &fetched);
+                match self.record_fetched(name, fetched) {
+                    outcome @ (Outcome::Synced(..) | Outcome::Instrumental(..)) => return outcome,
+                    outcome @ Outcome::Unsynced(..)
+                        if unsynced.as_ref().is_none_or(|(best, _)| priority < *best) =>
+                    {
+                        unsynced = Some((priority, outcome));
                     }
+                    Outcome::Unreachable => continue,
+                    _ => {}
+                }
+                unreachable = false;
+            }
+        }
+        match unsynced {
+            Some((_, outcome)) => outcome,
+            None if unreachable => Outcome::Unreachable,
+            None => Outcome::NotFound,
+        }
+    }
+
+    /// Record a provider's health after a fetch and log what it returned
+    pub(crate) fn record_fetched(
+        &self,
+        provider: &'static str,
+        fetched: Result<FetchedLyrics>,
+    ) -> Outcome {
+        self.status.record(
+            provider,
+            fetched
+                .as_ref()
+                .map_or_else(ProviderStatus::from_error, |_| ProviderStatus::Available),
+        );
+        let fetched = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                warn!("Provider {} failed with error: {}", provider, e);
+                return if e.is_network() {
+                    Outcome::Unreachable
+                } else {
+                    Outcome::NotFound
+                };
+            }
+        };
+        match fetched.result {
+            LyricsResult::Synced(ref lrc) => {
+                info!(
+                    "Found synced lyrics from {} ({} lines, provider_id: {})",
+                    provider,
+                    lrc.lines.len(),
+                    fetched.provider_id
+                );
+                Outcome::Synced(provider, fetched)
+            }
+            LyricsResult::Unsynced(_) => {
+                info!(
+                    "Provider {} returned unsynced lyrics (not usable for karaoke)",
+                    provider
+                );
+                if self.allow_unsynced.load(Ordering::Relaxed) {
+                    Outcome::Unsynced(provider, fetched)
+                } else {
+                    Outcome::NotFound
+                }
+            }
+            LyricsResult::Instrumental => {
+                info!("Provider {} reports the track as instrumental", provider);
+                Outcome::Instrumental(provider, fetched)
+            }
+            LyricsResult::NotFound => {
+                info!("Provider {} returned no lyrics", provider);
+                Outcome::NotFound
+            }
+        }
+    }
+
+    /// Fetch lyrics for a track, in a new fetch span. Returns whether every provider was
+    /// unreachable, in which case nothing is shown yet and the fetch is worth retrying.
+    async fn fetch_lyrics_for_track(&self, track: &TrackInfo) -> bool {
+        let fetch_id = self.next_fetch_id();
+        self.fetch_in_span(fetch_id, track)
+            .instrument(fetch_span(fetch_id, track))
+            .await
+    }
+
+    /// Fetch lyrics for a track as fetch `fetch_id`. Returns whether every provider was
+    /// unreachable.
+    async fn fetch_in_span(&self, fetch_id: u64, track: &TrackInfo) -> bool {
+        if let Some(kind) = self.blocked_by(track).await {
+            info!(
+                "Lyrics for {} - {} are blocked by {}",
+                track.artist, track.name, kind
+            );
+            self.sync_engine.set_lyrics_blocked(kind).await;
+            return false;
+        }
+
+        let provider_names: Vec<_> = self.providers().iter().map(|p| p.name()).collect();
+        info!(
+            "Fetching lyrics for: {} - {} (source: {}, providers: {:?})",
+            track.artist, track.name, track.source, provider_names
+        );
+
+        self.load_track_offset(track).await;
+
+        // User-supplied overrides are pinned over anything cached or fetched
+        if self.load_override(track).await {
+            return false;
+        }
+
+        // Check cache first using source-specific ID
+        if self.show_cached(track).await {
+            return false;
+        }
+
+        if self.is_known_missing(track).await {
+            info!(
+                "No lyrics for {} - {} (cached miss)",
+                track.artist, track.name
+            );
+            self.sync_engine.set_no_lyrics().await;
+            return false;
+        }
+
+        let query = self.query_for(track).await;
+        let outcome = if self.parallel.load(Ordering::Relaxed) {
+            self.query_providers_in_parallel(fetch_id, &query).await
+        } else {
+            self.query_providers_in_order(fetch_id, &query).await
+        };
+        match outcome {
+            Outcome::Synced(provider, fetched) => {
+                self.store_in_cache(track, &fetched, provider).await;
+                if let LyricsResult::Synced(lrc) = fetched.result {
+                    let mut display = lrc.clone();
+                    self.prepare_lrc(&mut display);
+                    self.sync_engine.set_lyrics(display).await;
+                    self.sync_engine.report_lyrics_source(provider);
+                    self.mirror_in_background(track, &lrc);
                 }
-                Err(e) => {
-                    warn!("Provider {} failed with error: {}", provider.name(), e);
+                return false;
+            }
+            Outcome::Instrumental(provider, fetched) => {
+                self.store_in_cache(track, &fetched, provider).await;
+                self.sync_engine.set_instrumental().await;
+                self.sync_engine.report_lyrics_source(provider);
+                return false;
+            }
+            Outcome::Unsynced(provider, fetched) => {
+                info!(
+                    "No synced lyrics found for {} - {}, showing unsynced lyrics from {}",
+                    track.artist, track.name, provider
+                );
+                self.store_in_cache(track, &fetched, provider).await;
+                if let LyricsResult::Unsynced(text) = fetched.result {
+                    let text = self.processors.apply_text(&text);
+                    self.sync_engine.set_unsynced_lyrics(text).await;
+                    self.sync_engine.report_lyrics_source(provider);
                 }
+                return false;
+            }
+            Outcome::Unreachable => {
+                info!(
+                    "Every provider is unreachable for {} - {}",
+                    track.artist, track.name
+                );
+                return true;
             }
+            Outcome::NotFound => {}
         }
 
-        // No synced lyrics found
+        // No lyrics found
         info!(
             "No synced lyrics found for {} - {} (tried {} providers: {:?})",
             track.artist,
             track.name,
-            self.providers.len(),
+            provider_names.len(),
             provider_names
         );
+        self.remember_missing(track).await;
         self.sync_engine.set_no_lyrics().await;
+        false
+    }
+
+    /// Show `track`'s cached synced lyrics, its plain lyrics when `allow_unsynced` is on, or
+    /// that it is instrumental. Returns whether any of them was cached.
+    async fn show_cached(&self, track: &TrackInfo) -> bool {
+        let Ok(Some(cached)) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await
+        else {
+            return false;
+        };
+        info!("Using cached lyrics for {}", track.name);
+        match cached.to_lyrics_result() {
+            LyricsResult::Synced(mut lrc) => {
+                self.prepare_lrc(&mut lrc);
+                self.sync_engine.set_lyrics(lrc).await;
+            }
+            LyricsResult::Unsynced(text) if self.allow_unsynced.load(Ordering::Relaxed) => {
+                let text = self.processors.apply_text(&text);
+                self.sync_engine.set_unsynced_lyrics(text).await;
+            }
+            LyricsResult::Instrumental => self.sync_engine.set_instrumental().await,
+            LyricsResult::Unsynced(_) | LyricsResult::NotFound => return false,
+        }
+        self.sync_engine.report_lyrics_source(&cached.provider);
+        true
+    }
+
+    /// Fetch the lyrics of an upcoming track, such as the next one in the player's queue,
+    /// into the cache without showing them, so they load straight away once it plays.
+    /// Tracks with cached lyrics or a remembered miss are skipped, and provider failures
+    /// are only logged.
+    pub async fn prefetch(&self, track: &TrackInfo) {
+        let fetch_id = self.next_fetch_id();
+        self.prefetch_in_span(track)
+            .instrument(fetch_span(fetch_id, track))
+            .await;
+    }
+
+    async fn prefetch_in_span(&self, track: &TrackInfo) {
+        if self.blocked_by(track).await.is_some() {
+            debug!("Lyrics for upcoming {} are blocked", track.name);
+            return;
+        }
+        if let Ok(Some(_)) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await
+        {
+            debug!("Lyrics for upcoming {} already cached", track.name);
+            return;
+        }
+        if self.is_known_missing(track).await {
+            return;
+        }
+
+        info!("Prefetching lyrics for: {} - {}", track.artist, track.name);
+        let query = self.query_for(track).await;
+        // Plain lyrics from the first provider that had them, cached if none has synced ones
+        let mut unsynced = None;
+        let providers = self.providers();
+        let mut unreachable = !providers.is_empty();
+        for provider in providers {
+            let fetched = provider
+                .fetch(&query)
+                .instrument(provider_span(provider.name()))
+                .await;
+            match self.record_fetched(provider.name(), fetched) {
+                Outcome::Synced(provider, fetched) | Outcome::Instrumental(provider, fetched) => {
+                    self.store_in_cache(track, &fetched, provider).await;
+                    if let LyricsResult::Synced(ref lrc) = fetched.result {
+                        self.mirror_in_background(track, lrc);
+                    }
+                    return;
+                }
+                Outcome::Unsynced(provider, fetched) => {
+                    unsynced.get_or_insert((provider, fetched));
+                }
+                Outcome::Unreachable => continue,
+                Outcome::NotFound => {}
+            }
+            unreachable = false;
+        }
+        if let Some((provider, fetched)) = unsynced {
+            self.store_in_cache(track, &fetched, provider).await;
+        } else if !unreachable {
+            // A miss is only remembered when a provider answered
+            self.remember_missing(track).await;
+        }
+    }
+
+    /// Run synced lyrics through the text processors and add romanized readings
+    fn prepare_lrc(&self, lrc: &mut LrcFile) {
+        self.processors.apply_lrc(lrc);
+        if self.romanize_lines {
+            for line in &mut lrc.lines {
+                line.romanized = romanize_line(&line.text);
+            }
+        }
+    }
+
+    /// Apply the timing offset saved for `track`, if any
+    async fn load_track_offset(&self, track: &TrackInfo) {
+        match self
+            .cache
+            .get_offset(track.source.as_str(), &track.source_track_id)
+            .await
+        {
+            Ok(0) => {}
+            Ok(offset_ms) => {
+                info!(
+                    "Using saved lyrics offset of {}ms for {}",
+                    offset_ms, track.name
+                );
+                self.sync_engine.set_track_offset_ms(offset_ms).await;
+            }
+            Err(e) => warn!("Failed to look up lyrics offset: {}", e),
+        }
+    }
+
+    /// Whether `track` was recently found to have no lyrics
+    async fn is_known_missing(&self, track: &TrackInfo) -> bool {
+        if self.not_found_ttl.is_zero() {
+            return false;
+        }
+        self.cache
+            .is_not_found(
+                track.source.as_str(),
+                &track.source_track_id,
+                self.not_found_ttl,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to look up missing lyrics: {}", e);
+                false
+            })
+    }
+
+    /// Record that no provider had lyrics for `track`. Skipped unless every provider
+    /// answered, so an outage is not mistaken for missing lyrics.
+    async fn remember_missing(&self, track: &TrackInfo) {
+        if self.not_found_ttl.is_zero() {
+            return;
+        }
+        let statuses = self.status.snapshot();
+        let all_answered = self.providers().iter().all(|provider| {
+            matches!(
+                statuses.get(provider.name()),
+                Some(ProviderStatus::Available)
+            )
+        });
+        if !all_answered {
+            return;
+        }
+        if let Err(e) = self
+            .cache
+            .mark_not_found(track.source.as_str(), &track.source_track_id)
+            .await
+        {
+            warn!("Failed to record missing lyrics: {}", e);
+        }
+    }
+}
+
+/// Span of one fetch of `track`'s lyrics
+pub(crate) fn fetch_span(fetch_id: u64, track: &TrackInfo) -> Span {
+    info_span!("fetch", id = fetch_id, track = %track.source_track_id)
+}
+
+/// Span of one provider's request within a fetch
+pub(crate) fn provider_span(provider: &'static str) -> Span {
+    info_span!("provider", name = provider)
+}
+
+/// Cache metadata of `track`
+fn track_metadata(track: &TrackInfo) -> TrackMetadata {
+    TrackMetadata {
+        artist: track.artist.clone(),
+        track: track.name.clone(),
+        album: Some(track.album.clone()),
+        duration_ms: Some(track.duration.as_millis_i64()),
+    }
+}
+
+/// Provider query for `track`, with all of its provider IDs
+fn query_for(track: &TrackInfo) -> LyricsQuery {
+    let mut query = LyricsQuery::new(&track.name, &track.artist)
+        .with_album(&track.album)
+        .with_duration(track.duration_secs())
+        .with_provider_id(track.source.as_str(), &track.source_track_id);
+
+    // Copy additional provider IDs
+    for (provider, id) in &track.provider_ids {
+        query = query.with_provider_id(provider, id);
+    }
+    if let Some(ref isrc) = track.isrc {
+        query = query.with_isrc(isrc);
+    }
+    query
+}
+
+/// Sleep until `at`, or forever without one
+async fn sleep_until_some(at: Option<tokio::time::Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::config::LyricsProviderType;
+    use crate::playback::PlaybackState;
+    use async_trait::async_trait;
+
+    /// Provider that answers after `delay` with synced or plain lyrics
+    struct FakeProvider {
+        name: &'static str,
+        delay: Duration,
+        synced: bool,
+    }
+
+    #[async_trait]
+    impl LyricsProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn fetch(&self, _query: &LyricsQuery) -> Result<FetchedLyrics> {
+            tokio::time::sleep(self.delay).await;
+            let result = if self.synced {
+                LyricsResult::Synced(LrcFile::parse("[00:01.00]Hello").unwrap())
+            } else {
+                LyricsResult::Unsynced("Hello".to_string())
+            };
+            Ok(FetchedLyrics {
+                result,
+                provider_id: self.name.to_string(),
+            })
+        }
+    }
+
+    fn provider(name: &'static str, delay_ms: u64, synced: bool) -> Box<dyn LyricsProvider> {
+        Box::new(FakeProvider {
+            name,
+            delay: Duration::from_millis(delay_ms),
+            synced,
+        })
+    }
+
+    async fn fetcher(name: &str, providers: Vec<Box<dyn LyricsProvider>>) -> LyricsFetcher {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-fetcher-{name}-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = Arc::new(LyricsCache::open(&path).await.unwrap());
+        LyricsFetcher::new(SyncEngine::new(), cache, providers, None)
+    }
+
+    fn track(id: &str) -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            id,
+            "Song",
+            "Artist",
+            "",
+            Duration::from_mins(3),
+        )
+    }
+
+    #[test]
+    fn test_debounce_skips_same_track_within_window() {
+        let now = Instant::now();
+        let mut debounce = FetchDebounce::default();
+        assert!(!debounce.is_repeat(&track("t1"), now));
+
+        debounce.record(&track("t1"), now);
+        assert!(debounce.is_repeat(&track("t1"), now + Duration::from_millis(500)));
+        assert!(!debounce.is_repeat(&track("t2"), now + Duration::from_millis(500)));
+        assert!(!debounce.is_repeat(&track("t1"), now + FETCH_DEBOUNCE));
+
+        // A different track in between resets the window for the first one
+        debounce.record(&track("t2"), now);
+        assert!(!debounce.is_repeat(&track("t1"), now));
+    }
+
+    #[tokio::test]
+    async fn test_next_alternative_cycles_cached_providers() {
+        let fetcher = fetcher("alternatives", Vec::new()).await;
+        let current = track("t1");
+        let metadata = TrackMetadata {
+            artist: current.artist.clone(),
+            track: current.name.clone(),
+            album: Some("Album".to_string()),
+            duration_ms: None,
+        };
+        for provider in ["lrclib", "musixmatch"] {
+            let lyrics = LyricsResult::Synced(LrcFile::parse("[00:01.00]Hello").unwrap());
+            fetcher
+                .cache
+                .store("spotify", "t1", &lyrics, &metadata, provider, "1")
+                .await
+                .unwrap();
+        }
+        fetcher
+            .sync_engine
+            .update_state(PlaybackState {
+                track: Some(current),
+                ..PlaybackState::default()
+            })
+            .await;
+
+        // Musixmatch was stored last, so it is shown and the next one wraps around
+        assert_eq!(
+            fetcher.next_alternative().await.unwrap().as_deref(),
+            Some("lrclib")
+        );
+        assert_eq!(
+            fetcher.next_alternative().await.unwrap().as_deref(),
+            Some("musixmatch")
+        );
+        assert!(fetcher.sync_engine.lyrics().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_use_candidate_pins_cached_or_fetched_lyrics() {
+        let fetcher = fetcher("candidates", vec![provider("fetched", 0, false)]).await;
+        let current = track("t1");
+        let lyrics = LyricsResult::Synced(LrcFile::parse("[00:01.00]Hello").unwrap());
+        for provider in ["lrclib", "musixmatch"] {
+            fetcher
+                .cache
+                .store(
+                    "spotify",
+                    "t1",
+                    &lyrics,
+                    &track_metadata(&current),
+                    provider,
+                    "1",
+                )
+                .await
+                .unwrap();
+        }
+
+        let candidates = fetcher.cached_candidates(&current).await.unwrap();
+        let providers: Vec<_> = candidates.iter().map(|c| c.provider.as_str()).collect();
+        assert_eq!(providers, ["lrclib", "musixmatch"]);
+        assert_eq!(candidates[0].preview.as_deref(), Some("Hello"));
+        assert!(candidates[0].synced);
+
+        let shown = |fetcher: &LyricsFetcher| {
+            let cache = fetcher.cache.clone();
+            async move {
+                cache
+                    .get_by_provider_id("spotify", "t1")
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .provider
+            }
+        };
+        fetcher
+            .use_candidate(&current, &candidates[0])
+            .await
+            .unwrap();
+        assert_eq!(shown(&fetcher).await, "lrclib");
+
+        // A provider's search result is fetched and pinned the same way
+        let searched = LyricsCandidate {
+            provider: "fetched".to_string(),
+            id: "42".to_string(),
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            duration_secs: None,
+            synced: false,
+            preview: None,
+        };
+        fetcher.use_candidate(&current, &searched).await.unwrap();
+        assert_eq!(shown(&fetcher).await, "fetched");
+        fetcher
+            .store_in_cache(
+                &current,
+                &FetchedLyrics {
+                    result: lyrics,
+                    provider_id: "2".to_string(),
+                },
+                "lrclib",
+            )
+            .await;
+        assert_eq!(shown(&fetcher).await, "fetched");
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_caches_without_showing() {
+        let fetcher = fetcher("prefetch", vec![provider("fake", 0, true)]).await;
+        fetcher.prefetch(&track("next")).await;
+
+        let cached = fetcher
+            .cache
+            .get_by_provider_id("spotify", "next")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.provider, "fake");
+        assert!(fetcher.sync_engine.lyrics().await.is_none());
+    }
+
+    /// Provider that reports every track as instrumental
+    struct InstrumentalProvider;
+
+    #[async_trait]
+    impl LyricsProvider for InstrumentalProvider {
+        fn name(&self) -> &'static str {
+            "instrumental"
+        }
+
+        async fn fetch(&self, _query: &LyricsQuery) -> Result<FetchedLyrics> {
+            Ok(FetchedLyrics {
+                result: LyricsResult::Instrumental,
+                provider_id: "1".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instrumental_tracks_are_reported_and_cached() {
+        let fetcher = fetcher(
+            "instrumental",
+            vec![Box::new(InstrumentalProvider), provider("later", 0, true)],
+        )
+        .await;
+        let mut rx = fetcher.sync_engine.subscribe();
+
+        // The instrumental answer ends the search before lower-priority providers
+        fetcher.fetch_lyrics_for_track(&track("t1")).await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, SyncEvent::TrackInstrumental))
+        );
+        assert!(!events.iter().any(|event| matches!(
+            event,
+            SyncEvent::LyricsFetchStarted { provider } if provider == "later"
+        )));
+
+        let cached = fetcher
+            .cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            cached.to_lyrics_result(),
+            LyricsResult::Instrumental
+        ));
+
+        // Replays are answered from the cache
+        fetcher.fetch_lyrics_for_track(&track("t1")).await;
+        assert!(matches!(rx.try_recv(), Ok(SyncEvent::TrackInstrumental)));
+    }
+
+    #[tokio::test]
+    async fn test_unsynced_lyrics_are_cached() {
+        let fetcher = fetcher("unsynced-cache", vec![provider("plain", 0, false)]).await;
+        let mut rx = fetcher.sync_engine.subscribe();
+
+        fetcher.fetch_lyrics_for_track(&track("t1")).await;
+        let cached = fetcher
+            .cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            cached.to_lyrics_result(),
+            LyricsResult::Unsynced(_)
+        ));
+
+        // Replays are answered from the cache without asking the provider
+        while rx.try_recv().is_ok() {}
+        fetcher.fetch_lyrics_for_track(&track("t1")).await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(events.iter().any(
+            |event| matches!(event, SyncEvent::UnsyncedLyricsLoaded { text } if text == "Hello")
+        ));
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, SyncEvent::LyricsFetchStarted { .. }))
+        );
+
+        // Without the unsynced fallback, cached plain lyrics are passed over
+        let fetcher = fetcher.with_unsynced_fallback(false);
+        fetcher.fetch_lyrics_for_track(&track("t1")).await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, SyncEvent::UnsyncedLyricsLoaded { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_blocked_tracks_are_not_fetched() {
+        let mut blocklist = Blocklist::new();
+        blocklist.insert(BlockKind::Track, "configured");
+        let fetcher = fetcher("blocklist", vec![provider("fake", 0, true)])
+            .await
+            .with_blocklist(blocklist);
+        let mut rx = fetcher.sync_engine.subscribe();
+
+        fetcher.fetch_lyrics_for_track(&track("configured")).await;
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SyncEvent::LyricsBlocked {
+                kind: BlockKind::Track
+            })
+        ));
+
+        // Blocking the artist from the app covers every track, prefetches included
+        let current = track("t1");
+        fetcher.block(BlockKind::Artist, &current).await.unwrap();
+        assert_eq!(
+            fetcher.blocked_by(&track("t2")).await,
+            Some(BlockKind::Artist)
+        );
+        fetcher.prefetch(&track("t2")).await;
+        assert!(
+            fetcher
+                .cache
+                .get_by_provider_id("spotify", "t2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Unblocking the playing track fetches its lyrics
+        fetcher
+            .sync_engine
+            .update_state(PlaybackState {
+                track: Some(current.clone()),
+                ..PlaybackState::default()
+            })
+            .await;
+        assert!(fetcher.unblock(&current).await.unwrap());
+        assert_eq!(fetcher.blocked_by(&current).await, None);
+        assert!(fetcher.sync_engine.lyrics().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_save_edited_lyrics() {
+        let fetcher = fetcher("editor", Vec::new()).await;
+        let current = track("t1");
+        let metadata = TrackMetadata {
+            artist: current.artist.clone(),
+            track: current.name.clone(),
+            album: None,
+            duration_ms: None,
+        };
+        let lyrics = LyricsResult::Unsynced("Hello\nWorld".to_string());
+        fetcher
+            .cache
+            .store("spotify", "t1", &lyrics, &metadata, "lrclib", "1")
+            .await
+            .unwrap();
+        fetcher
+            .sync_engine
+            .update_state(PlaybackState {
+                track: Some(current),
+                ..PlaybackState::default()
+            })
+            .await;
+        fetcher.sync_engine.set_track_offset_ms(300).await;
+
+        let (track, mut editor) = fetcher.edit_current_lyrics().await.unwrap().unwrap();
+        editor.tap(Duration::from_secs(2));
+        editor.tap(Duration::from_secs(4));
+        assert!(
+            fetcher
+                .save_edited_lyrics(&track, editor.finish())
+                .await
+                .unwrap()
+        );
+
+        let cached = fetcher
+            .cache
+            .get_by_provider_id("spotify", "t1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.provider, EDITOR_PROVIDER);
+        assert_eq!(cached.content, "[00:02.00]Hello\n[00:04.00]World\n");
+        assert_eq!(fetcher.sync_engine.track_offset_ms(), 0);
+        assert_eq!(fetcher.sync_engine.lyrics().await.unwrap().lines.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_line_romanization() {
+        let mut lrc = LrcFile::parse("[00:01.00]さくら\n[00:02.00]Hello").unwrap();
+        let fetcher = fetcher("romanize", Vec::new())
+            .await
+            .with_line_romanization(true);
+        fetcher.prepare_lrc(&mut lrc);
+
+        assert_eq!(lrc.lines[0].romanized.as_deref(), Some("sakura"));
+        assert_eq!(lrc.lines[1].romanized, None);
+    }
+
+    fn winner(outcome: &Outcome) -> Option<&'static str> {
+        match outcome {
+            Outcome::Synced(name, _)
+            | Outcome::Unsynced(name, _)
+            | Outcome::Instrumental(name, _) => Some(name),
+            Outcome::NotFound | Outcome::Unreachable => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_config_reorders_providers() {
+        let fetcher = fetcher(
+            "apply-config",
+            vec![provider("lrclib", 0, false), provider("genius", 0, true)],
+        )
+        .await;
+        let names = |fetcher: &LyricsFetcher| -> Vec<&str> {
+            fetcher.providers().iter().map(|p| p.name()).collect()
+        };
+        assert_eq!(names(&fetcher), ["lrclib", "genius"]);
+
+        let mut config = LyricsConfig {
+            providers: vec![LyricsProviderType::Genius, LyricsProviderType::Lrclib],
+            parallel_fetch: true,
+            ..LyricsConfig::default()
+        };
+        fetcher.apply_config(&config);
+        assert_eq!(names(&fetcher), ["genius", "lrclib"]);
+        assert!(fetcher.parallel.load(Ordering::Relaxed));
+
+        // Providers that were not set up are left out, and removed ones are no longer used
+        config.providers = vec![LyricsProviderType::Musixmatch, LyricsProviderType::Lrclib];
+        fetcher.apply_config(&config);
+        assert_eq!(names(&fetcher), ["lrclib"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_parallel_fetch_takes_first_synced() {
+        let fetcher = fetcher(
+            "first",
+            vec![
+                provider("slow", 5000, true),
+                provider("plain", 10, false),
+                provider("fast", 100, true),
+            ],
+        )
+        .await;
+        let query = LyricsQuery::new("Song", "Artist");
+
+        let parallel = fetcher.query_providers_in_parallel(1, &query).await;
+        assert!(matches!(parallel, Outcome::Synced("fast", _)));
+
+        let in_order = fetcher.query_providers_in_order(1, &query).await;
+        assert!(matches!(in_order, Outcome::Synced("slow", _)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_parallel_fetch_ties_go_to_priority() {
+        let fetcher = fetcher(
+            "tie",
+            vec![provider("first", 100, true), provider("second", 100, true)],
+        )
+        .await;
+
+        let outcome = fetcher
+            .query_providers_in_parallel(1, &LyricsQuery::new("Song", "Artist"))
+            .await;
+        assert_eq!(winner(&outcome), Some("first"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_parallel_fetch_prefers_priority_for_unsynced() {
+        let fetcher = fetcher(
+            "unsynced",
+            vec![provider("first", 300, false), provider("second", 10, false)],
+        )
+        .await;
+
+        let outcome = fetcher
+            .query_providers_in_parallel(1, &LyricsQuery::new("Song", "Artist"))
+            .await;
+        assert!(matches!(outcome, Outcome::Unsynced("first", _)));
+    }
+
+    /// Provider whose requests always fail
+    struct FailingProvider;
+
+    #[async_trait]
+    impl LyricsProvider for FailingProvider {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn fetch(&self, _query: &LyricsQuery) -> Result<FetchedLyrics> {
+            Err(CoreError::LyricsProviderFailed {
+                provider: "failing".to_string(),
+                reason: "offline".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures_carry_fetch_id() {
+        let fetcher = fetcher(
+            "failure",
+            vec![Box::new(FailingProvider), provider("ok", 0, true)],
+        )
+        .await;
+        let mut rx = fetcher.sync_engine.subscribe();
+        let first = fetcher.next_fetch_id();
+        let fetch_id = fetcher.next_fetch_id();
+        assert_eq!(fetch_id, first + 1);
+
+        let outcome = fetcher
+            .query_providers_in_order(fetch_id, &LyricsQuery::new("Song", "Artist"))
+            .await;
+        assert_eq!(winner(&outcome), Some("ok"));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SyncEvent::LyricsFetchStarted { provider }) if provider == "failing"
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SyncEvent::LyricsFetchFailed { provider, reason }) if provider == "failing" && reason.contains("offline")
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SyncEvent::Error { fetch_id: Some(id), message }) if id == fetch_id && message.contains("offline")
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(SyncEvent::LyricsFetchStarted { provider }) if provider == "ok"
+        ));
+    }
+
+    /// Provider whose server refuses connections
+    #[cfg(feature = "http")]
+    struct UnreachableProvider;
+
+    #[cfg(feature = "http")]
+    #[async_trait]
+    impl LyricsProvider for UnreachableProvider {
+        fn name(&self) -> &'static str {
+            "unreachable"
+        }
+
+        async fn fetch(&self, _query: &LyricsQuery) -> Result<FetchedLyrics> {
+            Err(reqwest::get("http://127.0.0.1:1/")
+                .await
+                .unwrap_err()
+                .into())
+        }
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_unreachable_providers_are_retried_not_remembered() {
+        let fetcher = fetcher("unreachable", vec![Box::new(UnreachableProvider)])
+            .await
+            .with_not_found_ttl(Duration::from_hours(1))
+            .with_unreachable_retry(Duration::from_secs(30), 2);
+        let track = track("t1");
+        let mut rx = fetcher.sync_engine.subscribe();
+
+        assert!(fetcher.fetch_in_span(1, &track).await);
+        assert!(!fetcher.is_known_missing(&track).await);
+
+        let retry = fetcher.schedule_retry(&track, 2).await.unwrap();
+        assert!(retry.is_for(&track));
+        assert!(!retry.is_for(&self::track("t2")));
+        let retrying = loop {
+            match rx.try_recv().unwrap() {
+                SyncEvent::LyricsRetrying { .. } => break true,
+                SyncEvent::LyricsNotFound => break false,
+                _ => {}
+            }
+        };
+        assert!(retrying);
+
+        // Out of attempts
+        assert!(fetcher.schedule_retry(&track, 3).await.is_none());
+        assert!(matches!(rx.try_recv(), Ok(SyncEvent::LyricsNotFound)));
     }
 }
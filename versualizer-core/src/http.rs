@@ -0,0 +1,354 @@
+//! Shared HTTP client setup for lyrics providers.
+//!
+//! [`base_client`] and [`provider_client`] add a timeout, retries with exponential backoff
+//! as configured under `[network.retry]`, and [`HttpLimits`] to a provider's client, so a misbehaving endpoint cannot stall the fetch pipeline or
+//! balloon memory. Responses that declare a size over the limit or an unexpected content
+//! type fail with [`CoreError::ResponseTooLarge`] or [`CoreError::UnexpectedContentType`].
+//! Bodies without a declared size are checked while they are read with [`read_json`] or
+//! [`read_text`]. Every attempt is logged at debug level, inside the caller's tracing span
+//! (such as the fetcher's `fetch` and `provider` spans).
+
+use crate::config::{RetryConfig, RetryJitter};
+use crate::error::{CoreError, Result};
+use async_trait::async_trait;
+use http::Extensions;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::{Request, Response};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next};
+use reqwest_retry::{Jitter, RetryTransientMiddleware, policies::ExponentialBackoff};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// User agent sent by providers that do not need to imitate a specific client
+pub const USER_AGENT: &str = "Versualizer/1.0 (https://github.com/versualizer)";
+
+/// Connection timeout for provider requests
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Limits on the responses a provider accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpLimits {
+    /// Time allowed for a whole request, including downloading the body
+    pub timeout: Duration,
+    /// Largest accepted response body
+    pub max_response_bytes: u64,
+    /// Accepted content types of successful responses (any if empty)
+    pub content_types: &'static [&'static str],
+}
+
+impl HttpLimits {
+    /// JSON APIs: 10 seconds, 2 MiB. Some APIs label JSON as plain text, so that is
+    /// accepted too.
+    pub const JSON: Self = Self {
+        timeout: Duration::from_secs(10),
+        max_response_bytes: 2 * 1024 * 1024,
+        content_types: &["application/json", "text/json", "text/plain"],
+    };
+
+    /// Web pages: 10 seconds, 5 MiB
+    pub const HTML: Self = Self {
+        timeout: Duration::from_secs(10),
+        max_response_bytes: 5 * 1024 * 1024,
+        content_types: &["text/html"],
+    };
+
+    /// Images such as album art: 10 seconds, 5 MiB
+    pub const IMAGE: Self = Self {
+        timeout: Duration::from_secs(10),
+        max_response_bytes: 5 * 1024 * 1024,
+        content_types: &["image/jpeg", "image/png", "image/webp", "image/gif"],
+    };
+
+    /// These limits with another set of accepted content types
+    #[must_use]
+    pub const fn with_content_types(mut self, content_types: &'static [&'static str]) -> Self {
+        self.content_types = content_types;
+        self
+    }
+
+    /// Whether a `Content-Type` header value is accepted
+    fn accepts(&self, content_type: &str) -> bool {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        self.content_types.is_empty() || self.content_types.contains(&essence.as_str())
+    }
+}
+
+/// Largest accepted body of a response, recorded by [`ResponseLimits`] for [`read_body`]
+#[derive(Debug, Clone, Copy)]
+struct MaxResponseBytes(u64);
+
+/// Middleware enforcing [`HttpLimits`] on response headers
+struct ResponseLimits(HttpLimits);
+
+#[async_trait]
+impl Middleware for ResponseLimits {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let mut response = next.run(req, extensions).await?;
+        check_headers(&response, &self.0).map_err(reqwest_middleware::Error::middleware)?;
+        response
+            .extensions_mut()
+            .insert(MaxResponseBytes(self.0.max_response_bytes));
+        Ok(response)
+    }
+}
+
+/// Middleware logging each request attempt with its outcome and duration. Only the host
+/// and path are logged, as some providers put tokens in the query string.
+struct RequestLog;
+
+#[async_trait]
+impl Middleware for RequestLog {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().clone();
+        let target = format!(
+            "{}{}",
+            req.url().host_str().unwrap_or_default(),
+            req.url().path()
+        );
+        let started = Instant::now();
+        let result = next.run(req, extensions).await;
+        match &result {
+            Ok(response) => debug!(
+                "{} {} -> {} in {:?}",
+                method,
+                target,
+                response.status(),
+                started.elapsed()
+            ),
+            Err(e) => debug!(
+                "{} {} failed after {:?}: {}",
+                method,
+                target,
+                started.elapsed(),
+                e
+            ),
+        }
+        result
+    }
+}
+
+/// Check the declared size and content type of `response` against `limits`
+fn check_headers(response: &Response, limits: &HttpLimits) -> Result<()> {
+    let url = || response.url().to_string();
+    let declared_len = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared_len.is_some_and(|len| len > limits.max_response_bytes) {
+        return Err(CoreError::ResponseTooLarge {
+            url: url(),
+            limit: limits.max_response_bytes,
+        });
+    }
+
+    // Error pages are often HTML regardless of the API, and their status says enough
+    if !response.status().is_success() {
+        return Ok(());
+    }
+    if let Some(content_type) = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        && !limits.accepts(content_type)
+    {
+        return Err(CoreError::UnexpectedContentType {
+            url: url(),
+            content_type: content_type.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Build a plain HTTP client from `builder` (with the provider's user agent and default
+/// headers) that gives up on requests after the timeout of `limits`. Use it as the base of
+/// [`provider_client`], or alone for small requests such as token refreshes.
+///
+/// # Errors
+///
+/// Returns an error if the HTTP client cannot be created.
+pub fn base_client(
+    builder: reqwest::ClientBuilder,
+    limits: &HttpLimits,
+) -> Result<reqwest::Client> {
+    Ok(builder
+        .timeout(limits.timeout)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()?)
+}
+
+/// Exponential backoff policy for `retry`
+fn retry_policy(retry: &RetryConfig) -> ExponentialBackoff {
+    let (min, max) = retry.backoff_bounds();
+    let jitter = match retry.jitter {
+        RetryJitter::None => Jitter::None,
+        RetryJitter::Full => Jitter::Full,
+        RetryJitter::Bounded => Jitter::Bounded,
+    };
+    ExponentialBackoff::builder()
+        .retry_bounds(min, max)
+        .jitter(jitter)
+        .build_with_max_retries(retry.max_retries)
+}
+
+/// Wrap `base_client` with the response checks of `limits` and retries of transient
+/// failures following `retry`. Read bodies with [`read_json`] or [`read_text`] to enforce
+/// the size limit on responses that do not declare their size.
+#[must_use]
+pub fn provider_client(
+    base_client: reqwest::Client,
+    limits: HttpLimits,
+    retry: &RetryConfig,
+) -> ClientWithMiddleware {
+    // Limit violations are not transient, so the retry middleware does not repeat them
+    ClientBuilder::new(base_client)
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy(
+            retry,
+        )))
+        .with(ResponseLimits(limits))
+        .with(RequestLog)
+        .build()
+}
+
+/// Read the body of `response`, failing once it grows past the limit of the client that
+/// made the request
+///
+/// # Errors
+///
+/// Returns an error if the body cannot be read or is larger than the limit.
+pub async fn read_body(mut response: Response) -> Result<Vec<u8>> {
+    let limit = response
+        .extensions()
+        .get::<MaxResponseBytes>()
+        .map_or(u64::MAX, |max| max.0);
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err(CoreError::ResponseTooLarge {
+                url: response.url().to_string(),
+                limit,
+            });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Read and deserialize a JSON body, within the size limit
+///
+/// # Errors
+///
+/// Returns an error if the body cannot be read, is too large, or is not valid JSON for `T`.
+pub async fn read_json<T: DeserializeOwned>(response: Response) -> Result<T> {
+    Ok(serde_json::from_slice(&read_body(response).await?)?)
+}
+
+/// Read a text body, within the size limit. Invalid UTF-8 is replaced.
+///
+/// # Errors
+///
+/// Returns an error if the body cannot be read or is too large.
+pub async fn read_text(response: Response) -> Result<String> {
+    Ok(String::from_utf8_lossy(&read_body(response).await?).into_owned())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, headers: &[(&str, &str)]) -> Response {
+        let mut builder = http::Response::builder()
+            .status(status)
+            .extension(MaxResponseBytes(4));
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Response::from(builder.body("hello").unwrap())
+    }
+
+    #[test]
+    fn test_accepts_content_type_essence() {
+        assert!(HttpLimits::JSON.accepts("application/json; charset=utf-8"));
+        assert!(HttpLimits::JSON.accepts("Application/JSON"));
+        assert!(!HttpLimits::JSON.accepts("text/html"));
+        assert!(!HttpLimits::JSON.accepts("application/octet-stream"));
+        assert!(
+            HttpLimits::JSON
+                .with_content_types(&[])
+                .accepts("text/html")
+        );
+    }
+
+    #[test]
+    fn test_check_headers() {
+        let limits = HttpLimits::JSON;
+        assert!(
+            check_headers(
+                &response(200, &[("content-type", "application/json")]),
+                &limits
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            check_headers(&response(200, &[("content-type", "text/html")]), &limits),
+            Err(CoreError::UnexpectedContentType { .. })
+        ));
+        // Error pages are left to the status check
+        assert!(check_headers(&response(500, &[("content-type", "text/html")]), &limits).is_ok());
+        assert!(matches!(
+            check_headers(&response(200, &[("content-length", "3000000")]), &limits),
+            Err(CoreError::ResponseTooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_enforces_limit() {
+        assert!(matches!(
+            read_body(response(200, &[])).await,
+            Err(CoreError::ResponseTooLarge { limit: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let retry = RetryConfig {
+            max_retries: 2,
+            backoff_base_ms: 100,
+            max_backoff_ms: 50,
+            jitter: RetryJitter::None,
+        };
+        let policy = retry_policy(&retry);
+        assert_eq!(policy.max_n_retries, Some(2));
+        assert_eq!(policy.min_retry_interval, Duration::from_millis(100));
+        assert_eq!(policy.max_retry_interval, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_limit_error_survives_middleware() {
+        let error = reqwest_middleware::Error::middleware(CoreError::ResponseTooLarge {
+            url: "https://example.com".to_string(),
+            limit: 1,
+        });
+        assert!(matches!(
+            CoreError::from(error),
+            CoreError::ResponseTooLarge { limit: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Decoding and parsing of Kugou KRC lyrics.
+//!
+//! A KRC file is the magic `krc1` followed by zlib compressed text, masked with a fixed XOR key.
+//! The text has LRC-style `[ti:...]` tags, then one line per row:
+//! `[line_start,line_duration]<word_offset,word_duration,0>word<...>word`, in milliseconds,
+//! with word offsets counted from the start of the line.
+
+use crate::error::{CoreError, Result};
+use crate::lrc::{LrcFile, LrcLine, LrcWord, apply_offset};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use std::time::Duration;
+
+/// Magic bytes at the start of a KRC file
+pub const KRC_MAGIC: &[u8] = b"krc1";
+
+/// XOR key for the compressed payload, repeated over its length
+const KEY: [u8; 16] = [
+    0x40, 0x47, 0x61, 0x77, 0x5e, 0x32, 0x74, 0x47, 0x51, 0x36, 0x31, 0x2d, 0xce, 0xd2, 0x6e, 0x69,
+];
+
+/// Unpack the text of a KRC file
+///
+/// # Errors
+///
+/// Returns an error if the data does not start with `krc1` or does not inflate to UTF-8 text.
+pub fn decode(data: &[u8]) -> Result<String> {
+    let payload = data
+        .strip_prefix(KRC_MAGIC)
+        .ok_or_else(|| CoreError::KrcParseError {
+            reason: "missing krc1 header".to_string(),
+        })?;
+    let unmasked: Vec<u8> = payload
+        .iter()
+        .zip(KEY.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+
+    let mut text = String::new();
+    ZlibDecoder::new(unmasked.as_slice())
+        .read_to_string(&mut text)
+        .map_err(|e| CoreError::KrcParseError {
+            reason: format!("could not decompress payload: {e}"),
+        })?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// Parse decoded KRC text into word-timed lyrics. Tags such as `[ti:]`, `[ar:]` and
+/// `[offset:]` are read as in LRC; lines without text are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the text has no timed lines.
+pub fn parse(text: &str) -> Result<LrcFile> {
+    // Timed rows are not LRC timestamps, so the LRC parser only picks up the tags
+    let metadata = LrcFile::parse(text)?.metadata;
+    let lines: Vec<LrcLine> = text
+        .lines()
+        .filter_map(|row| parse_line(row, metadata.offset))
+        .collect();
+    if lines.is_empty() {
+        return Err(CoreError::KrcParseError {
+            reason: "no timed lines found".to_string(),
+        });
+    }
+    Ok(LrcFile { metadata, lines })
+}
+
+/// Decode and parse a KRC file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be decoded or has no timed lines.
+pub fn read(data: &[u8]) -> Result<LrcFile> {
+    parse(&decode(data)?)
+}
+
+fn parse_line(row: &str, offset_ms: i64) -> Option<LrcLine> {
+    let rest = row.trim().strip_prefix('[')?;
+    let (timing, rest) = rest.split_once(']')?;
+    let (line_start, _) = parse_timing(timing)?;
+    let line_start = Duration::from_millis(line_start);
+
+    let mut words = Vec::new();
+    let mut text = String::new();
+    let mut remaining = rest;
+    // Text before the first word tag, kept as part of the line
+    if let Some(open) = remaining.find('<') {
+        text.push_str(&remaining[..open]);
+        remaining = &remaining[open..];
+    }
+    while let Some(tag) = remaining.strip_prefix('<') {
+        let Some((timing, tail)) = tag.split_once('>') else {
+            break;
+        };
+        let Some((start, duration)) = parse_timing(timing) else {
+            // A literal `<` in the lyrics
+            text.push('<');
+            remaining = tag;
+            continue;
+        };
+        let end = tail.find('<').unwrap_or(tail.len());
+        let syllable = &tail[..end];
+        text.push_str(syllable);
+        let word = syllable.trim();
+        if !word.is_empty() {
+            let start_time = line_start + Duration::from_millis(start);
+            words.push(LrcWord {
+                start_time: apply_offset(start_time, offset_ms),
+                end_time: Some(apply_offset(
+                    start_time + Duration::from_millis(duration),
+                    offset_ms,
+                )),
+                text: word.to_string(),
+            });
+        }
+        remaining = &tail[end..];
+    }
+    text.push_str(remaining);
+
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(LrcLine {
+        start_time: apply_offset(line_start, offset_ms),
+        text: text.to_string(),
+        words: (!words.is_empty()).then_some(words),
+        romanized: None,
+        part: None,
+    })
+}
+
+/// Parse the first two numbers of a `start,duration[,...]` group in milliseconds
+fn parse_timing(timing: &str) -> Option<(u64, u64)> {
+    let mut parts = timing.split(',');
+    let start = parts.next()?.trim().parse().ok()?;
+    let duration = parts.next()?.trim().parse().ok()?;
+    Some((start, duration))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    const TEXT: &str = "\u{feff}[ar:Artist]\n[ti:Song]\n[language:eyJjb250ZW50IjpbXX0=]\n\
+                        [1000,1500]<0,500,0>Hello <500,1000,0>world\n\
+                        [3000,900]<0,300,0>我<300,300,0>爱<600,300,0>你\n";
+
+    fn encode(text: &str) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut data = KRC_MAGIC.to_vec();
+        data.extend(
+            compressed
+                .iter()
+                .zip(KEY.iter().cycle())
+                .map(|(byte, key)| byte ^ key),
+        );
+        data
+    }
+
+    #[test]
+    fn test_read() {
+        let lrc = read(&encode(TEXT)).unwrap();
+
+        assert_eq!(lrc.metadata.title.as_deref(), Some("Song"));
+        assert_eq!(lrc.metadata.artist.as_deref(), Some("Artist"));
+        assert_eq!(lrc.lines.len(), 2);
+
+        assert_eq!(lrc.lines[0].start_time, Duration::from_secs(1));
+        assert_eq!(lrc.lines[0].text, "Hello world");
+        let words = lrc.lines[0].words.as_ref().unwrap();
+        assert_eq!(words[1].text, "world");
+        assert_eq!(words[1].start_time, Duration::from_millis(1500));
+        assert_eq!(words[1].end_time, Some(Duration::from_millis(2500)));
+
+        assert_eq!(lrc.lines[1].text, "我爱你");
+        assert_eq!(lrc.lines[1].words.as_ref().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_applies_offset() {
+        let lrc = parse("[offset:-500]\n[1000,1000]<0,400,0>Early <400,600,0>line").unwrap();
+        assert_eq!(lrc.lines[0].start_time, Duration::from_millis(500));
+        assert_eq!(
+            lrc.lines[0].words.as_ref().unwrap()[1].start_time,
+            Duration::from_millis(900)
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"not krc").is_err());
+        assert!(decode(b"krc1garbage").is_err());
+        assert!(parse("[ti:Song]").is_err());
+    }
+}
@@ -1,32 +1,156 @@
+#[cfg(feature = "cache")]
+pub mod archive;
+#[cfg(feature = "http")]
+pub mod artwork;
+pub mod audio_features;
+#[cfg(feature = "cache")]
+pub mod backup;
+pub mod blocklist;
+#[cfg(feature = "cache")]
+pub mod bookmarks;
+#[cfg(feature = "cache")]
 pub mod cache;
 pub mod config;
+#[cfg(feature = "watch")]
+pub mod config_editor;
+#[cfg(feature = "watch")]
+pub mod config_watcher;
+pub mod contrast;
+pub mod control;
+pub mod diff;
+pub mod encryption;
 pub mod error;
+#[cfg(feature = "cache")]
+pub mod event_log;
+#[cfg(feature = "cache")]
 pub mod fetcher;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "krc")]
+pub mod krc;
+pub mod limits;
+pub mod locale;
+#[cfg(feature = "cache")]
+pub mod lookup;
 pub mod lrc;
+pub mod lyric_card;
+#[cfg(feature = "cache")]
+pub mod maintenance;
+pub mod matching;
+#[cfg(feature = "cache")]
+pub mod mirror;
+#[cfg(feature = "http")]
+pub mod musicbrainz;
+pub mod normalize;
+pub mod now_singing;
+#[cfg(feature = "cache")]
+pub mod overrides;
 pub mod paths;
 pub mod playback;
+#[cfg(feature = "cache")]
+pub mod prefetch;
+pub mod preflight;
+pub mod processors;
 pub mod provider;
+pub mod provider_status;
+pub mod recording;
+pub mod resources;
+pub mod romanize;
+pub mod secrets;
+#[cfg(feature = "cache")]
+pub mod session_stats;
 pub mod source;
+pub mod subtitles;
 pub mod sync;
+pub mod tempo;
 pub mod time;
+pub mod timing_editor;
+#[cfg(all(feature = "cache", feature = "http"))]
+pub mod translation;
+pub mod ttml;
 
-pub use cache::LyricsCache;
+#[cfg(feature = "cache")]
+pub use archive::{ArchiveSummary, CacheArchive};
+#[cfg(feature = "http")]
+pub use artwork::{Artwork, ArtworkCache};
+pub use audio_features::{AudioFeatures, Section, TrackAudioFeatures};
+#[cfg(feature = "cache")]
+pub use backup::CacheBackups;
+pub use blocklist::{BlockKind, BlockedEntry, Blocklist};
+#[cfg(feature = "cache")]
+pub use bookmarks::BookmarkFormat;
+#[cfg(feature = "cache")]
+pub use cache::{
+    Bookmark, CacheStats, CachedTrack, ConflictResolution, LyricsAlternative, LyricsCache,
+    LyricsConflict,
+};
 pub use config::{
-    AnimationConfig, LayoutConfig, LyricsConfig, LyricsProviderType, MusicConfig, ProvidersConfig,
-    UiConfig, VersualizerConfig, build_config_template,
+    AnimationConfig, ArtworkConfig, ArtworkDisplay, BehaviorConfig, BlocklistConfig, CacheConfig,
+    ContrastConfig, CursorConfig, FillEasing, FollowConfig, LayoutConfig, LayoutMode, LineEntrance,
+    LyricsConfig, LyricsProviderType, MonitorChoice, MusicConfig, NetworkConfig, NoLyricsDisplay,
+    NowSingingConfig, OverlayView, ProcessorsConfig, ProvidersConfig, QueryConfig, RetryConfig,
+    RetryJitter, ScriptTarget, SecretStoreKind, ServerConfig, SyncConfig, TextProcessorKind,
+    TickerOverflow, TranslationBackend, TranslationConfig, UiConfig, VersualizerConfig,
+    WindowConfig, build_config_template,
 };
-
+#[cfg(feature = "watch")]
+pub use config_editor::ConfigEditor;
+#[cfg(feature = "watch")]
+pub use config_watcher::ConfigWatcher;
+pub use contrast::BackgroundTone;
+pub use control::PlaybackController;
+pub use diff::{DiffLine, LyricsDiff};
+pub use encryption::ContentCipher;
 pub use error::CoreError;
+#[cfg(feature = "cache")]
+pub use event_log::{EventLog, LoggedEvent, SESSION_STARTED};
+#[cfg(feature = "cache")]
 pub use fetcher::LyricsFetcher;
-pub use lrc::{LrcFile, LrcLine, LrcMetadata, LrcWord};
+pub use locale::LocaleFormatter;
+#[cfg(feature = "cache")]
+pub use lookup::{LyricsLookup, fetch_best_lyrics};
+pub use lrc::{
+    LrcFile, LrcLine, LrcMetadata, LrcWord, TOGETHER_PART, split_at_words, split_keeping_spacing,
+};
+pub use lyric_card::{AlbumArt, CardTheme, LyricCard};
+#[cfg(feature = "cache")]
+pub use maintenance::{CacheMaintenance, MaintenanceReport};
+#[cfg(feature = "cache")]
+pub use mirror::LyricsMirror;
+#[cfg(feature = "http")]
+pub use musicbrainz::MusicBrainzEnricher;
+pub use normalize::{CanonicalTrack, QueryEnricher, QueryNormalizer};
+pub use now_singing::NowSingingFiles;
+#[cfg(feature = "cache")]
+pub use overrides::LyricsOverrides;
 pub use paths::{
-    CONFIG_DIR_NAME, CONFIG_FILE_NAME, LYRICS_CACHE_DB_FILE_NAME, THEME_FILE_NAME,
-    WINDOW_STATE_FILE_NAME, config_dir, theme_path, window_state_path,
+    CONFIG_DIR_NAME, CONFIG_FILE_NAME, LYRICS_CACHE_DB_FILE_NAME, THEME_FILE_NAME, THEMES_DIR_NAME,
+    WINDOW_STATE_FILE_NAME, config_dir, theme_names, theme_path, theme_path_named, themes_dir,
+    window_state_path,
 };
 pub use playback::{PlaybackState, TrackInfo};
-pub use provider::{FetchedLyrics, LyricsProvider, LyricsQuery, LyricsResult};
+#[cfg(feature = "cache")]
+pub use prefetch::{PrefetchJob, PrefetchProgress, RateBudget};
+#[cfg(feature = "http")]
+pub use preflight::check_reachable;
+pub use preflight::{CheckResult, CheckStatus, Preflight};
+pub use processors::{TextPipeline, TextProcessor};
+pub use provider::{
+    AuthState, FetchedLyrics, LyricsCandidate, LyricsProvider, LyricsQuery, LyricsResult,
+};
+pub use provider_status::{ProviderStatus, ProviderStatusRegistry, ProviderStatuses};
+pub use recording::{SessionRecorder, TraceEntry, TraceEvent, TracePlayer};
+pub use secrets::{FileSecretStore, KeyringSecretStore, SecretStore};
+#[cfg(feature = "cache")]
+pub use session_stats::SessionStats;
 pub use source::{MusicSource, MusicSourceProvider, MusicSourceProviderBuilder};
-pub use sync::{SyncEngine, SyncEvent};
+pub use subtitles::SubtitleFormat;
+pub use sync::{SyncEngine, SyncEvent, apply_line_lead};
+pub use tempo::Tempo;
 pub use time::DurationExt;
+pub use timing_editor::TimingEditor;
 /// Re-export toml error type for config parsing error handling
 pub use toml::de::Error as TomlParseError;
+#[cfg(all(feature = "cache", feature = "http"))]
+pub use translation::{LyricsTranslation, LyricsTranslator};
+pub use ttml::{TtmlFile, TtmlLine};
@@ -0,0 +1,185 @@
+//! Size caps for lyrics, protecting the overlay from pathological provider responses.
+//!
+//! Real songs have a few hundred lines at most. A provider that returns a huge blob (a whole
+//! book, or binary data mislabeled as lyrics) would otherwise be precomputed and kept in
+//! memory by the overlay in full. [`cap_lyrics`] and [`cap_unsynced`] trim such payloads
+//! before they are shown: extra lines are dropped, overlong lines are cut, and word-level
+//! timing is dropped entirely when there is too much of it.
+
+use crate::lrc::LrcFile;
+
+/// Most lines kept from any lyrics
+pub const MAX_LINES: usize = 1000;
+/// Most characters kept from a single line
+pub const MAX_LINE_CHARS: usize = 300;
+/// Most timed words kept across all lines before word-level timing is dropped
+pub const MAX_WORDS: usize = 10_000;
+
+/// Trim `lrc` to the size caps. Returns a description of what was removed, or `None` if
+/// the lyrics were within the caps.
+pub fn cap_lyrics(lrc: &mut LrcFile) -> Option<String> {
+    let mut removed = Vec::new();
+
+    if lrc.lines.len() > MAX_LINES {
+        removed.push(format!(
+            "{} lines past the first {MAX_LINES}",
+            lrc.lines.len() - MAX_LINES
+        ));
+        lrc.lines.truncate(MAX_LINES);
+    }
+
+    let mut cut = 0;
+    for line in &mut lrc.lines {
+        if truncate_chars(&mut line.text, MAX_LINE_CHARS) {
+            // The word texts no longer add up to the line
+            line.words = None;
+            cut += 1;
+        }
+    }
+    if cut > 0 {
+        removed.push(format!(
+            "text past {MAX_LINE_CHARS} characters in {cut} lines"
+        ));
+    }
+
+    let words: usize = lrc
+        .lines
+        .iter()
+        .filter_map(|line| line.words.as_ref().map(Vec::len))
+        .sum();
+    if words > MAX_WORDS {
+        removed.push(format!("word timing for {words} words"));
+        for line in &mut lrc.lines {
+            line.words = None;
+        }
+    }
+
+    (!removed.is_empty()).then(|| removed.join(", "))
+}
+
+/// Trim plain lyrics to the size caps. Returns a description of what was removed, or
+/// `None` if the lyrics were within the caps.
+pub fn cap_unsynced(text: &mut String) -> Option<String> {
+    let mut removed = Vec::new();
+
+    let line_count = text.lines().count();
+    if line_count > MAX_LINES {
+        removed.push(format!(
+            "{} lines past the first {MAX_LINES}",
+            line_count - MAX_LINES
+        ));
+    }
+
+    let mut cut = 0;
+    let mut capped = String::new();
+    for line in text.lines().take(MAX_LINES) {
+        let mut line = line.to_string();
+        if truncate_chars(&mut line, MAX_LINE_CHARS) {
+            cut += 1;
+        }
+        if !capped.is_empty() {
+            capped.push('\n');
+        }
+        capped.push_str(&line);
+    }
+    if cut > 0 {
+        removed.push(format!(
+            "text past {MAX_LINE_CHARS} characters in {cut} lines"
+        ));
+    }
+
+    if removed.is_empty() {
+        return None;
+    }
+    *text = capped;
+    Some(removed.join(", "))
+}
+
+/// Approximate heap size of `lrc` in bytes, for logging
+#[must_use]
+pub fn lyrics_size(lrc: &LrcFile) -> usize {
+    lrc.lines
+        .iter()
+        .map(|line| {
+            line.text.len()
+                + line.words.as_ref().map_or(0, |words| {
+                    words
+                        .iter()
+                        .map(|word| word.text.len() + std::mem::size_of_val(word))
+                        .sum()
+                })
+                + std::mem::size_of_val(line)
+        })
+        .sum()
+}
+
+/// Cut `text` to at most `max` characters. Returns whether anything was cut.
+fn truncate_chars(text: &mut String, max: usize) -> bool {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => {
+            text.truncate(end);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_cap_lyrics_within_limits() {
+        let mut lrc = LrcFile::parse("[00:01.00]Hello\n[00:02.00]World").unwrap();
+
+        assert_eq!(cap_lyrics(&mut lrc), None);
+        assert_eq!(lrc.lines.len(), 2);
+    }
+
+    #[test]
+    fn test_cap_lyrics_drops_extra_lines_and_long_text() {
+        let mut input = String::new();
+        for i in 0..MAX_LINES + 5 {
+            let _ = writeln!(input, "[{:02}:{:02}.00]Line {i}", i / 60, i % 60);
+        }
+        let mut lrc = LrcFile::parse(&input).unwrap();
+        lrc.lines[0].text = "é".repeat(MAX_LINE_CHARS + 1);
+
+        let removed = cap_lyrics(&mut lrc).unwrap();
+
+        assert_eq!(lrc.lines.len(), MAX_LINES);
+        assert_eq!(lrc.lines[0].text.chars().count(), MAX_LINE_CHARS);
+        assert!(removed.contains("5 lines"));
+        assert!(removed.contains("in 1 lines"));
+    }
+
+    #[test]
+    fn test_cap_lyrics_drops_word_timing_when_too_large() {
+        // Short enough lines, but too many timed words in total
+        let words: String = (0..=MAX_WORDS / 100).map(|_| "<00:01.00>a ").collect();
+        let input = format!("[00:01.00]{words}\n").repeat(100);
+        let mut lrc = LrcFile::parse(&input).unwrap();
+        assert!(lrc.lines[0].words.is_some());
+
+        let removed = cap_lyrics(&mut lrc).unwrap();
+
+        assert!(lrc.lines.iter().all(|line| line.words.is_none()));
+        assert_eq!(
+            removed,
+            format!("word timing for {} words", 100 * (MAX_WORDS / 100 + 1))
+        );
+    }
+
+    #[test]
+    fn test_cap_unsynced() {
+        let mut text = "short\n".repeat(MAX_LINES + 1);
+        assert!(cap_unsynced(&mut text).is_some());
+        assert_eq!(text.lines().count(), MAX_LINES);
+
+        let mut text = "fine\nlyrics".to_string();
+        assert_eq!(cap_unsynced(&mut text), None);
+        assert_eq!(text, "fine\nlyrics");
+    }
+}
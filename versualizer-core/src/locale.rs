@@ -0,0 +1,190 @@
+//! Locale-aware number and duration formatting for UI display.
+//!
+//! Numbers shown in the overlay (progress times, countdowns, counters) use the
+//! digit grouping and decimal separator of the user's locale. The locale is taken
+//! from `[ui] locale` in the config, or detected from the environment when set to
+//! `"auto"`.
+
+use num_format::{Locale, ToFormattedString};
+use std::time::Duration;
+
+/// Config value that selects locale detection from the environment
+pub const AUTO_LOCALE: &str = "auto";
+
+/// Environment variables consulted (in order) when detecting the system locale
+const LOCALE_ENV_VARS: [&str; 3] = ["LC_ALL", "LC_NUMERIC", "LANG"];
+
+/// Formats numbers and durations for display according to a locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormatter {
+    locale: Locale,
+}
+
+impl Default for LocaleFormatter {
+    fn default() -> Self {
+        Self { locale: Locale::en }
+    }
+}
+
+impl LocaleFormatter {
+    /// Create a formatter from a config value.
+    ///
+    /// Accepts `"auto"` (detect from environment) or a BCP 47 tag such as `"de-CH"`
+    /// or `"fr"`. POSIX-style names like `de_DE.UTF-8` are also understood. Unknown
+    /// locales fall back to their language, then to English.
+    #[must_use]
+    pub fn from_config(locale: &str) -> Self {
+        if locale.eq_ignore_ascii_case(AUTO_LOCALE) {
+            return Self::from_env();
+        }
+        Self {
+            locale: resolve_locale(locale).unwrap_or(Locale::en),
+        }
+    }
+
+    /// Detect the locale from `LC_ALL`, `LC_NUMERIC` or `LANG`.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let locale = LOCALE_ENV_VARS
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find_map(|value| resolve_locale(&value))
+            .unwrap_or(Locale::en);
+        Self { locale }
+    }
+
+    /// Name of the resolved locale (e.g. `"de"`, `"en-IN"`)
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.locale.name()
+    }
+
+    /// Format an integer with locale digit grouping (e.g. `12,345` or `12.345`).
+    #[must_use]
+    pub fn format_count(&self, value: u64) -> String {
+        value.to_formatted_string(&self.locale)
+    }
+
+    /// Format a playback duration as `m:ss`, or `h:mm:ss` for an hour or longer.
+    ///
+    /// The leading component uses locale digit grouping so very long durations
+    /// stay readable.
+    #[must_use]
+    pub fn format_duration(&self, duration: Duration) -> String {
+        let total_secs = duration.as_secs();
+        let hours = total_secs / 3600;
+        let minutes = (total_secs % 3600) / 60;
+        let seconds = total_secs % 60;
+
+        if hours > 0 {
+            format!("{}:{minutes:02}:{seconds:02}", self.format_count(hours))
+        } else {
+            format!("{}:{seconds:02}", self.format_count(minutes))
+        }
+    }
+
+    /// Format remaining seconds for countdown badges, rounding up so the badge
+    /// never shows `0` while time remains.
+    #[must_use]
+    pub fn format_countdown(&self, remaining: Duration) -> String {
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        self.format_count(secs)
+    }
+
+    /// Format a duration in seconds with one decimal place using the locale's
+    /// decimal separator (e.g. `2.5` or `2,5`).
+    #[must_use]
+    pub fn format_seconds(&self, duration: Duration) -> String {
+        let tenths = duration.as_millis() / 100;
+        let whole = u64::try_from(tenths / 10).unwrap_or(u64::MAX);
+        let fraction = tenths % 10;
+        format!(
+            "{}{}{fraction}",
+            self.format_count(whole),
+            self.locale.decimal()
+        )
+    }
+}
+
+/// Resolve a locale name to a supported [`Locale`].
+///
+/// Strips POSIX encoding/modifier suffixes (`.UTF-8`, `@euro`), normalizes `_` to
+/// `-`, then tries the full tag before falling back to the language subtag.
+fn resolve_locale(name: &str) -> Option<Locale> {
+    let tag = name
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .replace('_', "-");
+
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+
+    Locale::from_name(&tag).ok().or_else(|| {
+        let language = tag.split('-').next().unwrap_or_default();
+        Locale::from_name(language).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_locale_full_tag() {
+        assert_eq!(resolve_locale("de-CH"), Some(Locale::de_CH));
+    }
+
+    #[test]
+    fn test_resolve_locale_posix_name() {
+        assert_eq!(resolve_locale("de_DE.UTF-8"), Some(Locale::de));
+        assert_eq!(resolve_locale("fr_FR@euro"), Some(Locale::fr));
+    }
+
+    #[test]
+    fn test_resolve_locale_unsupported() {
+        assert_eq!(resolve_locale("C"), None);
+        assert_eq!(resolve_locale("POSIX"), None);
+        assert_eq!(resolve_locale(""), None);
+        assert_eq!(resolve_locale("zz-ZZ"), None);
+    }
+
+    #[test]
+    fn test_from_config_falls_back_to_english() {
+        assert_eq!(LocaleFormatter::from_config("nonsense").name(), "en");
+    }
+
+    #[test]
+    fn test_format_count() {
+        let en = LocaleFormatter::from_config("en");
+        let de = LocaleFormatter::from_config("de");
+        assert_eq!(en.format_count(1_234_567), "1,234,567");
+        assert_eq!(de.format_count(1_234_567), "1.234.567");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        let en = LocaleFormatter::from_config("en");
+        assert_eq!(en.format_duration(Duration::from_secs(0)), "0:00");
+        assert_eq!(en.format_duration(Duration::from_secs(185)), "3:05");
+        assert_eq!(en.format_duration(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn test_format_countdown_rounds_up() {
+        let en = LocaleFormatter::from_config("en");
+        assert_eq!(en.format_countdown(Duration::from_millis(2100)), "3");
+        assert_eq!(en.format_countdown(Duration::from_secs(2)), "2");
+        assert_eq!(en.format_countdown(Duration::ZERO), "0");
+    }
+
+    #[test]
+    fn test_format_seconds_decimal_separator() {
+        let en = LocaleFormatter::from_config("en");
+        let de = LocaleFormatter::from_config("de");
+        assert_eq!(en.format_seconds(Duration::from_millis(2560)), "2.5");
+        assert_eq!(de.format_seconds(Duration::from_millis(2560)), "2,5");
+    }
+}
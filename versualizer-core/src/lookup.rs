@@ -0,0 +1,375 @@
+//! Looking up lyrics without the sync engine or UI.
+//!
+//! For scripts and other apps that only want the lyrics of a song: [`fetch_best_lyrics`]
+//! checks the cache, then asks the providers in order and caches what they find. A
+//! [`LyricsLookup`] does the same with the providers and options set up once, and can list
+//! every provider's candidates for a song so the right lyrics can be picked by hand.
+
+use crate::cache::{LyricsCache, TrackMetadata};
+use crate::error::{CoreError, Result};
+use crate::provider::{FetchedLyrics, LyricsCandidate, LyricsProvider, LyricsQuery, LyricsResult};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Find the best lyrics for `query`: cached synced lyrics, else the first synced lyrics a
+/// provider has, else the first plain lyrics. A provider reporting the track as instrumental
+/// ends the search with [`LyricsResult::Instrumental`], which is cached like synced lyrics.
+/// Provider and cache failures are logged and skipped, so they end in
+/// [`LyricsResult::NotFound`] rather than an error.
+pub async fn fetch_best_lyrics(
+    query: &LyricsQuery,
+    providers: &[Box<dyn LyricsProvider>],
+    cache: Option<&LyricsCache>,
+) -> LyricsResult {
+    lookup(query, providers, cache, true).await
+}
+
+/// Lyrics lookup over a fixed set of providers, with an optional cache
+pub struct LyricsLookup {
+    providers: Vec<Box<dyn LyricsProvider>>,
+    cache: Option<Arc<LyricsCache>>,
+    allow_unsynced: bool,
+}
+
+impl LyricsLookup {
+    /// Look up lyrics from `providers`, tried in order, without a cache
+    #[must_use]
+    pub fn new(providers: Vec<Box<dyn LyricsProvider>>) -> Self {
+        Self {
+            providers,
+            cache: None,
+            allow_unsynced: true,
+        }
+    }
+
+    /// Check `cache` before asking the providers, and keep the synced lyrics they find there
+    #[must_use]
+    pub fn with_cache(mut self, cache: Arc<LyricsCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Whether plain lyrics are returned when no provider has synced ones (the default)
+    #[must_use]
+    pub const fn with_unsynced(mut self, allow_unsynced: bool) -> Self {
+        self.allow_unsynced = allow_unsynced;
+        self
+    }
+
+    /// Find the best lyrics for `query`, as [`fetch_best_lyrics`] does
+    pub async fn fetch(&self, query: &LyricsQuery) -> LyricsResult {
+        lookup(
+            query,
+            &self.providers,
+            self.cache.as_deref(),
+            self.allow_unsynced,
+        )
+        .await
+    }
+
+    /// Every provider's candidates for `query`, in provider order and best match first within
+    /// each. Providers that fail are logged and skipped.
+    pub async fn search(&self, query: &LyricsQuery) -> Vec<LyricsCandidate> {
+        search_providers(query, self.providers.iter().map(AsRef::as_ref)).await
+    }
+
+    /// Fetch the lyrics of `candidate`, from [`Self::search`], and cache them for `query`
+    /// pinned over any other provider's
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the candidate's provider is not one of this lookup's, fails, or
+    /// has no lyrics for it.
+    pub async fn fetch_candidate(
+        &self,
+        query: &LyricsQuery,
+        candidate: &LyricsCandidate,
+    ) -> Result<LyricsResult> {
+        let provider = self
+            .providers
+            .iter()
+            .find(|provider| provider.name() == candidate.provider)
+            .ok_or_else(|| CoreError::LyricsProviderFailed {
+                provider: candidate.provider.clone(),
+                reason: "not configured".into(),
+            })?;
+        let fetched = provider.fetch_candidate(query, candidate).await?;
+        if !fetched.result.is_found() {
+            return Err(CoreError::LyricsNotFound {
+                track: candidate.title.clone(),
+                artist: candidate.artist.clone(),
+            });
+        }
+        if let Some(ref cache) = self.cache
+            && let Some(id) = store(cache, query, provider.name(), &fetched).await
+            && let Err(e) = cache.select_alternative(id, provider.name()).await
+        {
+            warn!("Failed to pin the chosen lyrics: {}", e);
+        }
+        Ok(fetched.result)
+    }
+}
+
+/// Candidates of every provider in `providers` for `query`, in order
+pub(crate) async fn search_providers(
+    query: &LyricsQuery,
+    providers: impl IntoIterator<Item = &dyn LyricsProvider>,
+) -> Vec<LyricsCandidate> {
+    let mut candidates = Vec::new();
+    for provider in providers {
+        match provider.search(query).await {
+            Ok(found) => {
+                debug!("{} candidates from {}", found.len(), provider.name());
+                candidates.extend(found);
+            }
+            Err(e) => warn!("Provider {} search failed: {}", provider.name(), e),
+        }
+    }
+    candidates
+}
+
+async fn lookup(
+    query: &LyricsQuery,
+    providers: &[Box<dyn LyricsProvider>],
+    cache: Option<&LyricsCache>,
+    allow_unsynced: bool,
+) -> LyricsResult {
+    if let Some(cache) = cache
+        && let Some(lyrics) = cached(cache, query).await
+    {
+        return lyrics;
+    }
+
+    let mut unsynced = None;
+    for provider in providers {
+        let fetched = match provider.fetch(query).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                warn!("Provider {} failed: {}", provider.name(), e);
+                continue;
+            }
+        };
+        match fetched.result {
+            LyricsResult::Synced(_) | LyricsResult::Instrumental => {
+                if fetched.result.is_synced() {
+                    info!("Found synced lyrics from {}", provider.name());
+                } else {
+                    info!("{} reports the track as instrumental", provider.name());
+                }
+                if let Some(cache) = cache {
+                    store(cache, query, provider.name(), &fetched).await;
+                }
+                return fetched.result;
+            }
+            LyricsResult::Unsynced(text) if allow_unsynced && unsynced.is_none() => {
+                unsynced = Some(text);
+            }
+            _ => debug!("Provider {} has no synced lyrics", provider.name()),
+        }
+    }
+    unsynced.map_or(LyricsResult::NotFound, LyricsResult::Unsynced)
+}
+
+/// Synced lyrics cached under one of the query's track IDs, or under its title and artist
+async fn cached(cache: &LyricsCache, query: &LyricsQuery) -> Option<LyricsResult> {
+    let mut entry = None;
+    for (provider, id) in &query.provider_ids {
+        entry = cache
+            .get_by_provider_id(provider, id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to read the lyrics cache: {}", e);
+                None
+            });
+        if entry.is_some() {
+            break;
+        }
+    }
+    if entry.is_none() {
+        entry = cache
+            .get_by_metadata(
+                &query.artist_name,
+                &query.track_name,
+                query.album_name.as_deref(),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to read the lyrics cache: {}", e);
+                None
+            });
+    }
+    let lyrics = entry?.to_lyrics_result();
+    (lyrics.is_synced() || matches!(lyrics, LyricsResult::Instrumental)).then_some(lyrics)
+}
+
+/// Cache lyrics `provider` found, under the query's first track ID or else the provider's own.
+/// Returns the cache entry's ID.
+async fn store(
+    cache: &LyricsCache,
+    query: &LyricsQuery,
+    provider: &str,
+    fetched: &FetchedLyrics,
+) -> Option<i64> {
+    let (source, source_id) = query
+        .provider_ids
+        .iter()
+        .min()
+        .map_or((provider, fetched.provider_id.as_str()), |(source, id)| {
+            (source.as_str(), id.as_str())
+        });
+    let metadata = TrackMetadata {
+        artist: query.artist_name.clone(),
+        track: query.track_name.clone(),
+        album: query.album_name.clone(),
+        duration_ms: query.duration_secs.map(|secs| i64::from(secs) * 1000),
+    };
+    cache
+        .store(
+            source,
+            source_id,
+            &fetched.result,
+            &metadata,
+            provider,
+            &fetched.provider_id,
+        )
+        .await
+        .inspect_err(|e| warn!("Failed to cache lyrics: {}", e))
+        .ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::error::{CoreError, Result};
+    use crate::lrc::LrcFile;
+    use async_trait::async_trait;
+
+    /// Provider that always gives the same answer
+    struct FakeProvider {
+        name: &'static str,
+        result: Option<LyricsResult>,
+    }
+
+    #[async_trait]
+    impl LyricsProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn fetch(&self, _query: &LyricsQuery) -> Result<FetchedLyrics> {
+            let result = self
+                .result
+                .clone()
+                .ok_or_else(|| CoreError::LyricsProviderFailed {
+                    provider: self.name.to_string(),
+                    reason: "unreachable".to_string(),
+                })?;
+            Ok(FetchedLyrics {
+                result,
+                provider_id: format!("{}-1", self.name),
+            })
+        }
+
+        async fn search(&self, _query: &LyricsQuery) -> Result<Vec<LyricsCandidate>> {
+            let found = self.fetch(&LyricsQuery::new("Song", "Artist")).await?;
+            Ok(vec![LyricsCandidate {
+                provider: self.name.to_string(),
+                id: found.provider_id,
+                title: "Song".to_string(),
+                artist: "Artist".to_string(),
+                duration_secs: None,
+                synced: found.result.is_synced(),
+                preview: found
+                    .result
+                    .text()
+                    .as_deref()
+                    .map(LyricsCandidate::preview_of),
+            }])
+        }
+    }
+
+    fn provider(name: &'static str, result: Option<LyricsResult>) -> Box<dyn LyricsProvider> {
+        Box::new(FakeProvider { name, result })
+    }
+
+    fn synced(text: &str) -> LyricsResult {
+        LyricsResult::Synced(LrcFile::parse(&format!("[00:01.00]{text}")).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_best_lyrics_prefers_synced() {
+        let providers = vec![
+            provider("failing", None),
+            provider("plain", Some(LyricsResult::Unsynced("Plain".to_string()))),
+            provider("synced", Some(synced("Synced"))),
+        ];
+        let query = LyricsQuery::new("Song", "Artist");
+        let result = fetch_best_lyrics(&query, &providers, None).await;
+        assert_eq!(result.as_synced().unwrap().lines[0].text, "Synced");
+
+        // Without synced lyrics anywhere, the plain ones are the best there is
+        let result = fetch_best_lyrics(&query, &providers[..2], None).await;
+        assert!(matches!(result, LyricsResult::Unsynced(ref text) if text == "Plain"));
+        let mut providers = providers;
+        providers.truncate(2);
+        let lookup = LyricsLookup::new(providers).with_unsynced(false);
+        assert!(!lookup.fetch(&query).await.is_found());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_uses_cache() {
+        let path =
+            std::env::temp_dir().join(format!("versualizer-lookup-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cache = Arc::new(LyricsCache::open(&path).await.unwrap());
+
+        let query = LyricsQuery::new("Song", "Artist").with_album("Album");
+        let lookup = LyricsLookup::new(vec![provider("first", Some(synced("Cached")))])
+            .with_cache(cache.clone());
+        assert!(lookup.fetch(&query).await.is_synced());
+
+        // A provider with different lyrics is not asked once they are cached
+        let lookup =
+            LyricsLookup::new(vec![provider("second", Some(synced("Fresh")))]).with_cache(cache);
+        let result = lookup.fetch(&query).await;
+        assert_eq!(result.as_synced().unwrap().lines[0].text, "Cached");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_search_and_pick_candidate() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-lookup-search-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = Arc::new(LyricsCache::open(&path).await.unwrap());
+
+        let query = LyricsQuery::new("Song", "Artist");
+        let lookup = LyricsLookup::new(vec![
+            provider("failing", None),
+            provider("auto", Some(synced("Wrong song"))),
+            provider("other", Some(synced("Right song"))),
+        ])
+        .with_cache(cache.clone());
+        assert!(lookup.fetch(&query).await.is_synced());
+
+        let candidates = lookup.search(&query).await;
+        let providers: Vec<_> = candidates.iter().map(|c| c.provider.as_str()).collect();
+        assert_eq!(providers, ["auto", "other"]);
+        assert_eq!(candidates[1].preview.as_deref(), Some("Right song"));
+
+        let chosen = lookup
+            .fetch_candidate(&query, &candidates[1])
+            .await
+            .unwrap();
+        assert!(chosen.is_synced());
+        // The choice is pinned in the cache over the automatic match
+        let result = lookup.fetch(&query).await;
+        assert_eq!(result.as_synced().unwrap().lines[0].text, "Right song");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,10 +1,13 @@
 use crate::error::Result;
+use std::fmt;
 use std::time::Duration;
 
 /// Parsed LRC file containing metadata and synchronized lines
 #[derive(Debug, Clone, Default)]
 pub struct LrcFile {
     pub metadata: LrcMetadata,
+    /// Lines sorted by start time, as [`LrcFile::parse`] leaves them. The position lookups
+    /// below binary search them.
     pub lines: Vec<LrcLine>,
 }
 
@@ -16,7 +19,9 @@ pub struct LrcMetadata {
     pub album: Option<String>,
     pub author: Option<String>,
     pub length: Option<Duration>,
-    pub offset: i64, // milliseconds, can be negative
+    /// The file's `[offset]` tag in milliseconds (can be negative). Line and word times
+    /// already include it, so it is not written back out.
+    pub offset: i64,
 }
 
 /// A single line of lyrics with timing
@@ -26,8 +31,17 @@ pub struct LrcLine {
     pub text: String,
     /// Word-level timing for enhanced LRC
     pub words: Option<Vec<LrcWord>>,
+    /// Romanized reading shown with the line, added by the fetcher when
+    /// `lyrics.processors.romanize_lines` is enabled
+    pub romanized: Option<String>,
+    /// Singer of the line in a duet, e.g. `v1` and `v2`, or `M` and `F`, written as a `v1:`
+    /// prefix. [`TOGETHER_PART`] marks lines sung by both.
+    pub part: Option<String>,
 }
 
+/// Part of duet lines sung together, written as a `D:` prefix
+pub const TOGETHER_PART: &str = "D";
+
 /// Word-level timing for enhanced LRC format
 #[derive(Debug, Clone)]
 pub struct LrcWord {
@@ -76,46 +90,51 @@ impl LrcFile {
             }
         }
 
-        // Apply offset to all lines
-        if metadata.offset != 0 {
-            for line in &mut lines {
-                line.start_time = apply_offset(line.start_time, metadata.offset);
-                if let Some(ref mut words) = line.words {
-                    for word in words {
-                        word.start_time = apply_offset(word.start_time, metadata.offset);
-                        if let Some(end) = word.end_time {
-                            word.end_time = Some(apply_offset(end, metadata.offset));
-                        }
+        // Sort lines by start time
+        lines.sort_by_key(|l| l.start_time);
+
+        let offset = metadata.offset;
+        let mut lrc = Self { metadata, lines };
+        lrc.shift(offset);
+        Ok(lrc)
+    }
+
+    /// Move every line and word by `offset_ms` (negative moves them earlier, stopping at 0)
+    pub fn shift(&mut self, offset_ms: i64) {
+        if offset_ms == 0 {
+            return;
+        }
+        for line in &mut self.lines {
+            line.start_time = apply_offset(line.start_time, offset_ms);
+            if let Some(ref mut words) = line.words {
+                for word in words {
+                    word.start_time = apply_offset(word.start_time, offset_ms);
+                    if let Some(end) = word.end_time {
+                        word.end_time = Some(apply_offset(end, offset_ms));
                     }
                 }
             }
         }
-
-        // Sort lines by start time
-        lines.sort_by_key(|l| l.start_time);
-
-        Ok(Self { metadata, lines })
     }
 
     /// Find the current line for a given playback position
     #[must_use]
     pub fn current_line(&self, position: Duration) -> Option<&LrcLine> {
-        // Find the last line that started before or at the current position
-        self.lines
-            .iter()
-            .rev()
-            .find(|line| line.start_time <= position)
+        self.current_line_index(position)
+            .and_then(|index| self.lines.get(index))
     }
 
     /// Find the current line index for a given playback position
     #[must_use]
     pub fn current_line_index(&self, position: Duration) -> Option<usize> {
+        // The last line that started before or at the current position
+        self.started_lines(position).checked_sub(1)
+    }
+
+    /// Number of lines that started before or at a given playback position
+    fn started_lines(&self, position: Duration) -> usize {
         self.lines
-            .iter()
-            .enumerate()
-            .rev()
-            .find(|(_, line)| line.start_time <= position)
-            .map(|(i, _)| i)
+            .partition_point(|line| line.start_time <= position)
     }
 
     /// Get lines around the current position for display
@@ -128,6 +147,44 @@ impl LrcFile {
 
         self.lines[start..end].iter().collect()
     }
+
+    /// Find the first line that starts after a given playback position
+    #[must_use]
+    pub fn next_line(&self, position: Duration) -> Option<&LrcLine> {
+        self.next_line_index(position)
+            .and_then(|index| self.lines.get(index))
+    }
+
+    /// Find the index of the first line that starts after a given playback position
+    #[must_use]
+    pub fn next_line_index(&self, position: Duration) -> Option<usize> {
+        let index = self.started_lines(position);
+        (index < self.lines.len()).then_some(index)
+    }
+
+    /// Time from a given playback position until the next line starts, or `None` after the
+    /// last line has started
+    #[must_use]
+    pub fn time_until_next(&self, position: Duration) -> Option<Duration> {
+        self.next_line(position)
+            .map(|line| line.start_time.saturating_sub(position))
+    }
+
+    /// Iterate over the lines from the current line at a given playback position onward,
+    /// or over all lines before the first one starts
+    pub fn iter_from(&self, position: Duration) -> impl Iterator<Item = &LrcLine> {
+        let start = self.started_lines(position).saturating_sub(1);
+        self.lines[start..].iter()
+    }
+
+    /// Iterate over each line together with the start time of the line after it
+    /// (`None` for the last line), the end point [`LrcLine::progress`] expects
+    pub fn windows(&self) -> impl Iterator<Item = (&LrcLine, Option<Duration>)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (line, self.lines.get(i + 1).map(|next| next.start_time)))
+    }
 }
 
 impl LrcLine {
@@ -241,6 +298,51 @@ impl LrcLine {
     }
 }
 
+/// Standard LRC text: ID tags, then one `[mm:ss.xx]` line each, in enhanced LRC with
+/// `<mm:ss.xx>` word timestamps for lines that have word timing
+impl fmt::Display for LrcFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref title) = self.metadata.title {
+            writeln!(f, "[ti:{title}]")?;
+        }
+        if let Some(ref artist) = self.metadata.artist {
+            writeln!(f, "[ar:{artist}]")?;
+        }
+        if let Some(ref album) = self.metadata.album {
+            writeln!(f, "[al:{album}]")?;
+        }
+
+        for line in &self.lines {
+            let timestamp = format_timestamp(line.start_time);
+            let part = line
+                .part
+                .as_ref()
+                .map_or_else(String::new, |part| format!("{part}: "));
+            if let Some(ref words) = line.words {
+                // Enhanced LRC format
+                write!(f, "[{timestamp}]{part}")?;
+                for word in words {
+                    write!(f, " <{}> {}", format_timestamp(word.start_time), word.text)?;
+                }
+                writeln!(f)?;
+            } else {
+                writeln!(f, "[{timestamp}]{part}{}", line.text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format a duration as LRC timestamp (mm:ss.xx)
+fn format_timestamp(duration: Duration) -> String {
+    let total_secs = duration.as_secs();
+    let minutes = total_secs / 60;
+    let seconds = total_secs % 60;
+    let hundredths = duration.subsec_millis() / 10;
+
+    format!("{minutes:02}:{seconds:02}.{hundredths:02}")
+}
+
 /// Parse an ID tag like [ti:Title] or [ar:Artist]
 fn parse_id_tag(line: &str) -> Option<(String, String)> {
     if !line.starts_with('[') || !line.contains(':') {
@@ -307,7 +409,7 @@ fn parse_lyric_line(line: &str) -> Option<Vec<LrcLine>> {
         return None;
     }
 
-    let text = remaining.trim();
+    let (part, text) = split_part(remaining.trim());
 
     // Check for enhanced LRC format with word timing
     let words = parse_enhanced_words(text);
@@ -331,12 +433,31 @@ fn parse_lyric_line(line: &str) -> Option<Vec<LrcLine>> {
                 text.to_string()
             },
             words: words.clone(),
+            romanized: None,
+            part: part.clone(),
         });
     }
 
     Some(results)
 }
 
+/// Split a duet part prefix such as `v1:`, or `M:`, `F:` and `D:` (male, female and both),
+/// off the start of a line's text
+fn split_part(text: &str) -> (Option<String>, &str) {
+    let Some((prefix, rest)) = text.split_once(':') else {
+        return (None, text);
+    };
+    let is_part = matches!(prefix, "M" | "F" | TOGETHER_PART)
+        || prefix
+            .strip_prefix('v')
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+    if is_part {
+        (Some(prefix.to_string()), rest.trim_start())
+    } else {
+        (None, text)
+    }
+}
+
 /// Parse a timestamp string like "00:12.34" or "00:12:34"
 #[allow(clippy::cast_precision_loss)]
 fn parse_timestamp(s: &str) -> Option<Duration> {
@@ -423,7 +544,7 @@ fn parse_enhanced_words(text: &str) -> Option<Vec<LrcWord>> {
 }
 
 /// Apply a millisecond offset to a duration (can be negative)
-fn apply_offset(duration: Duration, offset_ms: i64) -> Duration {
+pub(crate) fn apply_offset(duration: Duration, offset_ms: i64) -> Duration {
     if offset_ms >= 0 {
         // Safe: we just checked offset_ms is non-negative, so it fits in u64
         let offset = u64::try_from(offset_ms).unwrap_or(0);
@@ -433,11 +554,260 @@ fn apply_offset(duration: Duration, offset_ms: i64) -> Duration {
     }
 }
 
+/// Split `text` into one segment per whitespace-separated word, keeping the spacing and
+/// punctuation: each segment is a word with the whitespace after it, and the segments
+/// join back into `text`.
+#[must_use]
+pub fn split_keeping_spacing(text: &str) -> Vec<&str> {
+    let mut starts = vec![0];
+    let mut seen_word = false;
+    let mut prev_whitespace = false;
+    for (i, c) in text.char_indices() {
+        if !c.is_whitespace() {
+            if seen_word && prev_whitespace {
+                starts.push(i);
+            }
+            seen_word = true;
+        }
+        prev_whitespace = c.is_whitespace();
+    }
+    segments_at(text, &starts)
+}
+
+/// Split `text` into one segment per entry of `words`, each starting where that word
+/// appears. Text before the first word stays with the first segment and the segments join
+/// back into `text`. Returns `None` if the words do not appear in `text` in order.
+#[must_use]
+pub fn split_at_words<'a>(text: &'a str, words: &[&str]) -> Option<Vec<&'a str>> {
+    if words.is_empty() {
+        return None;
+    }
+    let mut starts = Vec::with_capacity(words.len());
+    let mut cursor = 0;
+    for word in words {
+        let word = word.trim();
+        let start = cursor + text[cursor..].find(word)?;
+        starts.push(start);
+        cursor = start + word.len();
+    }
+    starts[0] = 0;
+    Some(segments_at(text, &starts))
+}
+
+/// Slice `text` at the byte offsets in `starts` (ascending, first is 0)
+fn segments_at<'a>(text: &'a str, starts: &[usize]) -> Vec<&'a str> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| &text[start..starts.get(i + 1).copied().unwrap_or(text.len())])
+        .collect()
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::float_cmp)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_timestamp_basic() {
+        // 12 seconds, 340 milliseconds
+        let duration = Duration::from_millis(12340);
+        assert_eq!(format_timestamp(duration), "00:12.34");
+    }
+
+    #[test]
+    fn test_format_timestamp_with_minutes() {
+        // 1 minute, 30 seconds
+        let duration = Duration::from_secs(90);
+        assert_eq!(format_timestamp(duration), "01:30.00");
+    }
+
+    #[test]
+    fn test_format_timestamp_zero() {
+        let duration = Duration::ZERO;
+        assert_eq!(format_timestamp(duration), "00:00.00");
+    }
+
+    #[test]
+    fn test_format_timestamp_long_duration() {
+        // 5 minutes, 45 seconds, 670 ms
+        let duration = Duration::from_millis(5 * 60 * 1000 + 45 * 1000 + 670);
+        assert_eq!(format_timestamp(duration), "05:45.67");
+    }
+
+    #[test]
+    fn test_display_simple() {
+        let lrc = LrcFile {
+            metadata: LrcMetadata::default(),
+            lines: vec![
+                LrcLine {
+                    start_time: Duration::from_secs(5),
+                    text: "Hello world".to_string(),
+                    words: None,
+                    romanized: None,
+                    part: None,
+                },
+                LrcLine {
+                    start_time: Duration::from_secs(10),
+                    text: "Second line".to_string(),
+                    words: None,
+                    romanized: None,
+                    part: None,
+                },
+            ],
+        };
+
+        let serialized = lrc.to_string();
+        assert!(serialized.contains("[00:05.00]Hello world"));
+        assert!(serialized.contains("[00:10.00]Second line"));
+    }
+
+    #[test]
+    fn test_display_with_metadata() {
+        let lrc = LrcFile {
+            metadata: LrcMetadata {
+                title: Some("Test Song".to_string()),
+                artist: Some("Test Artist".to_string()),
+                album: Some("Test Album".to_string()),
+                offset: 0,
+                ..Default::default()
+            },
+            lines: vec![LrcLine {
+                start_time: Duration::from_secs(5),
+                text: "Lyrics here".to_string(),
+                words: None,
+                romanized: None,
+                part: None,
+            }],
+        };
+
+        let serialized = lrc.to_string();
+        assert!(serialized.contains("[ti:Test Song]"));
+        assert!(serialized.contains("[ar:Test Artist]"));
+        assert!(serialized.contains("[al:Test Album]"));
+    }
+
+    #[test]
+    fn test_display_with_offset() {
+        let lrc = LrcFile {
+            metadata: LrcMetadata {
+                offset: 500,
+                ..Default::default()
+            },
+            lines: vec![LrcLine {
+                start_time: Duration::from_secs(5),
+                text: "Test".to_string(),
+                words: None,
+                romanized: None,
+                part: None,
+            }],
+        };
+
+        // The timestamps already include the offset
+        let serialized = lrc.to_string();
+        assert!(!serialized.contains("[offset:"));
+        assert!(serialized.contains("[00:05.00]Test"));
+    }
+
+    #[test]
+    fn test_round_trip_with_offset() {
+        let lrc =
+            LrcFile::parse("[offset:-500]\n[00:10.00]<00:10.00> One <00:11.00> two\n").unwrap();
+        let reparsed = LrcFile::parse(&lrc.to_string()).unwrap();
+        assert_eq!(reparsed.lines[0].start_time, Duration::from_millis(9500));
+        let words = reparsed.lines[0].words.as_ref().unwrap();
+        assert_eq!(words[1].start_time, Duration::from_millis(10_500));
+        assert_eq!(reparsed.to_string(), lrc.to_string());
+    }
+
+    #[test]
+    fn test_display_enhanced_format() {
+        let lrc = LrcFile {
+            metadata: LrcMetadata::default(),
+            lines: vec![LrcLine {
+                start_time: Duration::from_secs(5),
+                text: "Hello world".to_string(),
+                words: Some(vec![
+                    LrcWord {
+                        start_time: Duration::from_secs(5),
+                        end_time: Some(Duration::from_millis(5500)),
+                        text: "Hello".to_string(),
+                    },
+                    LrcWord {
+                        start_time: Duration::from_millis(5500),
+                        end_time: Some(Duration::from_secs(6)),
+                        text: "world".to_string(),
+                    },
+                ]),
+                romanized: None,
+                part: None,
+            }],
+        };
+
+        let serialized = lrc.to_string();
+        assert!(serialized.contains("[00:05.00]"));
+        assert!(serialized.contains("<00:05.00>"));
+        assert!(serialized.contains("Hello"));
+        assert!(serialized.contains("<00:05.50>"));
+        assert!(serialized.contains("world"));
+    }
+
+    #[test]
+    fn test_parse_duet_parts() {
+        let lrc = LrcFile::parse(
+            "[00:01.00]v1: Hello\n[00:02.00]v2: <00:02.00> Hi <00:02.50> there\n[00:03.00]Both: yes",
+        )
+        .unwrap();
+        assert_eq!(lrc.lines[0].part.as_deref(), Some("v1"));
+        assert_eq!(lrc.lines[0].text, "Hello");
+        assert_eq!(lrc.lines[1].part.as_deref(), Some("v2"));
+        assert_eq!(lrc.lines[1].text, "Hi there");
+        assert_eq!(lrc.lines[2].part, None);
+        assert_eq!(lrc.lines[2].text, "Both: yes");
+
+        // Parts survive writing the lyrics back out
+        let reparsed = LrcFile::parse(&lrc.to_string()).unwrap();
+        assert_eq!(reparsed.lines[1].part.as_deref(), Some("v2"));
+        assert_eq!(reparsed.lines[1].words.as_ref().unwrap().len(), 2);
+
+        let lrc =
+            LrcFile::parse("[00:01.00]M: Hey\n[00:02.00]F:Ho\n[00:03.00]D: Go\n[00:04.00]MF: no")
+                .unwrap();
+        assert_eq!(lrc.lines[0].part.as_deref(), Some("M"));
+        assert_eq!(lrc.lines[1].part.as_deref(), Some("F"));
+        assert_eq!(lrc.lines[1].text, "Ho");
+        assert_eq!(lrc.lines[2].part.as_deref(), Some(TOGETHER_PART));
+        assert_eq!(lrc.lines[3].part, None);
+    }
+
+    #[test]
+    fn test_split_keeping_spacing() {
+        assert_eq!(
+            split_keeping_spacing("Hey,  you there!"),
+            vec!["Hey,  ", "you ", "there!"]
+        );
+        assert_eq!(split_keeping_spacing(" lead"), vec![" lead"]);
+        assert!(split_keeping_spacing("").is_empty());
+    }
+
+    #[test]
+    fn test_split_at_words() {
+        assert_eq!(
+            split_at_words("(Oh, yeah) now", &["Oh", "yeah", "now"]).unwrap(),
+            vec!["(Oh, ", "yeah) ", "now"]
+        );
+        // Words without spaces between them, as in CJK lyrics
+        assert_eq!(
+            split_at_words("我爱你", &["我", "爱", "你"]).unwrap(),
+            vec!["我", "爱", "你"]
+        );
+        assert!(split_at_words("one two", &["two", "one"]).is_none());
+        assert!(split_at_words("one", &[]).is_none());
+    }
+
     #[test]
     fn test_parse_simple_lrc() {
         let input = "[00:12.34]Hello world";
@@ -522,8 +892,8 @@ mod tests {
         assert_eq!(result.lines.len(), 2);
         assert_eq!(result.lines[0].text, "Repeated lyric");
         assert_eq!(result.lines[1].text, "Repeated lyric");
-        assert_eq!(result.lines[0].start_time, Duration::from_millis(5000));
-        assert_eq!(result.lines[1].start_time, Duration::from_millis(15000));
+        assert_eq!(result.lines[0].start_time, Duration::from_secs(5));
+        assert_eq!(result.lines[1].start_time, Duration::from_secs(15));
     }
 
     #[test]
@@ -556,6 +926,8 @@ mod tests {
             start_time: Duration::from_secs(10),
             text: "Hello world".to_string(),
             words: None,
+            romanized: None,
+            part: None,
         };
 
         let next_start = Some(Duration::from_secs(15));
@@ -613,6 +985,8 @@ mod tests {
             start_time: Duration::from_secs(10),
             text: String::new(),
             words: None,
+            romanized: None,
+            part: None,
         };
 
         // Empty text should return 1.0
@@ -625,6 +999,8 @@ mod tests {
             start_time: Duration::from_secs(10),
             text: "Hello world".to_string(), // 11 chars
             words: None,
+            romanized: None,
+            part: None,
         };
 
         // Without word timing, word_progress falls back to line.progress()
@@ -649,20 +1025,22 @@ mod tests {
             text: "Hello world".to_string(),
             words: Some(vec![
                 LrcWord {
-                    start_time: Duration::from_millis(10000),
+                    start_time: Duration::from_secs(10),
                     end_time: Some(Duration::from_millis(10500)),
                     text: "Hello".to_string(),
                 },
                 LrcWord {
                     start_time: Duration::from_millis(10500),
-                    end_time: Some(Duration::from_millis(11000)),
+                    end_time: Some(Duration::from_secs(11)),
                     text: "world".to_string(),
                 },
             ]),
+            romanized: None,
+            part: None,
         };
 
         // Before word starts - should be 0.0
-        assert_eq!(line.word_progress(Duration::from_millis(9000), 0), 0.0);
+        assert_eq!(line.word_progress(Duration::from_secs(9), 0), 0.0);
     }
 
     #[test]
@@ -672,22 +1050,69 @@ mod tests {
             text: "Hello world".to_string(),
             words: Some(vec![
                 LrcWord {
-                    start_time: Duration::from_millis(10000),
+                    start_time: Duration::from_secs(10),
                     end_time: Some(Duration::from_millis(10500)),
                     text: "Hello".to_string(),
                 },
                 LrcWord {
                     start_time: Duration::from_millis(10500),
-                    end_time: Some(Duration::from_millis(11000)),
+                    end_time: Some(Duration::from_secs(11)),
                     text: "world".to_string(),
                 },
             ]),
+            romanized: None,
+            part: None,
         };
 
         // After first word ends - char 0 (in "Hello") should be 1.0
         assert_eq!(line.word_progress(Duration::from_millis(10600), 0), 1.0);
     }
 
+    #[test]
+    fn test_next_line_and_time_until_next() {
+        let lrc = LrcFile::parse("[00:05.00]First\n[00:10.00]Second\n[00:15.00]Third").unwrap();
+
+        assert_eq!(lrc.next_line(Duration::ZERO).unwrap().text, "First");
+        assert_eq!(
+            lrc.next_line(Duration::from_secs(10)).unwrap().text,
+            "Third"
+        );
+        assert!(lrc.next_line(Duration::from_secs(15)).is_none());
+        assert_eq!(
+            lrc.time_until_next(Duration::from_millis(7500)),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(lrc.time_until_next(Duration::from_secs(20)), None);
+
+        // Lines sharing a start time are passed together
+        let lrc = LrcFile::parse("[00:05.00]A\n[00:05.00]B\n[00:10.00]C").unwrap();
+        assert_eq!(lrc.current_line_index(Duration::from_secs(5)), Some(1));
+        assert_eq!(lrc.next_line_index(Duration::from_secs(5)), Some(2));
+        assert_eq!(lrc.next_line_index(Duration::from_secs(10)), None);
+    }
+
+    #[test]
+    fn test_iter_from_and_windows() {
+        let lrc = LrcFile::parse("[00:05.00]First\n[00:10.00]Second\n[00:15.00]Third").unwrap();
+
+        let texts = |lines: Vec<&LrcLine>| lines.iter().map(|l| l.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(lrc.iter_from(Duration::ZERO).collect()).len(), 3);
+        assert_eq!(
+            texts(lrc.iter_from(Duration::from_secs(12)).collect()),
+            ["Second", "Third"]
+        );
+
+        let ends: Vec<_> = lrc.windows().map(|(_, next)| next).collect();
+        assert_eq!(
+            ends,
+            [
+                Some(Duration::from_secs(10)),
+                Some(Duration::from_secs(15)),
+                None
+            ]
+        );
+    }
+
     #[test]
     fn test_current_line_index() {
         let input = r"
@@ -736,27 +1161,29 @@ mod tests {
     #[test]
     fn test_line_progress_with_enhanced_words() {
         let line = LrcLine {
-            start_time: Duration::from_millis(10000),
+            start_time: Duration::from_secs(10),
             text: "Hello world".to_string(),
             words: Some(vec![
                 LrcWord {
-                    start_time: Duration::from_millis(10000),
+                    start_time: Duration::from_secs(10),
                     end_time: Some(Duration::from_millis(10500)),
                     text: "Hello".to_string(),
                 },
                 LrcWord {
                     start_time: Duration::from_millis(10500),
-                    end_time: Some(Duration::from_millis(11000)),
+                    end_time: Some(Duration::from_secs(11)),
                     text: "world".to_string(),
                 },
             ]),
+            romanized: None,
+            part: None,
         };
 
         // At start
-        assert_eq!(line.progress(Duration::from_millis(10000), None), 0.0);
+        assert_eq!(line.progress(Duration::from_secs(10), None), 0.0);
 
         // At end
-        assert_eq!(line.progress(Duration::from_millis(11000), None), 1.0);
+        assert_eq!(line.progress(Duration::from_secs(11), None), 1.0);
 
         // Halfway through
         let progress = line.progress(Duration::from_millis(10500), None);
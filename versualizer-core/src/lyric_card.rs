@@ -0,0 +1,296 @@
+//! Layout of shareable "lyric card" images.
+//!
+//! A card features one lyric line with the track title, artist, and optional album art,
+//! laid out as an SVG document. Rasterizing and saving the card is left to the UI, which
+//! also supplies the theme colors.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Card width in pixels (matches common social link preview size)
+pub const CARD_WIDTH: u32 = 1200;
+/// Card height in pixels
+pub const CARD_HEIGHT: u32 = 630;
+
+/// Outer margin around the card content
+const MARGIN: u32 = 60;
+/// Album art edge length
+const ART_SIZE: u32 = 300;
+/// Lyric font size for lines that fit in two rows
+const LARGE_FONT_SIZE: u32 = 56;
+/// Lyric font size for lines that need more rows
+const SMALL_FONT_SIZE: u32 = 40;
+/// Maximum rows of lyric text; anything beyond is cut with an ellipsis
+const MAX_ROWS: usize = 4;
+/// Average glyph width as a fraction of the font size, used to estimate row capacity
+const GLYPH_WIDTH_RATIO: f32 = 0.55;
+
+/// Theme values used to style the card
+#[derive(Debug, Clone, Deserialize)]
+pub struct CardTheme {
+    pub background: String,
+    pub text_color: String,
+    pub secondary_color: String,
+    pub font_family: String,
+}
+
+impl Default for CardTheme {
+    fn default() -> Self {
+        Self {
+            background: "rgba(0, 0, 0, 0.85)".into(),
+            text_color: "#00FF00".into(),
+            secondary_color: "rgba(255, 255, 255, 0.6)".into(),
+            font_family: "sans-serif".into(),
+        }
+    }
+}
+
+/// Encoded album art image
+#[derive(Debug, Clone)]
+pub struct AlbumArt {
+    /// MIME type such as `image/jpeg`
+    pub mime: String,
+    /// Encoded image bytes
+    pub data: Vec<u8>,
+}
+
+impl AlbumArt {
+    /// The image as a `data:` URI for embedding in SVG
+    #[must_use]
+    pub fn data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.mime, BASE64.encode(&self.data))
+    }
+}
+
+/// Content of a lyric card
+#[derive(Debug, Clone)]
+pub struct LyricCard {
+    /// The lyric line to feature
+    pub line: String,
+    /// Track title
+    pub title: String,
+    /// Track artist(s)
+    pub artist: String,
+    /// Album art shown beside the lyric, if available
+    pub album_art: Option<AlbumArt>,
+}
+
+impl LyricCard {
+    /// Lay out the card as an SVG document. Long lines are wrapped over several rows.
+    #[must_use]
+    pub fn to_svg(&self, theme: &CardTheme) -> String {
+        let text_left = if self.album_art.is_some() {
+            MARGIN * 2 + ART_SIZE
+        } else {
+            MARGIN
+        };
+        let text_width = CARD_WIDTH - MARGIN - text_left;
+        let center_x = text_left + text_width / 2;
+
+        let mut font_size = LARGE_FONT_SIZE;
+        let mut rows = wrap_text(&self.line, row_capacity(text_width, font_size), MAX_ROWS);
+        if rows.len() > 2 {
+            font_size = SMALL_FONT_SIZE;
+            rows = wrap_text(&self.line, row_capacity(text_width, font_size), MAX_ROWS);
+        }
+        let row_height = font_size * 5 / 4;
+        let first_row_y = CARD_HEIGHT / 2 - row_height * (row_count(&rows) - 1) / 2;
+        let footer_y = CARD_HEIGHT - 70;
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{CARD_WIDTH}" height="{CARD_HEIGHT}">"#
+        );
+        let _ = write!(
+            svg,
+            r#"<rect width="100%" height="100%" rx="32" fill="{}"/>"#,
+            escape_xml(&theme.background)
+        );
+        if let Some(art) = &self.album_art {
+            let art_y = (CARD_HEIGHT - ART_SIZE) / 2;
+            let _ = write!(
+                svg,
+                r#"<image x="{MARGIN}" y="{art_y}" width="{ART_SIZE}" height="{ART_SIZE}" preserveAspectRatio="xMidYMid slice" href="{}"/>"#,
+                art.data_uri()
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text text-anchor="middle" dominant-baseline="middle" font-family="{}" font-size="{font_size}" font-weight="bold" fill="{}">"#,
+            escape_xml(&theme.font_family),
+            escape_xml(&theme.text_color),
+        );
+        for (i, row) in (0u32..).zip(&rows) {
+            let _ = write!(
+                svg,
+                r#"<tspan x="{center_x}" y="{}">{}</tspan>"#,
+                first_row_y + row_height * i,
+                escape_xml(row)
+            );
+        }
+        svg.push_str("</text>");
+        let _ = write!(
+            svg,
+            r#"<text x="{center_x}" y="{footer_y}" text-anchor="middle" font-family="{}" font-size="28" fill="{}">{} — {}</text>"#,
+            escape_xml(&theme.font_family),
+            escape_xml(&theme.secondary_color),
+            escape_xml(&self.title),
+            escape_xml(&self.artist)
+        );
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+fn row_count(rows: &[String]) -> u32 {
+    u32::try_from(rows.len()).unwrap_or(1).max(1)
+}
+
+/// Approximate number of narrow characters that fit in `width` pixels
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn row_capacity(width: u32, font_size: u32) -> usize {
+    (width as f32 / (font_size as f32 * GLYPH_WIDTH_RATIO)) as usize
+}
+
+/// Display width of a character in narrow-character units. CJK and other wide scripts
+/// take about twice the width of Latin letters.
+fn char_width(c: char) -> usize {
+    if c >= '\u{2E80}' { 2 } else { 1 }
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Greedily wrap text into rows of at most `capacity` width units, breaking at spaces
+/// and inside words (or unspaced scripts) that are too long for a row. Rows past
+/// `max_rows` are dropped and the last kept row ends with an ellipsis.
+#[must_use]
+pub fn wrap_text(text: &str, capacity: usize, max_rows: usize) -> Vec<String> {
+    let capacity = capacity.max(1);
+    let mut rows: Vec<String> = Vec::new();
+    let mut row = String::new();
+    for word in text.split_whitespace() {
+        let separator = usize::from(!row.is_empty());
+        if text_width(&row) + separator + text_width(word) <= capacity {
+            if separator == 1 {
+                row.push(' ');
+            }
+            row.push_str(word);
+            continue;
+        }
+        if !row.is_empty() {
+            rows.push(std::mem::take(&mut row));
+        }
+        for c in word.chars() {
+            if text_width(&row) + char_width(c) > capacity && !row.is_empty() {
+                rows.push(std::mem::take(&mut row));
+            }
+            row.push(c);
+        }
+    }
+    if !row.is_empty() {
+        rows.push(row);
+    }
+
+    if rows.len() > max_rows {
+        rows.truncate(max_rows);
+        if let Some(last) = rows.last_mut() {
+            while !last.is_empty() && text_width(last) + 1 > capacity {
+                last.pop();
+            }
+            last.push('…');
+        }
+    }
+    rows
+}
+
+/// Escape text for inclusion in SVG markup
+#[must_use]
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn card(line: &str) -> LyricCard {
+        LyricCard {
+            line: line.to_string(),
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            album_art: None,
+        }
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"Rock & "Roll" <'n'>"#),
+            "Rock &amp; &quot;Roll&quot; &lt;&apos;n&apos;&gt;"
+        );
+        assert_eq!(escape_xml("晴天"), "晴天");
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(wrap_text("one two three", 9, 4), vec!["one two", "three"]);
+        assert_eq!(wrap_text("abcdefgh", 3, 4), vec!["abc", "def", "gh"]);
+        // Wide characters count double
+        assert_eq!(wrap_text("我爱你我爱你", 6, 4), vec!["我爱你", "我爱你"]);
+        assert_eq!(wrap_text("a b c d e", 1, 2), vec!["a", "…"]);
+        assert!(wrap_text("   ", 10, 2).is_empty());
+    }
+
+    #[test]
+    fn test_to_svg_escapes_text() {
+        let mut card = card("Love & <war>");
+        card.title = "Q&A".to_string();
+        let svg = card.to_svg(&CardTheme::default());
+        assert!(svg.contains(">Love &amp; &lt;war&gt;</tspan>"));
+        assert!(svg.contains("Q&amp;A — Artist"));
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_to_svg_wraps_long_lines() {
+        let short = card("Hello").to_svg(&CardTheme::default());
+        assert_eq!(short.matches("<tspan").count(), 1);
+        assert!(short.contains(r#"font-size="56""#));
+
+        let long = card(&"never gonna give you up ".repeat(6)).to_svg(&CardTheme::default());
+        assert!(long.matches("<tspan").count() > 2);
+        assert!(long.contains(r#"font-size="40""#));
+    }
+
+    #[test]
+    fn test_to_svg_embeds_album_art() {
+        let mut card = card("Hello");
+        card.album_art = Some(AlbumArt {
+            mime: "image/png".to_string(),
+            data: vec![1, 2, 3],
+        });
+        let svg = card.to_svg(&CardTheme::default());
+        assert!(svg.contains(r#"href="data:image/png;base64,AQID""#));
+    }
+}
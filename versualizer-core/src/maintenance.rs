@@ -0,0 +1,183 @@
+//! Periodic upkeep of the lyrics cache database.
+//!
+//! [`CacheMaintenance`] deletes lyrics older than `[cache] lyrics_ttl_days` and expired
+//! no-lyrics entries, returns the freed space to the file system and folds the write-ahead
+//! log back into the database. It runs every `[cache] maintenance_interval_hours`, counted
+//! across restarts, and waits for playback to stop so it never competes with a song.
+
+use crate::cache::LyricsCache;
+use crate::config::CacheConfig;
+use crate::error::Result;
+use crate::sync::SyncEngine;
+use chrono::Utc;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// How often a due run checks whether playback has stopped. Also the shortest wait before
+/// a run, so maintenance never slows down startup.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_mins(5);
+
+/// What a maintenance run removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub expired_lyrics: usize,
+    pub expired_not_found: usize,
+}
+
+impl fmt::Display for MaintenanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} expired lyrics, {} expired no-lyrics entries",
+            self.expired_lyrics, self.expired_not_found
+        )
+    }
+}
+
+/// Scheduled clean-up of a lyrics cache
+#[derive(Debug, Clone)]
+pub struct CacheMaintenance {
+    interval: Duration,
+    lyrics_ttl_days: u32,
+    not_found_ttl: Duration,
+}
+
+impl CacheMaintenance {
+    /// Run every `interval`, deleting lyrics fetched more than `lyrics_ttl_days` ago
+    /// (0 = keep them) and no-lyrics entries older than `not_found_ttl`
+    #[must_use]
+    pub const fn new(interval: Duration, lyrics_ttl_days: u32, not_found_ttl: Duration) -> Self {
+        Self {
+            interval,
+            lyrics_ttl_days,
+            not_found_ttl,
+        }
+    }
+
+    /// Maintenance configured under `[cache]`, or `None` if it is turned off
+    #[must_use]
+    pub fn from_config(config: &CacheConfig) -> Option<Self> {
+        config
+            .maintenance_interval()
+            .map(|interval| Self::new(interval, config.lyrics_ttl_days, config.not_found_ttl()))
+    }
+
+    /// Clean up `cache` now and record the run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any step fails; the steps before it are kept.
+    pub async fn run_once(&self, cache: &LyricsCache) -> Result<MaintenanceReport> {
+        let expired_lyrics = if self.lyrics_ttl_days > 0 {
+            cache.cleanup(self.lyrics_ttl_days).await?
+        } else {
+            0
+        };
+        let expired_not_found = cache.prune_not_found(self.not_found_ttl).await?;
+        cache.vacuum().await?;
+        cache.checkpoint().await?;
+        cache.mark_maintenance().await?;
+        Ok(MaintenanceReport {
+            expired_lyrics,
+            expired_not_found,
+        })
+    }
+
+    /// Time until the next run is due, given when the last one finished
+    async fn until_due(&self, cache: &LyricsCache) -> Duration {
+        match cache.last_maintenance().await {
+            Ok(Some(last)) => (last + self.interval - Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+            Ok(None) => Duration::ZERO,
+            Err(e) => {
+                warn!("Failed to read when cache maintenance last ran: {}", e);
+                self.interval
+            }
+        }
+        .max(IDLE_CHECK_INTERVAL)
+    }
+
+    /// Clean up `cache` whenever a run is due and nothing is playing, until cancelled
+    pub async fn run(
+        self,
+        cache: Arc<LyricsCache>,
+        sync_engine: Arc<SyncEngine>,
+        cancel_token: CancellationToken,
+    ) {
+        info!("Cleaning up the lyrics cache every {:?}", self.interval);
+        loop {
+            let wait = self.until_due(&cache).await;
+            tokio::select! {
+                () = cancel_token.cancelled() => break,
+                () = tokio::time::sleep(wait) => {}
+            }
+            if sync_engine.is_playing().await {
+                continue;
+            }
+            match self.run_once(&cache).await {
+                Ok(report) => info!("Cleaned up the lyrics cache: {}", report),
+                Err(e) => warn!("Failed to clean up the lyrics cache: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_once() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-maintenance-test-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = LyricsCache::open(&path).await.unwrap();
+        cache.mark_not_found("spotify", "t1").await.unwrap();
+        assert_eq!(cache.last_maintenance().await.unwrap(), None);
+
+        let maintenance = CacheMaintenance::new(Duration::from_hours(1), 0, Duration::ZERO);
+        let report = maintenance.run_once(&cache).await.unwrap();
+
+        assert_eq!(report.expired_lyrics, 0);
+        assert_eq!(report.expired_not_found, 1);
+        assert!(cache.last_maintenance().await.unwrap().is_some());
+        // The next run waits for the interval, less the time since this one
+        let until_due = maintenance.until_due(&cache).await;
+        assert!(until_due > Duration::from_secs(3500));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_converts_existing_database() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-maintenance-vacuum-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        // A database from before incremental auto-vacuum
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE old (id INTEGER)")
+            .unwrap();
+
+        let cache = LyricsCache::open(&path).await.unwrap();
+        cache.vacuum().await.unwrap();
+        drop(cache);
+
+        let mode: i64 = rusqlite::Connection::open(&path)
+            .unwrap()
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, 2);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
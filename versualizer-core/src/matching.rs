@@ -0,0 +1,232 @@
+//! Ranking of provider search results against a [`LyricsQuery`].
+//!
+//! Providers that search by name get back covers, karaoke versions and other artists'
+//! songs of the same title. [`match_score`] rates a result by how close its title and artist
+//! are to the query (the better of Jaro-Winkler and normalized Levenshtein similarity) and
+//! how close its duration is, and [`rank`] orders results by it, dropping those below
+//! [`MIN_MATCH_SCORE`]. A result whose title or artist is unlike the query's scores 0
+//! however well the rest matches, so a cover of the same length is not taken for the
+//! original.
+
+use std::cmp::Ordering;
+
+use crate::provider::LyricsQuery;
+
+/// Results scoring lower are not the queried track
+pub const MIN_MATCH_SCORE: f64 = 0.6;
+
+/// Share of the score from the title, artist and duration
+const TITLE_WEIGHT: f64 = 0.45;
+const ARTIST_WEIGHT: f64 = 0.35;
+const DURATION_WEIGHT: f64 = 0.2;
+
+/// Durations this close count as the same recording
+const DURATION_EXACT_SECS: f64 = 2.0;
+/// Durations this far apart get no duration score
+const DURATION_MAX_SECS: f64 = 20.0;
+
+/// Titles and artists less similar than this are different songs or artists
+const MIN_NAME_SIMILARITY: f64 = 0.7;
+
+/// Similarity of names where one contains the other, e.g. "Song" and "Song (Live)"
+const CONTAINED_SIMILARITY: f64 = 0.9;
+
+/// What a provider knows about one search result
+#[derive(Debug, Clone, Copy)]
+pub struct MatchCandidate<'a> {
+    pub title: &'a str,
+    pub artist: &'a str,
+    pub duration_secs: Option<f64>,
+}
+
+/// How well `candidate` matches `query`, from 0 (nothing alike) to 1 (same title, artist and
+/// duration). Names are compared with both the query's original and cleaned-up names.
+#[must_use]
+pub fn match_score(query: &LyricsQuery, candidate: &MatchCandidate<'_>) -> f64 {
+    let title = similarity(&query.track_name, candidate.title)
+        .max(similarity(query.search_track_name(), candidate.title));
+    let artist = similarity(&query.artist_name, candidate.artist)
+        .max(similarity(query.search_artist_name(), candidate.artist));
+    if title < MIN_NAME_SIMILARITY || artist < MIN_NAME_SIMILARITY {
+        return 0.0;
+    }
+    let duration = duration_similarity(query.duration_secs, candidate.duration_secs);
+    title * TITLE_WEIGHT + artist * ARTIST_WEIGHT + duration * DURATION_WEIGHT
+}
+
+/// `items` with their [`match_score`], best first, without those below [`MIN_MATCH_SCORE`]
+pub fn rank<T>(
+    query: &LyricsQuery,
+    items: impl IntoIterator<Item = T>,
+    candidate: impl Fn(&T) -> MatchCandidate<'_>,
+) -> Vec<(T, f64)> {
+    let mut ranked: Vec<(T, f64)> = items
+        .into_iter()
+        .map(|item| {
+            let score = match_score(query, &candidate(&item));
+            (item, score)
+        })
+        .filter(|(_, score)| *score >= MIN_MATCH_SCORE)
+        .collect();
+    ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    ranked
+}
+
+/// Similarity of two names from 0 to 1, ignoring case, punctuation and spacing
+#[must_use]
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    let contained = if a.contains(&b) || b.contains(&a) {
+        CONTAINED_SIMILARITY
+    } else {
+        0.0
+    };
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    jaro_winkler(&a, &b)
+        .max(levenshtein_similarity(&a, &b))
+        .max(contained)
+}
+
+/// Lowercase words of alphanumerics only, separated by single spaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 1 within [`DURATION_EXACT_SECS`], falling to 0 at [`DURATION_MAX_SECS`]; 0.5 when either
+/// duration is unknown
+fn duration_similarity(expected: Option<u32>, actual: Option<f64>) -> f64 {
+    let (Some(expected), Some(actual)) = (expected, actual) else {
+        return 0.5;
+    };
+    let diff = (actual - f64::from(expected)).abs();
+    if diff <= DURATION_EXACT_SECS {
+        1.0
+    } else {
+        (1.0 - (diff - DURATION_EXACT_SECS) / (DURATION_MAX_SECS - DURATION_EXACT_SECS)).max(0.0)
+    }
+}
+
+/// Levenshtein distance relative to the longer string, as a similarity
+#[allow(clippy::cast_precision_loss)]
+fn levenshtein_similarity(a: &[char], b: &[char]) -> f64 {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    1.0 - row[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+/// Jaro similarity with the Winkler bonus for a common prefix of up to 4 characters
+#[allow(clippy::cast_precision_loss)]
+fn jaro_winkler(a: &[char], b: &[char]) -> f64 {
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut taken = vec![false; b.len()];
+    let mut common = Vec::new();
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if let Some(j) = (start..end).find(|&j| !taken[j] && b[j] == *ca) {
+            taken[j] = true;
+            common.push(*ca);
+        }
+    }
+    if common.is_empty() {
+        return 0.0;
+    }
+    let common_in_b = b
+        .iter()
+        .zip(&taken)
+        .filter(|(_, matched)| **matched)
+        .map(|(c, _)| *c);
+    let transpositions = common
+        .iter()
+        .zip(common_in_b)
+        .filter(|(x, y)| **x != *y)
+        .count()
+        / 2;
+    let m = common.len() as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn candidate<'a>(title: &'a str, artist: &'a str, duration: f64) -> MatchCandidate<'a> {
+        MatchCandidate {
+            title,
+            artist,
+            duration_secs: Some(duration),
+        }
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert!((similarity("Don't Stop", "dont stop!") - 1.0).abs() < f64::EPSILON);
+        assert!(similarity("Yesterday", "Yesterdy") > 0.9);
+        assert!(similarity("Song", "Song (Live)") >= CONTAINED_SIMILARITY);
+        assert!(similarity("Yesterday", "Tomorrow") < 0.6);
+        assert!(similarity("", "Song").abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_jaro_winkler_reference_value() {
+        let a: Vec<char> = "martha".chars().collect();
+        let b: Vec<char> = "marhta".chars().collect();
+        assert!((jaro_winkler(&a, &b) - 0.961).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_rank_prefers_queried_artist_over_covers() {
+        let query = LyricsQuery::new("Hallelujah", "Leonard Cohen").with_duration(278);
+        let results = vec![
+            candidate("Hallelujah", "Jeff Buckley", 413.0),
+            candidate("Hallelujah", "Leonard Cohen", 279.0),
+            candidate("Hallelujah (Karaoke Version)", "Sing Along Band", 278.0),
+        ];
+        let ranked = rank(&query, results, |c| *c);
+        assert_eq!(ranked[0].0.artist, "Leonard Cohen");
+        assert!(ranked.iter().all(|(c, _)| c.artist != "Jeff Buckley"));
+    }
+
+    #[test]
+    fn test_rank_drops_other_songs() {
+        let query = LyricsQuery::new("Yesterday", "The Beatles");
+        let ranked = rank(
+            &query,
+            vec![candidate("Let It Be", "The Beatles", 243.0)],
+            |c| *c,
+        );
+        assert!(ranked.is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Write-through export of fetched lyrics to a folder of `.lrc` files.
+//!
+//! When enabled, every synced lyric fetched from a provider is also saved as a standard
+//! `.lrc` file named `{artist} - {title}.lrc`, so a portable lyrics library builds up
+//! as a side effect of listening.
+
+use crate::error::Result;
+use crate::lrc::{LrcFile, LrcMetadata};
+use crate::paths::sanitize_file_name;
+use crate::playback::TrackInfo;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Writes fetched lyrics into a mirror directory
+#[derive(Debug, Clone)]
+pub struct LyricsMirror {
+    dir: PathBuf,
+}
+
+impl LyricsMirror {
+    /// Mirror lyrics into `dir`, which is created on first write
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The mirror directory
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path the lyrics for `track` are written to
+    #[must_use]
+    pub fn path_for(&self, track: &TrackInfo) -> PathBuf {
+        let name = format!("{} - {}", track.artist, track.name);
+        self.dir.join(format!("{}.lrc", sanitize_file_name(&name)))
+    }
+
+    /// Write `lrc` for `track`, tagged with the track's metadata.
+    /// Existing files are left alone so hand-edited lyrics are never overwritten.
+    /// Returns the written path, or `None` if a file already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or file cannot be written.
+    pub async fn write(&self, track: &TrackInfo, lrc: &LrcFile) -> Result<Option<PathBuf>> {
+        let path = self.path_for(track);
+        if tokio::fs::try_exists(&path).await? {
+            debug!("Mirror file already exists, skipping: {:?}", path);
+            return Ok(None);
+        }
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(&path, tagged_lrc(track, lrc)).await?;
+        Ok(Some(path))
+    }
+}
+
+/// Serialize `lrc` with ID tags taken from the track
+fn tagged_lrc(track: &TrackInfo, lrc: &LrcFile) -> String {
+    let tagged = LrcFile {
+        metadata: LrcMetadata {
+            title: Some(track.name.clone()),
+            artist: Some(track.artist.clone()),
+            album: (!track.album.is_empty()).then(|| track.album.clone()),
+            author: lrc.metadata.author.clone(),
+            length: Some(track.duration),
+            offset: 0,
+        },
+        lines: lrc.lines.clone(),
+    };
+    tagged.to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+    use std::time::Duration;
+
+    fn track() -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            "track123",
+            "What/Why?",
+            "AC:DC",
+            "Album",
+            Duration::from_secs(200),
+        )
+    }
+
+    #[test]
+    fn test_path_for_sanitizes_name() {
+        let mirror = LyricsMirror::new("/lyrics");
+        assert_eq!(
+            mirror.path_for(&track()),
+            PathBuf::from("/lyrics/AC_DC - What_Why_.lrc")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_tags_and_keeps_existing() {
+        let dir = std::env::temp_dir().join(format!("versualizer-mirror-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mirror = LyricsMirror::new(&dir);
+        let lrc = LrcFile::parse("[offset:500]\n[00:01.00]Hello").unwrap();
+
+        let path = mirror.write(&track(), &lrc).await.unwrap().unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[ti:What/Why?]"));
+        assert!(content.contains("[ar:AC:DC]"));
+        assert!(!content.contains("[offset:"));
+        assert!(content.contains("[00:01.50]Hello"));
+
+        assert!(mirror.write(&track(), &lrc).await.unwrap().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
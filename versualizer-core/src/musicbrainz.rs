@@ -0,0 +1,229 @@
+//! Canonical track names from `MusicBrainz`, for searching lyrics with tidy metadata.
+//!
+//! Music sources report compilations as "Various Artists", misspell names or pile
+//! qualifiers onto titles. [`MusicBrainzEnricher`] looks the playing track up by ISRC when
+//! there is one, otherwise by title, artist and duration, and hands the recording's title,
+//! credited artists and ID to the fetcher (`[lyrics.query] musicbrainz = true`). Requests are
+//! spaced at least a second apart, as `MusicBrainz` asks of clients.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::config::RetryConfig;
+use crate::error::Result;
+use crate::http::{self, HttpLimits};
+use crate::normalize::{CanonicalTrack, QueryEnricher};
+use crate::provider::LyricsQuery;
+
+/// `MusicBrainz` web service base URL
+pub const MUSICBRAINZ_API_URL: &str = "https://musicbrainz.org/ws/2";
+
+/// Shortest time between two requests
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// Lowest search score (out of 100) accepted as the queried recording
+const MIN_SEARCH_SCORE: u32 = 90;
+/// Recordings whose length differs more than this are other versions
+const DURATION_TOLERANCE_SECS: u64 = 3;
+/// Artist names that say nothing about who performs a track
+const PLACEHOLDER_ARTISTS: [&str; 3] = ["various artists", "various", "unknown artist"];
+
+/// List of recordings, from both the ISRC lookup and the recording search
+#[derive(Debug, Deserialize)]
+struct Recordings {
+    #[serde(default)]
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    id: String,
+    title: String,
+    /// Search relevance, missing in ISRC lookups
+    score: Option<u32>,
+    /// Length in milliseconds
+    length: Option<u64>,
+    #[serde(rename = "artist-credit", default)]
+    artist_credit: Vec<ArtistCredit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtistCredit {
+    name: String,
+    #[serde(default)]
+    joinphrase: String,
+}
+
+impl Recording {
+    /// Credited artists as `MusicBrainz` writes them, e.g. "Artist feat. Other"
+    fn artist(&self) -> String {
+        self.artist_credit
+            .iter()
+            .flat_map(|credit| [credit.name.as_str(), credit.joinphrase.as_str()])
+            .collect()
+    }
+
+    /// Whether the recording is as long as `duration_secs`, when both are known
+    fn has_duration(&self, duration_secs: Option<u32>) -> bool {
+        match (self.length, duration_secs) {
+            (Some(length), Some(duration)) => {
+                (length / 1000).abs_diff(u64::from(duration)) <= DURATION_TOLERANCE_SECS
+            }
+            _ => true,
+        }
+    }
+}
+
+/// The first recording that is confidently the queried one
+fn pick(recordings: Vec<Recording>, duration_secs: Option<u32>) -> Option<CanonicalTrack> {
+    recordings
+        .into_iter()
+        .filter(|r| r.score.is_none_or(|score| score >= MIN_SEARCH_SCORE))
+        .find(|r| r.has_duration(duration_secs))
+        .map(|r| CanonicalTrack {
+            artist: r.artist(),
+            id: r.id,
+            title: r.title,
+        })
+}
+
+/// Lucene query for a recording search. A placeholder artist is left out, so the search
+/// goes by title and length alone.
+fn search_query(query: &LyricsQuery) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace(['\\', '"'], ""));
+    let mut terms = vec![format!("recording:{}", quote(query.search_track_name()))];
+    let artist = query.search_artist_name();
+    if !PLACEHOLDER_ARTISTS.contains(&artist.to_lowercase().as_str()) {
+        terms.push(format!("artist:{}", quote(artist)));
+    }
+    if let Some(duration) = query.duration_secs {
+        let tolerance = DURATION_TOLERANCE_SECS * 1000;
+        let ms = u64::from(duration) * 1000;
+        terms.push(format!(
+            "dur:[{} TO {}]",
+            ms.saturating_sub(tolerance),
+            ms + tolerance
+        ));
+    }
+    terms.join(" AND ")
+}
+
+/// Resolves tracks to `MusicBrainz` recordings
+pub struct MusicBrainzEnricher {
+    client: ClientWithMiddleware,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl MusicBrainzEnricher {
+    /// Create an enricher with the JSON API limits and the retries of `retry`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(retry: &RetryConfig) -> Result<Self> {
+        let base_client = http::base_client(
+            reqwest::Client::builder().user_agent(http::USER_AGENT),
+            &HttpLimits::JSON,
+        )?;
+        Ok(Self {
+            client: http::provider_client(base_client, HttpLimits::JSON, retry),
+            last_request: Mutex::new(None),
+        })
+    }
+
+    /// Recordings at `path` with query `params`, waiting out the request interval first
+    async fn recordings(&self, path: &str, params: &[(&str, &str)]) -> Result<Vec<Recording>> {
+        {
+            let mut last = self.last_request.lock().await;
+            if let Some(at) = *last {
+                tokio::time::sleep_until(at + REQUEST_INTERVAL).await;
+            }
+            *last = Some(Instant::now());
+        }
+        debug!("MusicBrainz GET: {} {:?}", path, params);
+        let response = self
+            .client
+            .get(format!("{MUSICBRAINZ_API_URL}/{path}"))
+            .query(params)
+            .query(&[("fmt", "json")])
+            .send()
+            .await?;
+        // Unknown ISRCs are a 404
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let list: Recordings = http::read_json(response.error_for_status()?).await?;
+        Ok(list.recordings)
+    }
+}
+
+#[async_trait]
+impl QueryEnricher for MusicBrainzEnricher {
+    fn name(&self) -> &'static str {
+        "musicbrainz"
+    }
+
+    async fn resolve(&self, query: &LyricsQuery) -> Result<Option<CanonicalTrack>> {
+        if let Some(ref isrc) = query.isrc
+            && isrc.chars().all(char::is_alphanumeric)
+        {
+            let recordings = self
+                .recordings(&format!("isrc/{isrc}"), &[("inc", "artist-credits")])
+                .await?;
+            if let Some(track) = pick(recordings, query.duration_secs) {
+                return Ok(Some(track));
+            }
+        }
+        let search = search_query(query);
+        let recordings = self
+            .recordings("recording", &[("query", &search), ("limit", "5")])
+            .await?;
+        Ok(pick(recordings, query.duration_secs))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const SEARCH: &str = r#"{"recordings": [
+        {"id": "a", "score": 100, "title": "Song", "length": 400000,
+         "artist-credit": [{"name": "Artist"}]},
+        {"id": "b", "score": 95, "title": "Song", "length": 241000,
+         "artist-credit": [{"name": "Artist", "joinphrase": " feat. "}, {"name": "Guest"}]},
+        {"id": "c", "score": 40, "title": "Song", "length": 240000,
+         "artist-credit": [{"name": "Other"}]}
+    ]}"#;
+
+    #[test]
+    fn test_pick_checks_score_and_duration() {
+        let list: Recordings = serde_json::from_str(SEARCH).unwrap();
+        let track = pick(list.recordings, Some(240)).unwrap();
+        assert_eq!(track.id, "b");
+        assert_eq!(track.title, "Song");
+        assert_eq!(track.artist, "Artist feat. Guest");
+
+        let list: Recordings = serde_json::from_str(SEARCH).unwrap();
+        assert!(pick(list.recordings, Some(100)).is_none());
+    }
+
+    #[test]
+    fn test_search_query_skips_placeholder_artists() {
+        let query = LyricsQuery::new("Song \"Live\"", "Various Artists").with_duration(240);
+        assert_eq!(
+            search_query(&query),
+            "recording:\"Song Live\" AND dur:[237000 TO 243000]"
+        );
+        let query = LyricsQuery::new("Song", "Artist");
+        assert_eq!(
+            search_query(&query),
+            "recording:\"Song\" AND artist:\"Artist\""
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Cleaning up track metadata before lyrics providers are searched with it.
+//!
+//! Music sources name tracks with qualifiers that lyrics databases leave out, such as
+//! "Song (Remastered 2011)", "Song - Live at Wembley" or "Artist feat. X". A
+//! [`QueryNormalizer`] strips them from the names a [`LyricsQuery`] is searched with, using
+//! built-in rules and extra patterns from `[lyrics.query]`. The original names stay in the
+//! query for display and cache lookups.
+//!
+//! A [`QueryEnricher`], such as the `MusicBrainz` lookup, can go further and resolve the track
+//! to its canonical title and artist, which are then searched with instead.
+
+use async_trait::async_trait;
+use regex::{Regex, RegexBuilder};
+use tracing::warn;
+
+use crate::config::QueryConfig;
+use crate::error::Result;
+use crate::provider::LyricsQuery;
+
+/// Words that mark a bracketed part or `- ...` suffix of a title as a release qualifier
+const QUALIFIERS: &str = r"remaster(?:ed)?|deluxe|bonus|live|version|edit|mono|stereo|explicit|clean|anniversary|expanded|demo|acoustic|remix|mix|\d{4}";
+
+/// Featured artists, e.g. `feat. X`, `ft. X` or `featuring X`
+const FEATURING: &str = r"(?:feat\.?|ft\.|featuring)\s";
+
+/// Built-in rules for titles: bracketed featured artists and qualifiers, `- Remastered`
+/// style suffixes, and a trailing `feat. X`
+fn title_rules() -> Vec<String> {
+    vec![
+        format!(r"\s*[(\[]{FEATURING}[^)\]]*[)\]]"),
+        format!(r"\s*[(\[][^)\]]*\b(?:{QUALIFIERS})\b[^)\]]*[)\]]"),
+        format!(r"\s+-\s+.*\b(?:{QUALIFIERS})\b.*$"),
+        format!(r"\s+{FEATURING}.*$"),
+    ]
+}
+
+/// Built-in rules for artists: featured artists, bracketed or not
+fn artist_rules() -> Vec<String> {
+    vec![
+        format!(r"\s*[(\[]{FEATURING}[^)\]]*[)\]]"),
+        format!(r"\s+{FEATURING}.*$"),
+    ]
+}
+
+/// Strips qualifiers and featured artists from the names lyrics are searched with
+pub struct QueryNormalizer {
+    title_rules: Vec<Regex>,
+    artist_rules: Vec<Regex>,
+}
+
+impl Default for QueryNormalizer {
+    fn default() -> Self {
+        Self::from_config(&QueryConfig::default())
+    }
+}
+
+impl QueryNormalizer {
+    /// Normalizer with the built-in rules
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Normalizer configured in `[lyrics.query]`: the built-in rules unless `normalize` is
+    /// off, then the extra patterns. Patterns that are not valid regular expressions are
+    /// skipped with a warning.
+    #[must_use]
+    pub fn from_config(config: &QueryConfig) -> Self {
+        let (mut titles, mut artists) = if config.normalize {
+            (title_rules(), artist_rules())
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        titles.extend(config.title_patterns.iter().cloned());
+        artists.extend(config.artist_patterns.iter().cloned());
+        Self {
+            title_rules: compile(&titles),
+            artist_rules: compile(&artists),
+        }
+    }
+
+    /// `title` without qualifiers and featured artists
+    #[must_use]
+    pub fn title(&self, title: &str) -> String {
+        strip(&self.title_rules, title)
+    }
+
+    /// `artist` without featured artists
+    #[must_use]
+    pub fn artist(&self, artist: &str) -> String {
+        strip(&self.artist_rules, artist)
+    }
+
+    /// `query` with the cleaned names to search with, where they differ from the originals
+    #[must_use]
+    pub fn apply(&self, query: LyricsQuery) -> LyricsQuery {
+        let track = self.title(&query.track_name);
+        let artist = self.artist(&query.artist_name);
+        query.with_search_names(track, artist)
+    }
+
+    /// `query` searched with the cleaned names of `canonical`, whose ID is added under
+    /// `enricher`
+    #[must_use]
+    pub fn apply_canonical(
+        &self,
+        query: LyricsQuery,
+        enricher: &str,
+        canonical: &CanonicalTrack,
+    ) -> LyricsQuery {
+        query
+            .with_provider_id(enricher, &canonical.id)
+            .with_search_names(self.title(&canonical.title), self.artist(&canonical.artist))
+    }
+}
+
+/// A track as a metadata database knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalTrack {
+    /// The database's ID for the recording
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+}
+
+/// Resolves a query to its canonical track, e.g. by ISRC or by title, artist and duration
+#[async_trait]
+pub trait QueryEnricher: Send + Sync {
+    /// Name of the database, also the key of its ID in the query's provider IDs
+    fn name(&self) -> &'static str;
+
+    /// The canonical track for `query`, or `None` if there is no confident match
+    async fn resolve(&self, query: &LyricsQuery) -> Result<Option<CanonicalTrack>>;
+}
+
+/// Compile `patterns` case-insensitively, skipping invalid ones
+fn compile(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .inspect_err(|e| warn!("Ignoring query pattern {:?}: {}", pattern, e))
+                .ok()
+        })
+        .collect()
+}
+
+/// Remove every match of `rules` from `text` and tidy the spacing. A name the rules would
+/// empty is kept as it is.
+fn strip(rules: &[Regex], text: &str) -> String {
+    let stripped = rules.iter().fold(text.to_string(), |text, rule| {
+        rule.replace_all(&text, "").into_owned()
+    });
+    let stripped = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+    if stripped.is_empty() {
+        text.trim().to_string()
+    } else {
+        stripped
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_title_qualifiers() {
+        let normalizer = QueryNormalizer::new();
+        for (title, expected) in [
+            ("Let It Be (Remastered 2009)", "Let It Be"),
+            ("Let It Be - Remastered 2009", "Let It Be"),
+            ("Hey Jude [2015 Mix]", "Hey Jude"),
+            ("Song (feat. Someone)", "Song"),
+            ("Song feat. Someone", "Song"),
+            ("Yellow - Live at Glastonbury", "Yellow"),
+            // Brackets and dashes that are part of the title stay
+            (
+                "(I Can't Get No) Satisfaction",
+                "(I Can't Get No) Satisfaction",
+            ),
+            ("Love - Hate", "Love - Hate"),
+            ("(Live)", "(Live)"),
+        ] {
+            assert_eq!(normalizer.title(title), expected, "{title}");
+        }
+    }
+
+    #[test]
+    fn test_strips_featured_artists() {
+        let normalizer = QueryNormalizer::new();
+        assert_eq!(normalizer.artist("Artist feat. X & Y"), "Artist");
+        assert_eq!(normalizer.artist("Artist (ft. X)"), "Artist");
+        assert_eq!(normalizer.artist("Simon & Garfunkel"), "Simon & Garfunkel");
+    }
+
+    #[test]
+    fn test_extra_patterns_and_originals_kept() {
+        let normalizer = QueryNormalizer::from_config(&QueryConfig {
+            normalize: false,
+            title_patterns: vec![r"\s*\(Taylor's Version\)".to_string(), "(".to_string()],
+            artist_patterns: Vec::new(),
+            musicbrainz: false,
+        });
+        let query = normalizer.apply(LyricsQuery::new(
+            "Love Story (Taylor's Version)",
+            "Taylor Swift feat. X",
+        ));
+        assert_eq!(query.search_track_name(), "Love Story");
+        // Built-in rules are off
+        assert_eq!(query.search_artist_name(), "Taylor Swift feat. X");
+        assert_eq!(query.track_name, "Love Story (Taylor's Version)");
+    }
+
+    #[test]
+    fn test_apply_canonical() {
+        let canonical = CanonicalTrack {
+            id: "mbid".to_string(),
+            title: "Song (Remastered)".to_string(),
+            artist: "Artist feat. Guest".to_string(),
+        };
+        let query = QueryNormalizer::new().apply_canonical(
+            LyricsQuery::new("Song", "Various Artists"),
+            "musicbrainz",
+            &canonical,
+        );
+        assert_eq!(query.search_track, None);
+        assert_eq!(query.search_artist_name(), "Artist");
+        assert_eq!(query.provider_id("musicbrainz"), Some("mbid"));
+        assert_eq!(query.artist_name, "Various Artists");
+    }
+}
@@ -0,0 +1,313 @@
+//! Plain-text "now singing" files for streaming software.
+//!
+//! [`NowSingingFiles`] keeps the current lyric line, the next line and the track in text
+//! files, so OBS text sources (or anything else that watches a file) can show them without a
+//! browser source. The files are refreshed on sync events and when the next line is due.
+//! They are only rewritten when their content changes, and each write goes to a temporary
+//! file that is renamed into place so readers never see a half-written line.
+
+use crate::config::NowSingingConfig;
+use crate::lrc::{LrcFile, LrcLine};
+use crate::playback::TrackInfo;
+use crate::sync::{SyncEngine, apply_line_lead};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Sync events this close together cause a single refresh, e.g. a track change and its lyrics
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Longest wait between refreshes, which also catches line changes while paused or without
+/// synced lyrics
+const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest wait between refreshes, so a line due right now is not checked in a busy loop
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A text file and the content last written to it
+struct TextFile {
+    path: PathBuf,
+    written: Option<String>,
+}
+
+impl TextFile {
+    const fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            written: None,
+        }
+    }
+
+    /// Write `content` unless the file already holds it
+    async fn update(&mut self, content: &str) {
+        if self.written.as_deref() == Some(content) {
+            return;
+        }
+        match write_atomically(&self.path, content).await {
+            Ok(()) => self.written = Some(content.to_string()),
+            Err(e) => warn!("Failed to write {:?}: {}", self.path, e),
+        }
+    }
+}
+
+/// What the files hold at one moment
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Contents {
+    line: String,
+    next_line: String,
+    track: String,
+}
+
+/// Writer for the configured line and track files
+pub struct NowSingingFiles {
+    line: Option<TextFile>,
+    next_line: Option<TextFile>,
+    track: Option<TextFile>,
+    track_format: String,
+}
+
+impl NowSingingFiles {
+    /// The files configured in `[now_singing]`, or `None` when all of them are disabled
+    #[must_use]
+    pub fn from_config(config: &NowSingingConfig) -> Option<Self> {
+        let line = config.line_file_path().map(TextFile::new);
+        let next_line = config.next_line_file_path().map(TextFile::new);
+        let track = config.track_file_path().map(TextFile::new);
+        if line.is_none() && next_line.is_none() && track.is_none() {
+            return None;
+        }
+        Some(Self {
+            line,
+            next_line,
+            track,
+            track_format: config.track_format.clone(),
+        })
+    }
+
+    /// Fill in `{title}`, `{artist}` and `{album}` in `format`
+    #[must_use]
+    pub fn format_track(format: &str, track: &TrackInfo) -> String {
+        format
+            .replace("{title}", &track.name)
+            .replace("{artist}", &track.artist)
+            .replace("{album}", &track.album)
+    }
+
+    /// Keep the files up to date until cancelled, then clear them
+    pub async fn run(mut self, sync_engine: Arc<SyncEngine>, cancel_token: CancellationToken) {
+        for (file, what) in [
+            (&self.line, "the current lyric line"),
+            (&self.next_line, "the next lyric line"),
+            (&self.track, "the current track"),
+        ] {
+            if let Some(file) = file {
+                info!("Writing {} to {:?}", what, file.path);
+            }
+        }
+
+        let mut events = sync_engine.subscribe();
+        let mut wait = self.refresh(&sync_engine).await;
+        loop {
+            tokio::select! {
+                () = cancel_token.cancelled() => break,
+                event = events.recv() => {
+                    if matches!(event, Err(RecvError::Closed)) {
+                        break;
+                    }
+                    tokio::time::sleep(EVENT_DEBOUNCE).await;
+                    while !matches!(
+                        events.try_recv(),
+                        Err(TryRecvError::Empty | TryRecvError::Closed)
+                    ) {}
+                    wait = self.refresh(&sync_engine).await;
+                }
+                () = tokio::time::sleep(wait) => wait = self.refresh(&sync_engine).await,
+            }
+        }
+
+        // Leave nothing stale on screen once the app has quit
+        self.write(&Contents::default()).await;
+    }
+
+    /// Write the current contents and return how long until the next line is due
+    async fn refresh(&mut self, sync_engine: &SyncEngine) -> Duration {
+        let state = sync_engine.state().await;
+        let Some(track) = state.track.as_ref() else {
+            self.write(&Contents::default()).await;
+            return MAX_REFRESH_INTERVAL;
+        };
+        // With the track's lyrics offset, like the overlay and the other frontends
+        let position = apply_line_lead(
+            sync_engine.current_position().await,
+            sync_engine.line_lead_ms(),
+        );
+        let (line, next_line, until_next) = match sync_engine.lyrics().await {
+            Some(lyrics) => lines_at(&lyrics, position),
+            None => (String::new(), String::new(), None),
+        };
+        self.write(&Contents {
+            line,
+            next_line,
+            track: Self::format_track(&self.track_format, track),
+        })
+        .await;
+
+        until_next
+            .filter(|_| state.is_playing)
+            .map_or(MAX_REFRESH_INTERVAL, |until| {
+                until.clamp(MIN_REFRESH_INTERVAL, MAX_REFRESH_INTERVAL)
+            })
+    }
+
+    async fn write(&mut self, contents: &Contents) {
+        for (file, content) in [
+            (&mut self.line, &contents.line),
+            (&mut self.next_line, &contents.next_line),
+            (&mut self.track, &contents.track),
+        ] {
+            if let Some(file) = file {
+                file.update(content).await;
+            }
+        }
+    }
+}
+
+/// The current and next line at `position`, and how long until the next line starts
+fn lines_at(lyrics: &LrcFile, position: Duration) -> (String, String, Option<Duration>) {
+    let text = |line: Option<&LrcLine>| line.map(|line| line.text.clone()).unwrap_or_default();
+    (
+        text(lyrics.current_line(position)),
+        text(lyrics.next_line(position)),
+        lyrics.time_until_next(position),
+    )
+}
+
+/// Replace `path` with `content` via a temporary file in the same directory
+async fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    tokio::fs::write(&tmp, content).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+
+    #[test]
+    fn test_format_track() {
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "id",
+            "Song",
+            "Artist",
+            "Album",
+            Duration::from_mins(3),
+        );
+        assert_eq!(
+            NowSingingFiles::format_track("{artist} - {title} ({album})", &track),
+            "Artist - Song (Album)"
+        );
+    }
+
+    #[test]
+    fn test_lines_at() {
+        let lyrics = LrcFile::parse("[00:01.00]One\n[00:03.00]Two").unwrap();
+        assert_eq!(
+            lines_at(&lyrics, Duration::from_millis(500)),
+            (
+                String::new(),
+                "One".to_string(),
+                Some(Duration::from_millis(500))
+            )
+        );
+        assert_eq!(
+            lines_at(&lyrics, Duration::from_secs(2)),
+            (
+                "One".to_string(),
+                "Two".to_string(),
+                Some(Duration::from_secs(1))
+            )
+        );
+        assert_eq!(
+            lines_at(&lyrics, Duration::from_secs(4)),
+            ("Two".to_string(), String::new(), None)
+        );
+    }
+
+    #[test]
+    fn test_from_config_disabled_when_no_files() {
+        assert!(NowSingingFiles::from_config(&NowSingingConfig::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_text_file_writes_only_changes() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-now-singing-{}.txt",
+            std::process::id()
+        ));
+        let mut file = TextFile::new(path.clone());
+        file.update("first line").await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first line");
+
+        // An unchanged line is not rewritten
+        std::fs::write(&path, "edited").unwrap();
+        file.update("first line").await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "edited");
+
+        file.update("").await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_applies_track_offset() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-now-singing-offset-{}.txt",
+            std::process::id()
+        ));
+        let mut files = NowSingingFiles::from_config(&NowSingingConfig {
+            line_file: path.to_string_lossy().into_owned(),
+            ..NowSingingConfig::default()
+        })
+        .unwrap();
+        let engine = SyncEngine::new();
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "id",
+            "Song",
+            "Artist",
+            "Album",
+            Duration::from_mins(3),
+        );
+        engine
+            .update_state(crate::playback::PlaybackState::new(
+                false,
+                Some(track),
+                Duration::from_millis(1500),
+                Duration::from_mins(3),
+            ))
+            .await;
+        engine
+            .set_lyrics(LrcFile::parse("[00:01.00]One\n[00:03.00]Two").unwrap())
+            .await;
+
+        files.refresh(&engine).await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "One");
+        engine.set_track_offset_ms(2000).await;
+        files.refresh(&engine).await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Two");
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+//! User-supplied `.lrc` files that take precedence over fetched lyrics.
+//!
+//! A file in the overrides folder named after the track's source ID (`{id}.lrc`) or as
+//! `{artist} - {title}.lrc` (the name the lyrics mirror uses) is shown instead of any cached
+//! or fetched lyrics. The ID name wins when both exist. Editing the file in an external LRC
+//! editor reloads it while the track plays, for quick timing fixes.
+
+use crate::error::Result;
+use crate::lrc::LrcFile;
+use crate::paths::sanitize_file_name;
+use crate::playback::TrackInfo;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Folder of override `.lrc` files
+#[derive(Debug, Clone)]
+pub struct LyricsOverrides {
+    dir: PathBuf,
+}
+
+impl LyricsOverrides {
+    /// Look for overrides in `dir`
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The overrides folder
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Candidate override paths for `track`, in order of precedence
+    #[must_use]
+    pub fn paths_for(&self, track: &TrackInfo) -> [PathBuf; 2] {
+        let name = format!("{} - {}", track.artist, track.name);
+        [
+            self.dir.join(format!(
+                "{}.lrc",
+                sanitize_file_name(&track.source_track_id)
+            )),
+            self.dir.join(format!("{}.lrc", sanitize_file_name(&name))),
+        ]
+    }
+
+    /// Whether `path` is one of the override files for `track`
+    #[must_use]
+    pub fn is_override_for(&self, path: &Path, track: &TrackInfo) -> bool {
+        self.paths_for(track)
+            .iter()
+            .any(|candidate| candidate == path)
+    }
+
+    /// The override lyrics for `track`, if a file exists and parses with timed lines.
+    /// Files that fail to parse are logged and skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an existing file cannot be read.
+    pub async fn load(&self, track: &TrackInfo) -> Result<Option<LrcFile>> {
+        for path in self.paths_for(track) {
+            if !tokio::fs::try_exists(&path).await? {
+                continue;
+            }
+            let content = tokio::fs::read_to_string(&path).await?;
+            match LrcFile::parse(&content) {
+                Ok(lrc) if !lrc.lines.is_empty() => {
+                    debug!("Using override lyrics from {:?}", path);
+                    return Ok(Some(lrc));
+                }
+                Ok(_) => warn!("Override file {:?} has no timed lines", path),
+                Err(e) => warn!("Failed to parse override file {:?}: {}", path, e),
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+    use std::time::Duration;
+
+    fn track() -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            "track123",
+            "What? Song",
+            "Artist",
+            "Album",
+            Duration::from_mins(3),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_load_prefers_track_id() {
+        let dir =
+            std::env::temp_dir().join(format!("versualizer-overrides-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let overrides = LyricsOverrides::new(&dir);
+        let [by_id, by_name] = overrides.paths_for(&track());
+        assert_eq!(by_name.file_name().unwrap(), "Artist - What_ Song.lrc");
+
+        assert!(overrides.load(&track()).await.unwrap().is_none());
+
+        std::fs::write(&by_name, "[00:01.00]By name").unwrap();
+        let lrc = overrides.load(&track()).await.unwrap().unwrap();
+        assert_eq!(lrc.lines[0].text, "By name");
+
+        std::fs::write(&by_id, "[00:01.00]By id").unwrap();
+        let lrc = overrides.load(&track()).await.unwrap().unwrap();
+        assert_eq!(lrc.lines[0].text, "By id");
+        assert!(overrides.is_override_for(&by_id, &track()));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,6 +1,9 @@
 //! Path constants for configuration and cache files.
 
-use std::path::PathBuf;
+use crate::playback::TrackInfo;
+use crate::source::MusicSource;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 /// The name of the configuration directory under ~/.config/
 pub const CONFIG_DIR_NAME: &str = "versualizer";
@@ -8,18 +11,51 @@ pub const CONFIG_DIR_NAME: &str = "versualizer";
 /// The name of the main configuration file
 pub const CONFIG_FILE_NAME: &str = "config.toml";
 
+/// The name of the directory of named config files, chosen with `--profile <name>`
+pub const PROFILES_DIR_NAME: &str = "profiles";
+
 /// The name of the lyrics cache database file
 pub const LYRICS_CACHE_DB_FILE_NAME: &str = "lyrics_cache.db";
 
+/// The name of the secrets file (prefixed with . for hidden)
+pub const SECRETS_FILE_NAME: &str = ".secrets.json";
+
+/// The name of the event log database file
+pub const EVENT_LOG_DB_FILE_NAME: &str = "event_log.db";
+
+/// The name of the file the last session's statistics are written to
+pub const SESSION_SUMMARY_FILE_NAME: &str = "last_session.txt";
+
 /// The name of the window state cache file (prefixed with . for hidden)
 pub const WINDOW_STATE_FILE_NAME: &str = ".window_state.json";
 
 /// The name of the theme CSS file
 pub const THEME_FILE_NAME: &str = "theme.css";
 
+/// The name of the directory of named themes, chosen with `ui.theme`
+pub const THEMES_DIR_NAME: &str = "themes";
+
 /// The name of the log file
 pub const LOG_FILE_NAME: &str = "versualizer.log";
 
+/// The name of the directory that session traces are recorded into
+pub const TRACES_DIR_NAME: &str = "traces";
+
+/// The name of the directory of user-supplied `.lrc` files that override fetched lyrics
+pub const OVERRIDES_DIR_NAME: &str = "overrides";
+
+/// The name of the directory that downloaded album art is cached in
+pub const ARTWORK_DIR_NAME: &str = "artwork";
+
+/// The name of the directory that lyrics cache backups are written into
+pub const BACKUPS_DIR_NAME: &str = "backups";
+
+/// The name of the directory that exported lyric cards are saved into
+pub const LYRIC_CARDS_DIR_NAME: &str = "Versualizer";
+
+/// The name of the directory that exported `.lrc` files are saved into
+pub const LYRICS_EXPORTS_DIR_NAME: &str = "Versualizer";
+
 /// Get the configuration directory path (~/.config/versualizer/)
 #[must_use]
 pub fn config_dir() -> PathBuf {
@@ -29,10 +65,33 @@ pub fn config_dir() -> PathBuf {
         .join(CONFIG_DIR_NAME)
 }
 
-/// Get the config file path (~/.config/versualizer/config.toml)
+/// Config file chosen at startup in place of `config.toml`
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Get the config file path: the one chosen with [`set_config_path`], otherwise
+/// `~/.config/versualizer/config.toml`
 #[must_use]
 pub fn config_path() -> PathBuf {
-    config_dir().join(CONFIG_FILE_NAME)
+    CONFIG_PATH
+        .get()
+        .cloned()
+        .unwrap_or_else(|| config_dir().join(CONFIG_FILE_NAME))
+}
+
+/// Use `path` as the config file for the rest of the process, as chosen with `--config` or
+/// `--profile`. Other files stay in the config directory, so profiles share the cache,
+/// theme and sign-ins. Returns `false` if a config file was already chosen.
+pub fn set_config_path(path: impl Into<PathBuf>) -> bool {
+    CONFIG_PATH.set(path.into()).is_ok()
+}
+
+/// Get the config file of a named profile (`~/.config/versualizer/profiles/<name>.toml`),
+/// with the name sanitized like cache profiles
+#[must_use]
+pub fn profile_config_path(profile: &str) -> PathBuf {
+    config_dir()
+        .join(PROFILES_DIR_NAME)
+        .join(format!("{}.toml", sanitize_profile(profile)))
 }
 
 /// Get the lyrics cache database path (`~/.config/versualizer/lyrics_cache.db`)
@@ -41,18 +100,129 @@ pub fn lyrics_cache_db_path() -> PathBuf {
     config_dir().join(LYRICS_CACHE_DB_FILE_NAME)
 }
 
+/// Get the lyrics cache database path for a profile and optional music source.
+///
+/// An empty profile without a source is the default `lyrics_cache.db`; otherwise the
+/// parts are appended with a `-p-` or `-s-` tag, e.g. `lyrics_cache-p-work-s-spotify.db`.
+/// Profile names are lowercased and anything other than letters and digits becomes `_`,
+/// so a profile can never contain a tag and two selections never share a file.
+#[must_use]
+pub fn lyrics_cache_db_path_for(profile: &str, source: Option<MusicSource>) -> PathBuf {
+    let mut stem = LYRICS_CACHE_DB_FILE_NAME
+        .trim_end_matches(".db")
+        .to_string();
+
+    let profile = sanitize_profile(profile);
+    if !profile.is_empty() {
+        stem.push_str("-p-");
+        stem.push_str(&profile);
+    }
+    if let Some(source) = source {
+        stem.push_str("-s-");
+        stem.push_str(source.as_str());
+    }
+
+    config_dir().join(format!("{stem}.db"))
+}
+
+/// `profile` lowercased, with anything other than letters and digits replaced by `_`
+fn sanitize_profile(profile: &str) -> String {
+    profile
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// All lyrics cache databases present in the config directory (every profile and source)
+#[must_use]
+pub fn lyrics_cache_db_paths() -> Vec<PathBuf> {
+    let mut paths = vec![lyrics_cache_db_path()];
+    let stem = LYRICS_CACHE_DB_FILE_NAME.trim_end_matches(".db");
+    if let Ok(entries) = std::fs::read_dir(config_dir()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&format!("{stem}-")) && name.ends_with(".db") {
+                paths.push(entry.path());
+            }
+        }
+    }
+    paths
+}
+
 /// Get the window state file path (`~/.config/versualizer/.window_state.json`)
 #[must_use]
 pub fn window_state_path() -> PathBuf {
     config_dir().join(WINDOW_STATE_FILE_NAME)
 }
 
+/// Get the secrets file path (`~/.config/versualizer/.secrets.json`)
+#[must_use]
+pub fn secrets_path() -> PathBuf {
+    config_dir().join(SECRETS_FILE_NAME)
+}
+
 /// Get the theme CSS file path (`~/.config/versualizer/theme.css`)
 #[must_use]
 pub fn theme_path() -> PathBuf {
     config_dir().join(THEME_FILE_NAME)
 }
 
+/// Get the named themes directory path (~/.config/versualizer/themes/)
+#[must_use]
+pub fn themes_dir() -> PathBuf {
+    config_dir().join(THEMES_DIR_NAME)
+}
+
+/// Get the CSS file of the theme called `name` (`themes/{name}.css`), or [`theme_path`]
+/// when `name` is empty
+#[must_use]
+pub fn theme_path_named(name: &str) -> PathBuf {
+    if name.is_empty() {
+        theme_path()
+    } else {
+        themes_dir().join(format!("{}.css", sanitize_file_name(name)))
+    }
+}
+
+/// Names of the themes in the themes directory, sorted
+#[must_use]
+pub fn theme_names() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(themes_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let is_css = path.extension().is_some_and(|extension| extension == "css");
+            is_css
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Get the lyrics overrides directory path (~/.config/versualizer/overrides/)
+#[must_use]
+pub fn overrides_dir() -> PathBuf {
+    config_dir().join(OVERRIDES_DIR_NAME)
+}
+
+/// Get the lyrics cache backups directory path (~/.config/versualizer/backups/)
+#[must_use]
+pub fn backups_dir() -> PathBuf {
+    config_dir().join(BACKUPS_DIR_NAME)
+}
+
 /// Get the cache directory path using `dirs::cache_dir()`
 ///
 /// Returns `{cache_dir}/versualizer/` where `cache_dir` is:
@@ -66,8 +236,256 @@ pub fn cache_dir() -> PathBuf {
         .join(CONFIG_DIR_NAME)
 }
 
+/// Get the album art cache directory path (`{cache_dir}/versualizer/artwork/`)
+#[must_use]
+pub fn artwork_dir() -> PathBuf {
+    cache_dir().join(ARTWORK_DIR_NAME)
+}
+
 /// Get the log file path (`{cache_dir}/versualizer/versualizer.log`)
 #[must_use]
 pub fn log_file_path() -> PathBuf {
     cache_dir().join(LOG_FILE_NAME)
 }
+
+/// Get the event log database path (`{cache_dir}/versualizer/event_log.db`)
+#[must_use]
+pub fn event_log_db_path() -> PathBuf {
+    cache_dir().join(EVENT_LOG_DB_FILE_NAME)
+}
+
+/// Get the last session summary path (`{cache_dir}/versualizer/last_session.txt`)
+#[must_use]
+pub fn session_summary_path() -> PathBuf {
+    cache_dir().join(SESSION_SUMMARY_FILE_NAME)
+}
+
+/// Get the session trace directory path (`{cache_dir}/versualizer/traces/`)
+#[must_use]
+pub fn traces_dir() -> PathBuf {
+    cache_dir().join(TRACES_DIR_NAME)
+}
+
+/// Get the lyric card export directory path
+///
+/// Returns `{picture_dir}/Versualizer/`, falling back to `{cache_dir}/versualizer/`
+/// when the platform has no pictures directory.
+#[must_use]
+pub fn lyric_cards_dir() -> PathBuf {
+    dirs::picture_dir().map_or_else(cache_dir, |dir| dir.join(LYRIC_CARDS_DIR_NAME))
+}
+
+/// Get the lyrics export directory path
+///
+/// Returns `{audio_dir}/Versualizer/`, falling back to `{cache_dir}/versualizer/`
+/// when the platform has no music directory.
+#[must_use]
+pub fn lyrics_exports_dir() -> PathBuf {
+    dirs::audio_dir().map_or_else(cache_dir, |dir| dir.join(LYRICS_EXPORTS_DIR_NAME))
+}
+
+/// Path the lyrics of `track` are exported to (`{lyrics_exports_dir}/{artist} - {title}.lrc`)
+#[must_use]
+pub fn lyrics_export_path(track: &TrackInfo) -> PathBuf {
+    let name = format!("{} - {}", track.artist, track.name);
+    lyrics_exports_dir().join(format!("{}.lrc", sanitize_file_name(&name)))
+}
+
+/// Replace characters that are invalid in file names on any major platform
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows rejects names ending in a dot or space
+    sanitized.trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Kinds of user data the app writes to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataCategory {
+    /// Cached lyrics, their backups, and the key that encrypts them
+    Cache,
+    /// Event log and recorded session traces
+    History,
+    /// Authentication tokens
+    Tokens,
+    /// Saved window position
+    WindowState,
+    /// Log files
+    Logs,
+}
+
+impl DataCategory {
+    /// All categories, in display order
+    pub const ALL: [Self; 5] = [
+        Self::Cache,
+        Self::History,
+        Self::Tokens,
+        Self::WindowState,
+        Self::Logs,
+    ];
+
+    /// Human-readable category name
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Cache => "cache",
+            Self::History => "history",
+            Self::Tokens => "tokens",
+            Self::WindowState => "window state",
+            Self::Logs => "logs",
+        }
+    }
+}
+
+/// A file or directory of user data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataPath {
+    pub category: DataCategory,
+    pub path: PathBuf,
+}
+
+impl DataPath {
+    #[must_use]
+    pub const fn new(category: DataCategory, path: PathBuf) -> Self {
+        Self { category, path }
+    }
+
+    /// Delete this file or directory. Returns `false` if it did not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path exists but cannot be removed.
+    pub fn remove(&self) -> std::io::Result<bool> {
+        let result = if self.path.is_dir() {
+            std::fs::remove_dir_all(&self.path)
+        } else {
+            std::fs::remove_file(&self.path)
+        };
+        match result {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Registry of every file and directory of user data written by the core crate.
+///
+/// Provider crates that persist their own data (e.g. OAuth token caches) expose
+/// additional [`DataPath`]s that callers should append to this list.
+#[must_use]
+pub fn user_data_paths() -> Vec<DataPath> {
+    let mut paths = Vec::new();
+    for db in lyrics_cache_db_paths() {
+        push_sqlite_paths(&mut paths, DataCategory::Cache, &db);
+    }
+    push_sqlite_paths(&mut paths, DataCategory::History, &event_log_db_path());
+    paths.push(DataPath::new(DataCategory::History, session_summary_path()));
+    paths.push(DataPath::new(DataCategory::History, traces_dir()));
+    // The secrets file holds the cache encryption key, which must go with the cache it
+    // encrypts: a cache without its key is unreadable, and a new key would not open it
+    paths.push(DataPath::new(DataCategory::Cache, secrets_path()));
+    paths.push(DataPath::new(DataCategory::Cache, backups_dir()));
+    paths.push(DataPath::new(DataCategory::Cache, artwork_dir()));
+    paths.push(DataPath::new(
+        DataCategory::WindowState,
+        window_state_path(),
+    ));
+    paths.push(DataPath::new(DataCategory::Logs, log_file_path()));
+    paths
+}
+
+/// Add a `SQLite` database along with its WAL and shared-memory sidecar files
+fn push_sqlite_paths(paths: &mut Vec<DataPath>, category: DataCategory, db: &Path) {
+    paths.push(DataPath::new(category, db.to_path_buf()));
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db.as_os_str().to_owned();
+        sidecar.push(suffix);
+        paths.push(DataPath::new(category, PathBuf::from(sidecar)));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_path_named() {
+        assert_eq!(theme_path_named(""), theme_path());
+        assert_eq!(theme_path_named("dark"), themes_dir().join("dark.css"));
+        assert_eq!(theme_path_named("../x"), themes_dir().join(".._x.css"));
+    }
+
+    #[test]
+    fn test_user_data_paths_cover_all_categories() {
+        let paths = user_data_paths();
+        // Tokens are persisted by provider crates, which register their own paths
+        for category in DataCategory::ALL {
+            if category != DataCategory::Tokens {
+                assert!(paths.iter().any(|p| p.category == category));
+            }
+        }
+        assert!(
+            paths
+                .iter()
+                .any(|p| p.path == secrets_path() && p.category == DataCategory::Cache)
+        );
+        assert!(
+            paths
+                .iter()
+                .any(|p| p.path.to_string_lossy().ends_with("lyrics_cache.db-wal"))
+        );
+    }
+
+    #[test]
+    fn test_profile_config_path() {
+        assert_eq!(
+            profile_config_path("Streaming"),
+            config_dir().join("profiles").join("streaming.toml")
+        );
+        // A profile name cannot leave the profiles directory
+        assert_eq!(
+            profile_config_path("../config").file_name().unwrap(),
+            "___config.toml"
+        );
+    }
+
+    #[test]
+    fn test_lyrics_cache_db_paths_are_distinct() {
+        let name = |profile, source| {
+            lyrics_cache_db_path_for(profile, source)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        };
+        assert_eq!(name("", None), "lyrics_cache.db");
+        assert_eq!(
+            name("Work", Some(MusicSource::Spotify)),
+            "lyrics_cache-p-work-s-spotify.db"
+        );
+        // A profile named after a source is not the per-source database
+        assert_ne!(name("spotify", None), name("", Some(MusicSource::Spotify)));
+        // A profile cannot spell out a source tag
+        assert_ne!(
+            name("work-s-spotify", None),
+            name("work", Some(MusicSource::Spotify))
+        );
+    }
+
+    #[test]
+    fn test_remove_missing_path() {
+        let path = DataPath::new(
+            DataCategory::Logs,
+            std::env::temp_dir().join("versualizer-does-not-exist.log"),
+        );
+        assert!(!path.remove().unwrap());
+    }
+}
@@ -121,6 +121,10 @@ pub struct TrackInfo {
     pub album: String,
     /// Track duration
     pub duration: Duration,
+    /// URL of the album (or show) artwork, if the source provides one
+    pub album_art_url: Option<String>,
+    /// International Standard Recording Code, if the source provides one
+    pub isrc: Option<String>,
 }
 
 impl TrackInfo {
@@ -141,6 +145,8 @@ impl TrackInfo {
             artist: artist.into(),
             album: album.into(),
             duration,
+            album_art_url: None,
+            isrc: None,
         }
     }
 
@@ -151,6 +157,20 @@ impl TrackInfo {
         self
     }
 
+    /// Set the album artwork URL
+    #[must_use]
+    pub fn with_album_art_url(mut self, url: impl Into<String>) -> Self {
+        self.album_art_url = Some(url.into());
+        self
+    }
+
+    /// Set the ISRC, which lets providers that support it look up the exact recording
+    #[must_use]
+    pub fn with_isrc(mut self, isrc: impl Into<String>) -> Self {
+        self.isrc = Some(isrc.into());
+        self
+    }
+
     /// Get duration in seconds (for lyrics query).
     ///
     /// Saturates at `u32::MAX` (approximately 136 years), which is more than sufficient
@@ -183,20 +203,20 @@ mod tests {
             "Test Song",
             "Test Artist",
             "Test Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         let state = PlaybackState::new(
             true,
             Some(track),
             Duration::from_secs(30),
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         assert!(state.is_playing);
         assert!(state.track.is_some());
         assert_eq!(state.position, Duration::from_secs(30));
-        assert_eq!(state.duration, Duration::from_secs(180));
+        assert_eq!(state.duration, Duration::from_mins(3));
     }
 
     #[test]
@@ -205,7 +225,7 @@ mod tests {
             is_playing: false,
             track: None,
             position: Duration::from_secs(30),
-            duration: Duration::from_secs(180),
+            duration: Duration::from_mins(3),
             updated_at: Instant::now() - Duration::from_secs(5),
         };
 
@@ -219,12 +239,12 @@ mod tests {
             is_playing: true,
             track: None,
             position: Duration::from_secs(178),
-            duration: Duration::from_secs(180),
+            duration: Duration::from_mins(3),
             updated_at: Instant::now() - Duration::from_secs(10), // 10 seconds ago
         };
 
         // Position should be clamped to duration
-        assert_eq!(state.interpolated_position(), Duration::from_secs(180));
+        assert_eq!(state.interpolated_position(), Duration::from_mins(3));
     }
 
     #[test]
@@ -235,20 +255,20 @@ mod tests {
             "Song",
             "Artist",
             "Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         let state1 = PlaybackState::new(
             true,
             Some(track.clone()),
             Duration::ZERO,
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
         let state2 = PlaybackState::new(
             true,
             Some(track),
             Duration::from_secs(30),
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         assert!(!state1.track_changed(&state2));
@@ -262,7 +282,7 @@ mod tests {
             "Song 1",
             "Artist",
             "Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         let track2 = TrackInfo::new(
@@ -274,8 +294,7 @@ mod tests {
             Duration::from_secs(200),
         );
 
-        let state1 =
-            PlaybackState::new(true, Some(track1), Duration::ZERO, Duration::from_secs(180));
+        let state1 = PlaybackState::new(true, Some(track1), Duration::ZERO, Duration::from_mins(3));
         let state2 =
             PlaybackState::new(true, Some(track2), Duration::ZERO, Duration::from_secs(200));
 
@@ -290,12 +309,11 @@ mod tests {
             "Song",
             "Artist",
             "Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         let state1 = PlaybackState::default();
-        let state2 =
-            PlaybackState::new(true, Some(track), Duration::ZERO, Duration::from_secs(180));
+        let state2 = PlaybackState::new(true, Some(track), Duration::ZERO, Duration::from_mins(3));
 
         assert!(state1.track_changed(&state2));
     }
@@ -331,7 +349,7 @@ mod tests {
             "Test Song",
             "Test Artist",
             "Test Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         );
 
         assert_eq!(track.source, MusicSource::Spotify);
@@ -339,7 +357,7 @@ mod tests {
         assert_eq!(track.name, "Test Song");
         assert_eq!(track.artist, "Test Artist");
         assert_eq!(track.album, "Test Album");
-        assert_eq!(track.duration, Duration::from_secs(180));
+        assert_eq!(track.duration, Duration::from_mins(3));
         assert!(track.provider_ids.is_empty());
     }
 
@@ -351,7 +369,7 @@ mod tests {
             "Song",
             "Artist",
             "Album",
-            Duration::from_secs(180),
+            Duration::from_mins(3),
         )
         .with_provider_id("spotify", "spotify_track_id")
         .with_provider_id("lrclib", "lrclib_id");
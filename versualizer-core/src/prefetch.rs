@@ -0,0 +1,413 @@
+//! Lyrics prefetch for a list of tracks, such as a playlist warmed up before it is played.
+//!
+//! A [`PrefetchJob`] works on several tracks at once. Each track tries the fetcher's
+//! providers in priority order like a live fetch, but every request first waits for its
+//! provider's [`RateBudget`]. While one provider's budget is spent, the tracks that moved on
+//! to other providers keep going, so requests end up interleaved across providers. A
+//! provider that answers with HTTP 429 is paused for [`RATE_LIMIT_BACKOFF`] before the
+//! request is retried.
+//!
+//! Finished tracks are recorded in the cache under the job's name, so an interrupted job
+//! resumes where it stopped. Tracks that no provider answered for (e.g. while offline) are
+//! not recorded and are retried on the next run.
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::collections::HashMap;
+use tokio::sync::{Mutex, watch};
+use tokio::time::{Duration, Instant};
+use tracing::{Instrument, info, warn};
+
+use crate::error::Result;
+use crate::fetcher::{LyricsFetcher, Outcome, fetch_span, provider_span};
+use crate::playback::TrackInfo;
+use crate::provider::LyricsResult;
+
+/// How long a provider is left alone after it reports a rate limit
+pub const RATE_LIMIT_BACKOFF: Duration = Duration::from_mins(1);
+
+/// Retries of a request refused for exceeding the rate limit before moving on
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Tracks worked on at once unless [`PrefetchJob::with_concurrency`] says otherwise
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Most requests a provider may receive in a period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateBudget {
+    requests: u32,
+    per: Duration,
+}
+
+impl RateBudget {
+    /// At most `requests` requests per `per`, spread out evenly
+    #[must_use]
+    pub const fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+
+    /// At most `requests` requests per minute
+    #[must_use]
+    pub const fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_mins(1))
+    }
+
+    /// Time between two requests
+    fn interval(self) -> Duration {
+        self.per / self.requests.max(1)
+    }
+}
+
+impl Default for RateBudget {
+    /// 30 requests per minute, well inside the limits of the public lyrics APIs
+    fn default() -> Self {
+        Self::per_minute(30)
+    }
+}
+
+/// How far a prefetch job has got
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefetchProgress {
+    /// Tracks in the job
+    pub total: usize,
+    /// Tracks finished, including those finished in earlier runs
+    pub done: usize,
+    /// Finished tracks that have synced lyrics
+    pub found: usize,
+    /// Estimated time until the job is done, once a track has been finished in this run
+    pub eta: Option<Duration>,
+}
+
+/// Spaces out requests to each provider according to its budget
+struct RateLimiter {
+    budgets: HashMap<String, RateBudget>,
+    default_budget: RateBudget,
+    /// Earliest time the next request to each provider may start
+    next_slot: Mutex<HashMap<&'static str, Instant>>,
+}
+
+impl RateLimiter {
+    /// Wait until a request to `provider` fits its budget, and reserve the slot
+    async fn acquire(&self, provider: &'static str) {
+        let interval = self
+            .budgets
+            .get(provider)
+            .copied()
+            .unwrap_or(self.default_budget)
+            .interval();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.get(provider).map_or(now, |&next| next.max(now));
+            next_slot.insert(provider, slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Hold off all requests to `provider` for `duration`
+    async fn back_off(&self, provider: &'static str, duration: Duration) {
+        let resume = Instant::now() + duration;
+        let mut next_slot = self.next_slot.lock().await;
+        let next = next_slot.entry(provider).or_insert(resume);
+        *next = (*next).max(resume);
+    }
+}
+
+/// Prefetch of lyrics for a list of tracks into the cache
+pub struct PrefetchJob {
+    name: String,
+    tracks: Vec<TrackInfo>,
+    budgets: HashMap<String, RateBudget>,
+    default_budget: RateBudget,
+    concurrency: usize,
+}
+
+impl PrefetchJob {
+    /// A job named `name` (e.g. the playlist ID) for `tracks`. Progress is kept per name.
+    pub fn new(name: impl Into<String>, tracks: Vec<TrackInfo>) -> Self {
+        Self {
+            name: name.into(),
+            tracks,
+            budgets: HashMap::new(),
+            default_budget: RateBudget::default(),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Limit requests to `provider` to `budget`
+    #[must_use]
+    pub fn with_budget(mut self, provider: impl Into<String>, budget: RateBudget) -> Self {
+        self.budgets.insert(provider.into(), budget);
+        self
+    }
+
+    /// Budget of providers without their own
+    #[must_use]
+    pub const fn with_default_budget(mut self, budget: RateBudget) -> Self {
+        self.default_budget = budget;
+        self
+    }
+
+    /// Work on up to `concurrency` tracks at once
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Fetch lyrics for every unfinished track through `fetcher`'s providers, publishing
+    /// progress to `progress_tx`. Stops early when the fetcher is cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job's progress cannot be read or saved.
+    pub async fn run(
+        &self,
+        fetcher: &LyricsFetcher,
+        progress_tx: &watch::Sender<PrefetchProgress>,
+    ) -> Result<PrefetchProgress> {
+        let finished = fetcher.cache().prefetched_tracks(&self.name).await?;
+        let mut progress = PrefetchProgress {
+            total: self.tracks.len(),
+            ..PrefetchProgress::default()
+        };
+        let mut remaining = Vec::new();
+        for track in &self.tracks {
+            let key = (
+                track.source.as_str().to_string(),
+                track.source_track_id.clone(),
+            );
+            match finished.get(&key) {
+                Some(&found) => {
+                    progress.done += 1;
+                    progress.found += usize::from(found);
+                }
+                None => remaining.push(track),
+            }
+        }
+        progress_tx.send_replace(progress);
+        info!(
+            "Prefetching lyrics for {} of {} tracks (job {})",
+            remaining.len(),
+            progress.total,
+            self.name
+        );
+
+        let limiter = RateLimiter {
+            budgets: self.budgets.clone(),
+            default_budget: self.default_budget,
+            next_slot: Mutex::new(HashMap::new()),
+        };
+        let cancel_token = fetcher.cancel_token();
+        let started = Instant::now();
+        let mut finished_now = 0u32;
+        let mut queue = remaining.into_iter();
+        let mut in_flight = FuturesUnordered::new();
+        let limiter = &limiter;
+        loop {
+            while in_flight.len() < self.concurrency {
+                let Some(track) = queue.next() else { break };
+                in_flight.push(async move {
+                    let span = fetch_span(fetcher.next_fetch_id(), track);
+                    (
+                        track,
+                        prefetch_track(fetcher, limiter, track)
+                            .instrument(span)
+                            .await,
+                    )
+                });
+            }
+            let next = tokio::select! {
+                () = cancel_token.cancelled() => break,
+                next = in_flight.next() => next,
+            };
+            let Some((track, found)) = next else { break };
+            // No provider answered, leave the track for the next run
+            let Some(found) = found else { continue };
+
+            fetcher
+                .cache()
+                .mark_prefetched(
+                    &self.name,
+                    track.source.as_str(),
+                    &track.source_track_id,
+                    found,
+                )
+                .await?;
+            finished_now += 1;
+            progress.done += 1;
+            progress.found += usize::from(found);
+            let left = u32::try_from(progress.total - progress.done).unwrap_or(u32::MAX);
+            progress.eta = Some(started.elapsed() / finished_now * left);
+            progress_tx.send_replace(progress);
+        }
+
+        info!(
+            "Prefetch job {} stopped: {}/{} tracks done, {} with synced lyrics",
+            self.name, progress.done, progress.total, progress.found
+        );
+        Ok(progress)
+    }
+}
+
+/// Fetch and cache synced lyrics for `track`. Returns whether synced lyrics were found, or
+/// `None` if no provider answered.
+async fn prefetch_track(
+    fetcher: &LyricsFetcher,
+    limiter: &RateLimiter,
+    track: &TrackInfo,
+) -> Option<bool> {
+    if let Ok(Some(cached)) = fetcher
+        .cache()
+        .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+        .await
+        && matches!(cached.to_lyrics_result(), LyricsResult::Synced(_))
+    {
+        return Some(true);
+    }
+
+    let query = fetcher.query_for(track).await;
+    let mut answered = false;
+    for provider in fetcher.providers() {
+        let name = provider.name();
+        let mut retries = 0;
+        let response = loop {
+            limiter.acquire(name).await;
+            let response = provider.fetch(&query).instrument(provider_span(name)).await;
+            match response {
+                Err(ref e) if e.is_rate_limited() && retries < MAX_RATE_LIMIT_RETRIES => {
+                    warn!("Provider {} is rate limiting, backing off", name);
+                    limiter.back_off(name, RATE_LIMIT_BACKOFF).await;
+                    retries += 1;
+                }
+                _ => break response,
+            }
+        };
+        answered |= response.is_ok();
+
+        if let Outcome::Synced(name, lyrics) | Outcome::Instrumental(name, lyrics) =
+            fetcher.record_fetched(name, response)
+        {
+            fetcher.store_in_cache(track, &lyrics, name).await;
+            if let LyricsResult::Synced(ref lrc) = lyrics.result {
+                fetcher.mirror_in_background(track, lrc);
+            }
+            return Some(true);
+        }
+    }
+    answered.then_some(false)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::cache::LyricsCache;
+    use crate::error::CoreError;
+    use crate::lrc::LrcFile;
+    use crate::provider::{FetchedLyrics, LyricsProvider, LyricsQuery};
+    use crate::source::MusicSource;
+    use crate::sync::SyncEngine;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Provider with synced lyrics for every track but the one named "Missing". Refuses the
+    /// first `rate_limited` requests with a 429.
+    struct FakeProvider {
+        requests: Arc<AtomicU32>,
+        rate_limited: u32,
+    }
+
+    #[async_trait]
+    impl LyricsProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics> {
+            if self.requests.fetch_add(1, Ordering::SeqCst) < self.rate_limited {
+                return Err(CoreError::LyricsProviderFailed {
+                    provider: "fake".to_string(),
+                    reason: "search returned status: 429 Too Many Requests".to_string(),
+                });
+            }
+            let result = if query.track_name == "Missing" {
+                LyricsResult::NotFound
+            } else {
+                LyricsResult::Synced(LrcFile::parse("[00:01.00]Hello").unwrap())
+            };
+            Ok(FetchedLyrics {
+                result,
+                provider_id: query.track_name.clone(),
+            })
+        }
+    }
+
+    fn track(id: &str, name: &str) -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            id,
+            name,
+            "Artist",
+            "Album",
+            std::time::Duration::from_mins(3),
+        )
+    }
+
+    async fn fetcher(name: &str, rate_limited: u32) -> (LyricsFetcher, Arc<AtomicU32>) {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-prefetch-{name}-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let cache = Arc::new(LyricsCache::open(&path).await.unwrap());
+        let requests = Arc::new(AtomicU32::new(0));
+        let provider = FakeProvider {
+            requests: requests.clone(),
+            rate_limited,
+        };
+        let fetcher = LyricsFetcher::new(SyncEngine::new(), cache, vec![Box::new(provider)], None);
+        (fetcher, requests)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_prefetch_respects_budget_and_resumes() {
+        let (fetcher, requests) = fetcher("budget", 0).await;
+        let tracks = vec![
+            track("t1", "One"),
+            track("t2", "Two"),
+            track("t3", "Missing"),
+        ];
+        let job = PrefetchJob::new("playlist", tracks)
+            .with_budget("fake", RateBudget::new(1, Duration::from_secs(10)));
+        let (progress_tx, _) = watch::channel(PrefetchProgress::default());
+
+        let started = Instant::now();
+        let progress = job.run(&fetcher, &progress_tx).await.unwrap();
+        assert_eq!(progress.done, 3);
+        assert_eq!(progress.found, 2);
+        assert_eq!(progress.eta, Some(Duration::ZERO));
+        // Three requests one budget interval apart
+        assert!(started.elapsed() >= Duration::from_secs(20));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // A second run has nothing left to do
+        let progress = job.run(&fetcher, &progress_tx).await.unwrap();
+        assert_eq!((progress.done, progress.found), (3, 2));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_prefetch_backs_off_when_rate_limited() {
+        let (fetcher, requests) = fetcher("rate-limit", 1).await;
+        let job = PrefetchJob::new("playlist", vec![track("t1", "One")]);
+        let (progress_tx, _) = watch::channel(PrefetchProgress::default());
+
+        let started = Instant::now();
+        let progress = job.run(&fetcher, &progress_tx).await.unwrap();
+        assert_eq!(progress.found, 1);
+        assert!(started.elapsed() >= RATE_LIMIT_BACKOFF);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,278 @@
+//! Startup preflight checks.
+//!
+//! A [`Preflight`] collects pass/warn/fail results for everything Versualizer depends on
+//! (config, provider reachability, credentials, cache database) and renders them as a
+//! table with a fix hint next to each problem. Provider crates contribute their own
+//! checks as [`CheckResult`]s.
+
+#[cfg(feature = "cache")]
+use crate::cache::LyricsCache;
+use crate::config::VersualizerConfig;
+use crate::error::CoreError;
+use std::fmt::Write;
+#[cfg(feature = "cache")]
+use std::path::Path;
+#[cfg(feature = "http")]
+use std::time::Duration;
+
+/// Timeout for reachability requests
+#[cfg(feature = "http")]
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    /// Working, but degraded or not set up
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// Result of a single preflight check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    #[must_use]
+    pub fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, detail)
+    }
+
+    #[must_use]
+    pub fn warn(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, detail)
+    }
+
+    #[must_use]
+    pub fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, detail)
+    }
+
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    /// Attach a fix hint
+    #[must_use]
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Collected preflight results
+#[derive(Debug, Default)]
+pub struct Preflight {
+    results: Vec<CheckResult>,
+}
+
+impl Preflight {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a check result
+    pub fn push(&mut self, result: CheckResult) {
+        self.results.push(result);
+    }
+
+    #[must_use]
+    pub fn results(&self) -> &[CheckResult] {
+        &self.results
+    }
+
+    /// Worst status across all checks, `Pass` if there are none
+    #[must_use]
+    pub fn status(&self) -> CheckStatus {
+        self.results
+            .iter()
+            .map(|result| result.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    /// Check that the config file exists and parses. Returns the config when it does.
+    pub fn check_config(&mut self) -> Option<VersualizerConfig> {
+        const NAME: &str = "Config";
+        let path = VersualizerConfig::config_path();
+        if !path.exists() {
+            self.push(
+                CheckResult::fail(NAME, format!("{} not found", path.display()))
+                    .with_hint("Start Versualizer once to generate a config template"),
+            );
+            return None;
+        }
+
+        match VersualizerConfig::load_or_create(None) {
+            Ok(config) => {
+                self.push(CheckResult::pass(NAME, path.display().to_string()));
+                Some(config)
+            }
+            Err(CoreError::ConfigParseError(e)) => {
+                self.push(
+                    CheckResult::fail(NAME, e.message().to_string()).with_hint(format!(
+                        "Fix the TOML in {}, or delete it to regenerate the template",
+                        path.display()
+                    )),
+                );
+                None
+            }
+            Err(e) => {
+                self.push(CheckResult::fail(NAME, e.to_string()));
+                None
+            }
+        }
+    }
+
+    /// Check that `url` answers HTTP requests
+    #[cfg(feature = "http")]
+    pub async fn check_reachable(&mut self, name: &str, url: &str) {
+        self.push(check_reachable(name, url).await);
+    }
+
+    /// Open the lyrics cache at `path` and run `SQLite`'s integrity check on it
+    #[cfg(feature = "cache")]
+    pub async fn check_cache(&mut self, path: &Path) {
+        const NAME: &str = "Lyrics cache";
+        if !path.exists() {
+            self.push(CheckResult::pass(
+                NAME,
+                format!("{} not created yet", path.display()),
+            ));
+            return;
+        }
+
+        let corrupt_hint = format!(
+            "Delete {} (or run `versualizer-cli purge --cache`) to start with a fresh cache",
+            path.display()
+        );
+        let result = match LyricsCache::open(path).await {
+            Ok(cache) => match cache.integrity_check().await {
+                Ok(problems) if problems.is_empty() => {
+                    CheckResult::pass(NAME, path.display().to_string())
+                }
+                Ok(problems) => {
+                    CheckResult::fail(NAME, problems.join("; ")).with_hint(corrupt_hint)
+                }
+                Err(e) => CheckResult::fail(NAME, e.to_string()).with_hint(corrupt_hint),
+            },
+            Err(e) => CheckResult::fail(NAME, e.to_string()).with_hint(corrupt_hint),
+        };
+        self.push(result);
+    }
+
+    /// Render results as an aligned pass/fail table, with hints indented under problems
+    #[must_use]
+    pub fn render_table(&self) -> String {
+        let name_width = self
+            .results
+            .iter()
+            .map(|result| result.name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        for result in &self.results {
+            let _ = writeln!(
+                out,
+                "[{}] {:<name_width$}  {}",
+                result.status.label(),
+                result.name,
+                result.detail
+            );
+            if let Some(ref hint) = result.hint {
+                let _ = writeln!(out, "       {:<name_width$}  -> {hint}", "");
+            }
+        }
+        out
+    }
+}
+
+/// Check that `url` answers HTTP requests. Any response counts, including errors,
+/// since only the connection matters here.
+#[cfg(feature = "http")]
+pub async fn check_reachable(name: &str, url: &str) -> CheckResult {
+    match reqwest::Client::builder()
+        .timeout(REACHABILITY_TIMEOUT)
+        .build()
+    {
+        Ok(client) => match client.head(url).send().await {
+            Ok(response) => CheckResult::pass(name, format!("HTTP {}", response.status())),
+            Err(e) => CheckResult::fail(name, format!("{url} unreachable: {e}"))
+                .with_hint("Check your internet connection, firewall, or proxy settings"),
+        },
+        Err(e) => CheckResult::fail(name, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_is_worst_result() {
+        let mut preflight = Preflight::new();
+        assert_eq!(preflight.status(), CheckStatus::Pass);
+
+        preflight.push(CheckResult::pass("A", "ok"));
+        preflight.push(CheckResult::warn("B", "meh"));
+        assert_eq!(preflight.status(), CheckStatus::Warn);
+
+        preflight.push(CheckResult::fail("C", "broken").with_hint("fix it"));
+        assert_eq!(preflight.status(), CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_render_table_aligns_names_and_hints() {
+        let mut preflight = Preflight::new();
+        preflight.push(CheckResult::pass("Config", "config.toml"));
+        preflight.push(CheckResult::fail("LRCLIB", "unreachable").with_hint("Check network"));
+
+        let table = preflight.render_table();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines[0], "[PASS] Config  config.toml");
+        assert_eq!(lines[1], "[FAIL] LRCLIB  unreachable");
+        assert_eq!(lines[2], "               -> Check network");
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn test_check_cache_reports_healthy_database() {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-preflight-cache-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        drop(LyricsCache::open(&path).await.unwrap());
+
+        let mut preflight = Preflight::new();
+        preflight.check_cache(&path).await;
+        assert_eq!(preflight.status(), CheckStatus::Pass);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,429 @@
+//! Text post-processors applied to lyrics before they are displayed.
+//!
+//! A [`TextPipeline`] runs an ordered list of [`TextProcessor`]s over every lyric line (and
+//! every timed word of a line). The built-in processors are selected in `[lyrics.processors]`;
+//! other text features can add their own processor to the pipeline instead of hooking into
+//! the fetcher separately. Lyrics are cached as fetched and processed each time they are
+//! shown, so a pipeline change also applies to songs that are already cached.
+
+use std::collections::HashSet;
+
+use crate::config::{LyricsConfig, ProcessorsConfig, ScriptTarget, TextProcessorKind};
+use crate::lrc::LrcFile;
+use crate::provider::LyricsResult;
+use crate::romanize::{hiragana_to_katakana, katakana_to_hiragana, romanize};
+
+/// A transformation of a single line (or word) of lyric text
+pub trait TextProcessor: Send + Sync {
+    /// Short name for logging
+    fn name(&self) -> &'static str;
+
+    /// Transform `text`
+    fn process(&self, text: &str) -> String;
+}
+
+/// Trims and collapses runs of whitespace into a single space
+pub struct NormalizeWhitespace;
+
+impl TextProcessor for NormalizeWhitespace {
+    fn name(&self) -> &'static str {
+        "normalize_whitespace"
+    }
+
+    fn process(&self, text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Words masked by the censor when `censor_words` is empty: common English swear words and
+/// slurs, in the forms lyrics use most
+pub const DEFAULT_CENSOR_WORDS: &[&str] = &[
+    "fuck",
+    "fucks",
+    "fucked",
+    "fucker",
+    "fuckers",
+    "fucking",
+    "fuckin",
+    "motherfucker",
+    "motherfuckers",
+    "motherfuckin",
+    "shit",
+    "shits",
+    "shitty",
+    "bullshit",
+    "bitch",
+    "bitches",
+    "cunt",
+    "asshole",
+    "assholes",
+    "dick",
+    "dicks",
+    "pussy",
+    "cock",
+    "whore",
+    "slut",
+    "nigga",
+    "niggas",
+    "nigger",
+    "faggot",
+    "fag",
+    "retard",
+];
+
+/// Replaces listed words (case-insensitive, whole words) with asterisks, keeping the first
+/// and last letter of words of three or more letters ("f**k")
+pub struct Censor {
+    words: HashSet<String>,
+}
+
+impl Censor {
+    #[must_use]
+    pub fn new(words: &[String]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Censor `words`, or [`DEFAULT_CENSOR_WORDS`] if there are none
+    #[must_use]
+    pub fn or_default(words: &[String]) -> Self {
+        if words.iter().all(|word| word.trim().is_empty()) {
+            Self {
+                words: DEFAULT_CENSOR_WORDS
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            }
+        } else {
+            Self::new(words)
+        }
+    }
+
+    fn mask(&self, word: &str, out: &mut String) {
+        if !self.words.contains(&word.to_lowercase()) {
+            out.push_str(word);
+            return;
+        }
+        let count = word.chars().count();
+        for (i, c) in word.chars().enumerate() {
+            let keep = count >= 3 && (i == 0 || i == count - 1);
+            out.push(if keep { c } else { '*' });
+        }
+    }
+}
+
+impl TextProcessor for Censor {
+    fn name(&self) -> &'static str {
+        "censor"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_alphanumeric() || c == '\'' {
+                word.push(c);
+            } else {
+                self.mask(&word, &mut out);
+                word.clear();
+                out.push(c);
+            }
+        }
+        self.mask(&word, &mut out);
+        out
+    }
+}
+
+/// Replaces kana and hangul with their romanization, so the original script is not shown
+pub struct Romanize;
+
+impl TextProcessor for Romanize {
+    fn name(&self) -> &'static str {
+        "romanize"
+    }
+
+    fn process(&self, text: &str) -> String {
+        romanize(text)
+    }
+}
+
+/// Converts characters between scripts
+pub struct ConvertScript {
+    target: ScriptTarget,
+}
+
+impl ConvertScript {
+    #[must_use]
+    pub const fn new(target: ScriptTarget) -> Self {
+        Self { target }
+    }
+}
+
+impl TextProcessor for ConvertScript {
+    fn name(&self) -> &'static str {
+        "convert_script"
+    }
+
+    fn process(&self, text: &str) -> String {
+        match self.target {
+            ScriptTarget::Hiragana => text.chars().map(katakana_to_hiragana).collect(),
+            ScriptTarget::Katakana => text.chars().map(hiragana_to_katakana).collect(),
+            ScriptTarget::Halfwidth => text.chars().map(fullwidth_to_halfwidth).collect(),
+        }
+    }
+}
+
+/// Map fullwidth ASCII forms (and the ideographic space) to regular ASCII
+fn fullwidth_to_halfwidth(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(u32::from(c) - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Replaces typographic quotes and apostrophes with ASCII ones
+pub struct SmartQuotes;
+
+impl TextProcessor for SmartQuotes {
+    fn name(&self) -> &'static str {
+        "smart_quotes"
+    }
+
+    fn process(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' | '`'
+                | '\u{00B4}' => '\'',
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => '"',
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// Ordered list of text processors
+#[derive(Default)]
+pub struct TextPipeline {
+    processors: Vec<Box<dyn TextProcessor>>,
+}
+
+impl TextPipeline {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the pipeline configured in `[lyrics.processors]`, ending with the censor when
+    /// `lyrics.censor` is on and the pipeline does not censor already
+    #[must_use]
+    pub fn from_config(config: &LyricsConfig) -> Self {
+        let pipeline = Self::from_processors(&config.processors);
+        if config.censor
+            && !config
+                .processors
+                .pipeline
+                .contains(&TextProcessorKind::Censor)
+        {
+            pipeline.with(Censor::or_default(&config.processors.censor_words))
+        } else {
+            pipeline
+        }
+    }
+
+    fn from_processors(config: &ProcessorsConfig) -> Self {
+        config
+            .pipeline
+            .iter()
+            .fold(Self::new(), |pipeline, kind| match kind {
+                TextProcessorKind::NormalizeWhitespace => pipeline.with(NormalizeWhitespace),
+                TextProcessorKind::Censor => {
+                    pipeline.with(Censor::or_default(&config.censor_words))
+                }
+                TextProcessorKind::Romanize => pipeline.with(Romanize),
+                TextProcessorKind::ConvertScript => {
+                    pipeline.with(ConvertScript::new(config.convert_script))
+                }
+                TextProcessorKind::SmartQuotes => pipeline.with(SmartQuotes),
+            })
+    }
+
+    /// Append a processor to the end of the pipeline
+    #[must_use]
+    pub fn with(mut self, processor: impl TextProcessor + 'static) -> Self {
+        self.processors.push(Box::new(processor));
+        self
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    /// Names of the processors, in order
+    #[must_use]
+    pub fn names(&self) -> Vec<&'static str> {
+        self.processors.iter().map(|p| p.name()).collect()
+    }
+
+    /// Run every processor over `text`
+    #[must_use]
+    pub fn process(&self, text: &str) -> String {
+        self.processors
+            .iter()
+            .fold(text.to_string(), |text, processor| processor.process(&text))
+    }
+
+    /// Run the pipeline over every line of `lrc`. Timed words are processed on their own
+    /// and dropped if nothing is left of them.
+    pub fn apply_lrc(&self, lrc: &mut LrcFile) {
+        if self.is_empty() {
+            return;
+        }
+        for line in &mut lrc.lines {
+            line.text = self.process(&line.text);
+            if let Some(ref mut words) = line.words {
+                for word in words.iter_mut() {
+                    word.text = self.process(&word.text);
+                }
+                words.retain(|word| !word.text.is_empty());
+            }
+        }
+    }
+
+    /// Run the pipeline over every line of plain `text`
+    #[must_use]
+    pub fn apply_text(&self, text: &str) -> String {
+        text.lines()
+            .map(|line| self.process(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Run the pipeline over every line of `result`
+    pub fn apply(&self, result: &mut LyricsResult) {
+        if self.is_empty() {
+            return;
+        }
+        match result {
+            LyricsResult::Synced(lrc) => self.apply_lrc(lrc),
+            LyricsResult::Unsynced(text) => *text = self.apply_text(text),
+            LyricsResult::Instrumental | LyricsResult::NotFound => {}
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn pipeline(kinds: &[TextProcessorKind]) -> TextPipeline {
+        TextPipeline::from_config(&LyricsConfig {
+            processors: ProcessorsConfig {
+                pipeline: kinds.to_vec(),
+                censor_words: vec!["darn".to_string()],
+                convert_script: ScriptTarget::Halfwidth,
+                romanize_lines: false,
+            },
+            ..LyricsConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_processors_run_in_order() {
+        let pipeline = pipeline(&[
+            TextProcessorKind::NormalizeWhitespace,
+            TextProcessorKind::SmartQuotes,
+            TextProcessorKind::Censor,
+        ]);
+        assert_eq!(
+            pipeline.names(),
+            vec!["normalize_whitespace", "smart_quotes", "censor"]
+        );
+        assert_eq!(
+            pipeline.process("  It\u{2019}s   DARN  \u{201C}late\u{201D} "),
+            "It's D**N \"late\""
+        );
+    }
+
+    #[test]
+    fn test_censor_matches_whole_words_only() {
+        let censor = Censor::new(&["darn".to_string(), "ok".to_string()]);
+        assert_eq!(
+            censor.process("darn, darning Darn ok"),
+            "d**n, darning D**n **"
+        );
+    }
+
+    #[test]
+    fn test_censor_option_adds_default_words() {
+        let config = LyricsConfig {
+            censor: true,
+            ..LyricsConfig::default()
+        };
+        let pipeline = TextPipeline::from_config(&config);
+        assert_eq!(pipeline.names(), vec!["censor"]);
+        assert_eq!(pipeline.process("What the FUCK"), "What the F**K");
+
+        // Already in the pipeline, so it is not added twice
+        let pipeline = TextPipeline::from_config(&LyricsConfig {
+            censor: true,
+            processors: ProcessorsConfig {
+                pipeline: vec![TextProcessorKind::Censor],
+                censor_words: vec!["darn".to_string()],
+                ..ProcessorsConfig::default()
+            },
+            ..LyricsConfig::default()
+        });
+        assert_eq!(pipeline.names(), vec!["censor"]);
+        assert_eq!(pipeline.process("darn shit"), "d**n shit");
+    }
+
+    #[test]
+    fn test_convert_script() {
+        assert_eq!(
+            ConvertScript::new(ScriptTarget::Halfwidth).process("ＡＢＣ１２３！"),
+            "ABC123!"
+        );
+        assert_eq!(
+            ConvertScript::new(ScriptTarget::Katakana).process("さくら"),
+            "サクラ"
+        );
+    }
+
+    #[test]
+    fn test_apply_processes_lines_and_words() {
+        let lrc = LrcFile::parse("[00:01.00]<00:01.00>さくら <00:02.00>  <00:03.00>はな").unwrap();
+        let mut result = LyricsResult::Synced(lrc);
+        pipeline(&[
+            TextProcessorKind::Romanize,
+            TextProcessorKind::NormalizeWhitespace,
+        ])
+        .apply(&mut result);
+
+        let words: Vec<_> = match result {
+            LyricsResult::Synced(lrc) => lrc.lines[0]
+                .words
+                .clone()
+                .unwrap()
+                .into_iter()
+                .map(|word| word.text)
+                .collect(),
+            _ => Vec::new(),
+        };
+        assert_eq!(words, vec!["sakura", "hana"]);
+    }
+
+    #[test]
+    fn test_apply_keeps_unsynced_line_breaks() {
+        let mut result = LyricsResult::Unsynced("  one  \n two".to_string());
+        pipeline(&[TextProcessorKind::NormalizeWhitespace]).apply(&mut result);
+        assert!(matches!(result, LyricsResult::Unsynced(ref text) if text == "one\ntwo"));
+    }
+}
@@ -1,7 +1,9 @@
 use crate::error::CoreError;
 use crate::lrc::LrcFile;
+use crate::preflight::CheckResult;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::time::SystemTime;
 
 /// Query parameters for fetching lyrics
 #[derive(Debug, Clone)]
@@ -16,6 +18,12 @@ pub struct LyricsQuery {
     pub duration_secs: Option<u32>,
     /// Provider-specific track IDs (key: provider name, value: track ID)
     pub provider_ids: HashMap<String, String>,
+    /// Track name cleaned up for searching, if it differs from `track_name`
+    pub search_track: Option<String>,
+    /// Artist name cleaned up for searching, if it differs from `artist_name`
+    pub search_artist: Option<String>,
+    /// International Standard Recording Code, for providers that can look tracks up by it
+    pub isrc: Option<String>,
 }
 
 impl LyricsQuery {
@@ -27,6 +35,9 @@ impl LyricsQuery {
             album_name: None,
             duration_secs: None,
             provider_ids: HashMap::new(),
+            search_track: None,
+            search_artist: None,
+            isrc: None,
         }
     }
 
@@ -51,6 +62,40 @@ impl LyricsQuery {
         self
     }
 
+    /// Set the ISRC
+    #[must_use]
+    pub fn with_isrc(mut self, isrc: impl Into<String>) -> Self {
+        self.isrc = Some(isrc.into());
+        self
+    }
+
+    /// Search with these names instead of the track and artist names, e.g. without
+    /// "(Remastered)" (see [`QueryNormalizer`](crate::QueryNormalizer)). Names equal to the
+    /// originals are not kept.
+    #[must_use]
+    pub fn with_search_names(
+        mut self,
+        track: impl Into<String>,
+        artist: impl Into<String>,
+    ) -> Self {
+        let (track, artist) = (track.into(), artist.into());
+        self.search_track = (track != self.track_name).then_some(track);
+        self.search_artist = (artist != self.artist_name).then_some(artist);
+        self
+    }
+
+    /// Track name to search providers with
+    #[must_use]
+    pub fn search_track_name(&self) -> &str {
+        self.search_track.as_deref().unwrap_or(&self.track_name)
+    }
+
+    /// Artist name to search providers with
+    #[must_use]
+    pub fn search_artist_name(&self) -> &str {
+        self.search_artist.as_deref().unwrap_or(&self.artist_name)
+    }
+
     /// Get a provider-specific track ID
     #[must_use]
     pub fn provider_id(&self, provider: &str) -> Option<&str> {
@@ -62,6 +107,12 @@ impl LyricsQuery {
     pub fn spotify_track_id(&self) -> Option<&str> {
         self.provider_id("spotify")
     }
+
+    /// Convenience method to get the Spotify episode ID of a playing podcast
+    #[must_use]
+    pub fn spotify_episode_id(&self) -> Option<&str> {
+        self.provider_id("spotify_episode")
+    }
 }
 
 /// Result from a lyrics provider
@@ -71,6 +122,8 @@ pub enum LyricsResult {
     Synced(LrcFile),
     /// Plain text lyrics without timing
     Unsynced(String),
+    /// The track has no vocals, so there are no lyrics to show
+    Instrumental,
     /// No lyrics found
     NotFound,
 }
@@ -118,11 +171,64 @@ impl LyricsResult {
                     .join("\n"),
             ),
             Self::Unsynced(text) => Some(text.clone()),
-            Self::NotFound => None,
+            Self::Instrumental | Self::NotFound => None,
         }
     }
 }
 
+/// Lines of lyrics shown in a candidate's preview
+pub const PREVIEW_LINES: usize = 2;
+
+/// One of a provider's search results, for choosing lyrics by hand when the automatic match
+/// is wrong (see [`LyricsProvider::search`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricsCandidate {
+    /// Name of the provider that found it
+    pub provider: String,
+    /// The provider's ID for these lyrics, used to fetch them
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    /// Length of the recording the lyrics are for, if known
+    pub duration_secs: Option<f64>,
+    /// Whether the lyrics are timed
+    pub synced: bool,
+    /// The first lines of the lyrics, if the search returned them
+    pub preview: Option<String>,
+}
+
+impl LyricsCandidate {
+    /// The first [`PREVIEW_LINES`] non-empty lines of `text`, without LRC timestamps
+    #[must_use]
+    pub fn preview_of(text: &str) -> String {
+        text.lines()
+            .map(|line| {
+                let mut line = line.trim();
+                while line.starts_with('[')
+                    && let Some(end) = line.find(']')
+                {
+                    line = line[end + 1..].trim_start();
+                }
+                line
+            })
+            .filter(|line| !line.is_empty())
+            .take(PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+/// Credential state of a provider or music source, as shown on the settings auth page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthState {
+    /// Whether credentials are configured and a token is currently held
+    pub authenticated: bool,
+    /// When the current access token expires, if known
+    pub expires_at: Option<SystemTime>,
+    /// Short description, e.g. "Valid" or "Not signed in"
+    pub detail: String,
+}
+
 /// Trait for lyrics providers
 #[async_trait]
 pub trait LyricsProvider: Send + Sync {
@@ -131,6 +237,39 @@ pub trait LyricsProvider: Send + Sync {
 
     /// Fetch lyrics for a query
     async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError>;
+
+    /// Every result that might be the lyrics for `query`, best match first, for choosing
+    /// by hand. Providers that only look tracks up by ID find none.
+    async fn search(&self, _query: &LyricsQuery) -> Result<Vec<LyricsCandidate>, CoreError> {
+        Ok(Vec::new())
+    }
+
+    /// Fetch the lyrics of `candidate`, one of this provider's [`search`](Self::search)
+    /// results for `query`. By default the query is fetched with the candidate's ID as this
+    /// provider's track ID.
+    async fn fetch_candidate(
+        &self,
+        query: &LyricsQuery,
+        candidate: &LyricsCandidate,
+    ) -> Result<FetchedLyrics, CoreError> {
+        let query = query.clone().with_provider_id(self.name(), &candidate.id);
+        self.fetch(&query).await
+    }
+
+    /// Credential state, or `None` for providers that need no authentication
+    async fn auth_state(&self) -> Option<AuthState> {
+        None
+    }
+
+    /// Drop any cached credentials and acquire fresh ones
+    async fn reauth(&self) -> Result<(), CoreError> {
+        Ok(())
+    }
+
+    /// Check that the provider is usable (the settings page "Test" button)
+    async fn check(&self) -> CheckResult {
+        CheckResult::pass(self.name(), "No credentials needed")
+    }
 }
 
 #[cfg(test)]
@@ -172,6 +311,7 @@ mod tests {
         assert_eq!(query.provider_id("spotify"), Some("spotify_track_123"));
         assert_eq!(query.provider_id("lrclib"), Some("12345"));
         assert_eq!(query.provider_id("unknown"), None);
+        assert_eq!(query.spotify_episode_id(), None);
     }
 
     #[test]
@@ -194,13 +334,15 @@ mod tests {
         let query = LyricsQuery::new("Test Song", "Test Artist")
             .with_album("Test Album")
             .with_duration(200)
-            .with_provider_id("spotify", "abc123");
+            .with_provider_id("spotify", "abc123")
+            .with_isrc("USUM71703861");
 
         assert_eq!(query.track_name, "Test Song");
         assert_eq!(query.artist_name, "Test Artist");
         assert_eq!(query.album_name, Some("Test Album".to_string()));
         assert_eq!(query.duration_secs, Some(200));
         assert_eq!(query.provider_id("spotify"), Some("abc123"));
+        assert_eq!(query.isrc.as_deref(), Some("USUM71703861"));
     }
 
     #[test]
@@ -213,6 +355,16 @@ mod tests {
         assert!(result.text().is_none());
     }
 
+    #[test]
+    fn test_lyrics_result_instrumental() {
+        let result = LyricsResult::Instrumental;
+
+        // An instrumental track is an answer, with no text to show
+        assert!(result.is_found());
+        assert!(!result.is_synced());
+        assert!(result.text().is_none());
+    }
+
     #[test]
     fn test_lyrics_result_unsynced() {
         let result = LyricsResult::Unsynced("Plain text lyrics\nLine 2".to_string());
@@ -257,4 +409,14 @@ mod tests {
         assert!(fetched.result.is_synced());
         assert_eq!(fetched.provider_id, "12345");
     }
+
+    #[test]
+    fn test_candidate_preview() {
+        let text = "[ti:Song]\n[00:01.00]First line\n\n[00:02.00][00:09.00]Second\n[00:03.00]Third";
+        assert_eq!(LyricsCandidate::preview_of(text), "First line / Second");
+        assert_eq!(
+            LyricsCandidate::preview_of("Plain\nlyrics\nhere"),
+            "Plain / lyrics"
+        );
+    }
 }
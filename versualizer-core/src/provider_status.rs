@@ -0,0 +1,177 @@
+//! Registry of lyrics provider health, updated by the fetcher after every provider request.
+//!
+//! The UI subscribes to the registry to tell a network outage ("offline, showing cached
+//! lyrics only") apart from a track that simply has no lyrics.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::error::CoreError;
+
+/// Health of a single provider, as of its most recent request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderStatus {
+    /// The provider answered, whether or not it had lyrics
+    Available,
+    /// The provider could not be reached (connection failure or timeout)
+    Unreachable { error: String },
+    /// The provider was reached but the request failed
+    Failing { error: String },
+}
+
+impl ProviderStatus {
+    /// Status after a request to the provider failed with `error`
+    #[must_use]
+    pub fn from_error(error: &CoreError) -> Self {
+        // Some providers report a miss as an error, but they still answered
+        if matches!(error, CoreError::LyricsNotFound { .. }) {
+            Self::Available
+        } else if error.is_network() {
+            Self::Unreachable {
+                error: error.to_string(),
+            }
+        } else {
+            Self::Failing {
+                error: error.to_string(),
+            }
+        }
+    }
+}
+
+/// Latest status per provider name
+pub type ProviderStatuses = BTreeMap<String, ProviderStatus>;
+
+/// Shared registry of provider statuses
+#[derive(Debug)]
+pub struct ProviderStatusRegistry {
+    tx: watch::Sender<ProviderStatuses>,
+}
+
+impl ProviderStatusRegistry {
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        let (tx, _) = watch::channel(ProviderStatuses::new());
+        Arc::new(Self { tx })
+    }
+
+    /// Record the outcome of a request to `provider`. Subscribers are only notified on change.
+    pub fn record(&self, provider: &str, status: ProviderStatus) {
+        self.tx.send_if_modified(|statuses| {
+            if statuses.get(provider) == Some(&status) {
+                return false;
+            }
+            statuses.insert(provider.to_string(), status);
+            true
+        });
+    }
+
+    /// Current status of every provider that has been tried
+    #[must_use]
+    pub fn snapshot(&self) -> ProviderStatuses {
+        self.tx.borrow().clone()
+    }
+
+    /// Subscribe to status changes
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<ProviderStatuses> {
+        self.tx.subscribe()
+    }
+}
+
+/// Whether every provider that has been tried is unreachable
+#[must_use]
+pub fn all_unreachable(statuses: &ProviderStatuses) -> bool {
+    !statuses.is_empty()
+        && statuses
+            .values()
+            .all(|status| matches!(status, ProviderStatus::Unreachable { .. }))
+}
+
+/// Overlay notice while every provider is unreachable, or `None` while any answers. The
+/// wording depends on whether (cached) lyrics are on screen.
+#[must_use]
+pub fn offline_notice(statuses: &ProviderStatuses, showing_lyrics: bool) -> Option<&'static str> {
+    if !all_unreachable(statuses) {
+        return None;
+    }
+    Some(if showing_lyrics {
+        "offline — showing cached lyrics only"
+    } else {
+        "offline — no cached lyrics for this track"
+    })
+}
+
+/// One `provider: error` row per unreachable provider, for the notice tooltip
+#[must_use]
+pub fn unreachable_details(statuses: &ProviderStatuses) -> String {
+    statuses
+        .iter()
+        .filter_map(|(provider, status)| match status {
+            ProviderStatus::Unreachable { error } => Some(format!("{provider}: {error}")),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unreachable() -> ProviderStatus {
+        ProviderStatus::Unreachable {
+            error: "connection refused".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_all_unreachable_requires_every_provider_offline() {
+        let registry = ProviderStatusRegistry::new();
+        assert!(!all_unreachable(&registry.snapshot()));
+
+        registry.record("lrclib", unreachable());
+        registry.record("spotify_lyrics", ProviderStatus::Available);
+        assert!(!all_unreachable(&registry.snapshot()));
+
+        registry.record("spotify_lyrics", unreachable());
+        assert!(all_unreachable(&registry.snapshot()));
+
+        registry.record("lrclib", ProviderStatus::Available);
+        assert!(!all_unreachable(&registry.snapshot()));
+    }
+
+    #[test]
+    fn test_record_only_notifies_on_change() {
+        let registry = ProviderStatusRegistry::new();
+        let mut rx = registry.subscribe();
+
+        registry.record("lrclib", ProviderStatus::Available);
+        assert!(rx.has_changed().unwrap_or(false));
+        rx.mark_unchanged();
+
+        registry.record("lrclib", ProviderStatus::Available);
+        assert!(!rx.has_changed().unwrap_or(true));
+    }
+
+    #[test]
+    fn test_offline_notice() {
+        let registry = ProviderStatusRegistry::new();
+        registry.record("lrclib", ProviderStatus::Available);
+        assert_eq!(offline_notice(&registry.snapshot(), true), None);
+
+        registry.record("lrclib", unreachable());
+        assert_eq!(
+            offline_notice(&registry.snapshot(), true),
+            Some("offline — showing cached lyrics only")
+        );
+        assert_eq!(
+            offline_notice(&registry.snapshot(), false),
+            Some("offline — no cached lyrics for this track")
+        );
+        assert_eq!(
+            unreachable_details(&registry.snapshot()),
+            "lrclib: connection refused"
+        );
+    }
+}
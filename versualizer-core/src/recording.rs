@@ -0,0 +1,659 @@
+//! Session recording and replay for reproducing sync bugs.
+//!
+//! [`SessionRecorder`] subscribes to a [`SyncEngine`] and writes a timestamped JSONL trace of
+//! playback state snapshots, lyrics loads (including word timing), and line transitions as
+//! they happen. [`TracePlayer`] reads such a trace back and feeds it into a fresh
+//! [`SyncEngine`] with the original timing.
+
+use crate::error::Result;
+use crate::lrc::{LrcFile, LrcLine, LrcWord};
+use crate::playback::{PlaybackState, ProviderTrackIds, TrackInfo};
+use crate::source::MusicSource;
+use crate::sync::{SyncEngine, SyncEvent};
+use crate::time::DurationExt;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// File name prefix of session traces
+const TRACE_FILE_PREFIX: &str = "session-";
+/// File extension of session traces
+const TRACE_FILE_EXTENSION: &str = "jsonl";
+
+/// A single line of a session trace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// Milliseconds since recording started
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+/// Recorded events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// Snapshot of the playback state after a track change, seek, or position sync
+    State {
+        is_playing: bool,
+        track: Option<TraceTrack>,
+        position_ms: u64,
+        duration_ms: u64,
+    },
+    /// Lyrics were loaded for the current track
+    Lyrics { lines: Vec<TraceLine> },
+    /// No lyrics were found for the current track
+    NoLyrics,
+    /// The current lyric line changed, recorded when the line starts while playing.
+    /// Informational only, not replayed.
+    LineChanged { index: usize, text: String },
+    /// An error was emitted by the sync engine
+    Error {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fetch_id: Option<u64>,
+    },
+}
+
+/// Serializable track metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceTrack {
+    pub source: MusicSource,
+    pub source_track_id: String,
+    #[serde(default, skip_serializing_if = "ProviderTrackIds::is_empty")]
+    pub provider_ids: ProviderTrackIds,
+    pub name: String,
+    pub artist: String,
+    pub album: String,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_art_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isrc: Option<String>,
+}
+
+impl From<&TrackInfo> for TraceTrack {
+    fn from(track: &TrackInfo) -> Self {
+        Self {
+            source: track.source,
+            source_track_id: track.source_track_id.clone(),
+            provider_ids: track.provider_ids.clone(),
+            name: track.name.clone(),
+            artist: track.artist.clone(),
+            album: track.album.clone(),
+            duration_ms: track.duration.as_millis_u64(),
+            album_art_url: track.album_art_url.clone(),
+            isrc: track.isrc.clone(),
+        }
+    }
+}
+
+impl From<TraceTrack> for TrackInfo {
+    fn from(track: TraceTrack) -> Self {
+        Self {
+            source: track.source,
+            source_track_id: track.source_track_id,
+            provider_ids: track.provider_ids,
+            name: track.name,
+            artist: track.artist,
+            album: track.album,
+            duration: Duration::from_millis(track.duration_ms),
+            album_art_url: track.album_art_url,
+            isrc: track.isrc,
+        }
+    }
+}
+
+/// Serializable lyric line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceLine {
+    pub start_ms: u64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<TraceWord>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<String>,
+}
+
+/// Serializable word timing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceWord {
+    pub start_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u64>,
+    pub text: String,
+}
+
+impl From<&LrcLine> for TraceLine {
+    fn from(line: &LrcLine) -> Self {
+        Self {
+            start_ms: line.start_time.as_millis_u64(),
+            text: line.text.clone(),
+            words: line.words.as_ref().map(|words| {
+                words
+                    .iter()
+                    .map(|word| TraceWord {
+                        start_ms: word.start_time.as_millis_u64(),
+                        end_ms: word.end_time.map(|end| end.as_millis_u64()),
+                        text: word.text.clone(),
+                    })
+                    .collect()
+            }),
+            part: line.part.clone(),
+        }
+    }
+}
+
+impl From<TraceLine> for LrcLine {
+    fn from(line: TraceLine) -> Self {
+        Self {
+            start_time: Duration::from_millis(line.start_ms),
+            text: line.text,
+            words: line.words.map(|words| {
+                words
+                    .into_iter()
+                    .map(|word| LrcWord {
+                        start_time: Duration::from_millis(word.start_ms),
+                        end_time: word.end_ms.map(Duration::from_millis),
+                        text: word.text,
+                    })
+                    .collect()
+            }),
+            romanized: None,
+            part: line.part,
+        }
+    }
+}
+
+impl TraceEvent {
+    /// Build a state snapshot event from a playback state
+    #[must_use]
+    pub fn from_state(state: &PlaybackState) -> Self {
+        Self::State {
+            is_playing: state.is_playing,
+            track: state.track.as_ref().map(TraceTrack::from),
+            position_ms: state.position.as_millis_u64(),
+            duration_ms: state.duration.as_millis_u64(),
+        }
+    }
+
+    /// Build a lyrics event from an LRC file
+    #[must_use]
+    pub fn from_lyrics(lyrics: &LrcFile) -> Self {
+        Self::Lyrics {
+            lines: lyrics.lines.iter().map(TraceLine::from).collect(),
+        }
+    }
+}
+
+/// Records sync engine activity to a JSONL trace file
+pub struct SessionRecorder {
+    writer: BufWriter<tokio::fs::File>,
+    started_at: Instant,
+    lyrics: Option<LrcFile>,
+    current_line: Option<usize>,
+    /// When the next lyric line starts, while playing
+    next_line_at: Option<Instant>,
+}
+
+impl SessionRecorder {
+    /// Create a recorder writing to the given path, creating parent directories as needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trace file cannot be created.
+    pub async fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::File::create(path).await?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            started_at: Instant::now(),
+            lyrics: None,
+            current_line: None,
+            next_line_at: None,
+        })
+    }
+
+    /// Default path for a new trace: `{cache_dir}/versualizer/traces/session-{timestamp}.jsonl`
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        crate::paths::traces_dir().join(format!(
+            "{TRACE_FILE_PREFIX}{timestamp}.{TRACE_FILE_EXTENSION}"
+        ))
+    }
+
+    /// Delete the oldest session traces in `dir` so that at most `keep` remain.
+    /// Returns the number of deleted traces.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read or a trace cannot be deleted.
+    pub async fn prune_traces(dir: &Path, keep: usize) -> Result<usize> {
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Trace names embed a sortable timestamp, so name order is age order
+        let mut traces = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_trace = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(TRACE_FILE_PREFIX))
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == TRACE_FILE_EXTENSION);
+            if is_trace {
+                traces.push(path);
+            }
+        }
+        traces.sort();
+
+        let excess = traces.len().saturating_sub(keep);
+        for path in &traces[..excess] {
+            tokio::fs::remove_file(path).await?;
+            debug!("Deleted old session trace {:?}", path);
+        }
+        Ok(excess)
+    }
+
+    /// Record sync engine events until cancelled or the engine is dropped
+    pub async fn run(mut self, sync_engine: Arc<SyncEngine>, cancel_token: CancellationToken) {
+        let mut rx = sync_engine.subscribe();
+        info!("Session recording started");
+
+        loop {
+            let next_line_at = self.next_line_at;
+            let result = tokio::select! {
+                () = cancel_token.cancelled() => break,
+                () = tokio::time::sleep_until(next_line_at.unwrap_or_else(Instant::now)),
+                    if next_line_at.is_some() =>
+                {
+                    let position = sync_engine.current_position().await;
+                    self.record_line(position).await
+                }
+                event = rx.recv() => match event {
+                    Ok(event) => self.record_event(&event, &sync_engine).await,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        debug!("Session recorder lagged {} events", n);
+                        Ok(())
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            let result = match result {
+                Ok(()) => self.writer.flush().await.map_err(Into::into),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Failed to write session trace: {}", e);
+                break;
+            }
+            self.schedule_next_line(&sync_engine).await;
+        }
+
+        let _ = self.writer.flush().await;
+        info!("Session recording stopped");
+    }
+
+    async fn record_event(&mut self, event: &SyncEvent, sync_engine: &SyncEngine) -> Result<()> {
+        match event {
+            SyncEvent::LyricsLoaded { lyrics } => {
+                self.write(&TraceEvent::from_lyrics(lyrics)).await?;
+                self.lyrics = Some(lyrics.clone());
+                self.current_line = None;
+                let position = sync_engine.current_position().await;
+                self.record_line(position).await?;
+            }
+            // Plain lyrics have no lines to time, so they are traced as no lyrics
+            SyncEvent::UnsyncedLyricsLoaded { .. }
+            | SyncEvent::LyricsNotFound
+            | SyncEvent::TrackInstrumental
+            | SyncEvent::LyricsBlocked { .. } => {
+                self.lyrics = None;
+                self.current_line = None;
+                self.write(&TraceEvent::NoLyrics).await?;
+            }
+            SyncEvent::Error { message, fetch_id } => {
+                self.write(&TraceEvent::Error {
+                    message: message.clone(),
+                    fetch_id: *fetch_id,
+                })
+                .await?;
+            }
+            SyncEvent::TrackChanged { .. } | SyncEvent::PlaybackStopped | SyncEvent::AdPlaying => {
+                self.lyrics = None;
+                self.current_line = None;
+                self.write(&TraceEvent::from_state(&sync_engine.state().await))
+                    .await?;
+            }
+            SyncEvent::PlaybackStarted { .. }
+            | SyncEvent::PlaybackPaused { .. }
+            | SyncEvent::PlaybackResumed { .. }
+            | SyncEvent::PositionSync { .. }
+            | SyncEvent::SeekOccurred { .. } => {
+                let state = sync_engine.state().await;
+                self.write(&TraceEvent::from_state(&state)).await?;
+                self.record_line(state.position).await?;
+            }
+            // The trimmed lyrics were already traced when they loaded
+            SyncEvent::LyricsTrimmed { .. }
+            | SyncEvent::LyricsSource { .. }
+            | SyncEvent::LyricsRetrying { .. }
+            | SyncEvent::LyricsFetchStarted { .. }
+            | SyncEvent::LyricsFetchFailed { .. }
+            | SyncEvent::AnalysisLoaded { .. }
+            | SyncEvent::ConfigChanged { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Arm the line timer for the next line start, or disarm it while paused or without lyrics
+    async fn schedule_next_line(&mut self, sync_engine: &SyncEngine) {
+        self.next_line_at = match self.lyrics {
+            Some(ref lyrics) if sync_engine.is_playing().await => {
+                let position = sync_engine.current_position().await;
+                lyrics
+                    .time_until_next(position)
+                    .map(|delay| Instant::now() + delay)
+            }
+            _ => None,
+        };
+    }
+
+    /// Emit a line transition if the line at `position` differs from the last recorded one
+    async fn record_line(&mut self, position: Duration) -> Result<()> {
+        let Some(ref lyrics) = self.lyrics else {
+            return Ok(());
+        };
+        let index = lyrics.current_line_index(position);
+        if index == self.current_line {
+            return Ok(());
+        }
+        self.current_line = index;
+
+        if let Some(index) = index {
+            let text = lyrics.lines[index].text.clone();
+            self.write(&TraceEvent::LineChanged { index, text }).await?;
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, event: &TraceEvent) -> Result<()> {
+        let entry = TraceEntry {
+            elapsed_ms: self.started_at.elapsed().as_millis_u64(),
+            event: event.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.writer.write_all(&line).await?;
+        Ok(())
+    }
+}
+
+/// Replays a recorded session trace into a sync engine
+pub struct TracePlayer {
+    entries: Vec<TraceEntry>,
+}
+
+impl TracePlayer {
+    /// Load a trace from a JSONL file. Malformed lines are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn load(path: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<TraceEntry>(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping malformed trace line {}: {}", number + 1, e),
+            }
+        }
+
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Create a player from already-parsed entries
+    #[must_use]
+    pub const fn from_entries(entries: Vec<TraceEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Recorded entries in order
+    #[must_use]
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+
+    /// Replay the trace into `sync_engine`, sleeping between entries to match the original timing.
+    /// Stops early if cancelled.
+    pub async fn play(self, sync_engine: Arc<SyncEngine>, cancel_token: CancellationToken) {
+        let started_at = Instant::now();
+        info!("Replaying {} trace entries", self.entries.len());
+
+        for entry in self.entries {
+            let due = started_at + Duration::from_millis(entry.elapsed_ms);
+            tokio::select! {
+                () = cancel_token.cancelled() => return,
+                () = tokio::time::sleep_until(due) => {}
+            }
+            apply_event(&sync_engine, entry.event).await;
+        }
+
+        info!("Trace replay finished");
+    }
+}
+
+async fn apply_event(sync_engine: &SyncEngine, event: TraceEvent) {
+    match event {
+        TraceEvent::State {
+            is_playing,
+            track,
+            position_ms,
+            duration_ms,
+        } => {
+            sync_engine
+                .update_state(PlaybackState::new(
+                    is_playing,
+                    track.map(TrackInfo::from),
+                    Duration::from_millis(position_ms),
+                    Duration::from_millis(duration_ms),
+                ))
+                .await;
+        }
+        TraceEvent::Lyrics { lines } => {
+            let lines = lines.into_iter().map(LrcLine::from).collect();
+            sync_engine
+                .set_lyrics(LrcFile {
+                    metadata: crate::lrc::LrcMetadata::default(),
+                    lines,
+                })
+                .await;
+        }
+        TraceEvent::NoLyrics => sync_engine.set_no_lyrics().await,
+        TraceEvent::Error {
+            message,
+            fetch_id: Some(fetch_id),
+        } => sync_engine.emit_fetch_error(fetch_id, message),
+        TraceEvent::Error {
+            message,
+            fetch_id: None,
+        } => sync_engine.emit_error(message),
+        TraceEvent::LineChanged { .. } => {}
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn test_track() -> TrackInfo {
+        TrackInfo::new(
+            MusicSource::Spotify,
+            "track123",
+            "Song",
+            "Artist",
+            "Album",
+            Duration::from_mins(3),
+        )
+        .with_provider_id("spotify", "track123")
+    }
+
+    #[test]
+    fn test_trace_entry_roundtrip() {
+        let state = PlaybackState::new(
+            true,
+            Some(test_track()),
+            Duration::from_millis(1500),
+            Duration::from_mins(3),
+        );
+        let entry = TraceEntry {
+            elapsed_ms: 42,
+            event: TraceEvent::from_state(&state),
+        };
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r#""type":"state""#));
+        let parsed: TraceEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, entry);
+
+        let track = match parsed.event {
+            TraceEvent::State { track, .. } => track.map(TrackInfo::from),
+            _ => None,
+        };
+        assert_eq!(track, Some(test_track()));
+    }
+
+    #[test]
+    fn test_lyrics_event_from_lrc() {
+        let lrc = LrcFile::parse("[00:01.00]First\n[00:02.50]Second").unwrap();
+        let lines = match TraceEvent::from_lyrics(&lrc) {
+            TraceEvent::Lyrics { lines } => lines,
+            _ => Vec::new(),
+        };
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].start_ms, 2500);
+        assert_eq!(lines[1].text, "Second");
+        assert!(lines[1].words.is_none());
+    }
+
+    #[test]
+    fn test_lyrics_event_keeps_word_timing() {
+        let line = LrcLine {
+            start_time: Duration::from_secs(1),
+            text: "Hello world".into(),
+            words: Some(vec![
+                LrcWord {
+                    start_time: Duration::from_secs(1),
+                    end_time: Some(Duration::from_millis(1400)),
+                    text: "Hello".into(),
+                },
+                LrcWord {
+                    start_time: Duration::from_millis(1500),
+                    end_time: None,
+                    text: "world".into(),
+                },
+            ]),
+            romanized: None,
+            part: None,
+        };
+
+        let traced = TraceLine::from(&line);
+        let json = serde_json::to_string(&traced).unwrap();
+        assert!(json.contains(r#""end_ms":1400"#));
+        let parsed: TraceLine = serde_json::from_str(&json).unwrap();
+        let restored = LrcLine::from(parsed);
+        assert_eq!(TraceLine::from(&restored), traced);
+        assert_eq!(restored.words.unwrap()[1].end_time, None);
+    }
+
+    #[tokio::test]
+    async fn test_prune_traces_keeps_newest() {
+        let dir =
+            std::env::temp_dir().join(format!("versualizer-traces-test-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        for name in [
+            "session-20260101-000000.jsonl",
+            "session-20260102-000000.jsonl",
+            "session-20260103-000000.jsonl",
+            "notes.txt",
+        ] {
+            tokio::fs::write(dir.join(name), "").await.unwrap();
+        }
+
+        assert_eq!(SessionRecorder::prune_traces(&dir, 2).await.unwrap(), 1);
+        assert!(!dir.join("session-20260101-000000.jsonl").exists());
+        assert!(dir.join("session-20260103-000000.jsonl").exists());
+        assert!(dir.join("notes.txt").exists());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+        assert_eq!(SessionRecorder::prune_traces(&dir, 2).await.unwrap(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_replay_into_sync_engine() {
+        let engine = SyncEngine::new();
+        let mut rx = engine.subscribe();
+
+        let player = TracePlayer::from_entries(vec![
+            TraceEntry {
+                elapsed_ms: 0,
+                event: TraceEvent::State {
+                    is_playing: true,
+                    track: Some(TraceTrack::from(&test_track())),
+                    position_ms: 0,
+                    duration_ms: 180_000,
+                },
+            },
+            TraceEntry {
+                elapsed_ms: 100,
+                event: TraceEvent::Lyrics {
+                    lines: vec![TraceLine {
+                        start_ms: 1000,
+                        text: "Hello".into(),
+                        words: None,
+                        part: None,
+                    }],
+                },
+            },
+        ]);
+        player.play(engine.clone(), CancellationToken::new()).await;
+
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            SyncEvent::TrackChanged { .. }
+        ));
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            SyncEvent::PlaybackResumed { .. }
+        ));
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            SyncEvent::LyricsLoaded { .. }
+        ));
+        assert_eq!(engine.lyrics().await.unwrap().lines[0].text, "Hello");
+    }
+}
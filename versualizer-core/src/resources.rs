@@ -0,0 +1,149 @@
+//! Lookup of resource files (default theme, icons, fonts) shipped with the app.
+//!
+//! Resources are compiled into the app, but store and package-manager bundles also ship
+//! them as files in the platform's bundle location. A resource is looked up in the user's
+//! config directory first and then in the bundle, so users can still override anything
+//! the bundle ships. Callers fall back to the compiled-in copy when neither has it.
+
+use crate::paths::{CONFIG_DIR_NAME, config_dir};
+use std::path::{Path, PathBuf};
+
+/// Directory of extra fonts, relative to a resource directory
+pub const FONTS_DIR_NAME: &str = "fonts";
+
+/// Font file extensions picked up from font directories
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "woff2"];
+
+/// Where platform bundles put resources, relative to the running executable:
+/// - macOS app bundle: `Versualizer.app/Contents/Resources/`
+/// - Windows MSIX or installer: next to the executable
+/// - Linux packages: `/usr/lib/versualizer/` or `/usr/share/versualizer/`
+#[must_use]
+pub fn bundle_resource_dirs(exe: &Path) -> Vec<PathBuf> {
+    let Some(exe_dir) = exe.parent() else {
+        return Vec::new();
+    };
+    if cfg!(target_os = "macos") {
+        vec![exe_dir.join("..").join("Resources")]
+    } else if cfg!(windows) {
+        vec![exe_dir.to_path_buf()]
+    } else {
+        vec![
+            exe_dir.join("..").join("lib").join(CONFIG_DIR_NAME),
+            exe_dir.join("..").join("share").join(CONFIG_DIR_NAME),
+        ]
+    }
+}
+
+/// Directories searched for resources, in order of precedence: the user's config
+/// directory, then the bundle locations when `include_bundle` is set
+#[must_use]
+pub fn resource_dirs(include_bundle: bool) -> Vec<PathBuf> {
+    let mut dirs = vec![config_dir()];
+    if include_bundle && let Ok(exe) = std::env::current_exe() {
+        dirs.extend(bundle_resource_dirs(&exe));
+    }
+    dirs
+}
+
+/// The first existing file at `relative` (e.g. `icons/64x64.png`) under `dirs`
+#[must_use]
+pub fn find_resource(dirs: &[PathBuf], relative: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .map(|dir| dir.join(relative))
+        .find(|path| path.is_file())
+}
+
+/// Font files in the `fonts` directory of each of `dirs`. When several directories have a
+/// font with the same file name, the one from the earlier directory wins.
+#[must_use]
+pub fn font_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut fonts: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir.join(FONTS_DIR_NAME)) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+            })
+            .filter(|path| {
+                !fonts
+                    .iter()
+                    .any(|font| font.file_name() == path.file_name())
+            })
+            .collect();
+        found.sort();
+        fonts.extend(found);
+    }
+    fonts
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "versualizer-resources-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join(FONTS_DIR_NAME)).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_find_resource_prefers_earlier_dirs() {
+        let user = temp_dir("user");
+        let bundle = temp_dir("bundle");
+        std::fs::write(bundle.join("theme.css"), "bundle").unwrap();
+        let dirs = vec![user.clone(), bundle.clone()];
+        assert_eq!(
+            find_resource(&dirs, "theme.css"),
+            Some(bundle.join("theme.css"))
+        );
+
+        std::fs::write(user.join("theme.css"), "user").unwrap();
+        assert_eq!(
+            find_resource(&dirs, "theme.css"),
+            Some(user.join("theme.css"))
+        );
+        assert!(find_resource(&dirs, "missing.png").is_none());
+
+        std::fs::remove_dir_all(user).unwrap();
+        std::fs::remove_dir_all(bundle).unwrap();
+    }
+
+    #[test]
+    fn test_font_files_are_overridden_by_name() {
+        let user = temp_dir("user-fonts");
+        let bundle = temp_dir("bundle-fonts");
+        std::fs::write(user.join("fonts/Inter.ttf"), "").unwrap();
+        std::fs::write(bundle.join("fonts/Inter.ttf"), "").unwrap();
+        std::fs::write(bundle.join("fonts/Noto.OTF"), "").unwrap();
+        std::fs::write(bundle.join("fonts/README.md"), "").unwrap();
+
+        let fonts = font_files(&[user.clone(), bundle.clone()]);
+        assert_eq!(
+            fonts,
+            vec![user.join("fonts/Inter.ttf"), bundle.join("fonts/Noto.OTF")]
+        );
+
+        std::fs::remove_dir_all(user).unwrap();
+        std::fs::remove_dir_all(bundle).unwrap();
+    }
+
+    #[test]
+    fn test_bundle_resource_dirs_are_relative_to_exe() {
+        let exe = Path::new("/opt/app/bin/versualizer");
+        let dirs = bundle_resource_dirs(exe);
+        assert!(!dirs.is_empty());
+        assert!(dirs.iter().all(|dir| dir.starts_with("/opt/app/bin")));
+        assert!(bundle_resource_dirs(Path::new("")).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! Table-based romanization of Japanese kana (Hepburn) and Korean hangul (Revised Romanization).
+//!
+//! Characters without a romanization, such as kanji and Latin text, are kept as they are.
+//! The hangul conversion works syllable by syllable and does not apply the sound change
+//! rules between syllables. Chinese has no romanization yet, since pinyin needs a
+//! per-character dictionary rather than a table.
+
+/// Offset between a katakana character and its hiragana counterpart
+const KATAKANA_OFFSET: u32 = 0x60;
+
+/// First and last precomposed hangul syllable
+const HANGUL_FIRST: u32 = 0xAC00;
+const HANGUL_LAST: u32 = 0xD7A3;
+
+const HANGUL_INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+const HANGUL_MEDIALS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+const HANGUL_FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// Romanize the kana and hangul in `text`
+#[must_use]
+pub fn romanize(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(katakana_to_hiragana).collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            'っ' => {
+                // Double the consonant of the following kana
+                if let Some(next) = next.and_then(kana_romaji) {
+                    if next.starts_with("ch") {
+                        out.push('t');
+                    } else if let Some(consonant) = next.chars().next().filter(|c| !is_vowel(*c)) {
+                        out.push(consonant);
+                    }
+                }
+            }
+            'ー' => {
+                if let Some(vowel) = out.chars().last().filter(|c| is_vowel(*c)) {
+                    out.push(vowel);
+                }
+            }
+            'ん' => {
+                out.push('n');
+                // Keep "n'a" distinct from "na"
+                if next
+                    .and_then(kana_romaji)
+                    .is_some_and(|r| r.starts_with(|c: char| is_vowel(c) || c == 'y'))
+                {
+                    out.push('\'');
+                }
+            }
+            _ => {
+                if let Some(romaji) = kana_romaji(c) {
+                    if let Some(glide) = next.and_then(small_y_vowel)
+                        && let Some(stem) = romaji.strip_suffix('i')
+                        && !stem.is_empty()
+                    {
+                        // きゃ -> kya, しゃ -> sha, ちゃ -> cha, じゃ -> ja
+                        out.push_str(stem);
+                        if !matches!(stem, "sh" | "ch" | "j") {
+                            out.push('y');
+                        }
+                        out.push(glide);
+                        i += 2;
+                        continue;
+                    }
+                    out.push_str(romaji);
+                } else if let Some(romaja) = hangul_romaja(c) {
+                    out.push_str(&romaja);
+                } else {
+                    out.push(c);
+                }
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Writing system of a lyric line, which decides how it is romanized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineScript {
+    Japanese,
+    Korean,
+    Chinese,
+}
+
+impl LineScript {
+    /// Detect the script of `text`. Kanji are shared with Chinese, so a line with any kana
+    /// is Japanese, and one with only Han characters is Chinese.
+    #[must_use]
+    pub fn detect(text: &str) -> Option<Self> {
+        let mut han = false;
+        for c in text.chars() {
+            match u32::from(c) {
+                0x3040..=0x30FF => return Some(Self::Japanese),
+                0x1100..=0x11FF | 0x3130..=0x318F | HANGUL_FIRST..=HANGUL_LAST => {
+                    return Some(Self::Korean);
+                }
+                0x3400..=0x4DBF | 0x4E00..=0x9FFF => han = true,
+                _ => {}
+            }
+        }
+        han.then_some(Self::Chinese)
+    }
+}
+
+/// Romanized reading of a lyric line to show alongside it, or `None` if the line has
+/// nothing that can be romanized
+#[must_use]
+pub fn romanize_line(text: &str) -> Option<String> {
+    match LineScript::detect(text)? {
+        LineScript::Japanese | LineScript::Korean => Some(romanize(text)),
+        LineScript::Chinese => None,
+    }
+}
+
+/// Map katakana to hiragana, leaving every other character unchanged
+#[must_use]
+pub fn katakana_to_hiragana(c: char) -> char {
+    if ('ァ'..='ヶ').contains(&c) {
+        char::from_u32(u32::from(c) - KATAKANA_OFFSET).unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+/// Map hiragana to katakana, leaving every other character unchanged
+#[must_use]
+pub fn hiragana_to_katakana(c: char) -> char {
+    if ('ぁ'..='ゖ').contains(&c) {
+        char::from_u32(u32::from(c) + KATAKANA_OFFSET).unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+const fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+/// Vowel of a small ゃ/ゅ/ょ
+const fn small_y_vowel(c: char) -> Option<char> {
+    match c {
+        'ゃ' => Some('a'),
+        'ゅ' => Some('u'),
+        'ょ' => Some('o'),
+        _ => None,
+    }
+}
+
+/// Hepburn romanization of a single hiragana character
+const fn kana_romaji(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+/// Revised Romanization of a single precomposed hangul syllable
+fn hangul_romaja(c: char) -> Option<String> {
+    let code = u32::from(c);
+    if !(HANGUL_FIRST..=HANGUL_LAST).contains(&code) {
+        return None;
+    }
+    let index = (code - HANGUL_FIRST) as usize;
+    let initial = HANGUL_INITIALS[index / 588];
+    let medial = HANGUL_MEDIALS[(index % 588) / 28];
+    let last = HANGUL_FINALS[index % 28];
+    Some(format!("{initial}{medial}{last}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_romanize_kana() {
+        assert_eq!(romanize("さくら"), "sakura");
+        assert_eq!(romanize("きょうは"), "kyouha");
+        assert_eq!(romanize("しゃしん"), "shashin");
+        assert_eq!(romanize("がっこう"), "gakkou");
+        assert_eq!(romanize("まっちゃ"), "matcha");
+        assert_eq!(romanize("コーヒー"), "koohii");
+        assert_eq!(romanize("こんや"), "kon'ya");
+    }
+
+    #[test]
+    fn test_romanize_keeps_other_text() {
+        assert_eq!(romanize("君のこと love"), "君nokoto love");
+    }
+
+    #[test]
+    fn test_romanize_hangul() {
+        assert_eq!(romanize("사랑해"), "saranghae");
+        assert_eq!(romanize("한국어 노래"), "hangukeo norae");
+    }
+
+    #[test]
+    fn test_detect_line_script() {
+        assert_eq!(LineScript::detect("君のこと"), Some(LineScript::Japanese));
+        assert_eq!(LineScript::detect("사랑해 baby"), Some(LineScript::Korean));
+        assert_eq!(LineScript::detect("我爱你"), Some(LineScript::Chinese));
+        assert_eq!(LineScript::detect("Hello"), None);
+
+        assert_eq!(romanize_line("さくら").as_deref(), Some("sakura"));
+        assert_eq!(romanize_line("我爱你"), None);
+        assert_eq!(romanize_line("Hello"), None);
+    }
+
+    #[test]
+    fn test_kana_conversion() {
+        assert_eq!(katakana_to_hiragana('カ'), 'か');
+        assert_eq!(hiragana_to_katakana('か'), 'カ');
+        assert_eq!(hiragana_to_katakana('a'), 'a');
+    }
+}
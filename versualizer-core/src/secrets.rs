@@ -0,0 +1,481 @@
+//! Storage for secrets such as encryption keys and service credentials.
+//!
+//! [`SecretStore`] abstracts where secrets live so that call sites do not care whether a
+//! value comes from the OS keyring or a local file. [`KeyringSecretStore`] is the default and
+//! keeps secrets in the Credential Manager on Windows, the login keychain on macOS or the
+//! Secret Service on Linux. [`FileSecretStore`] is an explicit fallback for systems without a
+//! keyring: it keeps secrets in a JSON file in the config directory, readable only by the
+//! current user on Unix.
+
+use crate::config::SecretStoreKind;
+use crate::error::{CoreError, Result};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Service name that secrets are filed under in the OS keyring
+pub const KEYRING_SERVICE: &str = "versualizer";
+
+/// A key-value store for secrets
+pub trait SecretStore: Send + Sync {
+    /// Get a secret by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing store cannot be read.
+    fn get(&self, name: &str) -> Result<Option<String>>;
+
+    /// Store a secret, replacing any previous value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing store cannot be written.
+    fn set(&self, name: &str, value: &str) -> Result<()>;
+
+    /// Delete a secret. Deleting a missing secret is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backing store cannot be written.
+    fn delete(&self, name: &str) -> Result<()>;
+}
+
+/// Secret store backed by the OS keyring.
+///
+/// Uses the Credential Manager on Windows, the login keychain on macOS and the Secret Service
+/// (through libsecret's `secret-tool`) on Linux and the BSDs. Other platforms have no keyring
+/// backend yet and every call fails; use [`FileSecretStore`] there.
+pub struct KeyringSecretStore {
+    service: String,
+}
+
+impl KeyringSecretStore {
+    /// Store secrets under [`KEYRING_SERVICE`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_service(KEYRING_SERVICE)
+    }
+
+    /// Store secrets under a specific keyring service name
+    #[must_use]
+    pub fn with_service(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    /// Whether a keyring backend is usable on this system
+    #[must_use]
+    pub fn is_available() -> bool {
+        keyring::is_available()
+    }
+}
+
+impl Default for KeyringSecretStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretStore for KeyringSecretStore {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        keyring::get(&self.service, name)
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        keyring::set(&self.service, name, value)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        keyring::delete(&self.service, name)
+    }
+}
+
+impl SecretStoreKind {
+    /// Open the store this kind names, at its default location
+    #[must_use]
+    pub fn open(self) -> Arc<dyn SecretStore> {
+        match self {
+            Self::Keyring => Arc::new(KeyringSecretStore::new()),
+            Self::File => Arc::new(FileSecretStore::new()),
+        }
+    }
+}
+
+fn keyring_error(reason: impl Into<String>) -> CoreError {
+    CoreError::SecretStoreError {
+        reason: reason.into(),
+    }
+}
+
+/// Login keychain backend
+#[cfg(target_os = "macos")]
+mod keyring {
+    use super::keyring_error;
+    use crate::error::Result;
+    use security_framework::passwords::{
+        delete_generic_password, get_generic_password, set_generic_password,
+    };
+
+    /// `errSecItemNotFound`
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub const fn is_available() -> bool {
+        true
+    }
+
+    pub fn get(service: &str, name: &str) -> Result<Option<String>> {
+        match get_generic_password(service, name) {
+            Ok(value) => String::from_utf8(value)
+                .map(Some)
+                .map_err(|_| keyring_error("stored secret is not valid UTF-8")),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(keyring_error(e.to_string())),
+        }
+    }
+
+    pub fn set(service: &str, name: &str, value: &str) -> Result<()> {
+        set_generic_password(service, name, value.as_bytes())
+            .map_err(|e| keyring_error(e.to_string()))
+    }
+
+    pub fn delete(service: &str, name: &str) -> Result<()> {
+        match delete_generic_password(service, name) {
+            Err(e) if e.code() != ITEM_NOT_FOUND => Err(keyring_error(e.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Secret Service backend, driven through libsecret's `secret-tool` so that no D-Bus client
+/// has to be linked in
+#[cfg(all(unix, not(target_os = "macos")))]
+mod keyring {
+    use super::keyring_error;
+    use crate::error::Result;
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    const SECRET_TOOL: &str = "secret-tool";
+
+    /// Whether `secret-tool` is installed and can reach a Secret Service. A lookup of a
+    /// missing item fails silently, while an unreachable service reports an error.
+    pub fn is_available() -> bool {
+        lookup(super::KEYRING_SERVICE, "availability-probe")
+            .is_ok_and(|output| output.status.success() || output.stderr.is_empty())
+    }
+
+    pub fn get(service: &str, name: &str) -> Result<Option<String>> {
+        let output = lookup(service, name)?;
+        if output.status.success() {
+            let value = String::from_utf8(output.stdout)
+                .map_err(|_| keyring_error("stored secret is not valid UTF-8"))?;
+            return Ok(Some(value.trim_end_matches('\n').to_string()));
+        }
+        if output.stderr.is_empty() {
+            return Ok(None);
+        }
+        Err(failure(&output))
+    }
+
+    pub fn set(service: &str, name: &str, value: &str) -> Result<()> {
+        // The secret goes through stdin so it never shows up in the process list
+        let mut child = Command::new(SECRET_TOOL)
+            .args(["store", "--label", &format!("Versualizer {name}")])
+            .args(["service", service, "account", name])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(&e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(value.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(failure(&output))
+        }
+    }
+
+    pub fn delete(service: &str, name: &str) -> Result<()> {
+        let output = Command::new(SECRET_TOOL)
+            .args(["clear", "service", service, "account", name])
+            .output()
+            .map_err(|e| spawn_error(&e))?;
+        if output.status.success() || output.stderr.is_empty() {
+            Ok(())
+        } else {
+            Err(failure(&output))
+        }
+    }
+
+    fn lookup(service: &str, name: &str) -> Result<Output> {
+        Command::new(SECRET_TOOL)
+            .args(["lookup", "service", service, "account", name])
+            .output()
+            .map_err(|e| spawn_error(&e))
+    }
+
+    fn spawn_error(e: &std::io::Error) -> crate::error::CoreError {
+        keyring_error(format!(
+            "could not run {SECRET_TOOL} ({e}); install libsecret-tools or set secret_store = \"file\""
+        ))
+    }
+
+    fn failure(output: &Output) -> crate::error::CoreError {
+        keyring_error(format!(
+            "{SECRET_TOOL} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Windows Credential Manager backend, keeping each secret as a generic credential
+/// targeted at `service/name`
+#[cfg(windows)]
+#[allow(unsafe_code)]
+mod keyring {
+    use super::keyring_error;
+    use crate::error::{CoreError, Result};
+    use windows_sys::Win32::Foundation::{ERROR_NOT_FOUND, GetLastError};
+    use windows_sys::Win32::Security::Credentials::{
+        CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC, CREDENTIALW, CredDeleteW, CredFree,
+        CredReadW, CredWriteW,
+    };
+
+    pub const fn is_available() -> bool {
+        true
+    }
+
+    pub fn get(service: &str, name: &str) -> Result<Option<String>> {
+        let target = wide(&format!("{service}/{name}"));
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        // SAFETY: `target` is NUL-terminated and outlives the call, and `credential` is a
+        // valid place for the result
+        if unsafe { CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &raw mut credential) } == 0 {
+            return match last_error() {
+                ERROR_NOT_FOUND => Ok(None),
+                code => Err(os_error("read", code)),
+            };
+        }
+        // SAFETY: CredReadW succeeded, so `credential` points to a credential whose blob
+        // holds `CredentialBlobSize` bytes. They are copied out before it is freed, once.
+        let value = unsafe {
+            let blob = &*credential;
+            let value = match usize::try_from(blob.CredentialBlobSize) {
+                Ok(len) if len > 0 && !blob.CredentialBlob.is_null() => {
+                    std::slice::from_raw_parts(blob.CredentialBlob, len).to_vec()
+                }
+                _ => Vec::new(),
+            };
+            CredFree(credential.cast_const().cast());
+            value
+        };
+        String::from_utf8(value)
+            .map(Some)
+            .map_err(|_| keyring_error("stored secret is not valid UTF-8"))
+    }
+
+    pub fn set(service: &str, name: &str, value: &str) -> Result<()> {
+        let mut target = wide(&format!("{service}/{name}"));
+        let mut user = wide(name);
+        let mut blob = value.as_bytes().to_vec();
+        let credential = CREDENTIALW {
+            Type: CRED_TYPE_GENERIC,
+            TargetName: target.as_mut_ptr(),
+            UserName: user.as_mut_ptr(),
+            CredentialBlobSize: u32::try_from(blob.len())
+                .map_err(|_| keyring_error("secret is too large"))?,
+            CredentialBlob: blob.as_mut_ptr(),
+            Persist: CRED_PERSIST_LOCAL_MACHINE,
+            ..Default::default()
+        };
+        // SAFETY: the buffers `credential` points into outlive the call, and CredWriteW only
+        // reads them
+        if unsafe { CredWriteW(&raw const credential, 0) } == 0 {
+            return Err(os_error("write", last_error()));
+        }
+        Ok(())
+    }
+
+    pub fn delete(service: &str, name: &str) -> Result<()> {
+        let target = wide(&format!("{service}/{name}"));
+        // SAFETY: `target` is NUL-terminated and outlives the call
+        if unsafe { CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) } == 0 {
+            return match last_error() {
+                ERROR_NOT_FOUND => Ok(()),
+                code => Err(os_error("delete", code)),
+            };
+        }
+        Ok(())
+    }
+
+    /// `text` as a NUL-terminated UTF-16 string
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn last_error() -> u32 {
+        // SAFETY: GetLastError has no preconditions
+        unsafe { GetLastError() }
+    }
+
+    fn os_error(action: &str, code: u32) -> CoreError {
+        keyring_error(format!(
+            "Credential Manager could not {action} the secret (error {code})"
+        ))
+    }
+}
+
+/// No keyring backend on this platform yet
+#[cfg(not(any(unix, windows)))]
+mod keyring {
+    use super::keyring_error;
+    use crate::error::Result;
+
+    const UNSUPPORTED: &str =
+        "the OS keyring is not supported on this platform; set secret_store = \"file\"";
+
+    pub const fn is_available() -> bool {
+        false
+    }
+
+    pub fn get(_service: &str, _name: &str) -> Result<Option<String>> {
+        Err(keyring_error(UNSUPPORTED))
+    }
+
+    pub fn set(_service: &str, _name: &str, _value: &str) -> Result<()> {
+        Err(keyring_error(UNSUPPORTED))
+    }
+
+    pub fn delete(_service: &str, _name: &str) -> Result<()> {
+        Err(keyring_error(UNSUPPORTED))
+    }
+}
+
+/// Secret store backed by a JSON file. Only meant as a fallback where no OS keyring is
+/// available, since the secrets sit on disk next to the data they protect.
+pub struct FileSecretStore {
+    path: PathBuf,
+}
+
+impl FileSecretStore {
+    /// Use the default secrets file (`~/.config/versualizer/.secrets.json`)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::open(crate::paths::secrets_path())
+    }
+
+    /// Use a secrets file at a specific path
+    #[must_use]
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn read_all(&self) -> Result<BTreeMap<String, String>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_all(&self, secrets: &BTreeMap<String, String>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = open_private(&self.path)?;
+        file.write_all(serde_json::to_string_pretty(secrets)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl Default for FileSecretStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn get(&self, name: &str) -> Result<Option<String>> {
+        Ok(self.read_all()?.remove(name))
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<()> {
+        let mut secrets = self.read_all()?;
+        secrets.insert(name.to_string(), value.to_string());
+        self.write_all(&secrets)
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let mut secrets = self.read_all()?;
+        if secrets.remove(name).is_some() {
+            self.write_all(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Open a file for writing, truncated, readable and writable by its owner only. New files
+/// are created with that mode so the secrets are never briefly world-readable.
+#[cfg(unix)]
+fn open_private(path: &std::path::Path) -> Result<std::fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // A file left over from an older version may have been created with a wider mode
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn open_private(path: &std::path::Path) -> Result<std::fs::File> {
+    Ok(std::fs::File::create(path)?)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_secret_store_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("versualizer-secrets-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = FileSecretStore::open(&path);
+
+        assert_eq!(store.get("key").unwrap(), None);
+        store.set("key", "value").unwrap();
+        assert_eq!(store.get("key").unwrap().as_deref(), Some("value"));
+        store.delete("key").unwrap();
+        assert_eq!(store.get("key").unwrap(), None);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_secret_store_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-secrets-mode-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        FileSecretStore::open(&path).set("key", "value").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
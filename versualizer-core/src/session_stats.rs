@@ -0,0 +1,208 @@
+//! Listening session statistics, computed from the persistent event log.
+
+use crate::event_log::{LoggedEvent, SESSION_STARTED};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Summary of a listening session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Tracks that started playing, including ones skipped straight away
+    pub tracks_played: usize,
+    /// Tracks whose lyrics lookup finished, with or without lyrics
+    pub lyrics_lookups: usize,
+    /// Lookups that found synced lyrics
+    pub lyrics_found: usize,
+    /// Time spent playing tracks with synced lyrics on screen
+    pub sing_along_time: Duration,
+    /// The provider that supplied lyrics for the most tracks, with its track count
+    pub top_provider: Option<(String, usize)>,
+}
+
+impl SessionStats {
+    /// Compute statistics from logged events, oldest first.
+    ///
+    /// Only the first lyrics lookup and source after a track starts count; later ones are
+    /// the user switching between cached alternatives.
+    #[must_use]
+    pub fn from_events(events: &[LoggedEvent]) -> Self {
+        let mut stats = Self::default();
+        let mut providers: HashMap<&str, usize> = HashMap::new();
+        let mut playing = false;
+        let mut showing_lyrics = false;
+        let mut lookup_pending = false;
+        let mut source_pending = false;
+        let mut last_time: Option<DateTime<Utc>> = None;
+
+        for event in events {
+            if playing
+                && showing_lyrics
+                && let Some(last) = last_time
+            {
+                stats.sing_along_time += (event.recorded_at - last).to_std().unwrap_or_default();
+            }
+            last_time = Some(event.recorded_at);
+
+            match event.kind.as_str() {
+                // A new session never continues the last one, even if the app crashed mid-track
+                SESSION_STARTED | "playback_stopped" => {
+                    playing = false;
+                    showing_lyrics = false;
+                }
+                "playback_started" | "track_changed" => {
+                    stats.tracks_played += 1;
+                    // A track change is followed by a pause or resume with its play state
+                    playing |= event.kind == "playback_started";
+                    showing_lyrics = false;
+                    lookup_pending = true;
+                    source_pending = true;
+                }
+                "playback_paused" => playing = false,
+                "playback_resumed" => playing = true,
+                "lyrics_loaded" => {
+                    showing_lyrics = true;
+                    if lookup_pending {
+                        stats.lyrics_lookups += 1;
+                        stats.lyrics_found += 1;
+                        lookup_pending = false;
+                    }
+                }
+                "unsynced_lyrics_loaded" | "lyrics_not_found" => {
+                    showing_lyrics = false;
+                    if lookup_pending {
+                        stats.lyrics_lookups += 1;
+                        lookup_pending = false;
+                    }
+                }
+                "lyrics_source" if source_pending => {
+                    *providers.entry(event.detail.as_str()).or_default() += 1;
+                    source_pending = false;
+                }
+                _ => {}
+            }
+        }
+
+        stats.top_provider = providers
+            .into_iter()
+            // Ties go to the alphabetically first provider so the summary is stable
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(provider, count)| (provider.to_string(), count));
+        stats
+    }
+
+    /// Percentage of lookups that found synced lyrics, or `None` if nothing was looked up
+    #[must_use]
+    pub fn hit_rate_percent(&self) -> Option<usize> {
+        (self.lyrics_lookups > 0).then(|| self.lyrics_found * 100 / self.lyrics_lookups)
+    }
+}
+
+impl fmt::Display for SessionStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Tracks played: {}", self.tracks_played)?;
+        match self.hit_rate_percent() {
+            Some(percent) => writeln!(
+                f,
+                "Synced lyrics: {} of {} tracks ({percent}%)",
+                self.lyrics_found, self.lyrics_lookups
+            )?,
+            None => writeln!(f, "Synced lyrics: none looked up")?,
+        }
+        writeln!(
+            f,
+            "Sing-along time: {}",
+            format_duration(self.sing_along_time)
+        )?;
+        match &self.top_provider {
+            Some((provider, count)) => write!(f, "Top provider: {provider} ({count} tracks)"),
+            None => write!(f, "Top provider: none"),
+        }
+    }
+}
+
+/// Format a duration as `1h 02m 03s`, `2m 03s` or `3s`
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m {seconds:02}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn events(entries: &[(i64, &str, &str)]) -> Vec<LoggedEvent> {
+        entries
+            .iter()
+            .zip(1..)
+            .map(|(&(secs, kind, detail), id)| LoggedEvent {
+                id,
+                recorded_at: DateTime::from_timestamp(secs, 0).unwrap(),
+                kind: kind.to_string(),
+                detail: detail.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_session_stats() {
+        let stats = SessionStats::from_events(&events(&[
+            (0, SESSION_STARTED, ""),
+            (10, "playback_started", "A - One at 0ms"),
+            (11, "lyrics_loaded", "40 lines"),
+            (11, "lyrics_source", "lrclib"),
+            (71, "playback_paused", "at 60000ms"),
+            (100, "playback_resumed", "at 60000ms"),
+            // Switching sources does not count as another lookup
+            (110, "lyrics_loaded", "38 lines"),
+            (110, "lyrics_source", "spotify"),
+            (130, "track_changed", "A - Two at 0ms"),
+            (130, "playback_resumed", "at 0ms"),
+            (131, "lyrics_not_found", ""),
+            (200, "track_changed", "B - Three at 0ms"),
+            (200, "playback_resumed", "at 0ms"),
+            (201, "lyrics_loaded", "20 lines"),
+            (201, "lyrics_source", "lrclib"),
+            (261, "playback_stopped", ""),
+            (900, SESSION_STARTED, ""),
+        ]));
+
+        assert_eq!(stats.tracks_played, 3);
+        assert_eq!(stats.lyrics_lookups, 3);
+        assert_eq!(stats.lyrics_found, 2);
+        assert_eq!(stats.hit_rate_percent(), Some(66));
+        // 60s before the pause, 30s after it and 60s of the third track
+        assert_eq!(stats.sing_along_time, Duration::from_secs(150));
+        assert_eq!(stats.top_provider, Some(("lrclib".to_string(), 2)));
+    }
+
+    #[test]
+    fn test_display() {
+        let stats = SessionStats {
+            tracks_played: 4,
+            lyrics_lookups: 4,
+            lyrics_found: 3,
+            sing_along_time: Duration::from_secs(3723),
+            top_provider: Some(("lrclib".to_string(), 3)),
+        };
+        assert_eq!(
+            stats.to_string(),
+            "Tracks played: 4\nSynced lyrics: 3 of 4 tracks (75%)\n\
+             Sing-along time: 1h 02m 03s\nTop provider: lrclib (3 tracks)"
+        );
+        assert!(
+            SessionStats::default()
+                .to_string()
+                .contains("Synced lyrics: none looked up")
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Parsing of SRT (`.srt`) and VTT (`.vtt`) subtitles as synced lyrics.
+//!
+//! Each cue becomes a line, with the cue's text lines joined by spaces and formatting tags
+//! removed. VTT inline timestamps (`<00:01.500>`), as used by karaoke-style captions,
+//! become word timing. A long enough pause between cues gets an empty line, so the last
+//! line does not stay on screen through an instrumental break.
+
+use crate::error::{CoreError, Result};
+use crate::lrc::{LrcFile, LrcLine, LrcMetadata, LrcWord};
+use std::path::Path;
+use std::time::Duration;
+
+/// Shortest pause between cues that clears the line
+const MIN_GAP: Duration = Duration::from_secs(3);
+
+/// Subtitle file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// Format of a file, from its `.srt` or `.vtt` extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    /// Parse subtitles in this format into synced lyrics
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a VTT file does not start with `WEBVTT`, or the text has no cues.
+    pub fn parse(self, input: &str) -> Result<LrcFile> {
+        let input = input.trim_start_matches('\u{feff}');
+        if self == Self::Vtt && !input.starts_with("WEBVTT") {
+            return Err(CoreError::SubtitleParseError {
+                reason: "missing WEBVTT header".to_string(),
+            });
+        }
+
+        let input = input.replace("\r\n", "\n");
+        let mut cues: Vec<Cue> = input.split("\n\n").filter_map(parse_cue).collect();
+        if cues.is_empty() {
+            return Err(CoreError::SubtitleParseError {
+                reason: "no cues found".to_string(),
+            });
+        }
+        cues.sort_by_key(|cue| cue.start);
+        // Rolling captions repeat a cue with a new time; keep the first
+        cues.dedup_by(|next, prev| next.text == prev.text && next.start <= prev.end);
+
+        let mut lines = Vec::with_capacity(cues.len());
+        for (i, cue) in cues.iter().enumerate() {
+            let next_start = cues.get(i + 1).map(|next| next.start);
+            lines.push(LrcLine {
+                start_time: cue.start,
+                text: cue.text.clone(),
+                words: cue.words.clone(),
+                romanized: None,
+                part: None,
+            });
+            if next_start.is_some_and(|next| next.saturating_sub(cue.end) >= MIN_GAP) {
+                lines.push(LrcLine {
+                    start_time: cue.end,
+                    text: String::new(),
+                    words: None,
+                    romanized: None,
+                    part: None,
+                });
+            }
+        }
+        Ok(LrcFile {
+            metadata: LrcMetadata::default(),
+            lines,
+        })
+    }
+}
+
+/// A timed block of subtitle text
+struct Cue {
+    start: Duration,
+    end: Duration,
+    text: String,
+    words: Option<Vec<LrcWord>>,
+}
+
+/// Parse a block of lines: an optional identifier, the `start --> end` timing line (with
+/// optional VTT settings after it), then the text. Headers, `NOTE` and `STYLE` blocks
+/// have no timing line and are skipped.
+fn parse_cue(block: &str) -> Option<Cue> {
+    let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+    let (start, rest) = lines.next()?.split_once("-->")?;
+    let start = parse_time(start)?;
+    let end = parse_time(rest.split_whitespace().next()?)?;
+
+    let raw = lines.collect::<Vec<_>>().join(" ");
+    let words = timed_words(&raw, start, end);
+    let text = words.as_ref().map_or_else(
+        || collapse_whitespace(&strip_tags(&raw)),
+        |words| {
+            words
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        },
+    );
+    if text.is_empty() {
+        return None;
+    }
+    Some(Cue {
+        start,
+        end,
+        text,
+        words,
+    })
+}
+
+/// Word timing from VTT inline timestamps: the text before the first timestamp starts
+/// with the cue, and each timestamp starts the text after it
+fn timed_words(raw: &str, cue_start: Duration, cue_end: Duration) -> Option<Vec<LrcWord>> {
+    let mut segments = vec![(cue_start, String::new())];
+    let mut rest = raw;
+    while let Some(open) = rest.find('<') {
+        let close = open + rest[open..].find('>')?;
+        if let Some(segment) = segments.last_mut() {
+            segment.1.push_str(&rest[..open]);
+        }
+        let tag = &rest[open + 1..close];
+        if let Some(time) = parse_time(tag) {
+            segments.push((time, String::new()));
+        }
+        rest = &rest[close + 1..];
+    }
+    if segments.len() == 1 {
+        return None;
+    }
+    if let Some(segment) = segments.last_mut() {
+        segment.1.push_str(rest);
+    }
+
+    let mut words: Vec<LrcWord> = segments
+        .into_iter()
+        .flat_map(|(start, text)| {
+            decode_entities(&text)
+                .split_whitespace()
+                .map(|word| LrcWord {
+                    start_time: start,
+                    end_time: None,
+                    text: word.to_string(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for i in 0..words.len() {
+        let end = words.get(i + 1).map_or(cue_end, |next| next.start_time);
+        words[i].end_time = Some(end);
+    }
+    (!words.is_empty()).then_some(words)
+}
+
+/// Remove `<i>`, `<c.class>`, `<v Speaker>` and similar tags, and SRT `{\an8}` overrides
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = None;
+    for c in text.chars() {
+        match (depth, c) {
+            (None, '<') => depth = Some('>'),
+            (None, '{') => depth = Some('}'),
+            (Some(close), c) if c == close => depth = None,
+            (None, c) => out.push(c),
+            (Some(_), _) => {}
+        }
+    }
+    decode_entities(&out)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse `hh:mm:ss,mmm` (SRT), `hh:mm:ss.mmm` or `mm:ss.mmm` (VTT)
+fn parse_time(value: &str) -> Option<Duration> {
+    let value = value.trim().replace(',', ".");
+    let parts: Vec<&str> = value.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let (seconds, whole) = parts.split_last()?;
+    let mut total: u64 = 0;
+    for part in whole {
+        total = total * 60 + part.parse::<u64>().ok()?;
+    }
+    let (secs, millis) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    let secs: u64 = secs.parse().ok()?;
+    // Pad or cut the fraction to milliseconds
+    let millis: u64 = format!("{millis:0<3}").get(..3)?.parse().ok()?;
+    Some(Duration::from_millis((total * 60 + secs) * 1000 + millis))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt() {
+        let srt = "1\r\n00:00:12,345 --> 00:00:15,000\r\n<i>First</i> line\r\nwraps\r\n\r\n\
+                   2\r\n00:00:15,000 --> 00:00:17,000\r\n{\\an8}Second\r\n\r\n\
+                   3\r\n00:00:30,000 --> 00:00:32,000\r\nAfter a break\r\n";
+        let lrc = SubtitleFormat::Srt.parse(srt).unwrap();
+
+        let texts: Vec<_> = lrc.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, ["First line wraps", "Second", "", "After a break"]);
+        assert_eq!(lrc.lines[0].start_time, Duration::from_millis(12_345));
+        // The break starts when the second cue ends
+        assert_eq!(lrc.lines[2].start_time, Duration::from_secs(17));
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        let vtt = "WEBVTT\n\nNOTE made by hand\n\n\
+                   intro\n00:01.000 --> 00:03.000 align:start\n<v Singer>Hello &amp; welcome</v>\n\n\
+                   00:03.000 --> 00:05.000\nSing <00:03.500>along <00:04.250>now\n";
+        let lrc = SubtitleFormat::Vtt.parse(vtt).unwrap();
+
+        assert_eq!(lrc.lines.len(), 2);
+        assert_eq!(lrc.lines[0].text, "Hello & welcome");
+        assert!(lrc.lines[0].words.is_none());
+
+        let words = lrc.lines[1].words.as_ref().unwrap();
+        assert_eq!(lrc.lines[1].text, "Sing along now");
+        assert_eq!(words[1].start_time, Duration::from_millis(3500));
+        assert_eq!(words[1].end_time, Some(Duration::from_millis(4250)));
+        assert_eq!(words[2].end_time, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(
+            SubtitleFormat::Vtt
+                .parse("00:01.000 --> 00:02.000\nNo header")
+                .is_err()
+        );
+        assert!(SubtitleFormat::Srt.parse("just some text").is_err());
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            SubtitleFormat::from_path(Path::new("song.SRT")),
+            Some(SubtitleFormat::Srt)
+        );
+        assert_eq!(
+            SubtitleFormat::from_path(Path::new("song.vtt")),
+            Some(SubtitleFormat::Vtt)
+        );
+        assert_eq!(SubtitleFormat::from_path(Path::new("song.lrc")), None);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(
+            parse_time("01:02:03,4"),
+            Some(Duration::from_millis(3_723_400))
+        );
+        assert_eq!(
+            parse_time("02:03.456"),
+            Some(Duration::from_millis(123_456))
+        );
+        assert_eq!(parse_time("c.yellow"), None);
+    }
+}
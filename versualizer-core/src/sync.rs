@@ -1,8 +1,16 @@
+use crate::audio_features::AudioFeatures;
+use crate::blocklist::BlockKind;
+use crate::config::{VersualizerConfig, clamp_global_offset_ms, clamp_line_lead_ms};
+use crate::error::Result;
+use crate::limits;
 use crate::lrc::LrcFile;
 use crate::playback::{PlaybackState, TrackInfo};
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
 use tokio::sync::{RwLock, broadcast};
+use tracing::{debug, warn};
 
 /// Events emitted by the sync engine
 #[derive(Debug, Clone)]
@@ -18,6 +26,9 @@ pub enum SyncEvent {
     PlaybackResumed { position: Duration },
     /// Playback stopped (no track playing)
     PlaybackStopped,
+    /// An ad started playing on a free account, in place of a track. Its lyrics are not
+    /// fetched and the overlay stays idle until the next track change.
+    AdPlaying,
     /// Track changed to a new track
     TrackChanged {
         track: TrackInfo,
@@ -27,26 +38,72 @@ pub enum SyncEvent {
     PositionSync { position: Duration },
     /// A seek occurred within the current track
     SeekOccurred { position: Duration },
+    /// The lyrics fetcher started asking `provider` for the current track's lyrics
+    LyricsFetchStarted { provider: String },
+    /// `provider` failed to answer for the current track, and the fetcher moves on
+    LyricsFetchFailed { provider: String, reason: String },
     /// Lyrics were loaded for current track
     LyricsLoaded { lyrics: LrcFile },
+    /// Only plain lyrics without timing were found for the current track
+    UnsyncedLyricsLoaded { text: String },
     /// No lyrics found for current track
     LyricsNotFound,
-    /// Error occurred
-    Error { message: String },
+    /// A lyrics provider reported the current track as instrumental, so it has no lyrics
+    /// rather than missing ones
+    TrackInstrumental,
+    /// The current track or its artist is on the blocklist (see [`crate::blocklist`]), so
+    /// its lyrics are not fetched and the overlay stays idle
+    LyricsBlocked { kind: BlockKind },
+    /// Every provider was unreachable, so the lyrics are fetched again in `retry_in`, as
+    /// retry `attempt` of `max_attempts`. No lyrics found is sent once retries run out.
+    LyricsRetrying {
+        attempt: u32,
+        max_attempts: u32,
+        retry_in: Duration,
+    },
+    /// The loaded lyrics were too large and were trimmed before display (see
+    /// [`crate::limits`]). Sent after the lyrics load event.
+    LyricsTrimmed { removed: String },
+    /// Where the loaded lyrics came from: a provider name, or `override` for a file from the
+    /// overrides folder. Sent after the lyrics load event.
+    LyricsSource { provider: String },
+    /// Audio analysis (tempo, beats, bars and sections) of the current track was loaded, for
+    /// visuals that react to the music
+    AnalysisLoaded { features: Arc<AudioFeatures> },
+    /// The config file was edited and reloaded, for settings that apply without a restart
+    ConfigChanged { config: Arc<VersualizerConfig> },
+    /// Error occurred, during the lyrics fetch with the given ID if it has one (see
+    /// [`crate::fetcher`])
+    Error {
+        message: String,
+        fetch_id: Option<u64>,
+    },
 }
 
 /// Sync engine state
 struct SyncEngineInner {
     state: PlaybackState,
     lyrics: Option<LrcFile>,
+    /// Whether an ad is playing, set by [`SyncEngine::set_ad_playing`]
+    ad_playing: bool,
 }
 
 /// Engine that synchronizes playback state and lyrics
 pub struct SyncEngine {
     inner: RwLock<SyncEngineInner>,
     event_tx: broadcast::Sender<SyncEvent>,
+    /// How early lines switch, in milliseconds (see [`apply_line_lead`])
+    line_lead_ms: AtomicI64,
+    /// Timing correction for the current track's lyrics, in milliseconds, added to every
+    /// emitted position
+    track_offset_ms: AtomicI64,
+    /// Timing correction for every track, in milliseconds, added on top of the track offset
+    global_offset_ms: AtomicI64,
 }
 
+/// Largest per-track lyrics offset, in either direction
+pub const MAX_TRACK_OFFSET_MS: i64 = 10_000;
+
 impl SyncEngine {
     /// Create a new sync engine
     #[must_use]
@@ -57,8 +114,12 @@ impl SyncEngine {
             inner: RwLock::new(SyncEngineInner {
                 state: PlaybackState::default(),
                 lyrics: None,
+                ad_playing: false,
             }),
             event_tx,
+            line_lead_ms: AtomicI64::new(0),
+            track_offset_ms: AtomicI64::new(0),
+            global_offset_ms: AtomicI64::new(0),
         })
     }
 
@@ -79,22 +140,23 @@ impl SyncEngine {
 
         // Emit appropriate events
         if track_changed {
-            // Clear lyrics for new/changed track
+            // Clear lyrics and the previous track's offset for new/changed track
             inner.lyrics = None;
+            self.track_offset_ms.store(0, Ordering::Relaxed);
 
             if let Some(ref track) = new_state.track {
                 let _ = self.event_tx.send(SyncEvent::TrackChanged {
                     track: track.clone(),
-                    position: new_state.position,
+                    position: self.offset_position(new_state.position),
                 });
                 // Also emit play state so listeners know if track is playing or paused
                 if new_state.is_playing {
                     let _ = self.event_tx.send(SyncEvent::PlaybackResumed {
-                        position: new_state.position,
+                        position: self.offset_position(new_state.position),
                     });
                 } else {
                     let _ = self.event_tx.send(SyncEvent::PlaybackPaused {
-                        position: new_state.position,
+                        position: self.offset_position(new_state.position),
                     });
                 }
             } else {
@@ -104,37 +166,92 @@ impl SyncEngine {
             if new_state.is_playing {
                 if old_state.track.is_some() {
                     let _ = self.event_tx.send(SyncEvent::PlaybackResumed {
-                        position: new_state.position,
+                        position: self.offset_position(new_state.position),
                     });
                 } else if let Some(ref track) = new_state.track {
                     let _ = self.event_tx.send(SyncEvent::PlaybackStarted {
                         track: track.clone(),
-                        position: new_state.position,
+                        position: self.offset_position(new_state.position),
                     });
                 }
             } else {
                 let _ = self.event_tx.send(SyncEvent::PlaybackPaused {
-                    position: new_state.position,
+                    position: self.offset_position(new_state.position),
                 });
             }
         } else if seek_occurred {
             let _ = self.event_tx.send(SyncEvent::SeekOccurred {
-                position: new_state.position,
+                position: self.offset_position(new_state.position),
             });
         } else {
             // Regular position update
             let _ = self.event_tx.send(SyncEvent::PositionSync {
-                position: new_state.position,
+                position: self.offset_position(new_state.position),
             });
         }
 
         inner.state = new_state;
+        inner.ad_playing = false;
+    }
+
+    /// Mark that an ad is playing instead of a track, clearing the track and its lyrics.
+    /// [`SyncEvent::AdPlaying`] is sent once per ad break, the next state update ends it.
+    pub async fn set_ad_playing(&self) {
+        let mut inner = self.inner.write().await;
+        if inner.ad_playing {
+            return;
+        }
+        inner.ad_playing = true;
+        inner.state = PlaybackState::default();
+        inner.lyrics = None;
+        self.track_offset_ms.store(0, Ordering::Relaxed);
+        let _ = self.event_tx.send(SyncEvent::AdPlaying);
     }
 
     /// Set lyrics for the current track
-    pub async fn set_lyrics(&self, lyrics: LrcFile) {
+    pub async fn set_lyrics(&self, mut lyrics: LrcFile) {
+        let trimmed = limits::cap_lyrics(&mut lyrics);
+        debug!(
+            "Lyrics: {} lines, ~{} bytes",
+            lyrics.lines.len(),
+            limits::lyrics_size(&lyrics)
+        );
         self.inner.write().await.lyrics = Some(lyrics.clone());
         let _ = self.event_tx.send(SyncEvent::LyricsLoaded { lyrics });
+        self.report_trimmed(trimmed);
+    }
+
+    /// Set plain lyrics for the current track, used when no synced lyrics exist
+    pub async fn set_unsynced_lyrics(&self, mut text: String) {
+        let trimmed = limits::cap_unsynced(&mut text);
+        self.inner.write().await.lyrics = None;
+        let _ = self.event_tx.send(SyncEvent::UnsyncedLyricsLoaded { text });
+        self.report_trimmed(trimmed);
+    }
+
+    /// Emit a warning event if lyrics were trimmed to the size caps
+    fn report_trimmed(&self, removed: Option<String>) {
+        if let Some(removed) = removed {
+            warn!("Lyrics too large, dropped {}", removed);
+            let _ = self.event_tx.send(SyncEvent::LyricsTrimmed { removed });
+        }
+    }
+
+    /// Report where the lyrics that were just loaded came from
+    pub fn report_lyrics_source(&self, provider: &str) {
+        let _ = self.event_tx.send(SyncEvent::LyricsSource {
+            provider: provider.to_string(),
+        });
+    }
+
+    /// Report the audio analysis of the current track
+    pub fn report_audio_analysis(&self, features: Arc<AudioFeatures>) {
+        let _ = self.event_tx.send(SyncEvent::AnalysisLoaded { features });
+    }
+
+    /// Report that the config file was reloaded
+    pub fn report_config_changed(&self, config: Arc<VersualizerConfig>) {
+        let _ = self.event_tx.send(SyncEvent::ConfigChanged { config });
     }
 
     /// Mark that no lyrics were found
@@ -143,9 +260,56 @@ impl SyncEngine {
         let _ = self.event_tx.send(SyncEvent::LyricsNotFound);
     }
 
+    /// Mark that the current track is instrumental, clearing any shown lyrics
+    pub async fn set_instrumental(&self) {
+        self.inner.write().await.lyrics = None;
+        let _ = self.event_tx.send(SyncEvent::TrackInstrumental);
+    }
+
+    /// Mark that the current track's lyrics are blocked, clearing any shown lyrics
+    pub async fn set_lyrics_blocked(&self, kind: BlockKind) {
+        self.inner.write().await.lyrics = None;
+        let _ = self.event_tx.send(SyncEvent::LyricsBlocked { kind });
+    }
+
+    /// Report that `provider` is being asked for the current track's lyrics
+    pub fn report_fetch_started(&self, provider: &str) {
+        let _ = self.event_tx.send(SyncEvent::LyricsFetchStarted {
+            provider: provider.to_string(),
+        });
+    }
+
+    /// Report that `provider` failed to answer with `reason`
+    pub fn report_fetch_failed(&self, provider: &str, reason: String) {
+        let _ = self.event_tx.send(SyncEvent::LyricsFetchFailed {
+            provider: provider.to_string(),
+            reason,
+        });
+    }
+
+    /// Report that the current track's lyrics will be fetched again in `retry_in`
+    pub fn report_lyrics_retrying(&self, attempt: u32, max_attempts: u32, retry_in: Duration) {
+        let _ = self.event_tx.send(SyncEvent::LyricsRetrying {
+            attempt,
+            max_attempts,
+            retry_in,
+        });
+    }
+
     /// Emit an error event
     pub fn emit_error(&self, message: String) {
-        let _ = self.event_tx.send(SyncEvent::Error { message });
+        let _ = self.event_tx.send(SyncEvent::Error {
+            message,
+            fetch_id: None,
+        });
+    }
+
+    /// Emit an error event of the lyrics fetch `fetch_id`
+    pub fn emit_fetch_error(&self, fetch_id: u64, message: String) {
+        let _ = self.event_tx.send(SyncEvent::Error {
+            message,
+            fetch_id: Some(fetch_id),
+        });
     }
 
     /// Get current playback state
@@ -158,8 +322,48 @@ impl SyncEngine {
         self.inner.read().await.lyrics.clone()
     }
 
-    /// Get interpolated current position
+    /// Write the current lyrics to `path` as a standard LRC file, with the current track's
+    /// offset applied to the timestamps and missing ID tags filled in from the track.
+    /// Returns `false` if no synced lyrics are loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file or its directory cannot be written.
+    pub async fn export_current_lyrics(&self, path: &Path) -> Result<bool> {
+        let (lyrics, track) = {
+            let inner = self.inner.read().await;
+            (inner.lyrics.clone(), inner.state.track.clone())
+        };
+        let Some(mut lyrics) = lyrics else {
+            return Ok(false);
+        };
+        // A positive track offset shows lines before their timestamps
+        lyrics.shift(-self.track_offset_ms());
+        if let Some(track) = track {
+            let metadata = &mut lyrics.metadata;
+            metadata.title.get_or_insert(track.name);
+            metadata.artist.get_or_insert(track.artist);
+            if !track.album.is_empty() {
+                metadata.album.get_or_insert(track.album);
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(path, lyrics.to_string()).await?;
+        Ok(true)
+    }
+
+    /// Get interpolated current position, corrected by the track offset
     pub async fn current_position(&self) -> Duration {
+        let position = self.inner.read().await.state.interpolated_position();
+        self.offset_position(position)
+    }
+
+    /// Get interpolated current position in the track itself, without the track offset, as
+    /// used to time lyrics against the song
+    pub async fn playback_position(&self) -> Duration {
         self.inner.read().await.state.interpolated_position()
     }
 
@@ -172,6 +376,71 @@ impl SyncEngine {
     pub async fn current_track(&self) -> Option<TrackInfo> {
         self.inner.read().await.state.track.clone()
     }
+
+    /// How many milliseconds early lines switch
+    pub fn line_lead_ms(&self) -> i64 {
+        self.line_lead_ms.load(Ordering::Relaxed)
+    }
+
+    /// Set how many milliseconds early lines switch, limited to `±MAX_LINE_LEAD_MS`.
+    /// Returns the value that was set.
+    pub fn set_line_lead_ms(&self, lead_ms: i64) -> i64 {
+        let lead_ms = clamp_line_lead_ms(lead_ms);
+        self.line_lead_ms.store(lead_ms, Ordering::Relaxed);
+        lead_ms
+    }
+
+    /// Timing correction for the current track's lyrics, in milliseconds
+    pub fn track_offset_ms(&self) -> i64 {
+        self.track_offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Set the current track's lyrics offset, limited to `±MAX_TRACK_OFFSET_MS`. A positive
+    /// offset moves the position later, so lyrics that lag behind the song catch up. The
+    /// offset resets when the track changes. Returns the value that was set.
+    pub async fn set_track_offset_ms(&self, offset_ms: i64) -> i64 {
+        let offset_ms = offset_ms.clamp(-MAX_TRACK_OFFSET_MS, MAX_TRACK_OFFSET_MS);
+        if self.track_offset_ms.swap(offset_ms, Ordering::Relaxed) != offset_ms {
+            // Listeners interpolate from the last position they got, so resync them
+            let position = self.current_position().await;
+            let _ = self.event_tx.send(SyncEvent::SeekOccurred { position });
+        }
+        offset_ms
+    }
+
+    /// Timing correction for every track, in milliseconds
+    pub fn global_offset_ms(&self) -> i64 {
+        self.global_offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Set the offset applied to every track, limited to `±MAX_GLOBAL_OFFSET_MS`. It adds to
+    /// the track offset but, unlike it, is left out of exported lyrics. Returns the value
+    /// that was set.
+    pub async fn set_global_offset_ms(&self, offset_ms: i64) -> i64 {
+        let offset_ms = clamp_global_offset_ms(offset_ms);
+        if self.global_offset_ms.swap(offset_ms, Ordering::Relaxed) != offset_ms {
+            let position = self.current_position().await;
+            let _ = self.event_tx.send(SyncEvent::SeekOccurred { position });
+        }
+        offset_ms
+    }
+
+    /// `position` corrected by the track and global offsets
+    fn offset_position(&self, position: Duration) -> Duration {
+        apply_line_lead(position, self.track_offset_ms() + self.global_offset_ms())
+    }
+}
+
+/// The position used to pick the current line: `position` moved `lead_ms` later, so lines
+/// switch that much early (or late for a negative lead)
+#[must_use]
+pub fn apply_line_lead(position: Duration, lead_ms: i64) -> Duration {
+    let lead = Duration::from_millis(lead_ms.unsigned_abs());
+    if lead_ms >= 0 {
+        position.saturating_add(lead)
+    } else {
+        position.saturating_sub(lead)
+    }
 }
 
 impl Default for SyncEngine {
@@ -181,8 +450,166 @@ impl Default for SyncEngine {
             inner: RwLock::new(SyncEngineInner {
                 state: PlaybackState::default(),
                 lyrics: None,
+                ad_playing: false,
             }),
             event_tx,
+            line_lead_ms: AtomicI64::new(0),
+            track_offset_ms: AtomicI64::new(0),
+            global_offset_ms: AtomicI64::new(0),
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::source::MusicSource;
+    use crate::time::DurationExt;
+
+    #[test]
+    fn test_apply_line_lead() {
+        let position = Duration::from_secs(1);
+        assert_eq!(apply_line_lead(position, 200).as_millis_u64(), 1200);
+        assert_eq!(apply_line_lead(position, -300).as_millis_u64(), 700);
+        assert_eq!(apply_line_lead(position, -5000), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_set_line_lead_is_clamped() {
+        let engine = SyncEngine::new();
+        assert_eq!(engine.line_lead_ms(), 0);
+        assert_eq!(
+            engine.set_line_lead_ms(i64::MAX),
+            crate::config::MAX_LINE_LEAD_MS
+        );
+        assert_eq!(engine.line_lead_ms(), crate::config::MAX_LINE_LEAD_MS);
+    }
+
+    #[tokio::test]
+    async fn test_track_offset_shifts_positions() {
+        let engine = SyncEngine::new();
+        let mut rx = engine.subscribe();
+
+        assert_eq!(
+            engine.set_track_offset_ms(-20_000).await,
+            -MAX_TRACK_OFFSET_MS
+        );
+        assert!(matches!(
+            rx.recv().await,
+            Ok(SyncEvent::SeekOccurred { .. })
+        ));
+        engine.set_track_offset_ms(250).await;
+        let _ = rx.recv().await;
+
+        engine
+            .update_state(PlaybackState {
+                position: Duration::from_secs(1),
+                ..PlaybackState::default()
+            })
+            .await;
+        assert!(matches!(
+            rx.recv().await,
+            Ok(SyncEvent::PositionSync { position }) if position.as_millis_u64() == 1250
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_global_offset_adds_to_track_offset() {
+        let engine = SyncEngine::new();
+        let mut rx = engine.subscribe();
+
+        assert_eq!(
+            engine.set_global_offset_ms(50_000).await,
+            crate::config::MAX_GLOBAL_OFFSET_MS
+        );
+        engine.set_global_offset_ms(100).await;
+        engine.set_track_offset_ms(250).await;
+        while rx.try_recv().is_ok() {}
+
+        engine
+            .update_state(PlaybackState {
+                position: Duration::from_secs(1),
+                ..PlaybackState::default()
+            })
+            .await;
+        assert!(matches!(
+            rx.recv().await,
+            Ok(SyncEvent::PositionSync { position }) if position.as_millis_u64() == 1350
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_current_lyrics() {
+        let path = std::env::temp_dir()
+            .join(format!("versualizer-export-{}", std::process::id()))
+            .join("song.lrc");
+        let engine = SyncEngine::new();
+        assert!(!engine.export_current_lyrics(&path).await.unwrap());
+
+        engine
+            .update_state(PlaybackState {
+                track: Some(TrackInfo::new(
+                    MusicSource::Spotify,
+                    "track123",
+                    "Song",
+                    "Artist",
+                    "",
+                    Duration::from_secs(200),
+                )),
+                ..PlaybackState::default()
+            })
+            .await;
+        engine
+            .set_lyrics(LrcFile::parse("[offset:500]\n[00:01.00]Hello").unwrap())
+            .await;
+        engine.set_track_offset_ms(250).await;
+        engine.set_global_offset_ms(1000).await;
+        assert!(engine.export_current_lyrics(&path).await.unwrap());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "[ti:Song]\n[ar:Artist]\n[00:01.25]Hello\n");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_ad_break_clears_track_until_next_one() {
+        let engine = SyncEngine::new();
+        let track = TrackInfo::new(
+            MusicSource::Spotify,
+            "track123",
+            "Song",
+            "Artist",
+            "",
+            Duration::from_secs(200),
+        );
+        engine
+            .update_state(PlaybackState::new(
+                true,
+                Some(track.clone()),
+                Duration::ZERO,
+                track.duration,
+            ))
+            .await;
+        let mut rx = engine.subscribe();
+
+        engine.set_ad_playing().await;
+        engine.set_ad_playing().await;
+        assert!(matches!(rx.recv().await, Ok(SyncEvent::AdPlaying)));
+        assert!(rx.try_recv().is_err());
+        assert!(engine.current_track().await.is_none());
+
+        engine
+            .update_state(PlaybackState::new(
+                true,
+                Some(track.clone()),
+                Duration::ZERO,
+                track.duration,
+            ))
+            .await;
+        assert!(matches!(
+            rx.recv().await,
+            Ok(SyncEvent::TrackChanged { .. })
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+//! Track tempo, used to line up line transitions with the beat.
+//!
+//! Only the tempo is known, not where the beats fall, so the beat grid is assumed to start
+//! at the beginning of the track. That holds for most studio recordings.
+
+use std::time::Duration;
+
+/// Tempos outside this range (beats per minute) are treated as unknown
+const BPM_RANGE: std::ops::RangeInclusive<f32> = 30.0..=300.0;
+
+/// Tempo of a track in beats per minute
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+    bpm: f32,
+}
+
+impl Tempo {
+    /// A tempo of `bpm` beats per minute, or `None` if it is not a plausible tempo
+    #[must_use]
+    pub fn new(bpm: f32) -> Option<Self> {
+        BPM_RANGE.contains(&bpm).then_some(Self { bpm })
+    }
+
+    /// Beats per minute
+    #[must_use]
+    pub const fn bpm(&self) -> f32 {
+        self.bpm
+    }
+
+    /// Length of one beat
+    #[must_use]
+    pub fn beat(&self) -> Duration {
+        Duration::from_secs_f32(60.0 / self.bpm)
+    }
+
+    /// The beat closest to `time`
+    #[must_use]
+    pub fn snap(&self, time: Duration) -> Duration {
+        let beat = self.beat();
+        beat.mul_f64((time.as_secs_f64() / beat.as_secs_f64()).round())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo_bounds() {
+        assert!(Tempo::new(0.0).is_none());
+        assert!(Tempo::new(f32::NAN).is_none());
+        assert!(Tempo::new(120.0).is_some());
+    }
+
+    #[test]
+    fn test_snap_to_nearest_beat() {
+        let tempo = Tempo { bpm: 120.0 };
+        assert_eq!(tempo.beat(), Duration::from_millis(500));
+        assert_eq!(
+            tempo.snap(Duration::from_millis(1240)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            tempo.snap(Duration::from_millis(1260)),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(tempo.snap(Duration::ZERO), Duration::ZERO);
+    }
+}
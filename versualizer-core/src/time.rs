@@ -58,7 +58,7 @@ mod tests {
 
     #[test]
     fn test_as_millis_i64() {
-        let duration = Duration::from_millis(5000);
+        let duration = Duration::from_secs(5);
         assert_eq!(duration.as_millis_i64(), 5000);
     }
 
@@ -70,7 +70,7 @@ mod tests {
 
     #[test]
     fn test_as_secs_u32() {
-        let duration = Duration::from_secs(300);
+        let duration = Duration::from_mins(5);
         assert_eq!(duration.as_secs_u32(), 300);
     }
 
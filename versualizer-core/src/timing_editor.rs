@@ -0,0 +1,179 @@
+//! Retiming lyrics by tapping along with playback.
+//!
+//! A [`TimingEditor`] steps through the lines of plain or mistimed lyrics. Each tap gives
+//! the next line the playback position at the time, so singing along and tapping at the
+//! start of every line produces synced lyrics.
+
+use crate::lrc::{LrcFile, LrcLine, LrcMetadata, apply_offset};
+use crate::time::DurationExt;
+use std::time::Duration;
+
+/// Provider name retimed lyrics are cached under
+pub const EDITOR_PROVIDER: &str = "editor";
+
+/// Lines being retimed, and the times tapped for them so far
+#[derive(Debug, Clone)]
+pub struct TimingEditor {
+    metadata: LrcMetadata,
+    lines: Vec<LrcLine>,
+    /// Whether the lines came with timestamps, which untapped lines then keep
+    synced: bool,
+    /// New start time of each line tapped so far, in line order
+    taps: Vec<Duration>,
+}
+
+impl TimingEditor {
+    /// Retime plain lyrics, one line per non-blank line of `text`
+    #[must_use]
+    pub fn from_text(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| LrcLine {
+                start_time: Duration::ZERO,
+                text: line.to_string(),
+                words: None,
+                romanized: None,
+                part: None,
+            })
+            .collect();
+        Self {
+            metadata: LrcMetadata::default(),
+            lines,
+            synced: false,
+            taps: Vec::new(),
+        }
+    }
+
+    /// Retime synced lyrics. Word timing moves along with its line.
+    #[must_use]
+    pub fn from_lrc(lrc: LrcFile) -> Self {
+        Self {
+            metadata: lrc.metadata,
+            lines: lrc.lines,
+            synced: true,
+            taps: Vec::new(),
+        }
+    }
+
+    /// The lines being retimed
+    #[must_use]
+    pub fn lines(&self) -> &[LrcLine] {
+        &self.lines
+    }
+
+    /// Index of the line the next tap is for, equal to the number of lines when every line
+    /// has been tapped
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.taps.len()
+    }
+
+    /// Whether every line has been tapped
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.taps.len() >= self.lines.len()
+    }
+
+    /// Start the current line at `position` and move to the next one. A position before the
+    /// previous tap, as after seeking back, starts the line with the previous one. Returns
+    /// `false` if every line has already been tapped.
+    pub fn tap(&mut self, position: Duration) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        let previous = self.taps.last().copied().unwrap_or_default();
+        self.taps.push(position.max(previous));
+        true
+    }
+
+    /// Forget the last tap, so its line is tapped again. Returns `false` if nothing was
+    /// tapped yet.
+    pub fn undo(&mut self) -> bool {
+        self.taps.pop().is_some()
+    }
+
+    /// The retimed lyrics. Synced lines not tapped yet move by as much as the last tapped
+    /// line did; plain lines not tapped yet are left out.
+    #[must_use]
+    pub fn finish(self) -> LrcFile {
+        let mut shift_ms = 0;
+        let mut lines = Vec::with_capacity(self.lines.len());
+        for (i, mut line) in self.lines.into_iter().enumerate() {
+            if let Some(tap) = self.taps.get(i) {
+                shift_ms = tap.as_millis_i64() - line.start_time.as_millis_i64();
+            } else if !self.synced {
+                break;
+            }
+            line.start_time = apply_offset(line.start_time, shift_ms);
+            for word in line.words.iter_mut().flatten() {
+                word.start_time = apply_offset(word.start_time, shift_ms);
+                word.end_time = word.end_time.map(|end| apply_offset(end, shift_ms));
+            }
+            lines.push(line);
+        }
+        lines.sort_by_key(|line| line.start_time);
+
+        // Parsed timestamps already include the file's own offset
+        let metadata = LrcMetadata {
+            offset: 0,
+            ..self.metadata
+        };
+        LrcFile { metadata, lines }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_plain_lyrics() {
+        let mut editor = TimingEditor::from_text("First\n\nSecond\nThird\n");
+        assert_eq!(editor.lines().len(), 3);
+
+        assert!(editor.tap(Duration::from_secs(5)));
+        assert!(editor.tap(Duration::from_secs(9)));
+        assert!(editor.undo());
+        // Earlier than the previous tap, so it starts with the first line
+        assert!(editor.tap(Duration::from_secs(1)));
+        assert_eq!(editor.current_index(), 2);
+        assert!(!editor.is_done());
+
+        // The untapped third line has no time, so it is left out
+        let lrc = editor.finish();
+        let times: Vec<_> = lrc.lines.iter().map(|line| line.start_time).collect();
+        assert_eq!(times, [Duration::from_secs(5), Duration::from_secs(5)]);
+        assert_eq!(lrc.lines[1].text, "Second");
+    }
+
+    #[test]
+    fn test_tap_synced_lyrics() {
+        let lrc = LrcFile::parse(
+            "[ti:Song]\n[offset:500]\n[00:10.00]<00:10.00>One <00:11.00>two\n[00:20.00]Three\n",
+        )
+        .unwrap();
+        let mut editor = TimingEditor::from_lrc(lrc);
+        assert!(editor.tap(Duration::from_secs(12)));
+
+        let lrc = editor.finish();
+        assert_eq!(lrc.metadata.title.as_deref(), Some("Song"));
+        assert_eq!(lrc.metadata.offset, 0);
+        assert_eq!(lrc.lines[0].start_time, Duration::from_secs(12));
+        let words = lrc.lines[0].words.as_ref().unwrap();
+        assert_eq!(words[1].start_time, Duration::from_secs(13));
+        // The untapped line moves with the tapped one
+        assert_eq!(lrc.lines[1].start_time, Duration::from_secs(22));
+    }
+
+    #[test]
+    fn test_tap_past_last_line() {
+        let mut editor = TimingEditor::from_text("Only");
+        assert!(!editor.undo());
+        assert!(editor.tap(Duration::from_secs(1)));
+        assert!(editor.is_done());
+        assert!(!editor.tap(Duration::from_secs(2)));
+    }
+}
@@ -0,0 +1,338 @@
+//! Lyric translation through a configurable `DeepL`, `LibreTranslate` or LLM endpoint.
+//!
+//! Each lyric line is sent as its own text segment so the translations come back aligned
+//! one-to-one with the lines. Results are cached per target language next to the lyrics.
+//! Songs are translated on demand, or as their lyrics load with `auto_translate`.
+
+use crate::cache::LyricsCache;
+use crate::config::{TranslationBackend, TranslationConfig};
+use crate::error::{CoreError, Result};
+use crate::lrc::LrcFile;
+use crate::playback::TrackInfo;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// Timeout for translation requests, which carry a whole song at once
+const TRANSLATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A song's lyrics translated line by line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricsTranslation {
+    /// Language the lines were translated into
+    pub target_lang: String,
+    /// One translation per lyric line, empty for blank lines
+    pub lines: Vec<String>,
+}
+
+/// Translates cached lyrics and caches the result
+pub struct LyricsTranslator {
+    config: TranslationConfig,
+    client: reqwest::Client,
+    cache: Arc<LyricsCache>,
+}
+
+impl LyricsTranslator {
+    /// Create a translator for the configured backend
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(config: TranslationConfig, cache: Arc<LyricsCache>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(TRANSLATION_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            config,
+            client,
+            cache,
+        })
+    }
+
+    /// Whether songs should be translated as soon as their lyrics load
+    #[must_use]
+    pub fn auto_translates(&self) -> bool {
+        self.config.auto_translate && self.config.is_enabled()
+    }
+
+    /// Cached translation of `track`'s lyrics into the configured language, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read.
+    pub async fn cached_translation(&self, track: &TrackInfo) -> Result<Option<LyricsTranslation>> {
+        let Some(cached) = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let target_lang = &self.config.target_lang;
+        Ok(self
+            .cache
+            .get_translation(cached.id, target_lang)
+            .await?
+            .map(|lines| self.translation(lines)))
+    }
+
+    /// Translate the cached lyrics of `track`, one translated string per lyric line.
+    /// A cached translation into the configured language is reused.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the track has no cached lyrics, no endpoint is configured,
+    /// or the translation request fails.
+    pub async fn translate_track(&self, track: &TrackInfo) -> Result<LyricsTranslation> {
+        let target_lang = &self.config.target_lang;
+        let cached = self
+            .cache
+            .get_by_provider_id(track.source.as_str(), &track.source_track_id)
+            .await?
+            .ok_or_else(|| translation_error("no cached lyrics for the current track"))?;
+
+        if let Some(lines) = self.cache.get_translation(cached.id, target_lang).await? {
+            return Ok(self.translation(lines));
+        }
+        if !self.config.is_enabled() {
+            return Err(translation_error(
+                "no endpoint configured in [lyrics.translation]",
+            ));
+        }
+
+        let lines = lyric_lines(&cached.content);
+        info!(
+            "Translating {} lines of {} - {} into {}",
+            lines.len(),
+            track.artist,
+            track.name,
+            target_lang
+        );
+        let translated = self.translate_lines(&lines).await?;
+        self.cache
+            .store_translation(cached.id, target_lang, &translated)
+            .await?;
+        Ok(self.translation(translated))
+    }
+
+    fn translation(&self, lines: Vec<String>) -> LyricsTranslation {
+        LyricsTranslation {
+            target_lang: self.config.target_lang.clone(),
+            lines,
+        }
+    }
+
+    /// Translate `lines`, keeping blank lines blank so the output stays aligned
+    async fn translate_lines(&self, lines: &[String]) -> Result<Vec<String>> {
+        let segments: Vec<&str> = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if segments.is_empty() {
+            return Ok(vec![String::new(); lines.len()]);
+        }
+
+        let translated = match self.config.backend {
+            TranslationBackend::LibreTranslate => self.request_libre_translate(&segments).await?,
+            TranslationBackend::Deepl => self.request_deepl(&segments).await?,
+            TranslationBackend::OpenAi => self.request_openai(&segments).await?,
+        };
+        align(lines, translated)
+    }
+
+    async fn request_libre_translate(&self, segments: &[&str]) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(rename = "translatedText")]
+            translated_text: Vec<String>,
+        }
+
+        let url = format!("{}/translate", self.config.endpoint.trim_end_matches('/'));
+        let response: Response = self
+            .client
+            .post(url)
+            .json(&json!({
+                "q": segments,
+                "source": "auto",
+                "target": self.config.target_lang,
+                "format": "text",
+                "api_key": self.config.api_key,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.translated_text)
+    }
+
+    async fn request_deepl(&self, segments: &[&str]) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Translation {
+            text: String,
+        }
+        #[derive(Deserialize)]
+        struct Response {
+            translations: Vec<Translation>,
+        }
+
+        let url = format!(
+            "{}/v2/translate",
+            self.config.endpoint.trim_end_matches('/')
+        );
+        let mut request = self.client.post(url).json(&json!({
+            "text": segments,
+            "target_lang": self.config.target_lang.to_uppercase(),
+        }));
+        if let Some(ref api_key) = self.config.api_key {
+            request = request.header("Authorization", format!("DeepL-Auth-Key {api_key}"));
+        }
+        let response: Response = request.send().await?.error_for_status()?.json().await?;
+        Ok(response
+            .translations
+            .into_iter()
+            .map(|translation| translation.text)
+            .collect())
+    }
+
+    async fn request_openai(&self, segments: &[&str]) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Message {
+            content: String,
+        }
+        #[derive(Deserialize)]
+        struct Choice {
+            message: Message,
+        }
+        #[derive(Deserialize)]
+        struct Response {
+            choices: Vec<Choice>,
+        }
+
+        let model = self
+            .config
+            .model
+            .as_deref()
+            .ok_or_else(|| translation_error("no model configured for the openai backend"))?;
+        let url = format!(
+            "{}/v1/chat/completions",
+            self.config.endpoint.trim_end_matches('/')
+        );
+        let instructions = format!(
+            "Translate each song lyric line in the JSON array into the language with code \
+             \"{}\". Reply with only a JSON array of strings holding the translations, in the \
+             same order and of the same length.",
+            self.config.target_lang
+        );
+        let mut request = self.client.post(url).json(&json!({
+            "model": model,
+            "temperature": 0,
+            "messages": [
+                {"role": "system", "content": instructions},
+                {"role": "user", "content": serde_json::to_string(segments)?},
+            ],
+        }));
+        if let Some(ref api_key) = self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response: Response = request.send().await?.error_for_status()?.json().await?;
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| translation_error("the model returned no reply"))?;
+        parse_llm_lines(&content)
+    }
+}
+
+/// Parse a model's reply as a JSON array of strings, allowing a Markdown code fence around it
+fn parse_llm_lines(reply: &str) -> Result<Vec<String>> {
+    let reply = reply.trim();
+    let json = reply
+        .strip_prefix("```")
+        .and_then(|fenced| fenced.strip_suffix("```"))
+        // Drop the fence's language tag, e.g. ```json
+        .map_or(reply, |fenced| {
+            fenced.trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        });
+    serde_json::from_str(json.trim())
+        .map_err(|e| translation_error(&format!("the model's reply is not a JSON array: {e}")))
+}
+
+/// Text of each lyric line in cached content (LRC for synced lyrics, plain text otherwise)
+fn lyric_lines(content: &str) -> Vec<String> {
+    match LrcFile::parse(content) {
+        Ok(lrc) if !lrc.lines.is_empty() => lrc.lines.into_iter().map(|line| line.text).collect(),
+        _ => content.lines().map(str::to_string).collect(),
+    }
+}
+
+/// Put translated segments back in place of the non-blank `lines` they came from
+fn align(lines: &[String], translated: Vec<String>) -> Result<Vec<String>> {
+    let expected = lines.iter().filter(|line| !line.trim().is_empty()).count();
+    if translated.len() != expected {
+        return Err(translation_error(&format!(
+            "expected {expected} translated lines, got {}",
+            translated.len()
+        )));
+    }
+
+    let mut translated = translated.into_iter();
+    Ok(lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                translated.next().unwrap_or_default()
+            }
+        })
+        .collect())
+}
+
+fn translation_error(reason: &str) -> CoreError {
+    CoreError::TranslationFailed {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_keeps_blank_lines_in_place() {
+        let lines = vec!["Hallo".to_string(), String::new(), "Welt".to_string()];
+        let aligned = align(&lines, vec!["Hello".to_string(), "World".to_string()]).unwrap();
+        assert_eq!(aligned, vec!["Hello", "", "World"]);
+
+        assert!(align(&lines, vec!["Hello".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_llm_lines() {
+        assert_eq!(
+            parse_llm_lines(r#"["Hello", "World"]"#).unwrap(),
+            vec!["Hello", "World"]
+        );
+        assert_eq!(
+            parse_llm_lines("```json\n[\"Hello\"]\n```").unwrap(),
+            vec!["Hello"]
+        );
+        assert!(parse_llm_lines("Sure! Here you go: Hello").is_err());
+    }
+
+    #[test]
+    fn test_lyric_lines_from_lrc_and_plain_text() {
+        assert_eq!(
+            lyric_lines("[00:01.00]First\n[00:02.00]\n[00:03.00]Third"),
+            vec!["First", "", "Third"]
+        );
+        assert_eq!(lyric_lines("Plain\nText"), vec!["Plain", "Text"]);
+    }
+}
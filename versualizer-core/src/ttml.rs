@@ -0,0 +1,360 @@
+//! Parsing of TTML lyrics, the format Apple Music and AMLL lyric repositories use.
+//!
+//! Each `<p>` in the body is a line. With word (syllable) timing, its `<span>`s carry the
+//! timing of each syllable, and whitespace between spans separates words. Spans with
+//! `ttm:role="x-bg"` hold background vocals; translation and romanization spans are
+//! skipped.
+
+use crate::error::{CoreError, Result};
+use crate::lrc::{LrcFile, LrcLine, LrcMetadata, LrcWord};
+use roxmltree::{Document, Node};
+use std::time::Duration;
+
+/// Namespace of `ttm:` attributes and elements such as `ttm:agent` and `ttm:role`
+const TTML_METADATA_NS: &str = "http://www.w3.org/ns/ttml#metadata";
+
+/// Namespace of AMLL's `amll:meta` elements
+const AMLL_NS: &str = "http://www.example.com/ns/amll";
+
+/// Parsed TTML lyrics
+#[derive(Debug, Clone, Default)]
+pub struct TtmlFile {
+    pub metadata: LrcMetadata,
+    pub lines: Vec<TtmlLine>,
+}
+
+/// A line (`<p>`) of TTML lyrics
+#[derive(Debug, Clone)]
+pub struct TtmlLine {
+    pub begin: Duration,
+    pub end: Option<Duration>,
+    /// Singer of the line (`ttm:agent`), e.g. `"v1"` and `"v2"` in a duet
+    pub agent: Option<String>,
+    pub text: String,
+    /// Word timing, if the lyrics have syllable timing
+    pub words: Option<Vec<LrcWord>>,
+    /// Timed background vocals sung during the line
+    pub background: Option<Vec<LrcWord>>,
+}
+
+impl TtmlFile {
+    /// Parse a TTML document
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not XML or has no `<tt>` root with a `<body>`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let doc = Document::parse(input).map_err(|e| CoreError::TtmlParseError {
+            reason: e.to_string(),
+        })?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "tt" {
+            return Err(CoreError::TtmlParseError {
+                reason: format!("root element is <{}>, not <tt>", root.tag_name().name()),
+            });
+        }
+        let body = child_element(root, "body").ok_or_else(|| CoreError::TtmlParseError {
+            reason: "document has no <body>".to_string(),
+        })?;
+
+        let mut metadata =
+            child_element(root, "head").map_or_else(LrcMetadata::default, head_metadata);
+        metadata.length = body.attribute("dur").and_then(parse_time);
+
+        let mut lines: Vec<TtmlLine> = body
+            .descendants()
+            .filter(|node| node.is_element() && node.tag_name().name() == "p")
+            .filter_map(parse_line)
+            .collect();
+        lines.sort_by_key(|line| line.begin);
+
+        Ok(Self { metadata, lines })
+    }
+
+    /// Convert to LRC lyrics. Background vocals are merged into their line's words in
+    /// the order they are sung, and each line's agent becomes its part.
+    #[must_use]
+    pub fn into_lrc(self) -> LrcFile {
+        let lines = self
+            .lines
+            .into_iter()
+            .map(|line| {
+                let words = match (line.words, line.background) {
+                    (Some(mut words), Some(background)) => {
+                        words.extend(background);
+                        words.sort_by_key(|word| word.start_time);
+                        Some(words)
+                    }
+                    (words, background) => words.or(background),
+                };
+                let text = words.as_ref().map_or(line.text, |words| {
+                    words
+                        .iter()
+                        .map(|word| word.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+                LrcLine {
+                    start_time: line.begin,
+                    text,
+                    words,
+                    romanized: None,
+                    part: line.agent,
+                }
+            })
+            .collect();
+        LrcFile {
+            metadata: self.metadata,
+            lines,
+        }
+    }
+}
+
+/// Title, artist and album from `<ttm:title>` or AMLL's `<amll:meta>` entries
+fn head_metadata(head: Node) -> LrcMetadata {
+    let mut metadata = LrcMetadata::default();
+    let mut artists = Vec::new();
+    for node in head.descendants().filter(Node::is_element) {
+        let name = node.tag_name().name();
+        if name == "title" && node.tag_name().namespace() == Some(TTML_METADATA_NS) {
+            metadata.title = node.text().map(|text| text.trim().to_string());
+        } else if name == "meta" && node.tag_name().namespace() == Some(AMLL_NS) {
+            let Some(value) = node.attribute("value") else {
+                continue;
+            };
+            match node.attribute("key") {
+                Some("musicName") => metadata.title = Some(value.to_string()),
+                Some("artists") => artists.push(value),
+                Some("album") => metadata.album = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+    if !artists.is_empty() {
+        metadata.artist = Some(artists.join(", "));
+    }
+    metadata
+}
+
+fn parse_line(p: Node) -> Option<TtmlLine> {
+    let begin = p.attribute("begin").and_then(parse_time)?;
+    let end = p.attribute("end").and_then(parse_time);
+
+    let mut main = WordBuilder::default();
+    let mut background = WordBuilder::default();
+    let mut untimed = String::new();
+    for child in p.children() {
+        if child.is_text() {
+            let text = child.text().unwrap_or_default();
+            main.push_text(text);
+            untimed.push_str(text);
+        } else if child.is_element() {
+            match child.attribute((TTML_METADATA_NS, "role")) {
+                Some("x-bg") => {
+                    for span in child.children() {
+                        background.push_node(span);
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    main.push_node(child);
+                    untimed.push_str(&text_content(child));
+                }
+            }
+        }
+    }
+
+    let words = main.finish();
+    let text = untimed.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() && words.is_none() {
+        return None;
+    }
+    Some(TtmlLine {
+        begin,
+        end,
+        agent: p
+            .attribute((TTML_METADATA_NS, "agent"))
+            .map(ToString::to_string),
+        text,
+        words,
+        background: background.finish(),
+    })
+}
+
+/// Joins timed syllables into words. Syllables with no whitespace between them belong
+/// to the same word.
+#[derive(Default)]
+struct WordBuilder {
+    words: Vec<LrcWord>,
+    space_before: bool,
+}
+
+impl WordBuilder {
+    /// Add a timed `<span>`, or whitespace between spans
+    fn push_node(&mut self, node: Node) {
+        if node.is_text() {
+            self.push_text(node.text().unwrap_or_default());
+            return;
+        }
+        let text = text_content(node);
+        match node.attribute("begin").and_then(parse_time) {
+            Some(begin) => {
+                let end = node.attribute("end").and_then(parse_time);
+                self.push_syllable(begin, end, &text);
+            }
+            None => self.push_text(&text),
+        }
+    }
+
+    /// Untimed text only separates words
+    fn push_text(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.space_before = true;
+        }
+    }
+
+    fn push_syllable(&mut self, begin: Duration, end: Option<Duration>, text: &str) {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            self.space_before = true;
+            return;
+        }
+        let new_word = self.space_before || text.starts_with(char::is_whitespace);
+        match self.words.last_mut() {
+            Some(word) if !new_word => {
+                word.text.push_str(trimmed);
+                word.end_time = end;
+            }
+            _ => self.words.push(LrcWord {
+                start_time: begin,
+                end_time: end,
+                text: trimmed.to_string(),
+            }),
+        }
+        self.space_before = text.ends_with(char::is_whitespace);
+    }
+
+    fn finish(self) -> Option<Vec<LrcWord>> {
+        (!self.words.is_empty()).then_some(self.words)
+    }
+}
+
+fn child_element<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// Text of a node and its descendants
+fn text_content(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|text| text.text())
+        .collect()
+}
+
+/// Parse a TTML time expression: clock time (`1:02:03.456`, `02:03.456`, `3.456`) or an
+/// offset in seconds or milliseconds (`3.456s`, `3456ms`)
+fn parse_time(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let seconds = if let Some(ms) = value.strip_suffix("ms") {
+        ms.parse::<f64>().ok()? / 1000.0
+    } else if let Some(secs) = value.strip_suffix('s') {
+        secs.parse().ok()?
+    } else {
+        let parts: Vec<&str> = value.split(':').collect();
+        if parts.len() > 3 {
+            return None;
+        }
+        parts.iter().try_fold(0.0, |total: f64, part| {
+            part.parse::<f64>().ok().map(|n| total.mul_add(60.0, n))
+        })?
+    };
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const WORD_TIMED: &str = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata" xmlns:itunes="http://music.apple.com/lyric-ttml-internal" xmlns:amll="http://www.example.com/ns/amll" itunes:timing="Word">
+<head><metadata>
+<ttm:agent type="person" xml:id="v1"/>
+<amll:meta key="musicName" value="Song"/>
+<amll:meta key="artists" value="A"/>
+<amll:meta key="artists" value="B"/>
+</metadata></head>
+<body dur="3:00.000"><div>
+<p begin="00:12.000" end="00:15.500" ttm:agent="v1"><span begin="00:12.000" end="00:12.400">Hel</span><span begin="00:12.400" end="00:13.000">lo</span> <span begin="00:13.000" end="00:14.000">world</span><span ttm:role="x-bg"><span begin="00:14.500" end="00:15.000">(ooh)</span></span><span ttm:role="x-translation">Hallo Welt</span></p>
+<p begin="00:10.000" end="00:11.000" ttm:agent="v2"><span begin="10s" end="10500ms">First</span></p>
+</div></body></tt>"#;
+
+    #[test]
+    fn test_parse_word_timing() {
+        let ttml = TtmlFile::parse(WORD_TIMED).unwrap();
+
+        assert_eq!(ttml.metadata.title.as_deref(), Some("Song"));
+        assert_eq!(ttml.metadata.artist.as_deref(), Some("A, B"));
+        assert_eq!(ttml.metadata.length, Some(Duration::from_mins(3)));
+
+        // Lines are sorted by start time
+        assert_eq!(ttml.lines[0].text, "First");
+        assert_eq!(ttml.lines[0].agent.as_deref(), Some("v2"));
+
+        let line = &ttml.lines[1];
+        assert_eq!(line.begin, Duration::from_secs(12));
+        assert_eq!(line.end, Some(Duration::from_millis(15_500)));
+        assert_eq!(line.text, "Hello world");
+        let words = line.words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!(words[0].end_time, Some(Duration::from_secs(13)));
+        assert_eq!(line.background.as_ref().unwrap()[0].text, "(ooh)");
+    }
+
+    #[test]
+    fn test_into_lrc() {
+        let lrc = TtmlFile::parse(WORD_TIMED).unwrap().into_lrc();
+
+        assert_eq!(lrc.lines.len(), 2);
+        assert_eq!(lrc.lines[1].start_time, Duration::from_secs(12));
+        assert_eq!(lrc.lines[1].text, "Hello world (ooh)");
+        assert_eq!(lrc.lines[1].words.as_ref().unwrap().len(), 3);
+        assert_eq!(lrc.lines[1].part.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn test_parse_line_timing() {
+        let ttml = TtmlFile::parse(
+            r#"<tt xmlns="http://www.w3.org/ns/ttml"><body><div>
+<p begin="1:02.5" end="1:05">Only  line
+timing</p>
+<p begin="1:06">  </p>
+</div></body></tt>"#,
+        )
+        .unwrap();
+
+        assert_eq!(ttml.lines.len(), 1);
+        assert_eq!(ttml.lines[0].begin, Duration::from_millis(62_500));
+        assert_eq!(ttml.lines[0].text, "Only line timing");
+        assert!(ttml.lines[0].words.is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_non_ttml() {
+        assert!(TtmlFile::parse("[00:01.00]LRC").is_err());
+        assert!(TtmlFile::parse("<html><body/></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(
+            parse_time("1:02:03.5"),
+            Some(Duration::from_millis(3_723_500))
+        );
+        assert_eq!(parse_time("03.25"), Some(Duration::from_millis(3250)));
+        assert_eq!(parse_time("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_time("-1s"), None);
+        assert_eq!(parse_time("1:2:3:4"), None);
+    }
+}
@@ -0,0 +1,63 @@
+/*
+ * C ABI of versualizer-ffi, the Versualizer lyric sync engine.
+ *
+ * Structured values are JSON strings; see the crate docs for their fields. Strings
+ * returned by the library belong to the caller and are freed with versualizer_string_free.
+ * A failed call returns NULL (or false, or 0) and leaves a message for
+ * versualizer_last_error on the calling thread.
+ */
+
+#ifndef VERSUALIZER_H
+#define VERSUALIZER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Sync engine and lyrics fetcher, driven by playback reported by the host */
+typedef struct VersualizerEngine VersualizerEngine;
+
+/* Message describing why the last call on this thread failed, or NULL if it succeeded */
+char *versualizer_last_error(void);
+
+/* Free a string returned by the library. NULL is ignored. */
+void versualizer_string_free(char *text);
+
+/* Parse LRC text into lyrics JSON, or return NULL on error */
+char *versualizer_lrc_parse(const char *lrc);
+
+/* Start an engine caching lyrics at cache_path, or in the app's lyrics cache if NULL */
+VersualizerEngine *versualizer_engine_new(const char *cache_path);
+
+/* Stop and free an engine. NULL is ignored. */
+void versualizer_engine_free(VersualizerEngine *engine);
+
+/* Report the playback state as JSON. Returns false on error. */
+bool versualizer_engine_set_playback(const VersualizerEngine *engine, const char *playback);
+
+/* What to show right now as JSON, or NULL on error */
+char *versualizer_engine_snapshot(const VersualizerEngine *engine);
+
+/* The current track's synced lyrics as JSON, "null" if none are loaded, or NULL on error */
+char *versualizer_engine_lyrics(const VersualizerEngine *engine);
+
+/* Switch to the next provider's cached lyrics for the current track. Returns the provider
+ * name, or NULL if there is no other or on error. */
+char *versualizer_engine_next_lyrics_source(const VersualizerEngine *engine);
+
+/* Set how many milliseconds early lines switch. Returns the value that was set. */
+int64_t versualizer_engine_set_line_lead_ms(const VersualizerEngine *engine, int64_t lead_ms);
+
+/* Set the current track's timing offset until the track changes. Returns the value that
+ * was set. */
+int64_t versualizer_engine_set_track_offset_ms(const VersualizerEngine *engine,
+                                               int64_t offset_ms);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VERSUALIZER_H */
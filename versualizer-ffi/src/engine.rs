@@ -0,0 +1,326 @@
+//! Sync engine and lyrics fetcher driven by playback state from the host.
+
+use crate::error::{FfiError, Result};
+use crate::strings::{ffi_call, into_c_string, read_str};
+use crate::types::{LyricsJson, PlaybackJson, SnapshotJson};
+use std::ffi::c_char;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio_util::sync::CancellationToken;
+use versualizer_core::{LyricsCache, LyricsFetcher, LyricsProvider, RetryConfig, SyncEngine};
+use versualizer_lyrics_lrclib::LrclibProvider;
+
+/// Worker threads of an engine's runtime, which only waits on the cache and LRCLIB
+const WORKER_THREADS: usize = 2;
+
+/// A [`SyncEngine`] with a [`LyricsFetcher`] that looks up lyrics on LRCLIB, running on a
+/// runtime of its own so hosts need no async support. The host is the music source: it
+/// reports playback with [`set_playback`](Self::set_playback) and polls
+/// [`snapshot`](Self::snapshot) for the line to show.
+pub struct VersualizerEngine {
+    runtime: Runtime,
+    sync_engine: Arc<SyncEngine>,
+    fetcher: Arc<LyricsFetcher>,
+    cancel_token: CancellationToken,
+}
+
+impl VersualizerEngine {
+    /// Start an engine that caches lyrics in the database at `cache_path`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime, the cache or the LRCLIB client cannot be created.
+    pub fn new(cache_path: &Path) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(WORKER_THREADS)
+            .thread_name("versualizer-ffi")
+            .enable_all()
+            .build()?;
+        let cache = Arc::new(runtime.block_on(LyricsCache::open(cache_path))?);
+        let providers: Vec<Box<dyn LyricsProvider>> =
+            vec![Box::new(LrclibProvider::new(&RetryConfig::default())?)];
+        let sync_engine = SyncEngine::new();
+        let cancel_token = CancellationToken::new();
+        let fetcher = Arc::new(LyricsFetcher::new(
+            sync_engine.clone(),
+            cache,
+            providers,
+            Some(cancel_token.clone()),
+        ));
+        {
+            let _runtime = runtime.enter();
+            // Stopped through the cancellation token on drop
+            drop(fetcher.clone().start());
+        }
+        Ok(Self {
+            runtime,
+            sync_engine,
+            fetcher,
+            cancel_token,
+        })
+    }
+
+    /// Report the current playback state. A new track starts a lyrics lookup.
+    pub fn set_playback(&self, playback: PlaybackJson) {
+        self.runtime
+            .block_on(self.sync_engine.update_state(playback.into()));
+    }
+
+    /// What to show right now
+    #[must_use]
+    pub fn snapshot(&self) -> SnapshotJson {
+        self.runtime.block_on(async {
+            let state = self.sync_engine.state().await;
+            let position = self.sync_engine.current_position().await;
+            let lyrics = self.sync_engine.lyrics().await;
+            SnapshotJson::new(
+                &state,
+                position,
+                lyrics.as_ref(),
+                self.sync_engine.line_lead_ms(),
+                self.sync_engine.track_offset_ms(),
+            )
+        })
+    }
+
+    /// The current track's synced lyrics, if loaded
+    #[must_use]
+    pub fn lyrics(&self) -> Option<LyricsJson> {
+        self.runtime
+            .block_on(self.sync_engine.lyrics())
+            .as_ref()
+            .map(LyricsJson::from)
+    }
+
+    /// Show the current track's lyrics from the next provider that has them cached.
+    /// Returns that provider, or `None` if there is no other.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be read.
+    pub fn next_lyrics_source(&self) -> Result<Option<String>> {
+        Ok(self.runtime.block_on(self.fetcher.next_alternative())?)
+    }
+
+    /// Set how many milliseconds early lines switch. Returns the value that was set.
+    pub fn set_line_lead_ms(&self, lead_ms: i64) -> i64 {
+        self.sync_engine.set_line_lead_ms(lead_ms)
+    }
+
+    /// Set the current track's timing offset until the track changes. Returns the value
+    /// that was set.
+    pub fn set_track_offset_ms(&self, offset_ms: i64) -> i64 {
+        self.runtime
+            .block_on(self.sync_engine.set_track_offset_ms(offset_ms))
+    }
+}
+
+impl Drop for VersualizerEngine {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+    }
+}
+
+/// Borrow the engine behind a handle
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle from [`versualizer_engine_new`].
+unsafe fn engine_ref<'a>(engine: *const VersualizerEngine) -> Result<&'a VersualizerEngine> {
+    // SAFETY: the caller guarantees the handle is live
+    unsafe { engine.as_ref() }.ok_or(FfiError::NullArgument("engine"))
+}
+
+/// Start an engine caching lyrics at `cache_path`, or in the app's lyrics cache if null.
+/// Returns null on error. Free it with [`versualizer_engine_free`].
+///
+/// # Safety
+///
+/// `cache_path` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_new(
+    cache_path: *const c_char,
+) -> *mut VersualizerEngine {
+    ffi_call(|| {
+        let path = if cache_path.is_null() {
+            versualizer_core::paths::lyrics_cache_db_path()
+        } else {
+            // SAFETY: forwarded from the caller
+            PathBuf::from(unsafe { read_str(cache_path, "cache_path") }?)
+        };
+        VersualizerEngine::new(&path)
+    })
+    .map_or(std::ptr::null_mut(), |engine| {
+        Box::into_raw(Box::new(engine))
+    })
+}
+
+/// Stop and free an engine. Null is ignored.
+///
+/// # Safety
+///
+/// `engine` must be null or a handle from [`versualizer_engine_new`] that was not freed
+/// yet, and no other call may be using it.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_free(engine: *mut VersualizerEngine) {
+    if !engine.is_null() {
+        // SAFETY: the caller guarantees it came from `Box::into_raw` in `versualizer_engine_new`
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// Report the playback state as JSON (see [`PlaybackJson`]). Returns false on error.
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle, and `playback` null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_set_playback(
+    engine: *const VersualizerEngine,
+    playback: *const c_char,
+) -> bool {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        let engine = unsafe { engine_ref(engine) }?;
+        // SAFETY: forwarded from the caller
+        let playback = serde_json::from_str(unsafe { read_str(playback, "playback") }?)?;
+        engine.set_playback(playback);
+        Ok(())
+    })
+    .is_some()
+}
+
+/// What to show right now as JSON (see [`SnapshotJson`]), or null on error.
+/// Free the result with [`versualizer_string_free`](crate::versualizer_string_free).
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_snapshot(
+    engine: *const VersualizerEngine,
+) -> *mut c_char {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        let engine = unsafe { engine_ref(engine) }?;
+        Ok(serde_json::to_string(&engine.snapshot())?)
+    })
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// The current track's synced lyrics as JSON (see [`LyricsJson`]), `null` if none are
+/// loaded, or a null pointer on error.
+/// Free the result with [`versualizer_string_free`](crate::versualizer_string_free).
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_lyrics(
+    engine: *const VersualizerEngine,
+) -> *mut c_char {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        let engine = unsafe { engine_ref(engine) }?;
+        Ok(serde_json::to_string(&engine.lyrics())?)
+    })
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// Show the current track's lyrics from the next provider that has them cached. Returns
+/// that provider's name, or null if there is no other or on error (check
+/// [`versualizer_last_error`](crate::versualizer_last_error)).
+/// Free the result with [`versualizer_string_free`](crate::versualizer_string_free).
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_next_lyrics_source(
+    engine: *const VersualizerEngine,
+) -> *mut c_char {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        unsafe { engine_ref(engine) }?.next_lyrics_source()
+    })
+    .flatten()
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// Set how many milliseconds early lines switch. Returns the value that was set, or 0 on
+/// error.
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_set_line_lead_ms(
+    engine: *const VersualizerEngine,
+    lead_ms: i64,
+) -> i64 {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        Ok(unsafe { engine_ref(engine) }?.set_line_lead_ms(lead_ms))
+    })
+    .unwrap_or_default()
+}
+
+/// Set the current track's timing offset until the track changes. Returns the value that
+/// was set, or 0 on error.
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_engine_set_track_offset_ms(
+    engine: *const VersualizerEngine,
+    offset_ms: i64,
+) -> i64 {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        Ok(unsafe { engine_ref(engine) }?.set_track_offset_ms(offset_ms))
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::strings::take_c_string;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_engine_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("versualizer-ffi-engine-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let engine = unsafe { versualizer_engine_new(c_path.as_ptr()) };
+        assert!(!engine.is_null());
+
+        // Nothing playing, so the fetcher stays idle
+        let playback = CString::new(r#"{"is_playing": false, "position_ms": 2000}"#).unwrap();
+        assert!(unsafe { versualizer_engine_set_playback(engine, playback.as_ptr()) });
+        let snapshot = take_c_string(unsafe { versualizer_engine_snapshot(engine) }).unwrap();
+        let snapshot: serde_json::Value = serde_json::from_str(&snapshot).unwrap();
+        assert_eq!(snapshot["position_ms"], 2000);
+        assert_eq!(snapshot["track"], serde_json::Value::Null);
+        assert_eq!(
+            take_c_string(unsafe { versualizer_engine_lyrics(engine) }).as_deref(),
+            Some("null")
+        );
+        assert_eq!(
+            unsafe { versualizer_engine_set_line_lead_ms(engine, 120) },
+            120
+        );
+
+        let invalid = CString::new("{").unwrap();
+        assert!(!unsafe { versualizer_engine_set_playback(engine, invalid.as_ptr()) });
+        assert!(unsafe { versualizer_engine_snapshot(std::ptr::null()) }.is_null());
+
+        unsafe { versualizer_engine_free(engine) };
+        let _ = std::fs::remove_file(path);
+    }
+}
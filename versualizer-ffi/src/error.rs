@@ -0,0 +1,27 @@
+use thiserror::Error;
+use versualizer_core::CoreError;
+
+/// Error type for versualizer-ffi, reported to the host through `versualizer_last_error`.
+#[derive(Debug, Error)]
+pub enum FfiError {
+    #[error("Argument {0} is null")]
+    NullArgument(&'static str),
+
+    #[error("Argument {0} is not valid UTF-8")]
+    InvalidUtf8(&'static str),
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Core(#[from] CoreError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Internal error: {0}")]
+    Panicked(String),
+}
+
+/// Convenience type alias for Results with `FfiError`.
+pub type Result<T> = std::result::Result<T, FfiError>;
@@ -0,0 +1,57 @@
+//! C ABI bindings to the Versualizer lyric sync engine.
+//!
+//! Native hosts, such as mobile apps or desktop widgets (a Rainmeter skin, a Tauri
+//! front-end), can reuse the LRC parser, sync engine and lyrics fetcher through this library
+//! instead of reimplementing them. It builds as a shared and a static library; the C
+//! declarations are in `include/versualizer.h`.
+//!
+//! Structured values cross the boundary as JSON strings. Strings returned by the library
+//! belong to the caller, who frees them with [`versualizer_string_free`]. A failed call
+//! returns null (or `false`, or 0) and leaves a message for [`versualizer_last_error`] on
+//! the calling thread.
+//!
+//! An engine is the whole overlay pipeline minus the window and the music source:
+//!
+//! 1. [`versualizer_engine_new`] opens a lyrics cache and starts fetching from LRCLIB.
+//! 2. The host reports playback with [`versualizer_engine_set_playback`]:
+//!
+//!    ```json
+//!    {
+//!      "is_playing": true,
+//!      "position_ms": 61500,
+//!      "track": {
+//!        "source": "windows_media",
+//!        "id": "Artist - Song",
+//!        "name": "Song",
+//!        "artist": "Artist",
+//!        "album": "Album",
+//!        "duration_ms": 215000
+//!      }
+//!    }
+//!    ```
+//!
+//!    on every change and every few seconds while playing; positions are interpolated in
+//!    between.
+//! 3. It polls [`versualizer_engine_snapshot`] for the current and next line, and
+//!    [`versualizer_engine_lyrics`] for all lines once they load.
+//! 4. [`versualizer_engine_free`] stops the engine.
+
+// Raw pointers are the C ABI; every other crate in the workspace denies unsafe code
+#![allow(unsafe_code)]
+
+mod engine;
+mod error;
+mod lrc;
+mod strings;
+mod types;
+
+pub use engine::{
+    VersualizerEngine, versualizer_engine_free, versualizer_engine_lyrics, versualizer_engine_new,
+    versualizer_engine_next_lyrics_source, versualizer_engine_set_line_lead_ms,
+    versualizer_engine_set_playback, versualizer_engine_set_track_offset_ms,
+    versualizer_engine_snapshot,
+};
+pub use error::{FfiError, Result};
+pub use lrc::versualizer_lrc_parse;
+pub use strings::{versualizer_last_error, versualizer_string_free};
+pub use types::{LineJson, LyricsJson, PlaybackJson, SnapshotJson, TrackJson, WordJson};
@@ -0,0 +1,41 @@
+//! LRC parsing without an engine.
+
+use crate::strings::{ffi_call, into_c_string, read_str};
+use crate::types::LyricsJson;
+use std::ffi::c_char;
+use versualizer_core::LrcFile;
+
+/// Parse LRC text into lyrics JSON (see [`LyricsJson`]), or return null on error.
+/// Free the result with [`versualizer_string_free`](crate::versualizer_string_free).
+///
+/// # Safety
+///
+/// `lrc` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_lrc_parse(lrc: *const c_char) -> *mut c_char {
+    ffi_call(|| {
+        // SAFETY: forwarded from the caller
+        let text = unsafe { read_str(lrc, "lrc") }?;
+        let lyrics = LrcFile::parse(text)?;
+        Ok(serde_json::to_string(&LyricsJson::from(&lyrics))?)
+    })
+    .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::strings::take_c_string;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_lrc_parse() {
+        let lrc = CString::new("[00:01.00]Hello").unwrap();
+        let json = take_c_string(unsafe { versualizer_lrc_parse(lrc.as_ptr()) }).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["lines"][0]["text"], "Hello");
+
+        assert!(unsafe { versualizer_lrc_parse(std::ptr::null()) }.is_null());
+    }
+}
@@ -0,0 +1,138 @@
+//! Strings crossing the C ABI, and the per-thread last error.
+
+use crate::error::{FfiError, Result};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+thread_local! {
+    /// Message of the last failed call on this thread
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run the body of an exported function. Its error, or a panic, is kept for
+/// [`versualizer_last_error`] and turned into `None`, since neither may cross the C ABI.
+pub(crate) fn ffi_call<T>(body: impl FnOnce() -> Result<T>) -> Option<T> {
+    let result = catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(FfiError::Panicked(message))
+    });
+    let (value, error) = match result {
+        Ok(value) => (Some(value), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = error);
+    value
+}
+
+/// Borrow a string argument
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that stays valid for `'a`.
+pub(crate) unsafe fn read_str<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(FfiError::NullArgument(name));
+    }
+    // SAFETY: non-null, and the caller guarantees it is a valid C string
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| FfiError::InvalidUtf8(name))
+}
+
+/// Hand `text` to the caller, who frees it with [`versualizer_string_free`]
+pub(crate) fn into_c_string(text: String) -> *mut c_char {
+    // Interior NULs would end the string early on the C side, so they are dropped
+    CString::new(text)
+        .unwrap_or_else(|e| {
+            let mut bytes = e.into_vec();
+            bytes.retain(|&byte| byte != 0);
+            CString::new(bytes).unwrap_or_default()
+        })
+        .into_raw()
+}
+
+/// Message describing why the last call on this thread failed, or null if it succeeded.
+/// Free the message with [`versualizer_string_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn versualizer_last_error() -> *mut c_char {
+    LAST_ERROR
+        .with(|last| last.borrow().clone())
+        .map_or(std::ptr::null_mut(), into_c_string)
+}
+
+/// Free a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `text` must be null or a string returned by this library that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn versualizer_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: the caller guarantees it came from `CString::into_raw` in `into_c_string`
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+/// Read and free a string returned by this library, for tests of the exported functions
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) fn take_c_string(ptr: *mut c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let text = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+    unsafe { versualizer_string_free(ptr) };
+    Some(text)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> Option<String> {
+        take_c_string(versualizer_last_error())
+    }
+
+    #[test]
+    fn test_ffi_call_records_errors_and_panics() {
+        assert_eq!(ffi_call(|| Ok(1)), Some(1));
+        assert_eq!(last_error(), None);
+
+        assert_eq!(ffi_call::<()>(|| Err(FfiError::NullArgument("lrc"))), None);
+        assert_eq!(last_error().as_deref(), Some("Argument lrc is null"));
+
+        #[allow(clippy::panic)]
+        let panicked = ffi_call::<()>(|| panic!("boom"));
+        assert_eq!(panicked, None);
+        assert_eq!(last_error().as_deref(), Some("Internal error: boom"));
+    }
+
+    #[test]
+    fn test_read_str() {
+        assert!(matches!(
+            unsafe { read_str(std::ptr::null(), "text") },
+            Err(FfiError::NullArgument("text"))
+        ));
+        let text = CString::new("héllo").unwrap();
+        assert_eq!(unsafe { read_str(text.as_ptr(), "text") }.unwrap(), "héllo");
+        let invalid = CString::new(vec![0xff, 0xfe]).unwrap();
+        assert!(matches!(
+            unsafe { read_str(invalid.as_ptr(), "text") },
+            Err(FfiError::InvalidUtf8("text"))
+        ));
+    }
+
+    #[test]
+    fn test_into_c_string_drops_interior_nul() {
+        assert_eq!(
+            take_c_string(into_c_string("a\0b".to_string())).as_deref(),
+            Some("ab")
+        );
+    }
+}
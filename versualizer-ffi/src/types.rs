@@ -0,0 +1,274 @@
+//! JSON shapes exchanged with the host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use versualizer_core::{
+    DurationExt, LrcFile, LrcLine, LrcWord, MusicSource, PlaybackState, TrackInfo, apply_line_lead,
+};
+
+/// Parsed LRC lyrics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LyricsJson {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// `[offset:]` tag in milliseconds, already applied to the line times
+    pub offset_ms: i64,
+    pub lines: Vec<LineJson>,
+}
+
+/// A lyrics line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineJson {
+    pub start_ms: u64,
+    pub text: String,
+    /// Word-level timing from enhanced LRC, if present
+    pub words: Option<Vec<WordJson>>,
+}
+
+/// A timed word of an enhanced LRC line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WordJson {
+    pub start_ms: u64,
+    pub end_ms: Option<u64>,
+    pub text: String,
+}
+
+impl From<&LrcFile> for LyricsJson {
+    fn from(lrc: &LrcFile) -> Self {
+        Self {
+            title: lrc.metadata.title.clone(),
+            artist: lrc.metadata.artist.clone(),
+            album: lrc.metadata.album.clone(),
+            offset_ms: lrc.metadata.offset,
+            lines: lrc.lines.iter().map(LineJson::from).collect(),
+        }
+    }
+}
+
+impl From<&LrcLine> for LineJson {
+    fn from(line: &LrcLine) -> Self {
+        Self {
+            start_ms: line.start_time.as_millis_u64(),
+            text: line.text.clone(),
+            words: line
+                .words
+                .as_ref()
+                .map(|words| words.iter().map(WordJson::from).collect()),
+        }
+    }
+}
+
+impl From<&LrcWord> for WordJson {
+    fn from(word: &LrcWord) -> Self {
+        Self {
+            start_ms: word.start_time.as_millis_u64(),
+            end_ms: word.end_time.map(|end| end.as_millis_u64()),
+            text: word.text.clone(),
+        }
+    }
+}
+
+/// The track being played, as reported by the host and echoed in snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackJson {
+    /// Music source the track ID belongs to, e.g. `"spotify"` or `"windows_media"`
+    pub source: MusicSource,
+    /// The source's ID for the track, used as the lyrics cache key
+    pub id: String,
+    pub name: String,
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Extra IDs for lyrics lookup, such as `{"spotify": "..."}`
+    #[serde(default)]
+    pub provider_ids: HashMap<String, String>,
+    #[serde(default)]
+    pub album_art_url: Option<String>,
+    /// International Standard Recording Code. An `"isrc"` entry of `provider_ids` is used
+    /// when this is missing.
+    #[serde(default)]
+    pub isrc: Option<String>,
+}
+
+impl From<TrackJson> for TrackInfo {
+    fn from(track: TrackJson) -> Self {
+        let mut info = Self::new(
+            track.source,
+            track.id,
+            track.name,
+            track.artist,
+            track.album,
+            Duration::from_millis(track.duration_ms),
+        );
+        info.isrc = track
+            .isrc
+            .or_else(|| track.provider_ids.get("isrc").cloned());
+        info.provider_ids = track.provider_ids;
+        info.album_art_url = track.album_art_url;
+        info
+    }
+}
+
+impl From<&TrackInfo> for TrackJson {
+    fn from(track: &TrackInfo) -> Self {
+        Self {
+            source: track.source,
+            id: track.source_track_id.clone(),
+            name: track.name.clone(),
+            artist: track.artist.clone(),
+            album: track.album.clone(),
+            duration_ms: track.duration.as_millis_u64(),
+            provider_ids: track.provider_ids.clone(),
+            album_art_url: track.album_art_url.clone(),
+            isrc: track.isrc.clone(),
+        }
+    }
+}
+
+/// Playback state pushed by the host whenever it changes, and periodically while playing
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PlaybackJson {
+    pub is_playing: bool,
+    #[serde(default)]
+    pub position_ms: u64,
+    /// `None` when nothing is playing
+    #[serde(default)]
+    pub track: Option<TrackJson>,
+}
+
+impl From<PlaybackJson> for PlaybackState {
+    fn from(playback: PlaybackJson) -> Self {
+        let track: Option<TrackInfo> = playback.track.map(Into::into);
+        let duration = track
+            .as_ref()
+            .map_or(Duration::ZERO, |track| track.duration);
+        Self::new(
+            playback.is_playing,
+            track,
+            Duration::from_millis(playback.position_ms),
+            duration,
+        )
+    }
+}
+
+/// What to show right now, polled by the host
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotJson {
+    pub track: Option<TrackJson>,
+    pub is_playing: bool,
+    /// Interpolated playback position, corrected by the track offset
+    pub position_ms: u64,
+    /// Whether synced lyrics are loaded for the track
+    pub has_lyrics: bool,
+    /// Index of the current line in the lyrics, `None` before the first line
+    pub line_index: Option<usize>,
+    pub line: Option<String>,
+    pub next_line: Option<String>,
+    /// Progress through the current line, 0.0 to 1.0
+    pub line_progress: f32,
+    /// Time until the next line starts, `None` after the last line has started
+    pub ms_until_next: Option<u64>,
+    pub line_lead_ms: i64,
+    pub track_offset_ms: i64,
+}
+
+impl SnapshotJson {
+    /// Snapshot of `state` at `position` with `lyrics`, switching lines `line_lead_ms` early
+    #[must_use]
+    pub fn new(
+        state: &PlaybackState,
+        position: Duration,
+        lyrics: Option<&LrcFile>,
+        line_lead_ms: i64,
+        track_offset_ms: i64,
+    ) -> Self {
+        let line_position = apply_line_lead(position, line_lead_ms);
+        let line_index = lyrics.and_then(|lrc| lrc.current_line_index(line_position));
+        let current = lyrics.zip(line_index).map(|(lrc, i)| &lrc.lines[i]);
+        let next = lyrics.and_then(|lrc| lrc.next_line(line_position));
+        Self {
+            track: state.track.as_ref().map(TrackJson::from),
+            is_playing: state.is_playing,
+            position_ms: position.as_millis_u64(),
+            has_lyrics: lyrics.is_some(),
+            line_index,
+            line: current.map(|line| line.text.clone()),
+            next_line: next.map(|line| line.text.clone()),
+            line_progress: current.map_or(0.0, |line| {
+                line.progress(line_position, next.map(|next| next.start_time))
+            }),
+            ms_until_next: lyrics
+                .and_then(|lrc| lrc.time_until_next(line_position))
+                .map(|until| until.as_millis_u64()),
+            line_lead_ms,
+            track_offset_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playback_json_defaults() {
+        let playback: PlaybackJson = serde_json::from_str(
+            r#"{"is_playing": true, "position_ms": 1500, "track": {"source": "windows_media", "id": "t1", "name": "Song", "artist": "Artist"}}"#,
+        )
+        .unwrap();
+        let state = PlaybackState::from(playback);
+
+        let track = state.track.unwrap();
+        assert_eq!(track.source, MusicSource::WindowsMedia);
+        assert_eq!(track.source_track_id, "t1");
+        assert_eq!(track.album, "");
+        assert_eq!(track.isrc, None);
+        assert_eq!(state.position, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_track_json_isrc_from_provider_ids() {
+        let track: TrackJson = serde_json::from_str(
+            r#"{"source": "windows_media", "id": "t1", "name": "Song", "artist": "Artist", "provider_ids": {"isrc": "GBAYE0601713"}}"#,
+        )
+        .unwrap();
+        assert_eq!(TrackInfo::from(track).isrc.as_deref(), Some("GBAYE0601713"));
+    }
+
+    #[test]
+    fn test_snapshot_lines() {
+        let lrc = LrcFile::parse("[00:01.00]First\n[00:03.00]Second").unwrap();
+        let state = PlaybackState::default();
+
+        let snapshot = SnapshotJson::new(&state, Duration::from_secs(2), Some(&lrc), 0, 0);
+        assert_eq!(snapshot.line_index, Some(0));
+        assert_eq!(snapshot.line.as_deref(), Some("First"));
+        assert_eq!(snapshot.next_line.as_deref(), Some("Second"));
+        assert_eq!(snapshot.ms_until_next, Some(1000));
+        assert!(snapshot.line_progress > 0.0);
+
+        // A line lead switches to the next line early
+        let snapshot = SnapshotJson::new(&state, Duration::from_secs(2), Some(&lrc), 1000, 0);
+        assert_eq!(snapshot.line.as_deref(), Some("Second"));
+        assert_eq!(snapshot.ms_until_next, None);
+
+        let snapshot = SnapshotJson::new(&state, Duration::ZERO, None, 0, 0);
+        assert!(!snapshot.has_lyrics);
+        assert_eq!(snapshot.line_index, None);
+    }
+
+    #[test]
+    fn test_lyrics_json() {
+        let lrc = LrcFile::parse("[ti:Song]\n[00:01.00]<00:01.00>Hi <00:01.50>there").unwrap();
+        let json = serde_json::to_value(LyricsJson::from(&lrc)).unwrap();
+
+        assert_eq!(json["title"], "Song");
+        assert_eq!(json["lines"][0]["start_ms"], 1000);
+        assert_eq!(json["lines"][0]["words"][1]["start_ms"], 1500);
+    }
+}
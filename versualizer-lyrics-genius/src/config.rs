@@ -0,0 +1,47 @@
+//! Genius provider configuration.
+
+use serde::{Deserialize, Serialize};
+use versualizer_core::{CoreError, ProvidersConfig};
+
+use crate::PROVIDER_NAME;
+
+/// Genius-specific configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeniusProviderConfig {
+    /// Genius API client access token
+    pub access_token: String,
+}
+
+impl GeniusProviderConfig {
+    /// Extract Genius config from the dynamic providers config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config cannot be parsed.
+    pub fn from_providers(providers: &ProvidersConfig) -> Result<Option<Self>, CoreError> {
+        providers.get(PROVIDER_NAME)
+    }
+
+    /// Validate that required fields are present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the access token is empty.
+    pub fn validate(&self) -> Result<(), CoreError> {
+        if self.access_token.trim().is_empty() {
+            return Err(CoreError::ConfigMissingField {
+                field: "providers.genius.access_token".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Config template for the Genius provider.
+/// This is appended to the base config template when creating a new config file.
+pub const CONFIG_TEMPLATE: &str = r#"[providers.genius]
+# Required when "genius" is in lyrics.providers. Genius only has plain (unsynced) lyrics,
+# so list it last. Create a client access token at https://genius.com/api-clients
+access_token = ""
+
+"#;
@@ -0,0 +1,288 @@
+//! Genius lyrics provider.
+//!
+//! Songs are found through the Genius API search and the lyrics are scraped from the song
+//! page, since the API does not serve lyrics. Genius only has plain lyrics, so this
+//! provider is a last resort for tracks that no provider has synced lyrics for.
+
+pub mod config;
+mod scrape;
+
+use async_trait::async_trait;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use tracing::{debug, info};
+use versualizer_core::http::{self, HttpLimits};
+use versualizer_core::{
+    AuthState, CheckResult, CoreError, FetchedLyrics, LyricsProvider, LyricsQuery, LyricsResult,
+    RetryConfig, check_reachable,
+};
+
+pub use config::{CONFIG_TEMPLATE as GENIUS_CONFIG_TEMPLATE, GeniusProviderConfig};
+
+/// Genius API base URL
+pub const GENIUS_API_URL: &str = "https://api.genius.com";
+
+/// Provider name used in config and the cache
+pub const PROVIDER_NAME: &str = "genius";
+
+/// Limits for both the JSON search API and the HTML song pages
+const LIMITS: HttpLimits = HttpLimits::HTML.with_content_types(&["application/json", "text/html"]);
+
+/// Genius lyrics provider
+pub struct GeniusProvider {
+    client: ClientWithMiddleware,
+    access_token: String,
+}
+
+impl GeniusProvider {
+    /// Create a new Genius provider with a 10-second timeout, 5 MiB responses and the retries
+    /// of `retry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(access_token: impl Into<String>, retry: &RetryConfig) -> Result<Self, CoreError> {
+        let base_client = http::base_client(
+            reqwest::Client::builder().user_agent(http::USER_AGENT),
+            &LIMITS,
+        )?;
+        Ok(Self {
+            client: http::provider_client(base_client, LIMITS, retry),
+            access_token: access_token.into(),
+        })
+    }
+
+    /// Search Genius for the song best matching `query`
+    async fn search(&self, query: &LyricsQuery) -> Result<Option<GeniusSong>, CoreError> {
+        let url = format!(
+            "{GENIUS_API_URL}/search?q={}",
+            urlencoding::encode(&format!("{} {}", query.artist_name, query.track_name))
+        );
+        debug!("Genius GET (search): {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(provider_error(format!(
+                "search returned status: {}",
+                response.status()
+            )));
+        }
+
+        let search: SearchResponse = http::read_json(response).await?;
+        Ok(best_match(search.response.hits, query))
+    }
+
+    /// Download a song page and extract its lyrics
+    async fn scrape(&self, song: &GeniusSong) -> Result<Option<String>, CoreError> {
+        debug!("Genius GET (song page): {}", song.url);
+        let response = self.client.get(&song.url).send().await?;
+        if !response.status().is_success() {
+            return Err(provider_error(format!(
+                "song page returned status: {}",
+                response.status()
+            )));
+        }
+        Ok(scrape::extract_lyrics(&http::read_text(response).await?))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    response: SearchHits,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchHits {
+    hits: Vec<SearchHit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchHit {
+    #[serde(rename = "type")]
+    kind: String,
+    result: GeniusSong,
+}
+
+/// Song from a search hit
+#[derive(Debug, Deserialize)]
+struct GeniusSong {
+    id: i64,
+    title: String,
+    url: String,
+    primary_artist: GeniusArtist,
+    #[serde(default)]
+    instrumental: bool,
+    #[serde(default)]
+    lyrics_state: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeniusArtist {
+    name: String,
+}
+
+#[async_trait]
+impl LyricsProvider for GeniusProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_NAME
+    }
+
+    async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
+        info!(
+            "Fetching lyrics from Genius for: {} - {}",
+            query.artist_name, query.track_name
+        );
+
+        let Some(song) = self.search(query).await? else {
+            return Err(CoreError::LyricsNotFound {
+                track: query.track_name.clone(),
+                artist: query.artist_name.clone(),
+            });
+        };
+        let provider_id = song.id.to_string();
+        info!(
+            "Genius matched song id: {} ({} - {})",
+            song.id, song.primary_artist.name, song.title
+        );
+
+        if song.instrumental {
+            debug!("Song is instrumental (genius id: {})", song.id);
+            return Ok(FetchedLyrics {
+                result: LyricsResult::Instrumental,
+                provider_id,
+            });
+        }
+        if song.lyrics_state.as_deref() == Some("unreleased") {
+            debug!("No lyrics to scrape (genius id: {})", song.id);
+            return Ok(FetchedLyrics {
+                result: LyricsResult::NotFound,
+                provider_id,
+            });
+        }
+
+        let result = match self.scrape(&song).await? {
+            Some(lyrics) => {
+                info!("Got Genius plain lyrics");
+                LyricsResult::Unsynced(lyrics)
+            }
+            None => LyricsResult::NotFound,
+        };
+        Ok(FetchedLyrics {
+            result,
+            provider_id,
+        })
+    }
+
+    async fn auth_state(&self) -> Option<AuthState> {
+        let authenticated = !self.access_token.is_empty();
+        Some(AuthState {
+            authenticated,
+            expires_at: None,
+            detail: if authenticated {
+                "Access token configured".into()
+            } else {
+                "No access token".into()
+            },
+        })
+    }
+
+    async fn check(&self) -> CheckResult {
+        check_reachable("Genius", GENIUS_API_URL).await
+    }
+}
+
+/// The first song hit by the queried artist whose title matches the queried track
+fn best_match(hits: Vec<SearchHit>, query: &LyricsQuery) -> Option<GeniusSong> {
+    let artist = normalize(&query.artist_name);
+    let title = normalize(strip_suffix(&query.track_name));
+    hits.into_iter()
+        .filter(|hit| hit.kind == "song")
+        .map(|hit| hit.result)
+        .find(|song| {
+            let song_artist = normalize(&song.primary_artist.name);
+            let song_title = normalize(strip_suffix(&song.title));
+            (song_artist.contains(&artist) || artist.contains(&song_artist))
+                && (song_title.starts_with(&title) || title.starts_with(&song_title))
+        })
+}
+
+/// Title without a trailing `(...)`, `[...]` or ` - ...` suffix such as "(Remastered 2011)"
+fn strip_suffix(title: &str) -> &str {
+    let end = title
+        .find(" - ")
+        .into_iter()
+        .chain(title.find(" ("))
+        .chain(title.find(" ["))
+        .min()
+        .unwrap_or(title.len());
+    &title[..end]
+}
+
+/// Lower-case alphanumerics only, for comparing names that differ in punctuation
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn provider_error(reason: String) -> CoreError {
+    CoreError::LyricsProviderFailed {
+        provider: PROVIDER_NAME.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn hit(kind: &str, id: i64, artist: &str, title: &str) -> SearchHit {
+        SearchHit {
+            kind: kind.to_string(),
+            result: GeniusSong {
+                id,
+                title: title.to_string(),
+                url: format!("https://genius.com/{id}"),
+                primary_artist: GeniusArtist {
+                    name: artist.to_string(),
+                },
+                instrumental: false,
+                lyrics_state: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_best_match_skips_other_artists() {
+        let hits = vec![
+            hit("song", 1, "Disturbed", "The Sound of Silence"),
+            hit("song", 2, "Simon & Garfunkel", "The Sound of Silence"),
+        ];
+        let query = LyricsQuery::new("The Sound of Silence", "Simon & Garfunkel");
+
+        assert_eq!(best_match(hits, &query).unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_best_match_ignores_version_suffixes() {
+        let hits = vec![hit("song", 3, "The Beatles", "Let It Be (Remastered 2009)")];
+        let query = LyricsQuery::new("Let It Be - Remastered 2009", "The Beatles");
+
+        assert_eq!(best_match(hits, &query).unwrap().id, 3);
+    }
+
+    #[test]
+    fn test_best_match_none() {
+        let hits = vec![hit("song", 4, "Someone Else", "Another Song")];
+        let query = LyricsQuery::new("Let It Be", "The Beatles");
+
+        assert!(best_match(hits, &query).is_none());
+    }
+}
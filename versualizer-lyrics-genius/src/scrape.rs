@@ -0,0 +1,194 @@
+//! Extraction of plain lyrics from a Genius song page.
+//!
+//! Lyrics are split across `<div data-lyrics-container="true">` elements, with `<br>` for
+//! line breaks and links/formatting tags around annotated parts. Blocks marked
+//! `data-exclude-from-selection` (contributor headers, embedded ads) are skipped.
+
+/// Attribute marking a lyrics container
+const CONTAINER_ATTR: &str = "data-lyrics-container=\"true\"";
+/// Attribute marking content inside a container that is not part of the lyrics
+const EXCLUDE_ATTR: &str = "data-exclude-from-selection=\"true\"";
+/// Longest character reference decoded, so a stray `&` does not swallow the rest of a line
+const MAX_ENTITY_LEN: usize = 10;
+
+/// Plain lyrics of a song page, or `None` if the page has no lyrics containers.
+/// Section headers such as `[Chorus]` are removed.
+pub fn extract_lyrics(html: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(CONTAINER_ATTR) {
+        let after_attr = &rest[start..];
+        let body_start = after_attr.find('>')? + 1;
+        let body = &after_attr[body_start..];
+        let body_len = element_len(body, "div");
+        append_text(&body[..body_len], &mut text);
+        text.push('\n');
+        rest = &body[body_len..];
+    }
+
+    let lyrics = clean_lines(&text);
+    (!lyrics.is_empty()).then_some(lyrics)
+}
+
+/// Length of an element's content up to its closing tag, given the content after the
+/// opening tag. Runs to the end of `html` if the element is never closed.
+fn element_len(html: &str, tag: &str) -> usize {
+    let mut depth = 1usize;
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find('<') {
+        let tag_start = pos + offset;
+        let Some(tag_len) = html[tag_start..].find('>') else {
+            break;
+        };
+        let inner = &html[tag_start + 1..tag_start + tag_len];
+        if tag_name(inner) == tag {
+            depth += 1;
+        } else if inner.strip_prefix('/').map(tag_name) == Some(tag) {
+            depth -= 1;
+            if depth == 0 {
+                return tag_start;
+            }
+        }
+        pos = tag_start + tag_len + 1;
+    }
+    html.len()
+}
+
+/// Append the text of `html` to `out`, turning `<br>` into line breaks
+fn append_text(html: &str, out: &mut String) {
+    let mut rest = html;
+    while let Some(tag_start) = rest.find('<') {
+        out.push_str(&decode_entities(&rest[..tag_start]));
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            return;
+        };
+        let inner = &rest[tag_start + 1..tag_start + tag_len];
+        rest = &rest[tag_start + tag_len + 1..];
+
+        let name = tag_name(inner);
+        if name == "br" {
+            out.push('\n');
+        } else if inner.contains(EXCLUDE_ATTR) && !inner.ends_with('/') {
+            rest = &rest[element_len(rest, name)..];
+            // Skip the closing tag as well
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        }
+    }
+    out.push_str(&decode_entities(rest));
+}
+
+/// Name of a tag from the text between `<` and `>`
+fn tag_name(inner: &str) -> &str {
+    let end = inner
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(inner.len());
+    &inner[..end]
+}
+
+/// Decode the named and numeric character references Genius uses
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest
+            .find(';')
+            .filter(|&semi| semi <= MAX_ENTITY_LEN)
+            .and_then(|semi| {
+                let entity = &rest[1..semi];
+                let c = match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => entity.strip_prefix('#').and_then(|code| {
+                        code.strip_prefix(['x', 'X'])
+                            .map_or_else(
+                                || code.parse().ok(),
+                                |hex| u32::from_str_radix(hex, 16).ok(),
+                            )
+                            .and_then(char::from_u32)
+                    }),
+                };
+                c.map(|c| (c, semi))
+            });
+        if let Some((c, semi)) = decoded {
+            out.push(c);
+            rest = &rest[semi + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Trim lines, drop `[Section]` headers, and collapse runs of blank lines
+fn clean_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            continue;
+        }
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_lyrics_across_containers() {
+        let html = r#"<html><body>
+            <div data-lyrics-container="true" class="Lyrics">[Verse 1]<br/>Hello <a href="/1"><span>darkness</span></a>, my old friend<br>I&#x27;ve come to talk</div>
+            <div class="Ad"><div>Ad text</div></div>
+            <div data-lyrics-container="true">[Chorus]<br/>And the &quot;vision&quot;<br/><i>still remains</i></div>
+            </body></html>"#;
+
+        assert_eq!(
+            extract_lyrics(html).unwrap(),
+            "Hello darkness, my old friend\nI've come to talk\nAnd the \"vision\"\nstill remains"
+        );
+    }
+
+    #[test]
+    fn test_extract_lyrics_skips_excluded_blocks() {
+        let html = r#"<div data-lyrics-container="true"><div data-exclude-from-selection="true"><div>12 Contributors</div>Translations</div>First line<br/><br/><br/>Second line</div>"#;
+
+        assert_eq!(extract_lyrics(html).unwrap(), "First line\n\nSecond line");
+    }
+
+    #[test]
+    fn test_extract_lyrics_without_containers() {
+        assert!(
+            extract_lyrics("<div>Lyrics for this song have yet to be released</div>").is_none()
+        );
+        assert!(
+            extract_lyrics(r#"<div data-lyrics-container="true">[Instrumental]</div>"#).is_none()
+        );
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("Rock &amp; roll &#39;n&#8217; &bogus;"),
+            "Rock & roll 'n\u{2019} &bogus;"
+        );
+    }
+}
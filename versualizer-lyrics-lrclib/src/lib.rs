@@ -1,38 +1,79 @@
 use async_trait::async_trait;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
-use std::time::Duration;
 use tracing::{debug, info, warn};
+use versualizer_core::http::{self, HttpLimits};
+use versualizer_core::matching::{self, MatchCandidate};
 use versualizer_core::{
-    CoreError, FetchedLyrics, LrcFile, LyricsProvider, LyricsQuery, LyricsResult,
+    CheckResult, CoreError, FetchedLyrics, LrcFile, LyricsCandidate, LyricsProvider, LyricsQuery,
+    LyricsResult, RetryConfig, check_reachable,
 };
 
-const LRCLIB_API_URL: &str = "https://lrclib.net/api";
-
-/// Default timeout for HTTP requests (10 seconds)
-const DEFAULT_TIMEOUT_SECS: u64 = 10;
-/// Default number of retry attempts
-const DEFAULT_MAX_RETRIES: u32 = 3;
-
-/// Calculate a score for duration matching (lower is better).
-/// Returns 0 for exact matches, higher values for larger differences.
-/// Capped at `i32::MAX` to prevent overflow.
-fn duration_score(actual: Option<f64>, expected: Option<u32>, scale: f64) -> i32 {
-    match (actual, expected) {
-        (Some(d), Some(q)) => {
-            let diff = (d - f64::from(q)).abs() * scale;
-            // Safely convert f64 to i32 with saturation.
-            // diff is always non-negative (abs), so we clamp to [0, i32::MAX].
-            // Note: Rust stable doesn't have f64::to_int_saturating(), so we use clamp + cast.
-            // The FloatToInt trait exists but is unstable (nightly-only).
-            #[allow(clippy::cast_possible_truncation)]
-            {
-                diff.clamp(0.0, f64::from(i32::MAX)) as i32
-            }
-        }
-        _ => 50, // Default score when duration is unknown
-    }
+/// LRCLIB API base URL
+pub const LRCLIB_API_URL: &str = "https://lrclib.net/api";
+
+/// Added to the match score of results with synced lyrics, so they win over plain lyrics
+/// of a similar match
+const SYNCED_BONUS: f64 = 0.1;
+
+/// The best-matching result with lyrics, by [`match_score`](matching::match_score)
+/// plus [`SYNCED_BONUS`]
+fn best_result(results: Vec<LrclibResponse>, query: &LyricsQuery) -> Option<LrclibResponse> {
+    let usable = results
+        .into_iter()
+        .filter(|r| r.synced_lyrics.is_some() || r.plain_lyrics.is_some());
+    matching::rank(query, usable, |r| MatchCandidate {
+        title: &r.track_name,
+        artist: &r.artist_name,
+        duration_secs: r.duration,
+    })
+    .into_iter()
+    .map(|(r, score)| {
+        let bonus = if r.synced_lyrics.is_some() {
+            SYNCED_BONUS
+        } else {
+            0.0
+        };
+        (r, score + bonus)
+    })
+    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    .map(|(r, _)| r)
+}
+
+/// Results with lyrics as candidates, best match first
+fn candidates(results: Vec<LrclibResponse>, query: &LyricsQuery) -> Vec<LyricsCandidate> {
+    let mut scored: Vec<_> = results
+        .into_iter()
+        .filter(|r| r.synced_lyrics.is_some() || r.plain_lyrics.is_some())
+        .map(|r| {
+            let score = matching::match_score(
+                query,
+                &MatchCandidate {
+                    title: &r.track_name,
+                    artist: &r.artist_name,
+                    duration_secs: r.duration,
+                },
+            );
+            (r, score)
+        })
+        .collect();
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scored
+        .into_iter()
+        .map(|(r, _)| LyricsCandidate {
+            provider: "lrclib".to_string(),
+            id: r.id.to_string(),
+            synced: r.synced_lyrics.is_some(),
+            preview: r
+                .plain_lyrics
+                .as_deref()
+                .or(r.synced_lyrics.as_deref())
+                .map(LyricsCandidate::preview_of),
+            title: r.track_name,
+            artist: r.artist_name,
+            duration_secs: r.duration,
+        })
+        .collect()
 }
 
 /// LRCLIB.net lyrics provider
@@ -41,36 +82,31 @@ pub struct LrclibProvider {
 }
 
 impl LrclibProvider {
-    /// Create a new LRCLIB provider with default 10-second timeout and 3 retries.
+    /// Create a new LRCLIB provider with the JSON API limits (10-second timeout, 2 MiB
+    /// responses) and the retries of `retry`.
     ///
     /// # Errors
     ///
     /// Returns an error if the HTTP client cannot be created.
-    pub fn new() -> Result<Self, CoreError> {
-        // Base client with timeout
-        let base_client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .connect_timeout(Duration::from_secs(5))
-            .user_agent("Versualizer/1.0 (https://github.com/versualizer)")
-            .build()?;
-
-        // Wrap with retry middleware (exponential backoff)
-        let retry_policy =
-            ExponentialBackoff::builder().build_with_max_retries(DEFAULT_MAX_RETRIES);
-        let client = ClientBuilder::new(base_client)
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
-
-        Ok(Self { client })
+    pub fn new(retry: &RetryConfig) -> Result<Self, CoreError> {
+        let base_client = http::base_client(
+            reqwest::Client::builder().user_agent(http::USER_AGENT),
+            &HttpLimits::JSON,
+        )?;
+        Ok(Self {
+            client: http::provider_client(base_client, HttpLimits::JSON, retry),
+        })
     }
 }
 
 /// Response from LRCLIB API
-/// Note: API returns additional fields (trackName, albumName) that we don't use;
+/// Note: API returns additional fields (albumName) that we don't use;
 /// serde ignores unknown fields by default.
 #[derive(Debug, Deserialize)]
 struct LrclibResponse {
     id: i64,
+    #[serde(rename = "trackName", default)]
+    track_name: String,
     #[serde(rename = "artistName")]
     artist_name: String,
     duration: Option<f64>,
@@ -97,8 +133,8 @@ impl LyricsProvider for LrclibProvider {
         let mut url = format!(
             "{}/get?artist_name={}&track_name={}",
             LRCLIB_API_URL,
-            urlencoding::encode(&query.artist_name),
-            urlencoding::encode(&query.track_name)
+            urlencoding::encode(query.search_artist_name()),
+            urlencoding::encode(query.search_track_name())
         );
 
         if let Some(ref album) = query.album_name {
@@ -130,10 +166,43 @@ impl LyricsProvider for LrclibProvider {
             });
         }
 
-        let result: LrclibResponse = response.json().await?;
+        let result: LrclibResponse = http::read_json(response).await?;
         info!("LRCLIB found match with id: {}", result.id);
         Ok(Self::parse_response(result))
     }
+
+    async fn search(&self, query: &LyricsQuery) -> Result<Vec<LyricsCandidate>, CoreError> {
+        let url = format!(
+            "{}/search?track_name={}&artist_name={}",
+            LRCLIB_API_URL,
+            urlencoding::encode(query.search_track_name()),
+            urlencoding::encode(query.search_artist_name())
+        );
+        info!("LRCLIB GET (candidates): {}", url);
+        let response = self.client.get(&url).send().await?.error_for_status()?;
+        let results: Vec<LrclibResponse> = http::read_json(response).await?;
+        Ok(candidates(results, query))
+    }
+
+    async fn fetch_candidate(
+        &self,
+        _query: &LyricsQuery,
+        candidate: &LyricsCandidate,
+    ) -> Result<FetchedLyrics, CoreError> {
+        let url = format!(
+            "{}/get/{}",
+            LRCLIB_API_URL,
+            urlencoding::encode(&candidate.id)
+        );
+        info!("LRCLIB GET (by id): {}", url);
+        let response = self.client.get(&url).send().await?.error_for_status()?;
+        let result: LrclibResponse = http::read_json(response).await?;
+        Ok(Self::parse_response(result))
+    }
+
+    async fn check(&self) -> CheckResult {
+        check_reachable("LRCLIB", LRCLIB_API_URL).await
+    }
 }
 
 /// Duration tolerance for matching (±2 seconds)
@@ -146,7 +215,7 @@ impl LrclibProvider {
         let url = format!(
             "{}/search?track_name={}",
             LRCLIB_API_URL,
-            urlencoding::encode(&query.track_name)
+            urlencoding::encode(query.search_track_name())
         );
 
         info!("LRCLIB GET (search by track): {}", url);
@@ -160,7 +229,7 @@ impl LrclibProvider {
             return self.search_fallback(query).await;
         }
 
-        let results: Vec<LrclibResponse> = response.json().await?;
+        let results: Vec<LrclibResponse> = http::read_json(response).await?;
 
         if results.is_empty() {
             info!("LRCLIB search by track name returned no results, trying full search");
@@ -188,24 +257,15 @@ impl LrclibProvider {
             return self.search_fallback(query).await;
         }
 
-        // Find the best match (prefer synced lyrics, then closest duration)
-        let best = filtered
-            .into_iter()
-            .filter(|r| r.synced_lyrics.is_some() || r.plain_lyrics.is_some())
-            .min_by_key(|r| {
-                // Prefer synced, then by duration match
-                let sync_score = if r.synced_lyrics.is_some() { 0 } else { 100 };
-                sync_score + duration_score(r.duration, query.duration_secs, 10.0)
-            });
-
-        if let Some(result) = best {
+        // Find the best match of title, artist and duration, preferring synced lyrics
+        if let Some(result) = best_result(filtered, query) {
             info!(
                 "LRCLIB found match by track name + duration (id: {}, artist: {}, duration: {:?})",
                 result.id, result.artist_name, result.duration
             );
             Ok(Self::parse_response(result))
         } else {
-            info!("LRCLIB search by track name: no usable lyrics, trying full search");
+            info!("LRCLIB search by track name: no matching lyrics, trying full search");
             self.search_fallback(query).await
         }
     }
@@ -213,7 +273,11 @@ impl LrclibProvider {
     async fn search_fallback(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
         info!("Trying LRCLIB search endpoint with artist + track as final fallback");
 
-        let search_query = format!("{} {}", query.artist_name, query.track_name);
+        let search_query = format!(
+            "{} {}",
+            query.search_artist_name(),
+            query.search_track_name()
+        );
         let url = format!(
             "{}/search?q={}",
             LRCLIB_API_URL,
@@ -232,7 +296,7 @@ impl LrclibProvider {
             });
         }
 
-        let results: Vec<LrclibResponse> = response.json().await?;
+        let results: Vec<LrclibResponse> = http::read_json(response).await?;
 
         if results.is_empty() {
             return Err(CoreError::LyricsNotFound {
@@ -241,17 +305,8 @@ impl LrclibProvider {
             });
         }
 
-        // Find the best match (prefer synced lyrics, then closest duration)
-        let best = results
-            .into_iter()
-            .filter(|r| r.synced_lyrics.is_some() || r.plain_lyrics.is_some())
-            .min_by_key(|r| {
-                // Prefer synced, then by duration match
-                let sync_score = if r.synced_lyrics.is_some() { 0 } else { 100 };
-                sync_score + duration_score(r.duration, query.duration_secs, 1.0)
-            });
-
-        match best {
+        // Find the best match of title, artist and duration, preferring synced lyrics
+        match best_result(results, query) {
             Some(result) => {
                 info!(
                     "LRCLIB found match via full search (id: {}, artist: {})",
@@ -272,7 +327,7 @@ impl LrclibProvider {
         if result.instrumental {
             debug!("Track is instrumental (lrclib id: {})", result.id);
             return FetchedLyrics {
-                result: LyricsResult::NotFound,
+                result: LyricsResult::Instrumental,
                 provider_id,
             };
         }
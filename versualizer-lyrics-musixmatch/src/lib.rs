@@ -0,0 +1,323 @@
+//! Musixmatch lyrics provider using the desktop app API.
+//!
+//! Tracks are matched by ISRC when the music source provides one, otherwise by title,
+//! artist, album, and duration. Word-timed "richsync" lyrics are preferred, then
+//! line-synced subtitles, then plain lyrics.
+
+mod parse;
+pub mod paths;
+mod token;
+
+use async_trait::async_trait;
+use reqwest_middleware::ClientWithMiddleware;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+use versualizer_core::http::{self, HttpLimits};
+use versualizer_core::{
+    AuthState, CheckResult, CoreError, FetchedLyrics, LrcFile, LrcLine, LrcMetadata,
+    LyricsProvider, LyricsQuery, LyricsResult, RetryConfig, check_reachable,
+};
+
+use token::{MusixmatchTokenManager, SESSION_COOKIE};
+
+pub use paths::{MUSIXMATCH_TOKEN_CACHE_FILE_NAME, musixmatch_data_paths};
+
+/// Musixmatch desktop API base URL
+pub const MUSIXMATCH_API_URL: &str = "https://apic-desktop.musixmatch.com/ws/1.1";
+
+/// App ID of the Musixmatch desktop client
+const APP_ID: &str = "web-desktop-app-v1.0";
+/// Provider name used in config and the cache
+const PROVIDER_NAME: &str = "musixmatch";
+
+/// Musixmatch lyrics provider
+pub struct MusixmatchProvider {
+    token_manager: MusixmatchTokenManager,
+    client: ClientWithMiddleware,
+}
+
+impl MusixmatchProvider {
+    /// Create a new Musixmatch provider with the JSON API limits (10-second timeout, 2 MiB
+    /// responses) and the retries of `retry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(retry: &RetryConfig) -> Result<Self, CoreError> {
+        let base_client = http::base_client(reqwest::Client::builder(), &HttpLimits::JSON)?;
+        let token_manager = MusixmatchTokenManager::new(base_client.clone());
+        Ok(Self {
+            token_manager,
+            client: http::provider_client(base_client, HttpLimits::JSON, retry),
+        })
+    }
+
+    /// Call an API method and return its message body, or `None` if nothing was found.
+    /// A rejected token is replaced and the call retried once.
+    async fn call(&self, method: &str, params: &str) -> Result<Option<Value>, CoreError> {
+        for attempt in 0..2 {
+            let token = self.token_manager.get_token().await?;
+            let url = format!(
+                "{MUSIXMATCH_API_URL}/{method}?app_id={APP_ID}&format=json&usertoken={}&{params}",
+                urlencoding::encode(&token)
+            );
+            debug!("Musixmatch GET: {}", method);
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Cookie", SESSION_COOKIE)
+                .send()
+                .await?
+                .error_for_status()?;
+            let response: Value = http::read_json(response).await?;
+
+            match status_code(&response) {
+                Some(200) => {
+                    // Empty bodies come back as an empty array instead of an object
+                    let body = response
+                        .pointer("/message/body")
+                        .filter(|body| body.is_object())
+                        .cloned();
+                    return Ok(body);
+                }
+                Some(404) => return Ok(None),
+                Some(401) if attempt == 0 => {
+                    warn!("Musixmatch rejected the user token, requesting a new one");
+                    self.token_manager.invalidate_token().await;
+                }
+                status => {
+                    return Err(provider_error(format!(
+                        "{method} returned status {}",
+                        status.unwrap_or_default()
+                    )));
+                }
+            }
+        }
+        Err(provider_error(format!("{method} rejected a fresh token")))
+    }
+
+    /// Fetch word-timed lyrics for a matched track
+    async fn fetch_richsync(&self, track_id: i64) -> Result<Vec<LrcLine>, CoreError> {
+        let body = self
+            .call("track.richsync.get", &format!("track_id={track_id}"))
+            .await?;
+        let Some(richsync) = body
+            .as_ref()
+            .and_then(|body| body.pointer("/richsync/richsync_body"))
+            .and_then(Value::as_str)
+        else {
+            return Ok(Vec::new());
+        };
+        parse::parse_richsync(richsync).map_err(Into::into)
+    }
+
+    /// Fetch line-synced lyrics for a matched track
+    async fn fetch_subtitle(&self, track_id: i64) -> Result<Vec<LrcLine>, CoreError> {
+        let body = self
+            .call(
+                "track.subtitle.get",
+                &format!("track_id={track_id}&subtitle_format=mxm"),
+            )
+            .await?;
+        let Some(subtitle) = body
+            .as_ref()
+            .and_then(|body| body.pointer("/subtitle/subtitle_body"))
+            .and_then(Value::as_str)
+        else {
+            return Ok(Vec::new());
+        };
+        parse::parse_subtitle(subtitle).map_err(Into::into)
+    }
+
+    /// Fetch plain lyrics for a matched track, without the trailing copyright notice
+    async fn fetch_plain(&self, track_id: i64) -> Result<Option<String>, CoreError> {
+        let body = self
+            .call("track.lyrics.get", &format!("track_id={track_id}"))
+            .await?;
+        Ok(body
+            .as_ref()
+            .and_then(|body| body.pointer("/lyrics/lyrics_body"))
+            .and_then(Value::as_str)
+            .map(|lyrics| {
+                lyrics
+                    .lines()
+                    .take_while(|line| !line.starts_with("*******"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|lyrics| !lyrics.is_empty()))
+    }
+
+    /// Find the queried track, by its ISRC when the query has one and Musixmatch knows it,
+    /// otherwise by title, artist, album and duration
+    async fn match_track(&self, query: &LyricsQuery) -> Result<Option<Value>, CoreError> {
+        if let Some(ref isrc) = query.isrc {
+            let params = format!("track_isrc={}", urlencoding::encode(isrc));
+            if let Some(track) = self
+                .call("matcher.track.get", &params)
+                .await?
+                .and_then(|body| body.get("track").cloned())
+            {
+                info!("Musixmatch matched ISRC {}", isrc);
+                return Ok(Some(track));
+            }
+            debug!(
+                "Musixmatch has no track with ISRC {}, searching by name",
+                isrc
+            );
+        }
+
+        let mut params = format!(
+            "q_track={}&q_artist={}",
+            urlencoding::encode(&query.track_name),
+            urlencoding::encode(&query.artist_name)
+        );
+        if let Some(ref album) = query.album_name {
+            use std::fmt::Write;
+            let _ = write!(params, "&q_album={}", urlencoding::encode(album));
+        }
+        if let Some(duration) = query.duration_secs {
+            use std::fmt::Write;
+            let _ = write!(params, "&q_duration={duration}");
+        }
+        Ok(self
+            .call("matcher.track.get", &params)
+            .await?
+            .and_then(|body| body.get("track").cloned()))
+    }
+
+    fn synced(query: &LyricsQuery, lines: Vec<LrcLine>, provider_id: String) -> FetchedLyrics {
+        FetchedLyrics {
+            result: LyricsResult::Synced(LrcFile {
+                metadata: LrcMetadata {
+                    title: Some(query.track_name.clone()),
+                    artist: Some(query.artist_name.clone()),
+                    album: query.album_name.clone(),
+                    ..Default::default()
+                },
+                lines,
+            }),
+            provider_id,
+        }
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for MusixmatchProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_NAME
+    }
+
+    async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
+        info!(
+            "Fetching lyrics from Musixmatch for: {} - {} (duration: {:?}s)",
+            query.artist_name, query.track_name, query.duration_secs
+        );
+
+        let Some(track) = self.match_track(query).await? else {
+            return Err(CoreError::LyricsNotFound {
+                track: query.track_name.clone(),
+                artist: query.artist_name.clone(),
+            });
+        };
+        let track_id = track
+            .get("track_id")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| provider_error("matched track has no track_id".to_string()))?;
+        let provider_id = track_id.to_string();
+        info!("Musixmatch matched track id: {}", track_id);
+
+        if flag(&track, "instrumental") {
+            debug!("Track is instrumental (musixmatch id: {})", track_id);
+            return Ok(FetchedLyrics {
+                result: LyricsResult::Instrumental,
+                provider_id,
+            });
+        }
+
+        if flag(&track, "has_richsync") {
+            let lines = self.fetch_richsync(track_id).await?;
+            if has_text(&lines) {
+                info!("Got Musixmatch richsync lyrics with {} lines", lines.len());
+                return Ok(Self::synced(query, lines, provider_id));
+            }
+        }
+
+        if flag(&track, "has_subtitles") {
+            let lines = self.fetch_subtitle(track_id).await?;
+            if has_text(&lines) {
+                info!("Got Musixmatch synced lyrics with {} lines", lines.len());
+                return Ok(Self::synced(query, lines, provider_id));
+            }
+        }
+
+        if flag(&track, "has_lyrics")
+            && let Some(plain) = self.fetch_plain(track_id).await?
+        {
+            info!("Got Musixmatch plain lyrics");
+            return Ok(FetchedLyrics {
+                result: LyricsResult::Unsynced(plain),
+                provider_id,
+            });
+        }
+
+        Ok(FetchedLyrics {
+            result: LyricsResult::NotFound,
+            provider_id,
+        })
+    }
+
+    async fn auth_state(&self) -> Option<AuthState> {
+        let authenticated = self.token_manager.has_token().await;
+        Some(AuthState {
+            authenticated,
+            expires_at: None,
+            detail: if authenticated {
+                "User token cached".into()
+            } else {
+                "No user token yet".into()
+            },
+        })
+    }
+
+    async fn reauth(&self) -> Result<(), CoreError> {
+        self.token_manager.refresh_token().await
+    }
+
+    async fn check(&self) -> CheckResult {
+        const NAME: &str = "Musixmatch user token";
+        match self.token_manager.get_token().await {
+            Ok(_) => CheckResult::pass(NAME, "Valid"),
+            Err(e) if e.is_network() => check_reachable(NAME, MUSIXMATCH_API_URL).await,
+            Err(e) => CheckResult::fail(NAME, e.to_string())
+                .with_hint("Musixmatch may be rate limiting this network, try again later"),
+        }
+    }
+}
+
+/// `message.header.status_code` of an API response
+fn status_code(response: &Value) -> Option<u64> {
+    response
+        .pointer("/message/header/status_code")
+        .and_then(Value::as_u64)
+}
+
+/// Read a 0/1 flag from a track object
+fn flag(track: &Value, key: &str) -> bool {
+    track.get(key).and_then(Value::as_u64) == Some(1)
+}
+
+/// Whether any line has text, since blank lines only mark instrumental breaks
+fn has_text(lines: &[LrcLine]) -> bool {
+    lines.iter().any(|line| !line.text.trim().is_empty())
+}
+
+fn provider_error(reason: String) -> CoreError {
+    CoreError::LyricsProviderFailed {
+        provider: PROVIDER_NAME.to_string(),
+        reason,
+    }
+}
@@ -0,0 +1,155 @@
+//! Conversion of Musixmatch richsync and subtitle bodies into LRC lines.
+//!
+//! Both bodies arrive as JSON encoded inside a JSON string field of the API response.
+
+use serde::Deserialize;
+use std::time::Duration;
+use versualizer_core::{LrcLine, LrcWord};
+
+/// One line of a richsync body
+#[derive(Debug, Deserialize)]
+struct RichsyncLine {
+    /// Line start in seconds
+    ts: f64,
+    /// Line end in seconds
+    te: f64,
+    /// Characters/words with offsets relative to `ts`
+    #[serde(default)]
+    l: Vec<RichsyncChunk>,
+    /// Full line text
+    x: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RichsyncChunk {
+    /// Chunk text, a word or a single space
+    c: String,
+    /// Offset from the line start in seconds
+    o: f64,
+}
+
+/// One line of an `mxm` format subtitle body
+#[derive(Debug, Deserialize)]
+struct SubtitleLine {
+    text: String,
+    time: SubtitleTime,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubtitleTime {
+    /// Line start in seconds
+    total: f64,
+}
+
+/// Parse a richsync body into word-timed lines
+///
+/// Whitespace chunks only separate words, so they are dropped and each word ends where
+/// the next one starts (the last word ends with its line). Lines without text are kept,
+/// as they mark instrumental breaks.
+pub fn parse_richsync(body: &str) -> serde_json::Result<Vec<LrcLine>> {
+    let lines: Vec<RichsyncLine> = serde_json::from_str(body)?;
+    Ok(lines
+        .into_iter()
+        .map(|line| {
+            let words: Vec<(f64, String)> = line
+                .l
+                .iter()
+                .filter(|chunk| !chunk.c.trim().is_empty())
+                .map(|chunk| (line.ts + chunk.o, chunk.c.trim().to_string()))
+                .collect();
+            let words = words
+                .iter()
+                .enumerate()
+                .map(|(i, (start, text))| {
+                    let end = words.get(i + 1).map_or(line.te, |(next, _)| *next);
+                    LrcWord {
+                        start_time: seconds(*start),
+                        end_time: Some(seconds(end)),
+                        text: text.clone(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            LrcLine {
+                start_time: seconds(line.ts),
+                text: line.x,
+                words: (!words.is_empty()).then_some(words),
+                romanized: None,
+                part: None,
+            }
+        })
+        .collect())
+}
+
+/// Parse an `mxm` format subtitle body into line-synced lines. Lines without text are
+/// kept, as they mark instrumental breaks.
+pub fn parse_subtitle(body: &str) -> serde_json::Result<Vec<LrcLine>> {
+    let lines: Vec<SubtitleLine> = serde_json::from_str(body)?;
+    Ok(lines
+        .into_iter()
+        .map(|line| LrcLine {
+            start_time: seconds(line.time.total),
+            text: line.text,
+            words: None,
+            romanized: None,
+            part: None,
+        })
+        .collect())
+}
+
+/// Convert API seconds to a duration, clamping negative or invalid values to zero
+fn seconds(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_richsync_drops_spaces_and_chains_word_ends() {
+        let body = r#"[
+            {"ts": 1.5, "te": 3.0, "l": [
+                {"c": "Hello", "o": 0.0},
+                {"c": " ", "o": 0.4},
+                {"c": "world", "o": 0.5}
+            ], "x": "Hello world"},
+            {"ts": 4.0, "te": 5.0, "l": [], "x": ""}
+        ]"#;
+
+        let lines = parse_richsync(body).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].start_time, Duration::from_millis(1500));
+        assert_eq!(lines[0].text, "Hello world");
+
+        let words = lines[0].words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!(words[0].start_time, Duration::from_millis(1500));
+        assert_eq!(words[0].end_time, Some(Duration::from_secs(2)));
+        assert_eq!(words[1].text, "world");
+        assert_eq!(words[1].end_time, Some(Duration::from_secs(3)));
+
+        // The empty line marks an instrumental break
+        assert_eq!(lines[1].start_time, Duration::from_secs(4));
+        assert!(lines[1].text.is_empty());
+        assert!(lines[1].words.is_none());
+    }
+
+    #[test]
+    fn test_parse_subtitle_keeps_blank_lines() {
+        let body = r#"[
+            {"text": "First", "time": {"total": 12.25, "minutes": 0, "seconds": 12, "hundredths": 25}},
+            {"text": "", "time": {"total": 15.0}},
+            {"text": "Second", "time": {"total": 17.5}}
+        ]"#;
+
+        let lines = parse_subtitle(body).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].start_time, Duration::from_millis(12250));
+        assert!(lines[1].text.is_empty());
+        assert_eq!(lines[2].text, "Second");
+        assert!(lines[2].words.is_none());
+    }
+}
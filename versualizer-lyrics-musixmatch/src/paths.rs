@@ -0,0 +1,22 @@
+//! Path constants for Musixmatch-specific files.
+
+use std::path::PathBuf;
+use versualizer_core::paths::{DataCategory, DataPath};
+
+/// The name of the Musixmatch user token cache file
+pub const MUSIXMATCH_TOKEN_CACHE_FILE_NAME: &str = ".musixmatch_token_cache.json";
+
+/// Get the Musixmatch token cache file path (`~/.config/versualizer/.musixmatch_token_cache.json`)
+#[must_use]
+pub fn musixmatch_token_cache_path() -> PathBuf {
+    versualizer_core::paths::config_dir().join(MUSIXMATCH_TOKEN_CACHE_FILE_NAME)
+}
+
+/// User data written by the Musixmatch provider, for the core data registry
+#[must_use]
+pub fn musixmatch_data_paths() -> Vec<DataPath> {
+    vec![DataPath::new(
+        DataCategory::Tokens,
+        musixmatch_token_cache_path(),
+    )]
+}
@@ -0,0 +1,225 @@
+//! Musixmatch user token acquisition and caching.
+//!
+//! The desktop app endpoints need a `usertoken` obtained anonymously from `token.get`.
+//! A token stays valid for a long time, so it is cached in memory and in a token cache
+//! file next to the other provider tokens until the API rejects it. Requesting tokens
+//! too often gets the network rate limited, so the file saves one per app start.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use versualizer_core::CoreError;
+
+use crate::paths::musixmatch_token_cache_path;
+use crate::{APP_ID, MUSIXMATCH_API_URL, PROVIDER_NAME, status_code};
+
+/// Musixmatch hands this placeholder out instead of a token when it wants a captcha solved
+const CAPTCHA_TOKEN_PREFIX: &str = "UpgradeOnly";
+
+/// Cookies the desktop endpoints expect, which otherwise redirect to a captcha page
+pub const SESSION_COOKIE: &str = "AWSELBCORS=0; AWSELB=0";
+
+/// Token cache file contents
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedToken {
+    user_token: String,
+}
+
+/// Caches the Musixmatch user token
+pub struct MusixmatchTokenManager {
+    client: reqwest::Client,
+    token_path: PathBuf,
+    cached_token: RwLock<Option<String>>,
+}
+
+impl MusixmatchTokenManager {
+    /// Token manager persisting to the default token cache file
+    #[must_use]
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_token_path(client, musixmatch_token_cache_path())
+    }
+
+    /// Token manager persisting to `token_path`
+    #[must_use]
+    pub const fn with_token_path(client: reqwest::Client, token_path: PathBuf) -> Self {
+        Self {
+            client,
+            token_path,
+            cached_token: RwLock::const_new(None),
+        }
+    }
+
+    /// Get the cached user token, loading it from the token cache file or requesting a
+    /// new one if there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token request fails or Musixmatch asks for a captcha.
+    pub async fn get_token(&self) -> Result<String, CoreError> {
+        if let Some(token) = self.cached_token.read().await.clone() {
+            return Ok(token);
+        }
+
+        let mut cached = self.cached_token.write().await;
+        // Another request may have refreshed the token while we waited for the lock
+        if let Some(token) = cached.clone() {
+            return Ok(token);
+        }
+
+        let token = if let Some(token) = self.load_token().await {
+            debug!("Loaded Musixmatch token from {:?}", self.token_path);
+            token
+        } else {
+            let token = self.request_token().await?;
+            self.save_token(&token).await;
+            token
+        };
+        *cached = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Request a new token and replace the cached one. The old token is kept if the
+    /// request fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token request fails or Musixmatch asks for a captcha.
+    pub async fn refresh_token(&self) -> Result<(), CoreError> {
+        let token = self.request_token().await?;
+        self.save_token(&token).await;
+        *self.cached_token.write().await = Some(token);
+        Ok(())
+    }
+
+    /// Whether a token is cached in memory or in the token cache file
+    pub async fn has_token(&self) -> bool {
+        self.cached_token.read().await.is_some()
+            || tokio::fs::try_exists(&self.token_path)
+                .await
+                .unwrap_or(false)
+    }
+
+    /// Drop the cached token so the next request acquires a fresh one
+    pub async fn invalidate_token(&self) {
+        debug!("Invalidating cached Musixmatch token");
+        *self.cached_token.write().await = None;
+        match tokio::fs::remove_file(&self.token_path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to delete Musixmatch token cache: {}", e),
+        }
+    }
+
+    /// Read the token cache file, if there is a readable one
+    async fn load_token(&self) -> Option<String> {
+        let content = tokio::fs::read_to_string(&self.token_path).await.ok()?;
+        match serde_json::from_str::<PersistedToken>(&content) {
+            Ok(persisted) if !persisted.user_token.is_empty() => Some(persisted.user_token),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Ignoring unreadable Musixmatch token cache: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Write the token cache file. Failures are logged, as the token still works for
+    /// this session.
+    async fn save_token(&self, token: &str) {
+        let persisted = PersistedToken {
+            user_token: token.to_string(),
+        };
+        let result = async {
+            if let Some(parent) = self.token_path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let content = serde_json::to_string_pretty(&persisted)?;
+            tokio::fs::write(&self.token_path, content).await?;
+            Ok::<_, CoreError>(())
+        }
+        .await;
+        match result {
+            Ok(()) => debug!("Saved Musixmatch token to {:?}", self.token_path),
+            Err(e) => warn!("Failed to save Musixmatch token: {}", e),
+        }
+    }
+
+    async fn request_token(&self) -> Result<String, CoreError> {
+        let url = format!("{MUSIXMATCH_API_URL}/token.get?app_id={APP_ID}&format=json");
+        info!("Requesting Musixmatch user token");
+
+        let response: Value = self
+            .client
+            .get(&url)
+            .header("Cookie", SESSION_COOKIE)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let status = status_code(&response);
+        let token = response
+            .pointer("/message/body/user_token")
+            .and_then(Value::as_str)
+            .filter(|token| !token.is_empty());
+        match token {
+            Some(token) if status == Some(200) && !token.starts_with(CAPTCHA_TOKEN_PREFIX) => {
+                Ok(token.to_string())
+            }
+            Some(_) => {
+                warn!("Musixmatch token request was rate limited (captcha required)");
+                Err(token_error("rate limited, try again later"))
+            }
+            None => Err(token_error(&format!(
+                "token.get returned status {}",
+                status.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+fn token_error(reason: &str) -> CoreError {
+    CoreError::LyricsProviderFailed {
+        provider: PROVIDER_NAME.to_string(),
+        reason: format!("could not acquire user token: {reason}"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn manager(name: &str) -> MusixmatchTokenManager {
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-musixmatch-{name}-{}.json",
+            std::process::id()
+        ));
+        MusixmatchTokenManager::with_token_path(reqwest::Client::new(), path)
+    }
+
+    #[tokio::test]
+    async fn test_token_persists_across_managers() {
+        let first = manager("persist");
+        first.save_token("abc123").await;
+
+        let second = manager("persist");
+        assert!(second.has_token().await);
+        assert_eq!(second.get_token().await.unwrap(), "abc123");
+
+        second.invalidate_token().await;
+        assert!(!second.has_token().await);
+        assert!(!first.token_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_token_cache_is_ignored() {
+        let manager = manager("unreadable");
+        std::fs::write(&manager.token_path, "not json").unwrap();
+        assert!(manager.load_token().await.is_none());
+        std::fs::remove_file(&manager.token_path).unwrap();
+    }
+}
@@ -0,0 +1,373 @@
+//! Lyrics provider that runs an external executable for each lookup.
+//!
+//! Plugins are listed under `[lyrics.plugins]` as `name = "path/to/executable"`. For every
+//! track the executable is started with no arguments, receives one JSON request on stdin and
+//! must print one JSON response on stdout, then exit with status 0.
+//!
+//! Request:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "track_name": "Let It Be",
+//!   "artist_name": "The Beatles",
+//!   "album_name": "Let It Be",
+//!   "duration_secs": 243,
+//!   "provider_ids": { "spotify": "7iN1s7xHE4ifF5povM6A48" },
+//!   "isrc": "GBAYE0601713"
+//! }
+//! ```
+//!
+//! `album_name`, `duration_secs` and `isrc` may be `null`. Response, one of:
+//!
+//! ```json
+//! { "version": 1, "status": "synced", "lyrics": "[00:01.00]First line\n[00:05.00]Second line", "id": "123" }
+//! { "version": 1, "status": "unsynced", "lyrics": "First line\nSecond line", "id": "123" }
+//! { "version": 1, "status": "not_found" }
+//! ```
+//!
+//! Synced lyrics are LRC text, enhanced word timing included. `id` is the plugin's own ID for
+//! the lyrics and is optional. `version` is the protocol version the plugin answers in and
+//! defaults to 1; responses in any other version are rejected. A non-zero exit status is
+//! reported as a provider error with the plugin's stderr, a response over 2 MiB is rejected,
+//! and a plugin that runs longer than 15 seconds is killed.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tracing::{debug, info};
+use versualizer_core::http::HttpLimits;
+use versualizer_core::{
+    CheckResult, CoreError, FetchedLyrics, LrcFile, LyricsProvider, LyricsQuery, LyricsResult,
+};
+
+/// Version of the request and response formats
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Largest accepted response, the same as for the JSON APIs of the other providers
+const MAX_RESPONSE_BYTES: u64 = HttpLimits::JSON.max_response_bytes;
+
+/// Most bytes of a plugin's stderr kept for an error
+const MAX_STDERR_BYTES: u64 = 16 * 1024;
+
+/// How long a plugin may run for one lookup
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Most characters of a plugin's stderr included in an error
+const MAX_STDERR_CHARS: usize = 500;
+
+/// Lyrics provider backed by a plugin executable
+pub struct PluginProvider {
+    /// Provider name from the config key. Leaked once at startup because providers are
+    /// identified by `&'static str` names.
+    name: &'static str,
+    path: PathBuf,
+}
+
+impl PluginProvider {
+    /// Create a provider that runs the executable at `path` under the name `name`
+    #[must_use]
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: Box::leak(name.into().into_boxed_str()),
+            path: path.into(),
+        }
+    }
+
+    /// Run the plugin with `request` on stdin and return its stdout
+    async fn run(&self, request: &[u8]) -> Result<Vec<u8>, CoreError> {
+        debug!("Running lyrics plugin {}: {:?}", self.name, self.path);
+        let mut child = Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| self.error(format!("failed to start {}: {e}", self.path.display())))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(request).await?;
+            // Dropping stdin closes it so the plugin sees the end of the request
+        }
+
+        let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            return Err(self.error("stdout and stderr were not captured".into()));
+        };
+        let output = async {
+            let ((stdout, stdout_cut), (stderr, _)) = tokio::try_join!(
+                read_capped(stdout, MAX_RESPONSE_BYTES),
+                read_capped(stderr, MAX_STDERR_BYTES),
+            )?;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, stdout, stdout_cut, stderr))
+        };
+        let (status, stdout, stdout_cut, stderr) = tokio::time::timeout(PLUGIN_TIMEOUT, output)
+            .await
+            .map_err(|_| self.error(format!("timed out after {PLUGIN_TIMEOUT:?}")))??;
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            let stderr: String = stderr.trim().chars().take(MAX_STDERR_CHARS).collect();
+            return Err(self.error(format!("exited with {status}: {stderr}")));
+        }
+        if stdout_cut {
+            return Err(self.error(format!(
+                "response is larger than the {MAX_RESPONSE_BYTES} byte limit"
+            )));
+        }
+        Ok(stdout)
+    }
+
+    /// Parse a response, rejecting other protocol versions before reading the rest
+    fn parse_response(&self, stdout: &[u8]) -> Result<PluginResponse, CoreError> {
+        let invalid = |e: serde_json::Error| self.error(format!("invalid response: {e}"));
+        let ResponseVersion { version } = serde_json::from_slice(stdout).map_err(invalid)?;
+        if version != PROTOCOL_VERSION {
+            return Err(self.error(format!(
+                "responded in protocol version {version}, only version {PROTOCOL_VERSION} is supported"
+            )));
+        }
+        serde_json::from_slice(stdout).map_err(invalid)
+    }
+
+    fn error(&self, reason: String) -> CoreError {
+        CoreError::LyricsProviderFailed {
+            provider: self.name.to_string(),
+            reason,
+        }
+    }
+}
+
+/// Request written to a plugin's stdin
+#[derive(Debug, Serialize)]
+struct PluginRequest<'a> {
+    version: u32,
+    track_name: &'a str,
+    artist_name: &'a str,
+    album_name: Option<&'a str>,
+    duration_secs: Option<u32>,
+    provider_ids: &'a HashMap<String, String>,
+    isrc: Option<&'a str>,
+}
+
+impl<'a> PluginRequest<'a> {
+    fn new(query: &'a LyricsQuery) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            track_name: &query.track_name,
+            artist_name: &query.artist_name,
+            album_name: query.album_name.as_deref(),
+            duration_secs: query.duration_secs,
+            provider_ids: &query.provider_ids,
+            isrc: query.isrc.as_deref(),
+        }
+    }
+}
+
+/// Protocol version of a response, 1 if the plugin leaves it out
+#[derive(Debug, Deserialize)]
+struct ResponseVersion {
+    #[serde(default = "default_version")]
+    version: u32,
+}
+
+const fn default_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+/// Read `reader` to the end, keeping at most `limit` bytes. Returns them and whether more
+/// were left out.
+async fn read_capped(
+    reader: impl AsyncRead + Unpin,
+    limit: u64,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut kept = Vec::new();
+    let mut reader = reader.take(limit);
+    reader.read_to_end(&mut kept).await?;
+    // Keep draining so a plugin writing more does not block on a full pipe
+    let rest = tokio::io::copy(&mut reader.into_inner(), &mut tokio::io::sink()).await?;
+    Ok((kept, rest > 0))
+}
+
+/// Response read from a plugin's stdout
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum PluginResponse {
+    Synced {
+        lyrics: String,
+        #[serde(default)]
+        id: Option<String>,
+    },
+    Unsynced {
+        lyrics: String,
+        #[serde(default)]
+        id: Option<String>,
+    },
+    NotFound,
+}
+
+impl PluginResponse {
+    /// Convert to fetched lyrics, parsing synced lyrics as LRC
+    fn into_fetched(self) -> Result<FetchedLyrics, CoreError> {
+        let (result, id) = match self {
+            Self::Synced { lyrics, id } => (LyricsResult::Synced(LrcFile::parse(&lyrics)?), id),
+            Self::Unsynced { lyrics, id } => (LyricsResult::Unsynced(lyrics), id),
+            Self::NotFound => (LyricsResult::NotFound, None),
+        };
+        Ok(FetchedLyrics {
+            result,
+            provider_id: id.unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for PluginProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
+        info!(
+            "Fetching lyrics from plugin {} for: {} - {}",
+            self.name, query.artist_name, query.track_name
+        );
+        let request = serde_json::to_vec(&PluginRequest::new(query))?;
+        let stdout = self.run(&request).await?;
+        self.parse_response(&stdout)?.into_fetched()
+    }
+
+    async fn check(&self) -> CheckResult {
+        let name = format!("Plugin {}", self.name);
+        match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) if metadata.is_file() => {
+                CheckResult::pass(name, self.path.display().to_string())
+            }
+            Ok(_) => CheckResult::fail(name, format!("{} is not a file", self.path.display())),
+            Err(e) => CheckResult::fail(name, format!("{}: {e}", self.path.display()))
+                .with_hint("Fix the path under [lyrics.plugins]"),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_format() {
+        let query = LyricsQuery::new("Let It Be", "The Beatles").with_duration(243);
+        let json: serde_json::Value = serde_json::to_value(PluginRequest::new(&query)).unwrap();
+
+        assert_eq!(json["version"], PROTOCOL_VERSION);
+        assert_eq!(json["track_name"], "Let It Be");
+        assert_eq!(json["duration_secs"], 243);
+        assert!(json["album_name"].is_null());
+        assert!(json["isrc"].is_null());
+        assert!(json["provider_ids"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_response_formats() {
+        let synced: PluginResponse = serde_json::from_str(
+            r#"{"status": "synced", "lyrics": "[00:01.00]Hello\n[00:05.00]World", "id": "42"}"#,
+        )
+        .unwrap();
+        let fetched = synced.into_fetched().unwrap();
+        assert_eq!(fetched.provider_id, "42");
+        assert!(matches!(fetched.result, LyricsResult::Synced(ref lrc) if lrc.lines.len() == 2));
+
+        let unsynced: PluginResponse =
+            serde_json::from_str(r#"{"status": "unsynced", "lyrics": "Hello"}"#).unwrap();
+        assert!(matches!(
+            unsynced.into_fetched().unwrap().result,
+            LyricsResult::Unsynced(ref text) if text == "Hello"
+        ));
+
+        let not_found: PluginResponse = serde_json::from_str(r#"{"status": "not_found"}"#).unwrap();
+        assert!(matches!(
+            not_found.into_fetched().unwrap().result,
+            LyricsResult::NotFound
+        ));
+
+        assert!(serde_json::from_str::<PluginResponse>(r#"{"status": "maybe"}"#).is_err());
+    }
+
+    #[test]
+    fn test_response_versions() {
+        let provider = PluginProvider::new("versioned", "plugin");
+        assert!(
+            provider
+                .parse_response(br#"{"version": 1, "status": "not_found"}"#)
+                .is_ok()
+        );
+        assert!(
+            provider
+                .parse_response(br#"{"status": "not_found"}"#)
+                .is_ok()
+        );
+
+        let err = provider
+            .parse_response(br#"{"version": 2, "status": "lines", "lines": []}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("protocol version 2"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fetch_runs_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path =
+            std::env::temp_dir().join(format!("versualizer-plugin-test-{}.sh", std::process::id()));
+        std::fs::write(
+            &path,
+            "#!/bin/sh\ncat > /dev/null\necho '{\"status\": \"unsynced\", \"lyrics\": \"Hi\"}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let provider = PluginProvider::new("test_plugin", &path);
+        let fetched = provider
+            .fetch(&LyricsQuery::new("Song", "Artist"))
+            .await
+            .unwrap();
+        assert!(matches!(fetched.result, LyricsResult::Unsynced(ref text) if text == "Hi"));
+        assert_eq!(provider.name(), "test_plugin");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fetch_rejects_oversized_response() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!(
+            "versualizer-plugin-large-{}.sh",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\ncat > /dev/null\nhead -c {} /dev/zero\n",
+                MAX_RESPONSE_BYTES + 1
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = PluginProvider::new("large_plugin", &path)
+            .fetch(&LyricsQuery::new("Song", "Artist"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("byte limit"), "{err}");
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,241 @@
+//! Push notifications of Spotify playback changes through the web player's dealer
+//! connection.
+//!
+//! The dealer is the WebSocket the Spotify web player listens on for Connect state. Once
+//! registered for player notifications it sends a message on every play, pause, seek and
+//! track change, so the REST poller can be told to poll straight away instead of waiting
+//! for its next interval. Polling keeps running alongside it, so a dropped connection only
+//! costs latency until it is back.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use versualizer_core::CoreError;
+use versualizer_core::http::{self, HttpLimits};
+use versualizer_spotify_api::config::DEFAULT_SECRET_KEY_URL;
+
+use crate::auth::SpotifyAuthError;
+use crate::token_manager::SpotifyTokenManager;
+
+/// Resolves the dealer hosts currently in use
+const APRESOLVE_URL: &str = "https://apresolve.spotify.com/?type=dealer";
+
+/// Dealer host used when apresolve does not answer
+const FALLBACK_DEALER: &str = "dealer.spotify.com:443";
+
+/// Subscribes a dealer connection to player notifications
+const NOTIFICATIONS_URL: &str = "https://api.spotify.com/v1/me/notifications/player";
+
+/// How often to ping the dealer to keep the connection open
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Reconnect delay after the first failure, doubled per failure up to `MAX_RECONNECT_DELAY`
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_mins(1);
+
+#[derive(Debug, thiserror::Error)]
+enum DealerError {
+    #[error("Failed to get access token: {0}")]
+    Auth(#[from] SpotifyAuthError),
+
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("Registering for player notifications failed with status {0}")]
+    Register(reqwest::StatusCode),
+
+    #[error("Connection closed")]
+    Closed,
+}
+
+/// A dealer message, of which only the routing fields are read
+#[derive(Debug, Deserialize)]
+struct DealerMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    uri: String,
+    #[serde(default)]
+    headers: std::collections::HashMap<String, String>,
+}
+
+impl DealerMessage {
+    /// Whether the message reports a change of playback state
+    fn is_player_change(&self) -> bool {
+        self.kind == "message"
+            && (self.uri.starts_with("hm://connect-state/") || self.uri.starts_with("wss://event"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApresolveResponse {
+    dealer: Vec<String>,
+}
+
+/// Listens for playback changes on the Spotify dealer and wakes the poller on each one.
+///
+/// **WARNING:** Like [`SpotifyLyricsProvider`](crate::SpotifyLyricsProvider), this signs in
+/// with the `sp_dc` cookie of a web session, which may violate Spotify's Terms of Service.
+pub struct SpotifyDealer {
+    token_manager: SpotifyTokenManager,
+    client: reqwest::Client,
+}
+
+impl SpotifyDealer {
+    /// Create a dealer client signing in with `sp_dc`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    pub fn new(
+        sp_dc: impl Into<String>,
+        secret_key_url: Option<String>,
+    ) -> Result<Self, CoreError> {
+        let client = http::base_client(reqwest::Client::builder(), &HttpLimits::JSON)?;
+        let secret_url = secret_key_url.unwrap_or_else(|| DEFAULT_SECRET_KEY_URL.to_string());
+        Ok(Self {
+            token_manager: SpotifyTokenManager::new(sp_dc, secret_url, client.clone()),
+            client,
+        })
+    }
+
+    /// Run [`run`](Self::run) in the background, returning what it notifies on each
+    /// playback change
+    #[must_use]
+    pub fn spawn(self, cancel_token: CancellationToken) -> Arc<Notify> {
+        let wakeup = Arc::new(Notify::new());
+        tokio::spawn(self.run(wakeup.clone(), cancel_token));
+        wakeup
+    }
+
+    /// Stay connected to the dealer until `cancel_token` is cancelled, notifying `wakeup`
+    /// on each playback change. Lost connections are reopened with exponential backoff.
+    pub async fn run(self, wakeup: Arc<Notify>, cancel_token: CancellationToken) {
+        let mut delay = MIN_RECONNECT_DELAY;
+        loop {
+            let result = tokio::select! {
+                () = cancel_token.cancelled() => return,
+                result = self.connect(&wakeup, &mut delay) => result,
+            };
+            if let Err(e) = result {
+                if let DealerError::Auth(_) = e {
+                    self.token_manager.invalidate_token().await;
+                }
+                warn!(
+                    "Spotify push connection lost, reconnecting in {}s: {}",
+                    delay.as_secs(),
+                    e
+                );
+            }
+            tokio::select! {
+                () = cancel_token.cancelled() => return,
+                () = tokio::time::sleep(delay) => {}
+            }
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    }
+
+    /// Open one connection and forward its playback changes until it drops. `delay` is
+    /// reset once the connection is registered.
+    async fn connect(&self, wakeup: &Notify, delay: &mut Duration) -> Result<(), DealerError> {
+        let token = self.token_manager.get_access_token().await?;
+        let host = self.resolve_host().await;
+        let url = format!("wss://{host}/?access_token={token}");
+        let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+        debug!("Connected to Spotify dealer {}", host);
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ping.tick() => {
+                    socket.send(Message::text(r#"{"type":"ping"}"#)).await?;
+                }
+                message = socket.next() => {
+                    let text = match message.ok_or(DealerError::Closed)?? {
+                        Message::Text(text) => text,
+                        Message::Close(_) => return Err(DealerError::Closed),
+                        _ => continue,
+                    };
+                    let Ok(message) = serde_json::from_str::<DealerMessage>(text.as_str()) else {
+                        continue;
+                    };
+                    if let Some(connection_id) = message.headers.get("Spotify-Connection-Id") {
+                        self.register(&token, connection_id).await?;
+                        info!("Listening for Spotify playback changes");
+                        *delay = MIN_RECONNECT_DELAY;
+                    } else if message.is_player_change() {
+                        wakeup.notify_one();
+                    }
+                }
+            }
+        }
+    }
+
+    /// The first dealer host apresolve lists, or the well-known one
+    async fn resolve_host(&self) -> String {
+        let response = match self.client.get(APRESOLVE_URL).send().await {
+            Ok(response) => response.json::<ApresolveResponse>().await.ok(),
+            Err(e) => {
+                debug!("Dealer apresolve failed: {}", e);
+                None
+            }
+        };
+        response
+            .and_then(|response| response.dealer.into_iter().next())
+            .unwrap_or_else(|| FALLBACK_DEALER.to_string())
+    }
+
+    /// Subscribe the connection `connection_id` to player notifications
+    async fn register(&self, token: &str, connection_id: &str) -> Result<(), DealerError> {
+        let response = self
+            .client
+            .put(NOTIFICATIONS_URL)
+            .query(&[("connection_id", connection_id)])
+            .bearer_auth(token)
+            .body("")
+            .send()
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(DealerError::Register(response.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn message(json: &str) -> DealerMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_player_changes() {
+        assert!(
+            message(r#"{"type": "message", "uri": "hm://connect-state/v1/cluster"}"#)
+                .is_player_change()
+        );
+        assert!(
+            message(r#"{"type": "message", "uri": "wss://event", "payloads": []}"#)
+                .is_player_change()
+        );
+        assert!(!message(r#"{"type": "pong"}"#).is_player_change());
+        assert!(
+            !message(r#"{"type": "message", "uri": "hm://pusher/v1/connections/abc"}"#)
+                .is_player_change()
+        );
+    }
+}
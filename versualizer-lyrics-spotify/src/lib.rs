@@ -5,6 +5,7 @@
 //! Use at your own risk.
 
 mod auth;
+mod dealer;
 mod token_manager;
 mod totp;
 
@@ -12,24 +13,29 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
 use tracing::{info, warn};
+use versualizer_core::http::{self, HttpLimits};
 use versualizer_core::{
-    CoreError, FetchedLyrics, LrcFile, LrcLine, LrcMetadata, LyricsProvider, LyricsQuery,
-    LyricsResult,
+    AuthState, CheckResult, CoreError, FetchedLyrics, LrcFile, LrcLine, LrcMetadata, LrcWord,
+    LyricsProvider, LyricsQuery, LyricsResult, RetryConfig,
 };
 use versualizer_spotify_api::config::DEFAULT_SECRET_KEY_URL;
 
+use auth::SpotifyAuthError;
 use token_manager::SpotifyTokenManager;
 
+pub use dealer::SpotifyDealer;
+
+/// Provider name used in config and the cache
+pub const PROVIDER_NAME: &str = "spotify_lyrics";
+
 const SPOTIFY_LYRICS_API: &str = "https://spclient.wg.spotify.com/color-lyrics/v2/track";
 
-/// Default timeout for HTTP requests (10 seconds)
-const DEFAULT_TIMEOUT_SECS: u64 = 10;
-/// Default number of retry attempts
-const DEFAULT_MAX_RETRIES: u32 = 3;
+const SPOTIFY_TRANSCRIPT_API: &str =
+    "https://spclient.wg.spotify.com/transcript-read-along/v2/episode";
+
 /// User agent for requests
 const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
 
@@ -45,12 +51,14 @@ pub struct SpotifyLyricsProvider {
 }
 
 impl SpotifyLyricsProvider {
-    /// Create a new Spotify lyrics provider with default 10-second timeout and 3 retries.
+    /// Create a new Spotify lyrics provider with the JSON API limits (10-second timeout,
+    /// 2 MiB responses) and the retries of `retry`.
     ///
     /// # Arguments
     ///
     /// * `sp_dc` - The Spotify `sp_dc` cookie value
     /// * `secret_key_url` - Optional custom URL for fetching secret keys (uses default if `None`)
+    /// * `retry` - Retries of failed lyrics requests
     ///
     /// # Errors
     ///
@@ -58,6 +66,7 @@ impl SpotifyLyricsProvider {
     pub fn new(
         sp_dc: impl Into<String>,
         secret_key_url: Option<String>,
+        retry: &RetryConfig,
     ) -> Result<Self, CoreError> {
         let sp_dc = sp_dc.into();
         let configured = !sp_dc.is_empty();
@@ -69,11 +78,7 @@ impl SpotifyLyricsProvider {
             );
         }
 
-        // Base client with timeout
-        let base_client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .connect_timeout(Duration::from_secs(5))
-            .build()?;
+        let base_client = http::base_client(reqwest::Client::builder(), &HttpLimits::JSON)?;
 
         // Create token manager with the base client
         let secret_url = secret_key_url.unwrap_or_else(|| DEFAULT_SECRET_KEY_URL.to_string());
@@ -83,16 +88,9 @@ impl SpotifyLyricsProvider {
             base_client.clone(),
         ));
 
-        // Wrap with retry middleware (exponential backoff) for lyrics requests
-        let retry_policy =
-            ExponentialBackoff::builder().build_with_max_retries(DEFAULT_MAX_RETRIES);
-        let client = ClientBuilder::new(base_client)
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
-
         Ok(Self {
             token_manager,
-            client,
+            client: http::provider_client(base_client, HttpLimits::JSON, retry),
             configured,
         })
     }
@@ -103,6 +101,29 @@ impl SpotifyLyricsProvider {
         self.configured
     }
 
+    /// Preflight check of the `sp_dc` cookie by requesting an access token
+    pub async fn check_sp_dc(&self) -> CheckResult {
+        const NAME: &str = "Spotify sp_dc cookie";
+        let cookie_hint =
+            "Copy a fresh sp_dc cookie from open.spotify.com into providers.spotify.sp_dc";
+        if !self.configured {
+            return CheckResult::pass(NAME, "Not configured, Spotify lyrics disabled");
+        }
+
+        match self.token_manager.get_access_token().await {
+            Ok(_) => CheckResult::pass(NAME, "Valid"),
+            Err(SpotifyAuthError::SpDcInvalid) => {
+                CheckResult::fail(NAME, "Invalid or expired").with_hint(cookie_hint)
+            }
+            Err(
+                e @ (SpotifyAuthError::SecretKeyFailed(_) | SpotifyAuthError::SecretDecodeError),
+            ) => CheckResult::fail(NAME, e.to_string()).with_hint(
+                "Check providers.spotify.secret_key_url, the TOTP secret source may have moved",
+            ),
+            Err(e) => CheckResult::fail(NAME, e.to_string()).with_hint(cookie_hint),
+        }
+    }
+
     /// Extract track ID from Spotify URI or URL
     fn extract_track_id(id: &str) -> Option<&str> {
         // Handle various formats:
@@ -130,14 +151,21 @@ impl SpotifyLyricsProvider {
         None
     }
 
-    /// Validate query and extract track ID
-    fn validate_query(&self, query: &LyricsQuery) -> Result<String, CoreError> {
-        if !self.is_configured() {
-            return Err(CoreError::LyricsProviderFailed {
+    /// Fail unless the `sp_dc` cookie is configured
+    fn ensure_configured(&self) -> Result<(), CoreError> {
+        if self.is_configured() {
+            Ok(())
+        } else {
+            Err(CoreError::LyricsProviderFailed {
                 provider: self.name().to_string(),
                 reason: "sp_dc cookie not configured".into(),
-            });
+            })
         }
+    }
+
+    /// Validate query and extract track ID
+    fn validate_query(&self, query: &LyricsQuery) -> Result<String, CoreError> {
+        self.ensure_configured()?;
 
         query.spotify_track_id().map_or_else(
             || {
@@ -157,22 +185,31 @@ impl SpotifyLyricsProvider {
         )
     }
 
-    /// Send request to Spotify lyrics API using Bearer token authentication.
-    async fn send_request(&self, track_id: &str) -> Result<reqwest::Response, CoreError> {
-        // Get valid access token (refreshes if needed)
-        let access_token = self.token_manager.get_access_token().await.map_err(|e| {
-            CoreError::LyricsProviderFailed {
+    /// Map a token error, keeping network failures distinguishable
+    fn auth_error(&self, e: SpotifyAuthError) -> CoreError {
+        match e {
+            SpotifyAuthError::Network(e) => CoreError::NetworkError(e),
+            e => CoreError::LyricsProviderFailed {
                 provider: self.name().to_string(),
                 reason: e.to_string(),
-            }
-        })?;
+            },
+        }
+    }
+
+    /// Send a GET request to `url` using Bearer token authentication.
+    async fn send_request(&self, url: &str) -> Result<reqwest::Response, CoreError> {
+        // Get valid access token (refreshes if needed)
+        let access_token = self
+            .token_manager
+            .get_access_token()
+            .await
+            .map_err(|e| self.auth_error(e))?;
 
-        let url = format!("{SPOTIFY_LYRICS_API}/{track_id}?format=json&market=from_token");
         info!("Spotify GET: {}", url);
 
         let response = self
             .client
-            .get(&url)
+            .get(url)
             .header("Authorization", format!("Bearer {access_token}"))
             .header("App-Platform", "WebPlayer")
             .header("User-Agent", USER_AGENT)
@@ -229,8 +266,10 @@ impl SpotifyLyricsProvider {
             .filter(|line| !line.words.is_empty() && line.words != "♪")
             .map(|line| LrcLine {
                 start_time: Duration::from_millis(line.start_time_ms.parse().unwrap_or(0)),
+                words: syllable_words(&line),
                 text: line.words,
-                words: None,
+                romanized: None,
+                part: None,
             })
             .collect();
 
@@ -258,6 +297,36 @@ impl SpotifyLyricsProvider {
         }
     }
 
+    /// Fetch the transcript of a podcast episode, shown as synced lyrics
+    async fn fetch_transcript(
+        &self,
+        query: &LyricsQuery,
+        episode_id: &str,
+    ) -> Result<FetchedLyrics, CoreError> {
+        self.ensure_configured()?;
+        let url = format!("{SPOTIFY_TRANSCRIPT_API}/{episode_id}?format=json");
+        let response = self.send_request(&url).await?;
+
+        // Most episodes have no transcript
+        if let Some(not_found) = Self::check_not_found(&response, episode_id) {
+            return Ok(not_found);
+        }
+        self.check_auth_error(&response).await?;
+
+        let transcript: SpotifyTranscript = http::read_json(response).await?;
+        let result = transcript_lrc(transcript, query).map_or(LyricsResult::NotFound, |lrc| {
+            info!(
+                "Got Spotify episode transcript with {} lines",
+                lrc.lines.len()
+            );
+            LyricsResult::Synced(lrc)
+        });
+        Ok(FetchedLyrics {
+            result,
+            provider_id: episode_id.to_string(),
+        })
+    }
+
     /// Parse unsynced lyrics from Spotify response
     fn parse_unsynced_lyrics(lyrics: &SpotifyLyrics, track_id: String) -> FetchedLyrics {
         let text: String = lyrics
@@ -300,18 +369,136 @@ struct SpotifyLyricsLine {
     #[serde(rename = "startTimeMs")]
     start_time_ms: String,
     words: String,
-    // Note: end_time_ms exists in API response but is unused; serde ignores unknown fields by default
+    /// `"0"` when the API does not know when the line ends
+    #[serde(rename = "endTimeMs", default)]
+    end_time_ms: Option<String>,
+    /// Timed slices of `words`, present for `SYLLABLE_SYNCED` lyrics
+    #[serde(default)]
+    syllables: Vec<SpotifySyllable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifySyllable {
+    #[serde(rename = "startTimeMs")]
+    start_time_ms: String,
+    /// Length of the syllable in characters of the line text
+    #[serde(rename = "numChars")]
+    num_chars: String,
+}
+
+/// Transcript of a podcast episode: timed sentences, between section titles
+#[derive(Debug, Deserialize)]
+struct SpotifyTranscript {
+    #[serde(default)]
+    section: Vec<SpotifyTranscriptSection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyTranscriptSection {
+    #[serde(rename = "startMs", default)]
+    start_ms: u64,
+    /// Missing for section titles
+    #[serde(default)]
+    text: Option<SpotifyTranscriptText>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyTranscriptText {
+    sentence: SpotifyTranscriptSentence,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyTranscriptSentence {
+    text: String,
+}
+
+/// Synced lyrics with a line per sentence of `transcript`, or `None` if it has none
+fn transcript_lrc(transcript: SpotifyTranscript, query: &LyricsQuery) -> Option<LrcFile> {
+    let mut lines: Vec<LrcLine> = transcript
+        .section
+        .into_iter()
+        .filter_map(|section| {
+            let text = section.text?.sentence.text.trim().to_string();
+            (!text.is_empty()).then(|| LrcLine {
+                start_time: Duration::from_millis(section.start_ms),
+                text,
+                words: None,
+                romanized: None,
+                part: None,
+            })
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    lines.sort_by_key(|line| line.start_time);
+
+    Some(LrcFile {
+        metadata: LrcMetadata {
+            title: Some(query.track_name.clone()),
+            artist: Some(query.artist_name.clone()),
+            album: query.album_name.clone(),
+            ..Default::default()
+        },
+        lines,
+    })
+}
+
+/// Word timing from the syllables of `line`, or `None` if it has none or they do not fit
+/// its text. Each syllable ends where the next one starts, the last one with the line.
+fn syllable_words(line: &SpotifyLyricsLine) -> Option<Vec<LrcWord>> {
+    let mut chars = line.words.chars();
+    let mut syllables = Vec::with_capacity(line.syllables.len());
+    for syllable in &line.syllables {
+        let start_ms: u64 = syllable.start_time_ms.parse().ok()?;
+        let len: usize = syllable.num_chars.parse().ok()?;
+        let text: String = chars.by_ref().take(len).collect();
+        if text.chars().count() < len {
+            return None;
+        }
+        syllables.push((start_ms, text));
+    }
+    // Text past the last syllable belongs to it
+    if let Some((_, text)) = syllables.last_mut() {
+        text.extend(chars);
+    }
+
+    let line_end_ms = line
+        .end_time_ms
+        .as_deref()
+        .and_then(|end| end.parse::<u64>().ok())
+        .filter(|&end| end > 0);
+    let words: Vec<LrcWord> = syllables
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, text))| !text.trim().is_empty())
+        .map(|(i, (start_ms, text))| LrcWord {
+            start_time: Duration::from_millis(*start_ms),
+            end_time: syllables
+                .get(i + 1)
+                .map(|(next_ms, _)| *next_ms)
+                .or(line_end_ms)
+                .map(Duration::from_millis),
+            text: text.trim().to_string(),
+        })
+        .collect();
+    (!words.is_empty()).then_some(words)
 }
 
 #[async_trait]
 impl LyricsProvider for SpotifyLyricsProvider {
     fn name(&self) -> &'static str {
-        "spotify_lyrics"
+        PROVIDER_NAME
     }
 
     async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
+        if let Some(episode_id) = query.spotify_episode_id() {
+            return self.fetch_transcript(query, episode_id).await;
+        }
+
         let track_id = self.validate_query(query)?;
-        let response = self.send_request(&track_id).await?;
+        let url = format!("{SPOTIFY_LYRICS_API}/{track_id}?format=json&market=from_token");
+        let response = self.send_request(&url).await?;
 
         // Handle 404 (not found) - return early with NotFound result
         if let Some(not_found) = Self::check_not_found(&response, &track_id) {
@@ -321,7 +508,7 @@ impl LyricsProvider for SpotifyLyricsProvider {
         // Check for auth errors and other failures
         self.check_auth_error(&response).await?;
 
-        let result: SpotifyLyricsResponse = response.json().await?;
+        let result: SpotifyLyricsResponse = http::read_json(response).await?;
 
         Ok(match result.lyrics.sync_type.as_str() {
             "LINE_SYNCED" | "SYLLABLE_SYNCED" => {
@@ -337,4 +524,106 @@ impl LyricsProvider for SpotifyLyricsProvider {
             }
         })
     }
+
+    async fn auth_state(&self) -> Option<AuthState> {
+        if !self.configured {
+            return Some(AuthState {
+                authenticated: false,
+                expires_at: None,
+                detail: "sp_dc cookie not configured".into(),
+            });
+        }
+        let expires_at = self.token_manager.cached_expiry().await;
+        Some(AuthState {
+            authenticated: expires_at.is_some(),
+            expires_at,
+            detail: if expires_at.is_some() {
+                "sp_dc cookie accepted".into()
+            } else {
+                "No access token yet".into()
+            },
+        })
+    }
+
+    async fn reauth(&self) -> Result<(), CoreError> {
+        self.token_manager.invalidate_token().await;
+        self.token_manager
+            .get_access_token()
+            .await
+            .map(|_| ())
+            .map_err(|e| self.auth_error(e))
+    }
+
+    async fn check(&self) -> CheckResult {
+        self.check_sp_dc().await
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn line(json: &str) -> SpotifyLyricsLine {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_syllable_words() {
+        let line = line(
+            r#"{"startTimeMs": "1000", "words": "Hello world", "endTimeMs": "3000", "syllables": [
+                {"startTimeMs": "1000", "numChars": "3"},
+                {"startTimeMs": "1400", "numChars": "3"},
+                {"startTimeMs": "2000", "numChars": "5"}
+            ]}"#,
+        );
+
+        let words = syllable_words(&line).unwrap();
+        let texts: Vec<_> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(texts, ["Hel", "lo", "world"]);
+        assert_eq!(words[1].start_time, Duration::from_millis(1400));
+        assert_eq!(words[1].end_time, Some(Duration::from_secs(2)));
+        assert_eq!(words[2].end_time, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_syllable_words_without_usable_syllables() {
+        // Line-synced lyrics have no syllables and an unknown end
+        assert!(
+            syllable_words(&line(
+                r#"{"startTimeMs": "1000", "words": "Hello", "endTimeMs": "0", "syllables": []}"#
+            ))
+            .is_none()
+        );
+        // Syllables longer than the text do not describe it
+        assert!(
+            syllable_words(&line(
+                r#"{"startTimeMs": "1000", "words": "Hi", "syllables": [{"startTimeMs": "1000", "numChars": "5"}]}"#
+            ))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_transcript_lrc() {
+        let transcript: SpotifyTranscript = serde_json::from_str(
+            r#"{"section": [
+                {"startMs": 0, "title": {"title": "Introduction"}},
+                {"startMs": 4200, "text": {"sentence": {"text": " Welcome back to the show. "}}},
+                {"startMs": 1500, "text": {"sentence": {"text": "Hi."}}},
+                {"startMs": 9000, "text": {"sentence": {"text": ""}}}
+            ]}"#,
+        )
+        .unwrap();
+        let query = LyricsQuery::new("Episode 12", "The Show").with_album("Podcast");
+
+        let lrc = transcript_lrc(transcript, &query).unwrap();
+        let texts: Vec<_> = lrc.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, ["Hi.", "Welcome back to the show."]);
+        assert_eq!(lrc.lines[1].start_time, Duration::from_millis(4200));
+        assert_eq!(lrc.metadata.title.as_deref(), Some("Episode 12"));
+
+        let empty: SpotifyTranscript = serde_json::from_str(r#"{"section": []}"#).unwrap();
+        assert!(transcript_lrc(empty, &query).is_none());
+    }
 }
@@ -25,7 +25,7 @@ const SERVER_TIME_URL: &str = "https://open.spotify.com/api/server-time";
 const TOKEN_URL: &str = "https://open.spotify.com/api/token";
 
 /// Maximum age for cached secret key (24 hours)
-const SECRET_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const SECRET_CACHE_MAX_AGE: Duration = Duration::from_hours(24);
 
 /// Buffer time before token expiration to trigger refresh (60 seconds)
 const TOKEN_REFRESH_BUFFER_SECS: u64 = 60;
@@ -234,6 +234,15 @@ impl SpotifyTokenManager {
         })
     }
 
+    /// Expiry of the cached access token, or `None` if no token is cached.
+    pub async fn cached_expiry(&self) -> Option<SystemTime> {
+        self.cached_token
+            .read()
+            .await
+            .as_ref()
+            .map(|token| UNIX_EPOCH + Duration::from_millis(token.expires_at_ms))
+    }
+
     /// Invalidate the cached token, forcing a refresh on next request.
     pub async fn invalidate_token(&self) {
         *self.cached_token.write().await = None;
@@ -0,0 +1,362 @@
+//! Lyrics frames from `ID3v2.3` and `ID3v2.4` tags (MP3, and some WAV and AIFF files).
+//!
+//! `SYLT` frames hold synchronized lyrics as a list of `(text, timestamp)` entries, either
+//! one per line or one per syllable with a leading newline starting each line. `USLT`
+//! frames hold plain text, which some taggers fill with LRC.
+
+use std::time::Duration;
+
+use versualizer_core::{LrcLine, LrcWord};
+
+use crate::TagLyrics;
+
+/// Size of the tag header and of each frame header
+const HEADER_LEN: usize = 10;
+
+/// Tag header flag: the whole tag is unsynchronised (`ID3v2.3`)
+const TAG_FLAG_UNSYNC: u8 = 0x80;
+/// Tag header flag: an extended header follows the tag header
+const TAG_FLAG_EXTENDED: u8 = 0x40;
+
+/// `SYLT` timestamp format for absolute milliseconds (the other format, MPEG frames,
+/// cannot be converted without decoding the audio)
+const SYLT_FORMAT_MILLIS: u8 = 2;
+
+/// Size of the `ID3v2` tag at the start of `data`, including its header, if there is one
+#[must_use]
+pub fn tag_len(data: &[u8]) -> Option<usize> {
+    if data.len() < HEADER_LEN || !data.starts_with(b"ID3") {
+        return None;
+    }
+    Some(HEADER_LEN + syncsafe(&data[6..10]))
+}
+
+/// Lyrics from the `ID3v2` tag at the start of `data`, preferring `SYLT` over `USLT`
+#[must_use]
+pub fn read(data: &[u8]) -> Option<TagLyrics> {
+    let end = tag_len(data)?.min(data.len());
+    let version = data[3];
+    if !matches!(version, 3 | 4) {
+        return None;
+    }
+    let flags = data[5];
+    let mut body = data[HEADER_LEN..end].to_vec();
+    if version == 3 && flags & TAG_FLAG_UNSYNC != 0 {
+        body = remove_unsync(&body);
+    }
+    let mut pos = 0;
+    if flags & TAG_FLAG_EXTENDED != 0 {
+        let size = body.get(0..4)?;
+        // v2.3 sizes exclude the size field itself, v2.4 sizes include it
+        pos = if version == 4 {
+            syncsafe(size)
+        } else {
+            be_u32(size) + 4
+        };
+    }
+
+    let mut synced = None;
+    let mut unsynced = None;
+    while let Some(header) = body.get(pos..pos + HEADER_LEN) {
+        if header[0] == 0 {
+            break; // Padding
+        }
+        let id = &header[0..4];
+        let size = if version == 4 {
+            syncsafe(&header[4..8])
+        } else {
+            be_u32(&header[4..8])
+        };
+        let format_flags = header[9];
+        pos += HEADER_LEN;
+        let Some(frame) = body.get(pos..pos + size) else {
+            break;
+        };
+        pos += size;
+
+        if id != b"SYLT" && id != b"USLT" {
+            continue;
+        }
+        let Some(frame) = frame_content(frame, version, format_flags) else {
+            continue;
+        };
+        if id == b"SYLT" && synced.is_none() {
+            synced = parse_sylt(&frame);
+        } else if id == b"USLT" && unsynced.is_none() {
+            unsynced = parse_uslt(&frame);
+        }
+    }
+
+    synced
+        .map(TagLyrics::Synced)
+        .or_else(|| unsynced.map(|text| TagLyrics::from_text(&text)))
+}
+
+/// Frame data with per-frame encodings undone, or `None` for compressed or encrypted frames
+fn frame_content(frame: &[u8], version: u8, format_flags: u8) -> Option<Vec<u8>> {
+    if version == 4 {
+        // Compression and encryption
+        if format_flags & 0x0C != 0 {
+            return None;
+        }
+        // A data length indicator precedes the data
+        let frame = if format_flags & 0x01 != 0 {
+            frame.get(4..)?
+        } else {
+            frame
+        };
+        if format_flags & 0x02 != 0 {
+            return Some(remove_unsync(frame));
+        }
+        Some(frame.to_vec())
+    } else {
+        if format_flags & 0xC0 != 0 {
+            return None;
+        }
+        // Group identity byte
+        let frame = if format_flags & 0x20 != 0 {
+            frame.get(1..)?
+        } else {
+            frame
+        };
+        Some(frame.to_vec())
+    }
+}
+
+/// `USLT`: encoding, language, descriptor, text
+fn parse_uslt(frame: &[u8]) -> Option<String> {
+    let encoding = *frame.first()?;
+    let rest = frame.get(4..)?;
+    let (_, text) = split_terminated(rest, encoding);
+    let text = decode(text, encoding);
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// `SYLT`: encoding, language, timestamp format, content type, descriptor, then
+/// `(text, timestamp)` entries
+fn parse_sylt(frame: &[u8]) -> Option<Vec<LrcLine>> {
+    let encoding = *frame.first()?;
+    if *frame.get(4)? != SYLT_FORMAT_MILLIS {
+        return None;
+    }
+    let (_, mut rest) = split_terminated(frame.get(6..)?, encoding);
+
+    let mut entries = Vec::new();
+    while !rest.is_empty() {
+        let (text, after) = split_terminated(rest, encoding);
+        let Some(timestamp) = after.get(0..4) else {
+            break;
+        };
+        let start = Duration::from_millis(u64::from(u32::from_be_bytes([
+            timestamp[0],
+            timestamp[1],
+            timestamp[2],
+            timestamp[3],
+        ])));
+        entries.push((start, decode(text, encoding)));
+        rest = &after[4..];
+    }
+    let lines = lines_from_entries(entries);
+    (!lines.is_empty()).then_some(lines)
+}
+
+/// Build lines from `SYLT` entries. When any entry starts with a newline, entries are
+/// syllables and each newline starts a new line; otherwise every entry is a line.
+fn lines_from_entries(entries: Vec<(Duration, String)>) -> Vec<LrcLine> {
+    let starts_line = |text: &str| text.starts_with(['\n', '\r']);
+    if !entries.iter().any(|(_, text)| starts_line(text)) {
+        return entries
+            .into_iter()
+            .map(|(start_time, text)| LrcLine {
+                start_time,
+                text: text.trim().to_string(),
+                words: None,
+                romanized: None,
+                part: None,
+            })
+            .collect();
+    }
+
+    let mut lines: Vec<LrcLine> = Vec::new();
+    let mut iter = entries.into_iter().peekable();
+    while let Some((start_time, text)) = iter.next() {
+        let end_time = iter.peek().map(|(next, _)| *next);
+        let word = LrcWord {
+            start_time,
+            end_time,
+            text: text.trim_start_matches(['\n', '\r']).to_string(),
+        };
+        match lines.last_mut() {
+            Some(line) if !starts_line(&text) => {
+                line.text.push_str(&word.text);
+                line.words.get_or_insert_with(Vec::new).push(word);
+            }
+            _ => lines.push(LrcLine {
+                start_time,
+                text: word.text.clone(),
+                words: Some(vec![word]),
+                romanized: None,
+                part: None,
+            }),
+        }
+    }
+    lines
+}
+
+/// Split `data` at the first string terminator for `encoding`, dropping the terminator
+fn split_terminated(data: &[u8], encoding: u8) -> (&[u8], &[u8]) {
+    if matches!(encoding, 1 | 2) {
+        let mut i = 0;
+        while i + 1 < data.len() {
+            if data[i] == 0 && data[i + 1] == 0 {
+                return (&data[..i], &data[i + 2..]);
+            }
+            i += 2;
+        }
+        (data, &[])
+    } else {
+        match data.iter().position(|&b| b == 0) {
+            Some(i) => (&data[..i], &data[i + 1..]),
+            None => (data, &[]),
+        }
+    }
+}
+
+/// Decode an `ID3v2` string: 0 is Latin-1, 1 is UTF-16 with a byte order mark, 2 is
+/// UTF-16BE and 3 is UTF-8
+fn decode(data: &[u8], encoding: u8) -> String {
+    match encoding {
+        1 | 2 => {
+            let (data, little_endian) = match data {
+                [0xFF, 0xFE, rest @ ..] => (rest, true),
+                [0xFE, 0xFF, rest @ ..] => (rest, false),
+                // Some taggers only mark the first string; Windows taggers write LE
+                _ => (data, encoding == 1),
+            };
+            let units: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|pair| {
+                    if little_endian {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(data).into_owned(),
+        _ => data.iter().copied().map(char::from).collect(),
+    }
+}
+
+/// Undo unsynchronisation: every `FF 00` becomes `FF`
+fn remove_unsync(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut previous = 0;
+    for &byte in data {
+        if !(previous == 0xFF && byte == 0) {
+            out.push(byte);
+        }
+        previous = byte;
+    }
+    out
+}
+
+/// A 28-bit integer stored in four bytes of seven bits each
+fn syncsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0, |acc, &b| (acc << 7) | usize::from(b & 0x7F))
+}
+
+fn be_u32(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0, |acc, &b| (acc << 8) | usize::from(b))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// An `ID3v2.3` tag holding `frames`
+    pub fn tag(frames: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (id, data) in frames {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&u32::try_from(data.len()).unwrap().to_be_bytes());
+            body.extend_from_slice(&[0, 0]);
+            body.extend_from_slice(data);
+        }
+        let size = u32::try_from(body.len()).unwrap();
+        let mut data = b"ID3\x03\x00\x00".to_vec();
+        for shift in [21, 14, 7, 0] {
+            data.push(u8::try_from((size >> shift) & 0x7F).unwrap());
+        }
+        data.extend(body);
+        data
+    }
+
+    fn sylt(entries: &[(&str, u32)]) -> Vec<u8> {
+        let mut data = vec![3, b'e', b'n', b'g', SYLT_FORMAT_MILLIS, 1, 0];
+        for (text, ms) in entries {
+            data.extend_from_slice(text.as_bytes());
+            data.push(0);
+            data.extend_from_slice(&ms.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_sylt_lines() {
+        let data = tag(&[(b"SYLT", sylt(&[("First", 1000), ("Second", 2500)]))]);
+        let lines = read(&data).and_then(TagLyrics::into_synced).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].start_time, Duration::from_millis(2500));
+        assert_eq!(lines[1].text, "Second");
+        assert!(lines[0].words.is_none());
+    }
+
+    #[test]
+    fn test_sylt_syllables_group_into_lines() {
+        let data = tag(&[(
+            b"SYLT",
+            sylt(&[("\nHel", 1000), ("lo", 1200), ("\nWorld", 2000)]),
+        )]);
+        let lines = read(&data).and_then(TagLyrics::into_synced).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "Hello");
+        let words = lines[0].words.as_ref().unwrap();
+        assert_eq!(words[1].end_time, Some(Duration::from_secs(2)));
+        assert_eq!(lines[1].start_time, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_uslt_utf16_with_bom() {
+        let mut frame = vec![1, b'e', b'n', b'g', 0xFF, 0xFE, 0, 0, 0xFF, 0xFE];
+        for unit in "Line one\nLine two".encode_utf16() {
+            frame.extend_from_slice(&unit.to_le_bytes());
+        }
+        let data = tag(&[(b"TIT2", vec![3, b'x']), (b"USLT", frame)]);
+        let text = read(&data).and_then(TagLyrics::into_unsynced).unwrap();
+        assert_eq!(text, "Line one\nLine two");
+    }
+
+    #[test]
+    fn test_sylt_preferred_over_uslt() {
+        let data = tag(&[
+            (b"USLT", b"\x03eng\x00Plain".to_vec()),
+            (b"SYLT", sylt(&[("Synced", 0)])),
+        ]);
+        assert!(matches!(read(&data), Some(TagLyrics::Synced(_))));
+    }
+
+    #[test]
+    fn test_remove_unsync() {
+        assert_eq!(remove_unsync(&[0xFF, 0x00, 0xE0, 0x01]), [0xFF, 0xE0, 0x01]);
+    }
+}
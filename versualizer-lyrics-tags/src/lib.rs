@@ -0,0 +1,227 @@
+//! Lyrics provider that reads lyrics embedded in local audio files, or kept next to them.
+//!
+//! Synced lyrics come from `ID3v2` `SYLT` frames or LRC stored in a tag; plain lyrics come
+//! from `USLT` frames and Vorbis `LYRICS` comments. Without synced lyrics in the tags, a
+//! sidecar file with the audio file's name is used instead: `.lrc`, `.ttml`, `.krc`, `.srt`
+//! or `.vtt`, tried in that order. The audio file is found through the
+//! `file` provider ID, which music sources that know the playing file (such as MPRIS with
+//! `xesam:url`) set to a path or `file://` URL. Tracks without one are skipped.
+
+mod id3;
+mod sidecar;
+mod vorbis;
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tracing::{debug, info};
+use versualizer_core::{
+    CoreError, FetchedLyrics, LrcFile, LrcLine, LrcMetadata, LyricsProvider, LyricsQuery,
+    LyricsResult,
+};
+
+/// Provider name used in config and the cache
+pub const PROVIDER_NAME: &str = "embedded_tags";
+
+/// Provider ID key under which music sources put the path of the playing file
+pub const FILE_PATH_ID: &str = "file";
+
+/// Most bytes read from the start of a file. Tags live at the start, but embedded cover art
+/// can make them several megabytes.
+const MAX_TAG_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Lyrics found in a file's tags
+#[derive(Debug, Clone)]
+pub enum TagLyrics {
+    /// Timed lines, from `SYLT` or LRC text
+    Synced(Vec<LrcLine>),
+    /// Plain text
+    Unsynced(String),
+}
+
+impl TagLyrics {
+    /// Lyrics from tag text, which is synced when it parses as LRC with timed lines
+    fn from_text(text: &str) -> Self {
+        match LrcFile::parse(text) {
+            Ok(lrc) if !lrc.lines.is_empty() => Self::Synced(lrc.lines),
+            _ => Self::Unsynced(text.trim().to_string()),
+        }
+    }
+
+    const fn is_synced(&self) -> bool {
+        matches!(self, Self::Synced(_))
+    }
+
+    fn into_result(self) -> LyricsResult {
+        match self {
+            Self::Synced(lines) => LyricsResult::Synced(LrcFile {
+                metadata: LrcMetadata::default(),
+                lines,
+            }),
+            Self::Unsynced(text) => LyricsResult::Unsynced(text),
+        }
+    }
+
+    #[cfg(test)]
+    fn into_synced(self) -> Option<Vec<LrcLine>> {
+        match self {
+            Self::Synced(lines) => Some(lines),
+            Self::Unsynced(_) => None,
+        }
+    }
+
+    #[cfg(test)]
+    fn into_unsynced(self) -> Option<String> {
+        match self {
+            Self::Unsynced(text) => Some(text),
+            Self::Synced(_) => None,
+        }
+    }
+}
+
+/// Read the lyrics embedded in the audio file at `path`
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn read_lyrics(path: &Path) -> std::io::Result<Option<TagLyrics>> {
+    let mut data = Vec::new();
+    std::fs::File::open(path)?
+        .take(MAX_TAG_BYTES)
+        .read_to_end(&mut data)?;
+    Ok(parse_tags(&data))
+}
+
+/// Lyrics for the audio file at `path`: synced lyrics from its tags or a sidecar file,
+/// otherwise plain lyrics from its tags
+///
+/// # Errors
+///
+/// Returns an error if the audio file cannot be read.
+pub fn read_local_lyrics(path: &Path) -> std::io::Result<Option<TagLyrics>> {
+    let embedded = read_lyrics(path)?;
+    if embedded.as_ref().is_some_and(TagLyrics::is_synced) {
+        return Ok(embedded);
+    }
+    Ok(sidecar::read(path).map(TagLyrics::Synced).or(embedded))
+}
+
+/// Lyrics from the tags at the start of `data`. An `ID3v2` tag may precede FLAC data too.
+fn parse_tags(data: &[u8]) -> Option<TagLyrics> {
+    if let Some(lyrics) = id3::read(data) {
+        return Some(lyrics);
+    }
+    let rest = id3::tag_len(data)
+        .and_then(|len| data.get(len..))
+        .unwrap_or(data);
+    vorbis::read(rest)
+}
+
+/// The audio file path from the query's `file` provider ID, accepting `file://` URLs
+#[must_use]
+pub fn track_file_path(query: &LyricsQuery) -> Option<PathBuf> {
+    let location = query.provider_id(FILE_PATH_ID)?.trim();
+    if location.is_empty() {
+        return None;
+    }
+    if location.starts_with("file://") {
+        return url::Url::parse(location).ok()?.to_file_path().ok();
+    }
+    // Streams and other remote URLs have no local tags
+    if location.contains("://") {
+        return None;
+    }
+    Some(PathBuf::from(location))
+}
+
+/// Provider for lyrics embedded in local audio files
+#[derive(Debug, Default)]
+pub struct EmbeddedTagsProvider;
+
+impl EmbeddedTagsProvider {
+    /// Create a new embedded tags provider
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for EmbeddedTagsProvider {
+    fn name(&self) -> &'static str {
+        PROVIDER_NAME
+    }
+
+    async fn fetch(&self, query: &LyricsQuery) -> Result<FetchedLyrics, CoreError> {
+        let Some(path) = track_file_path(query) else {
+            debug!(
+                "No local file for {} - {}",
+                query.artist_name, query.track_name
+            );
+            return Ok(FetchedLyrics {
+                result: LyricsResult::NotFound,
+                provider_id: String::new(),
+            });
+        };
+        info!("Reading local lyrics for {:?}", path);
+
+        let read_path = path.clone();
+        let lyrics = tokio::task::spawn_blocking(move || read_local_lyrics(&read_path))
+            .await
+            .map_err(|e| CoreError::LyricsProviderFailed {
+                provider: PROVIDER_NAME.to_string(),
+                reason: e.to_string(),
+            })??;
+
+        Ok(FetchedLyrics {
+            result: lyrics.map_or(LyricsResult::NotFound, TagLyrics::into_result),
+            provider_id: path.to_string_lossy().into_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_file_path() {
+        let query = |location: &str| {
+            LyricsQuery::new("Song", "Artist").with_provider_id(FILE_PATH_ID, location)
+        };
+        assert_eq!(
+            track_file_path(&query("/music/song.flac")),
+            Some(PathBuf::from("/music/song.flac"))
+        );
+        assert!(track_file_path(&query("https://example.com/stream.mp3")).is_none());
+        assert!(track_file_path(&LyricsQuery::new("Song", "Artist")).is_none());
+        #[cfg(unix)]
+        assert_eq!(
+            track_file_path(&query("file:///music/My%20Song.mp3")),
+            Some(PathBuf::from("/music/My Song.mp3"))
+        );
+    }
+
+    #[test]
+    fn test_id3_before_flac() {
+        let mut data = id3::tests::tag(&[(b"TIT2", vec![3, b'x'])]);
+        data.extend_from_slice(b"fLaC");
+        data.extend_from_slice(&[0x84, 0, 0, 0]);
+        assert!(parse_tags(&data).is_none());
+
+        let mut data = id3::tests::tag(&[(b"USLT", b"\x03eng\x00[00:01.00]Line".to_vec())]);
+        data.extend_from_slice(b"fLaC");
+        assert!(parse_tags(&data).is_some_and(|lyrics| lyrics.is_synced()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_without_file_is_not_found() {
+        let fetched = EmbeddedTagsProvider::new()
+            .fetch(&LyricsQuery::new("Song", "Artist"))
+            .await
+            .unwrap();
+        assert!(!fetched.result.is_found());
+    }
+}
@@ -0,0 +1,64 @@
+//! Lyrics files next to the audio file, such as `song.lrc` or `song.krc` for `song.flac`.
+
+use std::path::Path;
+
+use tracing::debug;
+use versualizer_core::{LrcFile, LrcLine, SubtitleFormat, TtmlFile, krc};
+
+/// Extensions of sidecar lyrics files, in the order they are tried
+const SIDECAR_EXTENSIONS: &[&str] = &["lrc", "ttml", "krc", "srt", "vtt"];
+
+/// Synced lines from the first sidecar file next to `audio` that parses with timed lines
+pub fn read(audio: &Path) -> Option<Vec<LrcLine>> {
+    SIDECAR_EXTENSIONS.iter().find_map(|extension| {
+        let path = audio.with_extension(extension);
+        let data = std::fs::read(&path).ok()?;
+        let lrc = parse(extension, &path, &data)?;
+        debug!("Read {} lines from {:?}", lrc.lines.len(), path);
+        Some(lrc.lines)
+    })
+}
+
+fn parse(extension: &str, path: &Path, data: &[u8]) -> Option<LrcFile> {
+    // KRC is the only binary format
+    if extension == "krc" {
+        return krc::read(data).ok().filter(|lrc| !lrc.lines.is_empty());
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    let lrc = match extension {
+        "lrc" => LrcFile::parse(text).ok()?,
+        "ttml" => TtmlFile::parse(text).ok()?.into_lrc(),
+        _ => SubtitleFormat::from_path(path)?.parse(text).ok()?,
+    };
+    (!lrc.lines.is_empty()).then_some(lrc)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_prefers_lrc() {
+        let dir = std::env::temp_dir().join(format!("versualizer-sidecar-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let audio = dir.join("song.flac");
+        assert!(read(&audio).is_none());
+
+        std::fs::write(
+            dir.join("song.srt"),
+            "1\n00:00:01,000 --> 00:00:02,000\nFrom subtitles\n",
+        )
+        .unwrap();
+        assert_eq!(read(&audio).unwrap()[0].text, "From subtitles");
+
+        // A file without timed lines is passed over
+        std::fs::write(dir.join("song.lrc"), "[ti:Song]").unwrap();
+        assert_eq!(read(&audio).unwrap()[0].text, "From subtitles");
+        std::fs::write(dir.join("song.lrc"), "[00:01.00]From LRC").unwrap();
+        assert_eq!(read(&audio).unwrap()[0].text, "From LRC");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,215 @@
+//! Lyrics from Vorbis comments, used by FLAC, Ogg Vorbis and Opus files.
+//!
+//! Lyrics are stored as a `LYRICS` (or `UNSYNCEDLYRICS`) comment. The value is plain text or
+//! LRC; lyrics with timestamps are returned as synced.
+
+use crate::TagLyrics;
+
+/// Comment names that hold lyrics, most common first
+const LYRICS_KEYS: [&str; 3] = ["LYRICS", "UNSYNCEDLYRICS", "UNSYNCED LYRICS"];
+
+/// FLAC metadata block type of the Vorbis comment block
+const FLAC_VORBIS_COMMENT: u8 = 4;
+
+/// Lyrics from the comments of a FLAC, Ogg Vorbis or Opus file
+#[must_use]
+pub fn read(data: &[u8]) -> Option<TagLyrics> {
+    let comments = if data.starts_with(b"fLaC") {
+        flac_comments(data)?
+    } else if data.starts_with(b"OggS") {
+        ogg_comments(data)?
+    } else {
+        return None;
+    };
+    lyrics_from_comments(&comments)
+}
+
+fn lyrics_from_comments(comments: &[(String, String)]) -> Option<TagLyrics> {
+    let mut found: Vec<TagLyrics> = LYRICS_KEYS
+        .iter()
+        .flat_map(|key| {
+            comments
+                .iter()
+                .filter(move |(name, _)| name.eq_ignore_ascii_case(key))
+        })
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(_, value)| TagLyrics::from_text(value))
+        .collect();
+    // Some files carry both plain and LRC lyrics; prefer the synced ones
+    let synced = found.iter().position(TagLyrics::is_synced).unwrap_or(0);
+    (!found.is_empty()).then(|| found.swap_remove(synced))
+}
+
+/// Comments from the Vorbis comment metadata block
+fn flac_comments(data: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut pos = 4;
+    loop {
+        let header = data.get(pos..pos + 4)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let len =
+            (usize::from(header[1]) << 16) | (usize::from(header[2]) << 8) | usize::from(header[3]);
+        pos += 4;
+        if block_type == FLAC_VORBIS_COMMENT {
+            return parse_comments(data.get(pos..pos + len)?);
+        }
+        if is_last {
+            return None;
+        }
+        pos += len;
+    }
+}
+
+/// Comments from the comment header of the first logical stream of an Ogg file. The
+/// identification header fills the first page, so the comment header starts the payload
+/// of the stream's second page and may continue over the following pages.
+fn ogg_comments(data: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut pos = 0;
+    let mut serial = None;
+    let mut page_index = 0;
+    let mut packet = Vec::new();
+    while let Some(header) = data.get(pos..pos + 27) {
+        if &header[0..4] != b"OggS" {
+            break;
+        }
+        let page_serial = &header[14..18];
+        let segments = usize::from(header[26]);
+        let lacing = data.get(pos + 27..pos + 27 + segments)?;
+        let payload_len: usize = lacing.iter().copied().map(usize::from).sum();
+        let payload_start = pos + 27 + segments;
+        let payload = data.get(payload_start..payload_start + payload_len)?;
+        pos = payload_start + payload_len;
+
+        if *serial.get_or_insert(page_serial) != page_serial {
+            continue;
+        }
+        page_index += 1;
+        if page_index == 1 {
+            continue;
+        }
+        packet.extend_from_slice(payload);
+        // A lacing value under 255 ends the packet
+        if lacing.last().is_some_and(|&l| l < 255) {
+            break;
+        }
+    }
+
+    let comments = packet
+        .strip_prefix(b"\x03vorbis")
+        .or_else(|| packet.strip_prefix(b"OpusTags"))?;
+    parse_comments(comments)
+}
+
+/// Parse a Vorbis comment list: vendor string, count, then `NAME=value` entries, each
+/// prefixed with its little-endian length
+fn parse_comments(data: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut pos = 0;
+    take_field(data, &mut pos)?; // Vendor
+    let count = le_u32(data.get(pos..pos + 4)?);
+    pos += 4;
+
+    let mut comments = Vec::new();
+    for _ in 0..count {
+        let Some(entry) = take_field(data, &mut pos) else {
+            break;
+        };
+        let entry = String::from_utf8_lossy(entry);
+        if let Some((name, value)) = entry.split_once('=') {
+            comments.push((name.to_string(), value.to_string()));
+        }
+    }
+    Some(comments)
+}
+
+/// A length-prefixed field at `pos`, advancing `pos` past it
+fn take_field<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = le_u32(data.get(*pos..*pos + 4)?);
+    let start = *pos + 4;
+    let value = data.get(start..start + len)?;
+    *pos = start + len;
+    Some(value)
+}
+
+fn le_u32(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .rev()
+        .fold(0, |acc, &b| (acc << 8) | usize::from(b))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn comments(entries: &[&str]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for entry in std::iter::once("vendor").chain(entries.iter().copied()) {
+            data.extend_from_slice(&u32::try_from(entry.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(entry.as_bytes());
+            if entry == "vendor" {
+                data.extend_from_slice(&u32::try_from(entries.len()).unwrap().to_le_bytes());
+            }
+        }
+        data
+    }
+
+    fn flac(entries: &[&str]) -> Vec<u8> {
+        let block = comments(entries);
+        let mut data = b"fLaC".to_vec();
+        // A STREAMINFO block, then the comment block marked as last
+        data.extend_from_slice(&[0, 0, 0, 34]);
+        data.extend_from_slice(&[0; 34]);
+        let len = u32::try_from(block.len()).unwrap().to_be_bytes();
+        data.extend_from_slice(&[0x80 | FLAC_VORBIS_COMMENT, len[1], len[2], len[3]]);
+        data.extend(block);
+        data
+    }
+
+    fn ogg_page(payload: &[u8]) -> Vec<u8> {
+        let mut page = b"OggS".to_vec();
+        page.extend_from_slice(&[0; 10]);
+        page.extend_from_slice(&[1, 0, 0, 0]);
+        page.extend_from_slice(&[0; 8]);
+        let mut lacing = vec![255; payload.len() / 255];
+        lacing.push(u8::try_from(payload.len() % 255).unwrap());
+        page.push(u8::try_from(lacing.len()).unwrap());
+        page.extend(lacing);
+        page.extend_from_slice(payload);
+        page
+    }
+
+    #[test]
+    fn test_flac_lrc_lyrics_are_synced() {
+        let data = flac(&["TITLE=Song", "LYRICS=[00:01.00]First\n[00:02.50]Second"]);
+        let lines = read(&data).and_then(TagLyrics::into_synced).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].text, "Second");
+    }
+
+    #[test]
+    fn test_flac_prefers_synced_comment() {
+        let data = flac(&["UNSYNCEDLYRICS=Plain", "lyrics=[00:01.00]Timed"]);
+        assert!(read(&data).is_some_and(|lyrics| lyrics.is_synced()));
+
+        let data = flac(&["UNSYNCEDLYRICS=Plain"]);
+        assert_eq!(
+            read(&data).and_then(TagLyrics::into_unsynced).unwrap(),
+            "Plain"
+        );
+        assert!(read(&flac(&["TITLE=Song"])).is_none());
+    }
+
+    #[test]
+    fn test_opus_comment_header() {
+        let mut packet = b"OpusTags".to_vec();
+        packet.extend(comments(&["LYRICS=One\nTwo"]));
+        let mut data = ogg_page(b"OpusHead");
+        data.extend(ogg_page(&packet));
+        assert_eq!(
+            read(&data).and_then(TagLyrics::into_unsynced).unwrap(),
+            "One\nTwo"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Lyrics providers from `[lyrics]` and `[providers]`, set up the same way by the app, CLI
+//! and TUI.
+
+use tracing::{error, info};
+use versualizer_core::config::LyricsProviderType;
+use versualizer_core::{LyricsProvider, VersualizerConfig};
+use versualizer_lyrics_genius::{GeniusProvider, GeniusProviderConfig};
+use versualizer_lyrics_lrclib::LrclibProvider;
+use versualizer_lyrics_musixmatch::MusixmatchProvider;
+use versualizer_lyrics_plugin::PluginProvider;
+use versualizer_lyrics_spotify::SpotifyLyricsProvider;
+use versualizer_lyrics_tags::EmbeddedTagsProvider;
+use versualizer_spotify_api::SpotifyProviderConfig;
+
+/// Create the configured lyrics providers in order, followed by the plugins. Providers that
+/// are not configured or fail to start are skipped and logged.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub fn create_providers(config: &VersualizerConfig) -> Vec<Box<dyn LyricsProvider>> {
+    config
+        .lyrics
+        .providers
+        .iter()
+        .filter_map(|provider_type| -> Option<Box<dyn LyricsProvider>> {
+            match provider_type {
+                LyricsProviderType::Lrclib => {
+                    info!("Initializing LRCLIB provider");
+                    match LrclibProvider::new(&config.network.retry) {
+                        Ok(provider) => Some(Box::new(provider)),
+                        Err(e) => {
+                            error!("Failed to create LRCLIB provider: {}", e);
+                            None
+                        }
+                    }
+                }
+                LyricsProviderType::Musixmatch => {
+                    info!("Initializing Musixmatch provider");
+                    match MusixmatchProvider::new(&config.network.retry) {
+                        Ok(provider) => Some(Box::new(provider)),
+                        Err(e) => {
+                            error!("Failed to create Musixmatch provider: {}", e);
+                            None
+                        }
+                    }
+                }
+                LyricsProviderType::EmbeddedTags => {
+                    info!("Initializing embedded tags provider");
+                    Some(Box::new(EmbeddedTagsProvider::new()))
+                }
+                LyricsProviderType::Genius => {
+                    let genius_config =
+                        match GeniusProviderConfig::from_providers(&config.providers) {
+                            Ok(Some(cfg)) => cfg,
+                            Ok(None) => {
+                                info!("Skipping Genius provider: not configured");
+                                return None;
+                            }
+                            Err(e) => {
+                                error!("Failed to parse Genius config: {}", e);
+                                return None;
+                            }
+                        };
+                    if let Err(e) = genius_config.validate() {
+                        info!("Skipping Genius provider: {}", e);
+                        return None;
+                    }
+                    info!("Initializing Genius provider");
+                    match GeniusProvider::new(genius_config.access_token, &config.network.retry) {
+                        Ok(provider) => Some(Box::new(provider)),
+                        Err(e) => {
+                            error!("Failed to create Genius provider: {}", e);
+                            None
+                        }
+                    }
+                }
+                LyricsProviderType::SpotifyLyrics => {
+                    // Access Spotify config from providers section
+                    let spotify_config = match SpotifyProviderConfig::load(config) {
+                        Ok(Some(cfg)) => cfg,
+                        Ok(None) => {
+                            info!("Skipping Spotify lyrics provider: not configured");
+                            return None;
+                        }
+                        Err(e) => {
+                            error!("Failed to parse Spotify config: {}", e);
+                            return None;
+                        }
+                    };
+
+                    spotify_config.sp_dc.as_ref().map_or_else(
+                        || {
+                            info!("Skipping Spotify lyrics provider: sp_dc not configured");
+                            None
+                        },
+                        |sp_dc| {
+                            if sp_dc.is_empty() {
+                                info!("Skipping Spotify lyrics provider: sp_dc is empty");
+                                None
+                            } else {
+                                info!("Initializing Spotify lyrics provider (sp_dc configured)");
+                                let secret_key_url = spotify_config.secret_key_url.clone();
+                                match SpotifyLyricsProvider::new(
+                                    sp_dc,
+                                    secret_key_url,
+                                    &config.network.retry,
+                                ) {
+                                    Ok(provider) => {
+                                        Some(Box::new(provider) as Box<dyn LyricsProvider>)
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to create Spotify lyrics provider: {}", e);
+                                        None
+                                    }
+                                }
+                            }
+                        },
+                    )
+                }
+            }
+        })
+        .chain(
+            config
+                .lyrics
+                .plugin_paths()
+                .into_iter()
+                .map(|(name, path)| {
+                    info!("Initializing lyrics plugin {} ({:?})", name, path);
+                    Box::new(PluginProvider::new(name, path)) as Box<dyn LyricsProvider>
+                }),
+        )
+        .collect()
+}
@@ -0,0 +1,355 @@
+//! Local HTTP and WebSocket server with the live lyric state, for OBS browser sources, web
+//! remotes and other visualizers.
+//!
+//! Enabled with `[server] enabled = true`, it serves:
+//! - `GET /`: a lyrics overlay page for browser sources, in the view chosen with
+//!   `?view=performer` (the default) or `?view=audience`
+//! - `GET /api/state`: a [`Snapshot`] of the track, the playback position and the current
+//!   and next lines
+//! - `GET /api/lyrics`: the current synced lyrics with word timing, or `null`
+//! - `GET /ws`: a WebSocket that sends a snapshot, then every sync event and line change as a
+//!   [`ServerMessage`]
+//!
+//! `/api/state` and `/ws` take the same `view` parameter: the audience view leaves out the
+//! next line, so its clients only hear about changes of the current one. Every view follows
+//! the same sync stream.
+//!
+//! Responses allow any origin, so pages opened from a file or another host can read them.
+
+mod message;
+
+pub use message::{LineInfo, ServerMessage, Snapshot};
+
+use axum::Router;
+use axum::extract::ws::{Message, Utf8Bytes, WebSocket, WebSocketUpgrade};
+use axum::extract::{RawQuery, State};
+use axum::http::HeaderValue;
+use axum::http::header::ACCESS_CONTROL_ALLOW_ORIGIN;
+use axum::response::{Html, Json, Response};
+use axum::routing::get;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use versualizer_core::recording::TraceLine;
+use versualizer_core::{OverlayView, ServerConfig, SyncEngine};
+
+/// How often the current line is checked for changes
+const LINE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Messages kept for slow WebSocket clients before they get a fresh snapshot instead
+const MESSAGE_CAPACITY: usize = 64;
+
+/// The overlay page served at `/`
+const OVERLAY_PAGE: &str = include_str!("../assets/overlay.html");
+
+/// What the request handlers share
+#[derive(Clone)]
+struct ServerState {
+    sync_engine: Arc<SyncEngine>,
+    /// JSON of every [`ServerMessage`], for the WebSocket clients
+    messages: broadcast::Sender<ViewMessages>,
+    cancel_token: CancellationToken,
+}
+
+/// JSON of a [`ServerMessage`] as each view gets it, `None` for a view it does not concern
+#[derive(Clone)]
+struct ViewMessages {
+    performer: Option<Utf8Bytes>,
+    audience: Option<Utf8Bytes>,
+}
+
+impl ViewMessages {
+    fn get(&self, view: OverlayView) -> Option<&Utf8Bytes> {
+        match view {
+            OverlayView::Performer => self.performer.as_ref(),
+            OverlayView::Audience => self.audience.as_ref(),
+        }
+    }
+}
+
+/// The view chosen with the `view` parameter of a request's query, the performer view if
+/// there is none
+fn requested_view(query: Option<&str>) -> OverlayView {
+    query.and_then(OverlayView::from_query).unwrap_or_default()
+}
+
+/// Server for the live lyric state of a [`SyncEngine`]
+pub struct LyricsServer {
+    address: String,
+    state: ServerState,
+}
+
+impl LyricsServer {
+    /// A server for `sync_engine` on the address configured in `[server]`, stopping when
+    /// `cancel_token` is cancelled
+    #[must_use]
+    pub fn new(
+        config: &ServerConfig,
+        sync_engine: Arc<SyncEngine>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        let (messages, _) = broadcast::channel(MESSAGE_CAPACITY);
+        Self {
+            address: format!("{}:{}", config.address, config.port),
+            state: ServerState {
+                sync_engine,
+                messages,
+                cancel_token,
+            },
+        }
+    }
+
+    /// Serve until cancelled. Failing to listen on the address is logged.
+    pub async fn run(self) {
+        match TcpListener::bind(&self.address).await {
+            Ok(listener) => {
+                info!("Serving the live lyric state on http://{}", self.address);
+                self.serve(listener).await;
+            }
+            Err(e) => warn!(
+                "Failed to start the lyrics server on {}: {}",
+                self.address, e
+            ),
+        }
+    }
+
+    async fn serve(self, listener: TcpListener) {
+        let state = self.state;
+        tokio::spawn(publish(state.clone()));
+        let cancel_token = state.cancel_token.clone();
+        if let Err(e) = axum::serve(listener, router(state))
+            .with_graceful_shutdown(cancel_token.cancelled_owned())
+            .await
+        {
+            warn!("Lyrics server stopped: {}", e);
+        }
+    }
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/", get(overlay_page))
+        .route("/api/state", get(current_state))
+        .route("/api/lyrics", get(current_lyrics))
+        .route("/ws", get(websocket))
+        .layer(axum::middleware::map_response(allow_any_origin))
+        .with_state(state)
+}
+
+async fn allow_any_origin(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    response
+}
+
+async fn overlay_page() -> Html<&'static str> {
+    Html(OVERLAY_PAGE)
+}
+
+async fn current_state(
+    State(state): State<ServerState>,
+    RawQuery(query): RawQuery,
+) -> Json<Snapshot> {
+    let view = requested_view(query.as_deref());
+    Json(Snapshot::current(&state.sync_engine).await.for_view(view))
+}
+
+async fn current_lyrics(State(state): State<ServerState>) -> Json<Option<Vec<TraceLine>>> {
+    let lyrics = state.sync_engine.lyrics().await;
+    Json(lyrics.map(|lyrics| lyrics.lines.iter().map(TraceLine::from).collect()))
+}
+
+async fn websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<ServerState>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let view = requested_view(query.as_deref());
+    ws.on_upgrade(move |socket| stream(socket, state, view))
+}
+
+/// Send a snapshot, then every message for `view` until the client leaves or the server
+/// stops
+async fn stream(mut socket: WebSocket, state: ServerState, view: OverlayView) {
+    let mut messages = state.messages.subscribe();
+    if send_snapshot(&mut socket, &state, view).await.is_err() {
+        return;
+    }
+    loop {
+        let sent = tokio::select! {
+            () = state.cancel_token.cancelled() => break,
+            message = messages.recv() => match message {
+                Ok(messages) => match messages.get(view) {
+                    Some(json) => socket.send(Message::Text(json.clone())).await,
+                    None => Ok(()),
+                },
+                // Skipped messages are made up for by a fresh snapshot
+                Err(RecvError::Lagged(_)) => send_snapshot(&mut socket, &state, view).await,
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                // Clients have nothing to say
+                Some(Ok(_)) => Ok(()),
+            },
+        };
+        if sent.is_err() {
+            break;
+        }
+    }
+    debug!("Lyrics server client disconnected");
+}
+
+async fn send_snapshot(
+    socket: &mut WebSocket,
+    state: &ServerState,
+    view: OverlayView,
+) -> Result<(), axum::Error> {
+    let snapshot =
+        ServerMessage::Snapshot(Snapshot::current(&state.sync_engine).await.for_view(view));
+    match to_json(&snapshot) {
+        Some(json) => socket.send(Message::Text(json)).await,
+        None => Ok(()),
+    }
+}
+
+/// Turn sync events into messages for the WebSocket clients, along with a line change
+/// whenever the lines a view shows change, until cancelled
+async fn publish(state: ServerState) {
+    let mut events = state.sync_engine.subscribe();
+    let mut interval = tokio::time::interval(LINE_POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_lines = None;
+    let mut last_audience_line = None;
+    loop {
+        tokio::select! {
+            () = state.cancel_token.cancelled() => break,
+            event = events.recv() => match event {
+                Ok(event) => broadcast_message(&state, &ServerMessage::from(&event)),
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+            _ = interval.tick() => {
+                // New clients start from a snapshot, so lines only matter while connected
+                if state.messages.receiver_count() == 0 {
+                    last_lines = None;
+                    last_audience_line = None;
+                    continue;
+                }
+                let snapshot = Snapshot::current(&state.sync_engine).await;
+                let lines = (snapshot.line, snapshot.next_line);
+                if last_lines.as_ref() == Some(&lines) {
+                    continue;
+                }
+                let (line, next_line) = lines.clone();
+                let audience = (last_audience_line.as_ref() != Some(&line)).then(|| {
+                    ServerMessage::LineChanged {
+                        line: line.clone(),
+                        next_line: None,
+                    }
+                });
+                last_audience_line = Some(line.clone());
+                last_lines = Some(lines);
+                send_messages(
+                    &state,
+                    ViewMessages {
+                        performer: to_json(&ServerMessage::LineChanged { line, next_line }),
+                        audience: audience.as_ref().and_then(to_json),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Send `message` to the clients of every view
+fn broadcast_message(state: &ServerState, message: &ServerMessage) {
+    if state.messages.receiver_count() == 0 {
+        return;
+    }
+    if let Some(json) = to_json(message) {
+        send_messages(
+            state,
+            ViewMessages {
+                performer: Some(json.clone()),
+                audience: Some(json),
+            },
+        );
+    }
+}
+
+fn send_messages(state: &ServerState, messages: ViewMessages) {
+    let _ = state.messages.send(messages);
+}
+
+fn to_json(message: &ServerMessage) -> Option<Utf8Bytes> {
+    match serde_json::to_string(message) {
+        Ok(json) => Some(json.into()),
+        Err(e) => {
+            warn!("Failed to serialize a lyrics server message: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use versualizer_core::LrcFile;
+
+    #[tokio::test]
+    async fn test_http_endpoints() {
+        let sync_engine = SyncEngine::new();
+        sync_engine
+            .set_lyrics(LrcFile::parse("[00:01.00]Hello").unwrap())
+            .await;
+        let server = LyricsServer::new(
+            &ServerConfig::default(),
+            sync_engine,
+            CancellationToken::new(),
+        );
+        let cancel_token = server.state.cancel_token.clone();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(server.serve(listener));
+
+        let response = reqwest::get(format!("{base}/api/state")).await.unwrap();
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        let state: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(state["is_playing"], false);
+        assert_eq!(state["next_line"]["text"], "Hello");
+
+        let state: serde_json::Value = reqwest::get(format!("{base}/api/state?view=audience"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(state["next_line"].is_null());
+
+        let page = reqwest::get(format!("{base}/?view=audience"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(page.contains("/ws?view="));
+
+        let lyrics: serde_json::Value = reqwest::get(format!("{base}/api/lyrics"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(lyrics[0]["start_ms"], 1000);
+
+        cancel_token.cancel();
+        handle.await.unwrap();
+    }
+}
@@ -0,0 +1,313 @@
+use serde::Serialize;
+use std::time::Duration;
+use versualizer_core::recording::{TraceLine, TraceTrack};
+use versualizer_core::{
+    BlockKind, DurationExt, LrcFile, OverlayView, PlaybackState, SyncEngine, SyncEvent,
+    apply_line_lead,
+};
+
+/// A lyric line and where it is in the lyrics
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineInfo {
+    pub index: usize,
+    pub start_ms: u64,
+    pub text: String,
+}
+
+/// The live state: the track, the playback position, and the current and next lines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snapshot {
+    pub track: Option<TraceTrack>,
+    pub is_playing: bool,
+    /// Playback position with the track's lyrics offset applied, as lyrics are timed against
+    pub position_ms: u64,
+    /// The line being sung, `None` before the first line or without synced lyrics
+    pub line: Option<LineInfo>,
+    pub next_line: Option<LineInfo>,
+}
+
+impl Snapshot {
+    /// The sync engine's state right now
+    pub async fn current(sync_engine: &SyncEngine) -> Self {
+        let state = sync_engine.state().await;
+        let position = sync_engine.current_position().await;
+        let lyrics = sync_engine.lyrics().await;
+        Self::new(
+            &state,
+            position,
+            lyrics.as_ref(),
+            sync_engine.line_lead_ms(),
+        )
+    }
+
+    /// The state at `position`, with lines switching `line_lead_ms` early like the overlay
+    fn new(
+        state: &PlaybackState,
+        position: Duration,
+        lyrics: Option<&LrcFile>,
+        line_lead_ms: i64,
+    ) -> Self {
+        let (line, next_line) = lyrics.map_or((None, None), |lyrics| {
+            let position = apply_line_lead(position, line_lead_ms);
+            (
+                lyrics
+                    .current_line_index(position)
+                    .and_then(|index| line_info(lyrics, index)),
+                lyrics
+                    .next_line_index(position)
+                    .and_then(|index| line_info(lyrics, index)),
+            )
+        });
+        Self {
+            track: state.track.as_ref().map(TraceTrack::from),
+            is_playing: state.is_playing,
+            position_ms: position.as_millis_u64(),
+            line,
+            next_line,
+        }
+    }
+}
+
+impl Snapshot {
+    /// The snapshot as `view` shows it: the audience view has no next line
+    #[must_use]
+    pub fn for_view(mut self, view: OverlayView) -> Self {
+        if view == OverlayView::Audience {
+            self.next_line = None;
+        }
+        self
+    }
+}
+
+fn line_info(lyrics: &LrcFile, index: usize) -> Option<LineInfo> {
+    lyrics.lines.get(index).map(|line| LineInfo {
+        index,
+        start_ms: line.start_time.as_millis_u64(),
+        text: line.text.clone(),
+    })
+}
+
+/// A message sent to WebSocket clients, as JSON tagged with its `type`. Apart from the
+/// snapshot and line changes, each one is a sync event, with positions in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The whole state, sent first and whenever the client fell behind
+    Snapshot(Snapshot),
+    /// The current or next line changed
+    LineChanged {
+        line: Option<LineInfo>,
+        next_line: Option<LineInfo>,
+    },
+    PlaybackStarted {
+        track: TraceTrack,
+        position_ms: u64,
+    },
+    PlaybackPaused {
+        position_ms: u64,
+    },
+    PlaybackResumed {
+        position_ms: u64,
+    },
+    PlaybackStopped,
+    /// Spotify is playing an ad, so there is no track or lyrics until the next track
+    AdPlaying,
+    TrackChanged {
+        track: TraceTrack,
+        position_ms: u64,
+    },
+    PositionSync {
+        position_ms: u64,
+    },
+    Seek {
+        position_ms: u64,
+    },
+    /// The lyrics fetcher started asking `provider`
+    LyricsFetchStarted {
+        provider: String,
+    },
+    LyricsFetchFailed {
+        provider: String,
+        reason: String,
+    },
+    LyricsLoaded {
+        lines: Vec<TraceLine>,
+    },
+    UnsyncedLyricsLoaded {
+        text: String,
+    },
+    LyricsNotFound,
+    /// A lyrics provider reported the track as instrumental
+    TrackInstrumental,
+    /// The track or its artist is on the blocklist, so no lyrics are shown
+    LyricsBlocked {
+        kind: BlockKind,
+    },
+    /// Every lyrics provider was unreachable, and they are asked again in `retry_in_ms`
+    LyricsRetrying {
+        attempt: u32,
+        max_attempts: u32,
+        retry_in_ms: u64,
+    },
+    LyricsTrimmed {
+        removed: String,
+    },
+    LyricsSource {
+        provider: String,
+    },
+    /// Beat, bar and section starts of the current track, for visuals that react to the
+    /// music
+    AnalysisLoaded {
+        beats_ms: Vec<u64>,
+        bars_ms: Vec<u64>,
+        sections_ms: Vec<u64>,
+    },
+    /// `config.toml` was edited. The config itself is not sent, as it can hold credentials.
+    ConfigChanged,
+    Error {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fetch_id: Option<u64>,
+    },
+}
+
+impl From<&SyncEvent> for ServerMessage {
+    fn from(event: &SyncEvent) -> Self {
+        match event {
+            SyncEvent::PlaybackStarted { track, position } => Self::PlaybackStarted {
+                track: track.into(),
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::PlaybackPaused { position } => Self::PlaybackPaused {
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::PlaybackResumed { position } => Self::PlaybackResumed {
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::PlaybackStopped => Self::PlaybackStopped,
+            SyncEvent::AdPlaying => Self::AdPlaying,
+            SyncEvent::TrackChanged { track, position } => Self::TrackChanged {
+                track: track.into(),
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::PositionSync { position } => Self::PositionSync {
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::SeekOccurred { position } => Self::Seek {
+                position_ms: position.as_millis_u64(),
+            },
+            SyncEvent::LyricsFetchStarted { provider } => Self::LyricsFetchStarted {
+                provider: provider.clone(),
+            },
+            SyncEvent::LyricsFetchFailed { provider, reason } => Self::LyricsFetchFailed {
+                provider: provider.clone(),
+                reason: reason.clone(),
+            },
+            SyncEvent::LyricsLoaded { lyrics } => Self::LyricsLoaded {
+                lines: lyrics.lines.iter().map(TraceLine::from).collect(),
+            },
+            SyncEvent::UnsyncedLyricsLoaded { text } => {
+                Self::UnsyncedLyricsLoaded { text: text.clone() }
+            }
+            SyncEvent::LyricsNotFound => Self::LyricsNotFound,
+            SyncEvent::TrackInstrumental => Self::TrackInstrumental,
+            SyncEvent::LyricsBlocked { kind } => Self::LyricsBlocked { kind: *kind },
+            SyncEvent::LyricsRetrying {
+                attempt,
+                max_attempts,
+                retry_in,
+            } => Self::LyricsRetrying {
+                attempt: *attempt,
+                max_attempts: *max_attempts,
+                retry_in_ms: retry_in.as_millis_u64(),
+            },
+            SyncEvent::LyricsTrimmed { removed } => Self::LyricsTrimmed {
+                removed: removed.clone(),
+            },
+            SyncEvent::LyricsSource { provider } => Self::LyricsSource {
+                provider: provider.clone(),
+            },
+            SyncEvent::AnalysisLoaded { features } => Self::AnalysisLoaded {
+                beats_ms: millis(&features.beats),
+                bars_ms: millis(&features.bars),
+                sections_ms: features
+                    .sections
+                    .iter()
+                    .map(|section| section.start.as_millis_u64())
+                    .collect(),
+            },
+            SyncEvent::ConfigChanged { .. } => Self::ConfigChanged,
+            SyncEvent::Error { message, fetch_id } => Self::Error {
+                message: message.clone(),
+                fetch_id: *fetch_id,
+            },
+        }
+    }
+}
+
+fn millis(times: &[Duration]) -> Vec<u64> {
+    times.iter().map(DurationExt::as_millis_u64).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use versualizer_core::{MusicSource, TrackInfo};
+
+    fn playing() -> PlaybackState {
+        PlaybackState {
+            track: Some(TrackInfo::new(
+                MusicSource::Spotify,
+                "t1",
+                "Song",
+                "Artist",
+                "Album",
+                Duration::from_mins(3),
+            )),
+            is_playing: true,
+            ..PlaybackState::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_lines() {
+        let lyrics = LrcFile::parse("[00:01.00]One\n[00:02.00]Two\n[00:03.00]Three").unwrap();
+
+        let intro = Snapshot::new(&playing(), Duration::from_millis(500), Some(&lyrics), 0);
+        assert_eq!(intro.line, None);
+        assert_eq!(intro.next_line.unwrap().text, "One");
+
+        // The line lead switches to the second line early
+        let snapshot = Snapshot::new(&playing(), Duration::from_millis(1900), Some(&lyrics), 200);
+        assert_eq!(snapshot.position_ms, 1900);
+        assert_eq!(snapshot.line.as_ref().unwrap().index, 1);
+        assert_eq!(snapshot.next_line.as_ref().unwrap().text, "Three");
+
+        // The audience view only shows the current line
+        let audience = snapshot.clone().for_view(OverlayView::Audience);
+        assert_eq!(audience.line, snapshot.line);
+        assert_eq!(audience.next_line, None);
+        assert_eq!(snapshot.clone().for_view(OverlayView::Performer), snapshot);
+
+        let plain = Snapshot::new(&playing(), Duration::from_secs(1), None, 0);
+        assert_eq!((plain.line, plain.next_line), (None, None));
+    }
+
+    #[test]
+    fn test_message_json() {
+        let message = ServerMessage::from(&SyncEvent::SeekOccurred {
+            position: Duration::from_millis(1500),
+        });
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"seek","position_ms":1500}"#
+        );
+
+        let snapshot = Snapshot::new(&PlaybackState::default(), Duration::ZERO, None, 0);
+        let json = serde_json::to_value(ServerMessage::Snapshot(snapshot)).unwrap();
+        assert_eq!(json["type"], "snapshot");
+        assert_eq!(json["is_playing"], false);
+        assert!(json["track"].is_null());
+    }
+}
@@ -0,0 +1,26 @@
+use thiserror::Error;
+use versualizer_core::{CoreError, MusicSource};
+
+/// Error type for the Windows media session source.
+#[derive(Debug, Error)]
+pub enum WindowsMediaError {
+    /// SMTC is a Windows API; there is nothing to follow on other platforms.
+    #[error("The windows_media music source is only available on Windows")]
+    Unsupported,
+
+    /// A `WinRT` call on the media session failed.
+    #[error("Windows media session error: {0}")]
+    Session(String),
+}
+
+/// Convenience type alias for Results with `WindowsMediaError`.
+pub type Result<T> = std::result::Result<T, WindowsMediaError>;
+
+impl From<WindowsMediaError> for CoreError {
+    fn from(e: WindowsMediaError) -> Self {
+        Self::SourceFailed {
+            name: MusicSource::WindowsMedia.as_str().to_string(),
+            reason: e.to_string(),
+        }
+    }
+}
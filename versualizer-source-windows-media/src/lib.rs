@@ -0,0 +1,15 @@
+//! Windows System Media Transport Controls (SMTC) music source.
+//!
+//! Follows whichever app Windows considers the current media session (iTunes,
+//! foobar2000, browser tabs, ...) through `GlobalSystemMediaTransportControlsSessionManager`,
+//! so lyrics can be synced without Spotify OAuth. On other platforms the poller fails
+//! to start with [`WindowsMediaError::Unsupported`].
+
+pub mod error;
+pub mod poller;
+mod session;
+pub mod snapshot;
+
+pub use error::WindowsMediaError;
+pub use poller::WindowsMediaPoller;
+pub use snapshot::SessionSnapshot;
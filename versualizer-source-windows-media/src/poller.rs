@@ -0,0 +1,124 @@
+//! Windows media session polling.
+
+use crate::error::WindowsMediaError;
+use crate::session::SessionReader;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+use versualizer_core::{CoreError, MusicSource, MusicSourceProvider, PlaybackState, SyncEngine};
+
+/// How often the current session is read. SMTC calls are local, so this can be
+/// much tighter than the Spotify Web API interval.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest wait between retries after consecutive errors
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Windows media session poller implementing [`MusicSourceProvider`].
+pub struct WindowsMediaPoller {
+    sync_engine: Arc<SyncEngine>,
+    poll_interval: Duration,
+    cancel_token: CancellationToken,
+}
+
+impl WindowsMediaPoller {
+    /// Create a new Windows media session poller
+    ///
+    /// # Arguments
+    /// * `sync_engine` - Sync engine to update with playback state
+    /// * `cancel_token` - Optional external cancellation token for graceful shutdown
+    pub fn new(sync_engine: Arc<SyncEngine>, cancel_token: Option<CancellationToken>) -> Self {
+        Self {
+            sync_engine,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            cancel_token: cancel_token.unwrap_or_default(),
+        }
+    }
+
+    /// Start polling in a background task
+    #[must_use]
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            if let Err(e) = self.run().await {
+                error!("Windows media poller stopped with error: {}", e);
+            }
+        })
+    }
+
+    /// Read the current session and update the sync engine
+    async fn poll_once(&self, reader: &Arc<SessionReader>) -> Result<(), WindowsMediaError> {
+        let reader = reader.clone();
+        let snapshot = tokio::task::spawn_blocking(move || reader.read())
+            .await
+            .map_err(|e| WindowsMediaError::Session(e.to_string()))??;
+
+        let state = snapshot.map_or_else(PlaybackState::default, |snapshot| {
+            snapshot.to_playback_state(SystemTime::now())
+        });
+
+        debug!(
+            "Polled Windows media session: playing={}, track={:?}, position={:?}",
+            state.is_playing,
+            state.track.as_ref().map(|t| &t.name),
+            state.position
+        );
+
+        self.sync_engine.update_state(state).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MusicSourceProvider for WindowsMediaPoller {
+    fn source(&self) -> MusicSource {
+        MusicSource::WindowsMedia
+    }
+
+    fn name(&self) -> &'static str {
+        "windows_media"
+    }
+
+    fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.clone()
+    }
+
+    async fn run(&self) -> Result<(), CoreError> {
+        info!("Starting Windows media session poller");
+
+        let reader = tokio::task::spawn_blocking(SessionReader::new)
+            .await
+            .map_err(|e| WindowsMediaError::Session(e.to_string()))
+            .and_then(|reader| reader)?;
+        let reader = Arc::new(reader);
+
+        let mut consecutive_errors: u32 = 0;
+        loop {
+            tokio::select! {
+                () = self.cancel_token.cancelled() => {
+                    info!("Windows media poller shutting down gracefully");
+                    break;
+                }
+                () = tokio::time::sleep(self.poll_interval) => {
+                    match self.poll_once(&reader).await {
+                        Ok(()) => consecutive_errors = 0,
+                        Err(e) => {
+                            consecutive_errors = consecutive_errors.saturating_add(1);
+                            warn!("Poll error (attempt {}): {}", consecutive_errors, e);
+
+                            // Exponential backoff: 100ms * 2^errors, capped at MAX_BACKOFF
+                            let backoff = Duration::from_millis(
+                                100_u64.saturating_mul(2_u64.saturating_pow(consecutive_errors.min(10))),
+                            )
+                            .min(MAX_BACKOFF);
+                            tokio::time::sleep(backoff).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Reading the current SMTC session. The `WinRT` calls block, so the poller runs
+//! [`SessionReader::read`] on the blocking thread pool.
+
+use crate::error::Result;
+use crate::snapshot::SessionSnapshot;
+
+#[cfg(windows)]
+pub use windows_impl::SessionReader;
+
+#[cfg(not(windows))]
+pub use unsupported::SessionReader;
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::{Result, SessionSnapshot};
+    use crate::error::WindowsMediaError;
+    use crate::snapshot::{ticks_to_duration, ticks_to_system_time};
+    use windows::Media::Control::{
+        GlobalSystemMediaTransportControlsSessionManager as SessionManager,
+        GlobalSystemMediaTransportControlsSessionPlaybackStatus as PlaybackStatus,
+    };
+
+    impl From<windows::core::Error> for WindowsMediaError {
+        fn from(error: windows::core::Error) -> Self {
+            Self::Session(error.message())
+        }
+    }
+
+    /// Handle on the system media session manager
+    pub struct SessionReader {
+        manager: SessionManager,
+    }
+
+    impl SessionReader {
+        /// Connect to the session manager
+        pub fn new() -> Result<Self> {
+            let manager = SessionManager::RequestAsync()?.get()?;
+            Ok(Self { manager })
+        }
+
+        /// Snapshot the current session, or `None` if no app is playing media
+        pub fn read(&self) -> Result<Option<SessionSnapshot>> {
+            // A null current session surfaces as an error with a success code
+            let session = match self.manager.GetCurrentSession() {
+                Ok(session) => session,
+                Err(e) if e.code().is_ok() => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+
+            let properties = session.TryGetMediaPropertiesAsync()?.get()?;
+            let timeline = session.GetTimelineProperties()?;
+            let playback = session.GetPlaybackInfo()?;
+
+            let start = timeline.StartTime()?.Duration;
+            Ok(Some(SessionSnapshot {
+                app_id: session.SourceAppUserModelId()?.to_string(),
+                title: properties.Title()?.to_string(),
+                artist: properties.Artist()?.to_string(),
+                album: properties.AlbumTitle()?.to_string(),
+                is_playing: playback.PlaybackStatus()? == PlaybackStatus::Playing,
+                position: ticks_to_duration(timeline.Position()?.Duration - start),
+                duration: ticks_to_duration(timeline.EndTime()?.Duration - start),
+                last_updated: ticks_to_system_time(timeline.LastUpdatedTime()?.UniversalTime),
+            }))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod unsupported {
+    use super::{Result, SessionSnapshot};
+    use crate::error::WindowsMediaError;
+
+    /// Stand-in on platforms without SMTC
+    pub struct SessionReader;
+
+    impl SessionReader {
+        pub fn new() -> Result<Self> {
+            Err(WindowsMediaError::Unsupported)
+        }
+
+        #[allow(clippy::unused_self)]
+        pub fn read(&self) -> Result<Option<SessionSnapshot>> {
+            Err(WindowsMediaError::Unsupported)
+        }
+    }
+}
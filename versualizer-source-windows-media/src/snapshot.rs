@@ -0,0 +1,142 @@
+//! Platform-independent view of a media session and its conversion to `PlaybackState`.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use versualizer_core::{MusicSource, PlaybackState, TrackInfo};
+
+/// 100ns ticks per second, the unit of `WinRT` `TimeSpan` and `DateTime`
+const TICKS_PER_SECOND: i64 = 10_000_000;
+
+/// `WinRT` `DateTime` ticks (since 1601-01-01) at the Unix epoch
+const UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+
+/// What the current media session reported on one poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSnapshot {
+    /// App user model ID of the player, e.g. `foobar2000.exe` or `Chrome`
+    pub app_id: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub is_playing: bool,
+    /// Position as of `last_updated`
+    pub position: Duration,
+    pub duration: Duration,
+    /// When the player last reported its position. Players only update the timeline
+    /// every few seconds, so the position is extrapolated from here while playing.
+    pub last_updated: Option<SystemTime>,
+}
+
+impl SessionSnapshot {
+    /// Convert to the playback state the `SyncEngine` understands, as of `now`.
+    ///
+    /// SMTC has no track IDs, so the source track ID is built from the player, artist,
+    /// and title. That keeps cache lookups stable across plays of the same track.
+    #[must_use]
+    pub fn to_playback_state(&self, now: SystemTime) -> PlaybackState {
+        if self.title.is_empty() {
+            return PlaybackState::default();
+        }
+
+        let track_id = format!("{}:{} - {}", self.app_id, self.artist, self.title);
+        let track = TrackInfo::new(
+            MusicSource::WindowsMedia,
+            track_id,
+            &self.title,
+            &self.artist,
+            &self.album,
+            self.duration,
+        );
+
+        let mut position = self.position;
+        if self.is_playing
+            && let Some(elapsed) = self
+                .last_updated
+                .and_then(|updated| now.duration_since(updated).ok())
+        {
+            position += elapsed;
+        }
+        if !self.duration.is_zero() {
+            position = position.min(self.duration);
+        }
+
+        PlaybackState::new(self.is_playing, Some(track), position, self.duration)
+    }
+}
+
+/// Convert a `WinRT` `TimeSpan` (100ns ticks) to a `Duration`, clamping negatives to zero
+#[must_use]
+pub fn ticks_to_duration(ticks: i64) -> Duration {
+    let ticks = u64::try_from(ticks).unwrap_or(0);
+    Duration::from_nanos(ticks.saturating_mul(100))
+}
+
+/// Convert a `WinRT` `DateTime` (100ns ticks since 1601-01-01 UTC) to a `SystemTime`.
+/// Returns `None` for unset (zero) or pre-1970 timestamps.
+#[must_use]
+pub fn ticks_to_system_time(ticks: i64) -> Option<SystemTime> {
+    let since_epoch = ticks.checked_sub(UNIX_EPOCH_TICKS)?;
+    if since_epoch < 0 || ticks == 0 {
+        return None;
+    }
+    let secs = u64::try_from(since_epoch / TICKS_PER_SECOND).ok()?;
+    let nanos = u32::try_from((since_epoch % TICKS_PER_SECOND) * 100).ok()?;
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn snapshot(is_playing: bool) -> SessionSnapshot {
+        SessionSnapshot {
+            app_id: "foobar2000.exe".to_string(),
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            is_playing,
+            position: Duration::from_secs(10),
+            duration: Duration::from_mins(3),
+            last_updated: Some(UNIX_EPOCH + Duration::from_secs(1_000)),
+        }
+    }
+
+    #[test]
+    fn test_ticks_conversion() {
+        assert_eq!(ticks_to_duration(15_000_000), Duration::from_millis(1_500));
+        assert_eq!(ticks_to_duration(-1), Duration::ZERO);
+
+        assert_eq!(ticks_to_system_time(0), None);
+        assert_eq!(ticks_to_system_time(UNIX_EPOCH_TICKS), Some(UNIX_EPOCH));
+        assert_eq!(
+            ticks_to_system_time(UNIX_EPOCH_TICKS + 25_000_000),
+            Some(UNIX_EPOCH + Duration::from_millis(2_500))
+        );
+    }
+
+    #[test]
+    fn test_position_is_extrapolated_while_playing() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_005);
+
+        let playing = snapshot(true).to_playback_state(now);
+        assert_eq!(playing.position, Duration::from_secs(15));
+        assert!(playing.is_playing);
+
+        let paused = snapshot(false).to_playback_state(now);
+        assert_eq!(paused.position, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_track_info_mapping() {
+        let state = snapshot(true).to_playback_state(UNIX_EPOCH);
+        let track = state.track.unwrap();
+        assert_eq!(track.source, MusicSource::WindowsMedia);
+        assert_eq!(track.source_track_id, "foobar2000.exe:Artist - Song");
+        assert_eq!(track.name, "Song");
+        assert_eq!(track.album, "Album");
+
+        let mut empty = snapshot(true);
+        empty.title.clear();
+        assert!(empty.to_playback_state(UNIX_EPOCH).track.is_none());
+    }
+}
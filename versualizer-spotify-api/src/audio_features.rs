@@ -0,0 +1,169 @@
+//! Track audio features from Spotify's audio features and audio analysis APIs.
+//!
+//! [`AudioFeaturesService`] follows the current track and publishes its tempo, energy,
+//! beats, bars and sections on a watch channel for beat-quantized line transitions and
+//! other features that react to the music. Tracks with known features are also announced
+//! as [`SyncEvent::AnalysisLoaded`]. Features are cached per track for the session, including tracks
+//! Spotify knows nothing about, so replays and skips back cost no requests.
+//!
+//! Spotify no longer grants audio features or analysis access to new apps; those requests
+//! fail with 403, and every track is then treated as having no known features.
+
+use crate::error::SpotifyError;
+use crate::oauth::SpotifyOAuth;
+use rspotify::model::{TimeInterval, TrackId};
+use rspotify::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use versualizer_core::{
+    AudioFeatures, MusicSource, Section, SyncEngine, SyncEvent, Tempo, TrackInfo,
+};
+
+/// Fetches and caches track audio features
+pub struct AudioFeaturesService {
+    oauth: Arc<SpotifyOAuth>,
+    cache: Mutex<HashMap<String, Arc<AudioFeatures>>>,
+}
+
+impl AudioFeaturesService {
+    /// Create an audio features service using the signed-in Spotify client
+    #[must_use]
+    pub fn new(oauth: Arc<SpotifyOAuth>) -> Self {
+        Self {
+            oauth,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Audio features of a Spotify track. Features Spotify does not know are left empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token cannot be refreshed.
+    pub async fn features(&self, track_id: &str) -> Result<Arc<AudioFeatures>, SpotifyError> {
+        if let Some(features) = self.cache.lock().await.get(track_id) {
+            return Ok(features.clone());
+        }
+        let mut features = AudioFeatures::new(track_id);
+        // Episodes and local files have no audio features
+        if let Ok(id) = TrackId::from_id(track_id) {
+            self.oauth.ensure_token_fresh().await?;
+            let client = self.oauth.client();
+            let (summary, analysis) =
+                tokio::join!(client.track_features(id.clone()), client.track_analysis(id));
+            match summary {
+                Ok(summary) => {
+                    features.tempo = Tempo::new(summary.tempo);
+                    features.energy = Some(summary.energy);
+                }
+                Err(e) => warn!("No audio features for track {}: {}", track_id, e),
+            }
+            match analysis {
+                Ok(analysis) => {
+                    features.sections = analysis
+                        .sections
+                        .iter()
+                        .map(|section| Section {
+                            start: secs(section.time_interval.start),
+                            duration: secs(section.time_interval.duration),
+                            loudness: section.loudness,
+                        })
+                        .collect();
+                    features.beats = starts(&analysis.beats);
+                    features.bars = starts(&analysis.bars);
+                }
+                Err(e) => warn!("No audio analysis for track {}: {}", track_id, e),
+            }
+        }
+        debug!(
+            "Audio features of track {}: tempo {:?}, energy {:?}, {} beats, {} sections",
+            track_id,
+            features.tempo,
+            features.energy,
+            features.beats.len(),
+            features.sections.len()
+        );
+
+        let features = Arc::new(features);
+        self.cache
+            .lock()
+            .await
+            .insert(track_id.to_string(), features.clone());
+        Ok(features)
+    }
+
+    /// Publish the audio features of the current track to `features_tx` until cancelled
+    pub async fn run(
+        self,
+        sync_engine: Arc<SyncEngine>,
+        features_tx: watch::Sender<Option<Arc<AudioFeatures>>>,
+        cancel_token: CancellationToken,
+    ) {
+        let mut rx = sync_engine.subscribe();
+        if let Some(track) = sync_engine.current_track().await {
+            self.publish(&track, &sync_engine, &features_tx).await;
+        }
+
+        loop {
+            let event = tokio::select! {
+                () = cancel_token.cancelled() => break,
+                event = rx.recv() => event,
+            };
+            match event {
+                Ok(
+                    SyncEvent::TrackChanged { track, .. }
+                    | SyncEvent::PlaybackStarted { track, .. },
+                ) => {
+                    self.publish(&track, &sync_engine, &features_tx).await;
+                }
+                Ok(SyncEvent::PlaybackStopped | SyncEvent::AdPlaying) => {
+                    features_tx.send_replace(None);
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        info!("Audio features service stopped");
+    }
+
+    async fn publish(
+        &self,
+        track: &TrackInfo,
+        sync_engine: &SyncEngine,
+        features_tx: &watch::Sender<Option<Arc<AudioFeatures>>>,
+    ) {
+        // Clear the old features right away so the new track never uses them
+        features_tx.send_replace(None);
+        if track.source != MusicSource::Spotify {
+            return;
+        }
+        match self.features(&track.source_track_id).await {
+            Ok(features) if features.is_empty() => {}
+            Ok(features) => {
+                features_tx.send_replace(Some(features.clone()));
+                sync_engine.report_audio_analysis(features);
+            }
+            Err(e) => warn!(
+                "Failed to fetch the audio features of {}: {}",
+                track.name, e
+            ),
+        }
+    }
+}
+
+/// Start times of analysis intervals such as beats or bars
+fn starts(intervals: &[TimeInterval]) -> Vec<Duration> {
+    intervals
+        .iter()
+        .map(|interval| secs(interval.start))
+        .collect()
+}
+
+/// Seconds from the analysis API as a duration, treating invalid values as zero
+fn secs(value: f32) -> Duration {
+    Duration::try_from_secs_f32(value).unwrap_or_default()
+}
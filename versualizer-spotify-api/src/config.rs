@@ -2,20 +2,31 @@
 
 use const_format::concatcp;
 use serde::{Deserialize, Serialize};
-use versualizer_core::{CoreError, ProvidersConfig};
+use tracing::warn;
+use versualizer_core::{CoreError, ProvidersConfig, SecretStore, VersualizerConfig};
 
 /// Provider name used in config file
 pub const PROVIDER_NAME: &str = "spotify";
 
+/// Spotify Web API base URL
+pub const SPOTIFY_API_URL: &str = "https://api.spotify.com/v1";
+
 /// Default URL for fetching Spotify TOTP secret keys
 pub const DEFAULT_SECRET_KEY_URL: &str = "https://raw.githubusercontent.com/xyloflake/spot-secrets-go/refs/heads/main/secrets/secretDict.json";
 
+/// Secret store name of the client secret, used when `client_secret` is left empty
+pub const CLIENT_SECRET_NAME: &str = "spotify.client_secret";
+
+/// Secret store name of the `sp_dc` cookie, used when `sp_dc` is set but empty
+pub const SP_DC_SECRET_NAME: &str = "spotify.sp_dc";
+
 /// Spotify-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpotifyProviderConfig {
     /// Spotify OAuth client ID
     pub client_id: String,
-    /// Spotify OAuth client secret
+    /// Spotify OAuth client secret. Empty to read it from the secret store.
+    #[serde(default)]
     pub client_secret: String,
     /// OAuth redirect URI
     #[serde(default = "default_redirect_uri")]
@@ -23,11 +34,19 @@ pub struct SpotifyProviderConfig {
     /// Polling interval in milliseconds
     #[serde(default = "default_poll_interval")]
     pub poll_interval_ms: u64,
-    /// Optional: For unofficial Spotify lyrics API (use at your own risk)
+    /// Optional: For unofficial Spotify lyrics API (use at your own risk). Empty to read it
+    /// from the secret store.
     pub sp_dc: Option<String>,
     /// Optional: URL for fetching Spotify TOTP secret keys
     #[serde(default)]
     pub secret_key_url: Option<String>,
+    /// Poll as soon as the web player's push connection reports a playback change, rather
+    /// than only every `poll_interval_ms`. Needs `sp_dc`.
+    #[serde(default)]
+    pub push_updates: bool,
+    /// Tracks ahead in the playback queue whose lyrics are fetched in advance, 0 for none
+    #[serde(default = "default_prefetch_queue")]
+    pub prefetch_queue: usize,
 }
 
 fn default_redirect_uri() -> String {
@@ -38,6 +57,10 @@ const fn default_poll_interval() -> u64 {
     1000
 }
 
+const fn default_prefetch_queue() -> usize {
+    2
+}
+
 impl SpotifyProviderConfig {
     /// Extract Spotify config from the dynamic providers config.
     ///
@@ -48,6 +71,38 @@ impl SpotifyProviderConfig {
         providers.get(PROVIDER_NAME)
     }
 
+    /// Extract Spotify config from `[providers.spotify]`, with secrets left out of it read
+    /// from `[lyrics] secret_store`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config cannot be parsed.
+    pub fn load(config: &VersualizerConfig) -> Result<Option<Self>, CoreError> {
+        let Some(mut spotify) = Self::from_providers(&config.providers)? else {
+            return Ok(None);
+        };
+        spotify.resolve_secrets(config.lyrics.secret_store.open().as_ref());
+        Ok(Some(spotify))
+    }
+
+    /// Fill an empty `client_secret` or `sp_dc` from `store`, under [`CLIENT_SECRET_NAME`]
+    /// and [`SP_DC_SECRET_NAME`]. Values in the config file are kept as they are, so the
+    /// store is only read for what was left out. A store that cannot be read is logged and
+    /// leaves the field empty, for [`validate`](Self::validate) to report.
+    pub fn resolve_secrets(&mut self, store: &dyn SecretStore) {
+        if self.client_secret.is_empty()
+            && let Some(secret) = stored_secret(store, CLIENT_SECRET_NAME)
+        {
+            self.client_secret = secret;
+        }
+        if let Some(sp_dc) = self.sp_dc.as_mut().filter(|sp_dc| sp_dc.is_empty()) {
+            match stored_secret(store, SP_DC_SECRET_NAME) {
+                Some(secret) => *sp_dc = secret,
+                None => self.sp_dc = None,
+            }
+        }
+    }
+
     /// Validate that required fields are present.
     ///
     /// # Errors
@@ -68,20 +123,39 @@ impl SpotifyProviderConfig {
     }
 }
 
+fn stored_secret(store: &dyn SecretStore, name: &str) -> Option<String> {
+    match store.get(name) {
+        Ok(secret) => secret.filter(|secret| !secret.is_empty()),
+        Err(e) => {
+            warn!("Failed to read {} from the secret store: {}", name, e);
+            None
+        }
+    }
+}
+
 /// Config template for Spotify provider.
 /// This is appended to the base config template when creating a new config file.
 pub const CONFIG_TEMPLATE: &str = concatcp!(
     r#"[providers.spotify]
 # Required when music.source = "spotify"
-# Get these from https://developer.spotify.com/dashboard
+# Get these from https://developer.spotify.com/dashboard. Leave client_secret empty to keep
+# it in [lyrics] secret_store instead: versualizer-cli secret set spotify.client_secret
 client_id = ""
 client_secret = ""
 oauth_redirect_uri = "http://127.0.0.1:8888/callback"
 poll_interval_ms = 1000
-# Optional: For unofficial Spotify lyrics API (use at your own risk - may violate TOS)
+# Optional: For unofficial Spotify lyrics API (use at your own risk - may violate TOS).
+# Set to "" to keep it in the secret store instead: versualizer-cli secret set spotify.sp_dc
 # sp_dc = ""
 # Optional: URL for fetching TOTP secret keys
 # secret_key_url = ""#,
     DEFAULT_SECRET_KEY_URL,
-    "\"\n\n"
+    "\"\n",
+    r"# Optional: Pick up skips, pauses and seeks instantly through the web player's push
+# connection, falling back to polling when it drops. Needs sp_dc (use at your own risk)
+# push_updates = false
+# Tracks ahead in the queue whose lyrics are fetched in advance (0 to turn off)
+# prefetch_queue = 2
+
+"
 );
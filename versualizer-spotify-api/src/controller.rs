@@ -0,0 +1,89 @@
+//! Playback control through the Spotify Web API player endpoints.
+//!
+//! Commands go to the user's active device and need the `user-modify-playback-state`
+//! scope, so tokens from before playback control existed fail until the user signs in
+//! again. Spotify only accepts player commands from Premium accounts.
+
+use crate::oauth::SpotifyOAuth;
+use async_trait::async_trait;
+use rspotify::AuthCodeSpotify;
+use rspotify::prelude::*;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use versualizer_core::{CoreError, PlaybackController};
+
+/// Controls playback on the active Spotify device
+pub struct SpotifyController {
+    oauth: Arc<SpotifyOAuth>,
+}
+
+impl SpotifyController {
+    /// Create a controller using the signed-in Spotify client
+    #[must_use]
+    pub const fn new(oauth: Arc<SpotifyOAuth>) -> Self {
+        Self { oauth }
+    }
+
+    /// The client with a fresh token, for the player command `command`
+    async fn client(&self, command: &str) -> Result<&AuthCodeSpotify, CoreError> {
+        debug!("Spotify playback command: {}", command);
+        self.oauth
+            .ensure_token_fresh()
+            .await
+            .map_err(|e| failed(command, e))?;
+        Ok(self.oauth.client())
+    }
+}
+
+fn failed(command: &str, error: impl Display) -> CoreError {
+    CoreError::SourceFailed {
+        name: "Spotify".to_string(),
+        reason: format!("{command} failed: {error}"),
+    }
+}
+
+#[async_trait]
+impl PlaybackController for SpotifyController {
+    async fn play(&self) -> Result<(), CoreError> {
+        self.client("play")
+            .await?
+            .resume_playback(None, None)
+            .await
+            .map_err(|e| failed("play", e))
+    }
+
+    async fn pause(&self) -> Result<(), CoreError> {
+        self.client("pause")
+            .await?
+            .pause_playback(None)
+            .await
+            .map_err(|e| failed("pause", e))
+    }
+
+    async fn next(&self) -> Result<(), CoreError> {
+        self.client("next")
+            .await?
+            .next_track(None)
+            .await
+            .map_err(|e| failed("next", e))
+    }
+
+    async fn previous(&self) -> Result<(), CoreError> {
+        self.client("previous")
+            .await?
+            .previous_track(None)
+            .await
+            .map_err(|e| failed("previous", e))
+    }
+
+    async fn seek(&self, position: Duration) -> Result<(), CoreError> {
+        let position = chrono::Duration::from_std(position).unwrap_or(chrono::Duration::MAX);
+        self.client("seek")
+            .await?
+            .seek_track(position, None)
+            .await
+            .map_err(|e| failed("seek", e))
+    }
+}
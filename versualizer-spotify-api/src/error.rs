@@ -34,6 +34,10 @@ pub enum SpotifyError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// Failed to read or write the secret store the token is kept in.
+    #[error("Secret store error: {0}")]
+    SecretStore(#[from] versualizer_core::CoreError),
+
     /// Poller was stopped.
     #[error("Spotify poller stopped")]
     PollerStopped,
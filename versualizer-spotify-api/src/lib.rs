@@ -1,11 +1,17 @@
+pub mod audio_features;
 pub mod config;
+pub mod controller;
 pub mod error;
 pub mod oauth;
 pub mod paths;
 pub mod poller;
 
-pub use config::{CONFIG_TEMPLATE as SPOTIFY_CONFIG_TEMPLATE, SpotifyProviderConfig};
+pub use audio_features::AudioFeaturesService;
+pub use config::{
+    CONFIG_TEMPLATE as SPOTIFY_CONFIG_TEMPLATE, SPOTIFY_API_URL, SpotifyProviderConfig,
+};
+pub use controller::SpotifyController;
 pub use error::SpotifyError;
-pub use oauth::SpotifyOAuth;
-pub use paths::SPOTIFY_TOKEN_CACHE_FILE_NAME;
+pub use oauth::{HeadlessSignIn, SpotifyOAuth, delete_keyring_token};
+pub use paths::{SPOTIFY_TOKEN_CACHE_FILE_NAME, spotify_data_paths};
 pub use poller::SpotifyPoller;
@@ -4,11 +4,28 @@ use rspotify::{AuthCodeSpotify, Credentials, OAuth, Token, prelude::*, scopes};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::oneshot;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Notify, oneshot};
 use tracing::{debug, info, warn};
+use versualizer_core::{AuthState, CheckResult, CoreError, KeyringSecretStore, SecretStore};
+
+/// Secret store name the sign-in token is kept under
+pub const TOKEN_SECRET_NAME: &str = "spotify.token";
+
+/// Delete the sign-in token from the OS keyring, for purging tokens along with the token
+/// file. Does nothing if no keyring is available.
+///
+/// # Errors
+///
+/// Returns an error if the keyring is available but the token cannot be deleted.
+pub fn delete_keyring_token() -> Result<(), CoreError> {
+    if !KeyringSecretStore::is_available() {
+        return Ok(());
+    }
+    KeyringSecretStore::new().delete(TOKEN_SECRET_NAME)
+}
 
 /// Timeout for interactive OAuth callback (10 minutes)
 const OAUTH_CALLBACK_TIMEOUT_SECS: u64 = 600;
@@ -52,10 +69,24 @@ impl TryFrom<PersistedToken> for Token {
     }
 }
 
+/// A sign-in for a machine without a browser, to finish on any device that has one
+#[derive(Debug, Clone)]
+pub struct HeadlessSignIn {
+    /// Address to open and sign in at
+    pub url: String,
+    /// Callback address the sign-in also completes through if that browser can reach it,
+    /// e.g. over an SSH port forward. `None` if the callback server could not start.
+    pub callback: Option<String>,
+}
+
 /// Spotify OAuth manager
 pub struct SpotifyOAuth {
     client: AuthCodeSpotify,
     token_path: PathBuf,
+    /// Where the token is kept instead of the token file
+    secret_store: Option<Arc<dyn SecretStore>>,
+    /// Notified after every successful interactive sign-in
+    signed_in: Notify,
 }
 
 impl SpotifyOAuth {
@@ -73,7 +104,11 @@ impl SpotifyOAuth {
 
         let oauth = OAuth {
             redirect_uri: redirect_uri.into(),
-            scopes: scopes!("user-read-currently-playing", "user-read-playback-state"),
+            scopes: scopes!(
+                "user-read-currently-playing",
+                "user-read-playback-state",
+                "user-modify-playback-state"
+            ),
             ..Default::default()
         };
 
@@ -81,7 +116,20 @@ impl SpotifyOAuth {
 
         let token_path = Self::token_path();
 
-        Ok(Self { client, token_path })
+        Ok(Self {
+            client,
+            token_path,
+            secret_store: None,
+            signed_in: Notify::new(),
+        })
+    }
+
+    /// Keep the token in `store` rather than in the plaintext token file. A token file
+    /// left by an earlier version is moved into the store the next time it is loaded.
+    #[must_use]
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(store);
+        self
     }
 
     /// Get the token file path (~/.`config/versualizer/.spotify_token_cache.json`)
@@ -110,15 +158,22 @@ impl SpotifyOAuth {
     ///
     /// # Errors
     ///
-    /// Returns an error if the token file cannot be read, parsed, or the token cannot be refreshed.
+    /// Returns an error if the saved token cannot be read, parsed, moved into the secret
+    /// store, or refreshed.
     pub async fn load_cached_token(&self) -> Result<bool, SpotifyError> {
-        if !self.token_path.exists() {
-            info!("No cached token file found at {:?}", self.token_path);
+        let store = self.secret_store.as_deref();
+        let Some((persisted, from_file)) = read_persisted(store, &self.token_path)? else {
+            info!("No saved Spotify token found");
             return Ok(false);
+        };
+        if from_file && let Some(store) = store {
+            store.set(TOKEN_SECRET_NAME, &serde_json::to_string(&persisted)?)?;
+            fs::remove_file(&self.token_path)?;
+            info!(
+                "Moved the Spotify token from {:?} into the secret store",
+                self.token_path
+            );
         }
-
-        let content = fs::read_to_string(&self.token_path)?;
-        let persisted: PersistedToken = serde_json::from_str(&content)?;
         let token = Token::try_from(persisted)?;
 
         // Check if token is expired
@@ -137,11 +192,52 @@ impl SpotifyOAuth {
         Ok(true)
     }
 
-    /// Save current token to file
+    /// Preflight check of the saved OAuth token, without refreshing or contacting Spotify
+    #[must_use]
+    pub fn check_saved_token(&self) -> CheckResult {
+        Self::check_cached_token(self.secret_store.as_deref())
+    }
+
+    /// Preflight check of the OAuth token saved in `store`, or in the token file without
+    /// one, without refreshing or contacting Spotify
+    #[must_use]
+    pub fn check_cached_token(store: Option<&dyn SecretStore>) -> CheckResult {
+        const NAME: &str = "Spotify OAuth token";
+        let login_hint = "Start Versualizer to sign in with Spotify in the browser";
+        let token = match read_persisted(store, &Self::token_path()) {
+            Ok(Some((token, _))) => token,
+            Ok(None) => return CheckResult::warn(NAME, "Not signed in").with_hint(login_hint),
+            Err(e) => {
+                return CheckResult::fail(NAME, format!("Unreadable saved token: {e}"))
+                    .with_hint("Sign in again with `versualizer-cli auth spotify --force`");
+            }
+        };
+
+        let expires_at = token
+            .expires_at
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0));
+        match expires_at {
+            Some(expires_at) if expires_at > chrono::Utc::now() => CheckResult::pass(
+                NAME,
+                format!("Valid until {}", expires_at.with_timezone(&chrono::Local)),
+            ),
+            _ if token.refresh_token.is_some() => {
+                CheckResult::pass(NAME, "Expired, will be refreshed on startup")
+            }
+            _ => CheckResult::fail(NAME, "Expired with no refresh token").with_hint(login_hint),
+        }
+    }
+
+    /// Save current token to the secret store, or the token file without one
     async fn save_token(&self) -> Result<(), SpotifyError> {
         let token_guard = self.lock_token().await?;
         if let Some(ref token) = *token_guard {
             let persisted = PersistedToken::from(token);
+            if let Some(store) = &self.secret_store {
+                store.set(TOKEN_SECRET_NAME, &serde_json::to_string(&persisted)?)?;
+                debug!("Saved Spotify token to the secret store");
+                return Ok(());
+            }
 
             // Ensure directory exists
             if let Some(parent) = self.token_path.parent() {
@@ -249,9 +345,17 @@ impl SpotifyOAuth {
 
         self.save_token().await?;
         info!("Successfully authenticated with Spotify");
+        self.signed_in.notify_one();
         Ok(())
     }
 
+    /// Wait for the next successful interactive sign-in, e.g. a re-authentication from the
+    /// settings panel after the startup sign-in failed. A sign-in that completes before
+    /// anyone waits is remembered for the next call.
+    pub async fn wait_for_sign_in(&self) {
+        self.signed_in.notified().await;
+    }
+
     /// Start the OAuth flow with a local HTTP server using axum
     ///
     /// # Errors
@@ -279,6 +383,85 @@ impl SpotifyOAuth {
         self.handle_callback(&code).await
     }
 
+    /// Sign in without opening a browser here. `show` is given the address to sign in at
+    /// on another device. This is a paste-back flow: Spotify has no device-code grant to
+    /// poll, so the sign-in completes when `pasted` yields the address that device's
+    /// browser was sent back to (which fails to load on a device other than this one),
+    /// or earlier if that browser reaches the callback server. The token is saved like an
+    /// interactive sign-in's.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pasted address is not from this sign-in, no sign-in arrives
+    /// in time, or the token exchange fails.
+    pub async fn authenticate_headless(
+        &self,
+        show: impl FnOnce(&HeadlessSignIn),
+        pasted: impl Future<Output = Option<String>>,
+    ) -> Result<(), SpotifyError> {
+        let (host, port, callback_path) = self.parse_redirect_uri()?;
+        let (tx, mut rx) = oneshot::channel::<String>();
+        let tx = Arc::new(tokio::sync::Mutex::new(Some(tx)));
+        let app = Self::build_callback_router(&callback_path, tx);
+        let server = match Self::start_callback_server(&host, port, &callback_path).await {
+            Ok((listener, addr)) => Some((listener, addr)),
+            Err(e) => {
+                warn!("{}, waiting for a pasted address only", e);
+                None
+            }
+        };
+
+        show(&HeadlessSignIn {
+            url: self.get_authorize_url()?,
+            callback: server
+                .as_ref()
+                .map(|(_, addr)| format!("http://{addr}{callback_path}")),
+        });
+
+        let serve = async move {
+            match server {
+                Some((listener, _)) => {
+                    let _ = axum::serve(listener, app).await;
+                }
+                None => std::future::pending().await,
+            }
+        };
+        let pasted = async {
+            match pasted.await {
+                Some(url) => url,
+                // Nothing more will be pasted, keep waiting for the callback
+                None => std::future::pending().await,
+            }
+        };
+        let code = tokio::select! {
+            result = &mut rx => result.map_err(|_| SpotifyError::AuthFailed {
+                reason: "Callback channel closed unexpectedly".into(),
+            })?,
+            url = pasted => self.client.parse_response_code(url.trim()).ok_or_else(|| {
+                SpotifyError::AuthFailed {
+                    reason: "The pasted address is not the one this sign-in was sent back to"
+                        .into(),
+                }
+            })?,
+            () = serve => {
+                return Err(SpotifyError::AuthFailed {
+                    reason: "Server stopped unexpectedly".into(),
+                });
+            }
+            () = tokio::time::sleep(Duration::from_secs(OAUTH_CALLBACK_TIMEOUT_SECS)) => {
+                return Err(SpotifyError::AuthFailed {
+                    reason: format!(
+                        "Sign-in timed out after {} minutes. Please try again.",
+                        OAUTH_CALLBACK_TIMEOUT_SECS / 60
+                    ),
+                });
+            }
+        };
+
+        info!("Received authorization code, exchanging for token...");
+        self.handle_callback(&code).await
+    }
+
     /// Ensure we have a valid token, refreshing or re-authenticating if needed
     ///
     /// # Errors
@@ -309,8 +492,56 @@ impl SpotifyOAuth {
         self.authenticate_interactive().await
     }
 
+    /// Credential state of the in-memory token, for the settings auth page
+    pub async fn auth_state(&self) -> AuthState {
+        let token = match self.lock_token().await {
+            Ok(token) => token.clone(),
+            Err(e) => {
+                return AuthState {
+                    authenticated: false,
+                    expires_at: None,
+                    detail: e.to_string(),
+                };
+            }
+        };
+        match token {
+            Some(token) => AuthState {
+                authenticated: !token.is_expired(),
+                expires_at: token.expires_at.map(SystemTime::from),
+                detail: if token.refresh_token.is_some() {
+                    "Signed in, refreshes automatically".into()
+                } else {
+                    "Signed in".into()
+                },
+            },
+            None => AuthState {
+                authenticated: false,
+                expires_at: None,
+                detail: "Not signed in".into(),
+            },
+        }
+    }
+
+    /// Sign in again through the browser. The current token is only replaced once the new
+    /// sign-in succeeds, so a cancelled or failed attempt leaves playback polling working.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if interactive authentication fails.
+    pub async fn reauthenticate(&self) -> Result<(), SpotifyError> {
+        self.authenticate_interactive().await
+    }
+
     /// Clear cached tokens
     pub fn clear_tokens(&self) {
+        if let Some(store) = &self.secret_store
+            && let Err(e) = store.delete(TOKEN_SECRET_NAME)
+        {
+            warn!(
+                "Failed to delete the Spotify token from the secret store: {}",
+                e
+            );
+        }
         if self.token_path.exists() {
             let _ = fs::remove_file(&self.token_path);
         }
@@ -524,3 +755,21 @@ const SUCCESS_HTML: &str = r#"<!DOCTYPE html>
     </div>
 </body>
 </html>"#;
+
+/// The saved token, from `store` if it holds one and otherwise from the token file at
+/// `path`, with whether it came from the file
+fn read_persisted(
+    store: Option<&dyn SecretStore>,
+    path: &Path,
+) -> Result<Option<(PersistedToken, bool)>, SpotifyError> {
+    if let Some(store) = store
+        && let Some(content) = store.get(TOKEN_SECRET_NAME)?
+    {
+        return Ok(Some((serde_json::from_str(&content)?, false)));
+    }
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(Some((serde_json::from_str(&content)?, true)))
+}
@@ -1,6 +1,7 @@
 //! Path constants for Spotify-specific files.
 
 use std::path::PathBuf;
+use versualizer_core::paths::{DataCategory, DataPath};
 
 /// The name of the Spotify token cache file
 pub const SPOTIFY_TOKEN_CACHE_FILE_NAME: &str = ".spotify_token_cache.json";
@@ -10,3 +11,12 @@ pub const SPOTIFY_TOKEN_CACHE_FILE_NAME: &str = ".spotify_token_cache.json";
 pub fn spotify_token_cache_path() -> PathBuf {
     versualizer_core::paths::config_dir().join(SPOTIFY_TOKEN_CACHE_FILE_NAME)
 }
+
+/// User data written by the Spotify integration, for the core data registry
+#[must_use]
+pub fn spotify_data_paths() -> Vec<DataPath> {
+    vec![DataPath::new(
+        DataCategory::Tokens,
+        spotify_token_cache_path(),
+    )]
+}
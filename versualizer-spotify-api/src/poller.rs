@@ -3,21 +3,34 @@
 use crate::error::SpotifyError;
 use crate::oauth::SpotifyOAuth;
 use async_trait::async_trait;
+use rspotify::model::{CurrentPlaybackContext, CurrentlyPlayingType, PlayableItem};
 use rspotify::prelude::*;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 use versualizer_core::{
-    CoreError, DurationExt, MusicSource, MusicSourceProvider, PlaybackState, SyncEngine, TrackInfo,
+    CoreError, DurationExt, LyricsFetcher, MusicSource, MusicSourceProvider, PlaybackState,
+    SyncEngine, TrackInfo,
 };
 
+/// Wait before prefetching the queue after a track change, so the new track's own lyrics
+/// fetch goes first
+const PREFETCH_DELAY: Duration = Duration::from_secs(5);
+
 /// Spotify playback state poller implementing [`MusicSourceProvider`].
 pub struct SpotifyPoller {
     oauth: Arc<SpotifyOAuth>,
     sync_engine: Arc<SyncEngine>,
     poll_interval: Duration,
     cancel_token: CancellationToken,
+    /// Polls right away when notified, e.g. by a push connection seeing a playback change
+    wakeup: Option<Arc<Notify>>,
+    /// Fetches lyrics of the tracks queued up next, and how many of them
+    prefetch: Option<(Arc<LyricsFetcher>, usize)>,
+    /// Track whose queue was last prefetched
+    prefetched_after: Mutex<Option<String>>,
 }
 
 impl SpotifyPoller {
@@ -39,6 +52,73 @@ impl SpotifyPoller {
             sync_engine,
             poll_interval: Duration::from_millis(poll_interval_ms),
             cancel_token: cancel_token.unwrap_or_default(),
+            wakeup: None,
+            prefetch: None,
+            prefetched_after: Mutex::new(None),
+        }
+    }
+
+    /// Also poll whenever `wakeup` is notified, so changes pushed from elsewhere show up
+    /// without waiting for the next poll
+    #[must_use]
+    pub fn with_wakeup(mut self, wakeup: Arc<Notify>) -> Self {
+        self.wakeup = Some(wakeup);
+        self
+    }
+
+    /// Prefetch the lyrics of the next `count` tracks in the playback queue through
+    /// `fetcher` whenever the track changes, so they show as soon as those tracks start
+    #[must_use]
+    pub fn with_queue_prefetch(mut self, fetcher: Arc<LyricsFetcher>, count: usize) -> Self {
+        if count > 0 {
+            self.prefetch = Some((fetcher, count));
+        }
+        self
+    }
+
+    /// The next `count` tracks in the playback queue
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the queue cannot be fetched.
+    pub async fn upcoming_tracks(&self, count: usize) -> Result<Vec<TrackInfo>, SpotifyError> {
+        queued_tracks(&self.oauth, count).await
+    }
+
+    /// Prefetch the lyrics of the tracks queued after `current` in the background, once per
+    /// track
+    fn prefetch_queue(&self, current: &TrackInfo) {
+        let Some((fetcher, count)) = self.prefetch.clone() else {
+            return;
+        };
+        {
+            let mut prefetched_after = self
+                .prefetched_after
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if prefetched_after.as_deref() == Some(current.source_track_id.as_str()) {
+                return;
+            }
+            *prefetched_after = Some(current.source_track_id.clone());
+        }
+        let oauth = self.oauth.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(PREFETCH_DELAY).await;
+            match queued_tracks(&oauth, count).await {
+                Ok(tracks) => {
+                    for track in &tracks {
+                        fetcher.prefetch(track).await;
+                    }
+                }
+                Err(e) => warn!("Failed to get the Spotify queue: {}", e),
+            }
+        });
+    }
+    /// Wait for a wakeup, forever without one
+    async fn woken(&self) {
+        match &self.wakeup {
+            Some(wakeup) => wakeup.notified().await,
+            None => std::future::pending().await,
         }
     }
 
@@ -67,53 +147,22 @@ impl SpotifyPoller {
 
         let request_latency = request_start.elapsed();
 
+        if playback.as_ref().is_some_and(is_ad) {
+            // Ads have no lyrics, so nothing is fetched until the next track
+            debug!("Polled Spotify: ad playing");
+            self.sync_engine.set_ad_playing().await;
+            return Ok(());
+        }
+
         let state = if let Some(context) = playback {
-            // Extract track info and duration together to avoid borrow issues
-            let (track_info, duration) = match &context.item {
-                Some(rspotify::model::PlayableItem::Track(track)) => {
-                    let artists = track
-                        .artists
-                        .iter()
-                        .map(|a| a.name.as_str())
-                        .collect::<Vec<_>>()
-                        .join(", ");
-
-                    let dur = track.duration.to_std().unwrap_or(Duration::ZERO);
-                    // Use just the ID part, not the full URI (spotify:track:xxx -> xxx)
-                    let track_id = track
-                        .id
-                        .as_ref()
-                        .map(|id| id.id().to_string())
-                        .unwrap_or_default();
-                    let info = TrackInfo::new(
-                        MusicSource::Spotify,
-                        &track_id,
-                        &track.name,
-                        artists,
-                        &track.album.name,
-                        dur,
-                    )
-                    // Also add the track ID under "spotify" for lyrics providers
-                    .with_provider_id("spotify", &track_id);
-                    (Some(info), dur)
-                }
-                Some(rspotify::model::PlayableItem::Episode(episode)) => {
-                    let dur = episode.duration.to_std().unwrap_or(Duration::ZERO);
-                    // Use just the ID part, not the full URI
-                    let episode_id = episode.id.id().to_string();
-                    let info = TrackInfo::new(
-                        MusicSource::Spotify,
-                        &episode_id,
-                        &episode.name,
-                        &episode.show.name,
-                        "Podcast",
-                        dur,
-                    )
-                    .with_provider_id("spotify", &episode_id);
-                    (Some(info), dur)
-                }
-                None => (None, Duration::ZERO),
-            };
+            let (track_info, duration) =
+                context
+                    .item
+                    .as_ref()
+                    .map_or((None, Duration::ZERO), |item| {
+                        let (info, duration) = item_info(item);
+                        (Some(info), duration)
+                    });
 
             // Compensate for network latency
             // Assume position is from halfway through the request
@@ -135,6 +184,10 @@ impl SpotifyPoller {
             state.position
         );
 
+        if let Some(track) = &state.track {
+            self.prefetch_queue(track);
+        }
+
         // Update sync engine
         self.sync_engine.update_state(state).await;
 
@@ -142,6 +195,78 @@ impl SpotifyPoller {
     }
 }
 
+/// Whether Spotify is playing an ad on a free account: reported as one, or as a track that
+/// has no ID and is not a local file
+fn is_ad(context: &CurrentPlaybackContext) -> bool {
+    context.currently_playing_type == CurrentlyPlayingType::Advertisement
+        || matches!(&context.item, Some(PlayableItem::Track(track)) if track.id.is_none() && !track.is_local)
+}
+
+/// The next `count` tracks in the playback queue, skipping episodes
+async fn queued_tracks(oauth: &SpotifyOAuth, count: usize) -> Result<Vec<TrackInfo>, SpotifyError> {
+    oauth.ensure_token_fresh().await?;
+    let queue = oauth.client().current_user_queue().await?;
+    Ok(queue
+        .queue
+        .iter()
+        .filter(|item| matches!(item, PlayableItem::Track(_)))
+        .take(count)
+        .map(|item| item_info(item).0)
+        .collect())
+}
+
+/// Track info and duration of a playing or queued item
+fn item_info(item: &PlayableItem) -> (TrackInfo, Duration) {
+    match item {
+        PlayableItem::Track(track) => {
+            let artists = track
+                .artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let dur = track.duration.to_std().unwrap_or(Duration::ZERO);
+            // Use just the ID part, not the full URI (spotify:track:xxx -> xxx)
+            let track_id = track
+                .id
+                .as_ref()
+                .map(|id| id.id().to_string())
+                .unwrap_or_default();
+            let mut info = TrackInfo::new(
+                MusicSource::Spotify,
+                &track_id,
+                &track.name,
+                artists,
+                &track.album.name,
+                dur,
+            )
+            // Also add the track ID under "spotify" for lyrics providers
+            .with_provider_id("spotify", &track_id);
+            info.album_art_url = track.album.images.first().map(|image| image.url.clone());
+            info.isrc = track.external_ids.get("isrc").cloned();
+            (info, dur)
+        }
+        PlayableItem::Episode(episode) => {
+            let dur = episode.duration.to_std().unwrap_or(Duration::ZERO);
+            // Use just the ID part, not the full URI
+            let episode_id = episode.id.id().to_string();
+            let mut info = TrackInfo::new(
+                MusicSource::Spotify,
+                &episode_id,
+                &episode.name,
+                &episode.show.name,
+                "Podcast",
+                dur,
+            )
+            // Under "spotify_episode" so the Spotify lyrics provider fetches its transcript
+            .with_provider_id("spotify_episode", &episode_id);
+            info.album_art_url = episode.images.first().map(|image| image.url.clone());
+            (info, dur)
+        }
+    }
+}
+
 #[async_trait]
 impl MusicSourceProvider for SpotifyPoller {
     fn source(&self) -> MusicSource {
@@ -168,34 +293,39 @@ impl MusicSourceProvider for SpotifyPoller {
                     info!("Poller shutting down gracefully");
                     break;
                 }
-                () = tokio::time::sleep(self.poll_interval) => {
-                    match self.poll_once().await {
-                        Ok(()) => {
-                            consecutive_errors = 0;
-                        }
-                        Err(e) => {
-                            consecutive_errors += 1;
-                            warn!("Poll error (attempt {}): {}", consecutive_errors, e);
-
-                            // Exponential backoff: 100ms * 2^errors, capped at max_backoff
-                            // consecutive_errors is capped at 10, so max is 100 * 2^10 = 102,400ms
-                            let backoff_ms = 100_u64
-                                .saturating_mul(2_u64.saturating_pow(consecutive_errors.min(10)));
-                            let backoff = Duration::from_millis(backoff_ms.min(max_backoff.as_millis_u64()));
-
-                            if consecutive_errors >= 5 {
-                                error!("Too many consecutive errors, waiting {} seconds", backoff.as_secs());
-                            }
-
-                            tokio::time::sleep(backoff).await;
-
-                            // Try to refresh token on auth errors
-                            if matches!(e, SpotifyError::Api(_))
-                                && let Err(refresh_err) = self.oauth.refresh_token().await
-                            {
-                                error!("Token refresh failed: {}", refresh_err);
-                            }
-                        }
+                () = tokio::time::sleep(self.poll_interval) => {}
+                () = self.woken() => debug!("Polling early after a pushed playback change"),
+            }
+
+            match self.poll_once().await {
+                Ok(()) => {
+                    consecutive_errors = 0;
+                }
+                Err(e) => {
+                    consecutive_errors += 1;
+                    warn!("Poll error (attempt {}): {}", consecutive_errors, e);
+
+                    // Exponential backoff: 100ms * 2^errors, capped at max_backoff
+                    // consecutive_errors is capped at 10, so max is 100 * 2^10 = 102,400ms
+                    let backoff_ms =
+                        100_u64.saturating_mul(2_u64.saturating_pow(consecutive_errors.min(10)));
+                    let backoff =
+                        Duration::from_millis(backoff_ms.min(max_backoff.as_millis_u64()));
+
+                    if consecutive_errors >= 5 {
+                        error!(
+                            "Too many consecutive errors, waiting {} seconds",
+                            backoff.as_secs()
+                        );
+                    }
+
+                    tokio::time::sleep(backoff).await;
+
+                    // Try to refresh token on auth errors
+                    if matches!(e, SpotifyError::Api(_))
+                        && let Err(refresh_err) = self.oauth.refresh_token().await
+                    {
+                        error!("Token refresh failed: {}", refresh_err);
                     }
                 }
             }
@@ -0,0 +1,300 @@
+//! Terminal frontend: the karaoke lyrics of the playing track, drawn with ratatui for SSH
+//! sessions, tiling window managers and machines without a compositor.
+
+mod ui;
+mod view;
+
+use pico_args::Arguments;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use versualizer_core::error::Result;
+use versualizer_core::paths;
+use versualizer_core::{
+    Blocklist, ContentCipher, CoreError, LyricsCache, LyricsFetcher, LyricsMirror, LyricsOverrides,
+    MusicBrainzEnricher, MusicSource, QueryNormalizer, SyncEngine, TextPipeline, TracePlayer,
+    VersualizerConfig,
+};
+use versualizer_lyrics_genius::GENIUS_CONFIG_TEMPLATE;
+use versualizer_lyrics_spotify::SpotifyDealer;
+use versualizer_providers::create_providers;
+use versualizer_source_windows_media::WindowsMediaPoller;
+use versualizer_spotify_api::{
+    SPOTIFY_CONFIG_TEMPLATE, SpotifyOAuth, SpotifyPoller, SpotifyProviderConfig,
+};
+
+/// `--replay`, `--config` and `--profile`
+type Args = (Option<PathBuf>, Option<PathBuf>, Option<String>);
+
+fn parse_args(args: &mut Arguments) -> std::result::Result<Args, pico_args::Error> {
+    Ok((
+        args.opt_value_from_str("--replay")?,
+        args.opt_value_from_str("--config")?,
+        args.opt_value_from_str("--profile")?,
+    ))
+}
+
+/// Log file of the terminal frontend, next to the app's own
+const LOG_FILE_NAME: &str = "versualizer-tui.log";
+
+const USAGE: &str = "\
+Usage: versualizer-tui [--config <path> | --profile <name>] [--replay <trace.jsonl>]
+
+Shows the lyrics of the playing track in the terminal, using the app's config and cache.
+
+Options:
+  --config <path>   Use this config file instead of config.toml
+  --profile <name>  Use the config file of a named profile, profiles/<name>.toml
+  --replay <path>   Replay a recorded session trace instead of following the music source
+
+Keys: q or Esc to quit, [ and ] to change the line lead";
+
+#[tokio::main]
+async fn main() {
+    let mut args = Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+        println!("{USAGE}");
+        return;
+    }
+    let (replay, config, profile) = match parse_args(&mut args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if let Some(path) = config.or_else(|| profile.map(|name| paths::profile_config_path(&name))) {
+        paths::set_config_path(path);
+    }
+
+    let provider_templates: &[&str] = &[SPOTIFY_CONFIG_TEMPLATE, GENIUS_CONFIG_TEMPLATE];
+    let config = match VersualizerConfig::load_or_create(Some(provider_templates)) {
+        Ok(config) => config,
+        Err(CoreError::ConfigNotFound { path }) => {
+            println!(
+                "Created a config file at {}. Fill it in and run versualizer-tui again.",
+                path.display()
+            );
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    init_tracing(config.logging.enabled);
+
+    if let Err(e) = run(config, replay).await {
+        error!("{e}");
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(config: VersualizerConfig, replay: Option<PathBuf>) -> Result<()> {
+    let sync_engine = SyncEngine::new();
+    sync_engine.set_line_lead_ms(config.sync.line_lead_ms);
+    sync_engine
+        .set_global_offset_ms(config.sync.offset_ms)
+        .await;
+    let cancel_token = CancellationToken::new();
+
+    let cache = Arc::new(open_cache(&config).await?);
+    let lyrics_fetcher = Arc::new(create_lyrics_fetcher(
+        &config,
+        sync_engine.clone(),
+        cache,
+        cancel_token.clone(),
+    ));
+    let fetcher_handle = lyrics_fetcher.clone().start();
+
+    if let Some(trace_path) = replay {
+        info!("Replaying session trace from {:?}", trace_path);
+        let player = TracePlayer::load(&trace_path)?;
+        tokio::spawn(player.play(sync_engine.clone(), cancel_token.clone()));
+    } else if config.music.source == MusicSource::WindowsMedia {
+        let poller = Arc::new(WindowsMediaPoller::new(
+            sync_engine.clone(),
+            Some(cancel_token.clone()),
+        ));
+        drop(poller.start());
+    } else {
+        tokio::spawn(start_spotify_poller(
+            config.clone(),
+            lyrics_fetcher.clone(),
+            sync_engine.clone(),
+            cancel_token.clone(),
+        ));
+    }
+
+    let result = ui::run(
+        sync_engine,
+        cancel_token.clone(),
+        config.ui.animation.framerate,
+    )
+    .await;
+    cancel_token.cancel();
+    let _ = fetcher_handle.await;
+    Ok(result?)
+}
+
+/// Open the lyrics cache the app uses, encrypted the same way
+async fn open_cache(config: &VersualizerConfig) -> Result<LyricsCache> {
+    let cache = LyricsCache::open(&config.cache.db_path(config.music.source)).await?;
+    if !config.lyrics.encrypt_cache {
+        return Ok(cache);
+    }
+    let secret_store = config.lyrics.secret_store.open();
+    let cache = cache.with_encryption(ContentCipher::load_or_create(secret_store.as_ref())?);
+    if let Err(e) = cache.encrypt_existing().await {
+        warn!("Failed to encrypt existing cache entries: {}", e);
+    }
+    Ok(cache)
+}
+
+/// Create the lyrics fetcher with the same options as the app
+fn create_lyrics_fetcher(
+    config: &VersualizerConfig,
+    sync_engine: Arc<SyncEngine>,
+    cache: Arc<LyricsCache>,
+    cancel_token: CancellationToken,
+) -> LyricsFetcher {
+    let providers = create_providers(config);
+    info!("Initialized {} lyrics provider(s)", providers.len());
+    let overrides = LyricsOverrides::new(versualizer_core::paths::overrides_dir());
+    let mut lyrics_fetcher = LyricsFetcher::new(sync_engine, cache, providers, Some(cancel_token))
+        .with_overrides(overrides)
+        .with_unsynced_fallback(config.lyrics.allow_unsynced)
+        .with_parallel_fetch(config.lyrics.parallel_fetch)
+        .with_line_romanization(config.lyrics.processors.romanize_lines)
+        .with_not_found_ttl(config.cache.not_found_ttl())
+        .with_unreachable_retry(config.lyrics.retry_after(), config.lyrics.retry_attempts)
+        .with_blocklist(Blocklist::from_config(&config.lyrics.blocklist));
+    if let Some(mirror_dir) = config.lyrics.mirror_dir_path() {
+        lyrics_fetcher = lyrics_fetcher.with_mirror(LyricsMirror::new(mirror_dir));
+    }
+    lyrics_fetcher =
+        lyrics_fetcher.with_query_normalizer(QueryNormalizer::from_config(&config.lyrics.query));
+    if config.lyrics.query.musicbrainz {
+        match MusicBrainzEnricher::new(&config.network.retry) {
+            Ok(enricher) => {
+                info!("Tracks are looked up on MusicBrainz before searching lyrics");
+                lyrics_fetcher = lyrics_fetcher.with_enricher(Box::new(enricher));
+            }
+            Err(e) => warn!("Failed to create MusicBrainz client: {}", e),
+        }
+    }
+    let processors = TextPipeline::from_config(&config.lyrics);
+    if !processors.is_empty() {
+        lyrics_fetcher = lyrics_fetcher.with_processors(processors);
+    }
+    lyrics_fetcher
+}
+
+/// Sign in to Spotify and poll its playback state, prefetching the lyrics of queued tracks
+/// through `lyrics_fetcher`. There is no settings panel to sign in from, so a failed
+/// sign-in is shown on screen and polling does not start.
+async fn start_spotify_poller(
+    config: VersualizerConfig,
+    lyrics_fetcher: Arc<LyricsFetcher>,
+    sync_engine: Arc<SyncEngine>,
+    cancel_token: CancellationToken,
+) {
+    let spotify_config = match SpotifyProviderConfig::load(&config) {
+        Ok(Some(spotify_config)) => spotify_config,
+        Ok(None) => {
+            sync_engine.emit_error("Spotify is not configured in [providers.spotify]".into());
+            return;
+        }
+        Err(e) => {
+            sync_engine.emit_error(format!("Failed to parse Spotify config: {e}"));
+            return;
+        }
+    };
+    let oauth = match SpotifyOAuth::new(
+        &spotify_config.client_id,
+        &spotify_config.client_secret,
+        &spotify_config.oauth_redirect_uri,
+    ) {
+        Ok(oauth) => Arc::new(oauth.with_secret_store(config.lyrics.secret_store.open())),
+        Err(e) => {
+            sync_engine.emit_error(format!("Failed to create Spotify OAuth: {e}"));
+            return;
+        }
+    };
+    if let Err(e) = oauth.ensure_authenticated().await {
+        sync_engine.emit_error(format!(
+            "Spotify authentication failed: {e}. Without a browser, sign in with \
+             `versualizer-cli auth spotify --no-browser`"
+        ));
+        return;
+    }
+
+    let mut poller = SpotifyPoller::new(
+        oauth,
+        sync_engine,
+        spotify_config.poll_interval_ms,
+        Some(cancel_token.clone()),
+    )
+    .with_queue_prefetch(lyrics_fetcher, spotify_config.prefetch_queue);
+    if let Some(wakeup) = spawn_spotify_dealer(&spotify_config, &cancel_token) {
+        poller = poller.with_wakeup(wakeup);
+    }
+    let _ = Arc::new(poller).start().await;
+}
+
+/// Listen for pushed Spotify playback changes when `push_updates` is on and `sp_dc` is
+/// set, returning what is notified on each one
+fn spawn_spotify_dealer(
+    spotify_config: &SpotifyProviderConfig,
+    cancel_token: &CancellationToken,
+) -> Option<Arc<Notify>> {
+    if !spotify_config.push_updates {
+        return None;
+    }
+    let Some(sp_dc) = spotify_config
+        .sp_dc
+        .as_ref()
+        .filter(|sp_dc| !sp_dc.is_empty())
+    else {
+        warn!("Spotify push updates need providers.spotify.sp_dc, polling only");
+        return None;
+    };
+    match SpotifyDealer::new(sp_dc, spotify_config.secret_key_url.clone()) {
+        Ok(dealer) => Some(dealer.spawn(cancel_token.clone())),
+        Err(e) => {
+            error!("Failed to start Spotify push updates: {}", e);
+            None
+        }
+    }
+}
+
+/// Log to a file when `[logging] enabled` is set. The terminal belongs to the lyrics, so
+/// nothing is logged to it.
+fn init_tracing(file_logging_enabled: bool) {
+    if !file_logging_enabled {
+        return;
+    }
+    let log_path = versualizer_core::paths::log_file_path().with_file_name(LOG_FILE_NAME);
+    if let Some(parent) = log_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match File::create(&log_path) {
+        Ok(file) => {
+            let env_filter = EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("info,rspotify_http=warn"));
+            let file_layer = tracing_subscriber::fmt::layer()
+                .with_writer(Arc::new(file))
+                .with_ansi(false);
+            tracing_subscriber::registry()
+                .with(env_filter)
+                .with(file_layer)
+                .init();
+        }
+        Err(e) => eprintln!("Failed to create log file at {}: {e}", log_path.display()),
+    }
+}
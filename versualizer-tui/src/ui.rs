@@ -0,0 +1,315 @@
+//! The terminal screen: track header, karaoke lines and a key hint footer.
+
+use crate::view::{LineState, LineView, format_time, karaoke_rows, plain_scroll};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use versualizer_core::{BlockKind, LrcFile, PlaybackState, SyncEngine, SyncEvent, apply_line_lead};
+
+/// How much `[` and `]` change the line lead by (ms)
+const LINE_LEAD_STEP_MS: i64 = 50;
+
+/// Lines above the current one, as a share of the lyrics area
+const HISTORY_DIVISOR: usize = 3;
+
+/// Lyrics of the current track, as far as the fetcher got
+enum Lyrics {
+    /// No track yet, or its lyrics are still being looked up
+    Pending,
+    Synced(LrcFile),
+    Unsynced(Vec<String>),
+    NotFound,
+    /// A provider reported the track as instrumental
+    Instrumental,
+    /// The track or its artist is on the blocklist
+    Blocked(BlockKind),
+    /// Spotify is playing an ad instead of a track
+    Ad,
+    /// Every provider was unreachable, they are asked again later
+    Retrying {
+        attempt: u32,
+        max_attempts: u32,
+        retry_in: Duration,
+    },
+}
+
+/// Everything on screen apart from the playback position
+struct Screen {
+    lyrics: Lyrics,
+    /// Provider the lyrics came from
+    source: Option<String>,
+    /// What the fetcher is doing while the lyrics are pending, e.g. "Searching lrclib..."
+    progress: Option<String>,
+    /// Last error reported by the sync engine, until the next track
+    error: Option<String>,
+}
+
+impl Screen {
+    fn new(lyrics: Option<LrcFile>) -> Self {
+        Self {
+            lyrics: lyrics.map_or(Lyrics::Pending, Lyrics::Synced),
+            source: None,
+            progress: None,
+            error: None,
+        }
+    }
+
+    fn apply(&mut self, event: &SyncEvent) {
+        match event {
+            SyncEvent::TrackChanged { .. } | SyncEvent::PlaybackStopped => {
+                self.lyrics = Lyrics::Pending;
+                self.source = None;
+                self.progress = None;
+                self.error = None;
+            }
+            SyncEvent::AdPlaying => {
+                self.lyrics = Lyrics::Ad;
+                self.source = None;
+                self.progress = None;
+                self.error = None;
+            }
+            SyncEvent::LyricsFetchStarted { provider } => {
+                self.progress = Some(format!("Searching {provider}..."));
+            }
+            SyncEvent::LyricsFetchFailed { provider, .. } => {
+                self.progress = Some(format!("{provider} failed, still searching..."));
+            }
+            SyncEvent::LyricsLoaded { lyrics } => self.lyrics = Lyrics::Synced(lyrics.clone()),
+            SyncEvent::UnsyncedLyricsLoaded { text } => {
+                self.lyrics = Lyrics::Unsynced(text.lines().map(str::to_string).collect());
+            }
+            SyncEvent::LyricsNotFound => self.lyrics = Lyrics::NotFound,
+            SyncEvent::TrackInstrumental => self.lyrics = Lyrics::Instrumental,
+            SyncEvent::LyricsBlocked { kind } => self.lyrics = Lyrics::Blocked(*kind),
+            SyncEvent::LyricsRetrying {
+                attempt,
+                max_attempts,
+                retry_in,
+            } => {
+                self.lyrics = Lyrics::Retrying {
+                    attempt: *attempt,
+                    max_attempts: *max_attempts,
+                    retry_in: *retry_in,
+                };
+            }
+            SyncEvent::LyricsSource { provider } => self.source = Some(provider.clone()),
+            SyncEvent::Error { message, .. } => self.error = Some(message.clone()),
+            _ => {}
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, state: &PlaybackState, position: Duration) {
+        let [header, lyrics, footer] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(Paragraph::new(self.header(state)), header);
+        self.render_lyrics(frame, lyrics, state, position);
+        let hint = self.error.as_ref().map_or_else(
+            || Line::styled("q quit  [ ] line lead", Style::new().fg(Color::DarkGray)),
+            |error| Line::styled(error.as_str(), Style::new().fg(Color::Red)),
+        );
+        frame.render_widget(Paragraph::new(hint), footer);
+    }
+
+    fn header(&self, state: &PlaybackState) -> Vec<Line<'_>> {
+        let Some(track) = &state.track else {
+            return vec![Line::styled(
+                "Waiting for playback...",
+                Style::new().fg(Color::DarkGray),
+            )];
+        };
+        let mut status = format!(
+            "{} / {}  {}",
+            format_time(state.interpolated_position()),
+            format_time(track.duration),
+            if state.is_playing {
+                "playing"
+            } else {
+                "paused"
+            },
+        );
+        if let Some(source) = &self.source {
+            status.push_str("  lyrics from ");
+            status.push_str(source);
+        }
+        vec![
+            Line::from(vec![
+                Span::styled(
+                    track.name.clone(),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" - "),
+                Span::raw(track.artist.clone()),
+            ]),
+            Line::styled(status, Style::new().fg(Color::DarkGray)),
+        ]
+    }
+
+    fn render_lyrics(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &PlaybackState,
+        position: Duration,
+    ) {
+        // Lines are a blank row apart
+        let rows = usize::from(area.height).div_ceil(2);
+        let lines: Vec<Line> = match &self.lyrics {
+            Lyrics::Pending if state.track.is_some() => vec![dim(self
+                .progress
+                .as_deref()
+                .unwrap_or("Looking for lyrics..."))],
+            Lyrics::Pending => Vec::new(),
+            Lyrics::NotFound => vec![dim("No lyrics found for this track")],
+            Lyrics::Instrumental => vec![Line::styled(
+                "Instrumental ♪",
+                Style::new().fg(Color::Cyan).add_modifier(Modifier::ITALIC),
+            )],
+            Lyrics::Blocked(BlockKind::Track) => vec![dim("Lyrics hidden for this track")],
+            Lyrics::Blocked(BlockKind::Artist) => vec![dim("Lyrics hidden for this artist")],
+            Lyrics::Ad => vec![dim("Ad playing")],
+            Lyrics::Retrying {
+                attempt,
+                max_attempts,
+                retry_in,
+            } => vec![Line::styled(
+                format!(
+                    "Lyrics providers unreachable, retrying in {}s ({attempt}/{max_attempts})",
+                    retry_in.as_secs()
+                ),
+                Style::new().fg(Color::DarkGray),
+            )],
+            Lyrics::Synced(lyrics) => karaoke_rows(lyrics, position, rows, rows / HISTORY_DIVISOR)
+                .into_iter()
+                .map(|row| row.map_or_else(Line::default, karaoke_line))
+                .collect(),
+            Lyrics::Unsynced(lines) => {
+                let duration = state.track.as_ref().map_or(Duration::ZERO, |t| t.duration);
+                let first = plain_scroll(lines.len(), position, duration, rows);
+                lines
+                    .iter()
+                    .skip(first)
+                    .take(rows)
+                    .map(|line| Line::styled(line.as_str(), Style::new().fg(Color::Gray)))
+                    .collect()
+            }
+        };
+        let spaced: Vec<Line> = lines
+            .into_iter()
+            .flat_map(|line| [line, Line::default()])
+            .collect();
+        frame.render_widget(Paragraph::new(spaced).alignment(Alignment::Center), area);
+    }
+}
+
+fn dim(text: &str) -> Line<'_> {
+    Line::styled(text, Style::new().fg(Color::DarkGray))
+}
+
+fn karaoke_line(line: LineView<'_>) -> Line<'_> {
+    match line.state {
+        LineState::Sung => Line::styled(line.text, Style::new().fg(Color::DarkGray)),
+        LineState::Upcoming => Line::styled(line.text, Style::new().fg(Color::Gray)),
+        LineState::Current { sung_chars } => {
+            let split = line
+                .text
+                .char_indices()
+                .nth(sung_chars)
+                .map_or(line.text.len(), |(i, _)| i);
+            let (sung, unsung) = line.text.split_at(split);
+            Line::from(vec![
+                Span::styled(
+                    sung,
+                    Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    unsung,
+                    Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
+                ),
+            ])
+        }
+    }
+}
+
+/// Draw the lyrics of `sync_engine` at `framerate` until `q`, Esc or Ctrl+C is pressed or
+/// `cancel_token` is cancelled. The terminal is restored before returning.
+pub async fn run(
+    sync_engine: Arc<SyncEngine>,
+    cancel_token: CancellationToken,
+    framerate: u32,
+) -> std::io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = draw_loop(&mut terminal, &sync_engine, &cancel_token, framerate).await;
+    ratatui::restore();
+    result
+}
+
+async fn draw_loop(
+    terminal: &mut DefaultTerminal,
+    sync_engine: &SyncEngine,
+    cancel_token: &CancellationToken,
+    framerate: u32,
+) -> std::io::Result<()> {
+    let mut events = sync_engine.subscribe();
+    let mut screen = Screen::new(sync_engine.lyrics().await);
+    let mut interval = tokio::time::interval(Duration::from_secs(1) / framerate.clamp(1, 1000));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            () = cancel_token.cancelled() => return Ok(()),
+            event = events.recv() => match event {
+                Ok(event) => screen.apply(&event),
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            _ = interval.tick() => {
+                if handle_keys(sync_engine)? {
+                    return Ok(());
+                }
+                let state = sync_engine.state().await;
+                let position = apply_line_lead(
+                    sync_engine.current_position().await,
+                    sync_engine.line_lead_ms(),
+                );
+                terminal.draw(|frame| screen.render(frame, &state, position))?;
+            }
+        }
+    }
+}
+
+/// Handle the keys pressed since the last frame. Returns whether to quit.
+fn handle_keys(sync_engine: &SyncEngine) -> std::io::Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(true);
+            }
+            KeyCode::Char(']') => {
+                sync_engine.set_line_lead_ms(sync_engine.line_lead_ms() + LINE_LEAD_STEP_MS);
+            }
+            KeyCode::Char('[') => {
+                sync_engine.set_line_lead_ms(sync_engine.line_lead_ms() - LINE_LEAD_STEP_MS);
+            }
+            _ => {}
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,170 @@
+//! What the terminal shows, worked out apart from drawing so it can be tested.
+
+use std::time::Duration;
+use versualizer_core::{DurationExt, LrcFile, LrcLine, split_at_words};
+
+/// How long the last line is sung for, without a next line to end it
+const LAST_LINE_DURATION: Duration = Duration::from_secs(5);
+
+/// Where a line is relative to the playback position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineState {
+    Sung,
+    /// Being sung, with the first `sung_chars` characters already reached
+    Current {
+        sung_chars: usize,
+    },
+    Upcoming,
+}
+
+/// A lyric line in one row of the karaoke view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineView<'a> {
+    pub text: &'a str,
+    pub state: LineState,
+}
+
+/// The lines for `rows` rows at `position`, with the current line in row `history` and the
+/// lines before and after it around it. Rows before the first or after the last line are
+/// `None`. Before the first line starts, it waits in row `history`.
+#[must_use]
+pub fn karaoke_rows(
+    lyrics: &LrcFile,
+    position: Duration,
+    rows: usize,
+    history: usize,
+) -> Vec<Option<LineView<'_>>> {
+    let current = lyrics.current_line_index(position);
+    let first = current.unwrap_or(0);
+    (0..rows)
+        .map(|row| {
+            let index = (first + row).checked_sub(history)?;
+            let line = lyrics.lines.get(index)?;
+            let state = match current {
+                Some(current) if index < current => LineState::Sung,
+                Some(current) if index == current => LineState::Current {
+                    sung_chars: sung_chars(line, line_end(lyrics, index), position),
+                },
+                _ => LineState::Upcoming,
+            };
+            Some(LineView {
+                text: &line.text,
+                state,
+            })
+        })
+        .collect()
+}
+
+/// The first line of plain lyrics shown at `position`, scrolling through them evenly over
+/// the track's `duration` with `rows` of them on screen
+#[must_use]
+pub fn plain_scroll(
+    line_count: usize,
+    position: Duration,
+    duration: Duration,
+    rows: usize,
+) -> usize {
+    let scrollable = line_count.saturating_sub(rows);
+    portion(scrollable, Duration::ZERO, duration, position)
+}
+
+/// When the line at `index` ends: when the next one starts
+fn line_end(lyrics: &LrcFile, index: usize) -> Duration {
+    lyrics.lines.get(index + 1).map_or_else(
+        || lyrics.lines[index].start_time + LAST_LINE_DURATION,
+        |next| next.start_time,
+    )
+}
+
+/// Characters of `line` reached at `position`, following its word timing if it has any and
+/// spreading evenly over the line otherwise
+fn sung_chars(line: &LrcLine, end: Duration, position: Duration) -> usize {
+    let total = line.text.chars().count();
+    let Some(words) = line.words.as_deref() else {
+        return portion(total, line.start_time, end, position);
+    };
+    let texts: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+    let Some(segments) = split_at_words(&line.text, &texts) else {
+        return portion(total, line.start_time, end, position);
+    };
+    words
+        .iter()
+        .zip(&segments)
+        .enumerate()
+        .map(|(i, (word, segment))| {
+            let word_end = word
+                .end_time
+                .or_else(|| words.get(i + 1).map(|next| next.start_time))
+                .unwrap_or(end);
+            portion(segment.chars().count(), word.start_time, word_end, position)
+        })
+        .sum::<usize>()
+        .min(total)
+}
+
+/// How much of `count` is reached at `position` going from `start` to `end`
+fn portion(count: usize, start: Duration, end: Duration, position: Duration) -> usize {
+    if position >= end {
+        return count;
+    }
+    if position <= start {
+        return 0;
+    }
+    let elapsed = position.saturating_sub(start).as_millis_u64();
+    let span = end.saturating_sub(start).as_millis_u64().max(1);
+    let count_u64 = u64::try_from(count).unwrap_or(u64::MAX);
+    usize::try_from(count_u64.saturating_mul(elapsed) / span).unwrap_or(count)
+}
+
+/// `duration` as `m:ss`
+#[must_use]
+pub fn format_time(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_karaoke_rows() {
+        let lyrics = LrcFile::parse("[00:01.00]One\n[00:02.00]Two\n[00:04.00]Three").unwrap();
+
+        // Before the first line it waits in the current line's row
+        let rows = karaoke_rows(&lyrics, Duration::from_millis(500), 4, 1);
+        assert_eq!(rows[0], None);
+        assert_eq!(rows[1].unwrap().text, "One");
+        assert_eq!(rows[1].unwrap().state, LineState::Upcoming);
+
+        let rows = karaoke_rows(&lyrics, Duration::from_secs(3), 4, 1);
+        assert_eq!(rows[0].unwrap().state, LineState::Sung);
+        assert_eq!(rows[1].unwrap().text, "Two");
+        // Halfway between "Two" and "Three" starting
+        assert_eq!(rows[1].unwrap().state, LineState::Current { sung_chars: 1 });
+        assert_eq!(rows[2].unwrap().state, LineState::Upcoming);
+        assert_eq!(rows[3], None);
+    }
+
+    #[test]
+    fn test_sung_chars_follows_words() {
+        let lyrics =
+            LrcFile::parse("[00:01.00]<00:01.00>Hey <00:01.20>there\n[00:05.00]Next").unwrap();
+        let line = &lyrics.lines[0];
+        let end = line_end(&lyrics, 0);
+        assert_eq!(sung_chars(line, end, Duration::from_secs(1)), 0);
+        // "Hey " is sung quickly, "there" takes until the next line
+        assert_eq!(sung_chars(line, end, Duration::from_millis(1200)), 4);
+        assert_eq!(sung_chars(line, end, Duration::from_millis(3100)), 6);
+        assert_eq!(sung_chars(line, end, Duration::from_secs(5)), 9);
+    }
+
+    #[test]
+    fn test_plain_scroll() {
+        let duration = Duration::from_secs(100);
+        assert_eq!(plain_scroll(30, Duration::ZERO, duration, 10), 0);
+        assert_eq!(plain_scroll(30, Duration::from_secs(50), duration, 10), 10);
+        assert_eq!(plain_scroll(5, Duration::from_secs(50), duration, 10), 0);
+    }
+}